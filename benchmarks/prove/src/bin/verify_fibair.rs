@@ -63,6 +63,7 @@ fn main() -> Result<()> {
 
         let compiler_options = CompilerOptions::default();
         let app_config = AppConfig {
+            preset: None,
             app_fri_params: app_fri_params.into(),
             app_vm_config,
             leaf_fri_params: app_fri_params.into(),
@@ -96,6 +96,7 @@ impl BenchmarkCli {
             app_vm_config.as_mut().segmentation_limits.max_cells = max_cells;
         }
         AppConfig {
+            preset: None,
             app_fri_params: FriParameters::standard_with_100_bits_conjectured_security(
                 app_log_blowup,
             )
@@ -4,19 +4,22 @@ use openvm_circuit_primitives::{utils::not, AlignedBorrow};
 use openvm_stark_backend::p3_field::FieldAlgebra;
 
 use super::{
-    SHA256_HASH_WORDS, SHA256_ROUNDS_PER_ROW, SHA256_ROW_VAR_CNT, SHA256_WORD_BITS,
-    SHA256_WORD_U16S, SHA256_WORD_U8S,
+    SHA256_ROW_VAR_CNT, SHA256_WORD_BITS, SHA256_WORD_U16S, SHA256_WORD_U8S, SHA2_HASH_WORDS,
+    SHA2_ROUNDS_PER_ROW,
 };
 
-/// In each SHA256 block:
-/// - First 16 rows use Sha256RoundCols
-/// - Final row uses Sha256DigestCols
+/// In each SHA-2 block:
+/// - First `ROUND_ROWS` rows (16 for SHA-256, 20 for SHA-512) use Sha2RoundCols
+/// - Final row uses Sha2DigestCols
 ///
 /// Note that for soundness, we require that there is always a padding row after the last digest row
-/// in the trace. Right now, this is true because the unpadded height is a multiple of 17, and thus
-/// not a power of 2.
+/// in the trace. Right now, this is true because the unpadded height is a multiple of
+/// `ROWS_PER_BLOCK` (17 or 21), and thus not a power of 2.
 ///
-/// Sha256RoundCols and Sha256DigestCols share the same first 3 fields:
+/// The columns are generic over the word size through `WORD_BITS`, `WORD_U16S` and `WORD_U8S`, and
+/// over the number of rows through `ROW_VAR_CNT`, the number of cells encoding the row index.
+///
+/// Sha2RoundCols and Sha2DigestCols share the same first 3 fields:
 /// - flags
 /// - work_vars/hash (same type, different name)
 /// - schedule_helper
@@ -26,91 +29,104 @@ use super::{
 /// 2. Specific constraints to use the appropriate struct, with flags helping to do conditional
 ///    constraints
 ///
-/// Note that the `Sha256WorkVarsCols` field it is used for different purposes in the two structs.
+/// Note that the `Sha2WorkVarsCols` field it is used for different purposes in the two structs.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, AlignedBorrow)]
-pub struct Sha256RoundCols<T> {
-    pub flags: Sha256FlagsCols<T>,
+pub struct Sha2RoundCols<
+    T,
+    const WORD_BITS: usize,
+    const WORD_U16S: usize,
+    const WORD_U8S: usize,
+    const ROW_VAR_CNT: usize,
+> {
+    pub flags: Sha2FlagsCols<T, ROW_VAR_CNT>,
     /// Stores the current state of the working variables
-    pub work_vars: Sha256WorkVarsCols<T>,
-    pub schedule_helper: Sha256MessageHelperCols<T>,
-    pub message_schedule: Sha256MessageScheduleCols<T>,
+    pub work_vars: Sha2WorkVarsCols<T, WORD_BITS, WORD_U16S>,
+    pub schedule_helper: Sha2MessageHelperCols<T, WORD_U16S>,
+    pub message_schedule: Sha2MessageScheduleCols<T, WORD_BITS, WORD_U8S>,
 }
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, AlignedBorrow)]
-pub struct Sha256DigestCols<T> {
-    pub flags: Sha256FlagsCols<T>,
+pub struct Sha2DigestCols<
+    T,
+    const WORD_BITS: usize,
+    const WORD_U16S: usize,
+    const WORD_U8S: usize,
+    const ROW_VAR_CNT: usize,
+> {
+    pub flags: Sha2FlagsCols<T, ROW_VAR_CNT>,
     /// Will serve as previous hash values for the next block.
     ///     - on non-last blocks, this is the final hash of the current block
-    ///     - on last blocks, this is the initial state constants, SHA256_H.
+    ///     - on last blocks, this is the initial hash of the AIR, e.g. SHA256_H.
     /// The work variables constraints are applied on all rows, so `carry_a` and `carry_e`
     /// must be filled in with dummy values to ensure these constraints hold.
-    pub hash: Sha256WorkVarsCols<T>,
-    pub schedule_helper: Sha256MessageHelperCols<T>,
+    pub hash: Sha2WorkVarsCols<T, WORD_BITS, WORD_U16S>,
+    pub schedule_helper: Sha2MessageHelperCols<T, WORD_U16S>,
     /// The actual final hash values of the given block
     /// Note: the above `hash` will be equal to `final_hash` unless we are on the last block
-    pub final_hash: [[T; SHA256_WORD_U8S]; SHA256_HASH_WORDS],
+    pub final_hash: [[T; WORD_U8S]; SHA2_HASH_WORDS],
     /// The final hash of the previous block
     /// Note: will be constrained using interactions with the chip itself
-    pub prev_hash: [[T; SHA256_WORD_U16S]; SHA256_HASH_WORDS],
+    pub prev_hash: [[T; WORD_U16S]; SHA2_HASH_WORDS],
 }
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, AlignedBorrow)]
-pub struct Sha256MessageScheduleCols<T> {
-    /// The message schedule words as 32-bit integers
+pub struct Sha2MessageScheduleCols<T, const WORD_BITS: usize, const WORD_U8S: usize> {
+    /// The message schedule words as `WORD_BITS`-bit integers
     /// The first 16 words will be the message data
-    pub w: [[T; SHA256_WORD_BITS]; SHA256_ROUNDS_PER_ROW],
-    /// Will be message schedule carries for rows 4..16 and a buffer for rows 0..4 to be used
+    pub w: [[T; WORD_BITS]; SHA2_ROUNDS_PER_ROW],
+    /// Will be message schedule carries for round rows 4.. and a buffer for rows 0..4 to be used
     /// freely by wrapper chips Note: carries are 2 bit numbers represented using 2 cells as
     /// individual bits
-    pub carry_or_buffer: [[T; SHA256_WORD_U8S]; SHA256_ROUNDS_PER_ROW],
+    pub carry_or_buffer: [[T; WORD_U8S]; SHA2_ROUNDS_PER_ROW],
 }
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, AlignedBorrow)]
-pub struct Sha256WorkVarsCols<T> {
-    /// `a` and `e` after each iteration as 32-bits
-    pub a: [[T; SHA256_WORD_BITS]; SHA256_ROUNDS_PER_ROW],
-    pub e: [[T; SHA256_WORD_BITS]; SHA256_ROUNDS_PER_ROW],
+pub struct Sha2WorkVarsCols<T, const WORD_BITS: usize, const WORD_U16S: usize> {
+    /// `a` and `e` after each iteration as `WORD_BITS` bits
+    pub a: [[T; WORD_BITS]; SHA2_ROUNDS_PER_ROW],
+    pub e: [[T; WORD_BITS]; SHA2_ROUNDS_PER_ROW],
     /// The carry's used for addition during each iteration when computing `a` and `e`
-    pub carry_a: [[T; SHA256_WORD_U16S]; SHA256_ROUNDS_PER_ROW],
-    pub carry_e: [[T; SHA256_WORD_U16S]; SHA256_ROUNDS_PER_ROW],
+    pub carry_a: [[T; WORD_U16S]; SHA2_ROUNDS_PER_ROW],
+    pub carry_e: [[T; WORD_U16S]; SHA2_ROUNDS_PER_ROW],
 }
 
 /// These are the columns that are used to help with the message schedule additions
 /// Note: these need to be correctly assigned for every row even on padding rows
 #[repr(C)]
 #[derive(Clone, Copy, Debug, AlignedBorrow)]
-pub struct Sha256MessageHelperCols<T> {
+pub struct Sha2MessageHelperCols<T, const WORD_U16S: usize> {
     /// The following are used to move data forward to constrain the message schedule additions
     /// The value of `w` (message schedule word) from 3 rounds ago
     /// In general, `w_i` means `w` from `i` rounds ago
-    pub w_3: [[T; SHA256_WORD_U16S]; SHA256_ROUNDS_PER_ROW - 1],
+    pub w_3: [[T; WORD_U16S]; SHA2_ROUNDS_PER_ROW - 1],
     /// Here intermediate(i) =  w_i + sig_0(w_{i+1})
     /// Intermed_t represents the intermediate t rounds ago
     /// This is needed to constrain the message schedule, since we can only constrain on two rows
     /// at a time
-    pub intermed_4: [[T; SHA256_WORD_U16S]; SHA256_ROUNDS_PER_ROW],
-    pub intermed_8: [[T; SHA256_WORD_U16S]; SHA256_ROUNDS_PER_ROW],
-    pub intermed_12: [[T; SHA256_WORD_U16S]; SHA256_ROUNDS_PER_ROW],
+    pub intermed_4: [[T; WORD_U16S]; SHA2_ROUNDS_PER_ROW],
+    pub intermed_8: [[T; WORD_U16S]; SHA2_ROUNDS_PER_ROW],
+    pub intermed_12: [[T; WORD_U16S]; SHA2_ROUNDS_PER_ROW],
 }
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, AlignedBorrow)]
-pub struct Sha256FlagsCols<T> {
-    /// A flag that indicates if the current row is among the first 16 rows of a block.
+pub struct Sha2FlagsCols<T, const ROW_VAR_CNT: usize> {
+    /// A flag that indicates if the current row is among the round rows of a block.
     pub is_round_row: T,
     /// A flag that indicates if the current row is among the first 4 rows of a block.
     pub is_first_4_rows: T,
-    /// A flag that indicates if the current row is the last (17th) row of a block.
+    /// A flag that indicates if the current row is the last row of a block.
     pub is_digest_row: T,
     // A flag that indicates if the current row is the last block of the message.
     // This flag is only used in digest rows.
     pub is_last_block: T,
-    /// We will encode the row index [0..17) using 5 cells
-    pub row_idx: [T; SHA256_ROW_VAR_CNT],
+    /// We will encode the row index [0..ROWS_PER_BLOCK] using `ROW_VAR_CNT` cells, where the
+    /// last index is used for padding rows
+    pub row_idx: [T; ROW_VAR_CNT],
     /// The index of the current block in the trace starting at 1.
     /// Set to 0 on padding rows.
     pub global_block_idx: T,
@@ -120,7 +136,9 @@ pub struct Sha256FlagsCols<T> {
     pub local_block_idx: T,
 }
 
-impl<O, T: Copy + core::ops::Add<Output = O>> Sha256FlagsCols<T> {
+impl<O, T: Copy + core::ops::Add<Output = O>, const ROW_VAR_CNT: usize>
+    Sha2FlagsCols<T, ROW_VAR_CNT>
+{
     // This refers to the padding rows that are added to the air to make the trace length a power of
     // 2. Not to be confused with the padding added to messages as part of the SHA hash
     // function.
@@ -138,3 +156,13 @@ impl<O, T: Copy + core::ops::Add<Output = O>> Sha256FlagsCols<T> {
         not(self.is_not_padding_row())
     }
 }
+
+pub type Sha256RoundCols<T> =
+    Sha2RoundCols<T, SHA256_WORD_BITS, SHA256_WORD_U16S, SHA256_WORD_U8S, SHA256_ROW_VAR_CNT>;
+pub type Sha256DigestCols<T> =
+    Sha2DigestCols<T, SHA256_WORD_BITS, SHA256_WORD_U16S, SHA256_WORD_U8S, SHA256_ROW_VAR_CNT>;
+pub type Sha256MessageScheduleCols<T> =
+    Sha2MessageScheduleCols<T, SHA256_WORD_BITS, SHA256_WORD_U8S>;
+pub type Sha256WorkVarsCols<T> = Sha2WorkVarsCols<T, SHA256_WORD_BITS, SHA256_WORD_U16S>;
+pub type Sha256MessageHelperCols<T> = Sha2MessageHelperCols<T, SHA256_WORD_U16S>;
+pub type Sha256FlagsCols<T> = Sha2FlagsCols<T, SHA256_ROW_VAR_CNT>;
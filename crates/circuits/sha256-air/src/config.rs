@@ -0,0 +1,110 @@
+use std::{
+    fmt::Debug,
+    ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr},
+};
+
+use sha2::{compress256, digest::generic_array::GenericArray};
+
+use super::{SHA256_H, SHA256_K, SHA2_HASH_WORDS, SHA2_ROUNDS_PER_ROW};
+
+/// A word of a SHA-2 hash function, i.e. `u32` for SHA-256 and `u64` for SHA-512
+pub trait Sha2Word:
+    Copy
+    + Debug
+    + Default
+    + Eq
+    + Send
+    + Sync
+    + 'static
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<usize, Output = Self>
+    + Shr<usize, Output = Self>
+{
+    /// Number of bits in the word
+    const BITS: usize;
+
+    fn wrapping_add(self, rhs: Self) -> Self;
+    fn rotate_right(self, n: usize) -> Self;
+    fn from_u32(num: u32) -> Self;
+    /// Returns the 32 least significant bits of the word
+    fn low_u32(self) -> u32;
+}
+
+macro_rules! impl_sha2_word {
+    ($($word:ty),*) => {
+        $(
+            impl Sha2Word for $word {
+                const BITS: usize = <$word>::BITS as usize;
+
+                #[inline]
+                fn wrapping_add(self, rhs: Self) -> Self {
+                    <$word>::wrapping_add(self, rhs)
+                }
+                #[inline]
+                fn rotate_right(self, n: usize) -> Self {
+                    <$word>::rotate_right(self, n as u32)
+                }
+                #[inline]
+                fn from_u32(num: u32) -> Self {
+                    num as $word
+                }
+                #[inline]
+                fn low_u32(self) -> u32 {
+                    self as u32
+                }
+            }
+        )*
+    };
+}
+
+impl_sha2_word!(u32, u64);
+
+/// The parameters of a SHA-2 compression function. The AIR does 4 rounds per row, so a block
+/// takes `ROUNDS / 4` round rows followed by a digest row.
+pub trait Sha2Config: Clone + Debug + Send + Sync + 'static {
+    type Word: Sha2Word;
+
+    /// Number of rounds of the compression function
+    const ROUNDS: usize;
+    /// Number of rows doing the rounds of a block
+    const ROUND_ROWS: usize = Self::ROUNDS / SHA2_ROUNDS_PER_ROW;
+    /// Number of rows per block
+    const ROWS_PER_BLOCK: usize = Self::ROUND_ROWS + 1;
+    /// Round constants, one per round
+    const K: &'static [Self::Word];
+    /// Initial hash values
+    const H: [Self::Word; SHA2_HASH_WORDS];
+    /// Rotation amounts of BigSigma0
+    const BIG_SIG0_ROT: [usize; 3];
+    /// Rotation amounts of BigSigma1
+    const BIG_SIG1_ROT: [usize; 3];
+    /// Rotation amounts of SmallSigma0, followed by its shift amount
+    const SMALL_SIG0_ROT: [usize; 3];
+    /// Rotation amounts of SmallSigma1, followed by its shift amount
+    const SMALL_SIG1_ROT: [usize; 3];
+
+    /// Compresses `block` into `state` with the reference implementation
+    fn compress(state: &mut [Self::Word; SHA2_HASH_WORDS], block: &[u8]);
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Sha256Config;
+
+impl Sha2Config for Sha256Config {
+    type Word = u32;
+
+    const ROUNDS: usize = 64;
+    const K: &'static [u32] = &SHA256_K;
+    const H: [u32; SHA2_HASH_WORDS] = SHA256_H;
+    const BIG_SIG0_ROT: [usize; 3] = [2, 13, 22];
+    const BIG_SIG1_ROT: [usize; 3] = [6, 11, 25];
+    const SMALL_SIG0_ROT: [usize; 3] = [7, 18, 3];
+    const SMALL_SIG1_ROT: [usize; 3] = [17, 19, 10];
+
+    fn compress(state: &mut [u32; SHA2_HASH_WORDS], block: &[u8]) {
+        compress256(state, &[*GenericArray::from_slice(block)]);
+    }
+}
@@ -1,13 +1,17 @@
-//! Implementation of the SHA256 compression function without padding
+//! Implementation of the SHA-2 compression functions without padding
 //! This this AIR doesn't constrain any of the message padding
+//! The AIR is generic over a [Sha2Config], which is [Sha256Config] for SHA256. The SHA512 and
+//! SHA384 configs live in `openvm-sha512-air`.
 
 mod air;
 mod columns;
+mod config;
 mod trace;
 mod utils;
 
 pub use air::*;
 pub use columns::*;
+pub use config::*;
 pub use trace::*;
 pub use utils::*;
 
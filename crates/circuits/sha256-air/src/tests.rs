@@ -66,7 +66,7 @@ where
     Val<SC>: PrimeField32,
 {
     fn generate_proving_ctx(&self, records: RecordType) -> AirProvingContext<CpuBackend<SC>> {
-        let trace = crate::generate_trace::<Val<SC>>(
+        let trace: RowMajorMatrix<Val<SC>> = crate::generate_trace(
             &self.step,
             self.bitwise_lookup_chip.as_ref(),
             SHA256_WIDTH,
@@ -1,4 +1,4 @@
-use std::{array, borrow::BorrowMut, ops::Range};
+use std::{array, borrow::BorrowMut, marker::PhantomData, ops::Range};
 
 use openvm_circuit_primitives::{
     bitwise_op_lookup::BitwiseOperationLookupChip, encoder::Encoder,
@@ -7,59 +7,106 @@ use openvm_circuit_primitives::{
 use openvm_stark_backend::{
     p3_field::PrimeField32, p3_matrix::dense::RowMajorMatrix, p3_maybe_rayon::prelude::*,
 };
-use sha2::{compress256, digest::generic_array::GenericArray};
 
 use super::{
-    big_sig0_field, big_sig1_field, ch_field, columns::Sha256RoundCols, compose, get_flag_pt_array,
-    maj_field, small_sig0_field, small_sig1_field, SHA256_BLOCK_WORDS, SHA256_DIGEST_WIDTH,
-    SHA256_HASH_WORDS, SHA256_ROUND_WIDTH,
+    big_sig0_field, big_sig1_field, ch_field, columns::Sha2RoundCols, compose, get_flag_pt_array,
+    maj_field, small_sig0_field, small_sig1_field, Sha256Config, Sha2Config, SHA256_H,
+    SHA256_ROW_VAR_CNT, SHA256_WORD_BITS, SHA256_WORD_U16S, SHA256_WORD_U8S, SHA2_BLOCK_WORDS,
+    SHA2_HASH_WORDS,
 };
 use crate::{
-    big_sig0, big_sig1, ch, columns::Sha256DigestCols, limbs_into_u32, maj, small_sig0, small_sig1,
-    u32_into_bits_field, u32_into_u16s, SHA256_BLOCK_U8S, SHA256_H, SHA256_INVALID_CARRY_A,
-    SHA256_INVALID_CARRY_E, SHA256_K, SHA256_ROUNDS_PER_ROW, SHA256_ROWS_PER_BLOCK,
-    SHA256_WORD_U16S, SHA256_WORD_U8S,
+    big_sig0, big_sig1, ch, columns::Sha2DigestCols, limbs_into_word, maj, small_sig0, small_sig1,
+    word_into_bits_field, word_into_u16s, Sha2Word, SHA256_INVALID_CARRY_A, SHA256_INVALID_CARRY_E,
+    SHA2_ROUNDS_PER_ROW,
 };
 
-/// A helper struct for the SHA256 trace generation.
+/// A helper struct for the SHA-2 trace generation, with the same parameters as
+/// [Sha2Air](crate::Sha2Air).
 /// Also, separates the inner AIR from the trace generation.
-pub struct Sha256FillerHelper {
+pub struct Sha2FillerHelper<
+    C: Sha2Config,
+    const WORD_BITS: usize,
+    const WORD_U16S: usize,
+    const WORD_U8S: usize,
+    const ROW_VAR_CNT: usize,
+> {
     pub row_idx_encoder: Encoder,
+    /// The `prev_hash` of the first block of every message
+    pub initial_hash: [C::Word; SHA2_HASH_WORDS],
+    /// The carries of the padding rows, whose work variables are [Self::initial_hash]
+    invalid_carry_a: [[u32; WORD_U16S]; SHA2_ROUNDS_PER_ROW],
+    invalid_carry_e: [[u32; WORD_U16S]; SHA2_ROUNDS_PER_ROW],
+    _config: PhantomData<C>,
 }
 
+pub type Sha256FillerHelper =
+    Sha2FillerHelper<Sha256Config, SHA256_WORD_BITS, WORD_U16S, WORD_U8S, SHA256_ROW_VAR_CNT>;
+
 impl Default for Sha256FillerHelper {
     fn default() -> Self {
         Self::new()
     }
 }
 
-/// The trace generation of SHA256 should be done in two passes.
+impl Sha256FillerHelper {
+    pub fn new() -> Self {
+        Self::new_with_initial_hash(SHA256_H, SHA256_INVALID_CARRY_A, SHA256_INVALID_CARRY_E)
+    }
+}
+
+/// The trace generation of SHA-2 should be done in two passes.
 /// The first pass should do `get_block_trace` for every block and generate the invalid rows through
 /// `get_default_row` The second pass should go through all the blocks and call
 /// `generate_missing_cells`
-impl Sha256FillerHelper {
-    pub fn new() -> Self {
+impl<
+        C: Sha2Config,
+        const WORD_BITS: usize,
+        const WORD_U16S: usize,
+        const WORD_U8S: usize,
+        const ROW_VAR_CNT: usize,
+    > Sha2FillerHelper<C, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT>
+{
+    /// Width of the Sha2RoundCols
+    const ROUND_WIDTH: usize =
+        Sha2RoundCols::<u8, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT>::width();
+    /// Width of the Sha2DigestCols
+    const DIGEST_WIDTH: usize =
+        Sha2DigestCols::<u8, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT>::width();
+
+    /// Creates a helper for messages starting from `initial_hash`, where `invalid_carry_a` and
+    /// `invalid_carry_e` are the carries of a padding row whose work variables are `initial_hash`
+    pub fn new_with_initial_hash(
+        initial_hash: [C::Word; SHA2_HASH_WORDS],
+        invalid_carry_a: [[u32; WORD_U16S]; SHA2_ROUNDS_PER_ROW],
+        invalid_carry_e: [[u32; WORD_U16S]; SHA2_ROUNDS_PER_ROW],
+    ) -> Self {
+        let row_idx_encoder = Encoder::new(C::ROWS_PER_BLOCK + 1, 2, false);
+        debug_assert_eq!(row_idx_encoder.width(), ROW_VAR_CNT);
         Self {
-            row_idx_encoder: Encoder::new(18, 2, false),
+            row_idx_encoder,
+            initial_hash,
+            invalid_carry_a,
+            invalid_carry_e,
+            _config: PhantomData,
         }
     }
+
     /// This function takes the input_message (padding not handled), the previous hash,
     /// and returns the new hash after processing the block input
     pub fn get_block_hash(
-        prev_hash: &[u32; SHA256_HASH_WORDS],
-        input: [u8; SHA256_BLOCK_U8S],
-    ) -> [u32; SHA256_HASH_WORDS] {
+        prev_hash: &[C::Word; SHA2_HASH_WORDS],
+        input: &[u8],
+    ) -> [C::Word; SHA2_HASH_WORDS] {
         let mut new_hash = *prev_hash;
-        let input_array = [GenericArray::from(input)];
-        compress256(&mut new_hash, &input_array);
+        C::compress(&mut new_hash, input);
         new_hash
     }
 
-    /// This function takes a 512-bit chunk of the input message (padding not handled), the previous
+    /// This function takes a block of the input message (padding not handled), the previous
     /// hash, a flag indicating if it's the last block, the global block index, the local block
     /// index, and the buffer values that will be put in rows 0..4.
     /// Will populate the given `trace` with the trace of the block, where the width of the trace is
-    /// `trace_width` and the starting column for the `Sha256Air` is `trace_start_col`.
+    /// `trace_width` and the starting column for the `Sha2Air` is `trace_start_col`.
     /// **Note**: this function only generates some of the required trace. Another pass is required,
     /// refer to [`Self::generate_missing_cells`] for details.
     #[allow(clippy::too_many_arguments)]
@@ -68,30 +115,94 @@ impl Sha256FillerHelper {
         trace: &mut [F],
         trace_width: usize,
         trace_start_col: usize,
-        input: &[u32; SHA256_BLOCK_WORDS],
+        input: &[C::Word; SHA2_BLOCK_WORDS],
         bitwise_lookup_chip: &BitwiseOperationLookupChip<8>,
-        prev_hash: &[u32; SHA256_HASH_WORDS],
+        prev_hash: &[C::Word; SHA2_HASH_WORDS],
         is_last_block: bool,
         global_block_idx: u32,
         local_block_idx: u32,
+    ) {
+        debug_assert!(local_block_idx != 0 || *prev_hash == self.initial_hash);
+        let next_hash = if is_last_block {
+            Some(&self.initial_hash)
+        } else {
+            None
+        };
+        self.generate_block_trace_impl(
+            trace,
+            trace_width,
+            trace_start_col,
+            input,
+            bitwise_lookup_chip,
+            prev_hash,
+            next_hash,
+            global_block_idx,
+            local_block_idx,
+        );
+    }
+
+    /// Generates the trace of a block for a [Sha2Air](crate::Sha2Air) created with
+    /// [new_with_free_prev_hash](crate::Sha2Air::new_with_free_prev_hash). Every block is the
+    /// only block of its message and starts from the arbitrary `prev_hash`, and `next_prev_hash`
+    /// is the `prev_hash` of the next block in the trace, wrapping around to the first block for
+    /// the last one.
+    /// **Note**: as for [`Self::generate_block_trace`], another pass is required, refer to
+    /// [`Self::generate_missing_cells`] for details.
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_free_block_trace<F: PrimeField32>(
+        &self,
+        trace: &mut [F],
+        trace_width: usize,
+        trace_start_col: usize,
+        input: &[C::Word; SHA2_BLOCK_WORDS],
+        bitwise_lookup_chip: &BitwiseOperationLookupChip<8>,
+        prev_hash: &[C::Word; SHA2_HASH_WORDS],
+        next_prev_hash: &[C::Word; SHA2_HASH_WORDS],
+        global_block_idx: u32,
+    ) {
+        self.generate_block_trace_impl(
+            trace,
+            trace_width,
+            trace_start_col,
+            input,
+            bitwise_lookup_chip,
+            prev_hash,
+            Some(next_prev_hash),
+            global_block_idx,
+            0,
+        );
+    }
+
+    /// Generates the trace of a block, where `next_hash` is the hash of the digest row if the
+    /// block is the last block of its message, and `None` otherwise.
+    #[allow(clippy::too_many_arguments)]
+    fn generate_block_trace_impl<F: PrimeField32>(
+        &self,
+        trace: &mut [F],
+        trace_width: usize,
+        trace_start_col: usize,
+        input: &[C::Word; SHA2_BLOCK_WORDS],
+        bitwise_lookup_chip: &BitwiseOperationLookupChip<8>,
+        prev_hash: &[C::Word; SHA2_HASH_WORDS],
+        next_hash: Option<&[C::Word; SHA2_HASH_WORDS]>,
+        global_block_idx: u32,
+        local_block_idx: u32,
     ) {
         #[cfg(debug_assertions)]
         {
-            assert!(trace.len() == trace_width * SHA256_ROWS_PER_BLOCK);
-            assert!(trace_start_col + super::SHA256_WIDTH <= trace_width);
-            if local_block_idx == 0 {
-                assert!(*prev_hash == SHA256_H);
-            }
+            assert!(trace.len() == trace_width * C::ROWS_PER_BLOCK);
+            assert!(trace_start_col + Self::ROUND_WIDTH.max(Self::DIGEST_WIDTH) <= trace_width);
         }
+        let is_last_block = next_hash.is_some();
         let get_range = |start: usize, len: usize| -> Range<usize> { start..start + len };
-        let mut message_schedule = [0u32; 64];
+        let mut message_schedule = vec![C::Word::default(); C::ROUNDS];
         message_schedule[..input.len()].copy_from_slice(input);
         let mut work_vars = *prev_hash;
         for (i, row) in trace.chunks_exact_mut(trace_width).enumerate() {
-            // doing the 64 rounds in 16 rows
-            if i < 16 {
-                let cols: &mut Sha256RoundCols<F> =
-                    row[get_range(trace_start_col, SHA256_ROUND_WIDTH)].borrow_mut();
+            // doing the rounds in ROUND_ROWS rows, 4 per row
+            if i < C::ROUND_ROWS {
+                let cols: &mut Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT> =
+                    row[get_range(trace_start_col, Self::ROUND_WIDTH)].borrow_mut();
                 cols.flags.is_round_row = F::ONE;
                 cols.flags.is_first_4_rows = if i < 4 { F::ONE } else { F::ZERO };
                 cols.flags.is_digest_row = F::ZERO;
@@ -103,29 +214,33 @@ impl Sha256FillerHelper {
 
                 // W_idx = M_idx
                 if i < 4 {
-                    for j in 0..SHA256_ROUNDS_PER_ROW {
-                        cols.message_schedule.w[j] =
-                            u32_into_bits_field::<F>(input[i * SHA256_ROUNDS_PER_ROW + j]);
+                    for j in 0..SHA2_ROUNDS_PER_ROW {
+                        cols.message_schedule.w[j] = word_into_bits_field::<F, C::Word, WORD_BITS>(
+                            input[i * SHA2_ROUNDS_PER_ROW + j],
+                        );
                     }
                 }
                 // W_idx = SIG1(W_{idx-2}) + W_{idx-7} + SIG0(W_{idx-15}) + W_{idx-16}
                 else {
-                    for j in 0..SHA256_ROUNDS_PER_ROW {
-                        let idx = i * SHA256_ROUNDS_PER_ROW + j;
-                        let nums: [u32; 4] = [
-                            small_sig1(message_schedule[idx - 2]),
+                    for j in 0..SHA2_ROUNDS_PER_ROW {
+                        let idx = i * SHA2_ROUNDS_PER_ROW + j;
+                        let nums: [C::Word; 4] = [
+                            small_sig1::<C>(message_schedule[idx - 2]),
                             message_schedule[idx - 7],
-                            small_sig0(message_schedule[idx - 15]),
+                            small_sig0::<C>(message_schedule[idx - 15]),
                             message_schedule[idx - 16],
                         ];
-                        let w: u32 = nums.iter().fold(0, |acc, &num| acc.wrapping_add(num));
-                        cols.message_schedule.w[j] = u32_into_bits_field::<F>(w);
+                        let w = nums
+                            .iter()
+                            .fold(C::Word::default(), |acc, &num| acc.wrapping_add(num));
+                        cols.message_schedule.w[j] =
+                            word_into_bits_field::<F, C::Word, WORD_BITS>(w);
 
-                        let nums_limbs = nums.map(u32_into_u16s);
-                        let w_limbs = u32_into_u16s(w);
+                        let nums_limbs = nums.map(word_into_u16s::<C::Word, WORD_U16S>);
+                        let w_limbs = word_into_u16s::<C::Word, WORD_U16S>(w);
 
                         // fill in the carrys
-                        for k in 0..SHA256_WORD_U16S {
+                        for k in 0..WORD_U16S {
                             let mut sum = nums_limbs.iter().fold(0, |acc, num| acc + num[k]);
                             if k > 0 {
                                 sum += (cols.message_schedule.carry_or_buffer[j][k * 2 - 2]
@@ -143,39 +258,50 @@ impl Sha256FillerHelper {
                     }
                 }
                 // fill in the work variables
-                for j in 0..SHA256_ROUNDS_PER_ROW {
+                for j in 0..SHA2_ROUNDS_PER_ROW {
                     // t1 = h + SIG1(e) + ch(e, f, g) + K_idx + W_idx
                     let t1 = [
                         work_vars[7],
-                        big_sig1(work_vars[4]),
+                        big_sig1::<C>(work_vars[4]),
                         ch(work_vars[4], work_vars[5], work_vars[6]),
-                        SHA256_K[i * SHA256_ROUNDS_PER_ROW + j],
-                        limbs_into_u32(cols.message_schedule.w[j].map(|f| f.as_canonical_u32())),
+                        C::K[i * SHA2_ROUNDS_PER_ROW + j],
+                        limbs_into_word::<C::Word, WORD_BITS>(
+                            cols.message_schedule.w[j].map(|f| f.as_canonical_u32()),
+                        ),
                     ];
-                    let t1_sum: u32 = t1.iter().fold(0, |acc, &num| acc.wrapping_add(num));
+                    let t1_sum = t1
+                        .iter()
+                        .fold(C::Word::default(), |acc, &num| acc.wrapping_add(num));
 
                     // t2 = SIG0(a) + maj(a, b, c)
                     let t2 = [
-                        big_sig0(work_vars[0]),
+                        big_sig0::<C>(work_vars[0]),
                         maj(work_vars[0], work_vars[1], work_vars[2]),
                     ];
 
-                    let t2_sum: u32 = t2.iter().fold(0, |acc, &num| acc.wrapping_add(num));
+                    let t2_sum = t2
+                        .iter()
+                        .fold(C::Word::default(), |acc, &num| acc.wrapping_add(num));
 
                     // e = d + t1
                     let e = work_vars[3].wrapping_add(t1_sum);
-                    cols.work_vars.e[j] = u32_into_bits_field::<F>(e);
-                    let e_limbs = u32_into_u16s(e);
+                    cols.work_vars.e[j] = word_into_bits_field::<F, C::Word, WORD_BITS>(e);
+                    let e_limbs = word_into_u16s::<C::Word, WORD_U16S>(e);
                     // a = t1 + t2
                     let a = t1_sum.wrapping_add(t2_sum);
-                    cols.work_vars.a[j] = u32_into_bits_field::<F>(a);
-                    let a_limbs = u32_into_u16s(a);
+                    cols.work_vars.a[j] = word_into_bits_field::<F, C::Word, WORD_BITS>(a);
+                    let a_limbs = word_into_u16s::<C::Word, WORD_U16S>(a);
                     // fill in the carrys
-                    for k in 0..SHA256_WORD_U16S {
-                        let t1_limb = t1.iter().fold(0, |acc, &num| acc + u32_into_u16s(num)[k]);
-                        let t2_limb = t2.iter().fold(0, |acc, &num| acc + u32_into_u16s(num)[k]);
+                    for k in 0..WORD_U16S {
+                        let t1_limb = t1.iter().fold(0, |acc, &num| {
+                            acc + word_into_u16s::<C::Word, WORD_U16S>(num)[k]
+                        });
+                        let t2_limb = t2.iter().fold(0, |acc, &num| {
+                            acc + word_into_u16s::<C::Word, WORD_U16S>(num)[k]
+                        });
 
-                        let mut e_limb = t1_limb + u32_into_u16s(work_vars[3])[k];
+                        let mut e_limb =
+                            t1_limb + word_into_u16s::<C::Word, WORD_U16S>(work_vars[3])[k];
                         let mut a_limb = t1_limb + t2_limb;
                         if k > 0 {
                             a_limb += cols.work_vars.carry_a[j][k - 1].as_canonical_u32();
@@ -201,41 +327,45 @@ impl Sha256FillerHelper {
 
                 // filling w_3 and intermed_4 here and the rest later
                 if i > 0 {
-                    for j in 0..SHA256_ROUNDS_PER_ROW {
-                        let idx = i * SHA256_ROUNDS_PER_ROW + j;
-                        let w_4 = u32_into_u16s(message_schedule[idx - 4]);
-                        let sig_0_w_3 = u32_into_u16s(small_sig0(message_schedule[idx - 3]));
+                    for j in 0..SHA2_ROUNDS_PER_ROW {
+                        let idx = i * SHA2_ROUNDS_PER_ROW + j;
+                        let w_4 = word_into_u16s::<C::Word, WORD_U16S>(message_schedule[idx - 4]);
+                        let sig_0_w_3 = word_into_u16s::<C::Word, WORD_U16S>(small_sig0::<C>(
+                            message_schedule[idx - 3],
+                        ));
                         cols.schedule_helper.intermed_4[j] =
                             array::from_fn(|k| F::from_canonical_u32(w_4[k] + sig_0_w_3[k]));
-                        if j < SHA256_ROUNDS_PER_ROW - 1 {
+                        if j < SHA2_ROUNDS_PER_ROW - 1 {
                             let w_3 = message_schedule[idx - 3];
-                            cols.schedule_helper.w_3[j] =
-                                u32_into_u16s(w_3).map(F::from_canonical_u32);
+                            cols.schedule_helper.w_3[j] = word_into_u16s::<C::Word, WORD_U16S>(w_3)
+                                .map(F::from_canonical_u32);
                         }
                     }
                 }
             }
             // generate the digest row
             else {
-                let cols: &mut Sha256DigestCols<F> =
-                    row[get_range(trace_start_col, SHA256_DIGEST_WIDTH)].borrow_mut();
-                for j in 0..SHA256_ROUNDS_PER_ROW - 1 {
-                    let w_3 = message_schedule[i * SHA256_ROUNDS_PER_ROW + j - 3];
-                    cols.schedule_helper.w_3[j] = u32_into_u16s(w_3).map(F::from_canonical_u32);
+                let cols: &mut Sha2DigestCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT> =
+                    row[get_range(trace_start_col, Self::DIGEST_WIDTH)].borrow_mut();
+                for j in 0..SHA2_ROUNDS_PER_ROW - 1 {
+                    let w_3 = message_schedule[i * SHA2_ROUNDS_PER_ROW + j - 3];
+                    cols.schedule_helper.w_3[j] =
+                        word_into_u16s::<C::Word, WORD_U16S>(w_3).map(F::from_canonical_u32);
                 }
                 cols.flags.is_round_row = F::ZERO;
                 cols.flags.is_first_4_rows = F::ZERO;
                 cols.flags.is_digest_row = F::ONE;
                 cols.flags.is_last_block = F::from_bool(is_last_block);
-                cols.flags.row_idx =
-                    get_flag_pt_array(&self.row_idx_encoder, 16).map(F::from_canonical_u32);
+                cols.flags.row_idx = get_flag_pt_array(&self.row_idx_encoder, C::ROUND_ROWS)
+                    .map(F::from_canonical_u32);
                 cols.flags.global_block_idx = F::from_canonical_u32(global_block_idx);
 
                 cols.flags.local_block_idx = F::from_canonical_u32(local_block_idx);
-                let final_hash: [u32; SHA256_HASH_WORDS] =
+                let final_hash: [C::Word; SHA2_HASH_WORDS] =
                     array::from_fn(|i| work_vars[i].wrapping_add(prev_hash[i]));
-                let final_hash_limbs: [[u8; SHA256_WORD_U8S]; SHA256_HASH_WORDS] =
-                    array::from_fn(|i| final_hash[i].to_le_bytes());
+                let final_hash_limbs: [[u8; WORD_U8S]; SHA2_HASH_WORDS] = array::from_fn(|i| {
+                    array::from_fn(|j| (final_hash[i] >> (8 * j)).low_u32() as u8)
+                });
                 // need to ensure final hash limbs are bytes, in order for
                 //   prev_hash[i] + work_vars[i] == final_hash[i]
                 // to be constrained correctly
@@ -247,45 +377,44 @@ impl Sha256FillerHelper {
                 cols.final_hash = array::from_fn(|i| {
                     array::from_fn(|j| F::from_canonical_u8(final_hash_limbs[i][j]))
                 });
-                cols.prev_hash = prev_hash.map(|f| u32_into_u16s(f).map(F::from_canonical_u32));
-                let hash = if is_last_block {
-                    SHA256_H.map(u32_into_bits_field::<F>)
+                cols.prev_hash = prev_hash
+                    .map(|f| word_into_u16s::<C::Word, WORD_U16S>(f).map(F::from_canonical_u32));
+                let hash = if let Some(next_hash) = next_hash {
+                    next_hash.map(word_into_bits_field::<F, C::Word, WORD_BITS>)
                 } else {
-                    cols.final_hash
-                        .map(|f| u32::from_le_bytes(f.map(|x| x.as_canonical_u32() as u8)))
-                        .map(u32_into_bits_field::<F>)
+                    final_hash.map(word_into_bits_field::<F, C::Word, WORD_BITS>)
                 };
 
-                for i in 0..SHA256_ROUNDS_PER_ROW {
-                    cols.hash.a[i] = hash[SHA256_ROUNDS_PER_ROW - i - 1];
-                    cols.hash.e[i] = hash[SHA256_ROUNDS_PER_ROW - i + 3];
+                for i in 0..SHA2_ROUNDS_PER_ROW {
+                    cols.hash.a[i] = hash[SHA2_ROUNDS_PER_ROW - i - 1];
+                    cols.hash.e[i] = hash[SHA2_ROUNDS_PER_ROW - i + 3];
                 }
             }
         }
 
-        for i in 0..SHA256_ROWS_PER_BLOCK - 1 {
+        for i in 0..C::ROWS_PER_BLOCK - 1 {
             let rows = &mut trace[i * trace_width..(i + 2) * trace_width];
             let (local, next) = rows.split_at_mut(trace_width);
-            let local_cols: &mut Sha256RoundCols<F> =
-                local[get_range(trace_start_col, SHA256_ROUND_WIDTH)].borrow_mut();
-            let next_cols: &mut Sha256RoundCols<F> =
-                next[get_range(trace_start_col, SHA256_ROUND_WIDTH)].borrow_mut();
+            let local_cols: &mut Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT> =
+                local[get_range(trace_start_col, Self::ROUND_WIDTH)].borrow_mut();
+            let next_cols: &mut Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT> =
+                next[get_range(trace_start_col, Self::ROUND_WIDTH)].borrow_mut();
             if i > 0 {
-                for j in 0..SHA256_ROUNDS_PER_ROW {
+                for j in 0..SHA2_ROUNDS_PER_ROW {
                     next_cols.schedule_helper.intermed_8[j] =
                         local_cols.schedule_helper.intermed_4[j];
-                    if (2..SHA256_ROWS_PER_BLOCK - 3).contains(&i) {
+                    if (2..C::ROWS_PER_BLOCK - 3).contains(&i) {
                         next_cols.schedule_helper.intermed_12[j] =
                             local_cols.schedule_helper.intermed_8[j];
                     }
                 }
             }
-            if i == SHA256_ROWS_PER_BLOCK - 2 {
+            if i == C::ROWS_PER_BLOCK - 2 {
                 // `next` is a digest row.
                 // Fill in `carry_a` and `carry_e` with dummy values so the constraints on `a` and
                 // `e` hold.
                 Self::generate_carry_ae(local_cols, next_cols);
-                // Fill in row 16's `intermed_4` with dummy values so the message schedule
+                // Fill in the digest row's `intermed_4` with dummy values so the message schedule
                 // constraints holds on that row
                 Self::generate_intermed_4(local_cols, next_cols);
             }
@@ -302,81 +431,111 @@ impl Sha256FillerHelper {
     /// This function should be called only after `generate_block_trace` was called for all blocks
     /// And [`Self::generate_default_row`] is called for all invalid rows
     /// Will populate the missing values of `trace`, where the width of the trace is `trace_width`
-    /// and the starting column for the `Sha256Air` is `trace_start_col`.
-    /// Note: `trace` needs to be the rows 1..17 of a block and the first row of the next block
+    /// and the starting column for the `Sha2Air` is `trace_start_col`.
+    /// Note: `trace` needs to be the rows 1..ROWS_PER_BLOCK of a block and the first row of the
+    /// next block
     pub fn generate_missing_cells<F: PrimeField32>(
         &self,
         trace: &mut [F],
         trace_width: usize,
         trace_start_col: usize,
     ) {
-        // Here row_17 = next blocks row 0
-        let rows_15_17 = &mut trace[14 * trace_width..17 * trace_width];
-        let (row_15, row_16_17) = rows_15_17.split_at_mut(trace_width);
-        let (row_16, row_17) = row_16_17.split_at_mut(trace_width);
-        let cols_15: &mut Sha256RoundCols<F> =
-            row_15[trace_start_col..trace_start_col + SHA256_ROUND_WIDTH].borrow_mut();
-        let cols_16: &mut Sha256RoundCols<F> =
-            row_16[trace_start_col..trace_start_col + SHA256_ROUND_WIDTH].borrow_mut();
-        let cols_17: &mut Sha256RoundCols<F> =
-            row_17[trace_start_col..trace_start_col + SHA256_ROUND_WIDTH].borrow_mut();
-        // Fill in row 15's `intermed_12` with dummy values so the message schedule constraints
-        // holds on row 16
-        Self::generate_intermed_12(cols_15, cols_16);
-        // Fill in row 16's `intermed_12` with dummy values so the message schedule constraints
-        // holds on the next block's row 0
-        Self::generate_intermed_12(cols_16, cols_17);
+        // Here the rows are the last round row, the digest row and the next block's row 0, which
+        // are rows 14..17 of `trace` for SHA-256
+        let last_rows =
+            &mut trace[(C::ROUND_ROWS - 2) * trace_width..C::ROWS_PER_BLOCK * trace_width];
+        let (last_round_row, rows) = last_rows.split_at_mut(trace_width);
+        let (digest_row, next_row) = rows.split_at_mut(trace_width);
+        let last_round_cols: &mut Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT> =
+            last_round_row[trace_start_col..trace_start_col + Self::ROUND_WIDTH].borrow_mut();
+        let digest_cols: &mut Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT> =
+            digest_row[trace_start_col..trace_start_col + Self::ROUND_WIDTH].borrow_mut();
+        let next_cols: &mut Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT> =
+            next_row[trace_start_col..trace_start_col + Self::ROUND_WIDTH].borrow_mut();
+        // Fill in the last round row's `intermed_12` with dummy values so the message schedule
+        // constraints holds on the digest row
+        Self::generate_intermed_12(last_round_cols, digest_cols);
+        // Fill in the digest row's `intermed_12` with dummy values so the message schedule
+        // constraints holds on the next block's row 0
+        Self::generate_intermed_12(digest_cols, next_cols);
         // Fill in row 0's `intermed_4` with dummy values so the message schedule constraints holds
         // on that row
-        Self::generate_intermed_4(cols_16, cols_17);
+        Self::generate_intermed_4(digest_cols, next_cols);
     }
 
     /// Fills the `cols` as a padding row
     /// Note: we still need to correctly fill in the hash values, carries and intermeds
     pub fn generate_default_row<F: PrimeField32>(
-        self: &Sha256FillerHelper,
-        cols: &mut Sha256RoundCols<F>,
+        &self,
+        cols: &mut Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT>,
     ) {
         cols.flags.row_idx =
-            get_flag_pt_array(&self.row_idx_encoder, 17).map(F::from_canonical_u32);
+            get_flag_pt_array(&self.row_idx_encoder, C::ROWS_PER_BLOCK).map(F::from_canonical_u32);
 
-        let hash = SHA256_H.map(u32_into_bits_field::<F>);
+        let hash = self
+            .initial_hash
+            .map(word_into_bits_field::<F, C::Word, WORD_BITS>);
 
-        for i in 0..SHA256_ROUNDS_PER_ROW {
-            cols.work_vars.a[i] = hash[SHA256_ROUNDS_PER_ROW - i - 1];
-            cols.work_vars.e[i] = hash[SHA256_ROUNDS_PER_ROW - i + 3];
+        for i in 0..SHA2_ROUNDS_PER_ROW {
+            cols.work_vars.a[i] = hash[SHA2_ROUNDS_PER_ROW - i - 1];
+            cols.work_vars.e[i] = hash[SHA2_ROUNDS_PER_ROW - i + 3];
         }
 
-        cols.work_vars.carry_a = array::from_fn(|i| {
-            array::from_fn(|j| F::from_canonical_u32(SHA256_INVALID_CARRY_A[i][j]))
-        });
-        cols.work_vars.carry_e = array::from_fn(|i| {
-            array::from_fn(|j| F::from_canonical_u32(SHA256_INVALID_CARRY_E[i][j]))
-        });
+        cols.work_vars.carry_a = self
+            .invalid_carry_a
+            .map(|carries| carries.map(F::from_canonical_u32));
+        cols.work_vars.carry_e = self
+            .invalid_carry_e
+            .map(|carries| carries.map(F::from_canonical_u32));
+    }
+
+    /// Fills the `cols` as a padding row for a [Sha2Air](crate::Sha2Air) created with
+    /// [new_with_free_prev_hash](crate::Sha2Air::new_with_free_prev_hash), where `hash` is the
+    /// hash of the last digest row of the trace
+    /// Note: we still need to correctly fill in the intermeds
+    pub fn generate_free_default_row<F: PrimeField32>(
+        &self,
+        cols: &mut Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT>,
+        hash: &[C::Word; SHA2_HASH_WORDS],
+    ) {
+        cols.flags.row_idx =
+            get_flag_pt_array(&self.row_idx_encoder, C::ROWS_PER_BLOCK).map(F::from_canonical_u32);
+
+        let hash = hash.map(word_into_bits_field::<F, C::Word, WORD_BITS>);
+
+        for i in 0..SHA2_ROUNDS_PER_ROW {
+            cols.work_vars.a[i] = hash[SHA2_ROUNDS_PER_ROW - i - 1];
+            cols.work_vars.e[i] = hash[SHA2_ROUNDS_PER_ROW - i + 3];
+        }
+
+        // The row above a padding row has the same work variables, so the carries are the same
+        // for every padding row
+        let prev_cols = *cols;
+        Self::generate_carry_ae(&prev_cols, cols);
     }
 
     /// The following functions do the calculations in native field since they will be called on
     /// padding rows which can overflow and we need to make sure it matches the AIR constraints
     /// Puts the correct carrys in the `next_row`, the resulting carrys can be out of bound
     fn generate_carry_ae<F: PrimeField32>(
-        local_cols: &Sha256RoundCols<F>,
-        next_cols: &mut Sha256RoundCols<F>,
+        local_cols: &Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT>,
+        next_cols: &mut Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT>,
     ) {
         let a = [local_cols.work_vars.a, next_cols.work_vars.a].concat();
         let e = [local_cols.work_vars.e, next_cols.work_vars.e].concat();
-        for i in 0..SHA256_ROUNDS_PER_ROW {
+        for i in 0..SHA2_ROUNDS_PER_ROW {
             let cur_a = a[i + 4];
-            let sig_a = big_sig0_field::<F>(&a[i + 3]);
-            let maj_abc = maj_field::<F>(&a[i + 3], &a[i + 2], &a[i + 1]);
+            let sig_a = big_sig0_field::<C, F, WORD_BITS>(&a[i + 3]);
+            let maj_abc = maj_field::<F, WORD_BITS>(&a[i + 3], &a[i + 2], &a[i + 1]);
             let d = a[i];
             let cur_e = e[i + 4];
-            let sig_e = big_sig1_field::<F>(&e[i + 3]);
-            let ch_efg = ch_field::<F>(&e[i + 3], &e[i + 2], &e[i + 1]);
+            let sig_e = big_sig1_field::<C, F, WORD_BITS>(&e[i + 3]);
+            let ch_efg = ch_field::<F, WORD_BITS>(&e[i + 3], &e[i + 2], &e[i + 1]);
             let h = e[i];
 
             let t1 = [h, sig_e, ch_efg];
             let t2 = [sig_a, maj_abc];
-            for j in 0..SHA256_WORD_U16S {
+            for j in 0..WORD_U16S {
                 let t1_limb_sum = t1.iter().fold(F::ZERO, |acc, x| {
                     acc + compose::<F>(&x[j * 16..(j + 1) * 16], 1)
                 });
@@ -413,17 +572,17 @@ impl Sha256FillerHelper {
 
     /// Puts the correct intermed_4 in the `next_row`
     fn generate_intermed_4<F: PrimeField32>(
-        local_cols: &Sha256RoundCols<F>,
-        next_cols: &mut Sha256RoundCols<F>,
+        local_cols: &Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT>,
+        next_cols: &mut Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT>,
     ) {
         let w = [local_cols.message_schedule.w, next_cols.message_schedule.w].concat();
-        let w_limbs: Vec<[F; SHA256_WORD_U16S]> = w
+        let w_limbs: Vec<[F; WORD_U16S]> = w
             .iter()
             .map(|x| array::from_fn(|i| compose::<F>(&x[i * 16..(i + 1) * 16], 1)))
             .collect();
-        for i in 0..SHA256_ROUNDS_PER_ROW {
-            let sig_w = small_sig0_field::<F>(&w[i + 1]);
-            let sig_w_limbs: [F; SHA256_WORD_U16S] =
+        for i in 0..SHA2_ROUNDS_PER_ROW {
+            let sig_w = small_sig0_field::<C, F, WORD_BITS>(&w[i + 1]);
+            let sig_w_limbs: [F; WORD_U16S] =
                 array::from_fn(|j| compose::<F>(&sig_w[j * 16..(j + 1) * 16], 1));
             for (j, sig_w_limb) in sig_w_limbs.iter().enumerate() {
                 next_cols.schedule_helper.intermed_4[i][j] = w_limbs[i][j] + *sig_w_limb;
@@ -433,18 +592,21 @@ impl Sha256FillerHelper {
 
     /// Puts the needed intermed_12 in the `local_row`
     fn generate_intermed_12<F: PrimeField32>(
-        local_cols: &mut Sha256RoundCols<F>,
-        next_cols: &Sha256RoundCols<F>,
+        local_cols: &mut Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT>,
+        next_cols: &Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT>,
     ) {
         let w = [local_cols.message_schedule.w, next_cols.message_schedule.w].concat();
-        let w_limbs: Vec<[F; SHA256_WORD_U16S]> = w
+        let w_limbs: Vec<[F; WORD_U16S]> = w
             .iter()
             .map(|x| array::from_fn(|i| compose::<F>(&x[i * 16..(i + 1) * 16], 1)))
             .collect();
-        for i in 0..SHA256_ROUNDS_PER_ROW {
+        for i in 0..SHA2_ROUNDS_PER_ROW {
             // sig_1(w_{t-2})
-            let sig_w_2: [F; SHA256_WORD_U16S] = array::from_fn(|j| {
-                compose::<F>(&small_sig1_field::<F>(&w[i + 2])[j * 16..(j + 1) * 16], 1)
+            let sig_w_2: [F; WORD_U16S] = array::from_fn(|j| {
+                compose::<F>(
+                    &small_sig1_field::<C, F, WORD_BITS>(&w[i + 2])[j * 16..(j + 1) * 16],
+                    1,
+                )
             });
             // w_{t-7}
             let w_7 = if i < 3 {
@@ -454,7 +616,7 @@ impl Sha256FillerHelper {
             };
             // w_t
             let w_cur = w_limbs[i + 4];
-            for j in 0..SHA256_WORD_U16S {
+            for j in 0..WORD_U16S {
                 let carry = next_cols.message_schedule.carry_or_buffer[i][j * 2]
                     + F::TWO * next_cols.message_schedule.carry_or_buffer[i][j * 2 + 1];
                 let sum = sig_w_2[j] + w_7[j] - carry * F::from_canonical_u32(1 << 16) - w_cur[j]
@@ -471,30 +633,43 @@ impl Sha256FillerHelper {
     }
 }
 
-/// Generates a trace for a standalone SHA256 computation (currently only used for testing)
+/// Generates a trace for a standalone SHA-2 computation (currently only used for testing)
 /// `records` consists of pairs of `(input_block, is_last_block)`.
-pub fn generate_trace<F: PrimeField32>(
-    step: &Sha256FillerHelper,
+pub fn generate_trace<
+    F: PrimeField32,
+    C: Sha2Config,
+    const WORD_BITS: usize,
+    const WORD_U16S: usize,
+    const WORD_U8S: usize,
+    const ROW_VAR_CNT: usize,
+    B: AsRef<[u8]> + Send,
+>(
+    step: &Sha2FillerHelper<C, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT>,
     bitwise_lookup_chip: &BitwiseOperationLookupChip<8>,
     width: usize,
-    records: Vec<([u8; SHA256_BLOCK_U8S], bool)>,
+    records: Vec<(B, bool)>,
 ) -> RowMajorMatrix<F> {
-    let non_padded_height = records.len() * SHA256_ROWS_PER_BLOCK;
+    let non_padded_height = records.len() * C::ROWS_PER_BLOCK;
     let height = next_power_of_two_or_zero(non_padded_height);
     let mut values = F::zero_vec(height * width);
 
-    struct BlockContext {
-        prev_hash: [u32; 8],
+    struct BlockContext<W, B> {
+        prev_hash: [W; SHA2_HASH_WORDS],
         local_block_idx: u32,
         global_block_idx: u32,
-        input: [u8; SHA256_BLOCK_U8S],
+        input: B,
         is_last_block: bool,
     }
-    let mut block_ctx: Vec<BlockContext> = Vec::with_capacity(records.len());
-    let mut prev_hash = SHA256_H;
+    let mut block_ctx: Vec<BlockContext<C::Word, B>> = Vec::with_capacity(records.len());
+    let mut prev_hash = step.initial_hash;
     let mut local_block_idx = 0;
     let mut global_block_idx = 1;
     for (input, is_last_block) in records {
+        let next_hash =
+            Sha2FillerHelper::<C, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT>::get_block_hash(
+                &prev_hash,
+                input.as_ref(),
+            );
         block_ctx.push(BlockContext {
             prev_hash,
             local_block_idx,
@@ -505,15 +680,15 @@ pub fn generate_trace<F: PrimeField32>(
         global_block_idx += 1;
         if is_last_block {
             local_block_idx = 0;
-            prev_hash = SHA256_H;
+            prev_hash = step.initial_hash;
         } else {
             local_block_idx += 1;
-            prev_hash = Sha256FillerHelper::get_block_hash(&prev_hash, input);
+            prev_hash = next_hash;
         }
     }
     // first pass
     values
-        .par_chunks_exact_mut(width * SHA256_ROWS_PER_BLOCK)
+        .par_chunks_exact_mut(width * C::ROWS_PER_BLOCK)
         .zip(block_ctx)
         .for_each(|(block, ctx)| {
             let BlockContext {
@@ -523,9 +698,10 @@ pub fn generate_trace<F: PrimeField32>(
                 input,
                 is_last_block,
             } = ctx;
+            let input = input.as_ref();
             let input_words = array::from_fn(|i| {
-                limbs_into_u32::<SHA256_WORD_U8S>(array::from_fn(|j| {
-                    input[(i + 1) * SHA256_WORD_U8S - j - 1] as u32
+                limbs_into_word::<C::Word, WORD_U8S>(array::from_fn(|j| {
+                    input[(i + 1) * WORD_U8S - j - 1] as u32
                 }))
             });
             step.generate_block_trace(
@@ -544,13 +720,14 @@ pub fn generate_trace<F: PrimeField32>(
     values[width * non_padded_height..]
         .par_chunks_mut(width)
         .for_each(|row| {
-            let cols: &mut Sha256RoundCols<F> = row.borrow_mut();
+            let cols: &mut Sha2RoundCols<F, WORD_BITS, WORD_U16S, WORD_U8S, ROW_VAR_CNT> =
+                row.borrow_mut();
             step.generate_default_row(cols);
         });
     // second pass: non-padding rows
     values[width..]
-        .par_chunks_mut(width * SHA256_ROWS_PER_BLOCK)
-        .take(non_padded_height / SHA256_ROWS_PER_BLOCK)
+        .par_chunks_mut(width * C::ROWS_PER_BLOCK)
+        .take(non_padded_height / C::ROWS_PER_BLOCK)
         .for_each(|chunk| {
             step.generate_missing_cells(chunk, width, 0);
         });
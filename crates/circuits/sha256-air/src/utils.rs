@@ -7,9 +7,15 @@ use openvm_circuit_primitives::{
 };
 use openvm_stark_backend::{p3_air::AirBuilder, p3_field::FieldAlgebra};
 
-use super::{Sha256DigestCols, Sha256RoundCols};
+use super::{Sha256DigestCols, Sha256RoundCols, Sha2Config, Sha2Word};
 
 // ==== Do not change these constants! ====
+/// Number of words in a SHA-2 block
+pub const SHA2_BLOCK_WORDS: usize = 16;
+/// Number of rounds per row
+pub const SHA2_ROUNDS_PER_ROW: usize = 4;
+/// Number of words in a SHA-2 hash
+pub const SHA2_HASH_WORDS: usize = 8;
 /// Number of bits in a SHA256 word
 pub const SHA256_WORD_BITS: usize = 32;
 /// Number of 16-bit limbs in a SHA256 word
@@ -17,7 +23,7 @@ pub const SHA256_WORD_U16S: usize = SHA256_WORD_BITS / 16;
 /// Number of 8-bit limbs in a SHA256 word
 pub const SHA256_WORD_U8S: usize = SHA256_WORD_BITS / 8;
 /// Number of words in a SHA256 block
-pub const SHA256_BLOCK_WORDS: usize = 16;
+pub const SHA256_BLOCK_WORDS: usize = SHA2_BLOCK_WORDS;
 /// Number of cells in a SHA256 block
 pub const SHA256_BLOCK_U8S: usize = SHA256_BLOCK_WORDS * SHA256_WORD_U8S;
 /// Number of bits in a SHA256 block
@@ -25,9 +31,9 @@ pub const SHA256_BLOCK_BITS: usize = SHA256_BLOCK_WORDS * SHA256_WORD_BITS;
 /// Number of rows per block
 pub const SHA256_ROWS_PER_BLOCK: usize = 17;
 /// Number of rounds per row
-pub const SHA256_ROUNDS_PER_ROW: usize = 4;
+pub const SHA256_ROUNDS_PER_ROW: usize = SHA2_ROUNDS_PER_ROW;
 /// Number of words in a SHA256 hash
-pub const SHA256_HASH_WORDS: usize = 8;
+pub const SHA256_HASH_WORDS: usize = SHA2_HASH_WORDS;
 /// Number of vars needed to encode the row index with [Encoder]
 pub const SHA256_ROW_VAR_CNT: usize = 5;
 /// Width of the Sha256RoundCols
@@ -80,42 +86,43 @@ pub fn get_sha256_num_blocks(len: u32) -> u32 {
     ((len << 3) as usize + 1 + 64).div_ceil(SHA256_BLOCK_BITS) as u32
 }
 
-/// Convert a u32 into a list of bits in little endian then convert each bit into a field element
-pub fn u32_into_bits_field<F: FieldAlgebra + Clone>(num: u32) -> [F; SHA256_WORD_BITS] {
-    array::from_fn(|i| F::from_bool((num >> i) & 1 == 1))
+/// Convert a word into a list of bits in little endian then convert each bit into a field element
+pub fn word_into_bits_field<F: FieldAlgebra + Clone, W: Sha2Word, const WORD_BITS: usize>(
+    num: W,
+) -> [F; WORD_BITS] {
+    array::from_fn(|i| F::from_bool((num >> i).low_u32() & 1 == 1))
 }
 
-/// Convert a u32 into a an array of 2 16-bit limbs in little endian
-pub fn u32_into_u16s(num: u32) -> [u32; 2] {
-    [num & 0xffff, num >> 16]
+/// Convert a word into an array of 16-bit limbs in little endian
+pub fn word_into_u16s<W: Sha2Word, const WORD_U16S: usize>(num: W) -> [u32; WORD_U16S] {
+    array::from_fn(|i| (num >> (16 * i)).low_u32() & 0xffff)
 }
 
-/// Convert a list of limbs in little endian into a u32
-pub fn limbs_into_u32<const NUM_LIMBS: usize>(limbs: [u32; NUM_LIMBS]) -> u32 {
-    let limb_bits = 32 / NUM_LIMBS;
-    limbs
-        .iter()
-        .rev()
-        .fold(0, |acc, &limb| (acc << limb_bits) | limb)
+/// Convert a list of limbs in little endian into a word
+pub fn limbs_into_word<W: Sha2Word, const NUM_LIMBS: usize>(limbs: [u32; NUM_LIMBS]) -> W {
+    let limb_bits = W::BITS / NUM_LIMBS;
+    limbs.iter().rev().fold(W::default(), |acc, &limb| {
+        (acc << limb_bits) | W::from_u32(limb)
+    })
 }
 
 /// Rotates `bits` right by `n` bits, assumes `bits` is in little-endian
 #[inline]
-pub(crate) fn rotr<F: FieldAlgebra + Clone>(
-    bits: &[impl Into<F> + Clone; SHA256_WORD_BITS],
+pub(crate) fn rotr<F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    bits: &[impl Into<F> + Clone; WORD_BITS],
     n: usize,
-) -> [F; SHA256_WORD_BITS] {
-    array::from_fn(|i| bits[(i + n) % SHA256_WORD_BITS].clone().into())
+) -> [F; WORD_BITS] {
+    array::from_fn(|i| bits[(i + n) % WORD_BITS].clone().into())
 }
 
 /// Shifts `bits` right by `n` bits, assumes `bits` is in little-endian
 #[inline]
-pub(crate) fn shr<F: FieldAlgebra + Clone>(
-    bits: &[impl Into<F> + Clone; SHA256_WORD_BITS],
+pub(crate) fn shr<F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    bits: &[impl Into<F> + Clone; WORD_BITS],
     n: usize,
-) -> [F; SHA256_WORD_BITS] {
+) -> [F; WORD_BITS] {
     array::from_fn(|i| {
-        if i + n < SHA256_WORD_BITS {
+        if i + n < WORD_BITS {
             bits[i + n].clone().into()
         } else {
             F::ZERO
@@ -137,44 +144,44 @@ pub(crate) fn xor_bit<F: FieldAlgebra + Clone>(
         + (not::<F>(x) * not::<F>(y) * z)
 }
 
-/// Computes x ^ y ^ z, where x, y, z are [SHA256_WORD_BITS] bit numbers
+/// Computes x ^ y ^ z, where x, y, z are `WORD_BITS` bit numbers
 #[inline]
-pub(crate) fn xor<F: FieldAlgebra + Clone>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-    y: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-    z: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
+pub(crate) fn xor<F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+    y: &[impl Into<F> + Clone; WORD_BITS],
+    z: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
     array::from_fn(|i| xor_bit(x[i].clone(), y[i].clone(), z[i].clone()))
 }
 
-/// Choose function from SHA256
+/// Choose function from SHA-2
 #[inline]
-pub fn ch(x: u32, y: u32, z: u32) -> u32 {
+pub fn ch<W: Sha2Word>(x: W, y: W, z: W) -> W {
     (x & y) ^ ((!x) & z)
 }
 
-/// Computes Ch(x,y,z), where x, y, z are [SHA256_WORD_BITS] bit numbers
+/// Computes Ch(x,y,z), where x, y, z are `WORD_BITS` bit numbers
 #[inline]
-pub(crate) fn ch_field<F: FieldAlgebra>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-    y: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-    z: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
+pub(crate) fn ch_field<F: FieldAlgebra, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+    y: &[impl Into<F> + Clone; WORD_BITS],
+    z: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
     array::from_fn(|i| select(x[i].clone(), y[i].clone(), z[i].clone()))
 }
 
-/// Majority function from SHA256
-pub fn maj(x: u32, y: u32, z: u32) -> u32 {
+/// Majority function from SHA-2
+pub fn maj<W: Sha2Word>(x: W, y: W, z: W) -> W {
     (x & y) ^ (x & z) ^ (y & z)
 }
 
-/// Computes Maj(x,y,z), where x, y, z are [SHA256_WORD_BITS] bit numbers
+/// Computes Maj(x,y,z), where x, y, z are `WORD_BITS` bit numbers
 #[inline]
-pub(crate) fn maj_field<F: FieldAlgebra + Clone>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-    y: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-    z: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
+pub(crate) fn maj_field<F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+    y: &[impl Into<F> + Clone; WORD_BITS],
+    z: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
     array::from_fn(|i| {
         let (x, y, z) = (
             x[i].clone().into(),
@@ -185,56 +192,94 @@ pub(crate) fn maj_field<F: FieldAlgebra + Clone>(
     })
 }
 
-/// Big sigma_0 function from SHA256
-pub fn big_sig0(x: u32) -> u32 {
-    x.rotate_right(2) ^ x.rotate_right(13) ^ x.rotate_right(22)
+/// Computes the xor of the rotations of `x` by `rot`
+#[inline]
+fn big_sig<W: Sha2Word>(x: W, rot: [usize; 3]) -> W {
+    x.rotate_right(rot[0]) ^ x.rotate_right(rot[1]) ^ x.rotate_right(rot[2])
+}
+
+/// Computes the xor of the rotations of `x` by `rot[0]` and `rot[1]` and its shift by `rot[2]`
+#[inline]
+fn small_sig<W: Sha2Word>(x: W, rot: [usize; 3]) -> W {
+    x.rotate_right(rot[0]) ^ x.rotate_right(rot[1]) ^ (x >> rot[2])
+}
+
+/// Computes [big_sig] on a `WORD_BITS` bit number in little-endian
+#[inline]
+fn big_sig_field<F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+    rot: [usize; 3],
+) -> [F; WORD_BITS] {
+    xor(
+        &rotr::<F, WORD_BITS>(x, rot[0]),
+        &rotr::<F, WORD_BITS>(x, rot[1]),
+        &rotr::<F, WORD_BITS>(x, rot[2]),
+    )
+}
+
+/// Computes [small_sig] on a `WORD_BITS` bit number in little-endian
+#[inline]
+fn small_sig_field<F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+    rot: [usize; 3],
+) -> [F; WORD_BITS] {
+    xor(
+        &rotr::<F, WORD_BITS>(x, rot[0]),
+        &rotr::<F, WORD_BITS>(x, rot[1]),
+        &shr::<F, WORD_BITS>(x, rot[2]),
+    )
+}
+
+/// Big sigma_0 function from SHA-2
+pub fn big_sig0<C: Sha2Config>(x: C::Word) -> C::Word {
+    big_sig(x, C::BIG_SIG0_ROT)
 }
 
-/// Computes BigSigma0(x), where x is a [SHA256_WORD_BITS] bit number in little-endian
+/// Computes BigSigma0(x), where x is a `WORD_BITS` bit number in little-endian
 #[inline]
-pub(crate) fn big_sig0_field<F: FieldAlgebra + Clone>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
-    xor(&rotr::<F>(x, 2), &rotr::<F>(x, 13), &rotr::<F>(x, 22))
+pub(crate) fn big_sig0_field<C: Sha2Config, F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
+    big_sig_field(x, C::BIG_SIG0_ROT)
 }
 
-/// Big sigma_1 function from SHA256
-pub fn big_sig1(x: u32) -> u32 {
-    x.rotate_right(6) ^ x.rotate_right(11) ^ x.rotate_right(25)
+/// Big sigma_1 function from SHA-2
+pub fn big_sig1<C: Sha2Config>(x: C::Word) -> C::Word {
+    big_sig(x, C::BIG_SIG1_ROT)
 }
 
-/// Computes BigSigma1(x), where x is a [SHA256_WORD_BITS] bit number in little-endian
+/// Computes BigSigma1(x), where x is a `WORD_BITS` bit number in little-endian
 #[inline]
-pub(crate) fn big_sig1_field<F: FieldAlgebra + Clone>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
-    xor(&rotr::<F>(x, 6), &rotr::<F>(x, 11), &rotr::<F>(x, 25))
+pub(crate) fn big_sig1_field<C: Sha2Config, F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
+    big_sig_field(x, C::BIG_SIG1_ROT)
 }
 
-/// Small sigma_0 function from SHA256
-pub fn small_sig0(x: u32) -> u32 {
-    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+/// Small sigma_0 function from SHA-2
+pub fn small_sig0<C: Sha2Config>(x: C::Word) -> C::Word {
+    small_sig(x, C::SMALL_SIG0_ROT)
 }
 
-/// Computes SmallSigma0(x), where x is a [SHA256_WORD_BITS] bit number in little-endian
+/// Computes SmallSigma0(x), where x is a `WORD_BITS` bit number in little-endian
 #[inline]
-pub(crate) fn small_sig0_field<F: FieldAlgebra + Clone>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
-    xor(&rotr::<F>(x, 7), &rotr::<F>(x, 18), &shr::<F>(x, 3))
+pub(crate) fn small_sig0_field<C: Sha2Config, F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
+    small_sig_field(x, C::SMALL_SIG0_ROT)
 }
 
-/// Small sigma_1 function from SHA256
-pub fn small_sig1(x: u32) -> u32 {
-    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+/// Small sigma_1 function from SHA-2
+pub fn small_sig1<C: Sha2Config>(x: C::Word) -> C::Word {
+    small_sig(x, C::SMALL_SIG1_ROT)
 }
 
-/// Computes SmallSigma1(x), where x is a [SHA256_WORD_BITS] bit number in little-endian
+/// Computes SmallSigma1(x), where x is a `WORD_BITS` bit number in little-endian
 #[inline]
-pub(crate) fn small_sig1_field<F: FieldAlgebra + Clone>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
-    xor(&rotr::<F>(x, 17), &rotr::<F>(x, 19), &shr::<F>(x, 10))
+pub(crate) fn small_sig1_field<C: Sha2Config, F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
+    small_sig_field(x, C::SMALL_SIG1_ROT)
 }
 
 /// Wrapper of `get_flag_pt` to get the flag pointer as an array
@@ -242,17 +287,17 @@ pub fn get_flag_pt_array<const N: usize>(encoder: &Encoder, flag_idx: usize) ->
     encoder.get_flag_pt(flag_idx).try_into().unwrap()
 }
 
-/// Constrain the addition of [SHA256_WORD_BITS] bit words in 16-bit limbs
+/// Constrain the addition of `WORD_BITS` bit words in 16-bit limbs
 /// It takes in the terms some in bits some in 16-bit limbs,
 /// the expected sum in bits and the carries
-pub fn constraint_word_addition<AB: AirBuilder>(
+pub fn constraint_word_addition<AB: AirBuilder, const WORD_BITS: usize, const WORD_U16S: usize>(
     builder: &mut AB,
-    terms_bits: &[&[impl Into<AB::Expr> + Clone; SHA256_WORD_BITS]],
-    terms_limb: &[&[impl Into<AB::Expr> + Clone; SHA256_WORD_U16S]],
-    expected_sum: &[impl Into<AB::Expr> + Clone; SHA256_WORD_BITS],
-    carries: &[impl Into<AB::Expr> + Clone; SHA256_WORD_U16S],
+    terms_bits: &[&[impl Into<AB::Expr> + Clone; WORD_BITS]],
+    terms_limb: &[&[impl Into<AB::Expr> + Clone; WORD_U16S]],
+    expected_sum: &[impl Into<AB::Expr> + Clone; WORD_BITS],
+    carries: &[impl Into<AB::Expr> + Clone; WORD_U16S],
 ) {
-    for i in 0..SHA256_WORD_U16S {
+    for i in 0..WORD_U16S {
         let mut limb_sum = if i == 0 {
             AB::Expr::ZERO
         } else {
@@ -0,0 +1,52 @@
+use openvm_sha256_air::{
+    Sha2Air, Sha2Config, Sha2DigestCols, Sha2FillerHelper, Sha2FlagsCols, Sha2MessageHelperCols,
+    Sha2MessageScheduleCols, Sha2RoundCols, Sha2WorkVarsCols, SHA2_HASH_WORDS,
+};
+use sha2::{compress512, digest::generic_array::GenericArray};
+
+use super::{
+    SHA512_H, SHA512_K, SHA512_ROUNDS, SHA512_ROW_VAR_CNT, SHA512_WORD_BITS, SHA512_WORD_U16S,
+    SHA512_WORD_U8S,
+};
+
+/// The parameters of the SHA512 compression function, which is shared with SHA384
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Sha512Config;
+
+impl Sha2Config for Sha512Config {
+    type Word = u64;
+
+    const ROUNDS: usize = SHA512_ROUNDS;
+    const K: &'static [u64] = &SHA512_K;
+    const H: [u64; SHA2_HASH_WORDS] = SHA512_H;
+    const BIG_SIG0_ROT: [usize; 3] = [28, 34, 39];
+    const BIG_SIG1_ROT: [usize; 3] = [14, 18, 41];
+    const SMALL_SIG0_ROT: [usize; 3] = [1, 8, 7];
+    const SMALL_SIG1_ROT: [usize; 3] = [19, 61, 6];
+
+    fn compress(state: &mut [u64; SHA2_HASH_WORDS], block: &[u8]) {
+        compress512(state, &[*GenericArray::from_slice(block)]);
+    }
+}
+
+/// Expects the message to be padded to a multiple of 1024 bits
+pub type Sha512Air =
+    Sha2Air<Sha512Config, SHA512_WORD_BITS, SHA512_WORD_U16S, SHA512_WORD_U8S, SHA512_ROW_VAR_CNT>;
+/// Use [Sha512Variant::filler_helper](crate::Sha512Variant::filler_helper) to create one
+pub type Sha512FillerHelper = Sha2FillerHelper<
+    Sha512Config,
+    SHA512_WORD_BITS,
+    SHA512_WORD_U16S,
+    SHA512_WORD_U8S,
+    SHA512_ROW_VAR_CNT,
+>;
+
+pub type Sha512RoundCols<T> =
+    Sha2RoundCols<T, SHA512_WORD_BITS, SHA512_WORD_U16S, SHA512_WORD_U8S, SHA512_ROW_VAR_CNT>;
+pub type Sha512DigestCols<T> =
+    Sha2DigestCols<T, SHA512_WORD_BITS, SHA512_WORD_U16S, SHA512_WORD_U8S, SHA512_ROW_VAR_CNT>;
+pub type Sha512MessageScheduleCols<T> =
+    Sha2MessageScheduleCols<T, SHA512_WORD_BITS, SHA512_WORD_U8S>;
+pub type Sha512WorkVarsCols<T> = Sha2WorkVarsCols<T, SHA512_WORD_BITS, SHA512_WORD_U16S>;
+pub type Sha512MessageHelperCols<T> = Sha2MessageHelperCols<T, SHA512_WORD_U16S>;
+pub type Sha512FlagsCols<T> = Sha2FlagsCols<T, SHA512_ROW_VAR_CNT>;
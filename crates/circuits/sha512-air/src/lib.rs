@@ -0,0 +1,14 @@
+//! The SHA-512 configuration of the SHA-2 AIR from `openvm-sha256-air`
+//! This AIR doesn't constrain any of the message padding.
+//! SHA-384 uses the same compression function with a different initial hash, so the AIR is
+//! created from the initial hash of a [Sha512Variant].
+
+mod config;
+mod utils;
+
+pub use config::*;
+pub use openvm_sha256_air::{compose, generate_trace, get_flag_pt_array};
+pub use utils::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,178 @@
+use std::{array, borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::arch::{
+    instructions::riscv::RV32_CELL_BITS,
+    testing::{VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{
+        BitwiseOperationLookupAir, BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+        SharedBitwiseOperationLookupChip,
+    },
+    SubAir,
+};
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    interaction::{BusIndex, InteractionBuilder},
+    p3_air::{Air, BaseAir},
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    prover::{cpu::CpuBackend, types::AirProvingContext},
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    AirRef, Chip,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use crate::{
+    Sha512Air, Sha512DigestCols, Sha512FillerHelper, Sha512Variant, SHA512_BLOCK_U8S,
+    SHA512_DIGEST_WIDTH, SHA512_HASH_WORDS, SHA512_WIDTH, SHA512_WORD_U8S,
+};
+
+// A wrapper AIR purely for testing purposes
+#[derive(Clone, Debug)]
+pub struct Sha512TestAir {
+    pub sub_air: Sha512Air,
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for Sha512TestAir {}
+impl<F: Field> PartitionedBaseAir<F> for Sha512TestAir {}
+impl<F: Field> BaseAir<F> for Sha512TestAir {
+    fn width(&self) -> usize {
+        <Sha512Air as BaseAir<F>>::width(&self.sub_air)
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for Sha512TestAir {
+    fn eval(&self, builder: &mut AB) {
+        self.sub_air.eval(builder, 0);
+    }
+}
+
+const SELF_BUS_IDX: BusIndex = 28;
+type F = BabyBear;
+type RecordType = Vec<([u8; SHA512_BLOCK_U8S], bool)>;
+
+// A wrapper Chip purely for testing purposes
+pub struct Sha512TestChip {
+    pub step: Sha512FillerHelper,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<8>,
+}
+
+impl<SC: StarkGenericConfig> Chip<RecordType, CpuBackend<SC>> for Sha512TestChip
+where
+    Val<SC>: PrimeField32,
+{
+    fn generate_proving_ctx(&self, records: RecordType) -> AirProvingContext<CpuBackend<SC>> {
+        let trace: RowMajorMatrix<Val<SC>> = crate::generate_trace(
+            &self.step,
+            self.bitwise_lookup_chip.as_ref(),
+            SHA512_WIDTH,
+            records,
+        );
+        AirProvingContext::simple_no_pis(Arc::new(trace))
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn create_air_with_air_ctx<SC: StarkGenericConfig>(
+    variant: Sha512Variant,
+) -> (
+    (AirRef<SC>, AirProvingContext<CpuBackend<SC>>),
+    (
+        BitwiseOperationLookupAir<RV32_CELL_BITS>,
+        SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    ),
+)
+where
+    Val<SC>: PrimeField32,
+{
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let len = rng.gen_range(1..100);
+    let random_records: Vec<_> = (0..len)
+        .map(|i| {
+            (
+                array::from_fn(|_| rng.gen::<u8>()),
+                rng.gen::<bool>() || i == len - 1,
+            )
+        })
+        .collect();
+
+    let air = Sha512TestAir {
+        sub_air: Sha512Air::new_with_initial_hash(
+            bitwise_bus,
+            SELF_BUS_IDX,
+            variant.initial_hash(),
+        ),
+    };
+    let chip = Sha512TestChip {
+        step: variant.filler_helper(),
+        bitwise_lookup_chip: bitwise_chip.clone(),
+    };
+    let air_ctx = chip.generate_proving_ctx(random_records);
+
+    ((Arc::new(air), air_ctx), (bitwise_chip.air, bitwise_chip))
+}
+
+fn rand_test(variant: Sha512Variant) {
+    let tester = VmChipTestBuilder::default();
+    let (air_ctx, bitwise) = create_air_with_air_ctx(variant);
+    let tester = tester
+        .build()
+        .load_air_proving_ctx(air_ctx)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rand_sha512_test() {
+    rand_test(Sha512Variant::Sha512);
+}
+
+#[test]
+fn rand_sha384_test() {
+    rand_test(Sha512Variant::Sha384);
+}
+
+#[test]
+fn negative_sha512_test_bad_final_hash() {
+    let tester = VmChipTestBuilder::default();
+    let ((air, mut air_ctx), bitwise) = create_air_with_air_ctx(Sha512Variant::Sha512);
+
+    // Set the final_hash to all zeros
+    let modify_trace = |trace: &mut RowMajorMatrix<F>| {
+        trace.row_chunks_exact_mut(1).for_each(|row| {
+            let mut row_slice = row.row_slice(0).to_vec();
+            let cols: &mut Sha512DigestCols<F> = row_slice[..SHA512_DIGEST_WIDTH].borrow_mut();
+            if cols.flags.is_last_block.is_one() && cols.flags.is_digest_row.is_one() {
+                for i in 0..SHA512_HASH_WORDS {
+                    for j in 0..SHA512_WORD_U8S {
+                        cols.final_hash[i][j] = F::ZERO;
+                    }
+                }
+                row.values.copy_from_slice(&row_slice);
+            }
+        });
+    };
+
+    // Modify the air_ctx
+    let trace = Option::take(&mut air_ctx.common_main).unwrap();
+    let mut trace = Arc::into_inner(trace).unwrap();
+    modify_trace(&mut trace);
+    air_ctx.common_main = Some(Arc::new(trace));
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_air_proving_ctx((air, air_ctx))
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
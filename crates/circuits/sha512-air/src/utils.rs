@@ -0,0 +1,231 @@
+use openvm_sha256_air::{SHA2_BLOCK_WORDS, SHA2_HASH_WORDS, SHA2_ROUNDS_PER_ROW};
+
+use super::{Sha512DigestCols, Sha512FillerHelper, Sha512RoundCols};
+
+// ==== Do not change these constants! ====
+/// Number of bits in a SHA512 word
+pub const SHA512_WORD_BITS: usize = 64;
+/// Number of 16-bit limbs in a SHA512 word
+pub const SHA512_WORD_U16S: usize = SHA512_WORD_BITS / 16;
+/// Number of 8-bit limbs in a SHA512 word
+pub const SHA512_WORD_U8S: usize = SHA512_WORD_BITS / 8;
+/// Number of words in a SHA512 block
+pub const SHA512_BLOCK_WORDS: usize = SHA2_BLOCK_WORDS;
+/// Number of cells in a SHA512 block
+pub const SHA512_BLOCK_U8S: usize = SHA512_BLOCK_WORDS * SHA512_WORD_U8S;
+/// Number of bits in a SHA512 block
+pub const SHA512_BLOCK_BITS: usize = SHA512_BLOCK_WORDS * SHA512_WORD_BITS;
+/// Number of rounds of the SHA512 compression function
+pub const SHA512_ROUNDS: usize = 80;
+/// Number of rounds per row
+pub const SHA512_ROUNDS_PER_ROW: usize = SHA2_ROUNDS_PER_ROW;
+/// Number of rows doing the rounds of a block
+pub const SHA512_ROUND_ROWS: usize = SHA512_ROUNDS / SHA512_ROUNDS_PER_ROW;
+/// Number of rows per block
+pub const SHA512_ROWS_PER_BLOCK: usize = SHA512_ROUND_ROWS + 1;
+/// Number of words in a SHA512 hash
+pub const SHA512_HASH_WORDS: usize = SHA2_HASH_WORDS;
+/// Number of vars needed to encode the row index with [Encoder]
+pub const SHA512_ROW_VAR_CNT: usize = 6;
+/// Width of the Sha512RoundCols
+pub const SHA512_ROUND_WIDTH: usize = Sha512RoundCols::<u8>::width();
+/// Width of the Sha512DigestCols
+pub const SHA512_DIGEST_WIDTH: usize = Sha512DigestCols::<u8>::width();
+/// Size of the buffer of the first 4 rows of a block (each row's size)
+pub const SHA512_BUFFER_SIZE: usize = SHA512_ROUNDS_PER_ROW * SHA512_WORD_U16S * 2;
+/// Width of the Sha512Cols
+pub const SHA512_WIDTH: usize = if SHA512_ROUND_WIDTH > SHA512_DIGEST_WIDTH {
+    SHA512_ROUND_WIDTH
+} else {
+    SHA512_DIGEST_WIDTH
+};
+/// We can notice that `carry_a`'s and `carry_e`'s are always the same on invalid rows, since the
+/// work variables there are set to the initial hash of the variant.
+/// To optimize the trace generation of invalid rows, we have those values precomputed here
+const SHA512_INVALID_CARRY_A: [[u32; SHA512_WORD_U16S]; SHA512_ROUNDS_PER_ROW] = [
+    [55971842, 827997017, 993005918, 512731953],
+    [227512322, 1697529235, 1936430385, 940122990],
+    [1939875843, 1173318562, 826201586, 1513494849],
+    [891955202, 1732283693, 1736658755, 223514501],
+];
+const SHA512_INVALID_CARRY_E: [[u32; SHA512_WORD_U16S]; SHA512_ROUNDS_PER_ROW] = [
+    [1384427522, 1509509767, 153131516, 102514978],
+    [1527552003, 1041677071, 837289497, 843522538],
+    [775188482, 1620184630, 744892564, 892058728],
+    [1801267202, 1393118048, 1846108940, 830635531],
+];
+const SHA384_INVALID_CARRY_A: [[u32; SHA512_WORD_U16S]; SHA512_ROUNDS_PER_ROW] = [
+    [1571481603, 1428841901, 1050676523, 793575075],
+    [1233315842, 1822329223, 112923808, 1874228927],
+    [1245603842, 927240770, 1579759431, 70557227],
+    [195532801, 594312107, 1429379950, 220407092],
+];
+const SHA384_INVALID_CARRY_E: [[u32; SHA512_WORD_U16S]; SHA512_ROUNDS_PER_ROW] = [
+    [1067980802, 1508061099, 1418826213, 1232569491],
+    [1453086722, 1702524575, 152427899, 238512408],
+    [1623674882, 701393097, 1002035664, 4776891],
+    [1888911362, 184963225, 1151849224, 1034237098],
+];
+/// SHA512 constant K's
+pub const SHA512_K: [u64; SHA512_ROUNDS] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+/// SHA512 initial hash values
+pub const SHA512_H: [u64; SHA512_HASH_WORDS] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// SHA384 initial hash values
+pub const SHA384_H: [u64; SHA512_HASH_WORDS] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4,
+];
+
+/// The hash functions built on the SHA512 compression function.
+/// They only differ in the initial hash values and in how much of the final hash is output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sha512Variant {
+    Sha512,
+    Sha384,
+}
+
+impl Sha512Variant {
+    /// The initial hash values
+    pub const fn initial_hash(&self) -> [u64; SHA512_HASH_WORDS] {
+        match self {
+            Sha512Variant::Sha512 => SHA512_H,
+            Sha512Variant::Sha384 => SHA384_H,
+        }
+    }
+
+    /// Number of bytes in the digest, which is a prefix of the final hash
+    pub const fn digest_len(&self) -> usize {
+        match self {
+            Sha512Variant::Sha512 => 64,
+            Sha512Variant::Sha384 => 48,
+        }
+    }
+
+    const fn invalid_carry_a(&self) -> [[u32; SHA512_WORD_U16S]; SHA512_ROUNDS_PER_ROW] {
+        match self {
+            Sha512Variant::Sha512 => SHA512_INVALID_CARRY_A,
+            Sha512Variant::Sha384 => SHA384_INVALID_CARRY_A,
+        }
+    }
+
+    const fn invalid_carry_e(&self) -> [[u32; SHA512_WORD_U16S]; SHA512_ROUNDS_PER_ROW] {
+        match self {
+            Sha512Variant::Sha512 => SHA512_INVALID_CARRY_E,
+            Sha512Variant::Sha384 => SHA384_INVALID_CARRY_E,
+        }
+    }
+
+    /// The trace filler for messages hashed with this variant
+    pub fn filler_helper(&self) -> Sha512FillerHelper {
+        Sha512FillerHelper::new_with_initial_hash(
+            self.initial_hash(),
+            self.invalid_carry_a(),
+            self.invalid_carry_e(),
+        )
+    }
+}
+
+/// Returns the number of blocks required to hash a message of length `len`
+pub fn get_sha512_num_blocks(len: u32) -> u32 {
+    // need to pad with one 1 bit, 128 bits for the message length and then pad until the length
+    // is divisible by [SHA512_BLOCK_BITS]
+    ((len as usize) * 8 + 1 + 128).div_ceil(SHA512_BLOCK_BITS) as u32
+}
@@ -26,8 +26,11 @@ pub struct VmCli {
 pub enum VmCliCommands {
     Build(BuildCmd),
     Commit(CommitCmd),
+    Disasm(DisasmCmd),
     Keygen(KeygenCmd),
     Init(InitCmd),
+    Inspect(InspectCmd),
+    Kv(KvCmd),
     Prove(ProveCmd),
     Run(RunCmd),
     #[cfg(feature = "evm-verify")]
@@ -49,8 +52,11 @@ async fn main() -> Result<()> {
     match command {
         VmCliCommands::Build(cmd) => cmd.run(),
         VmCliCommands::Commit(cmd) => cmd.run(),
+        VmCliCommands::Disasm(cmd) => cmd.run(),
         VmCliCommands::Keygen(cmd) => cmd.run(),
         VmCliCommands::Init(cmd) => cmd.run(),
+        VmCliCommands::Inspect(cmd) => cmd.run(),
+        VmCliCommands::Kv(cmd) => cmd.run(),
         VmCliCommands::Prove(cmd) => cmd.run(),
         VmCliCommands::Run(cmd) => cmd.run(),
         #[cfg(feature = "evm-verify")]
@@ -13,11 +13,17 @@ use openvm_build::{
 use openvm_circuit::arch::{
     instructions::exe::VmExe, InitFileGenerator, OPENVM_DEFAULT_INIT_FILE_NAME,
 };
-use openvm_sdk::{config::TranspilerConfig, fs::write_object_to_file};
+use openvm_sdk::{
+    compose::DEFAULT_ROOT_VERIFIER_ASM_FILENAME, config::TranspilerConfig, fs::write_object_to_file,
+};
 use openvm_transpiler::{elf::Elf, openvm_platform::memory::MEM_SIZE, FromElf};
 
-use crate::util::{
-    get_manifest_path_and_dir, get_target_dir, get_target_output_dir, read_config_toml_or_default,
+use crate::{
+    default::default_asm_path,
+    util::{
+        get_manifest_path_and_dir, get_target_dir, get_target_output_dir,
+        read_config_toml_or_default,
+    },
 };
 
 #[derive(Parser)]
@@ -67,6 +73,15 @@ pub struct BuildArgs {
         help_heading = "OpenVM Options"
     )]
     pub init_file_name: String,
+
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_missing_value = DEFAULT_ROOT_VERIFIER_ASM_FILENAME,
+        help = "Copy the root verifier ASM written by `cargo openvm setup` to ${manifest_dir}/${copy_root_verifier_asm}, for guests that verify STARK proofs with define_verify_openvm_stark!",
+        help_heading = "OpenVM Options"
+    )]
+    pub copy_root_verifier_asm: Option<String>,
 }
 
 impl Default for BuildArgs {
@@ -76,6 +91,7 @@ impl Default for BuildArgs {
             config: None,
             output_dir: None,
             init_file_name: OPENVM_DEFAULT_INIT_FILE_NAME.to_string(),
+            copy_root_verifier_asm: None,
         }
     }
 }
@@ -358,6 +374,22 @@ pub fn build(build_args: &BuildArgs, cargo_args: &BuildCargoArgs) -> Result<Path
         .app_vm_config
         .write_to_init_file(&manifest_dir, Some(&build_args.init_file_name))?;
 
+    // Copy the root verifier ASM, which the guest includes at compile time
+    if let Some(asm_filename) = &build_args.copy_root_verifier_asm {
+        let asm_path = manifest_dir.join(asm_filename);
+        copy(default_asm_path(), &asm_path).map_err(|e| {
+            eyre::eyre!(
+                "Failed to copy the root verifier ASM from {}: {e}\nPlease run 'cargo openvm setup' first",
+                default_asm_path()
+            )
+        })?;
+        println!(
+            "[openvm] Copied the root verifier ASM from {} to {}",
+            default_asm_path(),
+            asm_path.display()
+        );
+    }
+
     // Build (allowing passed options to decide what gets built)
     let elf_target_dir = match build_generic(&guest_options) {
         Ok(raw_target_dir) => raw_target_dir,
@@ -75,7 +75,14 @@ impl CommitCmd {
             output_dir: self.output_dir.clone(),
             init_file_name: self.init_file_name.clone(),
             input: None,
+            kv_input: vec![],
             mode: ExecutionMode::Pure,
+            trace_output: PathBuf::from("trace.jsonl"),
+            profile_out: None,
+            cost_report: None,
+            debug: false,
+            gdb: None,
+            elf: None,
         };
         let (exe, target_name_stem) = load_or_build_exe(&run_args, &self.cargo_args)?;
         let sdk = Sdk::new(app_pk.app_config())?.with_app_pk(app_pk);
@@ -0,0 +1,107 @@
+use std::{
+    fs::{read, write},
+    path::PathBuf,
+};
+
+use clap::Parser;
+use eyre::Result;
+use openvm_circuit::arch::instructions::exe::VmExe;
+use openvm_sdk::{config::TranspilerConfig, fs::read_object_from_file, F};
+use openvm_transpiler::disasm::Disassembler;
+
+use super::{build, BuildArgs, RunCargoArgs};
+use crate::util::{
+    get_elf_path, get_manifest_path_and_dir, get_single_target_name, get_target_dir,
+    read_config_toml_or_default,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "disasm",
+    about = "Disassemble an OpenVM executable into annotated OpenVM assembly"
+)]
+pub struct DisasmCmd {
+    #[arg(
+        long,
+        action,
+        help = "Path to OpenVM executable, if specified build will be skipped",
+        help_heading = "OpenVM Options"
+    )]
+    pub exe: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Path to the OpenVM config .toml file that specifies the VM extensions whose opcodes are named, by default will search for the file at ${manifest_dir}/openvm.toml",
+        help_heading = "OpenVM Options"
+    )]
+    pub config: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Path to the guest ELF used to label functions and show the original RISC-V instructions, by default uses the ELF built alongside the executable",
+        help_heading = "OpenVM Options"
+    )]
+    pub elf: Option<PathBuf>,
+
+    #[arg(
+        long,
+        short = 'o',
+        value_name = "PATH",
+        help = "Path to write the disassembly to, by default prints it to stdout",
+        help_heading = "OpenVM Options"
+    )]
+    pub output: Option<PathBuf>,
+
+    #[command(flatten)]
+    cargo_args: RunCargoArgs,
+}
+
+impl DisasmCmd {
+    pub fn run(&self) -> Result<()> {
+        let mut target_name = None;
+        let exe_path = if let Some(exe) = &self.exe {
+            exe
+        } else {
+            // Build and get the executable name
+            let name = target_name.insert(get_single_target_name(&self.cargo_args)?);
+            let build_args = BuildArgs {
+                config: self.config.clone(),
+                ..Default::default()
+            };
+            let cargo_args = self.cargo_args.clone().into();
+            let output_dir = build(&build_args, &cargo_args)?;
+            &output_dir.join(name.with_extension("vmexe"))
+        };
+
+        let (manifest_path, manifest_dir) =
+            get_manifest_path_and_dir(&self.cargo_args.manifest_path)?;
+        let config_path = self
+            .config
+            .to_owned()
+            .unwrap_or_else(|| manifest_dir.join("openvm.toml"));
+        let app_config = read_config_toml_or_default(&config_path)?;
+        let exe: VmExe<F> = read_object_from_file(exe_path)?;
+
+        let mut disassembler = Disassembler::new(app_config.app_vm_config.transpiler());
+        let elf_path = self.elf.clone().or_else(|| {
+            let target_dir = get_target_dir(&self.cargo_args.target_dir, &manifest_path);
+            target_name.map(|name| get_elf_path(&target_dir, &self.cargo_args.profile, &name))
+        });
+        match elf_path {
+            Some(elf_path) if elf_path.exists() => {
+                disassembler = disassembler.with_elf(&read(elf_path)?)?;
+            }
+            _ => {
+                eprintln!("[openvm] No guest ELF found, functions and RISC-V instructions will not be shown");
+            }
+        }
+
+        let disassembly = disassembler.disassemble(&exe);
+        match &self.output {
+            Some(output) => write(output, disassembly)?,
+            None => print!("{disassembly}"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,508 @@
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use eyre::Result;
+use openvm_circuit::arch::{instructions::exe::VmExe, ContinuationVmProof};
+use openvm_continuations::verifier::internal::types::VmStarkProof;
+use openvm_sdk::{
+    commit::{commit_app_exe, AppExecutionCommit, CommitBytes, VmCommittedExe},
+    config::{FriSecurity, SdkVmConfig},
+    fs::{
+        decode_from_file, read_agg_pk_metadata_from_file, read_artifact_header,
+        read_from_file_json, read_object_from_file, ArtifactHeader, ArtifactKind, ArtifactVersion,
+    },
+    keygen::{AggVerifyingKey, AggVkFingerprint, AirMetadata, AppProvingKey, AppVerifyingKey},
+    prover::{SegmentJob, SegmentProof},
+    types::{VersionedVmStarkProof, VmStarkProofBundle},
+    StdIn, F, OPENVM_VERSION, SC,
+};
+use openvm_stark_backend::{
+    config::StarkGenericConfig, keygen::types::TraceWidth, p3_field::FieldExtensionAlgebra,
+    proof::Proof,
+};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+#[derive(Parser)]
+#[command(
+    name = "inspect",
+    about = "Print a summary of an OpenVM artifact, such as an executable, key or proof"
+)]
+pub struct InspectCmd {
+    #[arg(
+        help = "Path to an artifact written by OpenVM, such as a proving key, verifying key, executable or proof"
+    )]
+    pub file: PathBuf,
+
+    #[arg(
+        long,
+        action,
+        help = "Path to app proving key, used to compute the app commit and committed program hash of executables",
+        help_heading = "OpenVM Options"
+    )]
+    pub app_pk: Option<PathBuf>,
+
+    #[arg(
+        long,
+        action,
+        help = "Print the summary as JSON",
+        help_heading = "OpenVM Options"
+    )]
+    pub json: bool,
+}
+
+impl InspectCmd {
+    pub fn run(&self) -> Result<()> {
+        let summary = match read_artifact_header(&self.file)? {
+            Some(header) => self.summarize_artifact(header)?,
+            None => summarize_headerless(&self.file),
+        };
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&summary.to_json())?);
+        } else {
+            summary.print();
+        }
+        Ok(())
+    }
+
+    fn summarize_artifact(&self, header: ArtifactHeader) -> Result<Summary> {
+        let path = &self.file;
+        let mut summary = Summary::default();
+        summary.add("type", "Type", header.kind.to_string());
+        summary.add("version", "OpenVM version", header.version.to_string());
+        summary.add_compatibility(header.is_compatible());
+        if !header.is_compatible() {
+            return Ok(summary);
+        }
+        match header.kind {
+            ArtifactKind::AppProvingKey => {
+                let app_pk: AppProvingKey<SdkVmConfig> = read_object_from_file(path)?;
+                summary.add(
+                    "app_vk_fingerprint",
+                    "App VK fingerprint",
+                    hex::encode(app_pk.get_app_vk().fingerprint()),
+                );
+                summary.add("app_vm_commit", "App VM commit", app_pk.app_vm_commit());
+                summary.add("fri_params", "FRI parameters", app_pk.app_fri_params());
+                summary.add(
+                    "fri_security",
+                    "FRI security",
+                    FriSecurity::new(&app_pk.app_fri_params()),
+                );
+                summary.add(
+                    "num_public_values",
+                    "Public values",
+                    app_pk.num_public_values(),
+                );
+                summary.airs = app_pk
+                    .app_vm_pk
+                    .vm_pk
+                    .per_air
+                    .iter()
+                    .enumerate()
+                    .map(|(air_id, pk)| {
+                        AirSummary::new(
+                            air_id,
+                            Some(pk.air_name.clone()),
+                            &pk.vk.params.width,
+                            pk.vk.symbolic_constraints.interactions.len(),
+                        )
+                    })
+                    .collect();
+            }
+            ArtifactKind::AppVerifyingKey => {
+                let app_vk: AppVerifyingKey = read_object_from_file(path)?;
+                summary.add(
+                    "app_vk_fingerprint",
+                    "App VK fingerprint",
+                    hex::encode(app_vk.fingerprint()),
+                );
+                summary.add("fri_params", "FRI parameters", app_vk.fri_params);
+                summary.add(
+                    "fri_security",
+                    "FRI security",
+                    FriSecurity::new(&app_vk.fri_params),
+                );
+                // Verifying keys do not record the names of the AIRs
+                summary.airs = app_vk
+                    .vk
+                    .inner
+                    .per_air
+                    .iter()
+                    .enumerate()
+                    .map(|(air_id, vk)| {
+                        AirSummary::new(
+                            air_id,
+                            None,
+                            &vk.params.width,
+                            vk.symbolic_constraints.interactions.len(),
+                        )
+                    })
+                    .collect();
+            }
+            ArtifactKind::AggProvingKey => {
+                // The key itself is too large to deserialize, so only its metadata is read
+                let metadata = read_agg_pk_metadata_from_file(path)?;
+                summary.add_agg_vk_fingerprint(&metadata.agg_vk_fingerprint);
+                summary.add(
+                    "num_user_public_values",
+                    "User public values",
+                    metadata.num_user_public_values,
+                );
+                summary.add(
+                    "leaf_fri_params",
+                    "Leaf FRI parameters",
+                    metadata.leaf_fri_params,
+                );
+                summary.add(
+                    "leaf_fri_security",
+                    "Leaf FRI security",
+                    FriSecurity::new(&metadata.leaf_fri_params),
+                );
+                summary.add(
+                    "internal_fri_params",
+                    "Internal FRI parameters",
+                    metadata.internal_fri_params,
+                );
+                summary.add(
+                    "internal_fri_security",
+                    "Internal FRI security",
+                    FriSecurity::new(&metadata.internal_fri_params),
+                );
+                summary.add(
+                    "root_fri_params",
+                    "Root FRI parameters",
+                    metadata.root_fri_params,
+                );
+                summary.add(
+                    "root_fri_security",
+                    "Root FRI security",
+                    FriSecurity::new(&metadata.root_fri_params),
+                );
+                summary.airs = [
+                    ("leaf", &metadata.leaf_airs),
+                    ("internal", &metadata.internal_airs),
+                    ("root", &metadata.root_airs),
+                ]
+                .into_iter()
+                .flat_map(|(circuit, airs)| {
+                    airs.iter()
+                        .enumerate()
+                        .map(move |(air_id, air)| AirSummary::from_metadata(circuit, air_id, air))
+                })
+                .collect();
+            }
+            ArtifactKind::AggVerifyingKey => {
+                let agg_vk: AggVerifyingKey = read_object_from_file(path)?;
+                summary.add_agg_vk_fingerprint(&agg_vk.fingerprint());
+            }
+            ArtifactKind::Exe => {
+                let exe: VmExe<F> = read_object_from_file(path)?;
+                summary.add_exe(&exe);
+                if let Some(app_pk) = self.load_app_pk()? {
+                    let committed_exe = commit_app_exe(app_pk.app_fri_params(), exe);
+                    summary.add_app_commit(&committed_exe, &app_pk);
+                }
+            }
+            ArtifactKind::CommittedExe => {
+                let committed_exe: VmCommittedExe<SC> = read_object_from_file(path)?;
+                summary.add_exe(&committed_exe.exe);
+                match self.load_app_pk()? {
+                    Some(app_pk) => summary.add_app_commit(&committed_exe, &app_pk),
+                    None => summary.add_program_commit(&committed_exe),
+                }
+            }
+            ArtifactKind::SegmentProof => {
+                let proof: SegmentProof<SC> = read_object_from_file(path)?;
+                summary.add("segment_idx", "Segment", proof.segment_idx);
+                summary.add("num_airs", "AIRs", proof.proof.per_air.len());
+                summary.add(
+                    "terminal",
+                    "Terminal segment",
+                    proof.user_public_values.is_some(),
+                );
+            }
+            ArtifactKind::SegmentJob => {
+                let job: SegmentJob = read_object_from_file(path)?;
+                summary.add("segment_idx", "Segment", job.segment_idx);
+                summary.add("instret_start", "Start instret", job.segment.instret_start);
+                summary.add("num_insns", "Instructions", job.segment.num_insns);
+                summary.add(
+                    "start_state_bytes",
+                    "Start state size (bytes)",
+                    job.start_state.len(),
+                );
+            }
+            ArtifactKind::StdIn => {
+                let input: StdIn = read_object_from_file(path)?;
+                summary.add_input(&input);
+            }
+            ArtifactKind::Halo2ProvingKey => {}
+        }
+        Ok(summary)
+    }
+
+    fn load_app_pk(&self) -> Result<Option<AppProvingKey<SdkVmConfig>>> {
+        self.app_pk.as_ref().map(read_object_from_file).transpose()
+    }
+}
+
+/// Summarizes a file without an artifact header, which is either a JSON proof written by
+/// `cargo openvm prove stark`, a proof in the language-independent encoding written by
+/// `cargo openvm prove app` or `prove agg`, or an artifact written before artifacts were versioned.
+fn summarize_headerless(path: &Path) -> Summary {
+    let current = format!("v{OPENVM_VERSION}");
+    let mut summary = Summary::default();
+    let is_encoded_proof = path.extension().is_some_and(|ext| ext == "proof")
+        && !path.to_string_lossy().ends_with(".stark.proof")
+        && !path.to_string_lossy().ends_with(".evm.proof");
+    if let Ok(bundle) = read_from_file_json::<VmStarkProofBundle>(path) {
+        summary.add("type", "Type", "STARK proof bundle");
+        summary.add("version", "OpenVM version", &bundle.version);
+        summary.add_compatibility(bundle.version == current);
+        summary.add(
+            "app_exe_commit",
+            "App exe commit",
+            bundle.app_commit.app_exe_commit,
+        );
+        summary.add(
+            "app_vm_commit",
+            "App VM commit",
+            bundle.app_commit.app_vm_commit,
+        );
+        summary.add(
+            "vm_config_hash",
+            "VM config hash",
+            hex::encode(bundle.vm_config_hash),
+        );
+        summary.add_agg_vk_fingerprint(&bundle.agg_vk_fingerprint);
+        if let Ok(proof) = VmStarkProof::<SC>::try_from(bundle) {
+            summary.add_stark_proof(&proof);
+        }
+    } else if let Ok(proof) = read_from_file_json::<VersionedVmStarkProof>(path) {
+        summary.add("type", "Type", "STARK proof");
+        summary.add("version", "OpenVM version", &proof.version);
+        summary.add_compatibility(proof.version == current);
+        if let Ok(proof) = VmStarkProof::<SC>::try_from(proof) {
+            summary.add_stark_proof(&proof);
+        }
+    } else if is_encoded_proof && path.to_string_lossy().ends_with(".app.proof") {
+        // The encoding of proofs records its own version, and decoding fails on other versions
+        summary.add("type", "Type", "app proof");
+        match decode_from_file::<ContinuationVmProof<SC>>(path) {
+            Ok(proof) => {
+                summary.add_compatibility(true);
+                summary.add("num_segments", "Segments", proof.per_segment.len());
+                summary.add(
+                    "num_user_public_values",
+                    "User public values",
+                    proof.user_public_values.public_values.len(),
+                );
+            }
+            Err(e) => summary.add_decode_error(e),
+        }
+    } else if is_encoded_proof {
+        summary.add("type", "Type", "STARK proof without public values");
+        match decode_from_file::<Proof<SC>>(path) {
+            Ok(proof) => {
+                summary.add_compatibility(true);
+                summary.add("num_airs", "AIRs", proof.per_air.len());
+            }
+            Err(e) => summary.add_decode_error(e),
+        }
+    } else {
+        summary.add("type", "Type", "unknown");
+        summary.add(
+            "note",
+            "Note",
+            format!(
+                "The file has no artifact header. It is either not an OpenVM artifact, or was written by an OpenVM version older than {}, and can only be read with read_legacy_object_from_file of openvm-sdk",
+                ArtifactVersion::current()
+            ),
+        );
+    }
+    summary
+}
+
+/// Facts about an artifact, printed as one `label: value` line per fact followed by a table of
+/// the AIRs, or as a JSON object keyed by the keys of the facts.
+#[derive(Default)]
+struct Summary {
+    facts: Vec<(&'static str, &'static str, Value)>,
+    airs: Vec<AirSummary>,
+}
+
+#[derive(Serialize)]
+struct AirSummary {
+    /// Circuit of the AIR, for artifacts with the keys of several circuits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    circuit: Option<&'static str>,
+    air_id: usize,
+    name: Option<String>,
+    main_width: usize,
+    /// Width of all the traces of the AIR, in base field elements.
+    total_width: usize,
+    num_interactions: usize,
+}
+
+impl AirSummary {
+    fn new(
+        air_id: usize,
+        name: Option<String>,
+        width: &TraceWidth,
+        num_interactions: usize,
+    ) -> Self {
+        Self {
+            circuit: None,
+            air_id,
+            name,
+            main_width: width.main_width(),
+            total_width: width.total_width(<SC as StarkGenericConfig>::Challenge::D),
+            num_interactions,
+        }
+    }
+
+    fn from_metadata(circuit: &'static str, air_id: usize, air: &AirMetadata) -> Self {
+        Self {
+            circuit: Some(circuit),
+            air_id,
+            name: Some(air.name.clone()),
+            main_width: air.main_width,
+            total_width: air.total_width,
+            num_interactions: air.num_interactions,
+        }
+    }
+}
+
+impl Summary {
+    fn add(&mut self, key: &'static str, label: &'static str, value: impl Serialize) {
+        self.facts.push((key, label, json!(value)));
+    }
+
+    fn add_compatibility(&mut self, compatible: bool) {
+        self.add(
+            "compatible",
+            "Compatible with this OpenVM version",
+            compatible,
+        );
+    }
+
+    fn add_agg_vk_fingerprint(&mut self, fingerprint: &AggVkFingerprint) {
+        self.add(
+            "agg_leaf_vk_fingerprint",
+            "Aggregation leaf VK fingerprint",
+            hex::encode(fingerprint.leaf_vk),
+        );
+        self.add(
+            "agg_internal_vk_fingerprint",
+            "Aggregation internal VK fingerprint",
+            hex::encode(fingerprint.internal_vk),
+        );
+        self.add(
+            "internal_verifier_program_commit",
+            "Internal verifier program commit",
+            fingerprint.internal_verifier_program_commit,
+        );
+    }
+
+    fn add_exe(&mut self, exe: &VmExe<F>) {
+        self.add(
+            "program_len",
+            "Program length",
+            exe.program.num_defined_instructions(),
+        );
+        self.add("pc_start", "Start pc", format!("{:#x}", exe.pc_start));
+        self.add(
+            "init_memory_size",
+            "Initial memory size (bytes)",
+            exe.init_memory.len(),
+        );
+        self.add("num_functions", "Functions", exe.fn_bounds.len());
+    }
+
+    fn add_program_commit(&mut self, committed_exe: &VmCommittedExe<SC>) {
+        self.add(
+            "program_commit",
+            "Committed program hash",
+            CommitBytes::from_field_digest(&committed_exe.get_program_commit().into()),
+        );
+    }
+
+    fn add_app_commit(
+        &mut self,
+        committed_exe: &VmCommittedExe<SC>,
+        app_pk: &AppProvingKey<SdkVmConfig>,
+    ) {
+        self.add_program_commit(committed_exe);
+        let app_commit = AppExecutionCommit::compute::<SC>(
+            &app_pk.vm_config().as_ref().memory_config,
+            &committed_exe.exe,
+            committed_exe.get_program_commit(),
+            app_pk.leaf_verifier_program_commit(),
+        );
+        self.add(
+            "app_exe_commit",
+            "App exe commit",
+            app_commit.app_exe_commit,
+        );
+        self.add("app_vm_commit", "App VM commit", app_commit.app_vm_commit);
+    }
+
+    fn add_stark_proof(&mut self, proof: &VmStarkProof<SC>) {
+        self.add(
+            "num_user_public_values",
+            "User public values",
+            proof.user_public_values.len(),
+        );
+        self.add("num_airs", "AIRs", proof.inner.per_air.len());
+    }
+
+    fn add_decode_error(&mut self, error: eyre::Report) {
+        self.add_compatibility(false);
+        self.add("error", "Error", error.to_string());
+    }
+
+    fn add_input(&mut self, input: &StdIn) {
+        self.add("num_inputs", "Inputs", input.buffer.len());
+        self.add("num_kv_entries", "Key-value entries", input.kv_store.len());
+    }
+
+    fn to_json(&self) -> Value {
+        let mut object: Map<String, Value> = self
+            .facts
+            .iter()
+            .map(|(key, _, value)| (key.to_string(), value.clone()))
+            .collect();
+        if !self.airs.is_empty() {
+            object.insert("airs".to_string(), json!(self.airs));
+        }
+        Value::Object(object)
+    }
+
+    fn print(&self) {
+        for (_, label, value) in &self.facts {
+            match value.as_str() {
+                Some(value) => println!("{label}: {value}"),
+                None => println!("{label}: {value}"),
+            }
+        }
+        if !self.airs.is_empty() {
+            println!("AIRs: {}", self.airs.len());
+            println!(
+                "{:>8} {:>6} {:>10} {:>11} {:>12}  name",
+                "circuit", "air_id", "main_width", "total_width", "interactions"
+            );
+            for air in &self.airs {
+                println!(
+                    "{:>8} {:>6} {:>10} {:>11} {:>12}  {}",
+                    air.circuit.unwrap_or("-"),
+                    air.air_id,
+                    air.main_width,
+                    air.total_width,
+                    air.num_interactions,
+                    air.name.as_deref().unwrap_or("-")
+                );
+            }
+        }
+    }
+}
@@ -5,7 +5,7 @@ use std::{
 
 use clap::Parser;
 use eyre::Result;
-use openvm_sdk::{fs::write_object_to_file, Sdk};
+use openvm_sdk::{config::FriPreset, fs::write_object_to_file, Sdk};
 
 use crate::{
     default::{DEFAULT_APP_PK_NAME, DEFAULT_APP_VK_NAME},
@@ -32,6 +32,14 @@ pub struct KeygenCmd {
     )]
     output_dir: Option<PathBuf>,
 
+    #[arg(
+        long,
+        value_enum,
+        help = "FRI preset to generate the keys with, overriding the preset and FRI parameters of the config",
+        help_heading = "OpenVM Options"
+    )]
+    preset: Option<FriPreset>,
+
     #[command(flatten)]
     cargo_args: KeygenCargoArgs,
 }
@@ -70,6 +78,7 @@ impl KeygenCmd {
             &app_pk_path,
             &app_vk_path,
             self.output_dir.as_ref(),
+            self.preset,
         )?;
         println!(
             "Successfully generated app pk and vk in {}",
@@ -88,8 +97,19 @@ pub(crate) fn keygen(
     app_pk_path: impl AsRef<Path>,
     app_vk_path: impl AsRef<Path>,
     output_dir: Option<impl AsRef<Path>>,
+    preset: Option<FriPreset>,
 ) -> Result<()> {
-    let app_config = read_config_toml_or_default(config)?;
+    let mut app_config = read_config_toml_or_default(config)?;
+    if let Some(preset) = preset {
+        app_config = app_config.with_preset(preset);
+    }
+    if let Some(preset) = app_config.preset {
+        let security = preset.security();
+        println!(
+            "Using FRI preset {preset} with {} bits of conjectured and {} bits of proven security",
+            security.conjectured_bits, security.proven_bits
+        );
+    }
     let (app_pk, app_vk) = Sdk::new(app_config)?.app_keygen();
     write_object_to_file(&app_vk_path, app_vk)?;
     write_object_to_file(&app_pk_path, app_pk)?;
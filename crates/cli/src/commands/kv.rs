@@ -0,0 +1,138 @@
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use eyre::{Context, Result};
+use openvm_continuations::verifier::internal::types::VmStarkProof;
+use openvm_sdk::{
+    commit::AppExecutionCommit,
+    compose::{ChildProof, DEFAULT_ROOT_VERIFIER_ASM_FILENAME},
+    fs::{read_from_file_json, write_to_file_json},
+    types::VersionedVmStarkProof,
+    OPENVM_VERSION, SC,
+};
+
+use super::SingleTargetCargoArgs;
+use crate::util::{
+    get_app_commit_path, get_files_with_ext, get_manifest_path_and_dir, get_single_target_name_raw,
+    get_target_dir, get_target_output_dir,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "kv",
+    about = "Generate key-value files to pass to the --kv-input option"
+)]
+pub struct KvCmd {
+    #[command(subcommand)]
+    command: KvSubCommand,
+}
+
+#[derive(Parser)]
+enum KvSubCommand {
+    /// Generate the key-value pair from which `verify_openvm_stark`, defined with the
+    /// `define_verify_openvm_stark!` macro of `openvm-verify-stark`, reads a STARK proof
+    VerifyStark {
+        #[arg(
+            long,
+            action,
+            help = "Path to app commit of the proven program, by default will search for it using the binary target name",
+            help_heading = "OpenVM Options"
+        )]
+        app_commit: Option<PathBuf>,
+
+        #[arg(
+            long,
+            action,
+            help = "Path to STARK proof, by default will search the working directory for a file with extension .stark.proof",
+            help_heading = "OpenVM Options"
+        )]
+        proof: Option<PathBuf>,
+
+        #[arg(
+            long,
+            default_value = DEFAULT_ROOT_VERIFIER_ASM_FILENAME,
+            help = "Filename of the root verifier ASM passed to define_verify_openvm_stark!",
+            help_heading = "OpenVM Options"
+        )]
+        asm_filename: String,
+
+        #[arg(
+            long,
+            action,
+            help = "Path to the key-value output file, by default will be ${proof_path} with extension .kv.json",
+            help_heading = "Output Options"
+        )]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        cargo_args: SingleTargetCargoArgs,
+    },
+}
+
+impl KvCmd {
+    pub fn run(&self) -> Result<()> {
+        match &self.command {
+            KvSubCommand::VerifyStark {
+                app_commit,
+                proof,
+                asm_filename,
+                output,
+                cargo_args,
+            } => {
+                let app_commit_path = if let Some(app_commit) = app_commit {
+                    app_commit.to_path_buf()
+                } else {
+                    let (manifest_path, _) = get_manifest_path_and_dir(&cargo_args.manifest_path)?;
+                    let target_dir = get_target_dir(&cargo_args.target_dir, &manifest_path);
+                    let target_output_dir = get_target_output_dir(&target_dir, &cargo_args.profile);
+                    let target_name = get_single_target_name_raw(
+                        &cargo_args.bin,
+                        &cargo_args.example,
+                        &cargo_args.manifest_path,
+                        &cargo_args.package,
+                    )?;
+                    get_app_commit_path(&target_output_dir, target_name)
+                };
+                let app_commit: AppExecutionCommit = read_from_file_json(app_commit_path)?;
+
+                let proof_path = if let Some(proof) = proof {
+                    proof.clone()
+                } else {
+                    let files = get_files_with_ext(Path::new("."), "stark.proof")?;
+                    if files.len() > 1 {
+                        return Err(eyre::eyre!("multiple .stark.proof files found, please specify the path using option --proof"));
+                    } else if files.is_empty() {
+                        return Err(eyre::eyre!("no .stark.proof file found, please specify the path using option --proof"));
+                    }
+                    files[0].clone()
+                };
+                let stark_proof: VersionedVmStarkProof = read_from_file_json(&proof_path)
+                    .with_context(|| {
+                        format!("Proof needs to be compatible with openvm v{OPENVM_VERSION}",)
+                    })?;
+                let stark_proof: VmStarkProof<SC> = stark_proof.try_into()?;
+
+                let (key, value) =
+                    ChildProof::new(app_commit, stark_proof).kv_entry(asm_filename)?;
+                let output_path = output
+                    .clone()
+                    .unwrap_or_else(|| proof_path.with_extension("kv.json"));
+                write_to_file_json(
+                    &output_path,
+                    serde_json::json!({
+                        "kv_store": [{
+                            "key": format!("0x{}", hex::encode(&key)),
+                            "value": format!("0x{}", hex::encode(&value)),
+                        }]
+                    }),
+                )?;
+                println!("Hint key: 0x{}", hex::encode(&key));
+                println!(
+                    "Key-value file written to {}, pass it to `cargo openvm run` or `cargo openvm prove` with --kv-input",
+                    output_path.display()
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -4,12 +4,21 @@ pub use build::*;
 mod commit;
 pub use commit::*;
 
+mod disasm;
+pub use disasm::*;
+
 mod keygen;
 pub use keygen::*;
 
 mod init;
 pub use init::*;
 
+mod inspect;
+pub use inspect::*;
+
+mod kv;
+pub use kv::*;
+
 mod prove;
 pub use prove::*;
 
@@ -1,20 +1,34 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    fs::rename,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use clap::Parser;
-use eyre::Result;
+use clap::{Parser, ValueEnum};
+use eyre::{Context, Result};
 use openvm_circuit::arch::{
     execution_mode::metered::segment_ctx::{
         SegmentationLimits, DEFAULT_MAX_CELLS, DEFAULT_MAX_TRACE_HEIGHT_BITS,
     },
     instructions::exe::VmExe,
+    ContinuationVmProof,
 };
+use openvm_continuations::verifier::internal::types::VmStarkProof;
 use openvm_sdk::{
-    config::{AggregationTreeConfig, AppConfig, SdkVmConfig},
-    fs::{encode_to_file, read_object_from_file, write_to_file_json},
-    keygen::AppProvingKey,
-    types::VersionedVmStarkProof,
-    Sdk, F,
+    compose::{check_child_commits, ChildProof, DEFAULT_ROOT_VERIFIER_ASM_FILENAME},
+    config::{AggregationTreeConfig, AppConfig, FriPreset, SdkVmConfig},
+    fs::{
+        decode_from_file, encode_to_file, read_from_file_json, read_object_from_file,
+        write_to_file_json,
+    },
+    keygen::{AggProvingKey, AggVkFingerprint, AppProvingKey},
+    prover::AggregationTreeShape,
+    types::{vm_config_hash, VersionedVmStarkProof, VmStarkProofBundle},
+    util::sha256_encoded,
+    Sdk, F, OPENVM_VERSION, SC,
 };
+use openvm_stark_sdk::openvm_stark_backend::proof::Proof;
+use serde::{Deserialize, Serialize};
 
 use super::{RunArgs, RunCargoArgs};
 #[cfg(feature = "evm-prove")]
@@ -65,11 +79,18 @@ enum ProveSubCommand {
         #[arg(
             long,
             action,
-            help = "Path to STARK proof output, by default will be ./${bin_name}.stark.proof",
+            help = "Path to STARK proof output, by default will be ./${bin_name}.stark.proof, or ./${bin_name}.stark.bundle with --bundle",
             help_heading = "Output"
         )]
         proof: Option<PathBuf>,
 
+        #[arg(
+            long,
+            help = "Write a self-describing proof bundle, which also contains the app commit, VM config hash and aggregation verifying key fingerprints, to be verified with `cargo openvm verify stark --bundle`",
+            help_heading = "Output"
+        )]
+        bundle: bool,
+
         #[arg(
             long,
             action,
@@ -88,7 +109,76 @@ enum ProveSubCommand {
         segmentation_args: SegmentationArgs,
 
         #[command(flatten)]
-        agg_tree_config: AggregationTreeConfig,
+        agg_tree_args: AggTreeArgs,
+
+        #[command(flatten)]
+        compose_args: ComposeArgs,
+    },
+    /// Proves nodes of the aggregation tree of an app proof, so that the tree can be proven in
+    /// separate runs or on separate machines. The proof of every node is written to the
+    /// aggregation directory, and nodes whose proof already exists are skipped, so a failed run
+    /// can simply be repeated.
+    Agg {
+        #[arg(
+            long,
+            value_enum,
+            help = "Level of the aggregation tree to prove",
+            help_heading = "Aggregation Tree Options"
+        )]
+        level: AggLevel,
+
+        #[arg(
+            long,
+            help = "Height of the internal layer to prove, by default all internal layers from the bottom up",
+            help_heading = "Aggregation Tree Options"
+        )]
+        height: Option<usize>,
+
+        #[arg(
+            long,
+            help = "Index of the node to prove within its layer, by default all nodes of the layer",
+            help_heading = "Aggregation Tree Options"
+        )]
+        index: Option<usize>,
+
+        #[arg(
+            long,
+            action,
+            help = "Path to the app proof written by `cargo openvm prove app`",
+            help_heading = "OpenVM Options"
+        )]
+        app_proof: PathBuf,
+
+        #[arg(
+            long,
+            action,
+            default_value = "agg",
+            help = "Directory of the proofs of the nodes of the aggregation tree",
+            help_heading = "OpenVM Options"
+        )]
+        agg_dir: PathBuf,
+
+        #[arg(
+            long,
+            action,
+            help = "Path to STARK proof output of --level stark, by default will be ./${app_proof_stem}.stark.proof",
+            help_heading = "Output"
+        )]
+        proof: Option<PathBuf>,
+
+        #[arg(
+            long,
+            action,
+            help = "Path to app proving key, by default will be ${target_dir}/openvm/app.pk",
+            help_heading = "OpenVM Options"
+        )]
+        app_pk: Option<PathBuf>,
+
+        #[command(flatten)]
+        cargo_args: RunCargoArgs,
+
+        #[command(flatten)]
+        agg_tree_args: AggTreeArgs,
     },
     #[cfg(feature = "evm-prove")]
     Evm {
@@ -118,10 +208,22 @@ enum ProveSubCommand {
         segmentation_args: SegmentationArgs,
 
         #[command(flatten)]
-        agg_tree_config: AggregationTreeConfig,
+        agg_tree_args: AggTreeArgs,
     },
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum AggLevel {
+    /// Leaf nodes, which aggregate app segment proofs
+    Leaf,
+    /// Internal nodes, which aggregate leaf or internal proofs
+    Internal,
+    /// The STARK proof written by `prove stark`, which consists of the proof of the single node
+    /// of the last internal layer and the app public values. This is not the root verifier proof
+    /// that EVM proofs wrap.
+    Stark,
+}
+
 #[derive(Clone, Copy, Parser)]
 pub struct SegmentationArgs {
     /// Trace height threshold, in bits, across all chips for triggering segmentation for
@@ -143,6 +245,81 @@ pub struct SegmentationArgs {
     pub segment_max_cells: usize,
 }
 
+#[derive(Clone, Copy, Parser)]
+pub struct AggTreeArgs {
+    #[command(flatten)]
+    pub agg_tree_config: AggregationTreeConfig,
+
+    #[arg(
+        long,
+        value_enum,
+        conflicts_with_all = ["num_children_leaf", "num_children_internal", "max_internal_wrapper_layers"],
+        help = "Use the aggregation tree of the FRI preset instead of the options above",
+        help_heading = "Aggregation Tree Options"
+    )]
+    pub preset: Option<FriPreset>,
+}
+
+impl AggTreeArgs {
+    fn agg_tree_config(&self) -> AggregationTreeConfig {
+        self.preset
+            .map_or(self.agg_tree_config, FriPreset::agg_tree_config)
+    }
+}
+
+/// STARK proofs of child programs which the guest verifies with `verify_openvm_stark`, defined with
+/// the `define_verify_openvm_stark!` macro of `openvm-verify-stark`.
+#[derive(Clone, Parser)]
+pub struct ComposeArgs {
+    #[arg(
+        long,
+        action,
+        help = "Path to a STARK proof for the guest to verify, which is attached to the input after --input; can be repeated",
+        help_heading = "Composition Options"
+    )]
+    pub child_proof: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        action,
+        help = "Path to the app commit of the program of the --child-proof at the same position; must be repeated once per --child-proof",
+        help_heading = "Composition Options"
+    )]
+    pub child_app_commit: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        default_value = DEFAULT_ROOT_VERIFIER_ASM_FILENAME,
+        help = "Filename of the root verifier ASM passed to define_verify_openvm_stark!",
+        help_heading = "Composition Options"
+    )]
+    pub root_verifier_asm: String,
+}
+
+impl ComposeArgs {
+    fn read_children(&self) -> Result<Vec<ChildProof>> {
+        if self.child_proof.len() != self.child_app_commit.len() {
+            eyre::bail!(
+                "{} --child-proof but {} --child-app-commit given",
+                self.child_proof.len(),
+                self.child_app_commit.len()
+            );
+        }
+        self.child_proof
+            .iter()
+            .zip(&self.child_app_commit)
+            .map(|(proof_path, app_commit_path)| {
+                let proof: VersionedVmStarkProof =
+                    read_from_file_json(proof_path).with_context(|| {
+                        format!("Proof needs to be compatible with openvm v{OPENVM_VERSION}")
+                    })?;
+                let app_commit = read_from_file_json(app_commit_path)?;
+                Ok(ChildProof::new(app_commit, proof.try_into()?))
+            })
+            .collect()
+    }
+}
+
 impl ProveCmd {
     pub fn run(&self) -> Result<()> {
         match &self.command {
@@ -160,7 +337,7 @@ impl ProveCmd {
 
                 let app_proof = sdk
                     .app_prover(exe)?
-                    .prove(read_to_stdin(&run_args.input)?)?;
+                    .prove(read_to_stdin(&run_args.input, &run_args.kv_input)?)?;
 
                 let proof_path = if let Some(proof) = proof {
                     proof
@@ -176,20 +353,24 @@ impl ProveCmd {
             ProveSubCommand::Stark {
                 app_pk,
                 proof,
+                bundle,
                 run_args,
                 cargo_args,
                 segmentation_args,
-                agg_tree_config,
+                agg_tree_args,
+                compose_args,
             } => {
+                let children = compose_args.read_children()?;
                 let mut app_pk = load_app_pk(app_pk, cargo_args)?;
                 let (exe, target_name) = load_or_build_exe(run_args, cargo_args)?;
 
                 let agg_pk = read_object_from_file(default_agg_stark_pk_path()).map_err(|e| {
                     eyre::eyre!("Failed to read aggregation proving key: {}\nPlease run 'cargo openvm setup' first", e)
                 })?;
+                check_agg_pk(&app_pk, &agg_pk)?;
                 let app_config = get_app_config(&mut app_pk, segmentation_args);
                 let sdk = Sdk::new(app_config)?
-                    .with_agg_tree_config(*agg_tree_config)
+                    .with_agg_tree_config(agg_tree_args.agg_tree_config())
                     .with_app_pk(app_pk)
                     .with_agg_pk(agg_pk);
                 let mut prover = sdk.prover(exe)?;
@@ -197,11 +378,24 @@ impl ProveCmd {
                 println!("exe commit: {:?}", app_commit.app_exe_commit.to_bn254());
                 println!("vm commit: {:?}", app_commit.app_vm_commit.to_bn254());
 
-                let stark_proof = prover.prove(read_to_stdin(&run_args.input)?)?;
-                let stark_proof_bytes = VersionedVmStarkProof::new(stark_proof)?;
+                let mut stdin = read_to_stdin(&run_args.input, &run_args.kv_input)?;
+                for child in &children {
+                    stdin.add_child_proof(&compose_args.root_verifier_asm, child)?;
+                }
+                let stark_proof = prover.prove(stdin)?;
+                let child_commits: Vec<_> = children.iter().map(|child| child.app_commit).collect();
+                check_child_commits(&stark_proof.user_public_values, &child_commits)?;
+                if !children.is_empty() {
+                    println!(
+                        "Verified that the public values contain the commits of all {} child proofs",
+                        children.len()
+                    );
+                }
 
                 let proof_path = if let Some(proof) = proof {
                     proof
+                } else if *bundle {
+                    &PathBuf::from(target_name).with_extension("stark.bundle")
                 } else {
                     &PathBuf::from(target_name).with_extension("stark.proof")
                 };
@@ -209,7 +403,108 @@ impl ProveCmd {
                     "STARK proof completed! Writing STARK proof to {}",
                     proof_path.display()
                 );
-                write_to_file_json(proof_path, stark_proof_bytes)?;
+                if *bundle {
+                    let proof_bundle = VmStarkProofBundle::new(
+                        stark_proof,
+                        app_commit,
+                        vm_config_hash(&sdk.app_config().app_vm_config)?,
+                        &sdk.agg_pk().get_agg_vk(),
+                    )?;
+                    write_to_file_json(proof_path, proof_bundle)?;
+                } else {
+                    write_to_file_json(proof_path, VersionedVmStarkProof::new(stark_proof)?)?;
+                }
+            }
+            ProveSubCommand::Agg {
+                level,
+                height,
+                index,
+                app_proof: app_proof_path,
+                agg_dir,
+                proof,
+                app_pk,
+                cargo_args,
+                agg_tree_args,
+            } => {
+                let app_pk = load_app_pk(app_pk, cargo_args)?;
+                let agg_pk = read_object_from_file(default_agg_stark_pk_path()).map_err(|e| {
+                    eyre::eyre!("Failed to read aggregation proving key: {}\nPlease run 'cargo openvm setup' first", e)
+                })?;
+                check_agg_pk(&app_pk, &agg_pk)?;
+                let sdk = Sdk::new(app_pk.app_config())?
+                    .with_agg_tree_config(agg_tree_args.agg_tree_config())
+                    .with_app_pk(app_pk)
+                    .with_agg_pk(agg_pk);
+                let app_proof: ContinuationVmProof<SC> = decode_from_file(app_proof_path)?;
+                let mut prover = sdk.agg_prover()?;
+                let shape = prover.tree_shape(app_proof.per_segment.len());
+                println!(
+                    "Aggregation tree: {} leaves, internal layers {:?}",
+                    shape.num_leaves, shape.internal_layers
+                );
+
+                let tree = AggTreeDir::new(
+                    agg_dir,
+                    AggTreeId {
+                        shape,
+                        app_proof_hash: hex::encode(sha256_encoded(&app_proof)?),
+                        agg_vk_fingerprint: sdk.agg_pk().get_agg_vk().fingerprint(),
+                    },
+                )?;
+                match level {
+                    AggLevel::Leaf => {
+                        for idx in node_indices(*index, tree.shape.num_leaves)? {
+                            tree.prove_node(&tree.leaf_path(idx), || {
+                                Ok(prover.generate_leaf_proof(&app_proof, idx)?)
+                            })?;
+                        }
+                    }
+                    AggLevel::Internal => {
+                        let heights = match height {
+                            Some(height) if *height > tree.shape.top_height() => {
+                                eyre::bail!(
+                                    "height {height} is above the top internal layer {}",
+                                    tree.shape.top_height()
+                                );
+                            }
+                            Some(height) => *height..*height + 1,
+                            None => 0..tree.shape.top_height() + 1,
+                        };
+                        for height in heights {
+                            let num_nodes = tree.shape.internal_layers[height];
+                            for idx in node_indices(*index, num_nodes)? {
+                                tree.prove_node(&tree.internal_path(height, idx), || {
+                                    let children = tree.read_children(height, idx)?;
+                                    Ok(prover.generate_internal_proof(children)?)
+                                })?;
+                            }
+                        }
+                    }
+                    AggLevel::Stark => {
+                        let top = tree.internal_path(tree.shape.top_height(), 0);
+                        let inner: Proof<SC> = decode_from_file(&top).map_err(|e| {
+                            eyre::eyre!(
+                                "Failed to read the proof of the top internal node {}: {e}\nPlease prove the internal level first",
+                                top.display()
+                            )
+                        })?;
+                        let stark_proof = VmStarkProof {
+                            inner,
+                            user_public_values: app_proof.user_public_values.public_values,
+                        };
+                        let proof_path = match proof {
+                            Some(proof) => proof.clone(),
+                            None => PathBuf::from(app_proof_path.file_name().unwrap())
+                                .with_extension("")
+                                .with_extension("stark.proof"),
+                        };
+                        println!(
+                            "STARK proof completed! Writing STARK proof to {}",
+                            proof_path.display()
+                        );
+                        write_to_file_json(&proof_path, VersionedVmStarkProof::new(stark_proof)?)?;
+                    }
+                }
             }
             #[cfg(feature = "evm-prove")]
             ProveSubCommand::Evm {
@@ -218,7 +513,7 @@ impl ProveCmd {
                 run_args,
                 cargo_args,
                 segmentation_args,
-                agg_tree_config,
+                agg_tree_args,
             } => {
                 let mut app_pk = load_app_pk(app_pk, cargo_args)?;
                 let (exe, target_name) = load_or_build_exe(run_args, cargo_args)?;
@@ -227,9 +522,10 @@ impl ProveCmd {
                 let (agg_pk, halo2_pk) = read_default_agg_and_halo2_pk().map_err(|e| {
                     eyre::eyre!("Failed to read aggregation proving key: {}\nPlease run 'cargo openvm setup' first", e)
                 })?;
+                check_agg_pk(&app_pk, &agg_pk)?;
                 let app_config = get_app_config(&mut app_pk, segmentation_args);
                 let sdk = Sdk::new(app_config)?
-                    .with_agg_tree_config(*agg_tree_config)
+                    .with_agg_tree_config(agg_tree_args.agg_tree_config())
                     .with_app_pk(app_pk)
                     .with_agg_pk(agg_pk)
                     .with_halo2_pk(halo2_pk);
@@ -237,7 +533,8 @@ impl ProveCmd {
                 let app_commit = prover.stark_prover.app_commit();
                 println!("exe commit: {:?}", app_commit.app_exe_commit.to_bn254());
                 println!("vm commit: {:?}", app_commit.app_vm_commit.to_bn254());
-                let evm_proof = prover.prove_evm(read_to_stdin(&run_args.input)?)?;
+                let evm_proof =
+                    prover.prove_evm(read_to_stdin(&run_args.input, &run_args.kv_input)?)?;
 
                 let proof_path = if let Some(proof) = proof {
                     proof
@@ -255,6 +552,121 @@ impl ProveCmd {
     }
 }
 
+/// Identifies an aggregation tree, i.e. the app proof it aggregates and the aggregation keys it
+/// is proven with.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct AggTreeId {
+    shape: AggregationTreeShape,
+    /// Hex of the SHA-256 of the encoded app proof.
+    app_proof_hash: String,
+    agg_vk_fingerprint: AggVkFingerprint,
+}
+
+/// Directory of the proofs of the nodes of an aggregation tree, where the proof of leaf `i` is
+/// `leaf_<i>.proof` and the proof of node `i` of the internal layer at height `h` is
+/// `internal_<h>_<i>.proof`. The [AggTreeId] of the tree is recorded in `tree.json`, so that
+/// nodes are never mixed between trees of different app proofs or aggregation keys.
+struct AggTreeDir {
+    dir: PathBuf,
+    shape: AggregationTreeShape,
+}
+
+impl AggTreeDir {
+    fn new(dir: &Path, id: AggTreeId) -> Result<Self> {
+        let id_path = dir.join("tree.json");
+        if id_path.exists() {
+            let existing: AggTreeId = read_from_file_json(&id_path)?;
+            let mismatch = if existing.app_proof_hash != id.app_proof_hash {
+                Some("a different app proof")
+            } else if existing.agg_vk_fingerprint != id.agg_vk_fingerprint {
+                Some("a different aggregation proving key")
+            } else if existing.shape != id.shape {
+                Some("a different aggregation tree shape")
+            } else {
+                None
+            };
+            if let Some(mismatch) = mismatch {
+                eyre::bail!(
+                    "{} contains the proofs of {mismatch} ({})\nPlease use a different --agg-dir",
+                    dir.display(),
+                    id_path.display()
+                );
+            }
+        } else {
+            write_to_file_json(&id_path, &id)?;
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            shape: id.shape,
+        })
+    }
+
+    fn leaf_path(&self, idx: usize) -> PathBuf {
+        self.dir.join(format!("leaf_{idx:05}.proof"))
+    }
+
+    fn internal_path(&self, height: usize, idx: usize) -> PathBuf {
+        self.dir.join(format!("internal_{height}_{idx:05}.proof"))
+    }
+
+    /// Proves the node with proof file `path` unless the file already exists. The proof is written
+    /// to a temporary file first, so that an interrupted run never leaves a partial proof.
+    fn prove_node(&self, path: &Path, prove: impl FnOnce() -> Result<Proof<SC>>) -> Result<()> {
+        if path.exists() {
+            println!("Skipping {}, which is already proven", path.display());
+            return Ok(());
+        }
+        println!("Proving {}", path.display());
+        let tmp = path.with_extension("proof.tmp");
+        encode_to_file(&tmp, prove()?)?;
+        rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// Reads the proofs of the children of node `idx` of the internal layer at `height`.
+    fn read_children(&self, height: usize, idx: usize) -> Result<Vec<Proof<SC>>> {
+        self.shape
+            .children(height, idx)
+            .map(|child| {
+                let path = match height {
+                    0 => self.leaf_path(child),
+                    _ => self.internal_path(height - 1, child),
+                };
+                decode_from_file(&path).map_err(|e| {
+                    eyre::eyre!(
+                        "Failed to read the proof of child {}: {e}\nPlease prove the layer below first",
+                        path.display()
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// Returns the node `index` if it is given, and otherwise all `num_nodes` nodes of the layer.
+fn node_indices(index: Option<usize>, num_nodes: usize) -> Result<std::ops::Range<usize>> {
+    match index {
+        Some(index) if index >= num_nodes => {
+            eyre::bail!("index {index} is out of bounds for a layer of {num_nodes} nodes")
+        }
+        Some(index) => Ok(index..index + 1),
+        None => Ok(0..num_nodes),
+    }
+}
+
+/// Checks that the aggregation proving key verifies leaf proofs with the leaf FRI parameters of
+/// the app proving key, which differ if the keys were generated with different FRI presets.
+fn check_agg_pk(app_pk: &AppProvingKey<SdkVmConfig>, agg_pk: &AggProvingKey) -> Result<()> {
+    if app_pk.leaf_fri_params != agg_pk.leaf_vm_pk.fri_params {
+        eyre::bail!(
+            "The app proving key has leaf FRI parameters {:?}, but the aggregation proving key was generated for {:?}\nPlease run 'cargo openvm setup' with the FRI preset of the app config",
+            app_pk.leaf_fri_params,
+            agg_pk.leaf_vm_pk.fri_params
+        );
+    }
+    Ok(())
+}
+
 pub(crate) fn load_app_pk(
     app_pk: &Option<PathBuf>,
     cargo_args: &RunCargoArgs,
@@ -1,17 +1,28 @@
-use std::path::PathBuf;
+use std::{
+    fs::read,
+    io::{stdin, stdout},
+    path::PathBuf,
+};
 
 use clap::{Parser, ValueEnum};
 use eyre::Result;
 use openvm_circuit::arch::{instructions::exe::VmExe, OPENVM_DEFAULT_INIT_FILE_NAME};
-use openvm_sdk::{config::SdkVmConfig, fs::read_object_from_file, keygen::AppProvingKey, Sdk, F};
+use openvm_sdk::{
+    config::SdkVmConfig, fs::read_object_from_file, keygen::AppProvingKey, Sdk, StdIn, F,
+};
+use openvm_transpiler::symbols::SymbolTable;
 
 use super::{build, BuildArgs, BuildCargoArgs};
 use crate::{
     commands::keygen::keygen,
+    cost_report::write_cost_report,
+    debugger::{gdb::serve_gdb, repl::run_repl, DebugSession},
     input::{read_to_stdin, Input},
+    profile::{fn_bounds_from_symbols, write_profile},
+    trace::TraceWriter,
     util::{
-        get_app_pk_path, get_app_vk_path, get_manifest_path_and_dir, get_single_target_name,
-        get_target_dir, read_config_toml_or_default,
+        get_app_pk_path, get_app_vk_path, get_elf_path, get_manifest_path_and_dir,
+        get_single_target_name, get_target_dir, read_config_toml_or_default,
     },
 };
 
@@ -24,6 +35,9 @@ pub enum ExecutionMode {
     /// Runs the program and calculates the number of segments that the execution will be split
     /// into for proving
     Segment,
+    /// Runs the program and writes a per-instruction trace of the pc, opcode, operands, register
+    /// writes and memory accesses to the file given by --trace-output
+    Trace,
 }
 
 #[derive(Parser)]
@@ -68,6 +82,14 @@ pub struct RunArgs {
     )]
     pub input: Option<Input>,
 
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Path to a JSON, TOML or CBOR file with key-value pairs to load into the kv_store for hint_load_by_key, can be specified multiple times",
+        help_heading = "OpenVM Options"
+    )]
+    pub kv_input: Vec<PathBuf>,
+
     #[arg(
         long,
         default_value = OPENVM_DEFAULT_INIT_FILE_NAME,
@@ -84,6 +106,55 @@ pub struct RunArgs {
         help_heading = "OpenVM Options"
     )]
     pub mode: ExecutionMode,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        default_value = "trace.jsonl",
+        help = "Output file of --mode trace, written as JSON lines if the extension is .jsonl and in binary otherwise",
+        help_heading = "OpenVM Options"
+    )]
+    pub trace_output: PathBuf,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Profile the guest by function call stack and write folded stacks and a pprof profile to the given directory, only supported with --mode pure or meter; with --mode meter the profile is also weighted by trace cells per chip",
+        help_heading = "OpenVM Options"
+    )]
+    pub profile_out: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write a report of the trace cells and interactions per chip attributed to each guest function and cycle tracker span, as JSON if the extension is .json and as a table otherwise, only supported with --mode meter",
+        help_heading = "OpenVM Options"
+    )]
+    pub cost_report: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Run the program in the interactive debugger instead of executing it to completion",
+        help_heading = "Debugger Options"
+    )]
+    pub debug: bool,
+
+    #[arg(
+        long,
+        value_name = "ADDR",
+        requires = "debug",
+        help = "Serve the debugger over the GDB remote serial protocol at the given address (e.g. 127.0.0.1:1234) instead of the interactive prompt",
+        help_heading = "Debugger Options"
+    )]
+    pub gdb: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Path to the guest ELF used to resolve function symbols for the debugger, --profile-out and --cost-report, by default uses the ELF built alongside the executable",
+        help_heading = "Debugger Options"
+    )]
+    pub elf: Option<PathBuf>,
 }
 
 impl From<RunArgs> for BuildArgs {
@@ -253,15 +324,16 @@ impl From<RunCargoArgs> for BuildCargoArgs {
 
 impl RunCmd {
     pub fn run(&self) -> Result<()> {
+        let mut target_name = None;
         let exe_path = if let Some(exe) = &self.run_args.exe {
             exe
         } else {
             // Build and get the executable name
-            let target_name = get_single_target_name(&self.cargo_args)?;
+            let name = target_name.insert(get_single_target_name(&self.cargo_args)?);
             let build_args = self.run_args.clone().into();
             let cargo_args = self.cargo_args.clone().into();
             let output_dir = build(&build_args, &cargo_args)?;
-            &output_dir.join(target_name.with_extension("vmexe"))
+            &output_dir.join(name.with_extension("vmexe"))
         };
 
         let (manifest_path, manifest_dir) =
@@ -273,11 +345,32 @@ impl RunCmd {
             .unwrap_or_else(|| manifest_dir.join("openvm.toml"));
         let app_config = read_config_toml_or_default(&config_path)?;
         let exe: VmExe<F> = read_object_from_file(exe_path)?;
-        let inputs = read_to_stdin(&self.run_args.input)?;
+        let inputs = read_to_stdin(&self.run_args.input, &self.run_args.kv_input)?;
 
         // Create SDK
         let sdk = Sdk::new(app_config)?;
 
+        let elf_path = self.run_args.elf.clone().or_else(|| {
+            let target_dir = get_target_dir(&self.cargo_args.target_dir, &manifest_path);
+            target_name.map(|name| get_elf_path(&target_dir, &self.cargo_args.profile, &name))
+        });
+        if self.run_args.debug {
+            return self.debug(&sdk, &exe, inputs, elf_path);
+        }
+        if self.run_args.profile_out.is_some()
+            && !matches!(
+                self.run_args.mode,
+                ExecutionMode::Pure | ExecutionMode::Meter
+            )
+        {
+            eyre::bail!("--profile-out is only supported with --mode pure or meter");
+        }
+        if self.run_args.cost_report.is_some()
+            && !matches!(self.run_args.mode, ExecutionMode::Meter)
+        {
+            eyre::bail!("--cost-report is only supported with --mode meter");
+        }
+
         // For metered modes, load existing app pk from disk or generate it
         if matches!(
             self.run_args.mode,
@@ -294,7 +387,7 @@ impl RunCmd {
                     .config
                     .to_owned()
                     .unwrap_or_else(|| manifest_dir.join("openvm.toml"));
-                keygen(&config_path, &app_pk_path, &app_vk_path, None::<&str>)?;
+                keygen(&config_path, &app_pk_path, &app_vk_path, None::<&str>, None)?;
             }
 
             // Load the app pk and set it
@@ -303,6 +396,10 @@ impl RunCmd {
                 .map_err(|_| eyre::eyre!("Failed to set app pk"))?;
         }
 
+        if self.run_args.profile_out.is_some() || self.run_args.cost_report.is_some() {
+            return self.profile(&sdk, exe, inputs, elf_path);
+        }
+
         match self.run_args.mode {
             ExecutionMode::Pure => {
                 let output = sdk.execute(exe, inputs)?;
@@ -323,8 +420,81 @@ impl RunCmd {
                 println!("Number of instructions executed: {}", total_instructions);
                 println!("Total segments: {}", segments.len());
             }
+            ExecutionMode::Trace => {
+                let instance = sdk.executor().trace_instance(&exe)?;
+                let mut writer = TraceWriter::create(&self.run_args.trace_output, &exe.program)?;
+                let result =
+                    instance.execute_trace(inputs, None, |step| writer.write_step(step))?;
+                let num_steps = writer.finish()?;
+                println!(
+                    "Trace of {num_steps} instructions written to {}",
+                    self.run_args.trace_output.display()
+                );
+                result?;
+            }
         }
 
         Ok(())
     }
+
+    fn profile(
+        &self,
+        sdk: &Sdk,
+        exe: VmExe<F>,
+        inputs: StdIn,
+        elf_path: Option<PathBuf>,
+    ) -> Result<()> {
+        let symbols = read_symbols(elf_path)?;
+        let profile_cells = matches!(self.run_args.mode, ExecutionMode::Meter);
+        let (output, profile) =
+            sdk.execute_profile(exe, inputs, fn_bounds_from_symbols(&symbols), profile_cells)?;
+        println!("Execution output: {:?}", output);
+
+        let instret: u64 = profile.nodes.iter().map(|node| node.instructions).sum();
+        println!("Number of instructions executed: {}", instret);
+        if profile_cells {
+            let cost: u64 = profile
+                .nodes
+                .iter()
+                .flat_map(|node| profile.cells(node))
+                .sum();
+            println!("Total cost: {}", cost);
+        }
+        if let Some(profile_dir) = &self.run_args.profile_out {
+            write_profile(profile_dir, &profile)?;
+            println!("Profile written to {}", profile_dir.display());
+        }
+        if let Some(report_path) = &self.run_args.cost_report {
+            write_cost_report(report_path, &profile)?;
+            println!("Cost report written to {}", report_path.display());
+        }
+        Ok(())
+    }
+
+    fn debug(
+        &self,
+        sdk: &Sdk,
+        exe: &VmExe<F>,
+        inputs: StdIn,
+        elf_path: Option<PathBuf>,
+    ) -> Result<()> {
+        let symbols = read_symbols(elf_path)?;
+        let instance = sdk.executor().debug_instance(exe)?;
+        let mut session = DebugSession::new(&instance, exe, inputs, symbols);
+        if let Some(addr) = &self.run_args.gdb {
+            serve_gdb(&mut session, addr.as_str())
+        } else {
+            run_repl(&mut session, stdin().lock(), stdout())
+        }
+    }
+}
+
+fn read_symbols(elf_path: Option<PathBuf>) -> Result<SymbolTable> {
+    Ok(match elf_path {
+        Some(elf_path) if elf_path.exists() => SymbolTable::decode(&read(elf_path)?)?,
+        _ => {
+            println!("[openvm] No guest ELF found, function symbols will not be available");
+            SymbolTable::default()
+        }
+    })
 }
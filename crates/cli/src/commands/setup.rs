@@ -8,10 +8,10 @@ use aws_sdk_s3::Client;
 use clap::Parser;
 use eyre::{eyre, Result};
 use openvm_sdk::{
-    config::DEFAULT_HALO2_VERIFIER_K,
+    config::{AppConfig, FriPreset, DEFAULT_HALO2_VERIFIER_K},
     fs::{
-        read_object_from_file, write_evm_halo2_verifier_to_folder, write_object_to_file,
-        EVM_HALO2_VERIFIER_BASE_NAME, EVM_HALO2_VERIFIER_INTERFACE_NAME,
+        read_agg_pk_metadata_from_file, read_object_from_file, write_evm_halo2_verifier_to_folder,
+        write_object_to_file, EVM_HALO2_VERIFIER_BASE_NAME, EVM_HALO2_VERIFIER_INTERFACE_NAME,
         EVM_HALO2_VERIFIER_PARENT_NAME,
     },
     Sdk,
@@ -43,21 +43,35 @@ pub struct SetupCmd {
         help = "force keygen even if the proving keys already exist"
     )]
     pub force_agg_keygen: bool,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = FriPreset::Balanced,
+        help = "FRI preset to generate the aggregation keys with, which must match the preset of the app config; existing keys of another preset are regenerated"
+    )]
+    pub preset: FriPreset,
 }
 
 impl SetupCmd {
     pub async fn run(&self) -> Result<()> {
+        let security = self.preset.security();
+        println!(
+            "Using FRI preset {} with {} bits of conjectured and {} bits of proven security",
+            self.preset, security.conjectured_bits, security.proven_bits
+        );
         let default_agg_stark_pk_path = default_agg_stark_pk_path();
         let default_agg_stark_vk_path = default_agg_stark_vk_path();
         let default_evm_halo2_verifier_path = default_evm_halo2_verifier_path();
         let default_asm_path = default_asm_path();
+        let keep_agg_pk =
+            !self.force_agg_keygen && self.agg_pk_matches_preset(&default_agg_stark_pk_path);
         if !self.evm {
-            if PathBuf::from(&default_agg_stark_pk_path).exists() && !self.force_agg_keygen {
+            if keep_agg_pk {
                 println!("Aggregation stark proving key already exists");
                 return Ok(());
             }
             // agg keygen does not depend on the app config
-            let sdk = Sdk::standard();
+            let sdk = Sdk::new(AppConfig::standard().with_preset(self.preset))?;
             let (agg_pk, agg_vk) = sdk.agg_keygen()?;
 
             println!(
@@ -78,7 +92,7 @@ impl SetupCmd {
             write(&default_asm_path, root_verifier_asm)?;
         } else {
             let default_agg_halo2_pk_path = default_agg_halo2_pk_path();
-            if PathBuf::from(&default_agg_stark_pk_path).exists()
+            if keep_agg_pk
                 && PathBuf::from(&default_agg_halo2_pk_path).exists()
                 && PathBuf::from(&default_evm_halo2_verifier_path)
                     .join(EVM_HALO2_VERIFIER_PARENT_NAME)
@@ -101,10 +115,9 @@ impl SetupCmd {
 
             Self::download_params(10, DEFAULT_HALO2_VERIFIER_K as u32).await?;
             // halo2 keygen does not depend on the app config
-            let sdk = Sdk::standard();
+            let sdk = Sdk::new(AppConfig::standard().with_preset(self.preset))?;
 
-            let agg_vk = if !self.force_agg_keygen
-                && PathBuf::from(&default_agg_stark_pk_path).exists()
+            let agg_vk = if keep_agg_pk
                 && PathBuf::from(&default_agg_stark_vk_path).exists()
                 && PathBuf::from(&default_agg_halo2_pk_path).exists()
             {
@@ -145,6 +158,35 @@ impl SetupCmd {
         Ok(())
     }
 
+    /// Whether the aggregation proving key at `path` exists and was generated with the FRI
+    /// parameters of the selected preset. Otherwise, it is regenerated, since the app proving key
+    /// of an app config with the preset cannot be aggregated with it.
+    fn agg_pk_matches_preset(&self, path: &str) -> bool {
+        if !PathBuf::from(path).exists() {
+            return false;
+        }
+        match read_agg_pk_metadata_from_file(path) {
+            Ok(metadata)
+                if metadata.leaf_fri_params == self.preset.leaf_fri_params()
+                    && metadata.internal_fri_params == self.preset.internal_fri_params()
+                    && metadata.root_fri_params == self.preset.root_fri_params() =>
+            {
+                true
+            }
+            Ok(_) => {
+                println!(
+                    "Existing aggregation proving key at {path} was not generated with FRI preset {}, regenerating it",
+                    self.preset
+                );
+                false
+            }
+            Err(e) => {
+                println!("Cannot read the existing aggregation proving key, regenerating it: {e}");
+                false
+            }
+        }
+    }
+
     fn check_solc_installed() -> bool {
         std::process::Command::new("solc")
             .arg("--version")
@@ -3,9 +3,11 @@ use std::path::{Path, PathBuf};
 use clap::Parser;
 use eyre::{Context, Result};
 use openvm_sdk::{
+    commit::AppExecutionCommit,
+    config::{AppConfig, SdkVmConfig},
     fs::{decode_from_file, read_from_file_json, read_object_from_file},
     prover::verify_app_proof,
-    types::VersionedVmStarkProof,
+    types::{vm_config_hash, VersionedVmStarkProof, VmStarkProofBundle},
     Sdk, OPENVM_VERSION,
 };
 
@@ -16,7 +18,7 @@ use crate::{
     default::default_agg_stark_vk_path,
     util::{
         get_app_commit_path, get_app_vk_path, get_files_with_ext, get_manifest_path_and_dir,
-        get_single_target_name_raw, get_target_dir, get_target_output_dir,
+        get_single_target_name_raw, get_target_dir, get_target_output_dir, read_to_struct_toml,
     },
 };
 
@@ -51,7 +53,8 @@ enum VerifySubCommand {
     },
     Stark {
         /// NOTE: if `openvm commit` was called with the `--exe` option, then `--app-commit` must
-        /// be specified so the command knows where to find the app commit.
+        /// be specified so the command knows where to find the app commit. Proof bundles are also
+        /// verified against this app commit, never against the one in the bundle alone.
         #[arg(
             long,
             action,
@@ -68,6 +71,26 @@ enum VerifySubCommand {
         )]
         proof: Option<PathBuf>,
 
+        /// The components of the bundle are checked against the aggregation verifying key and the
+        /// app commit, and against the VM config only if `--config` is given.
+        #[arg(
+            long,
+            action,
+            conflicts_with = "proof",
+            help = "Path to a STARK proof bundle written by `cargo openvm prove stark --bundle`, to verify instead of a STARK proof",
+            help_heading = "OpenVM Options"
+        )]
+        bundle: Option<PathBuf>,
+
+        #[arg(
+            long,
+            action,
+            requires = "bundle",
+            help = "Path to the OpenVM config .toml file that the VM config hash of the bundle is checked against",
+            help_heading = "OpenVM Options"
+        )]
+        config: Option<PathBuf>,
+
         #[command(flatten)]
         cargo_args: SingleTargetCargoArgs,
     },
@@ -171,6 +194,8 @@ impl VerifyCmd {
             VerifySubCommand::Stark {
                 app_commit,
                 proof,
+                bundle,
+                config,
                 cargo_args,
             } => {
                 let agg_vk = read_object_from_file(default_agg_stark_vk_path())
@@ -193,7 +218,38 @@ impl VerifyCmd {
                     )?;
                     get_app_commit_path(&target_output_dir, target_name)
                 };
-                let expected_app_commit = read_from_file_json(app_commit_path)?;
+                let expected_app_commit: AppExecutionCommit =
+                    read_from_file_json(&app_commit_path).with_context(|| {
+                        format!(
+                            "Failed to read the app commit from {}, please run 'cargo openvm commit' or specify it with --app-commit",
+                            app_commit_path.display()
+                        )
+                    })?;
+
+                if let Some(bundle_path) = bundle {
+                    println!("Verifying STARK proof bundle at {}", bundle_path.display());
+                    let proof_bundle: VmStarkProofBundle = read_from_file_json(bundle_path)
+                        .with_context(|| {
+                            format!(
+                                "Proof bundle needs to be compatible with openvm v{OPENVM_VERSION}",
+                            )
+                        })?;
+                    let expected_vm_config_hash = match config {
+                        Some(config) => {
+                            let app_config: AppConfig<SdkVmConfig> = read_to_struct_toml(config)?;
+                            Some(vm_config_hash(&app_config.app_vm_config)?)
+                        }
+                        None => None,
+                    };
+                    Sdk::verify_proof_bundle(
+                        &agg_vk,
+                        &proof_bundle,
+                        &expected_app_commit,
+                        expected_vm_config_hash.as_ref(),
+                    )?;
+                    println!("Proof verified successfully!");
+                    return Ok(());
+                }
 
                 let proof_path = if let Some(proof) = proof {
                     proof.clone()
@@ -211,9 +267,6 @@ impl VerifyCmd {
                     .with_context(|| {
                         format!("Proof needs to be compatible with openvm v{OPENVM_VERSION}",)
                     })?;
-                if stark_proof.version != format!("v{OPENVM_VERSION}") {
-                    eprintln!("Attempting to verify proof generated with openvm {}, but the verifier is on openvm v{OPENVM_VERSION}", stark_proof.version);
-                }
                 Sdk::verify_proof(&agg_vk, expected_app_commit, &stark_proof.try_into()?)?;
             }
             #[cfg(feature = "evm-verify")]
@@ -0,0 +1,179 @@
+//! Cost reports written by `cargo openvm run --cost-report`.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs::{create_dir_all, write},
+    path::Path,
+};
+
+use eyre::Result;
+use openvm_circuit::arch::execution_mode::{ProfileCtx, ProfileFrame, ProfileNode};
+use serde::{Deserialize, Serialize};
+
+/// Name of the entry for instructions executed outside of any known function.
+const UNKNOWN_FUNCTION: &str = "[unknown]";
+
+/// Trace cells and interactions added to a single air.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AirCost {
+    pub cells: u64,
+    pub interactions: u64,
+}
+
+/// Cost attributed to a function or span.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostEntry {
+    pub name: String,
+    pub instructions: u64,
+    pub cells: u64,
+    pub interactions: u64,
+    /// Cost per air, only for airs with a non-zero cost.
+    pub airs: BTreeMap<String, AirCost>,
+}
+
+impl CostEntry {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn add(&mut self, profile: &ProfileCtx, node: &ProfileNode) {
+        self.instructions += node.instructions;
+        for ((air_name, cells), interactions) in profile
+            .air_names
+            .iter()
+            .zip(profile.cells(node))
+            .zip(profile.interactions(node))
+        {
+            if cells == 0 && interactions == 0 {
+                continue;
+            }
+            self.cells += cells;
+            self.interactions += interactions;
+            let air = self.airs.entry(air_name.clone()).or_default();
+            air.cells += cells;
+            air.interactions += interactions;
+        }
+    }
+}
+
+/// Cost of an execution, attributed to guest functions and cycle tracker spans.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostReport {
+    pub total: CostEntry,
+    /// Exclusive cost of each function, i.e. of the instructions executed in the function itself
+    /// and in the spans it opened, but not in its callees. Sorted by decreasing cells.
+    pub functions: Vec<CostEntry>,
+    /// Inclusive cost of each span, i.e. of all instructions executed while the span was open,
+    /// counting nested spans of the same name once. Sorted by decreasing cells.
+    pub spans: Vec<CostEntry>,
+}
+
+impl CostReport {
+    pub fn new(profile: &ProfileCtx) -> Self {
+        let mut total = CostEntry::new("total");
+        let mut functions = BTreeMap::<&str, CostEntry>::new();
+        let mut spans = BTreeMap::<&str, CostEntry>::new();
+        for (idx, node) in profile.nodes.iter().enumerate() {
+            total.add(profile, node);
+            let stack = profile.stack(idx);
+
+            let function = stack
+                .iter()
+                .rev()
+                .find(|frame| matches!(frame, ProfileFrame::Function(_)))
+                .map_or(UNKNOWN_FUNCTION, |&frame| profile.frame_name(frame));
+            functions
+                .entry(function)
+                .or_insert_with(|| CostEntry::new(function))
+                .add(profile, node);
+
+            let mut span_names: Vec<&str> = stack
+                .into_iter()
+                .filter(|frame| matches!(frame, ProfileFrame::Span(_)))
+                .map(|frame| profile.frame_name(frame))
+                .collect();
+            span_names.sort_unstable();
+            span_names.dedup();
+            for span in span_names {
+                spans
+                    .entry(span)
+                    .or_insert_with(|| CostEntry::new(span))
+                    .add(profile, node);
+            }
+        }
+
+        Self {
+            total,
+            functions: sorted_by_cost(functions.into_values()),
+            spans: sorted_by_cost(spans.into_values()),
+        }
+    }
+
+    /// Formats the report as tables of the functions and spans, where the cost per air of each
+    /// entry is listed below it.
+    pub fn to_table(&self) -> String {
+        let mut out = String::new();
+        write_table(&mut out, "Total", std::slice::from_ref(&self.total));
+        write_table(&mut out, "Functions (exclusive)", &self.functions);
+        if !self.spans.is_empty() {
+            write_table(&mut out, "Spans (inclusive)", &self.spans);
+        }
+        out
+    }
+}
+
+fn sorted_by_cost(entries: impl Iterator<Item = CostEntry>) -> Vec<CostEntry> {
+    let mut entries: Vec<_> = entries.collect();
+    entries.sort_by(|a, b| {
+        (b.cells, b.instructions, &a.name).cmp(&(a.cells, a.instructions, &b.name))
+    });
+    entries
+}
+
+fn write_table(out: &mut String, title: &str, entries: &[CostEntry]) {
+    writeln!(out, "{title}").unwrap();
+    writeln!(
+        out,
+        "{:>14} {:>14} {:>14}  name",
+        "instructions", "cells", "interactions"
+    )
+    .unwrap();
+    for entry in entries {
+        writeln!(
+            out,
+            "{:>14} {:>14} {:>14}  {}",
+            entry.instructions, entry.cells, entry.interactions, entry.name
+        )
+        .unwrap();
+        let mut airs: Vec<_> = entry.airs.iter().collect();
+        airs.sort_by(|(a, a_cost), (b, b_cost)| (b_cost.cells, a).cmp(&(a_cost.cells, b)));
+        for (air_name, cost) in airs {
+            writeln!(
+                out,
+                "{:>14} {:>14} {:>14}    [{air_name}]",
+                "", cost.cells, cost.interactions
+            )
+            .unwrap();
+        }
+    }
+    writeln!(out).unwrap();
+}
+
+/// Writes the cost report of `profile` to `path`, as JSON if the extension is `.json` and as a
+/// table otherwise.
+pub fn write_cost_report(path: &Path, profile: &ProfileCtx) -> Result<()> {
+    let report = CostReport::new(profile);
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    if path.extension().is_some_and(|ext| ext == "json") {
+        write(path, serde_json::to_string_pretty(&report)?)?;
+    } else {
+        write(path, report.to_table())?;
+    }
+    Ok(())
+}
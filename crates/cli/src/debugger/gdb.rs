@@ -0,0 +1,254 @@
+//! Minimal GDB remote serial protocol stub, sufficient for `riscv32-unknown-elf-gdb` (or
+//! `gdb-multiarch`) to attach with `target remote <addr>`, set breakpoints, single-step, continue,
+//! and read registers and guest memory. Writes to registers and memory are not supported.
+
+use std::{
+    io::{BufReader, BufWriter, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+};
+
+use eyre::Result;
+use openvm_circuit::arch::execution_mode::DebugStop;
+
+use super::{DebugSession, SessionStatus};
+
+/// Register number of the pc in the RISC-V GDB register layout, after `x0..x31`.
+const PC_REGNUM: usize = 32;
+const SIGTRAP: u8 = 5;
+const SIGILL: u8 = 4;
+
+const TARGET_XML: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE target SYSTEM "gdb-target.dtd">
+<target version="1.0"><architecture>riscv:rv32</architecture></target>"#;
+
+/// Listens on `addr` and serves a single GDB connection until the client detaches or kills the
+/// session.
+pub fn serve_gdb(session: &mut DebugSession, addr: impl ToSocketAddrs) -> Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    println!(
+        "[openvm] Waiting for GDB to connect on {} (use `target remote {}`)",
+        listener.local_addr()?,
+        listener.local_addr()?
+    );
+    let (stream, peer) = listener.accept()?;
+    println!("[openvm] GDB connected from {peer}");
+    GdbConnection::new(stream)?.serve(session)
+}
+
+struct GdbConnection {
+    reader: BufReader<TcpStream>,
+    writer: BufWriter<TcpStream>,
+    no_ack: bool,
+}
+
+enum Reply {
+    Packet(String),
+    /// Send the packet and close the connection.
+    Close(String),
+}
+
+impl GdbConnection {
+    fn new(stream: TcpStream) -> Result<Self> {
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: BufWriter::new(stream),
+            no_ack: false,
+        })
+    }
+
+    fn serve(mut self, session: &mut DebugSession) -> Result<()> {
+        while let Some(packet) = self.read_packet()? {
+            match handle_packet(session, &packet, &mut self.no_ack) {
+                Reply::Packet(reply) => self.write_packet(&reply)?,
+                Reply::Close(reply) => {
+                    self.write_packet(&reply)?;
+                    break;
+                }
+            }
+        }
+        println!("[openvm] GDB disconnected");
+        Ok(())
+    }
+
+    /// Reads the next `$<data>#<checksum>` packet, skipping acks and interrupts. Returns `None`
+    /// when the connection is closed.
+    fn read_packet(&mut self) -> Result<Option<String>> {
+        let mut byte = [0u8; 1];
+        loop {
+            if self.reader.read(&mut byte)? == 0 {
+                return Ok(None);
+            }
+            // Skip `+`/`-` acks and `\x03` interrupt requests: execution is synchronous, so there
+            // is never anything running to interrupt.
+            if byte[0] == b'$' {
+                break;
+            }
+        }
+        let mut data = Vec::new();
+        loop {
+            if self.reader.read(&mut byte)? == 0 {
+                return Ok(None);
+            }
+            if byte[0] == b'#' {
+                break;
+            }
+            data.push(byte[0]);
+        }
+        let mut checksum = [0u8; 2];
+        self.reader.read_exact(&mut checksum)?;
+        let expected = u8::from_str_radix(std::str::from_utf8(&checksum)?, 16)?;
+        if !self.no_ack {
+            let ack = if checksum_of(&data) == expected {
+                b"+"
+            } else {
+                b"-"
+            };
+            self.writer.write_all(ack)?;
+            self.writer.flush()?;
+        }
+        Ok(Some(String::from_utf8(data)?))
+    }
+
+    fn write_packet(&mut self, data: &str) -> Result<()> {
+        write!(
+            self.writer,
+            "${}#{:02x}",
+            data,
+            checksum_of(data.as_bytes())
+        )?;
+        self.writer.flush()?;
+        if !self.no_ack {
+            // Wait for the client's ack before continuing
+            let mut ack = [0u8; 1];
+            self.reader.read_exact(&mut ack)?;
+        }
+        Ok(())
+    }
+}
+
+fn checksum_of(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |acc, b| acc.wrapping_add(*b))
+}
+
+fn handle_packet(session: &mut DebugSession, packet: &str, no_ack: &mut bool) -> Reply {
+    let reply = match packet.as_bytes().first() {
+        Some(b'?') => stop_reply(session, None),
+        Some(b'g') => {
+            let regs = session.registers();
+            regs.iter()
+                .chain(std::iter::once(&session.pc()))
+                .map(|reg| hex::encode(reg.to_le_bytes()))
+                .collect()
+        }
+        Some(b'p') => match usize::from_str_radix(&packet[1..], 16) {
+            Ok(regnum) if regnum < PC_REGNUM => {
+                hex::encode(session.registers()[regnum].to_le_bytes())
+            }
+            Ok(PC_REGNUM) => hex::encode(session.pc().to_le_bytes()),
+            _ => "E01".to_string(),
+        },
+        Some(b'm') => {
+            let read = parse_addr_len(&packet[1..])
+                .and_then(|(addr, len)| session.read_guest_memory(addr, len));
+            match read {
+                Ok(bytes) => hex::encode(bytes),
+                Err(_) => "E14".to_string(),
+            }
+        }
+        Some(b'c') => resume(session, None),
+        Some(b's') => resume(session, Some(1)),
+        Some(b'Z') | Some(b'z') => {
+            let insert = packet.starts_with('Z');
+            // Software and hardware breakpoints are handled identically.
+            match packet[1..].split(',').collect::<Vec<_>>().as_slice() {
+                [kind, addr, _] if *kind == "0" || *kind == "1" => {
+                    match u32::from_str_radix(addr, 16) {
+                        Ok(pc) => {
+                            if insert {
+                                session.breakpoints.insert(pc);
+                            } else {
+                                session.breakpoints.remove(&pc);
+                            }
+                            "OK".to_string()
+                        }
+                        Err(_) => "E01".to_string(),
+                    }
+                }
+                // Watchpoints are not supported
+                _ => String::new(),
+            }
+        }
+        Some(b'H') => "OK".to_string(),
+        Some(b'k') => return Reply::Close(String::new()),
+        Some(b'D') => return Reply::Close("OK".to_string()),
+        Some(b'q') | Some(b'Q') => query_reply(packet, no_ack),
+        _ => String::new(),
+    };
+    Reply::Packet(reply)
+}
+
+fn query_reply(packet: &str, no_ack: &mut bool) -> String {
+    if packet.starts_with("qSupported") {
+        "PacketSize=4000;QStartNoAckMode+;swbreak+;hwbreak+;qXfer:features:read+".to_string()
+    } else if packet == "QStartNoAckMode" {
+        *no_ack = true;
+        "OK".to_string()
+    } else if packet == "qAttached" {
+        "1".to_string()
+    } else if packet == "qC" {
+        "QC1".to_string()
+    } else if packet == "qfThreadInfo" {
+        "m1".to_string()
+    } else if packet == "qsThreadInfo" {
+        "l".to_string()
+    } else if let Some(range) = packet.strip_prefix("qXfer:features:read:target.xml:") {
+        match parse_addr_len(range) {
+            Ok((offset, len)) => {
+                let offset = (offset as usize).min(TARGET_XML.len());
+                let end = offset.saturating_add(len).min(TARGET_XML.len());
+                let prefix = if end == TARGET_XML.len() { "l" } else { "m" };
+                format!("{prefix}{}", &TARGET_XML[offset..end])
+            }
+            Err(_) => "E01".to_string(),
+        }
+    } else {
+        String::new()
+    }
+}
+
+fn resume(session: &mut DebugSession, num_insns: Option<u64>) -> String {
+    match session.status() {
+        SessionStatus::Suspended => {}
+        _ => return stop_reply(session, None),
+    }
+    match session.resume(num_insns) {
+        Ok(stop) => stop_reply(session, Some(stop)),
+        Err(err) => {
+            println!("[openvm] {err}");
+            stop_reply(session, None)
+        }
+    }
+}
+
+fn stop_reply(session: &DebugSession, stop: Option<DebugStop>) -> String {
+    match session.status() {
+        SessionStatus::Terminated(exit_code) => format!("W{:02x}", exit_code & 0xff),
+        SessionStatus::Faulted(_) => format!("S{SIGILL:02x}"),
+        SessionStatus::Suspended => match stop {
+            Some(DebugStop::Breakpoint(_)) => format!("T{SIGTRAP:02x}swbreak:;"),
+            _ => format!("S{SIGTRAP:02x}"),
+        },
+    }
+}
+
+/// Parses `addr,len` with both fields in hex.
+fn parse_addr_len(s: &str) -> Result<(u32, usize)> {
+    let (addr, len) = s
+        .split_once(',')
+        .ok_or_else(|| eyre::eyre!("Malformed packet"))?;
+    Ok((
+        u32::from_str_radix(addr, 16)?,
+        usize::from_str_radix(len, 16)?,
+    ))
+}
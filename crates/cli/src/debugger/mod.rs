@@ -0,0 +1,243 @@
+//! Interactive guest debugger for `cargo openvm run --debug`.
+//!
+//! The [DebugSession] drives an [InterpretedInstance] in [DebugCtx] mode, which suspends execution
+//! at breakpoints, and exposes the registers and guest memory of the suspended VM. Two front ends
+//! are provided: a line-based [repl] on stdin and a [gdb] remote serial protocol stub.
+
+use std::collections::BTreeSet;
+
+use eyre::Result;
+use openvm_circuit::{
+    arch::{
+        execution_mode::{DebugCtx, DebugStop},
+        instructions::{
+            exe::VmExe,
+            instruction::Instruction,
+            program::DEFAULT_PC_STEP,
+            riscv::{
+                RV32_MEMORY_AS, RV32_NUM_REGISTERS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS,
+            },
+        },
+        interpreter::InterpretedInstance,
+        MemoryCellType, Streams, VmState,
+    },
+    system::memory::online::GuestMemory,
+};
+use openvm_sdk::F;
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_transpiler::symbols::SymbolTable;
+
+pub mod gdb;
+pub mod repl;
+
+/// Execution status of the guest after the last command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SessionStatus {
+    /// The guest is suspended and can be resumed.
+    Suspended,
+    /// The guest terminated with the given exit code.
+    Terminated(u32),
+    /// Execution failed with the given error. The state at the time of failure can be inspected.
+    Faulted(String),
+}
+
+pub struct DebugSession<'a> {
+    instance: &'a InterpretedInstance<'a, F, DebugCtx>,
+    exe: &'a VmExe<F>,
+    state: Option<VmState<F, GuestMemory>>,
+    status: SessionStatus,
+    pub breakpoints: BTreeSet<u32>,
+    pub symbols: SymbolTable,
+}
+
+impl<'a> DebugSession<'a> {
+    pub fn new(
+        instance: &'a InterpretedInstance<'a, F, DebugCtx>,
+        exe: &'a VmExe<F>,
+        inputs: impl Into<Streams<F>>,
+        symbols: SymbolTable,
+    ) -> Self {
+        Self {
+            instance,
+            exe,
+            state: Some(instance.create_initial_vm_state(inputs)),
+            status: SessionStatus::Suspended,
+            breakpoints: BTreeSet::new(),
+            symbols,
+        }
+    }
+
+    pub fn status(&self) -> &SessionStatus {
+        &self.status
+    }
+
+    pub fn state(&self) -> &VmState<F, GuestMemory> {
+        self.state
+            .as_ref()
+            .expect("state is only taken during execution")
+    }
+
+    pub fn pc(&self) -> u32 {
+        self.state().pc()
+    }
+
+    pub fn instret(&self) -> u64 {
+        self.state().instret()
+    }
+
+    /// Executes at most `num_insns` instructions, or until a breakpoint or termination if
+    /// `num_insns` is `None`.
+    pub fn resume(&mut self, num_insns: Option<u64>) -> Result<DebugStop> {
+        if self.status != SessionStatus::Suspended {
+            return Err(eyre::eyre!("The program is no longer running"));
+        }
+        let state = self.state.take().unwrap();
+        let (state, stop) =
+            self.instance
+                .execute_debug_from_state(state, &self.breakpoints, num_insns);
+        self.state = Some(state);
+        match stop {
+            Ok(stop) => {
+                if let DebugStop::Terminated(exit_code) = stop {
+                    self.status = SessionStatus::Terminated(exit_code);
+                }
+                Ok(stop)
+            }
+            Err(err) => {
+                self.status = SessionStatus::Faulted(err.to_string());
+                Err(eyre::eyre!(
+                    "Execution failed at pc {:#x}: {err}",
+                    self.pc()
+                ))
+            }
+        }
+    }
+
+    /// Resolves a breakpoint location, which is either a pc in decimal or `0x` hex, or the name of
+    /// a function in the ELF symbol table.
+    pub fn resolve_location(&self, location: &str) -> Result<u32> {
+        if let Ok(pc) = parse_u32(location) {
+            return Ok(pc);
+        }
+        self.symbols
+            .find(location)
+            .map(|symbol| symbol.start)
+            .ok_or_else(|| eyre::eyre!("No function named `{location}` in symbol table"))
+    }
+
+    pub fn instruction_at(&self, pc: u32) -> Option<&Instruction<F>> {
+        let program = &self.exe.program;
+        let index = pc.checked_sub(program.pc_base)? / DEFAULT_PC_STEP;
+        program
+            .get_instruction_and_debug_info(index as usize)
+            .map(|(instruction, _)| instruction)
+    }
+
+    pub fn registers(&self) -> [u32; RV32_NUM_REGISTERS] {
+        let bytes = self
+            .read_bytes(
+                RV32_REGISTER_AS,
+                0,
+                RV32_NUM_REGISTERS * RV32_REGISTER_NUM_LIMBS,
+            )
+            .expect("register address space is always present");
+        let mut regs = [0u32; RV32_NUM_REGISTERS];
+        for (reg, limbs) in regs
+            .iter_mut()
+            .zip(bytes.chunks_exact(RV32_REGISTER_NUM_LIMBS))
+        {
+            *reg = u32::from_le_bytes(limbs.try_into().unwrap());
+        }
+        regs
+    }
+
+    /// Reads `len` bytes starting at `ptr` from an address space whose cells are bytes.
+    pub fn read_bytes(&self, addr_space: u32, ptr: u32, len: usize) -> Result<Vec<u8>> {
+        let memory = &self.state().memory.memory;
+        let config = memory
+            .config
+            .get(addr_space as usize)
+            .ok_or_else(|| eyre::eyre!("Address space {addr_space} does not exist"))?;
+        if config.layout != MemoryCellType::U8 {
+            return Err(eyre::eyre!(
+                "Address space {addr_space} does not consist of byte cells"
+            ));
+        }
+        if (ptr as usize).saturating_add(len) > config.num_cells {
+            return Err(eyre::eyre!(
+                "Range [{ptr:#x}, {ptr:#x} + {len}) is out of bounds of address space {addr_space}"
+            ));
+        }
+        // SAFETY: the address space exists and the range is checked to be in bounds
+        let bytes = unsafe { memory.get_u8_slice(addr_space, ptr as usize, len) };
+        Ok(bytes.to_vec())
+    }
+
+    /// Reads guest memory from the RISC-V memory address space.
+    pub fn read_guest_memory(&self, ptr: u32, len: usize) -> Result<Vec<u8>> {
+        self.read_bytes(RV32_MEMORY_AS, ptr, len)
+    }
+
+    /// Reads `len` cells starting at `ptr` from any address space as field elements.
+    pub fn read_cells(&self, addr_space: u32, ptr: u32, len: usize) -> Result<Vec<F>> {
+        let memory = &self.state().memory.memory;
+        let config = memory
+            .config
+            .get(addr_space as usize)
+            .ok_or_else(|| eyre::eyre!("Address space {addr_space} does not exist"))?;
+        if config.layout == MemoryCellType::Null {
+            return Err(eyre::eyre!("Address space {addr_space} has no memory"));
+        }
+        if (ptr as usize).saturating_add(len) > config.num_cells {
+            return Err(eyre::eyre!(
+                "Range [{ptr:#x}, {ptr:#x} + {len}) is out of bounds of address space {addr_space}"
+            ));
+        }
+        Ok((ptr..ptr + len as u32)
+            // SAFETY: the address space exists and the range is checked to be in bounds
+            .map(|p| unsafe { memory.get_f::<F>(addr_space, p) })
+            .collect())
+    }
+
+    /// Describes the current location: pc with symbol, and the instruction about to execute.
+    pub fn describe_location(&self) -> String {
+        let pc = self.pc();
+        let location = self.symbols.format_pc(pc);
+        match self.instruction_at(pc) {
+            Some(instruction) => format!("{location}: {}", format_instruction(instruction)),
+            None => location,
+        }
+    }
+}
+
+pub(crate) fn format_instruction(instruction: &Instruction<F>) -> String {
+    let Instruction {
+        opcode,
+        a,
+        b,
+        c,
+        d,
+        e,
+        f,
+        g,
+    } = instruction;
+    format!(
+        "{:?} {} {} {} {} {} {} {}",
+        opcode,
+        a.as_canonical_u32(),
+        b.as_canonical_u32(),
+        c.as_canonical_u32(),
+        d.as_canonical_u32(),
+        e.as_canonical_u32(),
+        f.as_canonical_u32(),
+        g.as_canonical_u32()
+    )
+}
+
+pub(crate) fn parse_u32(s: &str) -> Result<u32> {
+    let value = match s.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16)?,
+        None => s.parse::<u32>()?,
+    };
+    Ok(value)
+}
@@ -0,0 +1,189 @@
+use std::io::{BufRead, Write};
+
+use eyre::Result;
+use openvm_circuit::arch::{execution_mode::DebugStop, instructions::riscv::RV32_MEMORY_AS};
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use super::{parse_u32, DebugSession, SessionStatus};
+
+const DEFAULT_MEMORY_LEN: usize = 64;
+
+const HELP: &str = "\
+Commands:
+  break <pc|symbol>        Set a breakpoint (alias: b)
+  delete <pc|symbol>       Delete a breakpoint (alias: d)
+  breakpoints              List breakpoints (alias: bl)
+  step [n]                 Execute n instructions, default 1 (alias: s)
+  continue                 Run until the next breakpoint or termination (alias: c)
+  regs                     Print the registers and pc (alias: r)
+  mem <addr> [len]         Dump guest memory (alias: x)
+  mem <as>:<ptr> [len]     Dump cells of an address space
+  where                    Print the current location (alias: w)
+  help                     Print this message (alias: h)
+  quit                     Exit the debugger (alias: q)";
+
+/// Runs the line-based debugger on `input` until `quit` or end of input.
+pub fn run_repl(
+    session: &mut DebugSession,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<()> {
+    writeln!(
+        output,
+        "[openvm] Debugger started. Type `help` for commands."
+    )?;
+    writeln!(output, "{}", session.describe_location())?;
+    write!(output, "(openvm) ")?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            write!(output, "(openvm) ")?;
+            output.flush()?;
+            continue;
+        };
+        let args = words.collect::<Vec<_>>();
+        if matches!(command, "quit" | "q") {
+            break;
+        }
+        if let Err(err) = execute_command(session, command, &args, &mut output) {
+            writeln!(output, "error: {err}")?;
+        }
+        write!(output, "(openvm) ")?;
+        output.flush()?;
+    }
+    writeln!(output)?;
+    Ok(())
+}
+
+fn execute_command(
+    session: &mut DebugSession,
+    command: &str,
+    args: &[&str],
+    output: &mut impl Write,
+) -> Result<()> {
+    match command {
+        "break" | "b" => {
+            let location = args
+                .first()
+                .ok_or_else(|| eyre::eyre!("Usage: break <pc|symbol>"))?;
+            let pc = session.resolve_location(location)?;
+            if session.instruction_at(pc).is_none() {
+                return Err(eyre::eyre!("No instruction at pc {pc:#x}"));
+            }
+            session.breakpoints.insert(pc);
+            writeln!(output, "Breakpoint at {}", session.symbols.format_pc(pc))?;
+        }
+        "delete" | "d" => {
+            let location = args
+                .first()
+                .ok_or_else(|| eyre::eyre!("Usage: delete <pc|symbol>"))?;
+            let pc = session.resolve_location(location)?;
+            if !session.breakpoints.remove(&pc) {
+                return Err(eyre::eyre!("No breakpoint at pc {pc:#x}"));
+            }
+        }
+        "breakpoints" | "bl" => {
+            for &pc in &session.breakpoints {
+                writeln!(output, "  {}", session.symbols.format_pc(pc))?;
+            }
+        }
+        "step" | "s" => {
+            let n = match args.first() {
+                Some(n) => n.parse::<u64>()?,
+                None => 1,
+            };
+            let stop = session.resume(Some(n))?;
+            report_stop(session, stop, output)?;
+        }
+        "continue" | "c" => {
+            let stop = session.resume(None)?;
+            report_stop(session, stop, output)?;
+        }
+        "regs" | "r" => {
+            let regs = session.registers();
+            for (i, chunk) in regs.chunks(4).enumerate() {
+                let line = chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(j, value)| format!("x{:<2} = {:#010x}", 4 * i + j, value))
+                    .collect::<Vec<_>>()
+                    .join("  ");
+                writeln!(output, "{line}")?;
+            }
+            writeln!(output, "pc  = {}", session.symbols.format_pc(session.pc()))?;
+            writeln!(output, "instret = {}", session.instret())?;
+        }
+        "mem" | "x" => {
+            let location = args
+                .first()
+                .ok_or_else(|| eyre::eyre!("Usage: mem <addr> [len]"))?;
+            let len = match args.get(1) {
+                Some(len) => parse_u32(len)? as usize,
+                None => DEFAULT_MEMORY_LEN,
+            };
+            let (addr_space, ptr) = match location.split_once(':') {
+                Some((addr_space, ptr)) => (parse_u32(addr_space)?, parse_u32(ptr)?),
+                None => (RV32_MEMORY_AS, parse_u32(location)?),
+            };
+            match session.read_bytes(addr_space, ptr, len) {
+                Ok(bytes) => {
+                    for (i, row) in bytes.chunks(16).enumerate() {
+                        let hex = row.iter().map(|b| format!("{b:02x}")).collect::<Vec<_>>();
+                        writeln!(output, "{:#010x}: {}", ptr as usize + 16 * i, hex.join(" "))?;
+                    }
+                }
+                // Not a byte address space: print the cells as field elements
+                Err(_) => {
+                    let cells = session.read_cells(addr_space, ptr, len)?;
+                    for (i, row) in cells.chunks(8).enumerate() {
+                        let values = row
+                            .iter()
+                            .map(|f| f.as_canonical_u32().to_string())
+                            .collect::<Vec<_>>();
+                        writeln!(
+                            output,
+                            "{:#010x}: {}",
+                            ptr as usize + 8 * i,
+                            values.join(" ")
+                        )?;
+                    }
+                }
+            }
+        }
+        "where" | "w" => {
+            writeln!(output, "{}", session.describe_location())?;
+        }
+        "help" | "h" => {
+            writeln!(output, "{HELP}")?;
+        }
+        _ => {
+            return Err(eyre::eyre!(
+                "Unknown command `{command}`. Type `help` for commands."
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn report_stop(session: &DebugSession, stop: DebugStop, output: &mut impl Write) -> Result<()> {
+    match stop {
+        DebugStop::Breakpoint(_) => {
+            writeln!(output, "Breakpoint hit")?;
+            writeln!(output, "{}", session.describe_location())?;
+        }
+        DebugStop::InstretLimit => {
+            writeln!(output, "{}", session.describe_location())?;
+        }
+        DebugStop::Terminated(exit_code) => {
+            debug_assert_eq!(session.status(), &SessionStatus::Terminated(exit_code));
+            writeln!(
+                output,
+                "Program terminated with exit code {exit_code} after {} instructions",
+                session.instret()
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -34,6 +34,7 @@ pub fn default_evm_halo2_verifier_path() -> String {
 
 pub fn default_app_config() -> AppConfig<SdkVmConfig> {
     AppConfig {
+        preset: None,
         app_fri_params: FriParameters::standard_with_100_bits_conjectured_security(
             DEFAULT_APP_LOG_BLOWUP,
         )
@@ -1,15 +1,27 @@
-use std::{fs::read, path::PathBuf, str::FromStr};
+use std::{
+    fs::read,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use eyre::Result;
-use openvm_sdk::{StdIn, F};
-use openvm_stark_backend::p3_field::FieldAlgebra;
+use openvm_rv32im_guest::hint_load_by_key_encode;
+use openvm_sdk::{DirKvStore, MmapKvStore, StdIn, F};
+use openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField32};
+use serde::{
+    de::{DeserializeOwned, Error as _},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 
 /// Input can be either:
 /// (1) one single hex string
-/// (2) A JSON file containing an array of hex strings.
+/// (2) A JSON, TOML or CBOR file (selected by the file extension, JSON by default) containing an
+///     array of stream entries under the `input` key, and optionally `kv_store` entries.
 /// Each hex string (either in the file or the direct input) is either:
 /// - Hex strings of bytes, which is prefixed with 0x01
 /// - Hex strings of native field elements (represented as u32, little endian), prefixed with 0x02
+///
+/// Files may additionally contain typed entries, see [InputFile].
 #[derive(Debug, Clone)]
 pub enum Input {
     FilePath(PathBuf),
@@ -79,37 +91,334 @@ pub fn read_bytes_into_stdin(stdin: &mut StdIn, bytes: &[u8]) -> Result<()> {
     }
 }
 
-pub fn read_to_stdin(input: &Option<Input>) -> Result<StdIn> {
+/// Reads the program input, and the key-value pairs of each of the `kv_inputs` into the
+/// `kv_store`. A `kv_input` is either a directory read lazily as a [DirKvStore], a `.kvdb` file
+/// memory-mapped as a [MmapKvStore], or a JSON, TOML or CBOR [KvFile].
+pub fn read_to_stdin(input: &Option<Input>, kv_inputs: &[PathBuf]) -> Result<StdIn> {
+    let mut stdin = StdIn::default();
     match input {
         Some(Input::FilePath(path)) => {
-            let mut stdin = StdIn::default();
-            // read the json
-            let bytes = read(path)?;
-            let json: serde_json::Value = serde_json::from_slice(&bytes)?;
-            json["input"]
-                .as_array()
-                .ok_or_else(|| eyre::eyre!("Input must be an array under 'input' key"))?
-                .iter()
-                .try_for_each(|inner| {
-                    inner
-                        .as_str()
-                        .ok_or_else(|| eyre::eyre!("Each value must be a hex string"))
-                        .and_then(|s| match decode_hex_string(s) {
-                            Err(msg) => Err(eyre::eyre!("Invalid hex string: {}", msg)),
-                            Ok(bytes) => {
-                                read_bytes_into_stdin(&mut stdin, &bytes).expect("Fail: input validation accepted an input, but the deserialization rejected it");
-                                Ok(())
-                            }
-                        })
-                })?;
-
-            Ok(stdin)
+            let file: InputFile = read_document(path)?;
+            for entry in &file.input {
+                entry.write_to(&mut stdin)?;
+            }
+            write_kv_entries(&mut stdin, &file.kv_store)?;
         }
         Some(Input::HexBytes(bytes)) => {
-            let mut stdin = StdIn::default();
             read_bytes_into_stdin(&mut stdin, bytes)?;
-            Ok(stdin)
         }
-        None => Ok(StdIn::default()),
+        None => {}
+    }
+    for path in kv_inputs {
+        if path.is_dir() {
+            stdin.add_kv_backend(DirKvStore::new(path));
+        } else if path.extension().is_some_and(|ext| ext == "kvdb") {
+            let store = MmapKvStore::open(path)
+                .map_err(|e| eyre::eyre!("Failed to open kv input {}: {e}", path.display()))?;
+            stdin.add_kv_backend(store);
+        } else {
+            let file: KvFile = read_document(path)
+                .map_err(|e| eyre::eyre!("Failed to read kv input {}: {e}", path.display()))?;
+            write_kv_entries(&mut stdin, &file.kv_store)?;
+        }
+    }
+    Ok(stdin)
+}
+
+fn write_kv_entries(stdin: &mut StdIn, entries: &[KvEntry]) -> Result<()> {
+    for kv in entries {
+        let value = match &kv.value {
+            KvValue::Encoded(bytes) => bytes.0.clone(),
+            KvValue::Entries(entries) => {
+                let mut value = StdIn::default();
+                for entry in entries {
+                    entry.write_to(&mut value)?;
+                }
+                let value = value.buffer.into_iter().collect::<Vec<_>>();
+                hint_load_by_key_encode(&value)
+            }
+        };
+        stdin.add_key_value(kv.key.to_bytes()?, value);
+    }
+    Ok(())
+}
+
+/// Reads a JSON, TOML or CBOR document, selected by the file extension and JSON by default.
+fn read_document<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let bytes = read(path)?;
+    let document = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(std::str::from_utf8(&bytes)?)?,
+        Some("cbor") => ciborium::from_reader(bytes.as_slice())?,
+        _ => serde_json::from_slice(&bytes)?,
+    };
+    Ok(document)
+}
+
+/// Contents of an input file. For example, in JSON:
+///
+/// ```json
+/// {
+///   "input": [
+///     "0x01deadbeef",
+///     { "u32": 42 },
+///     { "u64": "0xffffffffffffffff" },
+///     { "string": "hello" },
+///     { "bytes": "0xdeadbeef" },
+///     { "struct": [{ "u8": 1 }, { "vec": [{ "u16": 2 }, { "u16": 3 }] }] },
+///     { "raw": "0xdeadbeef" },
+///     { "fields": [1, 2, 3] }
+///   ],
+///   "kv_store": [
+///     { "key": "0x6b6579", "value": [{ "u32": 7 }] }
+///   ]
+/// }
+/// ```
+///
+/// Each entry of `input` is pushed onto the input stream in order:
+/// - a hex string in the legacy `0x01`/`0x02` prefixed format;
+/// - `{ "raw": "0x.." }`: bytes written as-is, to be read with `openvm::io::read_vec`;
+/// - `{ "fields": [..] }`: native field elements given as canonical `u32` values;
+/// - a [TypedValue], serialized with `openvm::serde` exactly as `StdIn::write` would, to be read
+///   with `openvm::io::read`.
+///
+/// The value of a `kv_store` entry is a list of stream entries in the same format. When the guest
+/// calls `openvm::io::hint_load_by_key` with the key, they are pushed to the front of the input
+/// stream. The value may instead be a hex string of an already encoded value, see [KvValue]. Keys
+/// are hex strings if prefixed with `0x`, and UTF-8 strings otherwise.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InputFile {
+    pub input: Vec<StreamEntry>,
+    #[serde(default)]
+    pub kv_store: Vec<KvEntry>,
+}
+
+/// Contents of a `--kv-input` file: only the `kv_store` section of an [InputFile].
+#[derive(Debug, Clone, Deserialize)]
+pub struct KvFile {
+    pub kv_store: Vec<KvEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KvEntry {
+    pub key: KvKey,
+    pub value: KvValue,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KvValue {
+    /// Hex string of a value already encoded for `hint_load_by_key`, e.g. by
+    /// `encode_proof_to_kv_store_value`
+    Encoded(HexBytes),
+    /// Stream entries to be encoded for `hint_load_by_key`
+    Entries(Vec<StreamEntry>),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(transparent)]
+pub struct KvKey(String);
+
+impl KvKey {
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        match self.0.strip_prefix("0x") {
+            Some(hex) => hex::decode(hex).map_err(|e| eyre::eyre!("Invalid hex key: {}", e)),
+            None => Ok(self.0.as_bytes().to_vec()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum StreamEntry {
+    /// Hex string prefixed with `0x01` or `0x02`
+    Hex(String),
+    Typed(TypedEntry),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypedEntry {
+    Raw(HexBytes),
+    Fields(Vec<u32>),
+    #[serde(untagged)]
+    Value(TypedValue),
+}
+
+impl StreamEntry {
+    pub fn write_to(&self, stdin: &mut StdIn) -> Result<()> {
+        match self {
+            StreamEntry::Hex(s) => {
+                let bytes = decode_hex_string(s)
+                    .map_err(|msg| eyre::eyre!("Invalid hex string: {}", msg))?;
+                read_bytes_into_stdin(stdin, &bytes)
+            }
+            StreamEntry::Typed(TypedEntry::Raw(bytes)) => {
+                stdin.write_bytes(&bytes.0);
+                Ok(())
+            }
+            StreamEntry::Typed(TypedEntry::Fields(values)) => {
+                let fields = values
+                    .iter()
+                    .map(|&value| {
+                        if value < F::ORDER_U32 {
+                            Ok(F::from_canonical_u32(value))
+                        } else {
+                            Err(eyre::eyre!("{value} is not a canonical field element"))
+                        }
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                stdin.write_field(&fields);
+                Ok(())
+            }
+            StreamEntry::Typed(TypedEntry::Value(value)) => {
+                stdin.write(value);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A value with an explicit type, serialized the same way as the corresponding Rust type by
+/// `openvm::serde`. Integers can be given as numbers, or as decimal or `0x` hex strings, which is
+/// required for values that do not fit in the number type of the file format.
+///
+/// Structs, tuples and fixed-size arrays are all serialized as the concatenation of their fields,
+/// in declaration order, and are given as `{ "struct": [..] }`. Variable length sequences such as
+/// `Vec<T>` are prefixed by their length and are given as `{ "vec": [..] }`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypedValue {
+    Bool(bool),
+    #[serde(deserialize_with = "deserialize_int")]
+    U8(u8),
+    #[serde(deserialize_with = "deserialize_int")]
+    U16(u16),
+    #[serde(deserialize_with = "deserialize_int")]
+    U32(u32),
+    #[serde(deserialize_with = "deserialize_int")]
+    U64(u64),
+    #[serde(deserialize_with = "deserialize_int")]
+    U128(u128),
+    #[serde(deserialize_with = "deserialize_int")]
+    I8(i8),
+    #[serde(deserialize_with = "deserialize_int")]
+    I16(i16),
+    #[serde(deserialize_with = "deserialize_int")]
+    I32(i32),
+    #[serde(deserialize_with = "deserialize_int")]
+    I64(i64),
+    #[serde(deserialize_with = "deserialize_int")]
+    I128(i128),
+    String(String),
+    /// Hex encoded `Vec<u8>`
+    Bytes(HexBytes),
+    #[serde(alias = "tuple", alias = "array")]
+    Struct(Vec<TypedValue>),
+    Vec(Vec<TypedValue>),
+    /// `Option::Some`. `Option::None` is given as `{ "none": {} }`.
+    Some(Box<TypedValue>),
+    None(Empty),
+    /// Enum variant with the given index and fields
+    Variant {
+        index: u32,
+        fields: Vec<TypedValue>,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Empty {}
+
+impl Serialize for TypedValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{SerializeSeq, SerializeTuple, SerializeTupleVariant};
+
+        match self {
+            TypedValue::Bool(v) => serializer.serialize_bool(*v),
+            TypedValue::U8(v) => serializer.serialize_u8(*v),
+            TypedValue::U16(v) => serializer.serialize_u16(*v),
+            TypedValue::U32(v) => serializer.serialize_u32(*v),
+            TypedValue::U64(v) => serializer.serialize_u64(*v),
+            TypedValue::U128(v) => serializer.serialize_u128(*v),
+            TypedValue::I8(v) => serializer.serialize_i8(*v),
+            TypedValue::I16(v) => serializer.serialize_i16(*v),
+            TypedValue::I32(v) => serializer.serialize_i32(*v),
+            TypedValue::I64(v) => serializer.serialize_i64(*v),
+            TypedValue::I128(v) => serializer.serialize_i128(*v),
+            TypedValue::String(v) => serializer.serialize_str(v),
+            // Serialized as a sequence rather than with `serialize_bytes`, to match `Vec<u8>`
+            TypedValue::Bytes(v) => serializer.collect_seq(&v.0),
+            TypedValue::Struct(fields) => {
+                let mut tuple = serializer.serialize_tuple(fields.len())?;
+                for field in fields {
+                    tuple.serialize_element(field)?;
+                }
+                tuple.end()
+            }
+            TypedValue::Vec(elements) => {
+                let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+                for element in elements {
+                    seq.serialize_element(element)?;
+                }
+                seq.end()
+            }
+            TypedValue::Some(v) => serializer.serialize_some(v.as_ref()),
+            TypedValue::None(_) => serializer.serialize_none(),
+            TypedValue::Variant { index, fields } => {
+                let mut variant =
+                    serializer.serialize_tuple_variant("", *index, "", fields.len())?;
+                for field in fields {
+                    variant.serialize_field(field)?;
+                }
+                variant.end()
+            }
+        }
+    }
+}
+
+/// Bytes given as a hex string, with or without `0x` prefix.
+#[derive(Debug, Clone)]
+pub struct HexBytes(pub Vec<u8>);
+
+impl<'de> Deserialize<'de> for HexBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let s = s.strip_prefix("0x").unwrap_or(&s);
+        hex::decode(s)
+            .map(HexBytes)
+            .map_err(|e| D::Error::custom(format!("Invalid hex: {}", e)))
+    }
+}
+
+fn deserialize_int<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<u128> + TryFrom<i128>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum IntRepr {
+        Unsigned(u64),
+        Signed(i64),
+        String(String),
+    }
+
+    let out_of_range = || D::Error::custom("integer out of range for its type");
+    match IntRepr::deserialize(deserializer)? {
+        IntRepr::Unsigned(v) => T::try_from(v as u128).map_err(|_| out_of_range()),
+        IntRepr::Signed(v) => T::try_from(v as i128).map_err(|_| out_of_range()),
+        IntRepr::String(s) => {
+            if let Some(hex) = s.strip_prefix("0x") {
+                let v = u128::from_str_radix(hex, 16).map_err(D::Error::custom)?;
+                T::try_from(v).map_err(|_| out_of_range())
+            } else if s.starts_with('-') {
+                let v = s.parse::<i128>().map_err(D::Error::custom)?;
+                T::try_from(v).map_err(|_| out_of_range())
+            } else {
+                let v = s.parse::<u128>().map_err(D::Error::custom)?;
+                T::try_from(v).map_err(|_| out_of_range())
+            }
+        }
     }
 }
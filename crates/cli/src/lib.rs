@@ -2,8 +2,12 @@
 #![cfg_attr(feature = "tco", feature(explicit_tail_calls))]
 
 pub mod commands;
+pub mod cost_report;
+pub mod debugger;
 pub mod default;
 pub mod input;
+pub mod profile;
+pub mod trace;
 pub mod util;
 
 use std::process::{Command, Stdio};
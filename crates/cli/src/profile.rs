@@ -0,0 +1,229 @@
+//! Guest profiles written by `cargo openvm run --profile-out`.
+
+use std::{
+    collections::HashMap,
+    fs::{create_dir_all, write},
+    path::Path,
+};
+
+use eyre::Result;
+use openvm_circuit::arch::{
+    execution_mode::{ProfileCtx, ProfileFrame},
+    instructions::exe::{FnBound, FnBounds},
+};
+use openvm_transpiler::symbols::SymbolTable;
+
+/// Frame name for instructions executed outside of any known function.
+const UNKNOWN_FRAME: &str = "[unknown]";
+
+/// Converts the function symbols of the guest ELF to the bounds used for profiling.
+pub fn fn_bounds_from_symbols(symbols: &SymbolTable) -> FnBounds {
+    symbols
+        .iter()
+        .map(|symbol| {
+            let bound = FnBound {
+                start: symbol.start,
+                end: symbol.start + symbol.size.max(4) - 4,
+                name: symbol.name.clone(),
+            };
+            (symbol.start, bound)
+        })
+        .collect()
+}
+
+/// Writes the profile to the directory `dir`:
+/// - `instructions.folded`: folded stacks weighted by number of executed instructions;
+/// - `cells.folded`: folded stacks weighted by trace cells, with the air as the innermost frame, if
+///   trace cells were profiled;
+/// - `profile.pb`: uncompressed pprof protobuf with the same samples, where trace cells are labeled
+///   by `air`.
+pub fn write_profile(dir: &Path, profile: &ProfileCtx) -> Result<()> {
+    create_dir_all(dir)?;
+    let stacks: Vec<String> = (0..profile.nodes.len())
+        .map(|node| folded_stack(profile, node))
+        .collect();
+
+    let instructions: String = profile
+        .nodes
+        .iter()
+        .zip(&stacks)
+        .filter(|(node, _)| node.instructions > 0)
+        .map(|(node, stack)| format!("{stack} {}\n", node.instructions))
+        .collect();
+    write(dir.join("instructions.folded"), instructions)?;
+
+    if profile.profiles_cells() {
+        let mut cells = String::new();
+        for (node, stack) in profile.nodes.iter().zip(&stacks) {
+            for (air_name, value) in profile.air_names.iter().zip(profile.cells(node)) {
+                if value > 0 {
+                    cells += &format!("{stack};[{}] {value}\n", sanitize(air_name));
+                }
+            }
+        }
+        write(dir.join("cells.folded"), cells)?;
+    }
+
+    write(dir.join("profile.pb"), encode_pprof(profile))?;
+    Ok(())
+}
+
+fn folded_stack(profile: &ProfileCtx, node: usize) -> String {
+    let names = profile.stack_names(node);
+    if names.is_empty() {
+        return UNKNOWN_FRAME.to_string();
+    }
+    names
+        .into_iter()
+        .map(sanitize)
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Frames of folded stacks are separated by `;`, which occurs in Rust array types.
+fn sanitize(name: &str) -> String {
+    name.replace(';', ":")
+}
+
+/// Encodes the profile in the pprof format, see
+/// <https://github.com/google/pprof/blob/main/proto/profile.proto>.
+fn encode_pprof(profile: &ProfileCtx) -> Vec<u8> {
+    let mut strings = StringTable::default();
+    let mut out = Vec::new();
+
+    let mut sample_types = vec![("instructions", "count")];
+    if profile.profiles_cells() {
+        sample_types.push(("cells", "count"));
+    }
+    for (ty, unit) in &sample_types {
+        let mut value_type = Vec::new();
+        put_uint(&mut value_type, 1, strings.id(ty));
+        put_uint(&mut value_type, 2, strings.id(unit));
+        put_bytes(&mut out, 1, &value_type);
+    }
+
+    // Location and function ids are the same, with 1 for the unknown frame
+    let mut location_ids = HashMap::new();
+    let mut location_id = |frame: Option<ProfileFrame>| -> u64 {
+        let next_id = location_ids.len() as u64 + 1;
+        *location_ids.entry(frame).or_insert(next_id)
+    };
+    location_id(None);
+    let air_key = strings.id("air");
+    for idx in 0..profile.nodes.len() {
+        let mut locations = Vec::new();
+        let mut next = Some(idx);
+        while let Some(node) = next.map(|i| &profile.nodes[i]) {
+            if node.frame.is_some() || (locations.is_empty() && node.parent.is_none()) {
+                locations.push(location_id(node.frame));
+            }
+            next = node.parent;
+        }
+        let node = &profile.nodes[idx];
+        let mut samples = vec![(node.instructions, 0, None)];
+        for (air_name, value) in profile.air_names.iter().zip(profile.cells(node)) {
+            samples.push((0, value, Some(air_name)));
+        }
+        for (instructions, cells, air_name) in samples {
+            if instructions == 0 && cells == 0 {
+                continue;
+            }
+            let mut sample = Vec::new();
+            put_packed(&mut sample, 1, &locations);
+            let mut values = vec![instructions];
+            if profile.profiles_cells() {
+                values.push(cells);
+            }
+            put_packed(&mut sample, 2, &values);
+            if let Some(air_name) = air_name {
+                let mut label = Vec::new();
+                put_uint(&mut label, 1, air_key);
+                put_uint(&mut label, 2, strings.id(air_name));
+                put_bytes(&mut sample, 3, &label);
+            }
+            put_bytes(&mut out, 2, &sample);
+        }
+    }
+
+    let mut locations: Vec<_> = location_ids.into_iter().collect();
+    locations.sort_by_key(|&(_, id)| id);
+    for (frame, id) in locations {
+        let name = frame.map_or(UNKNOWN_FRAME, |frame| profile.frame_name(frame));
+        // Spans have no address
+        let address = match frame {
+            Some(ProfileFrame::Function(start)) => start,
+            _ => 0,
+        };
+        let mut line = Vec::new();
+        put_uint(&mut line, 1, id);
+        let mut location = Vec::new();
+        put_uint(&mut location, 1, id);
+        put_uint(&mut location, 3, address as u64);
+        put_bytes(&mut location, 4, &line);
+        put_bytes(&mut out, 4, &location);
+
+        let mut func = Vec::new();
+        put_uint(&mut func, 1, id);
+        put_uint(&mut func, 2, strings.id(name));
+        put_bytes(&mut out, 5, &func);
+    }
+
+    for s in &strings.strings {
+        put_bytes(&mut out, 6, s.as_bytes());
+    }
+    out
+}
+
+/// String table of a pprof profile, whose first entry must be the empty string.
+struct StringTable {
+    strings: Vec<String>,
+    ids: HashMap<String, u64>,
+}
+
+impl Default for StringTable {
+    fn default() -> Self {
+        Self {
+            strings: vec![String::new()],
+            ids: HashMap::from([(String::new(), 0)]),
+        }
+    }
+}
+
+impl StringTable {
+    fn id(&mut self, s: &str) -> u64 {
+        if let Some(&id) = self.ids.get(s) {
+            return id;
+        }
+        let id = self.strings.len() as u64;
+        self.strings.push(s.to_string());
+        self.ids.insert(s.to_string(), id);
+        id
+    }
+}
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn put_uint(out: &mut Vec<u8>, field: u32, value: u64) {
+    put_varint(out, (field as u64) << 3);
+    put_varint(out, value);
+}
+
+fn put_bytes(out: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    put_varint(out, ((field as u64) << 3) | 2);
+    put_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn put_packed(out: &mut Vec<u8>, field: u32, values: &[u64]) {
+    let mut packed = Vec::new();
+    for &value in values {
+        put_varint(&mut packed, value);
+    }
+    put_bytes(out, field, &packed);
+}
@@ -0,0 +1,105 @@
+//! Execution traces written by `cargo openvm run --mode trace`.
+
+use std::{
+    fs::{create_dir_all, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use eyre::Result;
+use openvm_circuit::arch::{
+    execution_mode::{TraceMemoryAccess, TraceStep},
+    instructions::program::{Program, DEFAULT_PC_STEP},
+};
+use openvm_sdk::F;
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+
+/// One executed instruction in a trace file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceRecord {
+    /// Number of instructions executed before this one.
+    pub instret: u64,
+    pub pc: u32,
+    /// Global opcode of the instruction.
+    pub opcode: usize,
+    /// Operands `a` through `g` of the instruction.
+    pub operands: [u32; 7],
+    /// RISC-V registers whose value changed, as `(index, new value)`.
+    pub register_writes: Vec<(u8, u32)>,
+    pub memory_accesses: Vec<TraceMemoryAccess>,
+}
+
+impl TraceRecord {
+    pub fn new(step: TraceStep, program: &Program<F>) -> Self {
+        let instruction = step
+            .pc
+            .checked_sub(program.pc_base)
+            .and_then(|offset| {
+                program.get_instruction_and_debug_info((offset / DEFAULT_PC_STEP) as usize)
+            })
+            .map(|(instruction, _)| instruction);
+        let (opcode, operands) = match instruction {
+            Some(insn) => (
+                insn.opcode.as_usize(),
+                [insn.a, insn.b, insn.c, insn.d, insn.e, insn.f, insn.g]
+                    .map(|x| x.as_canonical_u32()),
+            ),
+            // Only possible for the last step if execution failed with an out of bounds pc
+            None => (usize::MAX, [0; 7]),
+        };
+        Self {
+            instret: step.instret,
+            pc: step.pc,
+            opcode,
+            operands,
+            register_writes: step.register_writes,
+            memory_accesses: step.memory_accesses,
+        }
+    }
+}
+
+/// Writes trace records to a file as execution produces them, as one JSON object per line if the
+/// file extension is `jsonl` and as a sequence of bitcode-encoded [TraceRecord]s, each prefixed by
+/// its length as a little-endian `u32`, otherwise. Unlike the artifacts written by
+/// [openvm_sdk::fs], trace files have no header, so that they can be decoded directly.
+pub struct TraceWriter<'a> {
+    writer: BufWriter<File>,
+    jsonl: bool,
+    program: &'a Program<F>,
+    num_records: u64,
+}
+
+impl<'a> TraceWriter<'a> {
+    pub fn create(path: &Path, program: &'a Program<F>) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            jsonl: path.extension().is_some_and(|ext| ext == "jsonl"),
+            program,
+            num_records: 0,
+        })
+    }
+
+    pub fn write_step(&mut self, step: TraceStep) -> Result<()> {
+        let record = TraceRecord::new(step, self.program);
+        if self.jsonl {
+            serde_json::to_writer(&mut self.writer, &record)?;
+            writeln!(self.writer)?;
+        } else {
+            let bytes = bitcode::serialize(&record)?;
+            self.writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+            self.writer.write_all(&bytes)?;
+        }
+        self.num_records += 1;
+        Ok(())
+    }
+
+    /// Flushes the file and returns the number of records written.
+    pub fn finish(mut self) -> Result<u64> {
+        self.writer.flush()?;
+        Ok(self.num_records)
+    }
+}
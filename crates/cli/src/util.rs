@@ -78,6 +78,11 @@ pub fn get_target_output_dir(target_dir: &Path, profile: &str) -> PathBuf {
     target_dir.join("openvm").join(profile).to_path_buf()
 }
 
+/// Returns the path of the guest ELF that `cargo openvm build` produces for `target_name`.
+pub fn get_elf_path(target_dir: &Path, profile: &str, target_name: &Path) -> PathBuf {
+    openvm_build::get_dir_with_profile(target_dir, profile, false).join(target_name)
+}
+
 pub fn get_app_pk_path(target_dir: &Path) -> PathBuf {
     target_dir.join("openvm").join(DEFAULT_APP_PK_NAME)
 }
@@ -1,9 +1,13 @@
 use std::{
     env,
     fs::{self, read_to_string},
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
     path::Path,
-    process::Command,
+    process::{Command, Stdio},
     sync::OnceLock,
+    thread,
+    time::Duration,
 };
 
 use eyre::Result;
@@ -313,6 +317,409 @@ fn test_cli_run_meter() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_cli_run_trace() -> Result<()> {
+    use cargo_openvm::trace::TraceRecord;
+
+    install_cli();
+    let exe_path = build_fibonacci_once()?;
+    let temp_dir = tempdir()?;
+    let trace_path = temp_dir.path().join("fibonacci.trace.jsonl");
+
+    run_cmd(
+        "cargo",
+        &[
+            "openvm",
+            "run",
+            "--exe",
+            exe_path,
+            "--config",
+            "tests/programs/fibonacci/openvm.toml",
+            "--mode",
+            "trace",
+            "--trace-output",
+            trace_path.to_str().unwrap(),
+        ],
+    )?;
+
+    let records = read_to_string(&trace_path)?
+        .lines()
+        .map(serde_json::from_str::<TraceRecord>)
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(!records.is_empty());
+    for (i, record) in records.iter().enumerate() {
+        assert_eq!(record.instret, i as u64);
+    }
+    assert!(records
+        .iter()
+        .any(|record| !record.register_writes.is_empty()));
+    assert!(records
+        .iter()
+        .flat_map(|record| &record.memory_accesses)
+        .all(|access| !access.values.is_empty()));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_run_profile() -> Result<()> {
+    install_cli();
+    let exe_path = build_fibonacci_once()?;
+    let temp_dir = tempdir()?;
+    let profile_dir = temp_dir.path().join("profile");
+
+    run_cmd(
+        "cargo",
+        &[
+            "openvm",
+            "run",
+            "--exe",
+            exe_path,
+            "--config",
+            "tests/programs/fibonacci/openvm.toml",
+            "--elf",
+            "tests/programs/fibonacci/target/riscv32im-risc0-zkvm-elf/release/openvm-cli-example-test",
+            "--mode",
+            "meter",
+            "--profile-out",
+            profile_dir.to_str().unwrap(),
+        ],
+    )?;
+
+    let instructions = read_to_string(profile_dir.join("instructions.folded"))?;
+    assert!(instructions.lines().any(|line| line.contains("main")));
+    for line in instructions
+        .lines()
+        .chain(read_to_string(profile_dir.join("cells.folded"))?.lines())
+    {
+        let (_, weight) = line.rsplit_once(' ').unwrap();
+        assert!(weight.parse::<u64>()? > 0);
+    }
+    assert!(!fs::read(profile_dir.join("profile.pb"))?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_run_cost_report() -> Result<()> {
+    use cargo_openvm::cost_report::CostReport;
+
+    install_cli();
+    let exe_path = build_fibonacci_once()?;
+    let temp_dir = tempdir()?;
+    let report_path = temp_dir.path().join("cost.json");
+
+    run_cmd(
+        "cargo",
+        &[
+            "openvm",
+            "run",
+            "--exe",
+            exe_path,
+            "--config",
+            "tests/programs/fibonacci/openvm.toml",
+            "--elf",
+            "tests/programs/fibonacci/target/riscv32im-risc0-zkvm-elf/release/openvm-cli-example-test",
+            "--mode",
+            "meter",
+            "--cost-report",
+            report_path.to_str().unwrap(),
+        ],
+    )?;
+
+    let report: CostReport = serde_json::from_str(&read_to_string(&report_path)?)?;
+    assert!(report.total.cells > 0);
+    assert!(report
+        .functions
+        .iter()
+        .any(|entry| entry.name.contains("main")));
+    let functions = &report.functions;
+    assert_eq!(
+        functions.iter().map(|entry| entry.cells).sum::<u64>(),
+        report.total.cells
+    );
+    assert_eq!(
+        functions
+            .iter()
+            .map(|entry| entry.interactions)
+            .sum::<u64>(),
+        report.total.interactions
+    );
+    for entry in functions {
+        assert_eq!(
+            entry.airs.values().map(|air| air.cells).sum::<u64>(),
+            entry.cells
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_run_debug() -> Result<()> {
+    install_cli();
+    let exe_path = build_fibonacci_once()?;
+
+    // Drive the interactive debugger through stdin
+    let stdout = run_cmd_with_stdin(
+        "cargo",
+        &[
+            "openvm",
+            "run",
+            "--exe",
+            exe_path,
+            "--config",
+            "tests/programs/fibonacci/openvm.toml",
+            "--debug",
+        ],
+        "step 10\nregs\nmem 0x0 16\ncontinue\nquit\n",
+    )?;
+    assert!(stdout.contains("instret = 10"));
+    assert!(stdout.contains("Program terminated with exit code 0"));
+
+    Ok(())
+}
+
+#[test]
+fn test_typed_input_file() -> Result<()> {
+    use cargo_openvm::input::{read_to_stdin, Input};
+    use openvm_sdk::StdIn;
+
+    let temp_dir = tempdir()?;
+    let json_path = temp_dir.path().join("input.json");
+    fs::write(
+        &json_path,
+        r#"{
+            "input": [
+                "0x01deadbeef",
+                { "u32": 42 },
+                { "u64": "0xffffffffffffffff" },
+                { "i16": -3 },
+                { "string": "hello" },
+                { "struct": [{ "bool": true }, { "vec": [{ "u8": 1 }, { "u8": 2 }] }] },
+                { "some": { "bytes": "0xabcd" } },
+                { "none": {} }
+            ],
+            "kv_store": [{ "key": "key", "value": [{ "u32": 7 }] }]
+        }"#,
+    )?;
+    let toml_path = temp_dir.path().join("input.toml");
+    fs::write(
+        &toml_path,
+        r#"
+            input = [
+                "0x01deadbeef",
+                { u32 = 42 },
+                { u64 = "0xffffffffffffffff" },
+                { i16 = -3 },
+                { string = "hello" },
+                { struct = [{ bool = true }, { vec = [{ u8 = 1 }, { u8 = 2 }] }] },
+                { some = { bytes = "0xabcd" } },
+                { none = {} },
+            ]
+
+            [[kv_store]]
+            key = "0x6b6579"
+            value = [{ u32 = 7 }]
+        "#,
+    )?;
+
+    let mut expected = StdIn::default();
+    expected.write_bytes(&[0xde, 0xad, 0xbe, 0xef]);
+    expected.write(&42u32);
+    expected.write(&u64::MAX);
+    expected.write(&-3i16);
+    expected.write(&"hello");
+    expected.write(&(true, vec![1u8, 2]));
+    expected.write(&Some(vec![0xabu8, 0xcd]));
+    expected.write(&None::<u32>);
+    let mut value = StdIn::default();
+    value.write(&7u32);
+
+    for path in [json_path, toml_path] {
+        let stdin = read_to_stdin(&Some(Input::FilePath(path)), &[])?;
+        assert_eq!(stdin.buffer, expected.buffer);
+        let encoded = &stdin.kv_store[b"key".as_slice()];
+        let words = value.buffer[0].len() as u32;
+        // `hint_load_by_key` encoding: number of entries, then each entry's length and elements
+        assert_eq!(
+            &encoded[..8],
+            [1u32.to_le_bytes(), words.to_le_bytes()].concat()
+        );
+        assert_eq!(encoded.len(), 8 + 4 * words as usize);
+    }
+
+    let kv_path = temp_dir.path().join("proof.kv.json");
+    fs::write(
+        &kv_path,
+        r#"{ "kv_store": [{ "key": "0x0102", "value": "0xdeadbeef" }] }"#,
+    )?;
+    let stdin = read_to_stdin(&None, &[kv_path])?;
+    assert!(stdin.buffer.is_empty());
+    assert_eq!(
+        stdin.kv_store[[1u8, 2].as_slice()],
+        [0xde, 0xad, 0xbe, 0xef]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_cli_inspect() -> Result<()> {
+    install_cli();
+    let exe_path = build_fibonacci_once()?;
+    let temp_dir = tempdir()?;
+    let temp_pk = temp_dir.path().join("app.pk");
+    let temp_vk = temp_dir.path().join("app.vk");
+
+    run_cmd(
+        "cargo",
+        &[
+            "openvm",
+            "keygen",
+            "--config",
+            "tests/programs/fibonacci/openvm.toml",
+            "--output-dir",
+            temp_dir.path().to_str().unwrap(),
+        ],
+    )?;
+
+    let exe_summary: serde_json::Value = serde_json::from_str(&run_cmd_with_stdin(
+        "cargo",
+        &[
+            "openvm",
+            "inspect",
+            exe_path,
+            "--app-pk",
+            temp_pk.to_str().unwrap(),
+            "--json",
+        ],
+        "",
+    )?)?;
+    assert_eq!(exe_summary["type"], "executable");
+    assert_eq!(exe_summary["compatible"], true);
+    assert!(exe_summary["program_len"].as_u64().unwrap() > 0);
+    assert!(exe_summary["app_exe_commit"].is_string());
+
+    let pk_summary: serde_json::Value = serde_json::from_str(&run_cmd_with_stdin(
+        "cargo",
+        &["openvm", "inspect", temp_pk.to_str().unwrap(), "--json"],
+        "",
+    )?)?;
+    let vk_summary: serde_json::Value = serde_json::from_str(&run_cmd_with_stdin(
+        "cargo",
+        &["openvm", "inspect", temp_vk.to_str().unwrap(), "--json"],
+        "",
+    )?)?;
+    assert_eq!(pk_summary["type"], "app proving key");
+    assert_eq!(vk_summary["type"], "app verifying key");
+    assert_eq!(
+        pk_summary["app_vk_fingerprint"],
+        vk_summary["app_vk_fingerprint"]
+    );
+    assert_eq!(pk_summary["app_vm_commit"], exe_summary["app_vm_commit"]);
+    let airs = pk_summary["airs"].as_array().unwrap();
+    assert!(!airs.is_empty());
+    assert_eq!(airs.len(), vk_summary["airs"].as_array().unwrap().len());
+    assert!(airs.iter().all(|air| air["name"].is_string()));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_run_gdb() -> Result<()> {
+    install_cli();
+    let exe_path = build_fibonacci_once()?;
+
+    // Reserve a free port for the stub to listen on
+    let addr = TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+    let mut child = Command::new("cargo")
+        .args([
+            "openvm",
+            "run",
+            "--exe",
+            exe_path,
+            "--config",
+            "tests/programs/fibonacci/openvm.toml",
+            "--gdb",
+            &addr.to_string(),
+        ])
+        .current_dir(env::current_dir()?)
+        .stdout(Stdio::piped())
+        .spawn()?;
+    // Wait until the stub is listening before connecting
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    while !line.contains("Waiting for GDB") {
+        line.clear();
+        if stdout.read_line(&mut line)? == 0 {
+            return Err(eyre::eyre!("GDB stub exited before listening"));
+        }
+    }
+    let mut stream = TcpStream::connect(addr)?;
+    stream.set_read_timeout(Some(Duration::from_secs(600)))?;
+
+    let reply = gdb_round_trip(&mut stream, "qSupported:swbreak+")?;
+    assert!(reply.contains("QStartNoAckMode+"));
+    assert_eq!(gdb_round_trip(&mut stream, "?")?, "S05");
+    // 32 general purpose registers and the pc, as 8 hex digits each
+    assert_eq!(gdb_round_trip(&mut stream, "g")?.len(), 33 * 8);
+    assert_eq!(gdb_round_trip(&mut stream, "c")?, "W00");
+    assert_eq!(gdb_round_trip(&mut stream, "D")?, "OK");
+
+    // Drain the remaining output so the CLI does not block on a full pipe
+    let drain = thread::spawn(move || stdout.read_to_end(&mut Vec::new()));
+    assert!(child.wait()?.success());
+    drain.join().unwrap()?;
+    Ok(())
+}
+
+/// Sends a GDB remote serial protocol packet and returns the reply, acknowledging both.
+fn gdb_round_trip(stream: &mut TcpStream, packet: &str) -> Result<String> {
+    let checksum = packet.bytes().fold(0u8, |acc, b| acc.wrapping_add(b));
+    write!(stream, "${packet}#{checksum:02x}")?;
+
+    let mut byte = [0u8; 1];
+    stream.read_exact(&mut byte)?;
+    assert_eq!(byte[0], b'+', "packet {packet} was not acknowledged");
+    stream.read_exact(&mut byte)?;
+    assert_eq!(byte[0], b'$');
+    let mut reply = Vec::new();
+    loop {
+        stream.read_exact(&mut byte)?;
+        if byte[0] == b'#' {
+            break;
+        }
+        reply.push(byte[0]);
+    }
+    let mut received = [0u8; 2];
+    stream.read_exact(&mut received)?;
+    let expected = reply.iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+    assert_eq!(
+        u8::from_str_radix(std::str::from_utf8(&received)?, 16)?,
+        expected
+    );
+    stream.write_all(b"+")?;
+    Ok(String::from_utf8(reply)?)
+}
+
+fn run_cmd_with_stdin(program: &str, args: &[&str], input: &str) -> Result<String> {
+    let package_dir = env::current_dir()?;
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(package_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    child.stdin.take().unwrap().write_all(input.as_bytes())?;
+    let output = child.wait_with_output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+    println!("{stdout}");
+    if !output.status.success() {
+        return Err(eyre::eyre!("Command failed with status: {}", output.status));
+    }
+    Ok(stdout)
+}
+
 fn run_cmd(program: &str, args: &[&str]) -> Result<()> {
     let package_dir = env::current_dir()?;
     let prefix = "[test cli e2e]";
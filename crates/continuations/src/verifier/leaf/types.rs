@@ -56,6 +56,31 @@ impl<SC: StarkGenericConfig> LeafVmVerifierInput<SC> {
             Some(UserPublicValuesRootProof::extract(user_public_values));
         ret
     }
+
+    /// Returns the chunk with index `idx` of [chunk_continuation_vm_proof], cloning only its
+    /// proofs, or `None` if there is no such chunk.
+    ///
+    /// [chunk_continuation_vm_proof]: Self::chunk_continuation_vm_proof
+    pub fn continuation_vm_proof_chunk(
+        proof: &ContinuationVmProof<SC>,
+        chunk: usize,
+        idx: usize,
+    ) -> Option<Self> {
+        let ContinuationVmProof {
+            per_segment,
+            user_public_values,
+        } = proof;
+        let start = idx.checked_mul(chunk)?;
+        if start >= per_segment.len() {
+            return None;
+        }
+        let end = per_segment.len().min(start + chunk);
+        Some(Self {
+            proofs: per_segment[start..end].to_vec(),
+            public_values_root_proof: (end == per_segment.len())
+                .then(|| UserPublicValuesRootProof::extract(user_public_values)),
+        })
+    }
 }
 
 impl<F: Clone> UserPublicValuesRootProof<F> {
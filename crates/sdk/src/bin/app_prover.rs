@@ -1,3 +1,182 @@
-fn main() {
-    println!("Hello, world!");
+//! Worker which proves single continuation segments of an app execution, so that segment proving
+//! can be fanned out over several processes. A coordinator computes the segmentation plan with
+//! [Sdk::segmentation_plan](openvm_sdk::Sdk::segmentation_plan), creates the jobs with
+//! [Sdk::for_each_segment_job](openvm_sdk::Sdk::for_each_segment_job), and
+//! assembles the [SegmentProof]s returned by the workers with
+//! [stitch_segment_proofs](openvm_sdk::prover::stitch_segment_proofs).
+//!
+//! Jobs are received in one of two ways:
+//! - `--queue-dir <DIR>`: every job is a file `<name>.job` in the directory, which a worker claims
+//!   by renaming it. The proof is written to `<name>.proof`, or the error to `<name>.err`.
+//! - `--socket <PATH>`: the worker listens on a Unix socket, and every connection sends one job and
+//!   receives one `Result<SegmentProof, String>`.
+//!
+//! All messages are encoded with bitcode, and on the socket are prefixed by their length as a
+//! little-endian `u64`.
+
+use std::{
+    fs::{read_dir, remove_file, rename, write},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::Arc,
+    thread::sleep,
+    time::Duration,
+};
+
+use clap::{ArgGroup, Parser};
+use eyre::Result;
+use openvm_circuit::arch::instructions::exe::VmExe;
+use openvm_sdk::{
+    config::{SdkVmBuilder, SdkVmConfig},
+    fs::{read_object_from_file, write_object_to_file},
+    keygen::AppProvingKey,
+    prover::{AppProver, SegmentJob, SegmentProof},
+    DefaultStarkEngine, F, SC,
+};
+use openvm_stark_sdk::config::setup_tracing;
+
+type Prover = AppProver<DefaultStarkEngine, SdkVmBuilder>;
+
+#[derive(Debug, Parser)]
+#[command(group(ArgGroup::new("jobs").required(true).args(["queue_dir", "socket"])))]
+struct AppProverArgs {
+    /// Path to the app proving key
+    #[arg(long)]
+    app_pk: PathBuf,
+    /// Path to the app executable
+    #[arg(long)]
+    exe: PathBuf,
+    /// Directory of `<name>.job` files to take jobs from
+    #[arg(long)]
+    queue_dir: Option<PathBuf>,
+    /// Unix socket to listen on for jobs
+    #[arg(long)]
+    socket: Option<PathBuf>,
+    /// Interval in milliseconds at which an empty queue directory is polled
+    #[arg(long, default_value_t = 500)]
+    poll_interval_ms: u64,
+    /// Exit once the queue directory is empty instead of polling it for new jobs
+    #[arg(long)]
+    exit_when_empty: bool,
+}
+
+fn main() -> Result<()> {
+    let args = AppProverArgs::parse();
+    setup_tracing();
+
+    let app_pk: AppProvingKey<SdkVmConfig> = read_object_from_file(&args.app_pk)?;
+    let exe: VmExe<F> = read_object_from_file(&args.exe)?;
+    let mut prover = Prover::new(
+        SdkVmBuilder,
+        &app_pk.app_vm_pk,
+        Arc::new(exe),
+        app_pk.leaf_verifier_program_commit(),
+    )?;
+
+    if let Some(queue_dir) = &args.queue_dir {
+        let poll_interval = Duration::from_millis(args.poll_interval_ms);
+        run_queue(&mut prover, queue_dir, poll_interval, args.exit_when_empty)
+    } else {
+        serve_socket(&mut prover, args.socket.as_ref().unwrap())
+    }
+}
+
+fn prove(prover: &mut Prover, job: SegmentJob) -> Result<SegmentProof<SC>> {
+    Ok(prover.prove_segment(job)?)
+}
+
+fn run_queue(
+    prover: &mut Prover,
+    dir: &Path,
+    poll_interval: Duration,
+    exit_when_empty: bool,
+) -> Result<()> {
+    loop {
+        let Some((name, claimed)) = claim_job(dir)? else {
+            if exit_when_empty {
+                return Ok(());
+            }
+            sleep(poll_interval);
+            continue;
+        };
+        tracing::info!("Proving job {name}");
+        match read_object_from_file(&claimed).and_then(|job| prove(prover, job)) {
+            Ok(proof) => {
+                // Write to a temporary file first so that the coordinator never sees a partial
+                // proof
+                let tmp = dir.join(format!("{name}.proof.tmp"));
+                write_object_to_file(&tmp, proof)?;
+                rename(tmp, dir.join(format!("{name}.proof")))?;
+            }
+            Err(err) => {
+                tracing::error!("Job {name} failed: {err:?}");
+                write(dir.join(format!("{name}.err")), format!("{err:?}"))?;
+            }
+        }
+        remove_file(claimed)?;
+    }
+}
+
+/// Claims the first job in `dir` by renaming it, which fails if another worker has already claimed
+/// it. Returns the name of the job and the path of the claimed file.
+fn claim_job(dir: &Path) -> Result<Option<(String, PathBuf)>> {
+    let mut names: Vec<String> = read_dir(dir)?
+        .filter_map(|entry| {
+            let file_name = entry.ok()?.file_name().into_string().ok()?;
+            file_name.strip_suffix(".job").map(str::to_string)
+        })
+        .collect();
+    names.sort();
+    for name in names {
+        let claimed = dir.join(format!("{name}.job.{}", process::id()));
+        if rename(dir.join(format!("{name}.job")), &claimed).is_ok() {
+            return Ok(Some((name, claimed)));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(unix)]
+fn serve_socket(prover: &mut Prover, path: &Path) -> Result<()> {
+    use std::os::unix::net::UnixListener;
+
+    let listener = UnixListener::bind(path)?;
+    tracing::info!("Listening on {}", path.display());
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        let result = read_message(&mut stream)
+            .and_then(|job| prove(prover, job))
+            .map_err(|err| format!("{err:?}"));
+        if let Err(err) = &result {
+            tracing::error!("Job failed: {err}");
+        }
+        if let Err(err) = write_message(&mut stream, &result) {
+            tracing::warn!("Failed to send result: {err:?}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn serve_socket(_prover: &mut Prover, _path: &Path) -> Result<()> {
+    eyre::bail!("--socket is only supported on Unix")
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn read_message<T: serde::de::DeserializeOwned>(reader: &mut impl Read) -> Result<T> {
+    let mut len = [0u8; 8];
+    reader.read_exact(&mut len)?;
+    let mut bytes = vec![0u8; u64::from_le_bytes(len).try_into()?];
+    reader.read_exact(&mut bytes)?;
+    Ok(bitcode::deserialize(&bytes)?)
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn write_message<T: serde::Serialize>(writer: &mut impl Write, message: &T) -> Result<()> {
+    let bytes = bitcode::serialize(message)?;
+    writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+    Ok(())
 }
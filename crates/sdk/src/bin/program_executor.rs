@@ -1,16 +1,107 @@
-use std::path::PathBuf;
+//! Executes an app with segmentation and writes the resulting [SegmentationPlan], so that the
+//! segments can be proven by a separate fleet of provers, e.g. `app_prover` workers fed from
+//! `--jobs-dir`. Every job contains the start state of its segment, so that workers do not execute
+//! the program up to the segment.
+
+use std::{
+    fs::{read_to_string, rename},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use clap::Parser;
+use eyre::Result;
+use openvm_circuit::arch::instructions::exe::VmExe;
+use openvm_sdk::{
+    config::SdkVmConfig,
+    fs::{read_object_from_file, write_object_to_file, write_to_file_json},
+    keygen::AppProvingKey,
+    prover::SegmentJob,
+    types::SegmentationPlan,
+    Sdk, SdkError, StdIn, F,
+};
+use openvm_stark_sdk::config::setup_tracing;
 
 #[derive(Debug, Parser)]
 struct ExecutorArgs {
+    /// Path to the app executable
+    #[arg(long)]
+    exe: PathBuf,
+    /// Path to the openvm.toml app config
+    #[arg(long)]
+    config: PathBuf,
+    /// Path to the app proving key, which is generated from the config if not given
     #[arg(long)]
-    program_dir: PathBuf,
-    // input -> what type?
+    app_pk: Option<PathBuf>,
+    /// Path to the input, as a bitcode encoded `StdIn`
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// Output path of the segmentation plan, written as JSON
+    #[arg(long, default_value = "segmentation_plan.json")]
+    output: PathBuf,
+    /// Also write a `segment_<idx>.job` file for every segment to this directory, to be proven by
+    /// `app_prover --queue-dir`. Every job contains the encoded start state of its segment, which
+    /// is as large as the touched memory.
+    #[arg(long)]
+    jobs_dir: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let args = ExecutorArgs::parse();
+    setup_tracing();
+
+    let app_config = SdkVmConfig::from_toml(&read_to_string(&args.config)?)?;
+    let sdk = Sdk::new(app_config)?;
+    if let Some(app_pk_path) = &args.app_pk {
+        let app_pk: AppProvingKey<SdkVmConfig> = read_object_from_file(app_pk_path)?;
+        sdk.set_app_pk(app_pk)
+            .map_err(|_| eyre::eyre!("Failed to set app pk"))?;
+    }
+    let exe: Arc<VmExe<F>> = Arc::new(read_object_from_file(&args.exe)?);
+    let input: StdIn = match &args.input {
+        Some(path) => read_object_from_file(path)?,
+        None => StdIn::default(),
+    };
+
+    let (public_values, plan) = sdk.segmentation_plan(exe.clone(), input.clone())?;
+    println!("Execution output: {:?}", public_values);
+    print_plan(&plan);
+    write_to_file_json(&args.output, &plan)?;
+    println!("Segmentation plan written to {}", args.output.display());
+
+    if let Some(jobs_dir) = &args.jobs_dir {
+        sdk.for_each_segment_job(exe, input, &plan.segments, |job| {
+            write_job(jobs_dir, job).map_err(SdkError::Other)
+        })?;
+        println!("Segment jobs written to {}", jobs_dir.display());
+    }
+    Ok(())
+}
+
+fn write_job(jobs_dir: &Path, job: SegmentJob) -> Result<()> {
+    // Write to a temporary file first so that workers never claim a partial job
+    let name = format!("segment_{:05}", job.segment_idx);
+    let tmp = jobs_dir.join(format!("{name}.job.tmp"));
+    write_object_to_file(&tmp, job)?;
+    rename(tmp, jobs_dir.join(format!("{name}.job")))?;
+    Ok(())
 }
 
-fn main() {
-    let _args = ExecutorArgs::parse();
-    // 1. get the exe from program dir
-    println!("Hello, world!");
+fn print_plan(plan: &SegmentationPlan) {
+    let total: u64 = plan.segments.iter().map(|s| s.num_insns).sum();
+    println!("Number of instructions executed: {}", total);
+    println!("Total segments: {}", plan.segments.len());
+    for (idx, segment) in plan.segments.iter().enumerate() {
+        let max_height = segment
+            .trace_heights
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or_default();
+        println!(
+            "Segment {idx}: instret {}..{}, max trace height {max_height}",
+            segment.instret_start,
+            segment.instret_start + segment.num_insns,
+        );
+    }
 }
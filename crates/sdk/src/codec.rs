@@ -1,7 +1,16 @@
-use std::io::{self, Cursor, Read, Result, Write};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    io::{self, Cursor, Read, Result, Write},
+    sync::Arc,
+};
 
 use openvm_circuit::{
-    arch::ContinuationVmProof, system::memory::merkle::public_values::UserPublicValuesProof,
+    arch::{AddressSpaceHostConfig, ContinuationVmProof, MemoryCellType, Streams, VmState},
+    system::memory::{
+        merkle::public_values::UserPublicValuesProof,
+        online::{GuestMemory, LinearMemory},
+        AddressMap,
+    },
 };
 use openvm_continuations::verifier::{
     internal::types::VmStarkProof, root::types::RootVmVerifierInput,
@@ -26,6 +35,13 @@ type Challenge = BinomialExtensionField<F, 4>;
 /// It does correspond to the main openvm version (which may change more frequently).
 const CODEC_VERSION: u32 = 1;
 
+/// Version of the [VmState] encoding, which changes independently of [CODEC_VERSION] whenever the
+/// VM state or its encoding changes.
+const VM_STATE_CODEC_VERSION: u32 = 1;
+
+/// Memory is encoded in pages of this many bytes, skipping pages which are all zero.
+const MEMORY_PAGE_SIZE: usize = 1 << 12;
+
 /// Hardware and language independent encoding.
 /// Uses the Writer pattern for more efficient encoding without intermediate buffers.
 // @dev Trait just for implementation sanity
@@ -83,6 +99,110 @@ impl Encode for RootVmVerifierInput<SC> {
     }
 }
 
+/// Encodes a checkpoint of the VM state, from which execution can be resumed.
+///
+/// The RNG is encoded as its seed and the number of words drawn from it, see
+/// [VmState::rng_word_pos]. Encoding fails if the `kv_store` backend cannot list its keys.
+impl Encode for VmState<F> {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(&VM_STATE_CODEC_VERSION.to_le_bytes())?;
+        self.instret().encode(writer)?;
+        self.pc().encode(writer)?;
+        self.memory.encode(writer)?;
+        self.streams.encode(writer)?;
+        self.rng_seed().encode(writer)?;
+        self.rng_word_pos().encode(writer)?;
+        encode_slice(self.custom_pvs(), writer)
+    }
+}
+
+impl Encode for GuestMemory {
+    /// Encodes the host config of each address space, followed by the pages of its memory which
+    /// are not all zero.
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let AddressMap { mem, config } = &self.memory;
+        config.len().encode(writer)?;
+        for (mem, config) in mem.iter().zip(config) {
+            config.num_cells.encode(writer)?;
+            config.min_block_size.encode(writer)?;
+            config.layout.encode(writer)?;
+            let pages: Vec<(usize, &[u8])> = mem
+                .as_slice()
+                .chunks(MEMORY_PAGE_SIZE)
+                .enumerate()
+                .filter(|(_, page)| page.iter().any(|&b| b != 0))
+                .collect();
+            pages.len().encode(writer)?;
+            for (idx, page) in pages {
+                idx.encode(writer)?;
+                writer.write_all(page)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Encode for MemoryCellType {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        match self {
+            MemoryCellType::Null => writer.write_all(&[0]),
+            MemoryCellType::U8 => writer.write_all(&[1]),
+            MemoryCellType::U16 => writer.write_all(&[2]),
+            MemoryCellType::U32 => writer.write_all(&[3]),
+            MemoryCellType::Native { size } => writer.write_all(&[4, *size]),
+        }
+    }
+}
+
+impl Encode for Streams<F> {
+    /// Encodes the streams, with the `kv_store` as its entries sorted by key and each
+    /// `fd_buffers` entry as the file descriptor and its bytes.
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.input_stream.len().encode(writer)?;
+        for input in &self.input_stream {
+            encode_slice(input, writer)?;
+        }
+        self.hint_stream.len().encode(writer)?;
+        for val in &self.hint_stream {
+            val.encode(writer)?;
+        }
+        self.hint_space.len().encode(writer)?;
+        for hint in &self.hint_space {
+            encode_slice(hint, writer)?;
+        }
+
+        let mut kv_entries = self.kv_store.entries().ok_or_else(|| {
+            io::Error::other("kv_store backend cannot list its keys, so it cannot be encoded")
+        })?;
+        kv_entries.sort_unstable();
+        kv_entries.len().encode(writer)?;
+        for (key, value) in &kv_entries {
+            encode_bytes(key, writer)?;
+            encode_bytes(value, writer)?;
+        }
+
+        self.fd_buffers.len().encode(writer)?;
+        for (fd, buffer) in &self.fd_buffers {
+            fd.encode(writer)?;
+            buffer.len().encode(writer)?;
+            let (front, back) = buffer.as_slices();
+            writer.write_all(front)?;
+            writer.write_all(back)?;
+        }
+        Ok(())
+    }
+}
+
+impl Encode for Option<F> {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        match self {
+            // `F` is < MODULUS < 2^31 so it never collides with u32::MAX
+            Some(val) => val.encode(writer),
+            None => writer.write_all(&u32::MAX.to_le_bytes()),
+        }
+    }
+}
+
 impl Encode for Proof<SC> {
     // We need to know:
     // - Pcs is TwoAdicFriPcs
@@ -319,6 +439,30 @@ impl Encode for usize {
     }
 }
 
+impl Encode for u32 {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(&self.to_le_bytes())
+    }
+}
+
+impl Encode for u64 {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(&self.to_le_bytes())
+    }
+}
+
+impl Encode for u128 {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(&self.to_le_bytes())
+    }
+}
+
+/// Encodes length of bytes and then the bytes
+fn encode_bytes<W: Write>(bytes: &[u8], writer: &mut W) -> Result<()> {
+    bytes.len().encode(writer)?;
+    writer.write_all(bytes)
+}
+
 // ============ Decode implementation =============
 
 impl Decode for ContinuationVmProof<SC> {
@@ -367,6 +511,149 @@ impl Decode for RootVmVerifierInput<SC> {
     }
 }
 
+impl Decode for VmState<F> {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        let version = u32::decode(reader)?;
+        if version != VM_STATE_CODEC_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Invalid VM state codec version. Expected {}, got {}",
+                    VM_STATE_CODEC_VERSION, version
+                ),
+            ));
+        }
+        let instret = u64::decode(reader)?;
+        let pc = u32::decode(reader)?;
+        let memory = GuestMemory::decode(reader)?;
+        let streams = Streams::<F>::decode(reader)?;
+        let rng_seed = u64::decode(reader)?;
+        let rng_word_pos = u128::decode(reader)?;
+        let custom_pvs: Vec<Option<F>> = decode_vec(reader)?;
+
+        let mut state =
+            VmState::new_with_defaults(instret, pc, memory, streams, rng_seed, custom_pvs.len());
+        state.set_rng(rng_seed, rng_word_pos);
+        *state.custom_pvs_mut() = custom_pvs;
+        Ok(state)
+    }
+}
+
+impl Decode for GuestMemory {
+    /// See [GuestMemory::encode].
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        let num_addr_spaces = usize::decode(reader)?;
+        let mut config = Vec::with_capacity(num_addr_spaces);
+        let mut pages = Vec::with_capacity(num_addr_spaces);
+        for _ in 0..num_addr_spaces {
+            let num_cells = usize::decode(reader)?;
+            let min_block_size = usize::decode(reader)?;
+            let layout = MemoryCellType::decode(reader)?;
+            let addr_space = AddressSpaceHostConfig::new(num_cells, min_block_size, layout);
+            let size = addr_space.size();
+            let num_pages = usize::decode(reader)?;
+            let mut addr_space_pages = Vec::with_capacity(num_pages);
+            for _ in 0..num_pages {
+                let start = usize::decode(reader)?
+                    .checked_mul(MEMORY_PAGE_SIZE)
+                    .ok_or_else(|| invalid_data("Memory page index overflows"))?;
+                if start >= size {
+                    return Err(invalid_data("Memory page is out of bounds"));
+                }
+                let mut page = vec![0u8; MEMORY_PAGE_SIZE.min(size - start)];
+                reader.read_exact(&mut page)?;
+                addr_space_pages.push((start, page));
+            }
+            config.push(addr_space);
+            pages.push(addr_space_pages);
+        }
+        if config
+            .first()
+            .is_none_or(|addr_space| addr_space.num_cells != 0)
+        {
+            return Err(invalid_data("Address space 0 must have 0 cells"));
+        }
+
+        let mut memory: AddressMap = AddressMap::new(config);
+        for (mem, addr_space_pages) in memory.mem.iter_mut().zip(pages) {
+            let mem = mem.as_mut_slice();
+            for (start, page) in addr_space_pages {
+                mem[start..start + page.len()].copy_from_slice(&page);
+            }
+        }
+        Ok(GuestMemory::new(memory))
+    }
+}
+
+impl Decode for MemoryCellType {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        let mut tag = [0u8; 1];
+        reader.read_exact(&mut tag)?;
+        match tag[0] {
+            0 => Ok(MemoryCellType::Null),
+            1 => Ok(MemoryCellType::U8),
+            2 => Ok(MemoryCellType::U16),
+            3 => Ok(MemoryCellType::U32),
+            4 => {
+                let mut size = [0u8; 1];
+                reader.read_exact(&mut size)?;
+                Ok(MemoryCellType::Native { size: size[0] })
+            }
+            tag => Err(invalid_data(&format!("Unknown MemoryCellType: {tag}"))),
+        }
+    }
+}
+
+impl Decode for Streams<F> {
+    /// See [Streams::encode].
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        let input_count = usize::decode(reader)?;
+        let mut input_stream = VecDeque::with_capacity(input_count);
+        for _ in 0..input_count {
+            input_stream.push_back(decode_vec(reader)?);
+        }
+        let hint_stream = VecDeque::from(decode_vec(reader)?);
+        let hint_space_count = usize::decode(reader)?;
+        let mut hint_space = Vec::with_capacity(hint_space_count);
+        for _ in 0..hint_space_count {
+            hint_space.push(decode_vec(reader)?);
+        }
+
+        let kv_count = usize::decode(reader)?;
+        let mut kv_store = HashMap::with_capacity(kv_count);
+        for _ in 0..kv_count {
+            let key = decode_bytes(reader)?;
+            let value = decode_bytes(reader)?;
+            kv_store.insert(key, value);
+        }
+
+        let fd_count = usize::decode(reader)?;
+        let mut fd_buffers = BTreeMap::new();
+        for _ in 0..fd_count {
+            let fd = u32::decode(reader)?;
+            fd_buffers.insert(fd, VecDeque::from(decode_bytes(reader)?));
+        }
+
+        Ok(Streams {
+            input_stream,
+            hint_stream,
+            hint_space,
+            kv_store: Arc::new(kv_store),
+            fd_buffers,
+        })
+    }
+}
+
+impl Decode for Option<F> {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        let value = u32::decode(reader)?;
+        if value == u32::MAX {
+            return Ok(None);
+        }
+        Ok(Some(F::from_canonical_u32(value)))
+    }
+}
+
 impl Decode for Proof<SC> {
     /// Decode a proof using FRI as the PCS with `BabyBearPoseidon2Config`.
     fn decode<R: Read>(reader: &mut R) -> Result<Self> {
@@ -640,3 +927,39 @@ impl Decode for usize {
         Ok(value as usize)
     }
 }
+
+impl Decode for u32 {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        let mut bytes = [0u8; 4];
+        reader.read_exact(&mut bytes)?;
+        Ok(u32::from_le_bytes(bytes))
+    }
+}
+
+impl Decode for u64 {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        let mut bytes = [0u8; 8];
+        reader.read_exact(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+}
+
+impl Decode for u128 {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        let mut bytes = [0u8; 16];
+        reader.read_exact(&mut bytes)?;
+        Ok(u128::from_le_bytes(bytes))
+    }
+}
+
+/// Decodes bytes encoded with their length
+fn decode_bytes<R: Read>(reader: &mut R) -> Result<Vec<u8>> {
+    let len = usize::decode(reader)?;
+    let mut bytes = vec![0u8; len];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
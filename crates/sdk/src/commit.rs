@@ -54,6 +54,10 @@ impl CommitBytes {
         Self(u32_digest_to_bytes(digest))
     }
 
+    pub fn from_field_digest(digest: &[F; DIGEST_SIZE]) -> Self {
+        Self::from_u32_digest(&digest.map(|x| x.as_canonical_u32()))
+    }
+
     pub fn reverse(&mut self) {
         self.0.reverse();
     }
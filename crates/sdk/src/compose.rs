@@ -0,0 +1,192 @@
+//! Composition of OpenVM STARK proofs, where the guest of a parent program verifies the STARK
+//! proofs of child programs with the function defined by `define_verify_openvm_stark!` of
+//! `openvm-verify-stark`.
+//!
+//! The guest reads the proof of a child from the `kv_store` at the key computed by
+//! [verify_stark_hint_key], and the commits and user public values of the child from the input
+//! stream. [StdIn::add_child_proof] supplies both. To bind the parent proof to its children, the
+//! guest reveals the commits of the child with index `i` at [child_commit_offset]`(i)` of its user
+//! public values, which [check_child_commits] checks against the user public values of the parent
+//! proof.
+
+use openvm_continuations::verifier::internal::types::VmStarkProof;
+use openvm_native_compiler::ir::DIGEST_SIZE;
+use openvm_native_recursion::hints::Hintable;
+use openvm_rv32im_guest::hint_load_by_key_encode;
+use openvm_stark_backend::{
+    p3_field::{FieldAlgebra, PrimeField32},
+    proof::Proof,
+};
+
+use crate::{commit::AppExecutionCommit, SdkError, StdIn, F, SC};
+
+/// Default filename of the root verifier ASM passed to `define_verify_openvm_stark!`.
+pub const DEFAULT_ROOT_VERIFIER_ASM_FILENAME: &str = "root_verifier.asm";
+
+/// Number of bytes of user public values taken by the revealed commits of a child, see
+/// [revealed_commit_bytes].
+pub const CHILD_COMMIT_NUM_BYTES: usize = 2 * DIGEST_SIZE * 4;
+
+/// STARK proof of a child program, to be verified by the guest of a parent program.
+#[derive(Clone)]
+pub struct ChildProof {
+    pub app_commit: AppExecutionCommit,
+    pub proof: VmStarkProof<SC>,
+}
+
+impl ChildProof {
+    pub fn new(app_commit: AppExecutionCommit, proof: VmStarkProof<SC>) -> Self {
+        Self { app_commit, proof }
+    }
+
+    /// User public values of the proof as bytes, which is how `verify_openvm_stark` takes them.
+    pub fn user_public_values(&self) -> Result<Vec<u8>, SdkError> {
+        self.proof
+            .user_public_values
+            .iter()
+            .map(|pv| u8::try_from(pv.as_canonical_u32()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| {
+                SdkError::Other(eyre::eyre!(
+                    "verify_openvm_stark requires all user public values to be bytes"
+                ))
+            })
+    }
+
+    /// The `kv_store` key and value from which `verify_openvm_stark`, defined with the ASM file
+    /// `asm_filename`, reads the proof.
+    pub fn kv_entry(&self, asm_filename: &str) -> Result<(Vec<u8>, Vec<u8>), SdkError> {
+        let key = verify_stark_hint_key(
+            asm_filename,
+            &self.app_commit.app_exe_commit.to_u32_digest(),
+            &self.app_commit.app_vm_commit.to_u32_digest(),
+            &self.user_public_values()?,
+        );
+        Ok((key, encode_proof_to_kv_store_value(&self.proof.inner)))
+    }
+}
+
+/// Computes the `kv_store` key at which `verify_openvm_stark`, defined with the ASM file
+/// `asm_filename`, looks for the proof with the given commits and user public values.
+pub fn verify_stark_hint_key(
+    asm_filename: &str,
+    exe_commit_u32: &[u32; DIGEST_SIZE],
+    vm_commit_u32: &[u32; DIGEST_SIZE],
+    pvs: &[u8],
+) -> Vec<u8> {
+    asm_filename
+        .as_bytes()
+        .iter()
+        .cloned()
+        .chain(exe_commit_u32.iter().flat_map(|x| x.to_le_bytes()))
+        .chain(vm_commit_u32.iter().flat_map(|x| x.to_le_bytes()))
+        .chain(pvs.iter().cloned())
+        .collect()
+}
+
+/// The bytes a parent guest reveals for a child with commits `app_commit`: the `u32` words of the
+/// app exe commit followed by those of the app VM commit, in little-endian, as revealed by
+/// `openvm::io::reveal_u32`.
+pub fn revealed_commit_bytes(app_commit: &AppExecutionCommit) -> Vec<u8> {
+    app_commit
+        .app_exe_commit
+        .to_u32_digest()
+        .iter()
+        .chain(&app_commit.app_vm_commit.to_u32_digest())
+        .flat_map(|x| x.to_le_bytes())
+        .collect()
+}
+
+/// Encodes a proof into the `kv_store` value from which `verify_openvm_stark` hints it.
+pub fn encode_proof_to_kv_store_value(proof: &Proof<SC>) -> Vec<u8> {
+    let to_encode: Vec<Vec<F>> = proof.write();
+    hint_load_by_key_encode(&to_encode)
+}
+
+/// Offset in the user public values of a parent proof at which the guest reveals the commits of
+/// the child with index `idx`, which is the `u32` index `offset / 4` of `openvm::io::reveal_u32`.
+/// The commits of the children are revealed one after another from the start of the user public
+/// values.
+pub fn child_commit_offset(idx: usize) -> usize {
+    idx * CHILD_COMMIT_NUM_BYTES
+}
+
+/// Checks that the user public values of a parent proof contain the revealed commits of the child
+/// with index `i` at [child_commit_offset]`(i)`, see [revealed_commit_bytes].
+pub fn check_child_commits(
+    parent_user_public_values: &[F],
+    child_commits: &[AppExecutionCommit],
+) -> Result<(), SdkError> {
+    for (idx, app_commit) in child_commits.iter().enumerate() {
+        let expected: Vec<F> = revealed_commit_bytes(app_commit)
+            .into_iter()
+            .map(F::from_canonical_u8)
+            .collect();
+        let offset = child_commit_offset(idx);
+        if parent_user_public_values.get(offset..offset + CHILD_COMMIT_NUM_BYTES)
+            != Some(&expected[..])
+        {
+            return Err(SdkError::MissingChildCommit {
+                idx,
+                app_commit: *app_commit,
+            });
+        }
+    }
+    Ok(())
+}
+
+impl StdIn {
+    /// Supplies the STARK proof of a child program to a guest which verifies it with
+    /// `verify_openvm_stark`, defined with the ASM file `asm_filename`. The proof is added to the
+    /// `kv_store`, and the app exe commit, app VM commit and user public values of the child are
+    /// written to the input stream in this order, to be read with `openvm::io::read` as
+    /// `[u32; 8]`, `[u32; 8]` and `Vec<u8>`.
+    pub fn add_child_proof(
+        &mut self,
+        asm_filename: &str,
+        child: &ChildProof,
+    ) -> Result<(), SdkError> {
+        let (key, value) = child.kv_entry(asm_filename)?;
+        self.add_key_value(key, value);
+        self.write(&child.app_commit.app_exe_commit.to_u32_digest());
+        self.write(&child.app_commit.app_vm_commit.to_u32_digest());
+        self.write(&child.user_public_values()?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commit::CommitBytes;
+
+    fn app_commit(exe: u32, vm: u32) -> AppExecutionCommit {
+        AppExecutionCommit {
+            app_exe_commit: CommitBytes::from_u32_digest(&[exe; DIGEST_SIZE]),
+            app_vm_commit: CommitBytes::from_u32_digest(&[vm; DIGEST_SIZE]),
+        }
+    }
+
+    #[test]
+    fn test_check_child_commits() {
+        let commits = [app_commit(1, 2), app_commit(3, 4)];
+        let mut pvs = vec![];
+        for commit in &commits {
+            pvs.extend(
+                revealed_commit_bytes(commit)
+                    .into_iter()
+                    .map(F::from_canonical_u8),
+            );
+        }
+        pvs.extend([F::ZERO; 4]);
+        assert!(check_child_commits(&pvs, &commits).is_ok());
+        assert!(check_child_commits(&pvs[..2 * CHILD_COMMIT_NUM_BYTES - 1], &commits).is_err());
+        assert!(check_child_commits(&pvs, &[app_commit(2, 1)]).is_err());
+        // The commits must be at the offset of their child
+        assert!(check_child_commits(&pvs, &[commits[1]]).is_err());
+        assert!(check_child_commits(&pvs, &[commits[1], commits[0]]).is_err());
+        let mut shifted = vec![F::ZERO; 4];
+        shifted.extend(&pvs);
+        assert!(check_child_commits(&shifted, &commits).is_err());
+    }
+}
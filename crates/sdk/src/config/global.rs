@@ -6,6 +6,8 @@ use openvm_algebra_circuit::{
 use openvm_algebra_transpiler::{Fp2TranspilerExtension, ModularTranspilerExtension};
 use openvm_bigint_circuit::{Int256, Int256CpuProverExt, Int256Executor};
 use openvm_bigint_transpiler::Int256TranspilerExtension;
+use openvm_blake_circuit::{Blake, BlakeCpuProverExt, BlakeExecutor};
+use openvm_blake_transpiler::BlakeTranspilerExtension;
 use openvm_circuit::{
     arch::{instructions::NATIVE_AS, *},
     derive::VmConfig,
@@ -27,6 +29,8 @@ use openvm_pairing_circuit::{
     BLS12_381_COMPLEX_STRUCT_NAME, BN254_COMPLEX_STRUCT_NAME,
 };
 use openvm_pairing_transpiler::PairingTranspilerExtension;
+use openvm_poseidon2_circuit::{Poseidon2, Poseidon2CpuProverExt, Poseidon2Executor};
+use openvm_poseidon2_transpiler::Poseidon2TranspilerExtension;
 use openvm_rv32im_circuit::{
     Rv32I, Rv32IExecutor, Rv32ImCpuProverExt, Rv32Io, Rv32IoExecutor, Rv32M, Rv32MExecutor,
 };
@@ -35,6 +39,8 @@ use openvm_rv32im_transpiler::{
 };
 use openvm_sha256_circuit::{Sha256, Sha256Executor, Sha2CpuProverExt};
 use openvm_sha256_transpiler::Sha256TranspilerExtension;
+use openvm_sha512_circuit::{Sha512, Sha512CpuProverExt, Sha512Executor};
+use openvm_sha512_transpiler::Sha512TranspilerExtension;
 use openvm_stark_backend::{
     config::{StarkGenericConfig, Val},
     engine::StarkEngine,
@@ -47,6 +53,7 @@ cfg_if::cfg_if! {
     if #[cfg(feature = "cuda")] {
         use openvm_algebra_circuit::AlgebraProverExt;
         use openvm_bigint_circuit::Int256GpuProverExt;
+        use openvm_blake_circuit::BlakeGpuProverExt;
         use openvm_circuit::system::cuda::{extensions::SystemGpuBuilder, SystemChipInventoryGPU};
         use openvm_cuda_backend::{
             engine::GpuBabyBearPoseidon2Engine, prover_backend::GpuBackend, types::SC,
@@ -54,13 +61,16 @@ cfg_if::cfg_if! {
         use openvm_ecc_circuit::EccProverExt;
         use openvm_keccak256_circuit::Keccak256GpuProverExt;
         use openvm_native_circuit::NativeGpuProverExt;
+        use openvm_poseidon2_circuit::Poseidon2GpuProverExt;
         use openvm_rv32im_circuit::Rv32ImGpuProverExt;
         use openvm_sha256_circuit::Sha256GpuProverExt;
+        use openvm_sha512_circuit::Sha512GpuProverExt;
         pub use SdkVmGpuBuilder as SdkVmBuilder;
     } else if #[cfg(feature = "rocm")] {
         // HIP/ROCm backend support
         use openvm_algebra_circuit::AlgebraProverExt;
         use openvm_bigint_circuit::Int256ProverExt;
+        use openvm_blake_circuit::BlakeProverExt;
         use openvm_circuit::{
             arch::DenseRecordArena,
             system::hip::{extensions::SystemHipBuilder, SystemChipInventoryHIP},
@@ -71,8 +81,10 @@ cfg_if::cfg_if! {
         };
         use openvm_keccak256_circuit::Keccak256ProverExt;
         use openvm_native_circuit::NativeProverExt;
+        use openvm_poseidon2_circuit::Poseidon2ProverExt;
         use openvm_rv32im_circuit::Rv32ImHipProverExt;
         use openvm_sha256_circuit::Sha256ProverExt;
+        use openvm_sha512_circuit::Sha512ProverExt;
         pub use SdkVmHipBuilder as SdkVmBuilder;
     } else {
         pub use SdkVmCpuBuilder as SdkVmBuilder;
@@ -99,6 +111,9 @@ pub struct SdkVmConfig {
     pub io: Option<UnitStruct>,
     pub keccak: Option<UnitStruct>,
     pub sha256: Option<UnitStruct>,
+    pub sha512: Option<UnitStruct>,
+    pub poseidon2: Option<UnitStruct>,
+    pub blake: Option<UnitStruct>,
     pub native: Option<UnitStruct>,
     pub castf: Option<UnitStruct>,
 
@@ -219,6 +234,15 @@ impl TranspilerConfig<F> for SdkVmConfig {
         if self.sha256.is_some() {
             transpiler = transpiler.with_extension(Sha256TranspilerExtension);
         }
+        if self.sha512.is_some() {
+            transpiler = transpiler.with_extension(Sha512TranspilerExtension);
+        }
+        if self.poseidon2.is_some() {
+            transpiler = transpiler.with_extension(Poseidon2TranspilerExtension);
+        }
+        if self.blake.is_some() {
+            transpiler = transpiler.with_extension(BlakeTranspilerExtension);
+        }
         if self.native.is_some() {
             transpiler = transpiler.with_extension(LongFormTranspilerExtension);
         }
@@ -287,6 +311,9 @@ impl SdkVmConfig {
         let io = config.io.map(|_| Rv32Io);
         let keccak = config.keccak.map(|_| Keccak256);
         let sha256 = config.sha256.map(|_| Sha256);
+        let sha512 = config.sha512.map(|_| Sha512);
+        let poseidon2 = config.poseidon2.map(|_| Poseidon2);
+        let blake = config.blake.map(|_| Blake);
         let native = config.native.map(|_| Native);
         let castf = config.castf.map(|_| CastFExtension);
         let rv32m = config.rv32m;
@@ -302,6 +329,9 @@ impl SdkVmConfig {
             io,
             keccak,
             sha256,
+            sha512,
+            poseidon2,
+            blake,
             native,
             castf,
             rv32m,
@@ -334,6 +364,12 @@ pub struct SdkVmConfigInner {
     pub keccak: Option<Keccak256>,
     #[extension(executor = "Sha256Executor")]
     pub sha256: Option<Sha256>,
+    #[extension(executor = "Sha512Executor")]
+    pub sha512: Option<Sha512>,
+    #[extension(executor = "Poseidon2Executor<F>")]
+    pub poseidon2: Option<Poseidon2>,
+    #[extension(executor = "BlakeExecutor")]
+    pub blake: Option<Blake>,
     #[extension(executor = "NativeExecutor<F>")]
     pub native: Option<Native>,
     #[extension(executor = "CastFExtensionExecutor")]
@@ -412,6 +448,19 @@ where
         if let Some(sha256) = &config.sha256 {
             VmProverExtension::<E, _, _>::extend_prover(&Sha2CpuProverExt, sha256, inventory)?;
         }
+        if let Some(sha512) = &config.sha512 {
+            VmProverExtension::<E, _, _>::extend_prover(&Sha512CpuProverExt, sha512, inventory)?;
+        }
+        if let Some(poseidon2) = &config.poseidon2 {
+            VmProverExtension::<E, _, _>::extend_prover(
+                &Poseidon2CpuProverExt,
+                poseidon2,
+                inventory,
+            )?;
+        }
+        if let Some(blake) = &config.blake {
+            VmProverExtension::<E, _, _>::extend_prover(&BlakeCpuProverExt, blake, inventory)?;
+        }
         if let Some(native) = &config.native {
             VmProverExtension::<E, _, _>::extend_prover(&NativeCpuProverExt, native, inventory)?;
         }
@@ -476,6 +525,19 @@ impl VmBuilder<GpuBabyBearPoseidon2Engine> for SdkVmGpuBuilder {
         if let Some(sha256) = &config.sha256 {
             VmProverExtension::<E, _, _>::extend_prover(&Sha256GpuProverExt, sha256, inventory)?;
         }
+        if let Some(sha512) = &config.sha512 {
+            VmProverExtension::<E, _, _>::extend_prover(&Sha512GpuProverExt, sha512, inventory)?;
+        }
+        if let Some(poseidon2) = &config.poseidon2 {
+            VmProverExtension::<E, _, _>::extend_prover(
+                &Poseidon2GpuProverExt,
+                poseidon2,
+                inventory,
+            )?;
+        }
+        if let Some(blake) = &config.blake {
+            VmProverExtension::<E, _, _>::extend_prover(&BlakeGpuProverExt, blake, inventory)?;
+        }
         if let Some(native) = &config.native {
             VmProverExtension::<E, _, _>::extend_prover(&NativeGpuProverExt, native, inventory)?;
         }
@@ -545,6 +607,15 @@ impl VmBuilder<HipBabyBearPoseidon2Engine> for SdkVmHipBuilder {
         if let Some(sha256) = &config.sha256 {
             VmProverExtension::<E, _, _>::extend_prover(&Sha256ProverExt, sha256, inventory)?;
         }
+        if let Some(sha512) = &config.sha512 {
+            VmProverExtension::<E, _, _>::extend_prover(&Sha512ProverExt, sha512, inventory)?;
+        }
+        if let Some(poseidon2) = &config.poseidon2 {
+            VmProverExtension::<E, _, _>::extend_prover(&Poseidon2ProverExt, poseidon2, inventory)?;
+        }
+        if let Some(blake) = &config.blake {
+            VmProverExtension::<E, _, _>::extend_prover(&BlakeProverExt, blake, inventory)?;
+        }
         if let Some(native) = &config.native {
             VmProverExtension::<E, _, _>::extend_prover(&NativeProverExt, native, inventory)?;
         }
@@ -658,6 +729,24 @@ impl From<Sha256> for UnitStruct {
     }
 }
 
+impl From<Sha512> for UnitStruct {
+    fn from(_: Sha512) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Poseidon2> for UnitStruct {
+    fn from(_: Poseidon2) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Blake> for UnitStruct {
+    fn from(_: Blake) -> Self {
+        UnitStruct {}
+    }
+}
+
 impl From<Native> for UnitStruct {
     fn from(_: Native) -> Self {
         UnitStruct {}
@@ -679,6 +768,9 @@ struct SdkVmConfigWithDefaultDeser {
     pub io: Option<UnitStruct>,
     pub keccak: Option<UnitStruct>,
     pub sha256: Option<UnitStruct>,
+    pub sha512: Option<UnitStruct>,
+    pub poseidon2: Option<UnitStruct>,
+    pub blake: Option<UnitStruct>,
     pub native: Option<UnitStruct>,
     pub castf: Option<UnitStruct>,
 
@@ -698,6 +790,9 @@ impl From<SdkVmConfigWithDefaultDeser> for SdkVmConfig {
             io: config.io,
             keccak: config.keccak,
             sha256: config.sha256,
+            sha512: config.sha512,
+            poseidon2: config.poseidon2,
+            blake: config.blake,
             native: config.native,
             castf: config.castf,
             rv32m: config.rv32m,
@@ -10,7 +10,9 @@ use openvm_transpiler::transpiler::Transpiler;
 use serde::{Deserialize, Serialize};
 
 mod global;
+mod preset;
 pub use global::*;
+pub use preset::*;
 
 pub const DEFAULT_APP_LOG_BLOWUP: usize = 1;
 pub const DEFAULT_LEAF_LOG_BLOWUP: usize = 1;
@@ -33,6 +35,10 @@ pub trait TranspilerConfig<F> {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AppConfig<VC> {
+    /// If set, the app and leaf FRI parameters, as well as the aggregation config and tree of the
+    /// SDK, are those of the preset, see [AppConfig::with_preset].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<FriPreset>,
     #[serde(default)]
     pub app_fri_params: AppFriParams,
     pub app_vm_config: VC,
@@ -102,6 +108,7 @@ pub struct AggregationTreeConfig {
 impl<VC> AppConfig<VC> {
     pub fn new(app_fri_params: FriParameters, app_vm_config: VC) -> Self {
         Self {
+            preset: None,
             app_fri_params: AppFriParams::from(app_fri_params),
             app_vm_config,
             leaf_fri_params: Default::default(),
@@ -115,6 +122,7 @@ impl<VC> AppConfig<VC> {
         leaf_fri_params: FriParameters,
     ) -> Self {
         Self {
+            preset: None,
             app_fri_params: AppFriParams::from(app_fri_params),
             app_vm_config,
             leaf_fri_params: LeafFriParams::from(leaf_fri_params),
@@ -0,0 +1,274 @@
+use std::fmt::{self, Display};
+
+use clap::ValueEnum;
+use openvm_stark_backend::p3_field::{PrimeField32, TwoAdicField};
+use openvm_stark_sdk::config::FriParameters;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    AggregationConfig, AggregationTreeConfig, AppConfig, AppFriParams, LeafFriParams,
+    DEFAULT_APP_LOG_BLOWUP, DEFAULT_INTERNAL_LOG_BLOWUP, DEFAULT_LEAF_LOG_BLOWUP,
+    DEFAULT_MAX_INTERNAL_WRAPPER_LAYERS, DEFAULT_NUM_CHILDREN_INTERNAL, DEFAULT_NUM_CHILDREN_LEAF,
+    DEFAULT_ROOT_LOG_BLOWUP,
+};
+use crate::{SdkError, F};
+
+/// Named combinations of the FRI parameters of the app, leaf, internal and root proofs and of the
+/// aggregation tree shape, which trade off prover time, proof size and security.
+///
+/// Since the leaf, internal and root verifiers are keyed to the FRI parameters of the proofs they
+/// verify, the app proving key and the aggregation proving key must be generated with the same
+/// preset.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum FriPreset {
+    /// The default blowups with the fewest queries for 100 bits of conjectured security, and a
+    /// wider aggregation tree with fewer internal proofs.
+    FastProver,
+    /// Higher blowups with fewer queries for 100 bits of conjectured security, which makes every
+    /// proof, in particular the root proof, smaller at the cost of slower proving.
+    SmallProof,
+    /// The default FRI parameters and aggregation tree.
+    #[default]
+    Balanced,
+    /// The default blowups with more queries and proof-of-work bits for 128 bits of conjectured
+    /// security by query count, which is capped at the roughly 123 bits of the challenge field.
+    MaxSecurity,
+}
+
+impl FriPreset {
+    pub fn app_fri_params(self) -> FriParameters {
+        match self {
+            FriPreset::FastProver => fri_params(1, 84, 16),
+            FriPreset::SmallProof => fri_params(2, 42, 16),
+            FriPreset::Balanced => {
+                FriParameters::standard_with_100_bits_conjectured_security(DEFAULT_APP_LOG_BLOWUP)
+            }
+            FriPreset::MaxSecurity => fri_params(1, 108, 20),
+        }
+    }
+
+    pub fn leaf_fri_params(self) -> FriParameters {
+        match self {
+            FriPreset::FastProver => fri_params(1, 84, 16),
+            FriPreset::SmallProof => fri_params(2, 42, 16),
+            FriPreset::Balanced => {
+                FriParameters::standard_with_100_bits_conjectured_security(DEFAULT_LEAF_LOG_BLOWUP)
+            }
+            FriPreset::MaxSecurity => fri_params(1, 108, 20),
+        }
+    }
+
+    pub fn internal_fri_params(self) -> FriParameters {
+        match self {
+            FriPreset::FastProver => fri_params(2, 42, 16),
+            FriPreset::SmallProof => fri_params(3, 28, 16),
+            FriPreset::Balanced => FriParameters::standard_with_100_bits_conjectured_security(
+                DEFAULT_INTERNAL_LOG_BLOWUP,
+            ),
+            FriPreset::MaxSecurity => fri_params(2, 54, 20),
+        }
+    }
+
+    pub fn root_fri_params(self) -> FriParameters {
+        match self {
+            FriPreset::FastProver => fri_params(3, 28, 16),
+            FriPreset::SmallProof => fri_params(4, 21, 16),
+            FriPreset::Balanced => {
+                FriParameters::standard_with_100_bits_conjectured_security(DEFAULT_ROOT_LOG_BLOWUP)
+            }
+            FriPreset::MaxSecurity => fri_params(3, 36, 20),
+        }
+    }
+
+    pub fn agg_tree_config(self) -> AggregationTreeConfig {
+        let num_children_internal = match self {
+            FriPreset::FastProver => 4,
+            _ => DEFAULT_NUM_CHILDREN_INTERNAL,
+        };
+        AggregationTreeConfig {
+            num_children_leaf: DEFAULT_NUM_CHILDREN_LEAF,
+            num_children_internal,
+            max_internal_wrapper_layers: DEFAULT_MAX_INTERNAL_WRAPPER_LAYERS,
+        }
+    }
+
+    /// The FRI parameters of the app, leaf, internal and root proofs, labeled by proof type.
+    pub fn fri_params_by_proof(self) -> [(&'static str, FriParameters); 4] {
+        [
+            ("app", self.app_fri_params()),
+            ("leaf", self.leaf_fri_params()),
+            ("internal", self.internal_fri_params()),
+            ("root", self.root_fri_params()),
+        ]
+    }
+
+    /// Security of the final proof, which is that of the weakest of the app, leaf, internal and
+    /// root proofs.
+    pub fn security(self) -> FriSecurity {
+        self.fri_params_by_proof()
+            .iter()
+            .map(|(_, fri_params)| FriSecurity::new(fri_params))
+            .reduce(|a, b| FriSecurity {
+                conjectured_bits: a.conjectured_bits.min(b.conjectured_bits),
+                proven_bits: a.proven_bits.min(b.proven_bits),
+            })
+            .unwrap()
+    }
+}
+
+impl Display for FriPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FriPreset::FastProver => "fast-prover",
+            FriPreset::SmallProof => "small-proof",
+            FriPreset::Balanced => "balanced",
+            FriPreset::MaxSecurity => "max-security",
+        };
+        write!(f, "{name}")
+    }
+}
+
+fn fri_params(log_blowup: usize, num_queries: usize, proof_of_work_bits: usize) -> FriParameters {
+    FriParameters {
+        log_blowup,
+        log_final_poly_len: 0,
+        num_queries,
+        proof_of_work_bits,
+    }
+}
+
+/// Estimated bits of security of FRI over the degree 4 extension of BabyBear.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FriSecurity {
+    /// Security assuming the ethSTARK toy problem conjecture, under which every query gives
+    /// `log_blowup` bits.
+    pub conjectured_bits: usize,
+    /// Security proven in the unique decoding regime, where every query is rejected with
+    /// probability at least `(1 - rate) / 2`, and the commit phase is bounded by the size of the
+    /// largest evaluation domain over the size of the challenge field.
+    pub proven_bits: usize,
+}
+
+impl FriSecurity {
+    pub fn new(fri_params: &FriParameters) -> Self {
+        let log_blowup = fri_params.log_blowup as f64;
+        let num_queries = fri_params.num_queries as f64;
+        let pow_bits = fri_params.proof_of_work_bits as f64;
+        let challenge_field_bits = 4.0 * (F::ORDER_U32 as f64).log2();
+
+        let conjectured_bits = (log_blowup * num_queries + pow_bits).min(challenge_field_bits);
+
+        let rate = (-log_blowup).exp2();
+        let query_bits = -num_queries * ((1.0 + rate) / 2.0).log2() + pow_bits;
+        let commit_bits = challenge_field_bits - F::TWO_ADICITY as f64;
+        let proven_bits = query_bits.min(commit_bits);
+
+        Self {
+            conjectured_bits: conjectured_bits.floor() as usize,
+            proven_bits: proven_bits.floor() as usize,
+        }
+    }
+}
+
+impl<VC> AppConfig<VC> {
+    /// Selects `preset`, which sets the app and leaf FRI parameters.
+    pub fn with_preset(mut self, preset: FriPreset) -> Self {
+        self.preset = Some(preset);
+        self.app_fri_params = preset.app_fri_params().into();
+        self.leaf_fri_params = preset.leaf_fri_params().into();
+        self
+    }
+
+    /// Sets the app and leaf FRI parameters of the selected preset, if any. Since parameters equal
+    /// to the defaults cannot be told apart from unset ones, only parameters which differ from both
+    /// the defaults and those of the preset are a conflict, which is an error.
+    pub fn with_selected_preset(self) -> Result<Self, SdkError> {
+        let Some(preset) = self.preset else {
+            return Ok(self);
+        };
+        for (component, actual, default, expected) in [
+            (
+                "app",
+                self.app_fri_params.fri_params,
+                AppFriParams::default().fri_params,
+                preset.app_fri_params(),
+            ),
+            (
+                "leaf",
+                self.leaf_fri_params.fri_params,
+                LeafFriParams::default().fri_params,
+                preset.leaf_fri_params(),
+            ),
+        ] {
+            if actual != default && actual != expected {
+                return Err(SdkError::FriPresetConflict {
+                    preset,
+                    component,
+                    actual,
+                });
+            }
+        }
+        Ok(self.with_preset(preset))
+    }
+}
+
+impl AggregationConfig {
+    /// Sets the leaf, internal and root FRI parameters of `preset`.
+    pub fn with_preset(mut self, preset: FriPreset) -> Self {
+        self.leaf_fri_params = preset.leaf_fri_params();
+        self.internal_fri_params = preset.internal_fri_params();
+        self.root_fri_params = preset.root_fri_params();
+        self.root_max_constraint_degree = self.root_fri_params.max_constraint_degree();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_security() {
+        for &preset in FriPreset::value_variants() {
+            let security = preset.security();
+            assert!(security.conjectured_bits >= 100, "{preset}: {security:?}");
+            assert!(security.proven_bits < security.conjectured_bits);
+        }
+        let max_security = FriPreset::MaxSecurity.security();
+        let balanced = FriPreset::Balanced.security();
+        assert!(max_security.conjectured_bits > balanced.conjectured_bits);
+        assert!(max_security.proven_bits > balanced.proven_bits);
+        assert_eq!(
+            AggregationConfig::default()
+                .with_preset(FriPreset::Balanced)
+                .root_max_constraint_degree,
+            AggregationConfig::default().root_max_constraint_degree
+        );
+    }
+
+    #[test]
+    fn test_selected_preset_conflict() {
+        let mut app_config = AppConfig::new(FriPreset::SmallProof.app_fri_params(), ());
+        app_config.preset = Some(FriPreset::FastProver);
+        assert!(matches!(
+            app_config.clone().with_selected_preset(),
+            Err(SdkError::FriPresetConflict {
+                component: "app",
+                ..
+            })
+        ));
+        // Default and matching parameters are replaced by those of the preset
+        app_config.app_fri_params = AppFriParams::default();
+        let app_config = app_config.with_selected_preset().unwrap();
+        assert_eq!(
+            app_config.app_fri_params.fri_params,
+            FriPreset::FastProver.app_fri_params()
+        );
+        assert_eq!(
+            app_config.leaf_fri_params.fri_params,
+            FriPreset::FastProver.leaf_fri_params()
+        );
+        assert!(app_config.with_selected_preset().is_ok());
+    }
+}
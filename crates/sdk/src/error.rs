@@ -1,8 +1,12 @@
 use openvm_circuit::arch::{VirtualMachineError, VmVerificationError};
+use openvm_stark_sdk::config::FriParameters;
 use openvm_transpiler::transpiler::TranspilerError;
 use thiserror::Error;
 
-use crate::commit::CommitBytes;
+use crate::{
+    commit::{AppExecutionCommit, CommitBytes},
+    config::FriPreset,
+};
 
 #[derive(Error, Debug)]
 pub enum SdkError {
@@ -28,6 +32,23 @@ pub enum SdkError {
         expected: CommitBytes,
         actual: CommitBytes,
     },
+    #[error("Proof bundle mismatch in {component}: expected {expected}, bundle has {actual}")]
+    BundleMismatch {
+        component: &'static str,
+        expected: String,
+        actual: String,
+    },
+    #[error("Parent public values do not contain the commits of child {idx} at its offset: {app_commit:?}")]
+    MissingChildCommit {
+        idx: usize,
+        app_commit: AppExecutionCommit,
+    },
+    #[error("FRI preset {preset} conflicts with the {component} FRI parameters {actual:?} of the app config, remove one of them")]
+    FriPresetConflict {
+        preset: FriPreset,
+        component: &'static str,
+        actual: FriParameters,
+    },
     #[error("Other error: {0}")]
     Other(eyre::Error),
 }
@@ -1,16 +1,27 @@
 use std::{
+    fmt::{Display, Formatter},
     fs::{create_dir_all, read, write, File},
+    io::{BufWriter, ErrorKind, Read, Seek, SeekFrom, Write},
     path::Path,
 };
 
 use eyre::{Report, Result};
+use openvm_circuit::arch::instructions::exe::VmExe;
 #[cfg(feature = "evm-prove")]
 use openvm_native_recursion::halo2::wrapper::EvmVerifierByteCode;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::codec::{Decode, Encode};
+use crate::{
+    codec::{Decode, Encode},
+    commit::VmCommittedExe,
+    keygen::{
+        AggProvingKey, AggProvingKeyMetadata, AggVerifyingKey, AppProvingKey, AppVerifyingKey,
+    },
+    prover::{SegmentJob, SegmentProof},
+    StdIn, F, SC,
+};
 #[cfg(feature = "evm-prove")]
-use crate::{types::EvmHalo2Verifier, OPENVM_VERSION};
+use crate::{keygen::Halo2ProvingKey, types::EvmHalo2Verifier, OPENVM_VERSION};
 
 pub const EVM_HALO2_VERIFIER_INTERFACE_NAME: &str = "IOpenVmHalo2Verifier.sol";
 pub const EVM_HALO2_VERIFIER_PARENT_NAME: &str = "Halo2Verifier.sol";
@@ -94,31 +105,66 @@ pub fn write_evm_halo2_verifier_to_folder<P: AsRef<Path>>(
     Ok(())
 }
 
-pub fn read_object_from_file<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T> {
+pub fn read_object_from_file<T: Artifact + DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T> {
     read_from_file_bitcode(path)
 }
 
-pub fn write_object_to_file<T: Serialize, P: AsRef<Path>>(path: P, data: T) -> Result<()> {
+/// Reads an artifact without an [ArtifactHeader], which was written by OpenVM `version` before
+/// artifacts were versioned, and migrates it to the current version. Files with a header are
+/// rejected, since they are read with [read_object_from_file].
+pub fn read_legacy_object_from_file<T: Artifact + DeserializeOwned, P: AsRef<Path>>(
+    path: P,
+    version: ArtifactVersion,
+) -> Result<T> {
+    let bytes = read(&path).map_err(|e| read_error(&path, e.into()))?;
+    let header = match bytes.get(..ArtifactHeader::LEN) {
+        Some(header) => ArtifactHeader::from_bytes(header).map_err(|e| read_error(&path, e))?,
+        None => None,
+    };
+    if let Some(header) = header {
+        return Err(read_error(
+            &path,
+            eyre::eyre!(
+                "the file has a header for a {} of openvm {} and is not a legacy artifact",
+                header.kind,
+                header.version
+            ),
+        ));
+    }
+    tracing::warn!(
+        "Reading {} as a legacy {} of openvm {version} without an artifact header",
+        path.as_ref().display(),
+        T::KIND,
+    );
+    let payload = migrate(T::KIND, version, bytes).map_err(|e| read_error(&path, e))?;
+    bitcode::deserialize(&payload).map_err(|e: bitcode::Error| read_error(&path, e.into()))
+}
+
+pub fn write_object_to_file<T: Artifact + Serialize, P: AsRef<Path>>(
+    path: P,
+    data: T,
+) -> Result<()> {
     write_to_file_bitcode(path, data)
 }
 
-fn read_from_file_bitcode<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T> {
-    let ret = read(&path)
-        .map_err(|e| read_error(&path, e.into()))
-        .and_then(|data| {
-            bitcode::deserialize(&data).map_err(|e: bitcode::Error| read_error(&path, e.into()))
-        })?;
+fn read_from_file_bitcode<T: Artifact + DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T> {
+    let ret = read_artifact(&path, T::KIND).and_then(|payload| {
+        bitcode::deserialize(&payload).map_err(|e: bitcode::Error| read_error(&path, e.into()))
+    })?;
     Ok(ret)
 }
 
-fn write_to_file_bitcode<T: Serialize, P: AsRef<Path>>(path: P, data: T) -> Result<()> {
-    if let Some(parent) = path.as_ref().parent() {
-        create_dir_all(parent).map_err(|e| write_error(&path, e.into()))?;
-    }
-    bitcode::serialize(&data)
-        .map_err(|e| write_error(&path, e.into()))
-        .and_then(|bytes| write(&path, bytes).map_err(|e| write_error(&path, e.into())))?;
-    Ok(())
+fn write_to_file_bitcode<T: Artifact + Serialize, P: AsRef<Path>>(path: P, data: T) -> Result<()> {
+    let metadata = data.metadata_bytes().map_err(|e| write_error(&path, e))?;
+    let payload = bitcode::serialize(&data).map_err(|e| write_error(&path, e.into()))?;
+    write_artifact(path, T::KIND, &metadata, &payload)
+}
+
+/// Reads the [AggProvingKeyMetadata] written before the aggregation proving key at `path`,
+/// without reading the key itself.
+pub fn read_agg_pk_metadata_from_file<P: AsRef<Path>>(path: P) -> Result<AggProvingKeyMetadata> {
+    let metadata = read_artifact_metadata(&path, ArtifactKind::AggProvingKey)?;
+    bitcode::deserialize(&metadata).map_err(|e: bitcode::Error| read_error(&path, e.into()))
 }
 
 pub fn read_from_file_json<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T> {
@@ -151,12 +197,15 @@ pub fn write_to_file_bytes<T: Into<Vec<u8>>, P: AsRef<Path>>(path: P, data: T) -
     Ok(())
 }
 
+/// Reads a value encoded with [Encode]. Unlike the artifacts of [read_object_from_file], the file
+/// has no [ArtifactHeader], since the encoding is language independent and versions itself.
 pub fn decode_from_file<T: Decode, P: AsRef<Path>>(path: P) -> Result<T> {
     let reader = &mut File::open(path)?;
     let ret = T::decode(reader)?;
     Ok(ret)
 }
 
+/// Writes `data` encoded with [Encode], without an [ArtifactHeader], see [decode_from_file].
 pub fn encode_to_file<T: Encode, P: AsRef<Path>>(path: P, data: T) -> Result<()> {
     if let Some(parent) = path.as_ref().parent() {
         create_dir_all(parent)?;
@@ -166,6 +215,411 @@ pub fn encode_to_file<T: Encode, P: AsRef<Path>>(path: P, data: T) -> Result<()>
     Ok(())
 }
 
+/// Reads the header of the artifact at `path`, or returns `None` if the file has no header, such
+/// as files written before artifacts were versioned.
+pub fn read_artifact_header<P: AsRef<Path>>(path: P) -> Result<Option<ArtifactHeader>> {
+    let mut bytes = [0u8; ArtifactHeader::LEN];
+    let mut file = File::open(&path).map_err(|e| read_error(&path, e.into()))?;
+    match file.read_exact(&mut bytes) {
+        Ok(()) => ArtifactHeader::from_bytes(&bytes).map_err(|e| read_error(&path, e)),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(read_error(&path, e.into())),
+    }
+}
+
+/// Writes `metadata` and `payload` to `path` behind a header for the current OpenVM version. The
+/// metadata is prefixed with its length as a little-endian `u64`, so that it can be read without
+/// the payload, and is empty for most artifacts, see [Artifact::metadata_bytes].
+fn write_artifact<P: AsRef<Path>>(
+    path: P,
+    kind: ArtifactKind,
+    metadata: &[u8],
+    payload: &[u8],
+) -> Result<()> {
+    if let Some(parent) = path.as_ref().parent() {
+        create_dir_all(parent).map_err(|e| write_error(&path, e.into()))?;
+    }
+    File::create(&path)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            writer.write_all(&ArtifactHeader::current(kind).to_bytes())?;
+            writer.write_all(&(metadata.len() as u64).to_le_bytes())?;
+            writer.write_all(metadata)?;
+            writer.write_all(payload)?;
+            writer.flush()
+        })
+        .map_err(|e| write_error(&path, e.into()))?;
+    Ok(())
+}
+
+/// Reads the payload of the artifact of type `kind` at `path`, migrated to the current OpenVM
+/// version. The header is read first, the metadata is skipped and the payload is then read from
+/// the same file, so the payload is not copied. Files without a header are rejected, see
+/// [read_legacy_object_from_file].
+fn read_artifact<P: AsRef<Path>>(path: P, kind: ArtifactKind) -> Result<Vec<u8>> {
+    let (mut file, header, metadata_len) = open_artifact(&path, kind)?;
+    let mut payload = Vec::new();
+    file.seek(SeekFrom::Current(metadata_len as i64))
+        .and_then(|_| file.read_to_end(&mut payload))
+        .map_err(|e| read_error(&path, e.into()))?;
+    migrate(kind, header.version, payload).map_err(|e| read_error(&path, e))
+}
+
+/// Reads the metadata of the artifact of type `kind` at `path`, without reading its payload.
+/// Metadata is not migrated, so artifacts of other OpenVM versions are rejected.
+fn read_artifact_metadata<P: AsRef<Path>>(path: P, kind: ArtifactKind) -> Result<Vec<u8>> {
+    let (mut file, header, metadata_len) = open_artifact(&path, kind)?;
+    if header.version != ArtifactVersion::current() {
+        return Err(read_error(
+            &path,
+            eyre::eyre!(
+                "the metadata of the {kind} was written by openvm {}, but only metadata of openvm {} can be read",
+                header.version,
+                ArtifactVersion::current()
+            ),
+        ));
+    }
+    if metadata_len == 0 {
+        return Err(read_error(&path, eyre::eyre!("the {kind} has no metadata")));
+    }
+    let mut metadata = vec![0u8; metadata_len as usize];
+    file.read_exact(&mut metadata)
+        .map_err(|e| read_error(&path, e.into()))?;
+    Ok(metadata)
+}
+
+/// Opens the artifact of type `kind` at `path` and reads its header and the length of its
+/// metadata, leaving the file at the start of the metadata.
+fn open_artifact<P: AsRef<Path>>(
+    path: P,
+    kind: ArtifactKind,
+) -> Result<(File, ArtifactHeader, u64)> {
+    let mut file = File::open(&path).map_err(|e| read_error(&path, e.into()))?;
+    let mut header = [0u8; ArtifactHeader::LEN];
+    let header = match file.read_exact(&mut header) {
+        Ok(()) => ArtifactHeader::from_bytes(&header).map_err(|e| read_error(&path, e))?,
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => None,
+        Err(e) => return Err(read_error(&path, e.into())),
+    };
+    let Some(header) = header else {
+        return Err(read_error(
+            &path,
+            eyre::eyre!(
+                "the file has no artifact header; if it is a {kind} written by an openvm version before {}, read it with read_legacy_object_from_file",
+                ArtifactVersion::current()
+            ),
+        ));
+    };
+    if header.kind != kind {
+        return Err(read_error(
+            &path,
+            eyre::eyre!("expected a {kind}, but the file contains a {}", header.kind),
+        ));
+    }
+    let mut metadata_len = [0u8; 8];
+    file.read_exact(&mut metadata_len)
+        .map_err(|e| read_error(&path, e.into()))?;
+    Ok((file, header, u64::from_le_bytes(metadata_len)))
+}
+
+/// Upgrades the payload of an artifact of type `kind` written by OpenVM `version` to the current
+/// version, by applying the [MIGRATIONS] starting at `version` in order.
+fn migrate(
+    kind: ArtifactKind,
+    mut version: ArtifactVersion,
+    mut payload: Vec<u8>,
+) -> Result<Vec<u8>> {
+    let current = ArtifactVersion::current();
+    while version != current {
+        let Some(migration) = MIGRATIONS
+            .iter()
+            .find(|m| m.kind == kind && m.from == version)
+        else {
+            eyre::bail!(
+                "the {kind} was written by openvm {version}, which is incompatible with openvm {current}"
+            );
+        };
+        payload = (migration.apply)(payload)?;
+        version = migration.to;
+    }
+    Ok(payload)
+}
+
+/// Upgrade of the payload of one artifact type from one OpenVM version to the next one that
+/// changed its format.
+struct Migration {
+    kind: ArtifactKind,
+    from: ArtifactVersion,
+    to: ArtifactVersion,
+    apply: fn(Vec<u8>) -> Result<Vec<u8>>,
+}
+
+/// Migrations of artifacts whose format changed in a way that can be upgraded. Artifacts from
+/// other versions are rejected, since proofs and keys are only compatible within a minor version.
+const MIGRATIONS: &[Migration] = &[
+    // v1.4 removed `as_offset` from `MemoryDimensions` and fixed it to `ADDR_SPACE_OFFSET`, which
+    // changed the serialization of `AppVerifyingKey` but not the verifying key itself
+    Migration {
+        kind: ArtifactKind::AppVerifyingKey,
+        from: ArtifactVersion { major: 1, minor: 3 },
+        to: ArtifactVersion { major: 1, minor: 4 },
+        apply: legacy::migrate_app_vk_v1_3,
+    },
+];
+
+/// Formats of artifacts written by older OpenVM versions.
+mod legacy {
+    use eyre::Result;
+    use openvm_circuit::system::memory::dimensions::MemoryDimensions;
+    use openvm_stark_backend::keygen::types::MultiStarkVerifyingKey;
+    use openvm_stark_sdk::config::FriParameters;
+    use serde::Deserialize;
+
+    use crate::{keygen::AppVerifyingKey, SC};
+
+    /// `MemoryDimensions` of OpenVM v1.3.
+    #[derive(Deserialize)]
+    struct MemoryDimensionsV1_3 {
+        as_height: usize,
+        address_height: usize,
+        as_offset: u32,
+    }
+
+    /// `AppVerifyingKey` of OpenVM v1.3.
+    #[derive(Deserialize)]
+    struct AppVerifyingKeyV1_3 {
+        fri_params: FriParameters,
+        vk: MultiStarkVerifyingKey<SC>,
+        memory_dimensions: MemoryDimensionsV1_3,
+    }
+
+    pub(super) fn migrate_app_vk_v1_3(payload: Vec<u8>) -> Result<Vec<u8>> {
+        let old: AppVerifyingKeyV1_3 = bitcode::deserialize(&payload)?;
+        let MemoryDimensionsV1_3 {
+            as_height,
+            address_height,
+            as_offset,
+        } = old.memory_dimensions;
+        // Address spaces have started at 1 in every released config
+        if as_offset != 1 {
+            eyre::bail!("the app verifying key has address space offset {as_offset}, expected 1");
+        }
+        let app_vk = AppVerifyingKey {
+            fri_params: old.fri_params,
+            vk: old.vk,
+            memory_dimensions: MemoryDimensions::new(as_height, address_height),
+        };
+        Ok(bitcode::serialize(&app_vk)?)
+    }
+}
+
+/// Magic bytes at the start of every artifact written by this module.
+pub const ARTIFACT_MAGIC: [u8; 4] = *b"OVMA";
+/// Version of the layout of [ArtifactHeader] itself.
+const ARTIFACT_HEADER_FORMAT: u8 = 1;
+
+/// Header written before the metadata and payload of every artifact: [ARTIFACT_MAGIC], the header
+/// format, the [ArtifactKind] and the OpenVM version that wrote the artifact.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactHeader {
+    pub kind: ArtifactKind,
+    pub version: ArtifactVersion,
+}
+
+impl ArtifactHeader {
+    pub const LEN: usize = 10;
+
+    pub fn current(kind: ArtifactKind) -> Self {
+        Self {
+            kind,
+            version: ArtifactVersion::current(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[..4].copy_from_slice(&ARTIFACT_MAGIC);
+        bytes[4] = ARTIFACT_HEADER_FORMAT;
+        bytes[5] = self.kind as u8;
+        bytes[6..8].copy_from_slice(&self.version.major.to_le_bytes());
+        bytes[8..10].copy_from_slice(&self.version.minor.to_le_bytes());
+        bytes
+    }
+
+    /// Whether the artifact can be read by the current OpenVM version, possibly after migrating
+    /// it.
+    pub fn is_compatible(&self) -> bool {
+        let mut version = self.version;
+        while version != ArtifactVersion::current() {
+            match MIGRATIONS
+                .iter()
+                .find(|m| m.kind == self.kind && m.from == version)
+            {
+                Some(migration) => version = migration.to,
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Parses a header, or returns `None` if `bytes` does not start with [ARTIFACT_MAGIC].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Option<Self>> {
+        if bytes.len() < Self::LEN || bytes[..4] != ARTIFACT_MAGIC {
+            return Ok(None);
+        }
+        if bytes[4] != ARTIFACT_HEADER_FORMAT {
+            eyre::bail!("unsupported artifact header format {}", bytes[4]);
+        }
+        let kind = ArtifactKind::from_u8(bytes[5])
+            .ok_or_else(|| eyre::eyre!("unknown artifact type {}", bytes[5]))?;
+        let version = ArtifactVersion {
+            major: u16::from_le_bytes([bytes[6], bytes[7]]),
+            minor: u16::from_le_bytes([bytes[8], bytes[9]]),
+        };
+        Ok(Some(Self { kind, version }))
+    }
+}
+
+/// Major and minor OpenVM version. The formats of artifacts do not change on patch versions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl ArtifactVersion {
+    pub fn current() -> Self {
+        Self {
+            major: env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap(),
+            minor: env!("CARGO_PKG_VERSION_MINOR").parse().unwrap(),
+        }
+    }
+}
+
+impl Display for ArtifactVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "v{}.{}", self.major, self.minor)
+    }
+}
+
+/// Type of the payload of an artifact. Proofs written with [encode_to_file] are not artifacts,
+/// since their encoding versions itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum ArtifactKind {
+    AppProvingKey = 1,
+    AppVerifyingKey = 2,
+    AggProvingKey = 3,
+    AggVerifyingKey = 4,
+    Halo2ProvingKey = 5,
+    Exe = 6,
+    CommittedExe = 7,
+    SegmentProof = 8,
+    SegmentJob = 9,
+    StdIn = 10,
+}
+
+impl ArtifactKind {
+    const ALL: [Self; 10] = [
+        Self::AppProvingKey,
+        Self::AppVerifyingKey,
+        Self::AggProvingKey,
+        Self::AggVerifyingKey,
+        Self::Halo2ProvingKey,
+        Self::Exe,
+        Self::CommittedExe,
+        Self::SegmentProof,
+        Self::SegmentJob,
+        Self::StdIn,
+    ];
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| *kind as u8 == value)
+    }
+}
+
+impl Display for ArtifactKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::AppProvingKey => "app proving key",
+            Self::AppVerifyingKey => "app verifying key",
+            Self::AggProvingKey => "aggregation proving key",
+            Self::AggVerifyingKey => "aggregation verifying key",
+            Self::Halo2ProvingKey => "halo2 proving key",
+            Self::Exe => "executable",
+            Self::CommittedExe => "committed executable",
+            Self::SegmentProof => "segment proof",
+            Self::SegmentJob => "segment job",
+            Self::StdIn => "input",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Type which is written to files by this module, behind an [ArtifactHeader].
+pub trait Artifact {
+    const KIND: ArtifactKind;
+
+    /// Summary written before the payload, which can be read without deserializing the payload.
+    /// Only artifacts too large to deserialize just to inspect them have metadata.
+    fn metadata_bytes(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+}
+
+impl<T: Artifact> Artifact for &T {
+    const KIND: ArtifactKind = T::KIND;
+
+    fn metadata_bytes(&self) -> Result<Vec<u8>> {
+        (**self).metadata_bytes()
+    }
+}
+
+impl<VC> Artifact for AppProvingKey<VC> {
+    const KIND: ArtifactKind = ArtifactKind::AppProvingKey;
+}
+
+impl Artifact for AppVerifyingKey {
+    const KIND: ArtifactKind = ArtifactKind::AppVerifyingKey;
+}
+
+impl Artifact for AggProvingKey {
+    const KIND: ArtifactKind = ArtifactKind::AggProvingKey;
+
+    fn metadata_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bitcode::serialize(&self.metadata())?)
+    }
+}
+
+impl Artifact for AggVerifyingKey {
+    const KIND: ArtifactKind = ArtifactKind::AggVerifyingKey;
+}
+
+#[cfg(feature = "evm-prove")]
+impl Artifact for Halo2ProvingKey {
+    const KIND: ArtifactKind = ArtifactKind::Halo2ProvingKey;
+}
+
+impl Artifact for VmExe<F> {
+    const KIND: ArtifactKind = ArtifactKind::Exe;
+}
+
+impl Artifact for VmCommittedExe<SC> {
+    const KIND: ArtifactKind = ArtifactKind::CommittedExe;
+}
+
+impl Artifact for SegmentProof<SC> {
+    const KIND: ArtifactKind = ArtifactKind::SegmentProof;
+}
+
+impl Artifact for SegmentJob {
+    const KIND: ArtifactKind = ArtifactKind::SegmentJob;
+}
+
+impl Artifact for StdIn {
+    const KIND: ArtifactKind = ArtifactKind::StdIn;
+}
+
 fn read_error<P: AsRef<Path>>(path: P, error: Report) -> Report {
     eyre::eyre!(
         "reading from {} failed with the following error:\n    {}",
@@ -181,3 +635,51 @@ fn write_error<P: AsRef<Path>>(path: P, error: Report) -> Report {
         error,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_artifact_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("input");
+        let mut stdin = StdIn::default();
+        stdin.write(&42u32);
+        write_object_to_file(&path, &stdin).unwrap();
+        assert_eq!(
+            read_artifact_header(&path).unwrap(),
+            Some(ArtifactHeader::current(ArtifactKind::StdIn))
+        );
+        let read: StdIn = read_object_from_file(&path).unwrap();
+        assert_eq!(read.buffer, stdin.buffer);
+        // Only aggregation proving keys have metadata
+        assert!(read_artifact_metadata(&path, ArtifactKind::StdIn).is_err());
+        // Artifacts of another type are rejected
+        assert!(read_object_from_file::<VmExe<F>, _>(&path).is_err());
+
+        // Artifacts with a header are not legacy artifacts
+        assert!(
+            read_legacy_object_from_file::<StdIn, _>(&path, ArtifactVersion::current()).is_err()
+        );
+
+        // Headerless files are only read as legacy artifacts of an explicit version
+        write(&path, bitcode::serialize(&stdin).unwrap()).unwrap();
+        assert_eq!(read_artifact_header(&path).unwrap(), None);
+        assert!(read_object_from_file::<StdIn, _>(&path).is_err());
+        let read: StdIn = read_legacy_object_from_file(&path, ArtifactVersion::current()).unwrap();
+        assert_eq!(read.buffer, stdin.buffer);
+        let old = ArtifactVersion { major: 1, minor: 3 };
+        assert!(read_legacy_object_from_file::<StdIn, _>(&path, old).is_err());
+
+        // Artifacts of other versions without a migration are rejected
+        let mut header = ArtifactHeader::current(ArtifactKind::StdIn);
+        header.version.minor += 1;
+        assert!(!header.is_compatible());
+        let mut bytes = header.to_bytes().to_vec();
+        bytes.extend(0u64.to_le_bytes());
+        bytes.extend(bitcode::serialize(&stdin).unwrap());
+        write(&path, bytes).unwrap();
+        assert!(read_object_from_file::<StdIn, _>(&path).is_err());
+    }
+}
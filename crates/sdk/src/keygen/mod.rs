@@ -24,11 +24,12 @@ use openvm_stark_sdk::{
     engine::StarkFriEngine,
     openvm_stark_backend::{
         config::{Com, StarkGenericConfig},
-        keygen::types::MultiStarkVerifyingKey,
+        keygen::types::{MultiStarkProvingKey, MultiStarkVerifyingKey},
         proof::Proof,
     },
 };
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use tracing::{info_span, instrument};
 #[cfg(feature = "evm-prove")]
 use {
@@ -42,14 +43,14 @@ use {
 #[cfg(feature = "evm-prove")]
 use crate::config::Halo2Config;
 use crate::{
-    commit::VmCommittedExe,
-    config::{AggregationConfig, AppConfig},
+    commit::{CommitBytes, VmCommittedExe},
+    config::{AggregationConfig, AppConfig, FriPreset},
     keygen::{
         dummy::{compute_root_proof_heights, dummy_internal_proof_riscv_app_vm},
         perm::AirIdPermutation,
     },
     prover::vm::types::VmProvingKey,
-    util::check_max_constraint_degrees,
+    util::{check_max_constraint_degrees, sha256_fingerprint},
     RootSC, SC,
 };
 
@@ -62,6 +63,9 @@ pub mod static_verifier;
 /// This is lightweight to clone as it contains smart pointers to the proving keys.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AppProvingKey<VC> {
+    /// The FRI preset the key was generated with, which selects the FRI parameters of the
+    /// aggregation proofs of the app.
+    pub preset: Option<FriPreset>,
     /// The committed executable of the leaf verifier program that verifies proofs of the App VM
     /// circuit. The App VM circuit constraints are statically compiled into this executable.
     pub leaf_committed_exe: Arc<VmCommittedExe<SC>>,
@@ -100,6 +104,85 @@ pub struct AggVerifyingKey {
     pub(super) internal_verifier_program_commit: Com<SC>,
 }
 
+impl AppVerifyingKey {
+    /// SHA-256 of the key, which identifies the app config and OpenVM version it was generated
+    /// with without the key itself.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        sha256_fingerprint(self)
+    }
+}
+
+impl AggVerifyingKey {
+    /// Fingerprints of the components of the key, which identify the aggregation config and
+    /// OpenVM version it was generated with without the key itself.
+    pub fn fingerprint(&self) -> AggVkFingerprint {
+        let commit: [Val<SC>; DIGEST_SIZE] = self.internal_verifier_program_commit.into();
+        AggVkFingerprint {
+            leaf_vk: sha256_fingerprint(&(&self.leaf_fri_params, &self.leaf_vk)),
+            internal_vk: sha256_fingerprint(&(&self.internal_fri_params, &self.internal_vk)),
+            internal_verifier_program_commit: CommitBytes::from_u32_digest(
+                &commit.map(|x| x.as_canonical_u32()),
+            ),
+        }
+    }
+}
+
+/// Fingerprints of the components of an [AggVerifyingKey].
+#[serde_as]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggVkFingerprint {
+    /// SHA-256 of the leaf FRI parameters and verifying key.
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub leaf_vk: [u8; 32],
+    /// SHA-256 of the internal FRI parameters and verifying key.
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub internal_vk: [u8; 32],
+    pub internal_verifier_program_commit: CommitBytes,
+}
+
+/// Summary of an [AggProvingKey], which is written before the key so that the key can be
+/// inspected without deserializing it, see [crate::fs::read_agg_pk_metadata_from_file].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AggProvingKeyMetadata {
+    pub agg_vk_fingerprint: AggVkFingerprint,
+    pub leaf_fri_params: FriParameters,
+    pub internal_fri_params: FriParameters,
+    pub root_fri_params: FriParameters,
+    pub num_user_public_values: usize,
+    /// AIRs of the leaf, internal and root verifier circuits, in the order of their proving keys.
+    pub leaf_airs: Vec<AirMetadata>,
+    pub internal_airs: Vec<AirMetadata>,
+    pub root_airs: Vec<AirMetadata>,
+}
+
+/// Summary of one AIR of a proving key.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AirMetadata {
+    pub name: String,
+    pub main_width: usize,
+    /// Width of all traces of the AIR, counting extension field columns as base field columns.
+    pub total_width: usize,
+    pub num_interactions: usize,
+}
+
+impl AirMetadata {
+    fn from_pk<C: StarkGenericConfig>(pk: &MultiStarkProvingKey<C>) -> Vec<Self> {
+        pk.per_air
+            .iter()
+            .map(|air_pk| Self {
+                name: air_pk.air_name.clone(),
+                main_width: air_pk.vk.params.width.main_width(),
+                total_width: air_pk
+                    .vk
+                    .params
+                    .width
+                    .total_width(<C::Challenge as FieldExtensionAlgebra<Val<C>>>::D),
+                num_interactions: air_pk.vk.symbolic_constraints.interactions.len(),
+            })
+            .collect()
+    }
+}
+
 /// Attention: the serialized size of this struct is VERY large, usually >10GB.
 ///
 /// This is lightweight to clone as it contains smart pointers to the proving keys.
@@ -156,6 +239,7 @@ where
             ))
         };
         Ok(Self {
+            preset: config.preset,
             leaf_committed_exe,
             leaf_fri_params: config.leaf_fri_params.fri_params,
             app_vm_pk: Arc::new(app_vm_pk),
@@ -183,6 +267,12 @@ where
         self.leaf_committed_exe.get_program_commit()
     }
 
+    /// The leaf verifier program commit as the `app_vm_commit` of an
+    /// [AppExecutionCommit](crate::commit::AppExecutionCommit).
+    pub fn app_vm_commit(&self) -> CommitBytes {
+        CommitBytes::from_field_digest(&self.leaf_verifier_program_commit().into())
+    }
+
     pub fn app_fri_params(&self) -> FriParameters {
         self.app_vm_pk.fri_params
     }
@@ -193,6 +283,7 @@ where
 
     pub fn app_config(&self) -> AppConfig<VC> {
         AppConfig {
+            preset: self.preset,
             app_fri_params: self.app_fri_params().into(),
             app_vm_config: self.vm_config().clone(),
             leaf_fri_params: self.leaf_fri_params.into(),
@@ -441,6 +532,19 @@ impl AggProvingKey {
             .num_public_values
             - (2 * DIGEST_SIZE)
     }
+
+    pub fn metadata(&self) -> AggProvingKeyMetadata {
+        AggProvingKeyMetadata {
+            agg_vk_fingerprint: self.get_agg_vk().fingerprint(),
+            leaf_fri_params: self.leaf_vm_pk.fri_params,
+            internal_fri_params: self.internal_vm_pk.fri_params,
+            root_fri_params: self.root_verifier_pk.vm_pk.fri_params,
+            num_user_public_values: self.num_user_public_values(),
+            leaf_airs: AirMetadata::from_pk(&self.leaf_vm_pk.vm_pk),
+            internal_airs: AirMetadata::from_pk(&self.internal_vm_pk.vm_pk),
+            root_airs: AirMetadata::from_pk(&self.root_verifier_pk.vm_pk.vm_pk),
+        }
+    }
 }
 
 /// Proving key for the root verifier.
@@ -24,11 +24,11 @@ use openvm_build::{
 };
 use openvm_circuit::{
     arch::{
-        execution_mode::Segment,
+        execution_mode::{ProfileCtx, Segment},
         hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
-        instructions::exe::VmExe,
+        instructions::exe::{FnBounds, VmExe},
         Executor, InitFileGenerator, MeteredExecutor, PreflightExecutor, VirtualMachineError,
-        VmBuilder, VmExecutionConfig, VmExecutor, VmVerificationError, CONNECTOR_AIR_ID,
+        VmBuilder, VmExecutionConfig, VmExecutor, VmState, VmVerificationError, CONNECTOR_AIR_ID,
         PROGRAM_AIR_ID, PROGRAM_CACHED_TRACE_INDEX, PUBLIC_VALUES_AIR_ID,
     },
     system::{
@@ -63,15 +63,16 @@ use openvm_transpiler::{
 #[cfg(feature = "evm-verify")]
 use snark_verifier_sdk::{evm::gen_evm_verifier_sol_code, halo2::aggregation::AggregationCircuit};
 
-#[cfg(feature = "evm-prove")]
-use crate::{
-    config::Halo2Config, keygen::Halo2ProvingKey, prover::EvmHalo2Prover, types::EvmProof,
-};
 use crate::{
+    codec::Encode,
     config::{AggregationConfig, SdkVmConfig, SdkVmCpuBuilder, TranspilerConfig},
     keygen::{asm::program_to_asm, AggProvingKey, AggVerifyingKey},
-    prover::{AppProver, StarkProver},
-    types::ExecutableFormat,
+    prover::{AggStarkProver, AppProver, SegmentJob, StarkProver},
+    types::{ExecutableFormat, SegmentationPlan, VmStarkProofBundle},
+};
+#[cfg(feature = "evm-prove")]
+use crate::{
+    config::Halo2Config, keygen::Halo2ProvingKey, prover::EvmHalo2Prover, types::EvmProof,
 };
 
 cfg_if::cfg_if! {
@@ -96,6 +97,7 @@ cfg_if::cfg_if! {
 
 pub mod codec;
 pub mod commit;
+pub mod compose;
 pub mod config;
 pub mod fs;
 pub mod keygen;
@@ -241,11 +243,16 @@ where
 
     /// **Note**: This function does not set the transpiler, which must be done separately to
     /// support RISC-V ELFs.
+    ///
+    /// If `app_config` selects a [FriPreset](config::FriPreset), the FRI parameters of all proofs
+    /// and the aggregation tree are those of the preset, and explicit app or leaf FRI parameters
+    /// which conflict with the preset are an error, see [AppConfig::with_selected_preset].
     pub fn new_without_transpiler(app_config: AppConfig<VB::VmConfig>) -> Result<Self, SdkError>
     where
         VB: Default,
         NativeBuilder: Default,
     {
+        let app_config = app_config.with_selected_preset()?;
         let system_config = app_config.app_vm_config.as_ref();
         let profiling = system_config.profiling;
         let compiler_options = CompilerOptions {
@@ -254,13 +261,18 @@ where
         };
         let executor = VmExecutor::new(app_config.app_vm_config.clone())
             .map_err(|e| SdkError::Vm(e.into()))?;
-        let agg_config = AggregationConfig {
+        let mut agg_config = AggregationConfig {
             max_num_user_public_values: system_config.num_public_values,
-            leaf_fri_params: app_config.leaf_fri_params.fri_params,
             profiling,
             compiler_options,
             ..Default::default()
         };
+        let mut agg_tree_config = AggregationTreeConfig::default();
+        if let Some(preset) = app_config.preset {
+            agg_config = agg_config.with_preset(preset);
+            agg_tree_config = preset.agg_tree_config();
+        }
+        agg_config.leaf_fri_params = app_config.leaf_fri_params.fri_params;
         #[cfg(feature = "evm-prove")]
         let halo2_config = Halo2Config {
             profiling,
@@ -271,7 +283,7 @@ where
             agg_config,
             #[cfg(feature = "evm-prove")]
             halo2_config,
-            agg_tree_config: Default::default(),
+            agg_tree_config,
             app_vm_builder: Default::default(),
             native_builder: Default::default(),
             transpiler: None,
@@ -409,6 +421,90 @@ where
         Ok((public_values, segments))
     }
 
+    /// Executes with segmentation like [`execute_metered`](Self::execute_metered), and returns the
+    /// segments as a [SegmentationPlan] whose trace heights are labeled by air names. The jobs to
+    /// prove the segments are created with [`for_each_segment_job`](Self::for_each_segment_job).
+    pub fn segmentation_plan(
+        &self,
+        app_exe: impl Into<ExecutableFormat>,
+        inputs: StdIn,
+    ) -> Result<(Vec<u8>, SegmentationPlan), SdkError> {
+        let (public_values, segments) = self.execute_metered(app_exe, inputs)?;
+        let air_names = self
+            .app_pk()
+            .app_vm_pk
+            .vm_pk
+            .per_air
+            .iter()
+            .map(|pk| pk.air_name.clone())
+            .collect();
+        Ok((
+            public_values,
+            SegmentationPlan {
+                air_names,
+                segments,
+            },
+        ))
+    }
+
+    /// Creates the job to prove every segment of `segments`, as computed by
+    /// [`segmentation_plan`](Self::segmentation_plan), and calls `f` with each job as soon as the
+    /// start state of its segment is encoded, so that only one start state is held in memory.
+    /// This fails if the `kv_store` backend of `inputs` cannot list its keys.
+    pub fn for_each_segment_job(
+        &self,
+        app_exe: impl Into<ExecutableFormat>,
+        inputs: StdIn,
+        segments: &[Segment],
+        mut f: impl FnMut(SegmentJob) -> Result<(), SdkError>,
+    ) -> Result<(), SdkError> {
+        self.for_each_segment_state(app_exe, inputs, segments, |segment_idx, state| {
+            f(SegmentJob {
+                segment_idx,
+                segment: segments[segment_idx].clone(),
+                start_state: state.encode_to_vec()?,
+            })
+        })
+    }
+
+    /// Executes `segments`, as computed by [`execute_metered`](Self::execute_metered), by pure
+    /// execution and calls `f` with the index and the VM state at the start of each segment. The
+    /// states can be encoded as checkpoints with [Encode](codec::Encode), from which segments are
+    /// proven or execution is resumed without re-executing the program up to them. Only the VM
+    /// executor is used, so no proving key is needed.
+    pub fn for_each_segment_state(
+        &self,
+        app_exe: impl Into<ExecutableFormat>,
+        inputs: StdIn,
+        segments: &[Segment],
+        mut f: impl FnMut(usize, &VmState<F>) -> Result<(), SdkError>,
+    ) -> Result<(), SdkError> {
+        let exe = self.convert_to_exe(app_exe)?;
+        let instance = self
+            .executor
+            .instance(&exe)
+            .map_err(VirtualMachineError::from)?;
+        let mut state = instance.create_initial_vm_state(inputs);
+        for (segment_idx, segment) in segments.iter().enumerate() {
+            let num_insns = segment
+                .instret_start
+                .checked_sub(state.instret())
+                .ok_or_else(|| {
+                    SdkError::Other(eyre::eyre!(
+                        "segment {segment_idx} starts at instret {}, before the end of the \
+                         previous segment at {}",
+                        segment.instret_start,
+                        state.instret()
+                    ))
+                })?;
+            state = instance
+                .execute_from_state(state, Some(num_insns))
+                .map_err(VirtualMachineError::from)?;
+            f(segment_idx, &state)?;
+        }
+        Ok(())
+    }
+
     /// Executes with cost metering to measure computational cost in trace cells.
     /// Returns both user public values, and cost along with instruction count.
     pub fn execute_metered_cost(
@@ -439,6 +535,44 @@ where
         Ok((public_values, (cost, instret)))
     }
 
+    /// Executes while profiling the executed instructions by call stack, where `fn_bounds` are
+    /// the guest functions to profile and the cycle tracker spans of the program are also tracked.
+    /// If `profile_cells` is true, the trace cells and interactions generated per air are also
+    /// profiled, which requires the app proving key.
+    /// Returns both user public values and the profile.
+    pub fn execute_profile(
+        &self,
+        app_exe: impl Into<ExecutableFormat>,
+        inputs: StdIn,
+        fn_bounds: FnBounds,
+        profile_cells: bool,
+    ) -> Result<(Vec<u8>, ProfileCtx), SdkError> {
+        let (profile, final_state) = if profile_cells {
+            let app_prover = self.app_prover(app_exe)?;
+            let vm = app_prover.vm();
+            let exe = app_prover.exe();
+            let ctx = vm.build_profile_ctx(fn_bounds).with_spans(&exe.program);
+            vm.metered_profile_interpreter(&exe)
+                .map_err(VirtualMachineError::from)?
+                .execute_profile(inputs, ctx)
+        } else {
+            let exe = self.convert_to_exe(app_exe)?;
+            let ctx = ProfileCtx::new(fn_bounds).with_spans(&exe.program);
+            self.executor
+                .profile_instance(&exe)
+                .map_err(VirtualMachineError::from)?
+                .execute_profile(inputs, ctx)
+        }
+        .map_err(VirtualMachineError::from)?;
+
+        let public_values = extract_public_values(
+            self.executor.config.as_ref().num_public_values,
+            &final_state.memory.memory,
+        );
+
+        Ok((public_values, profile))
+    }
+
     // ======================== Proving Methods ============================
 
     /// Generates a single aggregate STARK proof of the full program execution of the given
@@ -468,6 +602,31 @@ where
         Ok((proof, app_commit))
     }
 
+    /// Generates a STARK proof of a parent program whose guest verifies the STARK proofs of
+    /// `children` with `verify_openvm_stark`, defined with the ASM file `asm_filename`, which is
+    /// generated by [`generate_root_verifier_asm`](Self::generate_root_verifier_asm).
+    ///
+    /// Every child is added to `inputs` after the existing inputs with
+    /// [`StdIn::add_child_proof`]. Since the parent proof only binds the children through its
+    /// public values, the guest must reveal the commits of every child it verifies at the offset
+    /// [`child_commit_offset`](compose::child_commit_offset) of the child, which is checked with
+    /// [`check_child_commits`](compose::check_child_commits) after proving.
+    pub fn prove_composed(
+        &self,
+        app_exe: impl Into<ExecutableFormat>,
+        mut inputs: StdIn,
+        asm_filename: &str,
+        children: &[compose::ChildProof],
+    ) -> Result<(VmStarkProof<SC>, AppExecutionCommit), SdkError> {
+        for child in children {
+            inputs.add_child_proof(asm_filename, child)?;
+        }
+        let (proof, app_commit) = self.prove(app_exe, inputs)?;
+        let child_commits: Vec<_> = children.iter().map(|child| child.app_commit).collect();
+        compose::check_child_commits(&proof.user_public_values, &child_commits)?;
+        Ok((proof, app_commit))
+    }
+
     #[cfg(feature = "evm-prove")]
     pub fn prove_evm(
         &self,
@@ -503,6 +662,18 @@ where
         Ok(stark_prover)
     }
 
+    /// Creates a prover of the aggregation tree of app proofs, which can prove the tree one node at
+    /// a time.
+    pub fn agg_prover(&self) -> Result<AggStarkProver<E, NativeBuilder>, SdkError> {
+        let agg_prover = AggStarkProver::new(
+            self.native_builder.clone(),
+            self.agg_pk(),
+            self.app_pk().leaf_committed_exe.exe.clone(),
+            self.agg_tree_config,
+        )?;
+        Ok(agg_prover)
+    }
+
     #[cfg(feature = "evm-prove")]
     pub fn evm_prover(
         &self,
@@ -701,6 +872,21 @@ where
 
     // ======================== Verification Methods ========================
 
+    /// Verifies a [VmStarkProofBundle]. The components of the bundle are checked against
+    /// `agg_vk`, the expected app commit and, if given, the expected VM config hash with
+    /// [check_components](VmStarkProofBundle::check_components), and then the proof is verified
+    /// against the expected app commit. The app commit in the bundle is never trusted on its own.
+    pub fn verify_proof_bundle(
+        agg_vk: &AggVerifyingKey,
+        bundle: &VmStarkProofBundle,
+        expected_app_commit: &AppExecutionCommit,
+        expected_vm_config_hash: Option<&[u8; 32]>,
+    ) -> Result<(), SdkError> {
+        bundle.check_components(agg_vk, expected_app_commit, expected_vm_config_hash)?;
+        let proof = bundle.clone().try_into()?;
+        Self::verify_proof(agg_vk, *expected_app_commit, &proof)
+    }
+
     /// Verifies aggregate STARK proof of VM execution.
     ///
     /// **Note**: This function does not have any reliance on `self` and does not depend on the app
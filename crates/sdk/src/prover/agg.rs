@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc};
 
 use openvm_circuit::arch::{
     instructions::exe::VmExe, ContinuationVmProof, PreflightExecutor, SingleSegmentVmProver,
@@ -13,11 +13,12 @@ use openvm_continuations::verifier::{
 use openvm_native_circuit::{NativeConfig, NATIVE_MAX_TRACE_HEIGHTS};
 use openvm_native_recursion::hints::Hintable;
 use openvm_stark_sdk::{engine::StarkFriEngine, openvm_stark_backend::proof::Proof};
+use serde::{Deserialize, Serialize};
 use tracing::{info_span, instrument};
 
 use crate::{
     config::AggregationTreeConfig, keygen::AggProvingKey, prover::vm::new_local_prover,
-    util::check_max_constraint_degrees, F, SC,
+    util::check_max_constraint_degrees, SdkError, F, SC,
 };
 #[cfg(feature = "evm-prove")]
 use crate::{prover::RootVerifierLocalProver, RootSC};
@@ -146,6 +147,70 @@ where
         Ok(wrapped_stark_proof)
     }
 
+    /// Generates the proof of the leaf node with index `leaf_idx` of the aggregation tree, which
+    /// is one of the leaf proofs returned by [generate_leaf_proofs](Self::generate_leaf_proofs).
+    /// Returns an error if `leaf_idx` is not less than the number of leaves of the tree.
+    #[instrument(name = "agg_layer", skip_all, fields(group = "leaf"))]
+    pub fn generate_leaf_proof(
+        &mut self,
+        app_proofs: &ContinuationVmProof<SC>,
+        leaf_idx: usize,
+    ) -> Result<Proof<SC>, SdkError> {
+        check_max_constraint_degrees(
+            self.leaf_prover.vm.config().as_ref(),
+            &self.leaf_prover.vm.engine.fri_params(),
+        );
+        let num_children = self.leaf_controller.num_children;
+        let input =
+            LeafVmVerifierInput::continuation_vm_proof_chunk(app_proofs, num_children, leaf_idx)
+                .ok_or_else(|| {
+                    SdkError::Other(eyre::eyre!(
+                        "leaf {leaf_idx} is out of bounds for an app proof of {} segments with {num_children} segments per leaf",
+                        app_proofs.per_segment.len()
+                    ))
+                })?;
+        let proof = info_span!("single_leaf_agg", idx = leaf_idx).in_scope(|| {
+            SingleSegmentVmProver::prove(
+                &mut self.leaf_prover,
+                input.write_to_stream(),
+                NATIVE_MAX_TRACE_HEIGHTS,
+            )
+        })?;
+        Ok(proof)
+    }
+
+    /// Generates the proof of an internal node of the aggregation tree from the proofs of its
+    /// children, which are given by [AggregationTreeShape::children]. Together with
+    /// [generate_leaf_proof](Self::generate_leaf_proof), this proves the same tree as
+    /// [aggregate_leaf_proofs](Self::aggregate_leaf_proofs) one node at a time.
+    pub fn generate_internal_proof(
+        &mut self,
+        children: Vec<Proof<SC>>,
+    ) -> Result<Proof<SC>, VirtualMachineError> {
+        check_max_constraint_degrees(
+            self.internal_prover.vm.config().as_ref(),
+            &self.internal_prover.vm.engine.fri_params(),
+        );
+        let input = InternalVmVerifierInput {
+            self_program_commit: (*self.internal_prover.program_commitment()).into(),
+            proofs: children,
+        };
+        SingleSegmentVmProver::prove(
+            &mut self.internal_prover,
+            input.write(),
+            NATIVE_MAX_TRACE_HEIGHTS,
+        )
+    }
+
+    /// Shape of the aggregation tree of an app proof with `num_segments` segments.
+    pub fn tree_shape(&self, num_segments: usize) -> AggregationTreeShape {
+        AggregationTreeShape::new(
+            num_segments,
+            self.leaf_controller.num_children,
+            self.num_children_internal,
+        )
+    }
+
     pub fn aggregate_leaf_proofs(
         &mut self,
         leaf_proofs: Vec<Proof<SC>>,
@@ -280,6 +345,61 @@ where
     }
 }
 
+/// Number of nodes in each layer of the aggregation tree built by [AggStarkProver]. A leaf node
+/// aggregates consecutive app segment proofs and an internal node aggregates consecutive proofs of
+/// the layer below it, where the internal layer of height 0 aggregates leaf proofs. The last
+/// internal layer has a single node, whose proof is the inner proof of the e2e
+/// [VmStarkProof].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregationTreeShape {
+    pub num_segments: usize,
+    pub num_leaves: usize,
+    /// Number of nodes in each internal layer, by height.
+    pub internal_layers: Vec<usize>,
+    pub num_children_leaf: usize,
+    pub num_children_internal: usize,
+}
+
+impl AggregationTreeShape {
+    pub fn new(
+        num_segments: usize,
+        num_children_leaf: usize,
+        num_children_internal: usize,
+    ) -> Self {
+        let num_leaves = num_segments.div_ceil(num_children_leaf);
+        let mut internal_layers = Vec::new();
+        let mut num_nodes = num_leaves;
+        // There is always at least one internal layer, see `aggregate_leaf_proofs`
+        while num_nodes > 1 || internal_layers.is_empty() {
+            num_nodes = num_nodes.div_ceil(num_children_internal);
+            internal_layers.push(num_nodes);
+        }
+        Self {
+            num_segments,
+            num_leaves,
+            internal_layers,
+            num_children_leaf,
+            num_children_internal,
+        }
+    }
+
+    /// Height of the last internal layer, whose single node is the top of the tree.
+    pub fn top_height(&self) -> usize {
+        self.internal_layers.len() - 1
+    }
+
+    /// Indices of the children of node `idx` of the internal layer at `height`, in the layer
+    /// below it.
+    pub fn children(&self, height: usize, idx: usize) -> Range<usize> {
+        let num_below = match height {
+            0 => self.num_leaves,
+            _ => self.internal_layers[height - 1],
+        };
+        let start = idx * self.num_children_internal;
+        start..num_below.min(start + self.num_children_internal)
+    }
+}
+
 impl LeafProvingController {
     pub fn with_num_children(mut self, num_children_leaf: usize) -> Self {
         self.num_children = num_children_leaf;
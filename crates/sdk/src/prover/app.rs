@@ -6,31 +6,36 @@ use getset::Getters;
 use itertools::Itertools;
 use openvm_circuit::{
     arch::{
+        execution_mode::Segment,
         hasher::poseidon2::{vm_poseidon2_hasher, Poseidon2Hasher},
         instructions::exe::VmExe,
         verify_segments, ContinuationVmProof, ContinuationVmProver, Executor, MeteredExecutor,
         PreflightExecutor, VerifiedExecutionPayload, VirtualMachine, VirtualMachineError,
-        VmBuilder, VmExecutionConfig, VmInstance, VmVerificationError,
+        VmBuilder, VmExecutionConfig, VmInstance, VmState, VmVerificationError,
     },
-    system::memory::CHUNK,
+    system::memory::{merkle::public_values::UserPublicValuesProof, CHUNK},
 };
 use openvm_stark_backend::{
-    config::{Com, Val},
+    config::{Com, StarkGenericConfig, Val},
     keygen::types::MultiStarkVerifyingKey,
     p3_field::PrimeField32,
+    proof::Proof,
 };
 use openvm_stark_sdk::{
     config::baby_bear_poseidon2::BabyBearPoseidon2Engine,
     engine::{StarkEngine, StarkFriEngine},
 };
+use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
 use crate::{
+    codec::Decode,
     commit::{AppExecutionCommit, CommitBytes},
     keygen::AppVerifyingKey,
     prover::vm::{new_local_prover, types::VmProvingKey},
+    types::SegmentationPlan,
     util::check_max_constraint_degrees,
-    StdIn, F, SC,
+    SdkError, StdIn, F, SC,
 };
 
 #[derive(Getters)]
@@ -182,6 +187,53 @@ where
         Ok(proofs)
     }
 
+    /// Generates the proof of a single continuation segment, so that segments can be proven by
+    /// different provers. Proving resumes from the start state of the segment stored in the job,
+    /// so the program is not executed up to the segment.
+    #[instrument(name = "prove_segment", skip_all, fields(segment = job.segment_idx))]
+    pub fn prove_segment(&mut self, job: SegmentJob) -> Result<SegmentProof<E::SC>, SdkError>
+    where
+        <VB::VmConfig as VmExecutionConfig<Val<E::SC>>>::Executor:
+            Executor<Val<E::SC>> + PreflightExecutor<Val<E::SC>, VB::RecordArena>,
+        VmState<Val<E::SC>>: Decode,
+    {
+        assert!(self.vm_config().as_ref().continuation_enabled);
+        let SegmentJob {
+            segment_idx,
+            segment,
+            start_state,
+        } = job;
+        let state = VmState::<Val<E::SC>>::decode_from_bytes(&start_state)?;
+        if state.instret() != segment.instret_start {
+            return Err(SdkError::Other(eyre::eyre!(
+                "start state of segment {segment_idx} is at instret {}, expected {}",
+                state.instret(),
+                segment.instret_start
+            )));
+        }
+        let instance = &mut self.instance;
+        let vm = &mut instance.vm;
+        let (proof, final_memory) = vm.prove(
+            &mut instance.interpreter,
+            state,
+            Some(segment.num_insns),
+            &segment.trace_heights,
+        )?;
+        let user_public_values = final_memory.map(|memory| {
+            UserPublicValuesProof::compute(
+                vm.config().as_ref().memory_config.memory_dimensions(),
+                vm.config().as_ref().num_public_values,
+                &vm_poseidon2_hasher(),
+                &memory.memory,
+            )
+        });
+        Ok(SegmentProof {
+            segment_idx,
+            proof,
+            user_public_values,
+        })
+    }
+
     /// App Exe
     pub fn exe(&self) -> Arc<VmExe<Val<E::SC>>> {
         self.instance.exe().clone()
@@ -198,6 +250,72 @@ where
     }
 }
 
+/// Job to prove a single continuation segment with [AppProver::prove_segment].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SegmentJob {
+    pub segment_idx: usize,
+    pub segment: Segment,
+    /// VM state at the start of the segment, encoded with [Encode](crate::codec::Encode). It
+    /// contains the remaining input streams of the execution.
+    pub start_state: Vec<u8>,
+}
+
+/// Proof of a single continuation segment generated by [AppProver::prove_segment].
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "Com<SC>: Serialize",
+    deserialize = "Com<SC>: Deserialize<'de>"
+))]
+pub struct SegmentProof<SC: StarkGenericConfig> {
+    pub segment_idx: usize,
+    pub proof: Proof<SC>,
+    /// Proof of the user public values, only present for the terminal segment of a successful
+    /// execution.
+    pub user_public_values: Option<UserPublicValuesProof<{ CHUNK }, Val<SC>>>,
+}
+
+/// Assembles the proofs of all segments of an execution, in any order, into a
+/// [ContinuationVmProof].
+pub fn stitch_segment_proofs<SC: StarkGenericConfig>(
+    mut proofs: Vec<SegmentProof<SC>>,
+) -> Result<ContinuationVmProof<SC>, SdkError> {
+    proofs.sort_by_key(|proof| proof.segment_idx);
+    if let Some((idx, _)) = proofs
+        .iter()
+        .enumerate()
+        .find(|(idx, proof)| proof.segment_idx != *idx)
+    {
+        return Err(SdkError::Other(eyre::eyre!(
+            "missing or duplicate proof of segment {idx}"
+        )));
+    }
+    let num_segments = proofs.len();
+    let mut per_segment = Vec::with_capacity(num_segments);
+    let mut user_public_values = None;
+    for proof in proofs {
+        match proof.user_public_values {
+            Some(pvs) if proof.segment_idx + 1 == num_segments => user_public_values = Some(pvs),
+            Some(_) => {
+                return Err(SdkError::Other(eyre::eyre!(
+                    "segment {} is terminal, but is not the last of {num_segments} segments",
+                    proof.segment_idx
+                )))
+            }
+            None => {}
+        }
+        per_segment.push(proof.proof);
+    }
+    let user_public_values = user_public_values.ok_or_else(|| {
+        SdkError::Other(eyre::eyre!(
+            "the last of {num_segments} segments is not terminal"
+        ))
+    })?;
+    Ok(ContinuationVmProof {
+        per_segment,
+        user_public_values,
+    })
+}
+
 /// The payload of a verified guest VM execution with user public values extracted and
 /// verified.
 pub struct VerifiedAppArtifacts {
@@ -1,16 +1,27 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{BTreeMap, HashMap, VecDeque},
     sync::Arc,
 };
 
 use openvm_circuit::arch::Streams;
+pub use openvm_circuit::arch::{ChainedKvStore, DirKvStore, FnKvStore, KvStore, MmapKvStore};
 use openvm_stark_backend::p3_field::Field;
-use serde::{Deserialize, Serialize};
+use serde::{ser::Error as _, Deserialize, Serialize, Serializer};
 
-#[derive(Clone, Default, Serialize, Deserialize)]
+/// Serializing a `StdIn` writes the entries of the `kv_backends` into the `kv_store`, and fails if
+/// a backend cannot list its keys, such as a [FnKvStore].
+#[derive(Clone, Default, Deserialize)]
 pub struct StdIn<F = crate::F> {
     pub buffer: VecDeque<Vec<F>>,
     pub kv_store: HashMap<Vec<u8>, Vec<u8>>,
+    /// Key-value store backends which are looked up in order for keys that are not in
+    /// `kv_store`.
+    #[serde(skip)]
+    pub kv_backends: Vec<Arc<dyn KvStore>>,
+    /// Bytes read by the guest from each file descriptor with `sys_read`, for example through
+    /// `std::io::stdin()`.
+    #[serde(default)]
+    pub fd_buffers: BTreeMap<u32, Vec<u8>>,
 }
 
 impl<F: Field> StdIn<F> {
@@ -41,6 +52,53 @@ impl<F: Field> StdIn<F> {
     pub fn add_key_value(&mut self, key: Vec<u8>, value: Vec<u8>) {
         self.kv_store.insert(key, value);
     }
+
+    /// Adds a key-value store backend, such as a [DirKvStore] or [MmapKvStore], to look up keys
+    /// that are not in `kv_store` lazily during execution.
+    pub fn add_kv_backend(&mut self, backend: impl KvStore + 'static) {
+        self.kv_backends.push(Arc::new(backend));
+    }
+
+    /// Appends `data` to the bytes the guest reads from file descriptor `fd`.
+    pub fn write_fd(&mut self, fd: u32, data: &[u8]) {
+        self.fd_buffers
+            .entry(fd)
+            .or_default()
+            .extend_from_slice(data);
+    }
+
+    /// Appends `data` to the bytes the guest reads from its stdin.
+    pub fn write_stdin(&mut self, data: &[u8]) {
+        self.write_fd(openvm::platform::fileno::STDIN, data);
+    }
+}
+
+/// Serialized form of [StdIn], with the entries of the backends merged into the `kv_store`.
+#[derive(Serialize)]
+struct StdInRef<'a, F> {
+    buffer: &'a VecDeque<Vec<F>>,
+    kv_store: HashMap<Vec<u8>, Vec<u8>>,
+    fd_buffers: &'a BTreeMap<u32, Vec<u8>>,
+}
+
+impl<F: Serialize> Serialize for StdIn<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut kv_store = HashMap::new();
+        // Earlier stores take precedence, as in lookups
+        for backend in self.kv_backends.iter().rev() {
+            let entries = backend.entries().ok_or_else(|| {
+                S::Error::custom("kv_store backend cannot list its keys and cannot be serialized")
+            })?;
+            kv_store.extend(entries);
+        }
+        kv_store.extend(self.kv_store.clone());
+        StdInRef {
+            buffer: &self.buffer,
+            kv_store,
+            fd_buffers: &self.fd_buffers,
+        }
+        .serialize(serializer)
+    }
 }
 
 impl<F: Field> From<StdIn<F>> for Streams<F> {
@@ -50,7 +108,18 @@ impl<F: Field> From<StdIn<F>> for Streams<F> {
             data.push(input);
         }
         let mut ret = Streams::new(data);
-        ret.kv_store = Arc::new(std_in.kv_store);
+        ret.kv_store = if std_in.kv_backends.is_empty() {
+            Arc::new(std_in.kv_store)
+        } else {
+            let mut stores: Vec<Arc<dyn KvStore>> = vec![Arc::new(std_in.kv_store)];
+            stores.extend(std_in.kv_backends);
+            Arc::new(ChainedKvStore(stores))
+        };
+        ret.fd_buffers = std_in
+            .fd_buffers
+            .into_iter()
+            .map(|(fd, data)| (fd, data.into()))
+            .collect();
         ret
     }
 }
@@ -64,3 +133,30 @@ impl<F: Field> From<Vec<Vec<F>>> for StdIn<F> {
         ret
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_kv_backends() {
+        let mut stdin = StdIn::<crate::F>::default();
+        stdin.add_key_value(b"key".to_vec(), b"from map".to_vec());
+        stdin.add_kv_backend(HashMap::from([
+            (b"key".to_vec(), b"from backend".to_vec()),
+            (b"other".to_vec(), b"other".to_vec()),
+        ]));
+        let decoded: StdIn = serde_json::from_str(&serde_json::to_string(&stdin).unwrap()).unwrap();
+        assert!(decoded.kv_backends.is_empty());
+        assert_eq!(
+            decoded.kv_store,
+            HashMap::from([
+                (b"key".to_vec(), b"from map".to_vec()),
+                (b"other".to_vec(), b"other".to_vec()),
+            ])
+        );
+
+        stdin.add_kv_backend(FnKvStore(|key: &[u8]| Some(key.to_vec())));
+        assert!(serde_json::to_string(&stdin).is_err());
+    }
+}
@@ -3,12 +3,13 @@ use std::{io::Cursor, sync::Arc};
 use derive_more::derive::From;
 use eyre::Result;
 use openvm::platform::memory::MEM_SIZE;
-use openvm_circuit::arch::instructions::exe::VmExe;
+use openvm_circuit::arch::{execution_mode::Segment, instructions::exe::VmExe};
 use openvm_continuations::{verifier::internal::types::VmStarkProof, SC};
 use openvm_stark_backend::proof::Proof;
 use openvm_transpiler::elf::Elf;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
+use sha2::{Digest, Sha256};
 #[cfg(feature = "evm-prove")]
 use {
     crate::commit::{AppExecutionCommit, CommitBytes},
@@ -20,7 +21,9 @@ use {
 
 use crate::{
     codec::{decode_vec, encode_slice, Decode, Encode},
-    OPENVM_VERSION,
+    commit::AppExecutionCommit,
+    keygen::{AggVerifyingKey, AggVkFingerprint},
+    SdkError, OPENVM_VERSION,
 };
 
 /// Number of bytes in a Bn254Fr.
@@ -50,6 +53,15 @@ impl From<Vec<u8>> for ExecutableFormat {
     }
 }
 
+/// Segmentation of an execution computed by metered execution, from which every segment can be
+/// proven independently.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SegmentationPlan {
+    /// Names of the airs that the trace heights of the segments are indexed by.
+    pub air_names: Vec<String>,
+    pub segments: Vec<Segment>,
+}
+
 #[cfg(feature = "evm-prove")]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EvmHalo2Verifier {
@@ -269,14 +281,24 @@ impl VersionedVmStarkProof {
     }
 }
 
+/// Decodes the proof, which fails if it was not generated by the current OpenVM major and minor
+/// version, since proofs are only compatible within a minor version.
 impl TryFrom<VersionedVmStarkProof> for VmStarkProof<SC> {
     type Error = std::io::Error;
     fn try_from(proof: VersionedVmStarkProof) -> Result<Self, std::io::Error> {
         let VersionedVmStarkProof {
+            version,
             proof,
             user_public_values,
-            ..
         } = proof;
+        if version != format!("v{OPENVM_VERSION}") {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "the proof was generated by openvm {version}, which is incompatible with openvm v{OPENVM_VERSION}"
+                ),
+            ));
+        }
         let mut reader = Cursor::new(user_public_values);
         let user_public_values = decode_vec(&mut reader)?;
         Ok(Self {
@@ -285,3 +307,143 @@ impl TryFrom<VersionedVmStarkProof> for VmStarkProof<SC> {
         })
     }
 }
+
+/// Self-describing STARK proof, which carries everything needed to check which program, VM config,
+/// aggregation key and OpenVM version it was generated with, in addition to the proof itself.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct VmStarkProofBundle {
+    /// The openvm major and minor version v{}.{}.
+    pub version: String,
+    pub app_commit: AppExecutionCommit,
+    /// SHA-256 of the app VM config, see [vm_config_hash]. The proof does not bind this hash, so
+    /// it only identifies the config if it is checked against an expected hash, and even then
+    /// the config is only enforced by the app VM commit.
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub vm_config_hash: [u8; 32],
+    pub agg_vk_fingerprint: AggVkFingerprint,
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub user_public_values: Vec<u8>,
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub proof: Vec<u8>,
+}
+
+impl VmStarkProofBundle {
+    pub fn new(
+        proof: VmStarkProof<SC>,
+        app_commit: AppExecutionCommit,
+        vm_config_hash: [u8; 32],
+        agg_vk: &AggVerifyingKey,
+    ) -> Result<Self> {
+        let VersionedVmStarkProof {
+            version,
+            user_public_values,
+            proof,
+        } = VersionedVmStarkProof::new(proof)?;
+        Ok(Self {
+            version,
+            app_commit,
+            vm_config_hash,
+            agg_vk_fingerprint: agg_vk.fingerprint(),
+            user_public_values,
+            proof,
+        })
+    }
+
+    /// Checks every component of the bundle other than the proof against the verifier's, where
+    /// the VM config hash is only checked if it is expected. Returns the first component that
+    /// mismatches as [SdkError::BundleMismatch].
+    pub fn check_components(
+        &self,
+        agg_vk: &AggVerifyingKey,
+        expected_app_commit: &AppExecutionCommit,
+        expected_vm_config_hash: Option<&[u8; 32]>,
+    ) -> Result<(), SdkError> {
+        fn check<T: PartialEq>(
+            component: &'static str,
+            expected: T,
+            actual: T,
+            display: impl Fn(T) -> String,
+        ) -> Result<(), SdkError> {
+            if expected == actual {
+                Ok(())
+            } else {
+                Err(SdkError::BundleMismatch {
+                    component,
+                    expected: display(expected),
+                    actual: display(actual),
+                })
+            }
+        }
+        check(
+            "openvm version",
+            &format!("v{OPENVM_VERSION}"),
+            &self.version,
+            String::clone,
+        )?;
+        let fingerprint = agg_vk.fingerprint();
+        let actual = &self.agg_vk_fingerprint;
+        check(
+            "aggregation leaf verifying key",
+            &fingerprint.leaf_vk,
+            &actual.leaf_vk,
+            hex::encode,
+        )?;
+        check(
+            "aggregation internal verifying key",
+            &fingerprint.internal_vk,
+            &actual.internal_vk,
+            hex::encode,
+        )?;
+        check(
+            "internal verifier program commit",
+            &fingerprint.internal_verifier_program_commit,
+            &actual.internal_verifier_program_commit,
+            ToString::to_string,
+        )?;
+        check(
+            "app exe commit",
+            &expected_app_commit.app_exe_commit,
+            &self.app_commit.app_exe_commit,
+            ToString::to_string,
+        )?;
+        check(
+            "app vm commit",
+            &expected_app_commit.app_vm_commit,
+            &self.app_commit.app_vm_commit,
+            ToString::to_string,
+        )?;
+        if let Some(expected) = expected_vm_config_hash {
+            check(
+                "vm config hash",
+                expected,
+                &self.vm_config_hash,
+                hex::encode,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<VmStarkProofBundle> for VmStarkProof<SC> {
+    type Error = std::io::Error;
+    fn try_from(bundle: VmStarkProofBundle) -> Result<Self, std::io::Error> {
+        VersionedVmStarkProof {
+            version: bundle.version,
+            user_public_values: bundle.user_public_values,
+            proof: bundle.proof,
+        }
+        .try_into()
+    }
+}
+
+/// SHA-256 of the TOML encoding of an app VM config, i.e. of its `[app_vm_config]` table in
+/// `openvm.toml` as written by `toml`, which identifies the config in a [VmStarkProofBundle].
+/// Unlike the app VM commit, it does not depend on the FRI parameters of the app.
+///
+/// The hash is informational: verification only enforces the VM config through the app VM
+/// commit, and the hash of a bundle is only checked if an expected hash is given.
+pub fn vm_config_hash<VC: Serialize>(app_vm_config: &VC) -> Result<[u8; 32]> {
+    let toml = toml::to_string(app_vm_config)?;
+    Ok(Sha256::digest(toml.as_bytes()).into())
+}
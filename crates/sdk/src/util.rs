@@ -2,6 +2,10 @@ use std::cmp::Ordering;
 
 use openvm_circuit::arch::SystemConfig;
 use openvm_stark_sdk::config::FriParameters;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::codec::Encode;
 
 pub fn check_max_constraint_degrees(config: &SystemConfig, fri_params: &FriParameters) {
     match config
@@ -25,3 +29,17 @@ pub fn check_max_constraint_degrees(config: &SystemConfig, fri_params: &FriParam
         Ordering::Equal => {}
     }
 }
+
+/// SHA-256 of the compact JSON encoding of `value`, used to fingerprint keys. The encoding lists
+/// the fields of structs in declaration order, so unlike a binary serialization such as bitcode it
+/// does not depend on the version of the serialization library.
+pub fn sha256_fingerprint<T: Serialize + ?Sized>(value: &T) -> [u8; 32] {
+    let bytes = serde_json::to_vec(value).expect("failed to serialize");
+    Sha256::digest(bytes).into()
+}
+
+/// SHA-256 of the [Encode] encoding of `value`, which does not depend on the serialization format
+/// of the Rust types.
+pub fn sha256_encoded<T: Encode>(value: &T) -> std::io::Result<[u8; 32]> {
+    Ok(Sha256::digest(value.encode_to_vec()?).into())
+}
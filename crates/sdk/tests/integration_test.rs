@@ -10,8 +10,9 @@ use openvm_circuit::{
     self,
     arch::{
         instructions::exe::VmExe, ContinuationVmProof, ExecutionError, VirtualMachine,
-        VirtualMachineError, VmExecState,
+        VirtualMachineError, VmExecState, VmState,
     },
+    system::memory::online::GuestMemory,
     utils::test_system_config,
 };
 use openvm_continuations::verifier::{
@@ -22,9 +23,13 @@ use openvm_native_circuit::{execute_program_with_config, NativeConfig, NativeCpu
 use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
 use openvm_sdk::{
     codec::{Decode, Encode},
-    config::{AggregationConfig, AppConfig, SdkSystemConfig, SdkVmBuilder, SdkVmConfig},
-    prover::verify_app_proof,
-    DefaultStarkEngine, Sdk, StdIn,
+    config::{
+        AggregationConfig, AggregationTreeConfig, AppConfig, SdkSystemConfig, SdkVmBuilder,
+        SdkVmConfig,
+    },
+    prover::{stitch_segment_proofs, verify_app_proof, SegmentJob},
+    types::{vm_config_hash, VmStarkProofBundle},
+    DefaultStarkEngine, Sdk, SdkError, StdIn,
 };
 use openvm_stark_sdk::{
     config::{
@@ -35,6 +40,7 @@ use openvm_stark_sdk::{
     openvm_stark_backend::p3_field::FieldAlgebra,
     p3_baby_bear::BabyBear,
 };
+use rand::RngCore;
 #[cfg(feature = "evm-verify")]
 use {
     openvm_continuations::{
@@ -138,6 +144,7 @@ fn app_vm_config_for_test() -> SdkVmConfig {
 
 fn small_test_app_config(app_log_blowup: usize) -> AppConfig<SdkVmConfig> {
     AppConfig {
+        preset: None,
         app_fri_params: FriParameters::new_for_testing(app_log_blowup).into(),
         app_vm_config: app_vm_config_for_test(),
         leaf_fri_params: FriParameters::new_for_testing(LEAF_LOG_BLOWUP).into(),
@@ -269,6 +276,225 @@ fn test_public_values_and_leaf_verification() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_segmentation_plan() -> eyre::Result<()> {
+    let sdk = Sdk::new(small_test_app_config(1))?;
+    let exe = app_exe_for_test();
+    let (public_values, segments) = sdk.execute_metered(exe.clone(), StdIn::default())?;
+    let (plan_public_values, plan) = sdk.segmentation_plan(exe.clone(), StdIn::default())?;
+    assert_eq!(plan_public_values, public_values);
+    assert_eq!(plan.segments.len(), segments.len());
+    let mut instret = 0;
+    for segment in &plan.segments {
+        assert_eq!(segment.instret_start, instret);
+        assert_eq!(segment.trace_heights.len(), plan.air_names.len());
+        instret += segment.num_insns;
+    }
+
+    let mut num_jobs = 0;
+    sdk.for_each_segment_job(exe, StdIn::default(), &plan.segments, |job| {
+        assert_eq!(job.segment_idx, num_jobs);
+        let state = VmState::<F>::decode_from_bytes(&job.start_state)?;
+        assert_eq!(state.instret(), job.segment.instret_start);
+        num_jobs += 1;
+        Ok(())
+    })?;
+    assert_eq!(num_jobs, plan.segments.len());
+    Ok(())
+}
+
+#[test]
+fn test_prove_segments_separately() -> eyre::Result<()> {
+    setup_tracing();
+    let sdk = Sdk::new(small_test_app_config(1))?;
+    let exe = app_exe_for_test();
+    let (_, plan) = sdk.segmentation_plan(exe.clone(), StdIn::default())?;
+    assert!(plan.segments.len() > 1);
+
+    // Prove the segments out of order, as distributed workers would
+    let mut jobs = Vec::new();
+    sdk.for_each_segment_job(exe.clone(), StdIn::default(), &plan.segments, |job| {
+        jobs.push(job);
+        Ok(())
+    })?;
+    let mut app_prover = sdk.app_prover(exe)?;
+    let proofs = jobs
+        .into_iter()
+        .rev()
+        .map(|job| app_prover.prove_segment(job))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(proofs[0].user_public_values.is_some());
+    assert!(proofs[1..].iter().all(|p| p.user_public_values.is_none()));
+
+    let proof = stitch_segment_proofs(proofs)?;
+    verify_app_proof(&sdk.app_pk().get_app_vk(), &proof)?;
+    Ok(())
+}
+
+#[test]
+fn test_prove_segments_from_checkpoints() -> eyre::Result<()> {
+    setup_tracing();
+    let sdk = Sdk::new(small_test_app_config(1))?;
+    let exe = app_exe_for_test();
+    let (_, segments) = sdk.execute_metered(exe.clone(), StdIn::default())?;
+    assert!(segments.len() > 1);
+
+    let mut jobs = Vec::new();
+    sdk.for_each_segment_state(exe.clone(), StdIn::default(), &segments, |idx, state| {
+        let bytes = state.encode_to_vec()?;
+        let decoded = VmState::<F>::decode_from_bytes(&bytes)?;
+        assert_eq!(decoded.pc(), state.pc());
+        assert_eq!(decoded.rng, state.rng);
+        assert_eq!(decoded.encode_to_vec()?, bytes);
+        // The RNG is restored at its position after draws
+        let mut drawn = state.clone();
+        drawn.rng.next_u64();
+        let decoded = VmState::<F>::decode_from_bytes(&drawn.encode_to_vec()?)?;
+        assert_eq!(decoded.rng_word_pos(), state.rng_word_pos() + 2);
+        assert_eq!(decoded.rng, drawn.rng);
+        jobs.push(SegmentJob {
+            segment_idx: idx,
+            segment: segments[idx].clone(),
+            start_state: bytes,
+        });
+        Ok(())
+    })?;
+
+    let mut app_prover = sdk.app_prover(exe)?;
+    let proofs = jobs
+        .into_iter()
+        .map(|job| app_prover.prove_segment(job))
+        .collect::<Result<Vec<_>, _>>()?;
+    let proof = stitch_segment_proofs(proofs)?;
+    verify_app_proof(&sdk.app_pk().get_app_vk(), &proof)?;
+    Ok(())
+}
+
+#[test]
+fn test_decode_memory_with_huge_page_index() {
+    // One U8 address space of 2^20 cells with a single page at index u32::MAX
+    let mut bytes = Vec::new();
+    for x in [1u32, 1 << 20, 1] {
+        bytes.extend_from_slice(&x.to_le_bytes());
+    }
+    bytes.push(1);
+    for x in [1u32, u32::MAX] {
+        bytes.extend_from_slice(&x.to_le_bytes());
+    }
+    bytes.extend_from_slice(&[0u8; 4096]);
+    let err = GuestMemory::decode_from_bytes(&bytes).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_aggregate_tree_node_by_node() -> eyre::Result<()> {
+    setup_tracing();
+    let mut sdk = Sdk::new(small_test_app_config(1))?.with_agg_tree_config(AggregationTreeConfig {
+        num_children_leaf: 1,
+        num_children_internal: 2,
+        ..Default::default()
+    });
+    *sdk.agg_config_mut() = agg_config_for_test();
+    let app_proof = sdk
+        .app_prover(app_exe_for_test())?
+        .prove(StdIn::default())?;
+    let mut agg_prover = sdk.agg_prover()?;
+    let shape = agg_prover.tree_shape(app_proof.per_segment.len());
+    assert!(shape.internal_layers.len() > 1);
+
+    let mut layer = (0..shape.num_leaves)
+        .map(|idx| agg_prover.generate_leaf_proof(&app_proof, idx))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(agg_prover
+        .generate_leaf_proof(&app_proof, shape.num_leaves)
+        .is_err());
+    for (height, &num_nodes) in shape.internal_layers.iter().enumerate() {
+        layer = (0..num_nodes)
+            .map(|idx| {
+                let children = layer[shape.children(height, idx)].to_vec();
+                agg_prover.generate_internal_proof(children)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+    }
+    assert_eq!(layer.len(), 1);
+
+    // The tree proven node by node is identical to the one proven at once
+    let leaf_proofs = agg_prover.generate_leaf_proofs(&app_proof)?;
+    let expected = agg_prover
+        .aggregate_leaf_proofs(leaf_proofs, app_proof.user_public_values.public_values)?;
+    assert_eq!(layer[0].encode_to_vec()?, expected.inner.encode_to_vec()?);
+    Ok(())
+}
+
+#[test]
+fn test_proof_bundle_verification() -> eyre::Result<()> {
+    setup_tracing();
+    let mut sdk = Sdk::new(small_test_app_config(1))?;
+    *sdk.agg_config_mut() = agg_config_for_test();
+    let (proof, app_commit) = sdk.prove(app_exe_for_test(), StdIn::default())?;
+    let agg_vk = sdk.agg_pk().get_agg_vk();
+    let config_hash = vm_config_hash(&sdk.app_config().app_vm_config)?;
+    let bundle = VmStarkProofBundle::new(proof, app_commit, config_hash, &agg_vk)?;
+    let bundle: VmStarkProofBundle = serde_json::from_slice(&serde_json::to_vec(&bundle)?)?;
+    Sdk::verify_proof_bundle(&agg_vk, &bundle, &app_commit, Some(&config_hash))?;
+    // The config hash is deterministic and identifies the config
+    assert_eq!(
+        vm_config_hash(&sdk.app_config().app_vm_config)?,
+        config_hash
+    );
+    let mut other_config = sdk.app_config().app_vm_config.clone();
+    other_config.system.config.num_public_values += 1;
+    assert_ne!(vm_config_hash(&other_config)?, config_hash);
+
+    let mismatched_component = |result: Result<(), SdkError>| match result {
+        Err(SdkError::BundleMismatch { component, .. }) => component,
+        other => panic!("expected a bundle mismatch, got {other:?}"),
+    };
+    let mut other_commit = app_commit;
+    other_commit.app_vm_commit = app_commit.app_exe_commit;
+    assert_eq!(
+        mismatched_component(Sdk::verify_proof_bundle(
+            &agg_vk,
+            &bundle,
+            &other_commit,
+            None
+        )),
+        "app vm commit"
+    );
+    assert_eq!(
+        mismatched_component(Sdk::verify_proof_bundle(
+            &agg_vk,
+            &bundle,
+            &app_commit,
+            Some(&[0; 32])
+        )),
+        "vm config hash"
+    );
+    let mut old_bundle = bundle.clone();
+    old_bundle.version = "v0.0".to_string();
+    assert_eq!(
+        mismatched_component(Sdk::verify_proof_bundle(
+            &agg_vk,
+            &old_bundle,
+            &app_commit,
+            None
+        )),
+        "openvm version"
+    );
+    let mut forged_bundle = bundle;
+    forged_bundle.agg_vk_fingerprint.internal_vk = [0; 32];
+    assert_eq!(
+        mismatched_component(Sdk::verify_proof_bundle(
+            &agg_vk,
+            &forged_bundle,
+            &app_commit,
+            None
+        )),
+        "aggregation internal verifying key"
+    );
+    Ok(())
+}
+
 #[test]
 fn test_metered_execution_suspension() -> eyre::Result<()> {
     setup_tracing();
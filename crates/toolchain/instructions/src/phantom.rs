@@ -17,4 +17,7 @@ pub enum SysPhantom {
     CtStart,
     /// End tracing
     CtEnd,
+    /// Resets the hint stream with the 8-byte little-endian decomposition of the number of
+    /// instructions executed before this instruction. The hinted value is unconstrained.
+    HintInstret,
 }
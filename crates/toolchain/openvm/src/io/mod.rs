@@ -66,6 +66,25 @@ pub fn hint_load_by_key(key: &[u8]) {
     panic!("hint_load_by_key cannot run on non-zkVM platforms");
 }
 
+/// Returns the number of instructions the guest has executed so far, which is deterministic for a
+/// given program and input.
+///
+/// The value is hinted by the host and is **not constrained** by the proof, so it must only be used
+/// for decisions that do not affect soundness, such as choosing between equivalent code paths or
+/// reporting costs. Returns 0 on non-zkVM platforms.
+#[inline(always)]
+pub fn cycle_count() -> u64 {
+    #[cfg(target_os = "zkvm")]
+    {
+        let mut words = [0u32; 2];
+        openvm_rv32im_guest::hint_instret();
+        hint_buffer_u32!(words.as_mut_ptr(), 2);
+        (words[0] as u64) | ((words[1] as u64) << 32)
+    }
+    #[cfg(not(target_os = "zkvm"))]
+    0
+}
+
 /// Read the next `len` bytes from the hint stream into a vector.
 pub(crate) fn read_vec_by_len(len: usize) -> Vec<u8> {
     let num_words = len.div_ceil(4);
@@ -5,9 +5,17 @@
 /// system operations in the same way: there is no operating system and even the standard
 /// library should be directly handled with intrinsics.
 use openvm_platform::{fileno::*, memory::sys_alloc_aligned, rust_rt::terminate, WORD_SIZE};
-use openvm_rv32im_guest::{hint_buffer_u32, hint_random, raw_print_str_from_bytes};
+use openvm_rv32im_guest::{
+    hint_buffer_u32, hint_random, hint_read, hint_store_u32, raw_print_str_from_bytes,
+};
+use openvm_sha256_guest::native_sha256_compress;
 
 const DIGEST_WORDS: usize = 8;
+/// Number of bytes in a SHA-256 message block.
+const SHA256_BLOCK_BYTES: usize = 64;
+/// Number of words `sys_read` hints into a stack buffer at a time before copying them to an
+/// unaligned destination.
+const READ_CHUNK_WORDS: usize = 64;
 
 pub mod exit_code {
     pub const SUCCESS: u8 = 0;
@@ -38,6 +46,12 @@ pub extern "C" fn sys_output(_output_id: u32, _output_value: u32) {
     terminate::<{ exit_code::UNIMP }>();
 }
 
+/// Compresses the 64-byte block made of the 32 bytes at `block1_ptr` followed by the 32 bytes at
+/// `block2_ptr` into the SHA-256 state `in_state`, and writes the new state to `out_state`.
+///
+/// As in the ABI, the state is a digest: its words are stored in big-endian byte order, so the
+/// state after the last block is the SHA-256 hash.
+///
 /// # Safety
 ///
 /// `out_state`, `in_state`, `block1_ptr`, and `block2_ptr` must be aligned and
@@ -45,26 +59,46 @@ pub extern "C" fn sys_output(_output_id: u32, _output_value: u32) {
 #[inline(always)]
 #[no_mangle]
 pub unsafe extern "C" fn sys_sha_compress(
-    _out_state: *mut [u32; DIGEST_WORDS],
-    _in_state: *const [u32; DIGEST_WORDS],
-    _block1_ptr: *const [u32; DIGEST_WORDS],
-    _block2_ptr: *const [u32; DIGEST_WORDS],
+    out_state: *mut [u32; DIGEST_WORDS],
+    in_state: *const [u32; DIGEST_WORDS],
+    block1_ptr: *const [u32; DIGEST_WORDS],
+    block2_ptr: *const [u32; DIGEST_WORDS],
 ) {
-    unreachable!("sha_compress should not be part of PAL")
+    let mut block = [0u32; 2 * DIGEST_WORDS];
+    block[..DIGEST_WORDS].copy_from_slice(&*block1_ptr);
+    block[DIGEST_WORDS..].copy_from_slice(&*block2_ptr);
+    let mut state = (*in_state).map(u32::from_be);
+    native_sha256_compress(
+        state.as_ptr(),
+        block.as_ptr() as *const u8,
+        state.as_mut_ptr(),
+    );
+    *out_state = state.map(u32::to_be);
 }
 
+/// Compresses the `count` consecutive 64-byte blocks at `buf` into the SHA-256 state `in_state`,
+/// and writes the new state to `out_state`. The state is a digest as in [sys_sha_compress].
+///
 /// # Safety
 ///
 /// `out_state`, `in_state`, and `buf` must be aligned and dereferenceable.
 #[inline(always)]
 #[no_mangle]
 pub unsafe extern "C" fn sys_sha_buffer(
-    _out_state: *mut [u32; DIGEST_WORDS],
-    _in_state: *const [u32; DIGEST_WORDS],
-    _buf: *const u8,
-    _count: u32,
+    out_state: *mut [u32; DIGEST_WORDS],
+    in_state: *const [u32; DIGEST_WORDS],
+    buf: *const u8,
+    count: u32,
 ) {
-    unreachable!("sha_buffer should not be part of PAL")
+    let mut state = (*in_state).map(u32::from_be);
+    for i in 0..count as usize {
+        native_sha256_compress(
+            state.as_ptr(),
+            buf.add(i * SHA256_BLOCK_BYTES),
+            state.as_mut_ptr(),
+        );
+    }
+    *out_state = state.map(u32::to_be);
 }
 
 /// # Safety
@@ -94,12 +128,11 @@ pub unsafe extern "C" fn sys_log(msg_ptr: *const u8, len: usize) {
     raw_print_str_from_bytes(msg_ptr, len);
 }
 
-/// Cycle count
+/// Number of instructions executed so far. The value is unconstrained, see
+/// [crate::io::cycle_count].
 #[no_mangle]
 pub extern "C" fn sys_cycle_count() -> u64 {
-    crate::io::println("TODO");
-    terminate::<{ exit_code::UNIMP }>();
-    0u64
+    crate::io::cycle_count()
 }
 
 /// Reads the given number of bytes into the given buffer, posix-style.  Returns
@@ -115,10 +148,20 @@ pub extern "C" fn sys_cycle_count() -> u64 {
 ///
 /// `recv_ptr` must be aligned and dereferenceable.
 #[no_mangle]
-pub unsafe extern "C" fn sys_read(_fd: u32, _recv_ptr: *mut u8, _nread: usize) -> usize {
-    crate::io::println("sys_read is todo");
-    terminate::<{ exit_code::UNIMP }>();
-    0
+pub unsafe extern "C" fn sys_read(fd: u32, recv_ptr: *mut u8, nread: usize) -> usize {
+    // The hinted length is unconstrained, so it is clamped to not write past `recv_ptr + nread`
+    let nbytes = hint_read_len(fd, nread).min(nread);
+    // The hinted bytes are padded to a multiple of 4, so they are read in whole words and then
+    // copied to `recv_ptr`, which need not be word-aligned nor have room for the padding.
+    let mut chunk = [0u32; READ_CHUNK_WORDS];
+    let mut offset = 0;
+    while offset < nbytes {
+        let len = (nbytes - offset).min(READ_CHUNK_WORDS * WORD_SIZE);
+        hint_buffer_u32!(chunk.as_mut_ptr(), len.div_ceil(WORD_SIZE));
+        core::ptr::copy_nonoverlapping(chunk.as_ptr() as *const u8, recv_ptr.add(offset), len);
+        offset += len;
+    }
+    nbytes
 }
 
 /// Reads up to the given number of words into the buffer [recv_buf,
@@ -140,10 +183,22 @@ pub unsafe extern "C" fn sys_read(_fd: u32, _recv_ptr: *mut u8, _nread: usize) -
 /// `recv_ptr' must be a word-aligned pointer and point to a region of
 /// `nwords' size.
 #[no_mangle]
-pub unsafe extern "C" fn sys_read_words(_fd: u32, _recv_ptr: *mut u32, _nwords: usize) -> usize {
-    crate::io::println("sys_read_words is todo");
-    terminate::<{ exit_code::UNIMP }>();
-    0
+pub unsafe extern "C" fn sys_read_words(fd: u32, recv_ptr: *mut u32, nwords: usize) -> usize {
+    // The hinted length is unconstrained, so it is clamped to not write past `recv_ptr + nwords`
+    let nbytes = hint_read_len(fd, nwords * WORD_SIZE).min(nwords * WORD_SIZE);
+    hint_buffer_u32!(recv_ptr, nbytes.div_ceil(WORD_SIZE));
+    nbytes
+}
+
+/// Resets the hint stream with at most `max_len` bytes read from the host buffer of `fd` and
+/// returns the number of bytes read. The bytes themselves remain in the hint stream.
+#[inline(always)]
+fn hint_read_len(fd: u32, max_len: usize) -> usize {
+    hint_read(fd, max_len);
+    let mut nbytes: u32 = 0;
+    let ptr = &mut nbytes as *mut u32;
+    hint_store_u32!(ptr);
+    nbytes as usize
 }
 
 /// # Safety
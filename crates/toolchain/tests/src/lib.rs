@@ -11,6 +11,8 @@ use openvm_circuit::arch::{InitFileGenerator, OPENVM_DEFAULT_INIT_FILE_BASENAME}
 use openvm_transpiler::{elf::Elf, openvm_platform::memory::MEM_SIZE};
 use tempfile::tempdir;
 
+pub mod reference;
+
 #[macro_export]
 macro_rules! get_programs_dir {
     () => {{
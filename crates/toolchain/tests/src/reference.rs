@@ -0,0 +1,299 @@
+//! Minimal reference interpreter of the RV32IM base instruction set, used to check the transpiler
+//! and the VM against an independent implementation in differential tests.
+//!
+//! Besides the standard RV32IM instructions, only the OpenVM `terminate` custom instruction is
+//! supported. Memory is byte-addressed and zero-initialized, and unaligned accesses are allowed.
+
+use std::collections::BTreeMap;
+
+use eyre::{eyre, Result};
+
+pub const OPCODE_LOAD: u32 = 0b0000011;
+pub const OPCODE_OP_IMM: u32 = 0b0010011;
+pub const OPCODE_AUIPC: u32 = 0b0010111;
+pub const OPCODE_STORE: u32 = 0b0100011;
+pub const OPCODE_OP: u32 = 0b0110011;
+pub const OPCODE_LUI: u32 = 0b0110111;
+pub const OPCODE_BRANCH: u32 = 0b1100011;
+pub const OPCODE_JALR: u32 = 0b1100111;
+pub const OPCODE_JAL: u32 = 0b1101111;
+/// Opcode of the OpenVM system custom instructions, of which only `terminate` (funct3 `0b000`) is
+/// supported.
+pub const OPCODE_SYSTEM: u32 = 0b0001011;
+
+/// State of the reference interpreter.
+#[derive(Clone, Debug)]
+pub struct Rv32ImReference {
+    pub registers: [u32; 32],
+    pub pc: u32,
+    pub memory: BTreeMap<u32, u8>,
+    pub instret: u64,
+}
+
+impl Rv32ImReference {
+    pub fn new(pc_start: u32) -> Self {
+        Self {
+            registers: [0; 32],
+            pc: pc_start,
+            memory: BTreeMap::new(),
+            instret: 0,
+        }
+    }
+
+    pub fn read_memory(&self, ptr: u32, len: usize) -> Vec<u8> {
+        (0..len as u32)
+            .map(|i| {
+                self.memory
+                    .get(&ptr.wrapping_add(i))
+                    .copied()
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    fn load(&self, ptr: u32, len: usize) -> u32 {
+        let mut bytes = [0u8; 4];
+        bytes[..len].copy_from_slice(&self.read_memory(ptr, len));
+        u32::from_le_bytes(bytes)
+    }
+
+    fn store(&mut self, ptr: u32, value: u32, len: usize) {
+        for (i, byte) in value.to_le_bytes()[..len].iter().enumerate() {
+            self.memory.insert(ptr.wrapping_add(i as u32), *byte);
+        }
+    }
+
+    fn write_register(&mut self, rd: usize, value: u32) {
+        if rd != 0 {
+            self.registers[rd] = value;
+        }
+    }
+
+    /// Executes `program`, whose first instruction is at `pc_base`, until it terminates or
+    /// `max_insns` instructions have been executed. Returns the exit code.
+    pub fn execute(&mut self, program: &[u32], pc_base: u32, max_insns: u64) -> Result<u32> {
+        while self.instret < max_insns {
+            let index = self
+                .pc
+                .checked_sub(pc_base)
+                .filter(|offset| offset % 4 == 0)
+                .map(|offset| (offset / 4) as usize)
+                .ok_or_else(|| eyre!("invalid pc {:#x}", self.pc))?;
+            let insn = *program
+                .get(index)
+                .ok_or_else(|| eyre!("pc {:#x} is out of bounds", self.pc))?;
+            self.instret += 1;
+            if let Some(exit_code) = self.step(insn)? {
+                return Ok(exit_code);
+            }
+        }
+        Err(eyre!(
+            "program did not terminate in {max_insns} instructions"
+        ))
+    }
+
+    /// Executes a single instruction. Returns the exit code if it is `terminate`.
+    pub fn step(&mut self, insn: u32) -> Result<Option<u32>> {
+        let opcode = insn & 0x7f;
+        let rd = ((insn >> 7) & 0x1f) as usize;
+        let funct3 = (insn >> 12) & 0x7;
+        let rs1 = ((insn >> 15) & 0x1f) as usize;
+        let rs2 = ((insn >> 20) & 0x1f) as usize;
+        let funct7 = insn >> 25;
+        let x1 = self.registers[rs1];
+        let x2 = self.registers[rs2];
+        let imm_i = (insn as i32 >> 20) as u32;
+        let imm_s = (((insn as i32 >> 25) << 5) as u32) | ((insn >> 7) & 0x1f);
+        let imm_b = (((insn as i32 >> 31) << 12) as u32)
+            | ((insn & 0x80) << 4)
+            | ((insn >> 20) & 0x7e0)
+            | ((insn >> 7) & 0x1e);
+        let imm_u = insn & 0xfffff000;
+        let imm_j = (((insn as i32 >> 31) << 20) as u32)
+            | (insn & 0xff000)
+            | ((insn >> 9) & 0x800)
+            | ((insn >> 20) & 0x7fe);
+        let unsupported = || eyre!("unsupported instruction {insn:#010x} at pc {:#x}", self.pc);
+
+        let mut next_pc = self.pc.wrapping_add(4);
+        match opcode {
+            OPCODE_LUI => self.write_register(rd, imm_u),
+            OPCODE_AUIPC => self.write_register(rd, self.pc.wrapping_add(imm_u)),
+            OPCODE_JAL => {
+                self.write_register(rd, next_pc);
+                next_pc = self.pc.wrapping_add(imm_j);
+            }
+            OPCODE_JALR if funct3 == 0 => {
+                self.write_register(rd, next_pc);
+                next_pc = x1.wrapping_add(imm_i) & !1;
+            }
+            OPCODE_BRANCH => {
+                let taken = match funct3 {
+                    0b000 => x1 == x2,
+                    0b001 => x1 != x2,
+                    0b100 => (x1 as i32) < (x2 as i32),
+                    0b101 => (x1 as i32) >= (x2 as i32),
+                    0b110 => x1 < x2,
+                    0b111 => x1 >= x2,
+                    _ => return Err(unsupported()),
+                };
+                if taken {
+                    next_pc = self.pc.wrapping_add(imm_b);
+                }
+            }
+            OPCODE_LOAD => {
+                let ptr = x1.wrapping_add(imm_i);
+                let value = match funct3 {
+                    0b000 => self.load(ptr, 1) as i8 as i32 as u32,
+                    0b001 => self.load(ptr, 2) as i16 as i32 as u32,
+                    0b010 => self.load(ptr, 4),
+                    0b100 => self.load(ptr, 1),
+                    0b101 => self.load(ptr, 2),
+                    _ => return Err(unsupported()),
+                };
+                self.write_register(rd, value);
+            }
+            OPCODE_STORE => {
+                let ptr = x1.wrapping_add(imm_s);
+                let len = match funct3 {
+                    0b000 => 1,
+                    0b001 => 2,
+                    0b010 => 4,
+                    _ => return Err(unsupported()),
+                };
+                self.store(ptr, x2, len);
+            }
+            OPCODE_OP_IMM => {
+                let shamt = imm_i & 0x1f;
+                let value = match (funct3, funct7) {
+                    (0b000, _) => x1.wrapping_add(imm_i),
+                    (0b010, _) => ((x1 as i32) < (imm_i as i32)) as u32,
+                    (0b011, _) => (x1 < imm_i) as u32,
+                    (0b100, _) => x1 ^ imm_i,
+                    (0b110, _) => x1 | imm_i,
+                    (0b111, _) => x1 & imm_i,
+                    (0b001, 0b0000000) => x1 << shamt,
+                    (0b101, 0b0000000) => x1 >> shamt,
+                    (0b101, 0b0100000) => ((x1 as i32) >> shamt) as u32,
+                    _ => return Err(unsupported()),
+                };
+                self.write_register(rd, value);
+            }
+            OPCODE_OP => {
+                let value = match (funct7, funct3) {
+                    (0b0000000, 0b000) => x1.wrapping_add(x2),
+                    (0b0100000, 0b000) => x1.wrapping_sub(x2),
+                    (0b0000000, 0b001) => x1 << (x2 & 0x1f),
+                    (0b0000000, 0b010) => ((x1 as i32) < (x2 as i32)) as u32,
+                    (0b0000000, 0b011) => (x1 < x2) as u32,
+                    (0b0000000, 0b100) => x1 ^ x2,
+                    (0b0000000, 0b101) => x1 >> (x2 & 0x1f),
+                    (0b0100000, 0b101) => ((x1 as i32) >> (x2 & 0x1f)) as u32,
+                    (0b0000000, 0b110) => x1 | x2,
+                    (0b0000000, 0b111) => x1 & x2,
+                    (0b0000001, funct3) => mul_div(funct3, x1, x2),
+                    _ => return Err(unsupported()),
+                };
+                self.write_register(rd, value);
+            }
+            OPCODE_SYSTEM if funct3 == 0 => return Ok(Some(imm_i & 0xfff)),
+            _ => return Err(unsupported()),
+        }
+        self.pc = next_pc;
+        Ok(None)
+    }
+}
+
+/// RV32M instruction with the given `funct3`, following the RISC-V conventions for division by
+/// zero and signed overflow.
+fn mul_div(funct3: u32, x1: u32, x2: u32) -> u32 {
+    let (s1, s2) = (x1 as i32 as i64, x2 as i32 as i64);
+    let (u1, u2) = (x1 as u64, x2 as u64);
+    match funct3 {
+        0b000 => x1.wrapping_mul(x2),
+        0b001 => ((s1 * s2) >> 32) as u32,
+        0b010 => ((s1 * u2 as i64) >> 32) as u32,
+        0b011 => ((u1 * u2) >> 32) as u32,
+        0b100 => match x2 {
+            0 => u32::MAX,
+            _ => (x1 as i32).wrapping_div(x2 as i32) as u32,
+        },
+        0b101 => x1.checked_div(x2).unwrap_or(u32::MAX),
+        0b110 => match x2 {
+            0 => x1,
+            _ => (x1 as i32).wrapping_rem(x2 as i32) as u32,
+        },
+        _ => x1.checked_rem(x2).unwrap_or(x1),
+    }
+}
+
+/// Encoders of RV32IM instructions, for generating test programs.
+pub mod encode {
+    pub fn r_type(opcode: u32, rd: usize, funct3: u32, rs1: usize, rs2: usize, funct7: u32) -> u32 {
+        (funct7 << 25)
+            | ((rs2 as u32) << 20)
+            | ((rs1 as u32) << 15)
+            | (funct3 << 12)
+            | ((rd as u32) << 7)
+            | opcode
+    }
+
+    pub fn i_type(opcode: u32, rd: usize, funct3: u32, rs1: usize, imm: i32) -> u32 {
+        (((imm as u32) & 0xfff) << 20)
+            | ((rs1 as u32) << 15)
+            | (funct3 << 12)
+            | ((rd as u32) << 7)
+            | opcode
+    }
+
+    pub fn s_type(opcode: u32, funct3: u32, rs1: usize, rs2: usize, imm: i32) -> u32 {
+        let imm = imm as u32;
+        (((imm >> 5) & 0x7f) << 25)
+            | ((rs2 as u32) << 20)
+            | ((rs1 as u32) << 15)
+            | (funct3 << 12)
+            | ((imm & 0x1f) << 7)
+            | opcode
+    }
+
+    pub fn b_type(opcode: u32, funct3: u32, rs1: usize, rs2: usize, imm: i32) -> u32 {
+        let imm = imm as u32;
+        (((imm >> 12) & 1) << 31)
+            | (((imm >> 5) & 0x3f) << 25)
+            | ((rs2 as u32) << 20)
+            | ((rs1 as u32) << 15)
+            | (funct3 << 12)
+            | (((imm >> 1) & 0xf) << 8)
+            | (((imm >> 11) & 1) << 7)
+            | opcode
+    }
+
+    pub fn u_type(opcode: u32, rd: usize, imm: u32) -> u32 {
+        (imm & 0xfffff000) | ((rd as u32) << 7) | opcode
+    }
+
+    pub fn j_type(opcode: u32, rd: usize, imm: i32) -> u32 {
+        let imm = imm as u32;
+        (((imm >> 20) & 1) << 31)
+            | (((imm >> 1) & 0x3ff) << 21)
+            | (((imm >> 11) & 1) << 20)
+            | (((imm >> 12) & 0xff) << 12)
+            | ((rd as u32) << 7)
+            | opcode
+    }
+
+    /// Instructions setting `rd` to `value` with `lui` followed by `addi`.
+    pub fn li(rd: usize, value: u32) -> [u32; 2] {
+        let lo = ((value << 20) as i32) >> 20;
+        let hi = value.wrapping_sub(lo as u32);
+        [
+            u_type(super::OPCODE_LUI, rd, hi),
+            i_type(super::OPCODE_OP_IMM, rd, 0b000, rd, lo),
+        ]
+    }
+
+    /// OpenVM `terminate` custom instruction with the given exit code.
+    pub fn terminate(exit_code: u8) -> u32 {
+        i_type(super::OPCODE_SYSTEM, 0, 0b000, 0, exit_code as i32)
+    }
+}
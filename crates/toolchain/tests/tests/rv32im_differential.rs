@@ -0,0 +1,268 @@
+//! Differential tests which execute random RV32IM programs with the VM and with the reference
+//! interpreter in [openvm_toolchain_tests::reference], and compare the final registers and memory.
+
+use eyre::Result;
+use openvm_circuit::{
+    arch::{
+        debug_proving_ctx, execution_mode::Segment, instructions::exe::VmExe, vm::VirtualMachine,
+        PreflightExecutionOutput, VmExecutor, VmState,
+    },
+    system::memory::online::GuestMemory,
+    utils::TestStarkEngine,
+};
+use openvm_instructions::{
+    program::Program,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+};
+use openvm_rv32im_circuit::{Rv32ImBuilder, Rv32ImConfig};
+use openvm_rv32im_transpiler::{
+    Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+};
+use openvm_stark_sdk::{config::FriParameters, engine::StarkFriEngine, p3_baby_bear::BabyBear};
+use openvm_toolchain_tests::reference::{encode::*, *};
+use openvm_transpiler::transpiler::Transpiler;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+type F = BabyBear;
+
+const PC_BASE: u32 = 0x1000;
+/// Start of the memory region accessed by loads and stores, which is held in [BASE_REG].
+const DATA_BASE: u32 = 0x10000;
+const DATA_LEN: usize = 256;
+const BASE_REG: usize = 31;
+const NUM_BLOCKS: usize = 200;
+
+/// Part of a random program. Control flow only skips whole blocks, so that an `auipc` and `jalr`
+/// pair is never split.
+enum Block {
+    Insn(u32),
+    Branch {
+        funct3: u32,
+        rs1: usize,
+        rs2: usize,
+        skip: usize,
+    },
+    Jal {
+        rd: usize,
+        skip: usize,
+    },
+    AuipcJalr {
+        tmp: usize,
+        rd: usize,
+        skip: usize,
+    },
+}
+
+impl Block {
+    fn len(&self) -> usize {
+        match self {
+            Block::AuipcJalr { .. } => 2,
+            _ => 1,
+        }
+    }
+}
+
+fn random_rd(rng: &mut StdRng) -> usize {
+    rng.gen_range(0..BASE_REG)
+}
+
+fn random_reg(rng: &mut StdRng) -> usize {
+    rng.gen_range(0..32)
+}
+
+fn random_block(rng: &mut StdRng, blocks_left: usize) -> Block {
+    let (rd, rs1, rs2) = (random_rd(rng), random_reg(rng), random_reg(rng));
+    let skip = rng.gen_range(0..=blocks_left.min(3));
+    match rng.gen_range(0..10) {
+        0..=2 => {
+            let (funct7, funct3) = match rng.gen_range(0..18) {
+                0 => (0b0100000, 0b000),
+                1 => (0b0100000, 0b101),
+                i @ 2..=9 => (0b0000000, i - 2),
+                i => (0b0000001, i - 10),
+            };
+            Block::Insn(r_type(OPCODE_OP, rd, funct3, rs1, rs2, funct7))
+        }
+        3..=4 => {
+            let funct3 =
+                [0b000, 0b010, 0b011, 0b100, 0b110, 0b111, 0b001, 0b101][rng.gen_range(0..8)];
+            let imm = match funct3 {
+                0b001 => rng.gen_range(0..32),
+                0b101 => rng.gen_range(0..32) | (rng.gen_range(0..2) << 10),
+                _ => rng.gen_range(-2048..2048),
+            };
+            Block::Insn(i_type(OPCODE_OP_IMM, rd, funct3, rs1, imm))
+        }
+        5 => {
+            let opcode = [OPCODE_LUI, OPCODE_AUIPC][rng.gen_range(0..2)];
+            Block::Insn(u_type(opcode, rd, rng.gen()))
+        }
+        6 => {
+            let (funct3, size) =
+                [(0b000, 1), (0b001, 2), (0b010, 4), (0b100, 1), (0b101, 2)][rng.gen_range(0..5)];
+            let offset = rng.gen_range(0..DATA_LEN as i32 / size) * size;
+            Block::Insn(i_type(OPCODE_LOAD, rd, funct3, BASE_REG, offset))
+        }
+        7 => {
+            let (funct3, size) = [(0b000, 1), (0b001, 2), (0b010, 4)][rng.gen_range(0..3)];
+            let offset = rng.gen_range(0..DATA_LEN as i32 / size) * size;
+            Block::Insn(s_type(OPCODE_STORE, funct3, BASE_REG, rs2, offset))
+        }
+        8 => Block::Branch {
+            funct3: [0b000, 0b001, 0b100, 0b101, 0b110, 0b111][rng.gen_range(0..6)],
+            rs1,
+            rs2,
+            skip,
+        },
+        _ => match rng.gen_range(0..2) {
+            0 => Block::Jal { rd, skip },
+            _ => Block::AuipcJalr {
+                tmp: rng.gen_range(1..BASE_REG),
+                rd,
+                skip,
+            },
+        },
+    }
+}
+
+/// Generates a random program which sets the registers to random values, executes [NUM_BLOCKS]
+/// random blocks and terminates.
+fn random_program(rng: &mut StdRng) -> Vec<u32> {
+    let mut program = Vec::new();
+    for rd in 1..BASE_REG {
+        program.extend(li(rd, rng.gen()));
+    }
+    program.extend(li(BASE_REG, DATA_BASE));
+
+    let blocks: Vec<Block> = (0..NUM_BLOCKS)
+        .map(|i| random_block(rng, NUM_BLOCKS - 1 - i))
+        .collect();
+    // Byte offset from the start of block `i` to the start of block `i + skip + 1`
+    let jump_offset = |i: usize, skip: usize| {
+        4 * blocks[i..=i + skip].iter().map(Block::len).sum::<usize>() as i32
+    };
+    for (i, block) in blocks.iter().enumerate() {
+        match *block {
+            Block::Insn(insn) => program.push(insn),
+            Block::Branch {
+                funct3,
+                rs1,
+                rs2,
+                skip,
+            } => program.push(b_type(
+                OPCODE_BRANCH,
+                funct3,
+                rs1,
+                rs2,
+                jump_offset(i, skip),
+            )),
+            Block::Jal { rd, skip } => program.push(j_type(OPCODE_JAL, rd, jump_offset(i, skip))),
+            Block::AuipcJalr { tmp, rd, skip } => program.extend([
+                u_type(OPCODE_AUIPC, tmp, 0),
+                i_type(OPCODE_JALR, rd, 0b000, tmp, jump_offset(i, skip)),
+            ]),
+        }
+    }
+    program.push(terminate(0));
+    program
+}
+
+fn transpile(program: &[u32]) -> Result<VmExe<F>> {
+    let transpiler = Transpiler::<F>::default()
+        .with_extension(Rv32ITranspilerExtension)
+        .with_extension(Rv32MTranspilerExtension)
+        .with_extension(Rv32IoTranspilerExtension);
+    let instructions = transpiler.transpile(program)?;
+    let program = Program::new_without_debug_infos_with_option(&instructions, PC_BASE);
+    Ok(VmExe::new(program).with_pc_start(PC_BASE))
+}
+
+fn run_reference(program: &[u32]) -> Result<Rv32ImReference> {
+    let mut reference = Rv32ImReference::new(PC_BASE);
+    let exit_code = reference.execute(program, PC_BASE, 10 * program.len() as u64)?;
+    assert_eq!(exit_code, 0);
+    Ok(reference)
+}
+
+fn assert_state_eq(state: &VmState<F, GuestMemory>, reference: &Rv32ImReference, seed: u64) {
+    // SAFETY: the register and memory address spaces of Rv32ImConfig consist of byte cells and
+    // contain the accessed ranges
+    let (registers, data) = unsafe {
+        (
+            state.memory.memory.get_u8_slice(RV32_REGISTER_AS, 0, 128),
+            state
+                .memory
+                .memory
+                .get_u8_slice(RV32_MEMORY_AS, DATA_BASE as usize, DATA_LEN),
+        )
+    };
+    for (i, limbs) in registers.chunks_exact(4).enumerate() {
+        assert_eq!(
+            u32::from_le_bytes(limbs.try_into().unwrap()),
+            reference.registers[i],
+            "register x{i} differs for seed {seed}"
+        );
+    }
+    assert_eq!(
+        data,
+        reference.read_memory(DATA_BASE, DATA_LEN),
+        "memory differs for seed {seed}"
+    );
+}
+
+#[test]
+fn test_rv32im_differential_execute() -> Result<()> {
+    let executor = VmExecutor::new(Rv32ImConfig::default())?;
+    for seed in 0..200 {
+        let program = random_program(&mut StdRng::seed_from_u64(seed));
+        let reference = run_reference(&program)?;
+        let exe = transpile(&program)?;
+        let state = executor.instance(&exe)?.execute(vec![], None)?;
+        assert_state_eq(&state, &reference, seed);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_rv32im_differential_preflight() -> Result<()> {
+    let engine = TestStarkEngine::new(FriParameters::new_for_testing(1));
+    let (mut vm, pk) =
+        VirtualMachine::new_with_keygen(engine, Rv32ImBuilder, Rv32ImConfig::default())?;
+    for seed in 0..5 {
+        let program = random_program(&mut StdRng::seed_from_u64(seed));
+        let reference = run_reference(&program)?;
+        let exe = transpile(&program)?;
+
+        let metered_ctx = vm.build_metered_ctx(&exe);
+        let (segments, _) = vm
+            .metered_interpreter(&exe)?
+            .execute_metered(vec![], metered_ctx)?;
+        let cached_program_trace = vm.commit_program_on_device(&exe.program);
+        vm.load_program(cached_program_trace);
+        let mut preflight_interpreter = vm.preflight_interpreter(&exe)?;
+        let mut state = vm.create_initial_state(&exe, vec![]);
+        for Segment {
+            num_insns,
+            trace_heights,
+            ..
+        } in segments
+        {
+            vm.transport_init_memory_to_device(&state.memory);
+            let PreflightExecutionOutput {
+                system_records,
+                record_arenas,
+                to_state,
+            } = vm.execute_preflight(
+                &mut preflight_interpreter,
+                state,
+                Some(num_insns),
+                &trace_heights,
+            )?;
+            state = to_state;
+            let ctx = vm.generate_proving_ctx(system_records, record_arenas)?;
+            debug_proving_ctx(&vm, &pk, &ctx);
+        }
+        assert_state_eq(&state, &reference, seed);
+    }
+    Ok(())
+}
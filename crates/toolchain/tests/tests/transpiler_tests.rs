@@ -24,7 +24,7 @@ use openvm_rv32im_transpiler::{
     Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
 };
 use openvm_stark_sdk::p3_baby_bear::BabyBear;
-use openvm_transpiler::{elf::Elf, transpiler::Transpiler, FromElf};
+use openvm_transpiler::{disasm::Disassembler, elf::Elf, transpiler::Transpiler, FromElf};
 use serde::{Deserialize, Serialize};
 use test_case::test_case;
 
@@ -64,6 +64,47 @@ fn test_generate_program(elf_path: &str) -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_disassemble() -> Result<()> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let data = read(dir.join("tests/data/rv32im-intrin-from-as"))?;
+    let transpiler = Transpiler::<F>::default()
+        .with_extension(Rv32ITranspilerExtension)
+        .with_extension(Rv32MTranspilerExtension)
+        .with_extension(Rv32IoTranspilerExtension)
+        .with_extension(ModularTranspilerExtension);
+    let exe = VmExe::from_elf(Elf::decode(&data, MEM_SIZE as u32)?, transpiler.clone())?;
+    let disassembly = Disassembler::new(transpiler)
+        .with_elf(&data)?
+        .disassemble(&exe);
+    let find_line = |mnemonic: &str| {
+        disassembly
+            .lines()
+            .find(|line| line.contains(mnemonic))
+            .unwrap_or_else(|| panic!("no {mnemonic} line in the disassembly"))
+    };
+    // The second modulus uses the second copy of the modular opcodes, and every instruction
+    // decoded from the ELF is annotated with its RISC-V source
+    for mnemonic in [
+        "Rv32ModularArithmeticOpcode::ADD[0]",
+        "Rv32ModularArithmeticOpcode::ADD[1]",
+        "SystemOpcode::TERMINATE",
+    ] {
+        let line = find_line(mnemonic);
+        assert!(line.starts_with("0x"), "{line}");
+        assert!(line.contains(" # "), "{line}");
+    }
+    assert!(!disassembly.contains("UNKNOWN"));
+    assert_eq!(
+        disassembly
+            .lines()
+            .filter(|line| line.contains(" # "))
+            .count(),
+        exe.program.num_defined_instructions()
+    );
+    Ok(())
+}
+
 #[test_case("tests/data/rv32im-exp-from-as")]
 #[test_case("tests/data/rv32im-fib-from-as")]
 fn test_rv32im_runtime(elf_path: &str) -> Result<()> {
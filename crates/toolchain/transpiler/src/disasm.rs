@@ -0,0 +1,101 @@
+//! Disassembler of OpenVM executables into annotated OpenVM assembly.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use eyre::Result;
+use openvm_instructions::{
+    exe::VmExe, instruction::Instruction, program::DEFAULT_PC_STEP, LocalOpcode,
+    PhantomDiscriminant, SystemOpcode,
+};
+use openvm_platform::memory::MEM_SIZE;
+use openvm_stark_backend::p3_field::PrimeField32;
+use rrs_lib::{instruction_string_outputter::InstructionStringOutputter, process_instruction};
+
+use crate::{elf::Elf, symbols::SymbolTable, transpiler::Transpiler};
+
+/// Disassembles [VmExe] programs, naming every opcode after the opcode class of the
+/// [Transpiler] extension which produces it. Given the ELF which the executable was transpiled
+/// from, it also labels the start of every function and shows the original RISC-V instruction
+/// at every pc.
+pub struct Disassembler<F> {
+    transpiler: Transpiler<F>,
+    symbols: SymbolTable,
+    /// 32-bit RISC-V instructions of the ELF, by pc.
+    riscv_instructions: BTreeMap<u32, u32>,
+}
+
+impl<F: PrimeField32> Disassembler<F> {
+    pub fn new(transpiler: Transpiler<F>) -> Self {
+        Self {
+            transpiler,
+            symbols: SymbolTable::default(),
+            riscv_instructions: BTreeMap::new(),
+        }
+    }
+
+    /// Annotates the disassembly with the function symbols and RISC-V instructions of the ELF
+    /// `elf_bytes`.
+    pub fn with_elf(mut self, elf_bytes: &[u8]) -> Result<Self> {
+        let elf = Elf::decode(elf_bytes, MEM_SIZE as u32)?;
+        self.riscv_instructions = elf
+            .instructions
+            .iter()
+            .enumerate()
+            .map(|(i, &instruction)| (elf.pc_base + DEFAULT_PC_STEP * i as u32, instruction))
+            .collect();
+        self.symbols = SymbolTable::decode(elf_bytes)?;
+        Ok(self)
+    }
+
+    /// Disassembles the program of `exe` into one line per instruction of the form
+    /// `pc: MNEMONIC a b c d e f g`, followed by the original RISC-V instruction if the ELF is
+    /// known.
+    pub fn disassemble(&self, exe: &VmExe<F>) -> String {
+        let mut output = String::new();
+        for (pc, instruction, _) in exe.program.enumerate_by_pc() {
+            if let Some(symbol) = self.symbols.lookup(pc).filter(|s| s.start == pc) {
+                writeln!(output, "\n{:#010x} <{}>:", pc, symbol.name).unwrap();
+            }
+            let mut line = format!("{:#010x}: {}", pc, self.format_instruction(&instruction));
+            if let Some(riscv) = self.format_riscv_instruction(pc) {
+                line = format!("{line:<72} # {riscv}");
+            }
+            writeln!(output, "{line}").unwrap();
+        }
+        output
+    }
+
+    /// Formats `instruction` as its mnemonic followed by its operands `a` through `g`. Phantom
+    /// instructions are named after their discriminant, which is the lower 16 bits of `c`.
+    pub fn format_instruction(&self, instruction: &Instruction<F>) -> String {
+        let mnemonic = if instruction.opcode == SystemOpcode::PHANTOM.global_opcode() {
+            let discriminant = PhantomDiscriminant(instruction.c.as_canonical_u32() as u16);
+            match self.transpiler.phantom_name(discriminant) {
+                Some(name) => format!("PHANTOM {name}"),
+                None => format!("PHANTOM {:#x}", discriminant.0),
+            }
+        } else {
+            self.transpiler
+                .opcode_name(instruction.opcode)
+                .unwrap_or_else(|| format!("UNKNOWN({:#x})", instruction.opcode.as_usize()))
+        };
+        let operands = instruction
+            .operands()
+            .iter()
+            .map(|operand| operand.as_canonical_u32().to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("{mnemonic:<40} {operands}")
+    }
+
+    /// Formats the RISC-V instruction at `pc` of the ELF, if it is known. Custom instructions
+    /// which are not standard RISC-V are formatted as `.insn` with their encoding.
+    fn format_riscv_instruction(&self, pc: u32) -> Option<String> {
+        let &instruction = self.riscv_instructions.get(&pc)?;
+        let mut outputter = InstructionStringOutputter { insn_pc: pc };
+        Some(
+            process_instruction(&mut outputter, instruction)
+                .unwrap_or_else(|| format!(".insn {instruction:#010x}")),
+        )
+    }
+}
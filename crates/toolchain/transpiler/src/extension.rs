@@ -1,4 +1,4 @@
-use openvm_instructions::instruction::Instruction;
+use openvm_instructions::{instruction::Instruction, PhantomDiscriminant, VmOpcode};
 
 /// Trait to add custom RISC-V instruction transpilation to OpenVM instruction format.
 /// RISC-V instructions always come in 32-bit chunks.
@@ -12,6 +12,18 @@ pub trait TranspilerExtension<F> {
     /// Otherwise it returns `TranspilerOutput { instructions, used_u32s }` to indicate that
     /// `instruction_stream[..used_u32s]` should be transpiled into `instructions`.
     fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>>;
+
+    /// Returns the mnemonic of `opcode`, such as `Rv32WeierstrassOpcode::EC_ADD_NE`, if this
+    /// extension transpiles to instructions with that opcode. Used to disassemble programs.
+    fn opcode_name(&self, _opcode: VmOpcode) -> Option<String> {
+        None
+    }
+
+    /// Returns the name of the phantom instruction with `discriminant`, such as
+    /// `Rv32Phantom::HintInput`, if this extension transpiles to it.
+    fn phantom_name(&self, _discriminant: PhantomDiscriminant) -> Option<String> {
+        None
+    }
 }
 
 pub struct TranspilerOutput<F> {
@@ -8,7 +8,9 @@ use transpiler::{Transpiler, TranspilerError};
 
 use crate::util::elf_memory_image_to_openvm_memory_image;
 
+pub mod disasm;
 pub mod elf;
+pub mod symbols;
 pub mod transpiler;
 pub mod util;
 
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use elf::{abi::STT_FUNC, endian::LittleEndian, ElfBytes};
+use eyre::Result;
+
+/// A function symbol read from the ELF symbol table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// Demangled name, without the trailing hash. Names are kept mangled unless the `demangle`
+    /// feature is enabled.
+    pub name: String,
+    /// Start address of the function.
+    pub start: u32,
+    /// Size of the function in bytes.
+    pub size: u32,
+}
+
+impl Symbol {
+    /// Returns true if `pc` lies within the function body.
+    pub fn contains(&self, pc: u32) -> bool {
+        pc >= self.start && pc - self.start < self.size.max(1)
+    }
+}
+
+/// Function symbols of a RISC-V ELF, indexed by start address. Used to symbolize guest PCs for
+/// debugging and profiling.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    by_addr: BTreeMap<u32, Symbol>,
+}
+
+impl SymbolTable {
+    /// Reads the function symbols from an ELF file. Returns an empty table if the ELF was stripped.
+    pub fn decode(input: &[u8]) -> Result<Self> {
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(input)
+            .map_err(|err| eyre::eyre!("Elf parse error: {err}"))?;
+        let mut by_addr = BTreeMap::new();
+        if let Some((symtab, strtab)) = elf.symbol_table()? {
+            for symbol in symtab.iter() {
+                if symbol.st_symtype() != STT_FUNC || symbol.st_value == 0 {
+                    continue;
+                }
+                let raw_name = strtab.get(symbol.st_name as usize)?;
+                let name = demangle(raw_name);
+                let start = symbol.st_value as u32;
+                by_addr.insert(
+                    start,
+                    Symbol {
+                        name,
+                        start,
+                        size: symbol.st_size as u32,
+                    },
+                );
+            }
+        }
+        Ok(Self { by_addr })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_addr.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_addr.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Symbol> {
+        self.by_addr.values()
+    }
+
+    /// Returns the function containing `pc`, if any.
+    pub fn lookup(&self, pc: u32) -> Option<&Symbol> {
+        self.by_addr
+            .range(..=pc)
+            .next_back()
+            .map(|(_, symbol)| symbol)
+            .filter(|symbol| symbol.contains(pc))
+    }
+
+    /// Finds a function by name. An exact match is preferred; otherwise a unique function whose
+    /// path ends with `::{name}` is returned.
+    pub fn find(&self, name: &str) -> Option<&Symbol> {
+        if let Some(symbol) = self.iter().find(|symbol| symbol.name == name) {
+            return Some(symbol);
+        }
+        let suffix = format!("::{name}");
+        let mut candidates = self.iter().filter(|symbol| symbol.name.ends_with(&suffix));
+        match (candidates.next(), candidates.next()) {
+            (Some(symbol), None) => Some(symbol),
+            _ => None,
+        }
+    }
+
+    /// Formats `pc` as `name+offset` if it falls inside a known function.
+    pub fn format_pc(&self, pc: u32) -> String {
+        match self.lookup(pc) {
+            Some(symbol) if symbol.start == pc => format!("{:#010x} <{}>", pc, symbol.name),
+            Some(symbol) => format!("{:#010x} <{}+{}>", pc, symbol.name, pc - symbol.start),
+            None => format!("{:#010x}", pc),
+        }
+    }
+}
+
+#[cfg(feature = "demangle")]
+fn demangle(raw_name: &str) -> String {
+    format!("{:#}", rustc_demangle::demangle(raw_name))
+}
+
+#[cfg(not(feature = "demangle"))]
+fn demangle(raw_name: &str) -> String {
+    raw_name.to_string()
+}
@@ -1,10 +1,14 @@
 use std::rc::Rc;
 
-use openvm_instructions::instruction::Instruction;
+use openvm_instructions::{
+    instruction::Instruction, PhantomDiscriminant, PublishOpcode, SysPhantom, SystemOpcode,
+    VmOpcode,
+};
 use openvm_stark_backend::p3_field::PrimeField32;
+use strum::IntoEnumIterator;
 use thiserror::Error;
 
-use crate::TranspilerExtension;
+use crate::{util::local_opcode_name, TranspilerExtension};
 
 /// Collection of [`TranspilerExtension`]s.
 /// The transpiler can be configured to transpile any ELF in 32-bit chunks.
@@ -73,4 +77,28 @@ impl<F: PrimeField32> Transpiler<F> {
         }
         Ok(instructions)
     }
+
+    /// Returns the mnemonic of `opcode` if it is a system opcode or an opcode of one of the
+    /// extensions of the transpiler.
+    pub fn opcode_name(&self, opcode: VmOpcode) -> Option<String> {
+        local_opcode_name(opcode, SystemOpcode::iter(), 1)
+            .or_else(|| local_opcode_name(opcode, PublishOpcode::iter(), 1))
+            .or_else(|| {
+                self.processors
+                    .iter()
+                    .find_map(|proc| proc.opcode_name(opcode))
+            })
+    }
+
+    /// Returns the name of the phantom instruction with `discriminant` if it is a system phantom
+    /// or a phantom of one of the extensions of the transpiler.
+    pub fn phantom_name(&self, discriminant: PhantomDiscriminant) -> Option<String> {
+        SysPhantom::from_repr(discriminant.0)
+            .map(|phantom| format!("SysPhantom::{phantom:?}"))
+            .or_else(|| {
+                self.processors
+                    .iter()
+                    .find_map(|proc| proc.phantom_name(discriminant))
+            })
+    }
 }
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::{any::type_name, collections::BTreeMap, fmt::Debug};
 
 use openvm_instructions::{
     exe::SparseMemoryImage,
@@ -177,3 +177,37 @@ pub fn elf_memory_image_to_openvm_memory_image(
     }
     result
 }
+
+/// Returns the name of `opcode` as `Class::VARIANT` if it is one of `opcodes`, which must be all
+/// the opcodes of the class. Classes which are repeated once per modulus or curve, with the
+/// opcodes of the `i`-th copy shifted by `i` times the number of opcodes, are looked up in the
+/// first `num_copies` copies and named `Class::VARIANT[i]`.
+pub fn local_opcode_name<T: LocalOpcode + Debug>(
+    opcode: VmOpcode,
+    opcodes: impl IntoIterator<Item = T>,
+    num_copies: usize,
+) -> Option<String> {
+    let opcodes: Vec<T> = opcodes.into_iter().collect();
+    let local = opcode.as_usize().checked_sub(T::CLASS_OFFSET)?;
+    let (copy, local) = match num_copies {
+        1 => (0, local),
+        _ => (local / opcodes.len(), local % opcodes.len()),
+    };
+    if copy >= num_copies {
+        return None;
+    }
+    let variant = opcodes.iter().find(|op| op.local_usize() == local)?;
+    let class = type_name::<T>().rsplit("::").next().unwrap();
+    // Classes which wrap another class, such as `Rv32BaseAlu256Opcode(BaseAluOpcode)`, are
+    // formatted as `Class(VARIANT)`
+    let variant = format!("{variant:?}");
+    let variant = variant
+        .strip_prefix(class)
+        .and_then(|s| s.strip_prefix('('))
+        .and_then(|s| s.strip_suffix(')'))
+        .unwrap_or(&variant);
+    Some(match num_copies {
+        1 => format!("{class}::{variant}"),
+        _ => format!("{class}::{variant}[{copy}]"),
+    })
+}
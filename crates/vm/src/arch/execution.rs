@@ -7,11 +7,10 @@ use openvm_stark_backend::{
     interaction::{BusIndex, InteractionBuilder, PermutationCheckBus},
     p3_field::FieldAlgebra,
 };
-use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::{execution_mode::ExecutionCtxTrait, Streams, VmExecState};
+use super::{execution_mode::ExecutionCtxTrait, Streams, VmExecState, VmRng};
 #[cfg(feature = "tco")]
 use crate::arch::interpreter::InterpretedInstance;
 #[cfg(feature = "metrics")]
@@ -216,9 +215,11 @@ pub trait PreflightExecutor<F, RA = MatrixRecordArena<F>> {
 #[derive(derive_new::new)]
 pub struct VmStateMut<'a, F, MEM, RA> {
     pub pc: &'a mut u32,
+    /// Number of instructions executed before the current instruction.
+    pub instret: u64,
     pub memory: &'a mut MEM,
     pub streams: &'a mut Streams<F>,
-    pub rng: &'a mut StdRng,
+    pub rng: &'a mut VmRng,
     /// Custom public values to be set by the system PublicValuesExecutor
     pub(crate) custom_pvs: &'a mut Vec<Option<F>>,
     pub ctx: &'a mut RA,
@@ -450,7 +451,7 @@ pub trait PhantomSubExecutor<F>: Send + Sync {
         &self,
         memory: &GuestMemory,
         streams: &mut Streams<F>,
-        rng: &mut StdRng,
+        rng: &mut VmRng,
         discriminant: PhantomDiscriminant,
         a: u32,
         b: u32,
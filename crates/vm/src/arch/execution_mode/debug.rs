@@ -0,0 +1,54 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    arch::{execution_mode::ExecutionCtxTrait, VmExecState},
+    system::memory::online::GuestMemory,
+};
+
+/// Execution context for interactive debugging. Behaves like pure execution, but suspends before
+/// executing an instruction whose pc is a breakpoint.
+#[derive(Clone, Debug, Default)]
+pub struct DebugCtx {
+    pub breakpoints: BTreeSet<u32>,
+    pub instret_end: u64,
+    /// Instret at which execution was resumed. A breakpoint at the resumption pc does not suspend
+    /// execution again until at least one instruction has been executed.
+    pub(crate) instret_start: u64,
+}
+
+impl DebugCtx {
+    pub fn new(breakpoints: BTreeSet<u32>, instret_end: Option<u64>) -> Self {
+        Self {
+            breakpoints,
+            instret_end: instret_end.unwrap_or(u64::MAX),
+            instret_start: 0,
+        }
+    }
+}
+
+impl ExecutionCtxTrait for DebugCtx {
+    #[inline(always)]
+    fn on_memory_operation(&mut self, _address_space: u32, _ptr: u32, _size: u32) {}
+
+    #[inline(always)]
+    fn should_suspend<F>(
+        instret: u64,
+        pc: u32,
+        instret_end: u64,
+        exec_state: &mut VmExecState<F, GuestMemory, Self>,
+    ) -> bool {
+        instret >= instret_end
+            || (instret != exec_state.ctx.instret_start && exec_state.ctx.breakpoints.contains(&pc))
+    }
+}
+
+/// Reason why debug execution returned control to the caller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugStop {
+    /// Execution reached a breakpoint. The instruction at the breakpoint has not been executed.
+    Breakpoint(u32),
+    /// The requested number of instructions has been executed.
+    InstretLimit,
+    /// The program terminated with the given exit code.
+    Terminated(u32),
+}
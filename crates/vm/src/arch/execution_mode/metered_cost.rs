@@ -35,6 +35,23 @@ impl AccessAdapterCtx {
         address_space: u32,
         size_bits: u32,
         widths: &[usize],
+    ) {
+        self.for_each_height_change(address_space, size_bits, |adapter_idx, height_delta| {
+            debug_assert!(adapter_idx < widths.len());
+            // SAFETY: widths is initialized taking access adapters into account
+            let width = unsafe { *widths.get_unchecked(adapter_idx) };
+            *cost += (height_delta as u64) * (width as u64);
+        });
+    }
+
+    /// Calls `f` with the air index and height delta of every access adapter used by a memory
+    /// access of `1 << size_bits` cells in `address_space`.
+    #[inline(always)]
+    pub fn for_each_height_change(
+        &self,
+        address_space: u32,
+        size_bits: u32,
+        mut f: impl FnMut(usize, u32),
     ) {
         debug_assert!((address_space as usize) < self.min_block_size_bits.len());
 
@@ -54,11 +71,8 @@ impl AccessAdapterCtx {
 
         for adapter_bits in (align_bits as u32 + 1..=size_bits).rev() {
             let adapter_idx = self.idx_offset + adapter_bits as usize - 1;
-            debug_assert!(adapter_idx < widths.len());
-            // SAFETY: widths is initialized taking access adapters into account
-            let width = unsafe { *widths.get_unchecked(adapter_idx) };
             let height_delta = 1 << (size_bits - adapter_bits + 1);
-            *cost += (height_delta as u64) * (width as u64);
+            f(adapter_idx, height_delta);
         }
     }
 }
@@ -1,18 +1,50 @@
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
 use crate::{arch::VmExecState, system::memory::online::GuestMemory};
 
+mod debug;
 pub mod metered;
 pub mod metered_cost;
 mod preflight;
+mod profile;
 mod pure;
+mod trace;
 
+pub use debug::{DebugCtx, DebugStop};
 pub use metered::{ctx::MeteredCtx, segment_ctx::Segment};
 pub use metered_cost::MeteredCostCtx;
 pub use preflight::PreflightCtx;
+pub use profile::{ProfileCtx, ProfileFrame, ProfileNode};
 pub use pure::ExecutionCtx;
+pub use trace::{TraceCtx, TraceMemoryAccess, TraceStep};
+
+/// Whether a memory access reads or writes the accessed cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryAccessKind {
+    Read,
+    Write,
+}
 
 pub trait ExecutionCtxTrait: Sized {
     fn on_memory_operation(&mut self, address_space: u32, ptr: u32, size: u32);
 
+    /// Called for every memory access with the accessed cells: the values read for a read, and the
+    /// values written for a write. Defaults to [Self::on_memory_operation], for contexts that only
+    /// need the address and size of the access.
+    #[inline(always)]
+    fn on_memory_access<T: Copy + Debug>(
+        &mut self,
+        _kind: MemoryAccessKind,
+        address_space: u32,
+        ptr: u32,
+        data: &[T],
+    ) {
+        self.on_memory_operation(address_space, ptr, data.len() as u32);
+    }
+
     fn should_suspend<F>(
         instret: u64,
         pc: u32,
@@ -0,0 +1,333 @@
+use std::{collections::HashMap, num::NonZero};
+
+use openvm_instructions::{
+    exe::{FnBound, FnBounds},
+    program::Program,
+    LocalOpcode, SysPhantom, SystemOpcode,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use crate::{
+    arch::{
+        execution_mode::{metered_cost::AccessAdapterCtx, ExecutionCtxTrait},
+        MeteredExecutionCtxTrait, SystemConfig, VmExecState,
+    },
+    system::memory::online::GuestMemory,
+};
+
+/// Frame of a call stack in the call tree built during profiling.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProfileFrame {
+    /// Function with the given start address in [ProfileCtx::fn_bounds].
+    Function(u32),
+    /// Cycle tracker span between `CtStart` and `CtEnd` phantom instructions, with the given
+    /// index in [ProfileCtx::span_names].
+    Span(usize),
+}
+
+/// Node of the call tree built during profiling.
+#[derive(Clone, Debug, Default)]
+pub struct ProfileNode {
+    /// Index of the parent, `None` for the root.
+    pub parent: Option<usize>,
+    /// `None` for the root, which collects the instructions executed before any known function is
+    /// entered.
+    pub frame: Option<ProfileFrame>,
+    /// Number of instructions executed in this frame with this call stack, excluding callees.
+    pub instructions: u64,
+    /// Trace rows added by those instructions, indexed by air. Empty if trace cells are not
+    /// profiled.
+    pub rows: Vec<u64>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum SpanOp {
+    Start(usize),
+    End,
+}
+
+/// Execution context which builds a call tree of the functions in `fn_bounds` and of cycle
+/// tracker spans, weighted by the number of executed instructions and optionally by the trace
+/// rows added per air.
+///
+/// A function is entered when the pc jumps to its start address, which treats tail calls as calls,
+/// and returned from when the pc moves to a function further up the call stack. Spans are only
+/// tracked by a context created with [with_spans](Self::with_spans), and trace rows are only
+/// profiled with a metered interpreter and a context created by [with_cells](Self::with_cells).
+#[derive(Clone, Debug)]
+pub struct ProfileCtx {
+    pub instret_end: u64,
+    pub fn_bounds: FnBounds,
+    pub span_names: Vec<String>,
+    pub nodes: Vec<ProfileNode>,
+    /// Names of the airs that [ProfileNode::rows] is indexed by.
+    pub air_names: Vec<String>,
+    /// Total widths of the airs.
+    pub widths: Vec<usize>,
+    /// Number of interactions per row of the airs.
+    pub interactions: Vec<usize>,
+    access_adapter_ctx: Option<AccessAdapterCtx>,
+    children: HashMap<(usize, ProfileFrame), usize>,
+    /// Nodes of the current call stack, from the root.
+    stack: Vec<usize>,
+    /// Bounds of the innermost function on the call stack.
+    current_fn: Option<(u32, u32)>,
+    /// Span operations by pc.
+    span_ops: HashMap<u32, SpanOp>,
+}
+
+impl ProfileCtx {
+    pub fn new(fn_bounds: FnBounds) -> Self {
+        Self {
+            instret_end: u64::MAX,
+            fn_bounds,
+            span_names: Vec::new(),
+            nodes: vec![ProfileNode::default()],
+            air_names: Vec::new(),
+            widths: Vec::new(),
+            interactions: Vec::new(),
+            access_adapter_ctx: None,
+            children: HashMap::new(),
+            stack: vec![0],
+            current_fn: None,
+            span_ops: HashMap::new(),
+        }
+    }
+
+    /// Also track the cycle tracker spans of `program`. A span is named by the DSL instruction in
+    /// the debug info of its `CtStart` instruction, without the `CT-` prefix.
+    pub fn with_spans<F: PrimeField32>(mut self, program: &Program<F>) -> Self {
+        let phantom_opcode = SystemOpcode::PHANTOM.global_opcode();
+        for (pc, instruction, debug_info) in program.enumerate_by_pc() {
+            if instruction.opcode != phantom_opcode {
+                continue;
+            }
+            let op = match SysPhantom::from_repr(instruction.c.as_canonical_u32() as u16) {
+                Some(SysPhantom::CtStart) => {
+                    let name = match debug_info {
+                        Some(info) => info
+                            .dsl_instruction
+                            .strip_prefix("CT-")
+                            .unwrap_or(&info.dsl_instruction)
+                            .to_string(),
+                        None => format!("span@{pc:#x}"),
+                    };
+                    let idx = match self.span_names.iter().position(|n| *n == name) {
+                        Some(idx) => idx,
+                        None => {
+                            self.span_names.push(name);
+                            self.span_names.len() - 1
+                        }
+                    };
+                    SpanOp::Start(idx)
+                }
+                Some(SysPhantom::CtEnd) => SpanOp::End,
+                _ => continue,
+            };
+            self.span_ops.insert(pc, op);
+        }
+        self
+    }
+
+    /// Also profile trace rows, where `air_names`, `widths` and `interactions` are the names,
+    /// total widths and numbers of interactions of the airs.
+    pub fn with_cells(
+        mut self,
+        air_names: Vec<String>,
+        widths: Vec<usize>,
+        interactions: Vec<usize>,
+        config: &SystemConfig,
+    ) -> Self {
+        assert_eq!(air_names.len(), widths.len());
+        assert_eq!(air_names.len(), interactions.len());
+        self.nodes[0].rows = vec![0; widths.len()];
+        self.air_names = air_names;
+        self.widths = widths;
+        self.interactions = interactions;
+        self.access_adapter_ctx = Some(AccessAdapterCtx::new(config));
+        self
+    }
+
+    pub fn profiles_cells(&self) -> bool {
+        self.access_adapter_ctx.is_some()
+    }
+
+    /// Trace cells added by the instructions of `node`, indexed by air.
+    pub fn cells<'a>(&'a self, node: &'a ProfileNode) -> impl Iterator<Item = u64> + 'a {
+        node.rows
+            .iter()
+            .zip(&self.widths)
+            .map(|(&rows, &width)| rows * width as u64)
+    }
+
+    /// Interactions added by the instructions of `node`, indexed by air.
+    pub fn interactions<'a>(&'a self, node: &'a ProfileNode) -> impl Iterator<Item = u64> + 'a {
+        node.rows
+            .iter()
+            .zip(&self.interactions)
+            .map(|(&rows, &interactions)| rows * interactions as u64)
+    }
+
+    pub fn frame_name(&self, frame: ProfileFrame) -> &str {
+        match frame {
+            ProfileFrame::Function(start) => &self.fn_bounds[&start].name,
+            ProfileFrame::Span(idx) => &self.span_names[idx],
+        }
+    }
+
+    /// Frames on the call stack of `node`, from the outermost caller.
+    pub fn stack(&self, node: usize) -> Vec<ProfileFrame> {
+        let mut frames = Vec::new();
+        let mut next = Some(node);
+        while let Some(idx) = next {
+            let node = &self.nodes[idx];
+            frames.extend(node.frame);
+            next = node.parent;
+        }
+        frames.reverse();
+        frames
+    }
+
+    /// Names of the frames on the call stack of `node`, from the outermost caller.
+    pub fn stack_names(&self, node: usize) -> Vec<&str> {
+        self.stack(node)
+            .into_iter()
+            .map(|frame| self.frame_name(frame))
+            .collect()
+    }
+
+    fn current_node(&self) -> usize {
+        *self.stack.last().unwrap()
+    }
+
+    fn lookup(&self, pc: u32) -> Option<&FnBound> {
+        self.fn_bounds
+            .range(..=pc)
+            .next_back()
+            .map(|(_, bound)| bound)
+            .filter(|bound| pc <= bound.end)
+    }
+
+    fn child(&mut self, parent: usize, frame: ProfileFrame) -> usize {
+        *self.children.entry((parent, frame)).or_insert_with(|| {
+            self.nodes.push(ProfileNode {
+                parent: Some(parent),
+                frame: Some(frame),
+                instructions: 0,
+                rows: vec![0; self.widths.len()],
+            });
+            self.nodes.len() - 1
+        })
+    }
+
+    #[cold]
+    fn update_stack(&mut self, pc: u32) {
+        let Some((start, end)) = self.lookup(pc).map(|bound| (bound.start, bound.end)) else {
+            self.current_fn = None;
+            return;
+        };
+        self.current_fn = Some((start, end));
+        let frame = ProfileFrame::Function(start);
+        if pc == start {
+            let node = self.child(self.current_node(), frame);
+            self.stack.push(node);
+            return;
+        }
+        // Return to the innermost caller in the function, or treat the jump as a call from the
+        // root if the function is not on the call stack
+        match self
+            .stack
+            .iter()
+            .rposition(|&node| self.nodes[node].frame == Some(frame))
+        {
+            Some(depth) => self.stack.truncate(depth + 1),
+            None => {
+                let node = self.child(0, frame);
+                self.stack = vec![0, node];
+            }
+        }
+    }
+
+    /// Enters a span at its `CtStart` instruction, and leaves the innermost span at a `CtEnd`
+    /// instruction.
+    fn update_spans(&mut self, pc: u32) {
+        match self.span_ops.get(&pc) {
+            Some(&SpanOp::Start(idx)) => {
+                let node = self.child(self.current_node(), ProfileFrame::Span(idx));
+                self.stack.push(node);
+            }
+            Some(SpanOp::End) => {
+                if let Some(depth) = self.stack.iter().rposition(|&node| {
+                    matches!(self.nodes[node].frame, Some(ProfileFrame::Span(_)))
+                }) {
+                    self.stack.truncate(depth);
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+impl ExecutionCtxTrait for ProfileCtx {
+    #[inline(always)]
+    fn on_memory_operation(&mut self, address_space: u32, _ptr: u32, size: u32) {
+        let Self {
+            access_adapter_ctx: Some(access_adapter_ctx),
+            nodes,
+            stack,
+            ..
+        } = self
+        else {
+            return;
+        };
+        let rows = &mut nodes[*stack.last().unwrap()].rows;
+        // SAFETY: size passed is always a non-zero power of 2
+        let size_bits = unsafe { NonZero::new_unchecked(size).ilog2() };
+        access_adapter_ctx.for_each_height_change(
+            address_space,
+            size_bits,
+            |adapter_idx, height_delta| {
+                if let Some(rows) = rows.get_mut(adapter_idx) {
+                    *rows += height_delta as u64;
+                }
+            },
+        );
+    }
+
+    #[inline(always)]
+    fn should_suspend<F>(
+        instret: u64,
+        pc: u32,
+        instret_end: u64,
+        exec_state: &mut VmExecState<F, GuestMemory, Self>,
+    ) -> bool {
+        if instret >= instret_end {
+            return true;
+        }
+        let ctx = &mut exec_state.ctx;
+        if !ctx
+            .current_fn
+            .is_some_and(|(start, end)| start < pc && pc <= end)
+        {
+            ctx.update_stack(pc);
+        }
+        if !ctx.span_ops.is_empty() {
+            ctx.update_spans(pc);
+        }
+        let node = ctx.current_node();
+        ctx.nodes[node].instructions += 1;
+        false
+    }
+}
+
+impl MeteredExecutionCtxTrait for ProfileCtx {
+    #[inline(always)]
+    fn on_height_change(&mut self, chip_idx: usize, height_delta: u32) {
+        // Rows are only tracked by a context created with `with_cells`, and airs outside of the
+        // widths given there are skipped
+        let node = self.current_node();
+        if let Some(rows) = self.nodes[node].rows.get_mut(chip_idx) {
+            *rows += height_delta as u64;
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::{
+    fmt::Debug,
+    mem::{size_of, size_of_val},
+};
+
+use openvm_instructions::riscv::{RV32_NUM_REGISTERS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS};
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    arch::{
+        execution_mode::{ExecutionCtxTrait, MemoryAccessKind},
+        MemoryCellType, VmExecState,
+    },
+    system::memory::online::GuestMemory,
+};
+
+/// Number of finished steps after which execution is suspended so that the steps can be passed on
+/// to the caller of
+/// [`execute_trace`](crate::arch::interpreter::InterpretedInstance::execute_trace).
+pub(crate) const TRACE_FLUSH_STEPS: usize = 1 << 12;
+
+/// Memory access made by an instruction through the execution context, which includes register
+/// accesses.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceMemoryAccess {
+    pub kind: MemoryAccessKind,
+    pub address_space: u32,
+    pub ptr: u32,
+    /// Values of the accessed cells, which are the values read for a read and the values written
+    /// for a write. Field elements of native address spaces are in canonical form.
+    pub values: Vec<u32>,
+}
+
+/// Record of a single executed instruction.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceStep {
+    /// Number of instructions executed before this one.
+    pub instret: u64,
+    pub pc: u32,
+    /// RISC-V registers whose value changed, as `(index, new value)`.
+    pub register_writes: Vec<(u8, u32)>,
+    /// Memory accesses in the order they were made.
+    pub memory_accesses: Vec<TraceMemoryAccess>,
+}
+
+/// Execution context which behaves like pure execution, but records a [TraceStep] for every
+/// executed instruction. Only the steps since the last flush are kept in `steps`.
+///
+/// Register writes are found by comparing the RISC-V registers before and after each instruction,
+/// so they are only recorded when the register address space consists of byte cells.
+#[derive(Clone, Debug, Default)]
+pub struct TraceCtx {
+    pub instret_end: u64,
+    pub steps: Vec<TraceStep>,
+    pub(crate) track_registers: bool,
+    /// Registers before the last step in `steps`, if it has not been finished yet.
+    registers: Option<[u32; RV32_NUM_REGISTERS]>,
+    /// Cell type of each address space, used to convert the accessed cells to values.
+    layouts: Vec<MemoryCellType>,
+    /// Converts the bytes of a native field element in memory to its canonical value.
+    native_value: Option<fn(&[u8]) -> u32>,
+}
+
+impl TraceCtx {
+    pub fn new(instret_end: Option<u64>) -> Self {
+        Self {
+            instret_end: instret_end.unwrap_or(u64::MAX),
+            ..Default::default()
+        }
+    }
+
+    /// Reads the layout of the address spaces of `memory`, which determines how accessed cells
+    /// are converted to values and whether register writes can be tracked.
+    pub(crate) fn init<F: PrimeField32>(&mut self, memory: &GuestMemory) {
+        self.layouts = memory
+            .memory
+            .config
+            .iter()
+            .map(|config| config.layout)
+            .collect();
+        self.native_value = Some(native_value::<F>);
+        self.track_registers = memory
+            .memory
+            .config
+            .get(RV32_REGISTER_AS as usize)
+            .is_some_and(|config| {
+                config.layout == MemoryCellType::U8
+                    && config.num_cells >= RV32_NUM_REGISTERS * RV32_REGISTER_NUM_LIMBS
+            });
+    }
+
+    /// Records the register writes of the last step, if it has not been finished yet.
+    pub(crate) fn finish_step(&mut self, memory: &GuestMemory) {
+        let Some(before) = self.registers.take() else {
+            return;
+        };
+        let after = read_registers(memory);
+        if let Some(step) = self.steps.last_mut() {
+            step.register_writes = (0..RV32_NUM_REGISTERS)
+                .filter(|&i| before[i] != after[i])
+                .map(|i| (i as u8, after[i]))
+                .collect();
+        }
+    }
+
+    fn start_step(&mut self, instret: u64, pc: u32, memory: &GuestMemory) {
+        self.steps.push(TraceStep {
+            instret,
+            pc,
+            ..Default::default()
+        });
+        if self.track_registers {
+            self.registers = Some(read_registers(memory));
+        }
+    }
+
+    fn cell_values<T: Copy>(&self, address_space: u32, data: &[T]) -> Vec<u32> {
+        let cell_size = size_of::<T>();
+        // SAFETY: `data` is a slice of plain `Copy` memory cells, so its bytes are initialized
+        let bytes =
+            unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, size_of_val(data)) };
+        let layout = self.layouts.get(address_space as usize).copied();
+        bytes
+            .chunks_exact(cell_size)
+            .map(|cell| match (layout, cell_size) {
+                (Some(MemoryCellType::Native { .. }), _) => self
+                    .native_value
+                    .map_or(0, |native_value| native_value(cell)),
+                (_, 1) => cell[0] as u32,
+                (_, 2) => u16::from_le_bytes(cell.try_into().unwrap()) as u32,
+                _ => u32::from_le_bytes(cell[..4].try_into().unwrap()),
+            })
+            .collect()
+    }
+}
+
+fn native_value<F: PrimeField32>(bytes: &[u8]) -> u32 {
+    debug_assert_eq!(bytes.len(), size_of::<F>());
+    // SAFETY: native address spaces store field elements of type `F`, and `bytes` is one cell
+    unsafe { (bytes.as_ptr() as *const F).read_unaligned() }.as_canonical_u32()
+}
+
+fn read_registers(memory: &GuestMemory) -> [u32; RV32_NUM_REGISTERS] {
+    // SAFETY: `track_registers` is only set if the register address space consists of byte cells
+    // and contains all registers
+    let bytes = unsafe {
+        memory.memory.get_u8_slice(
+            RV32_REGISTER_AS,
+            0,
+            RV32_NUM_REGISTERS * RV32_REGISTER_NUM_LIMBS,
+        )
+    };
+    let mut registers = [0u32; RV32_NUM_REGISTERS];
+    for (register, limbs) in registers
+        .iter_mut()
+        .zip(bytes.chunks_exact(RV32_REGISTER_NUM_LIMBS))
+    {
+        *register = u32::from_le_bytes(limbs.try_into().unwrap());
+    }
+    registers
+}
+
+impl ExecutionCtxTrait for TraceCtx {
+    #[inline(always)]
+    fn on_memory_operation(&mut self, _address_space: u32, _ptr: u32, _size: u32) {}
+
+    #[inline(always)]
+    fn on_memory_access<T: Copy + Debug>(
+        &mut self,
+        kind: MemoryAccessKind,
+        address_space: u32,
+        ptr: u32,
+        data: &[T],
+    ) {
+        let values = self.cell_values(address_space, data);
+        if let Some(step) = self.steps.last_mut() {
+            step.memory_accesses.push(TraceMemoryAccess {
+                kind,
+                address_space,
+                ptr,
+                values,
+            });
+        }
+    }
+
+    #[inline(always)]
+    fn should_suspend<F>(
+        instret: u64,
+        pc: u32,
+        instret_end: u64,
+        exec_state: &mut VmExecState<F, GuestMemory, Self>,
+    ) -> bool {
+        let memory = &exec_state.vm_state.memory;
+        let ctx = &mut exec_state.ctx;
+        ctx.finish_step(memory);
+        if instret >= instret_end || ctx.steps.len() >= TRACE_FLUSH_STEPS {
+            return true;
+        }
+        ctx.start_step(instret, pc, memory);
+        false
+    }
+
+    fn on_terminate<F>(
+        _instret: u64,
+        _pc: u32,
+        exec_state: &mut VmExecState<F, GuestMemory, Self>,
+    ) {
+        exec_state.ctx.finish_step(&exec_state.vm_state.memory);
+    }
+}
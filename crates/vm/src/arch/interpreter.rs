@@ -3,6 +3,7 @@ use std::marker::PhantomData;
 use std::{
     alloc::{alloc, dealloc, handle_alloc_error, Layout},
     borrow::{Borrow, BorrowMut},
+    collections::BTreeSet,
     iter::repeat_n,
     ptr::NonNull,
 };
@@ -23,8 +24,8 @@ use crate::arch::Handler;
 use crate::{
     arch::{
         execution_mode::{
-            ExecutionCtx, ExecutionCtxTrait, MeteredCostCtx, MeteredCtx, MeteredExecutionCtxTrait,
-            Segment,
+            DebugCtx, DebugStop, ExecutionCtx, ExecutionCtxTrait, MeteredCostCtx, MeteredCtx,
+            MeteredExecutionCtxTrait, ProfileCtx, Segment, TraceCtx, TraceStep,
         },
         ExecuteFunc, ExecutionError, Executor, ExecutorInventory, ExitCode, MeteredExecutor,
         StaticProgramError, Streams, SystemConfig, VmExecState, VmState,
@@ -394,6 +395,163 @@ where
     }
 }
 
+impl<F> InterpretedInstance<'_, F, DebugCtx>
+where
+    F: PrimeField32,
+{
+    /// Debug execution from the given `VmState`. Executes the program until it terminates, until
+    /// `num_insns` instructions have been executed if `num_insns` is `Some`, or until the pc
+    /// reaches one of the `breakpoints`. A breakpoint at the pc of `from_state` is ignored so that
+    /// execution can be resumed from a breakpoint.
+    ///
+    /// Unlike the other execution modes, the VM state is returned even if execution fails so that
+    /// it can still be inspected.
+    pub fn execute_debug_from_state(
+        &self,
+        from_state: VmState<F, GuestMemory>,
+        breakpoints: &BTreeSet<u32>,
+        num_insns: Option<u64>,
+    ) -> (VmState<F, GuestMemory>, Result<DebugStop, ExecutionError>) {
+        let instret = from_state.instret();
+        let instret_end = num_insns.map(|n| instret.saturating_add(n));
+        let mut ctx = DebugCtx::new(breakpoints.clone(), instret_end);
+        ctx.instret_start = instret;
+        let mut exec_state = VmExecState::new(from_state, ctx);
+
+        let pc = exec_state.pc();
+        let instret_end = exec_state.ctx.instret_end;
+        let result = (|| -> Result<(), ExecutionError> {
+            run!(
+                "execute_debug",
+                self,
+                instret,
+                pc,
+                instret_end,
+                exec_state,
+                DebugCtx
+            );
+            Ok(())
+        })();
+        let VmExecState {
+            vm_state,
+            exit_code,
+            ..
+        } = exec_state;
+        let stop = result.and(exit_code).map(|exit_code| match exit_code {
+            Some(exit_code) => DebugStop::Terminated(exit_code),
+            None if vm_state.instret() >= instret_end => DebugStop::InstretLimit,
+            None => DebugStop::Breakpoint(vm_state.pc()),
+        });
+        (vm_state, stop)
+    }
+}
+
+impl<F> InterpretedInstance<'_, F, TraceCtx>
+where
+    F: PrimeField32,
+{
+    /// Executes the program from the initial state for the given `inputs`, passing a
+    /// [TraceStep] for every executed instruction to `on_step` as execution proceeds, so that the
+    /// trace is never held in memory as a whole. Execution stops at termination, or after
+    /// `num_insns` instructions if specified.
+    ///
+    /// The steps executed so far are passed on even if execution fails, so that the trace leading
+    /// up to the failure can be inspected. The outer error is the first error returned by
+    /// `on_step`, which stops execution, and the inner result is the result of execution.
+    pub fn execute_trace<E>(
+        &self,
+        inputs: impl Into<Streams<F>>,
+        num_insns: Option<u64>,
+        mut on_step: impl FnMut(TraceStep) -> Result<(), E>,
+    ) -> Result<Result<VmState<F, GuestMemory>, ExecutionError>, E> {
+        let from_state = self.create_initial_vm_state(inputs);
+        let mut ctx = TraceCtx::new(num_insns);
+        ctx.init::<F>(&from_state.memory);
+        let mut exec_state = VmExecState::new(from_state, ctx);
+
+        let instret_end = exec_state.ctx.instret_end;
+        let result = loop {
+            let instret = exec_state.instret();
+            let pc = exec_state.pc();
+            let result = (|| -> Result<(), ExecutionError> {
+                run!(
+                    "execute_trace",
+                    self,
+                    instret,
+                    pc,
+                    instret_end,
+                    exec_state,
+                    TraceCtx
+                );
+                Ok(())
+            })();
+            // Execution is also suspended every time the context has buffered enough steps, in
+            // which case it continues after the steps are flushed
+            let done = result.is_err()
+                || !matches!(exec_state.exit_code, Ok(None))
+                || exec_state.instret() >= instret_end;
+            if done {
+                // The last step is not finished if execution failed during it
+                exec_state.ctx.finish_step(&exec_state.vm_state.memory);
+            }
+            for step in exec_state.ctx.steps.drain(..) {
+                on_step(step)?;
+            }
+            if done {
+                break result;
+            }
+        };
+        let VmExecState {
+            vm_state,
+            exit_code,
+            ..
+        } = exec_state;
+        Ok(result.and_then(|()| {
+            if num_insns.is_some() {
+                check_exit_code(exit_code)?;
+            } else {
+                check_termination(exit_code)?;
+            }
+            Ok(vm_state)
+        }))
+    }
+}
+
+impl<F> InterpretedInstance<'_, F, ProfileCtx>
+where
+    F: PrimeField32,
+{
+    /// Executes the program from the initial state for the given `inputs` until termination,
+    /// building the call tree in `ctx`. Trace cells are only profiled if the instance was created
+    /// for metered execution and `ctx` was created with [ProfileCtx::with_cells].
+    ///
+    /// Returns the profile and final VM state when execution stops.
+    pub fn execute_profile(
+        &self,
+        inputs: impl Into<Streams<F>>,
+        ctx: ProfileCtx,
+    ) -> Result<(ProfileCtx, VmState<F, GuestMemory>), ExecutionError> {
+        let from_state = self.create_initial_vm_state(inputs);
+        let mut exec_state = VmExecState::new(from_state, ctx);
+
+        let instret = exec_state.instret();
+        let pc = exec_state.pc();
+        let instret_end = exec_state.ctx.instret_end;
+        run!(
+            "execute_profile",
+            self,
+            instret,
+            pc,
+            instret_end,
+            exec_state,
+            ProfileCtx
+        );
+        check_termination(exec_state.exit_code)?;
+        let VmExecState { ctx, vm_state, .. } = exec_state;
+        Ok((ctx, vm_state))
+    }
+}
+
 impl<F> InterpretedInstance<'_, F, MeteredCtx>
 where
     F: PrimeField32,
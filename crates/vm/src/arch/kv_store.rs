@@ -0,0 +1,405 @@
+//! [KvStore] backends which load values lazily, so that a large key-value store does not need to
+//! be read into memory before execution when the guest only looks up a few keys.
+
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use memmap2::Mmap;
+
+use super::KvStore;
+
+impl<T: KvStore + ?Sized> KvStore for Arc<T> {
+    fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.as_ref().get(key)
+    }
+
+    fn load(&self, key: &[u8]) -> Option<Cow<'_, [u8]>> {
+        self.as_ref().load(key)
+    }
+
+    fn entries(&self) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+        self.as_ref().entries()
+    }
+}
+
+/// [KvStore] which looks up a key in each of its stores in order and returns the first value
+/// found.
+#[derive(Clone, Default)]
+pub struct ChainedKvStore(pub Vec<Arc<dyn KvStore>>);
+
+impl KvStore for ChainedKvStore {
+    fn load(&self, key: &[u8]) -> Option<Cow<'_, [u8]>> {
+        self.0.iter().find_map(|store| store.load(key))
+    }
+
+    /// Returns the entries of all stores, where a key takes its value from the first store which
+    /// contains it.
+    fn entries(&self) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+        let mut entries = BTreeMap::new();
+        for store in self.0.iter().rev() {
+            entries.extend(store.entries()?);
+        }
+        Some(entries.into_iter().collect())
+    }
+}
+
+/// [KvStore] which calls a closure on every lookup, for example to fetch values from a database.
+pub struct FnKvStore<G>(pub G);
+
+impl<G> KvStore for FnKvStore<G>
+where
+    G: Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync,
+{
+    fn load(&self, key: &[u8]) -> Option<Cow<'_, [u8]>> {
+        (self.0)(key).map(Cow::Owned)
+    }
+}
+
+/// [KvStore] backed by a directory with one file per key. The file name is the lowercase hex
+/// encoding of the key and the file contents are the value. Values are read from disk on every
+/// lookup.
+///
+/// Keys longer than [DirKvStore::MAX_KEY_LEN] bytes are not supported, since their file names
+/// would exceed the limit of common file systems.
+#[derive(Clone, Debug)]
+pub struct DirKvStore {
+    dir: PathBuf,
+}
+
+impl DirKvStore {
+    /// Maximum length of a key in bytes, so that its file name fits in 255 bytes.
+    pub const MAX_KEY_LEN: usize = 127;
+
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path of the file holding the value of `key`, or an error if the key is longer
+    /// than [DirKvStore::MAX_KEY_LEN].
+    pub fn path_of(&self, key: &[u8]) -> io::Result<PathBuf> {
+        if key.len() > Self::MAX_KEY_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "kv_store key of {} bytes exceeds the maximum of {} bytes",
+                    key.len(),
+                    Self::MAX_KEY_LEN
+                ),
+            ));
+        }
+        let file_name: String = key.iter().map(|b| format!("{b:02x}")).collect();
+        Ok(self.dir.join(file_name))
+    }
+
+    /// Writes `value` as the value of `key`, creating the directory if it does not exist.
+    pub fn insert(&self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let path = self.path_of(key)?;
+        fs::create_dir_all(&self.dir)?;
+        fs::write(path, value)
+    }
+}
+
+impl KvStore for DirKvStore {
+    fn load(&self, key: &[u8]) -> Option<Cow<'_, [u8]>> {
+        let path = match self.path_of(key) {
+            Ok(path) => path,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to look up key in kv_store at {}: {e}",
+                    self.dir.display()
+                );
+                return None;
+            }
+        };
+        match fs::read(&path) {
+            Ok(value) => Some(Cow::Owned(value)),
+            Err(e) => {
+                if e.kind() != io::ErrorKind::NotFound {
+                    tracing::warn!("Failed to read kv_store value at {}: {e}", path.display());
+                }
+                None
+            }
+        }
+    }
+
+    /// Returns the entries of all files in the directory whose name is a valid key, or `None` if
+    /// the directory cannot be read.
+    fn entries(&self) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+        let read_dir = match fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Some(Vec::new()),
+            Err(e) => {
+                tracing::warn!("Failed to list kv_store at {}: {e}", self.dir.display());
+                return None;
+            }
+        };
+        let mut entries = Vec::new();
+        for entry in read_dir {
+            let entry = entry.ok()?;
+            let Some(key) = entry.file_name().to_str().and_then(decode_hex) else {
+                continue;
+            };
+            entries.push((key, fs::read(entry.path()).ok()?));
+        }
+        Some(entries)
+    }
+}
+
+/// Decodes a lowercase hex string, as written by [DirKvStore::path_of].
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 || !s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
+
+const MMAP_KV_MAGIC: [u8; 8] = *b"OVMKV\0\0\x01";
+const MMAP_KV_HEADER_SIZE: usize = 16;
+const MMAP_KV_INDEX_ENTRY_SIZE: usize = 24;
+
+/// [KvStore] backed by a memory-mapped file of key-value pairs sorted by key. Lookups are a
+/// binary search over the index, and values are read directly from the mapped file.
+///
+/// The file layout, with all integers in little-endian, is:
+/// - 8 magic bytes `OVMKV\0\0\x01`, followed by the number of entries `n` as a `u64`;
+/// - `n` index entries sorted by key, each consisting of the key offset (`u64`), key length
+///   (`u32`), value offset (`u64`) and value length (`u32`), with offsets relative to the start of
+///   the file;
+/// - the key and value bytes.
+///
+/// Such a file can be written with [MmapKvStore::write_file].
+pub struct MmapKvStore {
+    mmap: Mmap,
+    num_entries: usize,
+}
+
+impl MmapKvStore {
+    /// Maps the file at `path` and validates its header and index.
+    ///
+    /// The file must not be modified while it is mapped.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the file is only read through the mapping, and the caller ensures it is not
+        // modified while mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+        if mmap.len() < MMAP_KV_HEADER_SIZE || mmap[..8] != MMAP_KV_MAGIC {
+            return Err(invalid_data("missing kv_store file header"));
+        }
+        let num_entries = u64::from_le_bytes(mmap[8..16].try_into().unwrap()) as usize;
+        let index_end = num_entries
+            .checked_mul(MMAP_KV_INDEX_ENTRY_SIZE)
+            .and_then(|len| len.checked_add(MMAP_KV_HEADER_SIZE))
+            .ok_or_else(|| invalid_data("kv_store index is too large"))?;
+        if index_end > mmap.len() {
+            return Err(invalid_data("kv_store index is out of bounds"));
+        }
+        let store = Self { mmap, num_entries };
+        for i in 0..num_entries {
+            let (key, value) = store.ranges(i);
+            if key.end > store.mmap.len() || value.end > store.mmap.len() {
+                return Err(invalid_data("kv_store entry is out of bounds"));
+            }
+            if i > 0 && store.key(i - 1) >= store.key(i) {
+                return Err(invalid_data("kv_store keys are not strictly increasing"));
+            }
+        }
+        Ok(store)
+    }
+
+    /// Writes `entries` to `path` in the format read by [MmapKvStore::open]. Returns an error if
+    /// a key occurs more than once.
+    pub fn write_file<K, V>(
+        path: impl AsRef<Path>,
+        entries: impl IntoIterator<Item = (K, V)>,
+    ) -> io::Result<()>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let mut entries: Vec<(K, V)> = entries.into_iter().collect();
+        entries.sort_by(|(a, _), (b, _)| a.as_ref().cmp(b.as_ref()));
+        if entries
+            .windows(2)
+            .any(|pair| pair[0].0.as_ref() == pair[1].0.as_ref())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "duplicate kv_store key",
+            ));
+        }
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&MMAP_KV_MAGIC)?;
+        writer.write_all(&(entries.len() as u64).to_le_bytes())?;
+        let mut offset = (MMAP_KV_HEADER_SIZE + entries.len() * MMAP_KV_INDEX_ENTRY_SIZE) as u64;
+        for (key, value) in &entries {
+            let (key, value) = (key.as_ref(), value.as_ref());
+            writer.write_all(&offset.to_le_bytes())?;
+            writer.write_all(
+                &u32::try_from(key.len())
+                    .map_err(invalid_input)?
+                    .to_le_bytes(),
+            )?;
+            offset += key.len() as u64;
+            writer.write_all(&offset.to_le_bytes())?;
+            writer.write_all(
+                &u32::try_from(value.len())
+                    .map_err(invalid_input)?
+                    .to_le_bytes(),
+            )?;
+            offset += value.len() as u64;
+        }
+        for (key, value) in &entries {
+            writer.write_all(key.as_ref())?;
+            writer.write_all(value.as_ref())?;
+        }
+        writer.flush()
+    }
+
+    pub fn len(&self) -> usize {
+        self.num_entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_entries == 0
+    }
+
+    fn ranges(&self, i: usize) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+        let start = MMAP_KV_HEADER_SIZE + i * MMAP_KV_INDEX_ENTRY_SIZE;
+        let entry = &self.mmap[start..start + MMAP_KV_INDEX_ENTRY_SIZE];
+        let key_offset = u64::from_le_bytes(entry[0..8].try_into().unwrap()) as usize;
+        let key_len = u32::from_le_bytes(entry[8..12].try_into().unwrap()) as usize;
+        let value_offset = u64::from_le_bytes(entry[12..20].try_into().unwrap()) as usize;
+        let value_len = u32::from_le_bytes(entry[20..24].try_into().unwrap()) as usize;
+        (
+            key_offset..key_offset.saturating_add(key_len),
+            value_offset..value_offset.saturating_add(value_len),
+        )
+    }
+
+    fn key(&self, i: usize) -> &[u8] {
+        &self.mmap[self.ranges(i).0]
+    }
+}
+
+impl KvStore for MmapKvStore {
+    fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        let (mut lo, mut hi) = (0, self.num_entries);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match self.key(mid).cmp(key) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => {
+                    return Some(&self.mmap[self.ranges(mid).1]);
+                }
+            }
+        }
+        None
+    }
+
+    fn entries(&self) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+        Some(
+            (0..self.num_entries)
+                .map(|i| {
+                    let (key, value) = self.ranges(i);
+                    (self.mmap[key].to_vec(), self.mmap[value].to_vec())
+                })
+                .collect(),
+        )
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn invalid_input(e: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_mmap_kv_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kv.bin");
+        let entries = [
+            (b"b".to_vec(), vec![2u8; 3]),
+            (b"a".to_vec(), vec![1u8; 5]),
+            (b"ab".to_vec(), vec![]),
+        ];
+        MmapKvStore::write_file(&path, entries.clone()).unwrap();
+        let store = MmapKvStore::open(&path).unwrap();
+        assert_eq!(store.len(), 3);
+        for (key, value) in &entries {
+            assert_eq!(store.get(key), Some(value.as_slice()));
+            assert_eq!(store.load(key).as_deref(), Some(value.as_slice()));
+        }
+        assert!(store.get(b"c").is_none());
+        assert!(store.get(b"").is_none());
+        let mut sorted = entries.to_vec();
+        sorted.sort();
+        assert_eq!(store.entries(), Some(sorted));
+
+        assert!(MmapKvStore::write_file(&path, [(b"a", b"1"), (b"a", b"2")]).is_err());
+    }
+
+    #[test]
+    fn test_chained_kv_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_store = DirKvStore::new(dir.path().join("kv"));
+        dir_store.insert(b"key", b"from dir").unwrap();
+        dir_store.insert(b"other", b"other").unwrap();
+        let map = HashMap::from([(b"key".to_vec(), b"from map".to_vec())]);
+        let store = ChainedKvStore(vec![
+            Arc::new(map) as Arc<dyn KvStore>,
+            Arc::new(dir_store),
+            Arc::new(FnKvStore(|key: &[u8]| Some(key.to_vec()))),
+        ]);
+        assert_eq!(store.load(b"key").as_deref(), Some(b"from map".as_slice()));
+        assert_eq!(store.load(b"other").as_deref(), Some(b"other".as_slice()));
+        assert_eq!(store.load(b"echo").as_deref(), Some(b"echo".as_slice()));
+        // The closure store cannot list its keys
+        assert_eq!(store.entries(), None);
+        let store = ChainedKvStore(store.0[..2].to_vec());
+        assert_eq!(
+            store.entries(),
+            Some(vec![
+                (b"key".to_vec(), b"from map".to_vec()),
+                (b"other".to_vec(), b"other".to_vec()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_dir_kv_store_key_len() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DirKvStore::new(dir.path());
+        let key = [0xabu8; DirKvStore::MAX_KEY_LEN];
+        store.insert(&key, b"value").unwrap();
+        assert_eq!(store.load(&key).as_deref(), Some(b"value".as_slice()));
+
+        let long_key = [0xabu8; DirKvStore::MAX_KEY_LEN + 1];
+        let err = store.insert(&long_key, b"value").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(store.load(&long_key).is_none());
+    }
+}
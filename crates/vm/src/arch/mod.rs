@@ -7,6 +7,8 @@ pub mod execution_mode;
 mod extensions;
 /// Traits and wrappers to facilitate VM chip integration
 mod integration_api;
+/// Lazily loaded [KvStore] backends
+mod kv_store;
 /// [RecordArena] trait definitions and implementations. Currently there are two concrete
 /// implementations: [MatrixRecordArena] and [DenseRecordArena].
 mod record_arena;
@@ -30,6 +32,7 @@ pub use execution_mode::{ExecutionCtxTrait, MeteredExecutionCtxTrait};
 pub use extensions::*;
 pub use integration_api::*;
 pub use interpreter::InterpretedInstance;
+pub use kv_store::*;
 pub use openvm_circuit_derive::create_handler;
 pub use openvm_instructions as instructions;
 pub use record_arena::*;
@@ -4,21 +4,30 @@ use std::{
 };
 
 use eyre::eyre;
-use getset::{CopyGetters, MutGetters};
+use getset::{CopyGetters, Getters, MutGetters};
 use openvm_instructions::exe::SparseMemoryImage;
-use rand::{rngs::StdRng, SeedableRng};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
 use tracing::instrument;
 
 use super::{create_memory_image, ExecutionError, Streams};
 #[cfg(feature = "metrics")]
 use crate::metrics::VmMetrics;
 use crate::{
-    arch::{execution_mode::ExecutionCtxTrait, SystemConfig, VmStateMut},
+    arch::{
+        execution_mode::{ExecutionCtxTrait, MemoryAccessKind},
+        SystemConfig, VmStateMut,
+    },
     system::memory::online::GuestMemory,
 };
 
+/// RNG of the VM, which is the source of randomness of hints such as `hintrandom`. This is the
+/// algorithm behind `rand::rngs::StdRng`, fixed so that execution stays deterministic across
+/// versions of `rand` and so that the position of the RNG can be read for checkpoints.
+pub type VmRng = ChaCha12Rng;
+
 /// Represents the core state of a VM.
-#[derive(derive_new::new, CopyGetters, MutGetters, Clone)]
+#[derive(derive_new::new, CopyGetters, Getters, MutGetters, Clone)]
 pub struct VmState<F, MEM = GuestMemory> {
     #[getset(get_copy = "pub", get_mut = "pub")]
     instret: u64,
@@ -26,8 +35,12 @@ pub struct VmState<F, MEM = GuestMemory> {
     pc: u32,
     pub memory: MEM,
     pub streams: Streams<F>,
-    pub rng: StdRng,
+    pub rng: VmRng,
+    /// The seed `rng` was created with.
+    #[getset(get_copy = "pub")]
+    pub(crate) rng_seed: u64,
     /// The public values of the PublicValuesAir when it exists
+    #[getset(get = "pub", get_mut = "pub")]
     pub(crate) custom_pvs: Vec<Option<F>>,
     #[cfg(feature = "metrics")]
     pub metrics: VmMetrics,
@@ -50,7 +63,8 @@ impl<F: Clone, MEM> VmState<F, MEM> {
             pc,
             memory,
             streams: streams.into(),
-            rng: StdRng::seed_from_u64(seed),
+            rng: VmRng::seed_from_u64(seed),
+            rng_seed: seed,
             custom_pvs: vec![None; num_custom_pvs],
             #[cfg(feature = "metrics")]
             metrics: VmMetrics::default(),
@@ -63,10 +77,24 @@ impl<F: Clone, MEM> VmState<F, MEM> {
         self.pc = pc;
     }
 
+    /// Number of `u32` words drawn from `rng` since it was seeded. Together with
+    /// [rng_seed](Self::rng_seed) this determines the state of the RNG.
+    pub fn rng_word_pos(&self) -> u128 {
+        self.rng.get_word_pos()
+    }
+
+    /// Sets `rng` to the state after drawing `word_pos` words from the RNG seeded with `seed`.
+    pub fn set_rng(&mut self, seed: u64, word_pos: u128) {
+        self.rng = VmRng::seed_from_u64(seed);
+        self.rng.set_word_pos(word_pos);
+        self.rng_seed = seed;
+    }
+
     #[inline(always)]
     pub fn into_mut<'a, RA>(&'a mut self, ctx: &'a mut RA) -> VmStateMut<'a, F, MEM, RA> {
         VmStateMut {
             pc: &mut self.pc,
+            instret: self.instret,
             memory: &mut self.memory,
             streams: &mut self.streams,
             rng: &mut self.rng,
@@ -113,7 +141,8 @@ impl<F: Clone> VmState<F, GuestMemory> {
         self.memory.memory.fill_zero();
         self.memory.memory.set_from_sparse(init_memory);
         self.streams = streams.into();
-        self.rng = StdRng::seed_from_u64(DEFAULT_RNG_SEED);
+        self.rng = VmRng::seed_from_u64(DEFAULT_RNG_SEED);
+        self.rng_seed = DEFAULT_RNG_SEED;
     }
 }
 
@@ -183,9 +212,10 @@ where
         addr_space: u32,
         ptr: u32,
     ) -> [T; BLOCK_SIZE] {
+        let data = self.host_read(addr_space, ptr);
         self.ctx
-            .on_memory_operation(addr_space, ptr, BLOCK_SIZE as u32);
-        self.host_read(addr_space, ptr)
+            .on_memory_access(MemoryAccessKind::Read, addr_space, ptr, &data);
+        data
     }
 
     /// Runtime write operation for a block of memory
@@ -197,7 +227,7 @@ where
         data: &[T; BLOCK_SIZE],
     ) {
         self.ctx
-            .on_memory_operation(addr_space, ptr, BLOCK_SIZE as u32);
+            .on_memory_access(MemoryAccessKind::Write, addr_space, ptr, data);
         self.host_write(addr_space, ptr, data)
     }
 
@@ -208,8 +238,12 @@ where
         ptr: u32,
         len: usize,
     ) -> &[T] {
-        self.ctx.on_memory_operation(addr_space, ptr, len as u32);
-        self.host_read_slice(addr_space, ptr, len)
+        // SAFETY: same as `host_read_slice`, which cannot be used here since the slice has to be
+        // borrowed at the same time as the context
+        let data = unsafe { self.vm_state.memory.get_slice(addr_space, ptr, len) };
+        self.ctx
+            .on_memory_access(MemoryAccessKind::Read, addr_space, ptr, data);
+        data
     }
 
     #[inline(always)]
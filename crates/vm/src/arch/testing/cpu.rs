@@ -41,7 +41,7 @@ use crate::{
             MEMORY_MERKLE_BUS, POSEIDON2_DIRECT_BUS, RANGE_CHECKER_BUS, READ_INSTRUCTION_BUS,
         },
         vm_poseidon2_config, Arena, ExecutionBridge, ExecutionBus, ExecutionState,
-        MatrixRecordArena, MemoryConfig, PreflightExecutor, Streams, VmStateMut,
+        MatrixRecordArena, MemoryConfig, PreflightExecutor, Streams, VmRng, VmStateMut,
     },
     system::{
         memory::{
@@ -59,7 +59,7 @@ use crate::{
 pub struct VmChipTestBuilder<F: Field> {
     pub memory: MemoryTester<F>,
     pub streams: Streams<F>,
-    pub rng: StdRng,
+    pub rng: VmRng,
     pub execution: ExecutionTester<F>,
     pub program: ProgramTester<F>,
     internal_rng: StdRng,
@@ -100,6 +100,7 @@ where
         let mut pc = initial_pc;
         let state_mut = VmStateMut {
             pc: &mut pc,
+            instret: 0,
             memory: &mut self.memory.memory,
             streams: &mut self.streams,
             rng: &mut self.rng,
@@ -205,7 +206,7 @@ impl<F: PrimeField32> VmChipTestBuilder<F> {
         controller: MemoryController<F>,
         memory: TracingMemory,
         streams: Streams<F>,
-        rng: StdRng,
+        rng: VmRng,
         execution_bus: ExecutionBus,
         program_bus: ProgramBus,
         internal_rng: StdRng,
@@ -364,7 +365,7 @@ impl<F: PrimeField32> VmChipTestBuilder<F> {
         Self {
             memory: MemoryTester::new(memory_controller, memory),
             streams: Default::default(),
-            rng: StdRng::seed_from_u64(0),
+            rng: VmRng::seed_from_u64(0),
             custom_pvs: Vec::new(),
             execution: ExecutionTester::new(ExecutionBus::new(EXECUTION_BUS)),
             program: ProgramTester::new(ProgramBus::new(READ_INSTRUCTION_BUS)),
@@ -385,7 +386,7 @@ impl<F: PrimeField32> VmChipTestBuilder<F> {
         Self {
             memory: MemoryTester::new(memory_controller, memory),
             streams: Default::default(),
-            rng: StdRng::seed_from_u64(0),
+            rng: VmRng::seed_from_u64(0),
             custom_pvs: Vec::new(),
             execution: ExecutionTester::new(ExecutionBus::new(EXECUTION_BUS)),
             program: ProgramTester::new(ProgramBus::new(READ_INSTRUCTION_BUS)),
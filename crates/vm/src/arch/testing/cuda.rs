@@ -37,7 +37,7 @@ use openvm_stark_sdk::{
     config::{setup_tracing_with_log_level, FriParameters},
     engine::{StarkFriEngine, VerificationDataWithFriParams},
 };
-use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand::{Rng, SeedableRng};
 use tracing::Level;
 
 #[cfg(feature = "metrics")]
@@ -54,7 +54,7 @@ use crate::{
             POSEIDON2_DIRECT_BUS, READ_INSTRUCTION_BUS,
         },
         Arena, DenseRecordArena, ExecutionBridge, ExecutionBus, ExecutionState, MatrixRecordArena,
-        MemoryConfig, PreflightExecutor, Streams, VmStateMut,
+        MemoryConfig, PreflightExecutor, Streams, VmRng, VmStateMut,
     },
     system::{
         cuda::{poseidon2::Poseidon2PeripheryChipGPU, DIGEST_WIDTH},
@@ -134,6 +134,7 @@ impl TestBuilder<F> for GpuChipTestBuilder {
         let mut pc = initial_pc;
         let state_mut = VmStateMut::new(
             &mut pc,
+            0,
             &mut self.memory.memory,
             &mut self.streams,
             &mut self.rng,
@@ -244,7 +245,7 @@ pub struct GpuChipTestBuilder {
     bitwise_op_lookup: Option<Arc<BitwiseOperationLookupChipGPU<8>>>,
     range_tuple_checker: Option<Arc<RangeTupleCheckerChipGPU<2>>>,
 
-    rng: StdRng,
+    rng: VmRng,
     pub custom_pvs: Vec<Option<F>>,
     default_register: usize,
     default_pointer: usize,
@@ -286,7 +287,7 @@ impl GpuChipTestBuilder {
             var_range_checker: range_checker,
             bitwise_op_lookup: None,
             range_tuple_checker: None,
-            rng: StdRng::seed_from_u64(0),
+            rng: VmRng::seed_from_u64(0),
             custom_pvs: Vec::new(),
             default_register: 0,
             default_pointer: 0,
@@ -314,7 +315,7 @@ impl GpuChipTestBuilder {
             var_range_checker: range_checker,
             bitwise_op_lookup: None,
             range_tuple_checker: None,
-            rng: StdRng::seed_from_u64(0),
+            rng: VmRng::seed_from_u64(0),
             custom_pvs: Vec::new(),
             default_register: 0,
             default_pointer: 0,
@@ -415,7 +416,7 @@ impl GpuChipTestBuilder {
         self.memory.mem_bus
     }
 
-    pub fn rng(&mut self) -> &mut StdRng {
+    pub fn rng(&mut self) -> &mut VmRng {
         &mut self.rng
     }
 
@@ -8,8 +8,8 @@
 //! execute+prove an arbitrary program for a fixed config - it will internally still hold VmExecutor
 use std::{
     any::TypeId,
-    borrow::Borrow,
-    collections::{HashMap, VecDeque},
+    borrow::{Borrow, Cow},
+    collections::{BTreeMap, HashMap, VecDeque},
     marker::PhantomData,
     sync::Arc,
 };
@@ -18,7 +18,7 @@ use getset::{Getters, MutGetters, Setters, WithSetters};
 use itertools::{zip_eq, Itertools};
 use openvm_circuit::system::program::trace::compute_exe_commit;
 use openvm_instructions::{
-    exe::{SparseMemoryImage, VmExe},
+    exe::{FnBounds, SparseMemoryImage, VmExe},
     program::Program,
 };
 use openvm_stark_backend::{
@@ -40,7 +40,10 @@ use thiserror::Error;
 use tracing::{info_span, instrument};
 
 use super::{
-    execution_mode::{ExecutionCtx, MeteredCostCtx, MeteredCtx, PreflightCtx, Segment},
+    execution_mode::{
+        DebugCtx, ExecutionCtx, MeteredCostCtx, MeteredCtx, PreflightCtx, ProfileCtx, Segment,
+        TraceCtx,
+    },
     hasher::poseidon2::vm_poseidon2_hasher,
     interpreter::InterpretedInstance,
     interpreter_preflight::PreflightInterpretedInstance,
@@ -93,15 +96,38 @@ pub enum GenerationError {
     },
 }
 
-/// A trait for key-value store for `Streams`.
+/// A trait for key-value store for `Streams`. Besides the in-memory `HashMap`, there are backends
+/// which load values lazily, such as [DirKvStore](super::DirKvStore),
+/// [MmapKvStore](super::MmapKvStore) and [FnKvStore](super::FnKvStore).
+///
+/// In-memory stores implement [KvStore::get], while backends which load values lazily implement
+/// [KvStore::load]. Execution looks up keys with [KvStore::load].
 pub trait KvStore: Send + Sync {
-    fn get(&self, key: &[u8]) -> Option<&[u8]>;
+    /// Returns the value of `key` if it is held in memory by the store.
+    fn get(&self, _key: &[u8]) -> Option<&[u8]> {
+        None
+    }
+
+    /// Returns the value of `key`, loading it if necessary. Defaults to [KvStore::get].
+    fn load(&self, key: &[u8]) -> Option<Cow<'_, [u8]>> {
+        self.get(key).map(Cow::Borrowed)
+    }
+
+    /// Returns all key-value pairs, for example to write the store into a checkpoint of the VM
+    /// state. Returns `None` if the backend cannot list its keys.
+    fn entries(&self) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+        None
+    }
 }
 
 impl KvStore for HashMap<Vec<u8>, Vec<u8>> {
     fn get(&self, key: &[u8]) -> Option<&[u8]> {
         self.get(key).map(|v| v.as_slice())
     }
+
+    fn entries(&self) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+        Some(self.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+    }
 }
 
 #[derive(Clone)]
@@ -112,6 +138,9 @@ pub struct Streams<F> {
     /// The key-value store for hints. Both key and value are byte arrays. Executors which
     /// read `kv_store` need to encode the key and decode the value.
     pub kv_store: Arc<dyn KvStore>,
+    /// Bytes provided by the host for each file descriptor, which the guest reads with
+    /// `sys_read`. File descriptor 0 is the guest's stdin.
+    pub fd_buffers: BTreeMap<u32, VecDeque<u8>>,
 }
 
 impl<F> Streams<F> {
@@ -121,6 +150,7 @@ impl<F> Streams<F> {
             hint_stream: VecDeque::default(),
             hint_space: Vec::default(),
             kv_store: Arc::new(HashMap::new()),
+            fd_buffers: BTreeMap::new(),
         }
     }
 }
@@ -233,6 +263,33 @@ where
     ) -> Result<InterpretedInstance<F, ExecutionCtx>, StaticProgramError> {
         InterpretedInstance::new(&self.inventory, exe)
     }
+
+    /// Creates an instance of the interpreter specialized for interactive debugging of the given
+    /// `exe`. See [`InterpretedInstance::execute_debug_from_state`].
+    pub fn debug_instance(
+        &self,
+        exe: &VmExe<F>,
+    ) -> Result<InterpretedInstance<F, DebugCtx>, StaticProgramError> {
+        InterpretedInstance::new(&self.inventory, exe)
+    }
+
+    /// Creates an instance of the interpreter which records a trace of every executed instruction
+    /// of the given `exe`. See [`InterpretedInstance::execute_trace`].
+    pub fn trace_instance(
+        &self,
+        exe: &VmExe<F>,
+    ) -> Result<InterpretedInstance<F, TraceCtx>, StaticProgramError> {
+        InterpretedInstance::new(&self.inventory, exe)
+    }
+
+    /// Creates an instance of the interpreter which profiles the executed instructions by call
+    /// stack. See [`InterpretedInstance::execute_profile`].
+    pub fn profile_instance(
+        &self,
+        exe: &VmExe<F>,
+    ) -> Result<InterpretedInstance<F, ProfileCtx>, StaticProgramError> {
+        InterpretedInstance::new(&self.inventory, exe)
+    }
 }
 
 impl<F, VC> VmExecutor<F, VC>
@@ -259,6 +316,16 @@ where
     ) -> Result<InterpretedInstance<F, MeteredCostCtx>, StaticProgramError> {
         InterpretedInstance::new_metered(&self.inventory, exe, executor_idx_to_air_idx)
     }
+
+    /// Creates an instance of the interpreter which profiles the executed instructions and the
+    /// generated trace cells by call stack.
+    pub fn metered_profile_instance(
+        &self,
+        exe: &VmExe<F>,
+        executor_idx_to_air_idx: &[usize],
+    ) -> Result<InterpretedInstance<F, ProfileCtx>, StaticProgramError> {
+        InterpretedInstance::new_metered(&self.inventory, exe, executor_idx_to_air_idx)
+    }
 }
 
 #[derive(Error, Debug)]
@@ -426,6 +493,19 @@ where
             .metered_cost_instance(exe, &executor_idx_to_air_idx)
     }
 
+    pub fn metered_profile_interpreter(
+        &self,
+        exe: &VmExe<Val<E::SC>>,
+    ) -> Result<InterpretedInstance<Val<E::SC>, ProfileCtx>, StaticProgramError>
+    where
+        Val<E::SC>: PrimeField32,
+        <VB::VmConfig as VmExecutionConfig<Val<E::SC>>>::Executor: MeteredExecutor<Val<E::SC>>,
+    {
+        let executor_idx_to_air_idx = self.executor_idx_to_air_idx();
+        self.executor()
+            .metered_profile_instance(exe, &executor_idx_to_air_idx)
+    }
+
     pub fn preflight_interpreter(
         &self,
         exe: &VmExe<Val<E::SC>>,
@@ -497,6 +577,7 @@ where
             memory,
             state.streams,
             state.rng,
+            state.rng_seed,
             state.custom_pvs,
             #[cfg(feature = "metrics")]
             state.metrics,
@@ -539,6 +620,7 @@ where
             memory.data,
             exec_state.vm_state.streams,
             exec_state.vm_state.rng,
+            exec_state.vm_state.rng_seed,
             exec_state.vm_state.custom_pvs,
             #[cfg(feature = "metrics")]
             exec_state.vm_state.metrics,
@@ -839,6 +921,32 @@ where
         self.executor().build_metered_cost_ctx(&widths)
     }
 
+    /// Convenience method to construct a [ProfileCtx] which profiles trace cells and interactions,
+    /// using the air widths and interactions from the stored proving key.
+    pub fn build_profile_ctx(&self, fn_bounds: FnBounds) -> ProfileCtx {
+        let (widths, interactions): (Vec<_>, Vec<_>) = self
+            .pk
+            .per_air
+            .iter()
+            .map(|pk| {
+                let width = pk
+                    .vk
+                    .params
+                    .width
+                    .total_width(<<E::SC as StarkGenericConfig>::Challenge>::D);
+                (width, pk.vk.symbolic_constraints.interactions.len())
+            })
+            .unzip();
+
+        let air_names = self.air_names().map(str::to_owned).collect();
+        ProfileCtx::new(fn_bounds).with_cells(
+            air_names,
+            widths,
+            interactions,
+            self.config().as_ref(),
+        )
+    }
+
     pub fn num_airs(&self) -> usize {
         let num_airs = self.pk.per_air.len();
         debug_assert_eq!(num_airs, self.chip_complex.inventory.airs().num_airs());
@@ -275,6 +275,11 @@ impl<F: PrimeField32> VmExecutionConfig<F> for SystemConfig {
             PhantomDiscriminant(SysPhantom::CtEnd as u16),
             Arc::new(CycleEndPhantomExecutor),
         );
+        // `HintInstret` needs the instruction count, so it is also handled specially.
+        phantom_executors.insert(
+            PhantomDiscriminant(SysPhantom::HintInstret as u16),
+            Arc::new(NopPhantomExecutor),
+        );
         let phantom = PhantomExecutor::new(phantom_executors, phantom_opcode);
         inventory.add_executor(phantom, [phantom_opcode])?;
 
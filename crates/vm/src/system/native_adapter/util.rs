@@ -3,7 +3,10 @@ use openvm_instructions::{riscv::RV32_IMM_AS, NATIVE_AS};
 use openvm_stark_backend::p3_field::PrimeField32;
 
 use crate::{
-    arch::{execution_mode::ExecutionCtxTrait, VmStateMut},
+    arch::{
+        execution_mode::{ExecutionCtxTrait, MemoryAccessKind},
+        VmStateMut,
+    },
     system::memory::{offline_checker::MemoryWriteAuxCols, online::GuestMemory},
 };
 
@@ -51,9 +54,11 @@ where
     F: PrimeField32,
     Ctx: ExecutionCtxTrait,
 {
-    state.ctx.on_memory_operation(NATIVE_AS, ptr, N as u32);
-
-    memory_read_native(state.memory, ptr)
+    let data = memory_read_native(state.memory, ptr);
+    state
+        .ctx
+        .on_memory_access(MemoryAccessKind::Read, NATIVE_AS, ptr, &data);
+    data
 }
 
 #[inline(always)]
@@ -85,7 +90,9 @@ pub fn memory_write_native_from_state<Ctx, F, const N: usize>(
     F: PrimeField32,
     Ctx: ExecutionCtxTrait,
 {
-    state.ctx.on_memory_operation(NATIVE_AS, ptr, N as u32);
+    state
+        .ctx
+        .on_memory_access(MemoryAccessKind::Write, NATIVE_AS, ptr, &data);
 
     memory_write_native(state.memory, ptr, data)
 }
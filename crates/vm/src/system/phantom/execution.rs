@@ -8,7 +8,6 @@ use openvm_instructions::{
     instruction::Instruction, program::DEFAULT_PC_STEP, PhantomDiscriminant, SysPhantom,
 };
 use openvm_stark_backend::p3_field::PrimeField32;
-use rand::rngs::StdRng;
 
 #[cfg(not(feature = "tco"))]
 use crate::arch::ExecuteFunc;
@@ -19,9 +18,12 @@ use crate::{
         create_handler,
         execution_mode::{ExecutionCtxTrait, MeteredExecutionCtxTrait},
         E2PreCompute, ExecutionError, Executor, MeteredExecutor, PhantomSubExecutor,
-        StaticProgramError, Streams, VmExecState,
+        StaticProgramError, Streams, VmExecState, VmRng,
+    },
+    system::{
+        memory::online::GuestMemory,
+        phantom::{hint_instret, PhantomExecutor},
     },
-    system::{memory::online::GuestMemory, phantom::PhantomExecutor},
 };
 
 #[derive(Clone, AlignedBytesBorrow)]
@@ -81,9 +83,10 @@ where
 
 pub(super) struct PhantomStateMut<'a, F> {
     pub(super) pc: &'a mut u32,
+    pub(super) instret: u64,
     pub(super) memory: &'a mut GuestMemory,
     pub(super) streams: &'a mut Streams<F>,
-    pub(super) rng: &'a mut StdRng,
+    pub(super) rng: &'a mut VmRng,
 }
 
 impl<F> PhantomExecutor<F>
@@ -152,7 +155,7 @@ where
 }
 
 #[inline(always)]
-fn execute_impl<F>(
+fn execute_impl<F: PrimeField32>(
     state: PhantomStateMut<F>,
     operands: &PhantomOperands,
     sub_executor: &dyn PhantomSubExecutor<F>,
@@ -160,15 +163,20 @@ fn execute_impl<F>(
     let &PhantomOperands { a, b, c } = operands;
 
     let discriminant = PhantomDiscriminant(c as u16);
-    // SysPhantom::{CtStart, CtEnd} are only handled in Preflight Execution, so the only SysPhantom
-    // to handle here is DebugPanic.
-    if let Some(discr) = SysPhantom::from_repr(discriminant.0) {
-        if discr == SysPhantom::DebugPanic {
+    // SysPhantom::{CtStart, CtEnd} are only handled in Preflight Execution, so the only
+    // SysPhantoms to handle here are DebugPanic and HintInstret.
+    match SysPhantom::from_repr(discriminant.0) {
+        Some(SysPhantom::DebugPanic) => {
             return Err(ExecutionError::Fail {
                 pc: *state.pc,
                 msg: "DebugPanic",
             });
         }
+        Some(SysPhantom::HintInstret) => {
+            hint_instret(state.streams, state.instret);
+            return Ok(());
+        }
+        _ => {}
     }
     sub_executor
         .phantom_execute(
@@ -200,6 +208,7 @@ unsafe fn execute_e12_impl<F: PrimeField32, CTX: ExecutionCtxTrait>(
     execute_impl(
         PhantomStateMut {
             pc,
+            instret: *instret,
             memory: &mut exec_state.vm_state.memory,
             streams: &mut exec_state.vm_state.streams,
             rng: &mut exec_state.vm_state.rng,
@@ -20,7 +20,6 @@ use openvm_stark_backend::{
     p3_matrix::Matrix,
     rap::{BaseAirWithPublicValues, PartitionedBaseAir},
 };
-use rand::rngs::StdRng;
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use serde_big_array::BigArray;
@@ -30,7 +29,7 @@ use crate::{
     arch::{
         get_record_from_slice, EmptyMultiRowLayout, ExecutionBridge, ExecutionError,
         ExecutionState, PcIncOrSet, PhantomSubExecutor, PreflightExecutor, RecordArena, Streams,
-        TraceFiller, VmChipWrapper, VmStateMut,
+        TraceFiller, VmChipWrapper, VmRng, VmStateMut,
     },
     system::memory::MemoryAuxColsFactory,
 };
@@ -167,6 +166,7 @@ where
                         metrics.cycle_tracker.end(info.dsl_instruction.clone());
                     }
                 }
+                SysPhantom::HintInstret => hint_instret(state.streams, state.instret),
                 _ => {}
             }
         } else {
@@ -214,6 +214,15 @@ impl<F: Field> TraceFiller<F> for PhantomFiller {
     }
 }
 
+/// Resets the hint stream with the little-endian bytes of `instret`, for [SysPhantom::HintInstret].
+#[inline(always)]
+pub(super) fn hint_instret<F: Field>(streams: &mut Streams<F>, instret: u64) {
+    streams.hint_stream.clear();
+    streams
+        .hint_stream
+        .extend(instret.to_le_bytes().map(F::from_canonical_u8));
+}
+
 pub struct NopPhantomExecutor;
 pub struct CycleStartPhantomExecutor;
 pub struct CycleEndPhantomExecutor;
@@ -224,7 +233,7 @@ impl<F> PhantomSubExecutor<F> for NopPhantomExecutor {
         &self,
         _memory: &GuestMemory,
         _streams: &mut Streams<F>,
-        _rng: &mut StdRng,
+        _rng: &mut VmRng,
         _discriminant: PhantomDiscriminant,
         _a: u32,
         _b: u32,
@@ -240,7 +249,7 @@ impl<F> PhantomSubExecutor<F> for CycleStartPhantomExecutor {
         &self,
         _memory: &GuestMemory,
         _streams: &mut Streams<F>,
-        _rng: &mut StdRng,
+        _rng: &mut VmRng,
         _discriminant: PhantomDiscriminant,
         _a: u32,
         _b: u32,
@@ -257,7 +266,7 @@ impl<F> PhantomSubExecutor<F> for CycleEndPhantomExecutor {
         &self,
         _memory: &GuestMemory,
         _streams: &mut Streams<F>,
-        _rng: &mut StdRng,
+        _rng: &mut VmRng,
         _discriminant: PhantomDiscriminant,
         _a: u32,
         _b: u32,
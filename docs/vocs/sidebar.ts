@@ -165,6 +165,14 @@ export const bookSidebar: SidebarItem[] = [
                 text: "SHA2",
                 link: "/book/guest-libraries/sha2"
             },
+            {
+                text: "Poseidon2",
+                link: "/book/guest-libraries/poseidon2"
+            },
+            {
+                text: "BLAKE",
+                link: "/book/guest-libraries/blake"
+            },
             {
                 text: "Ruint",
                 link: "/book/guest-libraries/ruint"
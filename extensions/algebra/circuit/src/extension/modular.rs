@@ -489,7 +489,7 @@ pub(crate) mod phantom {
     use eyre::bail;
     use num_bigint::BigUint;
     use openvm_circuit::{
-        arch::{PhantomSubExecutor, Streams},
+        arch::{PhantomSubExecutor, Streams, VmRng},
         system::memory::online::GuestMemory,
     };
     use openvm_instructions::{riscv::RV32_MEMORY_AS, PhantomDiscriminant};
@@ -517,7 +517,7 @@ pub(crate) mod phantom {
             &self,
             memory: &GuestMemory,
             streams: &mut Streams<F>,
-            _: &mut StdRng,
+            _: &mut VmRng,
             _: PhantomDiscriminant,
             a: u32,
             _: u32,
@@ -605,7 +605,7 @@ pub(crate) mod phantom {
             &self,
             _: &GuestMemory,
             streams: &mut Streams<F>,
-            _: &mut StdRng,
+            _: &mut VmRng,
             _: PhantomDiscriminant,
             _: u32,
             _: u32,
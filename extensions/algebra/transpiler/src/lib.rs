@@ -8,9 +8,12 @@ use openvm_instructions::{
 };
 use openvm_instructions_derive::LocalOpcode;
 use openvm_stark_backend::p3_field::PrimeField32;
-use openvm_transpiler::{util::from_r_type, TranspilerExtension, TranspilerOutput};
+use openvm_transpiler::{
+    util::{from_r_type, local_opcode_name},
+    TranspilerExtension, TranspilerOutput,
+};
 use rrs_lib::instruction_formats::RType;
-use strum::{EnumCount, EnumIter, FromRepr};
+use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator};
 
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
@@ -161,6 +164,17 @@ impl<F: PrimeField32> TranspilerExtension<F> for ModularTranspilerExtension {
         };
         instruction.map(TranspilerOutput::one_to_one)
     }
+
+    fn opcode_name(&self, opcode: VmOpcode) -> Option<String> {
+        // One copy of the opcodes per modulus, indexed by funct7
+        let num_moduli = 128 / ModArithBaseFunct7::MODULAR_ARITHMETIC_MAX_KINDS as usize;
+        local_opcode_name(opcode, Rv32ModularArithmeticOpcode::iter(), num_moduli)
+    }
+
+    fn phantom_name(&self, discriminant: PhantomDiscriminant) -> Option<String> {
+        ModularPhantom::from_repr(discriminant.0)
+            .map(|phantom| format!("ModularPhantom::{phantom:?}"))
+    }
 }
 
 impl<F: PrimeField32> TranspilerExtension<F> for Fp2TranspilerExtension {
@@ -231,4 +245,10 @@ impl<F: PrimeField32> TranspilerExtension<F> for Fp2TranspilerExtension {
         };
         instruction.map(TranspilerOutput::one_to_one)
     }
+
+    fn opcode_name(&self, opcode: VmOpcode) -> Option<String> {
+        // One copy of the opcodes per complex extension field, indexed by funct7
+        let num_fields = 128 / ComplexExtFieldBaseFunct7::COMPLEX_EXT_FIELD_MAX_KINDS as usize;
+        local_opcode_name(opcode, Fp2Opcode::iter(), num_fields)
+    }
 }
@@ -8,7 +8,10 @@ use openvm_rv32im_transpiler::{
     BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, LessThanOpcode, MulOpcode, ShiftOpcode,
 };
 use openvm_stark_backend::p3_field::PrimeField32;
-use openvm_transpiler::{util::from_r_type, TranspilerExtension, TranspilerOutput};
+use openvm_transpiler::{
+    util::{from_r_type, local_opcode_name},
+    TranspilerExtension, TranspilerOutput,
+};
 use rrs_lib::instruction_formats::{BType, RType};
 use strum::IntoEnumIterator;
 
@@ -156,4 +159,13 @@ impl<F: PrimeField32> TranspilerExtension<F> for Int256TranspilerExtension {
         };
         instruction.map(TranspilerOutput::one_to_one)
     }
+
+    fn opcode_name(&self, opcode: VmOpcode) -> Option<String> {
+        local_opcode_name(opcode, Rv32BaseAlu256Opcode::iter(), 1)
+            .or_else(|| local_opcode_name(opcode, Rv32Shift256Opcode::iter(), 1))
+            .or_else(|| local_opcode_name(opcode, Rv32LessThan256Opcode::iter(), 1))
+            .or_else(|| local_opcode_name(opcode, Rv32BranchEqual256Opcode::iter(), 1))
+            .or_else(|| local_opcode_name(opcode, Rv32BranchLessThan256Opcode::iter(), 1))
+            .or_else(|| local_opcode_name(opcode, Rv32Mul256Opcode::iter(), 1))
+    }
 }
@@ -0,0 +1,508 @@
+use std::{array::from_fn, borrow::Borrow};
+
+use itertools::izip;
+use openvm_blake_transpiler::Rv32BlakeOpcode;
+use openvm_circuit::{
+    arch::ExecutionBridge,
+    system::memory::{offline_checker::MemoryBridge, MemoryAddress},
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::BitwiseOperationLookupBus, utils::assert_array_eq,
+};
+use openvm_instructions::riscv::{
+    RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS,
+};
+use openvm_rv32im_circuit::adapters::abstract_compose;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::FieldAlgebra,
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+
+use super::{
+    blake_round_flag_idx, blake_timestamp_delta, BlakeVmCols, BlakeWordBits, BLAKE2S_OUTPUT_WRITES,
+    BLAKE_ACCESS_SIZE, BLAKE_VM_WIDTH, BLAKE_WORD_BITS,
+};
+use crate::utils::{
+    blake_msg_schedule, blake_num_rounds, BLAKE_G_ROTATIONS, BLAKE_IV, BLAKE_NUM_G,
+};
+
+/// Number of bits of the limbs in which additions of words are constrained
+const LIMB_BITS: usize = 16;
+const NUM_LIMBS: usize = BLAKE_WORD_BITS / LIMB_BITS;
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct BlakeVmAir {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    /// Bus to send 8-bit range check requests to.
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    /// Maximum number of bits allowed for an address pointer
+    pub ptr_max_bits: usize,
+    pub(crate) offset: usize,
+}
+
+impl<F> BaseAirWithPublicValues<F> for BlakeVmAir {}
+impl<F> PartitionedBaseAir<F> for BlakeVmAir {}
+impl<F> BaseAir<F> for BlakeVmAir {
+    fn width(&self) -> usize {
+        BLAKE_VM_WIDTH
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for BlakeVmAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &BlakeVmCols<AB::Var> = (*local).borrow();
+        let next: &BlakeVmCols<AB::Var> = (*next).borrow();
+
+        self.eval_round_flags(builder, local, next);
+        self.eval_round(builder, local);
+        self.eval_init(builder, local);
+        self.eval_finalize(builder, local);
+
+        // Every round other than the last one is followed by the next round of the same
+        // instruction, which starts from the state at the end of the round
+        let is_valid: AB::Expr = local.round_flags.iter().map(|&flag| flag.into()).sum();
+        let mut transition_builder = builder.when_transition();
+        let mut round_builder = transition_builder.when(is_valid - is_last_round::<AB>(local));
+        for (next_word, word) in next.state.into_iter().zip(local.round_output()) {
+            assert_array_eq(&mut round_builder, next_word, word);
+        }
+        local
+            .instruction
+            .assert_eq(&mut round_builder, next.instruction);
+
+        let start_read_timestamp = self.eval_instruction(builder, local);
+        let start_write_timestamp = self.constrain_input_read(builder, local, start_read_timestamp);
+        self.constrain_output_write(builder, local, start_write_timestamp);
+    }
+}
+
+impl BlakeVmAir {
+    /// Constrain that the round flags are one-hot or all zero, and that every instruction takes
+    /// consecutive rows for all of its rounds in order. Since all interactions happen on the last
+    /// round, an instruction cut off by the end of the trace has no effect.
+    pub fn eval_round_flags<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var>,
+        next: &BlakeVmCols<AB::Var>,
+    ) {
+        for &flag in &local.round_flags {
+            builder.assert_bool(flag);
+        }
+        let is_valid: AB::Expr = local.round_flags.iter().map(|&flag| flag.into()).sum();
+        builder.assert_bool(is_valid);
+
+        for is_blake3 in [false, true] {
+            let num_rounds = blake_num_rounds(is_blake3);
+            for round in 1..num_rounds {
+                builder
+                    .when_first_row()
+                    .assert_zero(local.round_flags[blake_round_flag_idx(is_blake3, round)]);
+            }
+            for round in 0..num_rounds - 1 {
+                builder.when_transition().assert_eq(
+                    next.round_flags[blake_round_flag_idx(is_blake3, round + 1)],
+                    local.round_flags[blake_round_flag_idx(is_blake3, round)],
+                );
+            }
+        }
+    }
+
+    /// Constrain the applications of `G` in the round of the row. Additions are constrained on
+    /// 16-bit limbs with explicit carries, and xors and rotations on bits. The constraints hold
+    /// for all-zero dummy rows, so they are not gated.
+    pub fn eval_round<AB: AirBuilder>(&self, builder: &mut AB, local: &BlakeVmCols<AB::Var>) {
+        for word in &local.state {
+            for &bit in word {
+                builder.assert_bool(bit);
+            }
+        }
+
+        let [r1, r2, r3, r4] = BLAKE_G_ROTATIONS;
+        for g in 0..BLAKE_NUM_G {
+            let cols = &local.g[g];
+            let [a1, c1, a2, b2, c2, d2] = [cols.a1, cols.c1, cols.a2, cols.b2, cols.c2, cols.d2];
+            for word in [a1, c1, a2, b2, c2, d2] {
+                for bit in word {
+                    builder.assert_bool(bit);
+                }
+            }
+            let [a, b, c, d] = local.g_inputs(g).map(word_expr::<AB>);
+            let [a1, c1, a2, b2, c2, d2] = [a1, c1, a2, b2, c2, d2].map(word_expr::<AB>);
+            let [mx, my] = [2 * g, 2 * g + 1].map(|j| message_limbs::<AB>(local, j));
+
+            assert_add(
+                builder,
+                &[limbs(&a), limbs(&b), mx],
+                limbs(&a1),
+                cols.carries[0],
+            );
+            let d1 = rotr(&xor_word(&d, &a1), r1);
+            assert_add(
+                builder,
+                &[limbs(&c), limbs(&d1)],
+                limbs(&c1),
+                cols.carries[1],
+            );
+            let b1 = rotr(&xor_word(&b, &c1), r2);
+            assert_add(
+                builder,
+                &[limbs(&a1), limbs(&b1), my],
+                limbs(&a2),
+                cols.carries[2],
+            );
+            for (bit, expected) in d2.iter().zip(rotr(&xor_word(&d1, &a2), r3)) {
+                builder.assert_eq(bit.clone(), expected);
+            }
+            assert_add(
+                builder,
+                &[limbs(&c1), limbs(&d2)],
+                limbs(&c2),
+                cols.carries[3],
+            );
+            for (bit, expected) in b2.iter().zip(rotr(&xor_word(&b1, &c2), r4)) {
+                builder.assert_eq(bit.clone(), expected);
+            }
+        }
+    }
+
+    /// Constrain that the state on the first round is initialized from the chaining value and the
+    /// parameter words read from memory.
+    pub fn eval_init<AB: AirBuilder>(&self, builder: &mut AB, local: &BlakeVmCols<AB::Var>) {
+        let is_first_blake2s = local.is_first_round(false);
+        let is_first_blake3 = local.is_first_round(true);
+        let instruction = &local.instruction;
+
+        let mut first_builder = builder.when(is_first_blake2s + is_first_blake3);
+        for (word, cv_word) in local.state.into_iter().zip(instruction.cv) {
+            assert_array_eq(&mut first_builder, word, cv_word);
+        }
+        for (word, iv) in local.state[8..12].iter().zip(BLAKE_IV) {
+            for (bit, iv_bit) in word.iter().zip(word_const_bits::<AB>(iv)) {
+                first_builder.assert_eq(*bit, iv_bit);
+            }
+        }
+
+        // The last 4 words are the parameters for BLAKE3, and the parameters xored with the end
+        // of the IV for BLAKE2s
+        for (i, word) in local.state[12..].iter().enumerate() {
+            let word = word_expr::<AB>(*word);
+            let xored_word = xor_word(&word, &word_const_bits::<AB>(BLAKE_IV[4 + i]));
+            for k in 0..4 {
+                let byte = instruction.params[4 * i + k];
+                let bits = 8 * k..8 * (k + 1);
+                builder
+                    .when(is_first_blake3)
+                    .assert_eq(byte, compose_bits(&word[bits.clone()]));
+                builder
+                    .when(is_first_blake2s)
+                    .assert_eq(byte, compose_bits(&xored_word[bits]));
+            }
+        }
+    }
+
+    /// Constrain the output bytes on the last round, given the state `v` at the end of the round.
+    /// BLAKE2s outputs `v[i] ^ v[i + 8] ^ cv[i]`, and BLAKE3 outputs `v[i] ^ v[i + 8]` followed by
+    /// `v[i + 8] ^ cv[i]`.
+    pub fn eval_finalize<AB: AirBuilder>(&self, builder: &mut AB, local: &BlakeVmCols<AB::Var>) {
+        let is_last_blake2s = local.is_last_round(false);
+        let is_last_blake3 = local.is_last_round(true);
+        let v = local.round_output().map(word_expr::<AB>);
+        let cv = local.instruction.cv.map(word_expr::<AB>);
+
+        for (i, final_xor) in local.final_xor.iter().enumerate() {
+            for (bit, expected) in final_xor.iter().zip(xor_word(&v[i], &v[i + 8])) {
+                builder
+                    .when(is_last_blake2s + is_last_blake3)
+                    .assert_eq(*bit, expected);
+            }
+
+            let final_xor = word_expr::<AB>(*final_xor);
+            let blake2s_output = xor_word(&final_xor, &cv[i]);
+            let blake3_output_hi = xor_word(&v[i + 8], &cv[i]);
+            for k in 0..4 {
+                let bits = 8 * k..8 * (k + 1);
+                builder.when(is_last_blake2s).assert_eq(
+                    local.output[4 * i + k],
+                    compose_bits(&blake2s_output[bits.clone()]),
+                );
+                builder.when(is_last_blake3).assert_eq(
+                    local.output[4 * i + k],
+                    compose_bits(&final_xor[bits.clone()]),
+                );
+                builder.when(is_last_blake3).assert_eq(
+                    local.output[32 + 4 * i + k],
+                    compose_bits(&blake3_output_hi[bits]),
+                );
+            }
+        }
+    }
+
+    /// Receive the instruction itself on program bus. Send+receive on execution bus.
+    /// Then does memory reads in addr space 1 to get the dst, state and block pointers from the
+    /// `rd`, `rs1` and `rs2` registers, and range checks their most significant limbs using
+    /// BitwiseOperationLookupBus. All of this happens on the last round of the instruction.
+    ///
+    /// Returns `start_read_timestamp` for the state and block reads.
+    pub fn eval_instruction<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var>,
+    ) -> AB::Expr {
+        let instruction = &local.instruction;
+        let is_last_blake2s = local.is_last_round(false);
+        let is_last_blake3 = local.is_last_round(true);
+        let is_last = is_last_round::<AB>(local);
+
+        let opcode = is_last_blake2s
+            * AB::F::from_canonical_usize(Rv32BlakeOpcode::BLAKE2S_COMPRESS as usize + self.offset)
+            + is_last_blake3
+                * AB::F::from_canonical_usize(
+                    Rv32BlakeOpcode::BLAKE3_COMPRESS as usize + self.offset,
+                );
+        let timestamp_delta = is_last_blake2s
+            * AB::F::from_canonical_usize(blake_timestamp_delta(false))
+            + is_last_blake3 * AB::F::from_canonical_usize(blake_timestamp_delta(true));
+        self.execution_bridge
+            .execute_and_increment_pc(
+                opcode,
+                [
+                    instruction.rd_ptr.into(),
+                    instruction.rs1_ptr.into(),
+                    instruction.rs2_ptr.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                instruction.from_state,
+                timestamp_delta,
+            )
+            .eval(builder, is_last.clone());
+
+        let mut timestamp: AB::Expr = instruction.from_state.timestamp.into();
+        for (reg_ptr, ptr, aux) in izip!(
+            [instruction.rd_ptr, instruction.rs1_ptr, instruction.rs2_ptr],
+            [
+                instruction.dst_ptr,
+                instruction.state_ptr,
+                instruction.block_ptr
+            ],
+            &local.mem_oc.register_aux
+        ) {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(AB::Expr::from_canonical_u32(RV32_REGISTER_AS), reg_ptr),
+                    ptr,
+                    timestamp.clone(),
+                    aux,
+                )
+                .eval(builder, is_last.clone());
+            timestamp += AB::Expr::ONE;
+        }
+
+        // See Rv32VecHeapAdapterAir
+        let limb_shift = AB::F::from_canonical_usize(
+            1 << (RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.ptr_max_bits),
+        );
+        let [dst_msl, state_msl, block_msl] = [
+            instruction.dst_ptr,
+            instruction.state_ptr,
+            instruction.block_ptr,
+        ]
+        .map(|ptr| ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift);
+        self.bitwise_lookup_bus
+            .send_range(dst_msl, state_msl)
+            .eval(builder, is_last.clone());
+        self.bitwise_lookup_bus
+            .send_range(block_msl.clone(), block_msl)
+            .eval(builder, is_last);
+
+        timestamp
+    }
+
+    /// Constrain that the chaining value, the parameters and the message block are read from
+    /// memory on the last round. The chaining value bytes are composed from its bits, so they are
+    /// bytes, and the other bytes are bytes since they are read from memory.
+    ///
+    /// Returns the `start_write_timestamp` for the output writes.
+    pub fn constrain_input_read<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var>,
+        start_read_timestamp: AB::Expr,
+    ) -> AB::Expr {
+        let instruction = &local.instruction;
+        let is_last = is_last_round::<AB>(local);
+        let state_bytes: Vec<AB::Expr> = instruction
+            .cv
+            .iter()
+            .flat_map(|&word| {
+                let word = word_expr::<AB>(word);
+                (0..4)
+                    .map(|k| compose_bits(&word[8 * k..8 * (k + 1)]))
+                    .collect::<Vec<_>>()
+            })
+            .chain(instruction.params.iter().map(|&byte| byte.into()))
+            .collect();
+        let block_bytes: Vec<AB::Expr> =
+            instruction.block.iter().map(|&byte| byte.into()).collect();
+
+        let mut timestamp = start_read_timestamp;
+        for (ptr, bytes, mem_aux) in [
+            (
+                instruction.state_ptr,
+                &state_bytes,
+                &local.mem_oc.state_reads[..],
+            ),
+            (
+                instruction.block_ptr,
+                &block_bytes,
+                &local.mem_oc.block_reads[..],
+            ),
+        ] {
+            let ptr = abstract_compose::<AB::Expr, _>(ptr);
+            for (i, (chunk, aux)) in
+                izip!(bytes.chunks_exact(BLAKE_ACCESS_SIZE), mem_aux).enumerate()
+            {
+                self.memory_bridge
+                    .read(
+                        MemoryAddress::new(
+                            AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                            ptr.clone() + AB::F::from_canonical_usize(i * BLAKE_ACCESS_SIZE),
+                        ),
+                        from_fn::<_, BLAKE_ACCESS_SIZE, _>(|j| chunk[j].clone()),
+                        timestamp.clone(),
+                        aux,
+                    )
+                    .eval(builder, is_last.clone());
+                timestamp += AB::Expr::ONE;
+            }
+        }
+        timestamp
+    }
+
+    /// Constrain that the output is written to memory on the last round. BLAKE2s only writes the
+    /// first half of the output. The output bytes are composed from bits, so they are bytes.
+    pub fn constrain_output_write<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var>,
+        start_write_timestamp: AB::Expr,
+    ) {
+        let is_last = is_last_round::<AB>(local);
+        let is_last_blake3: AB::Expr = local.is_last_round(true).into();
+        let dst_ptr = abstract_compose::<AB::Expr, _>(local.instruction.dst_ptr);
+        for (i, (chunk, aux)) in izip!(
+            local.output.chunks_exact(BLAKE_ACCESS_SIZE),
+            &local.mem_oc.output_writes
+        )
+        .enumerate()
+        {
+            let count = if i < BLAKE2S_OUTPUT_WRITES {
+                is_last.clone()
+            } else {
+                is_last_blake3.clone()
+            };
+            self.memory_bridge
+                .write(
+                    MemoryAddress::new(
+                        AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                        dst_ptr.clone() + AB::F::from_canonical_usize(i * BLAKE_ACCESS_SIZE),
+                    ),
+                    from_fn::<_, BLAKE_ACCESS_SIZE, _>(|j| chunk[j]),
+                    start_write_timestamp.clone() + AB::Expr::from_canonical_usize(i),
+                    aux,
+                )
+                .eval(builder, count);
+        }
+    }
+}
+
+fn is_last_round<AB: AirBuilder>(local: &BlakeVmCols<AB::Var>) -> AB::Expr {
+    local.is_last_round(false) + local.is_last_round(true)
+}
+
+fn word_expr<AB: AirBuilder>(word: BlakeWordBits<AB::Var>) -> BlakeWordBits<AB::Expr> {
+    word.map(Into::into)
+}
+
+fn word_const_bits<AB: AirBuilder>(word: u32) -> BlakeWordBits<AB::Expr> {
+    from_fn(|i| AB::Expr::from_bool((word >> i) & 1 == 1))
+}
+
+/// The `j`-th message word of the schedule of the round of the row, as `u16` limbs. It is zero
+/// on dummy rows.
+fn message_limbs<AB: AirBuilder>(local: &BlakeVmCols<AB::Var>, j: usize) -> [AB::Expr; NUM_LIMBS] {
+    let block = &local.instruction.block;
+    let mut limbs = [AB::Expr::ZERO, AB::Expr::ZERO];
+    for is_blake3 in [false, true] {
+        for round in 0..blake_num_rounds(is_blake3) {
+            let flag = local.round_flags[blake_round_flag_idx(is_blake3, round)];
+            let word = blake_msg_schedule(is_blake3, round)[j];
+            for (i, limb) in limbs.iter_mut().enumerate() {
+                // Conversion from bytes to words is little-endian
+                let lo = block[4 * word + 2 * i];
+                let hi = block[4 * word + 2 * i + 1];
+                *limb += flag * (lo + hi * AB::F::from_canonical_u32(1 << 8));
+            }
+        }
+    }
+    limbs
+}
+
+/// Constrain that the sum of the `operands` is `sum` modulo `2^32`, given the carries of the
+/// additions of the `u16` limbs. The limbs of `sum` are range checked by being composed from bits.
+fn assert_add<AB: AirBuilder>(
+    builder: &mut AB,
+    operands: &[[AB::Expr; NUM_LIMBS]],
+    sum: [AB::Expr; NUM_LIMBS],
+    carries: [AB::Var; NUM_LIMBS],
+) {
+    let mut carry = AB::Expr::ZERO;
+    for (i, (limb, &limb_carry)) in sum.into_iter().zip(&carries).enumerate() {
+        let lhs = operands.iter().map(|x| x[i].clone()).sum::<AB::Expr>() + carry;
+        builder.assert_eq(
+            lhs,
+            limb + limb_carry * AB::F::from_canonical_u32(1 << LIMB_BITS),
+        );
+        // The sum of `n` limbs and a carry less than `n` is less than `n * 2^16`, so the carry is
+        // less than `n`
+        builder.assert_zero(
+            (0..operands.len())
+                .map(|k| limb_carry - AB::F::from_canonical_usize(k))
+                .product::<AB::Expr>(),
+        );
+        carry = limb_carry.into();
+    }
+}
+
+fn xor<E: FieldAlgebra>(x: E, y: E) -> E {
+    x.clone() + y.clone() - x * y * E::TWO
+}
+
+fn xor_word<E: FieldAlgebra>(
+    x: &[E; BLAKE_WORD_BITS],
+    y: &[E; BLAKE_WORD_BITS],
+) -> [E; BLAKE_WORD_BITS] {
+    from_fn(|i| xor(x[i].clone(), y[i].clone()))
+}
+
+/// Rotates a word given by its little-endian bits right by `n` bits
+fn rotr<E: Clone>(x: &[E; BLAKE_WORD_BITS], n: usize) -> [E; BLAKE_WORD_BITS] {
+    from_fn(|i| x[(i + n) % BLAKE_WORD_BITS].clone())
+}
+
+fn compose_bits<E: FieldAlgebra>(bits: &[E]) -> E {
+    bits.iter()
+        .rev()
+        .fold(E::ZERO, |acc, bit| acc * E::TWO + bit.clone())
+}
+
+fn limbs<E: FieldAlgebra>(bits: &[E; BLAKE_WORD_BITS]) -> [E; NUM_LIMBS] {
+    from_fn(|i| compose_bits(&bits[i * LIMB_BITS..(i + 1) * LIMB_BITS]))
+}
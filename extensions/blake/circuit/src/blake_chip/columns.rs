@@ -0,0 +1,166 @@
+use openvm_circuit::{
+    arch::ExecutionState,
+    system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols},
+};
+use openvm_circuit_primitives::{utils::assert_array_eq, AlignedBorrow};
+use openvm_instructions::riscv::RV32_REGISTER_NUM_LIMBS;
+use openvm_stark_backend::p3_air::AirBuilder;
+
+use super::{
+    BLAKE_ACCESS_SIZE, BLAKE_BLOCK_BYTES, BLAKE_BLOCK_READS, BLAKE_OUTPUT_BYTES,
+    BLAKE_OUTPUT_WRITES, BLAKE_REGISTER_READS, BLAKE_STATE_READS,
+};
+use crate::utils::{
+    BLAKE2S_ROUNDS, BLAKE3_ROUNDS, BLAKE_G_INDICES, BLAKE_NUM_G, BLAKE_STATE_WIDTH,
+};
+
+pub const BLAKE_WORD_BITS: usize = 32;
+/// Number of round flags: one for each round of BLAKE2s, followed by one for each round of BLAKE3
+pub const BLAKE_NUM_ROUND_FLAGS: usize = BLAKE2S_ROUNDS + BLAKE3_ROUNDS;
+
+/// A 32-bit word as its little-endian bits
+pub type BlakeWordBits<T> = [T; BLAKE_WORD_BITS];
+
+#[repr(C)]
+#[derive(Debug, AlignedBorrow)]
+pub struct BlakeVmCols<T> {
+    /// One-hot flags for the round of the row, which also determine the opcode. None of them is
+    /// set on dummy rows.
+    pub round_flags: [T; BLAKE_NUM_ROUND_FLAGS],
+    /// The state at the start of the round
+    pub state: [BlakeWordBits<T>; BLAKE_STATE_WIDTH],
+    /// The applications of `G` in the round, in the order of
+    /// [BLAKE_G_INDICES](crate::utils::BLAKE_G_INDICES)
+    pub g: [BlakeGCols<T>; BLAKE_NUM_G],
+    /// Columns for the instruction interface, which are the same on all rows of an instruction
+    pub instruction: BlakeInstructionCols<T>,
+    /// `v[i] ^ v[i + 8]` where `v` is the state at the end of the last round. Only used on the
+    /// last round.
+    pub final_xor: [BlakeWordBits<T>; BLAKE_STATE_WIDTH / 2],
+    /// The bytes written to memory on the last round
+    pub output: [T; BLAKE_OUTPUT_BYTES],
+    /// Auxiliary columns for offline memory checking. Only used on the last round.
+    pub mem_oc: BlakeMemoryCols<T>,
+}
+
+/// Columns for an application of `G` to the state words `(a, b, c, d)` with message words
+/// `(mx, my)`. The intermediate values `d1 = (d ^ a1) >>> 16` and `b1 = (b ^ c1) >>> 12` are
+/// not stored, since they are of degree 2 in the other columns.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct BlakeGCols<T> {
+    /// `a + b + mx`
+    pub a1: BlakeWordBits<T>,
+    /// `c + d1`
+    pub c1: BlakeWordBits<T>,
+    /// `a1 + b1 + my`
+    pub a2: BlakeWordBits<T>,
+    /// `(b1 ^ c2) >>> 7`
+    pub b2: BlakeWordBits<T>,
+    /// `c1 + d2`
+    pub c2: BlakeWordBits<T>,
+    /// `(d1 ^ a2) >>> 8`
+    pub d2: BlakeWordBits<T>,
+    /// Carries of the 16-bit limbs of the additions computing `a1`, `c1`, `a2` and `c2`
+    pub carries: [[T; 2]; 4],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct BlakeInstructionCols<T> {
+    pub from_state: ExecutionState<T>,
+    pub rd_ptr: T,
+    pub rs1_ptr: T,
+    pub rs2_ptr: T,
+    /// Memory pointers read from the `rd`, `rs1` and `rs2` registers
+    pub dst_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub state_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub block_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    /// The chaining value, which is the first 8 words of the state in memory
+    pub cv: [BlakeWordBits<T>; 8],
+    /// The bytes of the 4 parameter words, which are the rest of the state in memory
+    pub params: [T; 16],
+    /// The bytes of the message block
+    pub block: [T; BLAKE_BLOCK_BYTES],
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, AlignedBorrow)]
+pub struct BlakeMemoryCols<T> {
+    pub register_aux: [MemoryReadAuxCols<T>; BLAKE_REGISTER_READS],
+    pub state_reads: [MemoryReadAuxCols<T>; BLAKE_STATE_READS],
+    pub block_reads: [MemoryReadAuxCols<T>; BLAKE_BLOCK_READS],
+    pub output_writes: [MemoryWriteAuxCols<T, BLAKE_ACCESS_SIZE>; BLAKE_OUTPUT_WRITES],
+}
+
+/// Index of the round flag of the given round
+#[inline(always)]
+pub const fn blake_round_flag_idx(is_blake3: bool, round: usize) -> usize {
+    if is_blake3 {
+        BLAKE2S_ROUNDS + round
+    } else {
+        round
+    }
+}
+
+impl<T: Copy> BlakeVmCols<T> {
+    pub fn is_first_round(&self, is_blake3: bool) -> T {
+        self.round_flags[blake_round_flag_idx(is_blake3, 0)]
+    }
+
+    pub fn is_last_round(&self, is_blake3: bool) -> T {
+        let num_rounds = if is_blake3 {
+            BLAKE3_ROUNDS
+        } else {
+            BLAKE2S_ROUNDS
+        };
+        self.round_flags[blake_round_flag_idx(is_blake3, num_rounds - 1)]
+    }
+
+    /// The state words `(a, b, c, d)` input to the `g`-th application of `G`, which are either
+    /// words of the state at the start of the round or outputs of previous applications.
+    pub fn g_inputs(&self, g: usize) -> [BlakeWordBits<T>; 4] {
+        let state = self.state_after(g);
+        BLAKE_G_INDICES[g].map(|i| state[i])
+    }
+
+    /// The state at the end of the round
+    pub fn round_output(&self) -> [BlakeWordBits<T>; BLAKE_STATE_WIDTH] {
+        self.state_after(BLAKE_NUM_G)
+    }
+
+    /// The state after the first `num_g` applications of `G` in the round
+    fn state_after(&self, num_g: usize) -> [BlakeWordBits<T>; BLAKE_STATE_WIDTH] {
+        let mut state = self.state;
+        for (cols, [a, b, c, d]) in self.g.iter().zip(BLAKE_G_INDICES).take(num_g) {
+            state[a] = cols.a2;
+            state[b] = cols.b2;
+            state[c] = cols.c2;
+            state[d] = cols.d2;
+        }
+        state
+    }
+}
+
+impl<T: Copy> BlakeInstructionCols<T> {
+    pub fn assert_eq<AB: AirBuilder>(&self, builder: &mut AB, other: Self)
+    where
+        T: Into<AB::Expr>,
+    {
+        builder.assert_eq(self.from_state.pc, other.from_state.pc);
+        builder.assert_eq(self.from_state.timestamp, other.from_state.timestamp);
+        builder.assert_eq(self.rd_ptr, other.rd_ptr);
+        builder.assert_eq(self.rs1_ptr, other.rs1_ptr);
+        builder.assert_eq(self.rs2_ptr, other.rs2_ptr);
+        assert_array_eq(builder, self.dst_ptr, other.dst_ptr);
+        assert_array_eq(builder, self.state_ptr, other.state_ptr);
+        assert_array_eq(builder, self.block_ptr, other.block_ptr);
+        for (word, other_word) in self.cv.into_iter().zip(other.cv) {
+            assert_array_eq(builder, word, other_word);
+        }
+        assert_array_eq(builder, self.params, other.params);
+        assert_array_eq(builder, self.block, other.block);
+    }
+}
+
+pub const BLAKE_VM_WIDTH: usize = BlakeVmCols::<u8>::width();
@@ -0,0 +1,221 @@
+use std::borrow::{Borrow, BorrowMut};
+
+use openvm_blake_transpiler::Rv32BlakeOpcode;
+use openvm_circuit::{arch::*, system::memory::online::GuestMemory};
+use openvm_circuit_primitives::AlignedBytesBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+    LocalOpcode,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use super::{
+    BlakeVmExecutor, BLAKE2S_OUTPUT_WRITES, BLAKE_ACCESS_SIZE, BLAKE_BLOCK_BYTES,
+    BLAKE_OUTPUT_WRITES, BLAKE_STATE_BYTES,
+};
+use crate::utils::{blake_compress_bytes, blake_num_rounds};
+
+#[derive(AlignedBytesBorrow, Clone)]
+#[repr(C)]
+struct BlakePreCompute {
+    a: u8,
+    b: u8,
+    c: u8,
+}
+
+macro_rules! dispatch {
+    ($execute_impl:ident, $opcode:expr) => {
+        if $opcode == Rv32BlakeOpcode::BLAKE3_COMPRESS.global_opcode() {
+            Ok($execute_impl::<_, _, true>)
+        } else {
+            Ok($execute_impl::<_, _, false>)
+        }
+    };
+}
+
+impl<F: PrimeField32> Executor<F> for BlakeVmExecutor {
+    fn pre_compute_size(&self) -> usize {
+        size_of::<BlakePreCompute>()
+    }
+
+    #[cfg(not(feature = "tco"))]
+    fn pre_compute<Ctx>(
+        &self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<ExecuteFunc<F, Ctx>, StaticProgramError>
+    where
+        Ctx: ExecutionCtxTrait,
+    {
+        let data: &mut BlakePreCompute = data.borrow_mut();
+        self.pre_compute_impl(pc, inst, data)?;
+        dispatch!(execute_e1_impl, inst.opcode)
+    }
+
+    #[cfg(feature = "tco")]
+    fn handler<Ctx>(
+        &self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<Handler<F, Ctx>, StaticProgramError>
+    where
+        Ctx: ExecutionCtxTrait,
+    {
+        let data: &mut BlakePreCompute = data.borrow_mut();
+        self.pre_compute_impl(pc, inst, data)?;
+        dispatch!(execute_e1_handler, inst.opcode)
+    }
+}
+
+impl<F: PrimeField32> MeteredExecutor<F> for BlakeVmExecutor {
+    fn metered_pre_compute_size(&self) -> usize {
+        size_of::<E2PreCompute<BlakePreCompute>>()
+    }
+
+    #[cfg(not(feature = "tco"))]
+    fn metered_pre_compute<Ctx>(
+        &self,
+        chip_idx: usize,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<ExecuteFunc<F, Ctx>, StaticProgramError>
+    where
+        Ctx: MeteredExecutionCtxTrait,
+    {
+        let data: &mut E2PreCompute<BlakePreCompute> = data.borrow_mut();
+        data.chip_idx = chip_idx as u32;
+        self.pre_compute_impl(pc, inst, &mut data.data)?;
+        dispatch!(execute_e2_impl, inst.opcode)
+    }
+
+    #[cfg(feature = "tco")]
+    fn metered_handler<Ctx>(
+        &self,
+        chip_idx: usize,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<Handler<F, Ctx>, StaticProgramError>
+    where
+        Ctx: MeteredExecutionCtxTrait,
+    {
+        let data: &mut E2PreCompute<BlakePreCompute> = data.borrow_mut();
+        data.chip_idx = chip_idx as u32;
+        self.pre_compute_impl(pc, inst, &mut data.data)?;
+        dispatch!(execute_e2_handler, inst.opcode)
+    }
+}
+
+#[inline(always)]
+unsafe fn execute_e12_impl<F: PrimeField32, CTX: ExecutionCtxTrait, const IS_BLAKE3: bool>(
+    pre_compute: &BlakePreCompute,
+    instret: &mut u64,
+    pc: &mut u32,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let dst = u32::from_le_bytes(exec_state.vm_read(RV32_REGISTER_AS, pre_compute.a as u32));
+    let state_ptr = u32::from_le_bytes(exec_state.vm_read(RV32_REGISTER_AS, pre_compute.b as u32));
+    let block_ptr = u32::from_le_bytes(exec_state.vm_read(RV32_REGISTER_AS, pre_compute.c as u32));
+
+    // Memory is accessed in chunks of `BLAKE_ACCESS_SIZE` bytes, as in the AIR
+    let mut state = [0u8; BLAKE_STATE_BYTES];
+    for (i, chunk) in state.chunks_exact_mut(BLAKE_ACCESS_SIZE).enumerate() {
+        chunk.copy_from_slice(&exec_state.vm_read::<u8, BLAKE_ACCESS_SIZE>(
+            RV32_MEMORY_AS,
+            state_ptr + (i * BLAKE_ACCESS_SIZE) as u32,
+        ));
+    }
+    let mut block = [0u8; BLAKE_BLOCK_BYTES];
+    for (i, chunk) in block.chunks_exact_mut(BLAKE_ACCESS_SIZE).enumerate() {
+        chunk.copy_from_slice(&exec_state.vm_read::<u8, BLAKE_ACCESS_SIZE>(
+            RV32_MEMORY_AS,
+            block_ptr + (i * BLAKE_ACCESS_SIZE) as u32,
+        ));
+    }
+
+    let output = blake_compress_bytes(IS_BLAKE3, &state, &block);
+    let num_writes = if IS_BLAKE3 {
+        BLAKE_OUTPUT_WRITES
+    } else {
+        BLAKE2S_OUTPUT_WRITES
+    };
+    for (i, chunk) in output
+        .chunks_exact(BLAKE_ACCESS_SIZE)
+        .take(num_writes)
+        .enumerate()
+    {
+        exec_state.vm_write::<u8, BLAKE_ACCESS_SIZE>(
+            RV32_MEMORY_AS,
+            dst + (i * BLAKE_ACCESS_SIZE) as u32,
+            chunk.try_into().unwrap(),
+        );
+    }
+
+    *pc = pc.wrapping_add(DEFAULT_PC_STEP);
+    *instret += 1;
+}
+
+#[create_handler]
+#[inline(always)]
+unsafe fn execute_e1_impl<F: PrimeField32, CTX: ExecutionCtxTrait, const IS_BLAKE3: bool>(
+    pre_compute: &[u8],
+    instret: &mut u64,
+    pc: &mut u32,
+    _instret_end: u64,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let pre_compute: &BlakePreCompute = pre_compute.borrow();
+    execute_e12_impl::<F, CTX, IS_BLAKE3>(pre_compute, instret, pc, exec_state);
+}
+
+#[create_handler]
+#[inline(always)]
+unsafe fn execute_e2_impl<F: PrimeField32, CTX: MeteredExecutionCtxTrait, const IS_BLAKE3: bool>(
+    pre_compute: &[u8],
+    instret: &mut u64,
+    pc: &mut u32,
+    _arg: u64,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let pre_compute: &E2PreCompute<BlakePreCompute> = pre_compute.borrow();
+    execute_e12_impl::<F, CTX, IS_BLAKE3>(&pre_compute.data, instret, pc, exec_state);
+    exec_state.ctx.on_height_change(
+        pre_compute.chip_idx as usize,
+        blake_num_rounds(IS_BLAKE3) as u32,
+    );
+}
+
+impl BlakeVmExecutor {
+    fn pre_compute_impl<F: PrimeField32>(
+        &self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut BlakePreCompute,
+    ) -> Result<(), StaticProgramError> {
+        let Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = inst;
+        if d.as_canonical_u32() != RV32_REGISTER_AS || e.as_canonical_u32() != RV32_MEMORY_AS {
+            return Err(StaticProgramError::InvalidInstruction(pc));
+        }
+        *data = BlakePreCompute {
+            a: a.as_canonical_u32() as u8,
+            b: b.as_canonical_u32() as u8,
+            c: c.as_canonical_u32() as u8,
+        };
+        let local_opcode = opcode.local_opcode_idx(self.offset);
+        assert!(Rv32BlakeOpcode::from_repr(local_opcode).is_some());
+        Ok(())
+    }
+}
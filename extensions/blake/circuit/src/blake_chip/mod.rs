@@ -0,0 +1,68 @@
+//! BLAKE2s and BLAKE3 compression functions applied to a state and a message block in VM memory.
+//! Both variants share the same AIR: every instruction takes one row per round, and each row
+//! computes the eight applications of the `G` function of its round on the bit decomposition of
+//! the state words.
+//!
+//! The state read from memory is the 8-word chaining value followed by 4 parameter words, and
+//! the output is either the new chaining value for BLAKE2s, or the full 16-word output for
+//! BLAKE3. Padding, counters and flags are left to the guest, so that streaming hashers, keyed
+//! hashing and the BLAKE3 tree mode can be built on top.
+
+use openvm_circuit::arch::VmChipWrapper;
+use openvm_circuit_primitives::bitwise_op_lookup::SharedBitwiseOperationLookupChip;
+use openvm_instructions::riscv::RV32_CELL_BITS;
+
+mod air;
+mod columns;
+mod execution;
+mod trace;
+
+pub use air::*;
+pub use columns::*;
+pub use trace::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of bytes of the state read from memory: the chaining value and the parameter words
+pub const BLAKE_STATE_BYTES: usize = 48;
+pub const BLAKE_BLOCK_BYTES: usize = 64;
+/// Number of bytes written by BLAKE3. BLAKE2s only writes the first half.
+pub const BLAKE_OUTPUT_BYTES: usize = 64;
+/// Number of bytes of every memory access of the state, block and output
+pub const BLAKE_ACCESS_SIZE: usize = 16;
+/// Register reads to get dst, state and block pointers
+pub(crate) const BLAKE_REGISTER_READS: usize = 3;
+pub(crate) const BLAKE_STATE_READS: usize = BLAKE_STATE_BYTES / BLAKE_ACCESS_SIZE;
+pub(crate) const BLAKE_BLOCK_READS: usize = BLAKE_BLOCK_BYTES / BLAKE_ACCESS_SIZE;
+pub(crate) const BLAKE_OUTPUT_WRITES: usize = BLAKE_OUTPUT_BYTES / BLAKE_ACCESS_SIZE;
+/// Number of output writes of BLAKE2s, which only writes the chaining value
+pub(crate) const BLAKE2S_OUTPUT_WRITES: usize = BLAKE_OUTPUT_WRITES / 2;
+
+/// Amount the timestamp advances by: the register reads, the state and block reads, and the
+/// output writes
+#[inline(always)]
+pub(crate) const fn blake_timestamp_delta(is_blake3: bool) -> usize {
+    BLAKE_REGISTER_READS
+        + BLAKE_STATE_READS
+        + BLAKE_BLOCK_READS
+        + if is_blake3 {
+            BLAKE_OUTPUT_WRITES
+        } else {
+            BLAKE2S_OUTPUT_WRITES
+        }
+}
+
+pub type BlakeVmChip<F> = VmChipWrapper<F, BlakeVmFiller>;
+
+#[derive(derive_new::new, Clone, Copy)]
+pub struct BlakeVmExecutor {
+    pub offset: usize,
+    pub pointer_max_bits: usize,
+}
+
+#[derive(derive_new::new)]
+pub struct BlakeVmFiller {
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    pub pointer_max_bits: usize,
+}
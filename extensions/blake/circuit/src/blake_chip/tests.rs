@@ -0,0 +1,281 @@
+use std::{array, borrow::BorrowMut, sync::Arc};
+
+use hex_literal::hex;
+use openvm_blake_transpiler::Rv32BlakeOpcode;
+use openvm_circuit::{
+    arch::{
+        testing::{
+            memory::gen_pointer, TestBuilder, TestChipHarness, VmChipTestBuilder,
+            BITWISE_OP_LOOKUP_BUS,
+        },
+        Arena, ExecutionBridge, PreflightExecutor,
+    },
+    system::memory::{offline_checker::MemoryBridge, SharedMemoryHelper},
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupAir, BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+    SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS},
+    LocalOpcode,
+};
+use openvm_stark_backend::{
+    p3_field::FieldAlgebra,
+    p3_matrix::{dense::DenseMatrix, Matrix},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{
+    BlakeVmAir, BlakeVmChip, BlakeVmCols, BlakeVmExecutor, BlakeVmFiller, BLAKE_BLOCK_BYTES,
+    BLAKE_OUTPUT_BYTES, BLAKE_STATE_BYTES,
+};
+use crate::utils::{blake_compress, blake_compress_bytes, BLAKE_IV};
+
+type F = BabyBear;
+const MAX_INS_CAPACITY: usize = 128;
+type Harness<RA> = TestChipHarness<F, BlakeVmExecutor, BlakeVmAir, BlakeVmChip<F>, RA>;
+
+fn create_harness_fields(
+    execution_bridge: ExecutionBridge,
+    memory_bridge: MemoryBridge,
+    bitwise_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    memory_helper: SharedMemoryHelper<F>,
+    address_bits: usize,
+) -> (BlakeVmAir, BlakeVmExecutor, BlakeVmChip<F>) {
+    let air = BlakeVmAir::new(
+        execution_bridge,
+        memory_bridge,
+        bitwise_chip.bus(),
+        address_bits,
+        Rv32BlakeOpcode::CLASS_OFFSET,
+    );
+    let executor = BlakeVmExecutor::new(Rv32BlakeOpcode::CLASS_OFFSET, address_bits);
+    let chip = BlakeVmChip::new(
+        BlakeVmFiller::new(bitwise_chip, address_bits),
+        memory_helper,
+    );
+    (air, executor, chip)
+}
+
+fn create_test_harness<RA: Arena>(
+    tester: &mut VmChipTestBuilder<F>,
+) -> (
+    Harness<RA>,
+    (
+        BitwiseOperationLookupAir<RV32_CELL_BITS>,
+        SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    ),
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let (air, executor, chip) = create_harness_fields(
+        tester.execution_bridge(),
+        tester.memory_bridge(),
+        bitwise_chip.clone(),
+        tester.memory_helper(),
+        tester.address_bits(),
+    );
+
+    let harness = Harness::<RA>::with_capacity(executor, air, chip, MAX_INS_CAPACITY);
+
+    (harness, (bitwise_chip.air, bitwise_chip))
+}
+
+fn write_bytes(tester: &mut impl TestBuilder<F>, ptr: usize, bytes: &[u8]) {
+    for (i, word) in bytes.chunks_exact(4).enumerate() {
+        tester.write::<4>(
+            RV32_MEMORY_AS as usize,
+            ptr + i * 4,
+            array::from_fn(|j| F::from_canonical_u8(word[j])),
+        );
+    }
+}
+
+/// Executes the compression of `state` and `block`, which are random if not given. If `in_place`,
+/// the output overwrites the state.
+#[allow(clippy::too_many_arguments)]
+fn set_and_execute<RA: Arena, E: PreflightExecutor<F, RA>>(
+    tester: &mut impl TestBuilder<F>,
+    executor: &mut E,
+    arena: &mut RA,
+    rng: &mut StdRng,
+    opcode: Rv32BlakeOpcode,
+    state: Option<[u8; BLAKE_STATE_BYTES]>,
+    block: Option<[u8; BLAKE_BLOCK_BYTES]>,
+    in_place: bool,
+) {
+    let state = state.unwrap_or_else(|| array::from_fn(|_| rng.gen()));
+    let block = block.unwrap_or_else(|| array::from_fn(|_| rng.gen()));
+
+    let rd = gen_pointer(rng, 4);
+    let rs1 = gen_pointer(rng, 4);
+    let rs2 = gen_pointer(rng, 4);
+    let state_ptr = gen_pointer(rng, 4);
+    let block_ptr = gen_pointer(rng, 4);
+    let dst_ptr = if in_place {
+        state_ptr
+    } else {
+        gen_pointer(rng, 4)
+    };
+    for (reg, ptr) in [(rd, dst_ptr), (rs1, state_ptr), (rs2, block_ptr)] {
+        tester.write(
+            RV32_REGISTER_AS as usize,
+            reg,
+            (ptr as u32).to_le_bytes().map(F::from_canonical_u8),
+        );
+    }
+    write_bytes(tester, state_ptr, &state);
+    write_bytes(tester, block_ptr, &block);
+
+    tester.execute(
+        executor,
+        arena,
+        &Instruction::from_usize(
+            opcode.global_opcode(),
+            [
+                rd,
+                rs1,
+                rs2,
+                RV32_REGISTER_AS as usize,
+                RV32_MEMORY_AS as usize,
+            ],
+        ),
+    );
+
+    let is_blake3 = opcode == Rv32BlakeOpcode::BLAKE3_COMPRESS;
+    let expected = blake_compress_bytes(is_blake3, &state, &block);
+    let output_len = if is_blake3 {
+        BLAKE_OUTPUT_BYTES
+    } else {
+        BLAKE_OUTPUT_BYTES / 2
+    };
+    for (i, word) in expected[..output_len].chunks_exact(4).enumerate() {
+        assert_eq!(
+            array::from_fn(|j| F::from_canonical_u8(word[j])),
+            tester.read::<4>(RV32_MEMORY_AS as usize, dst_ptr + i * 4)
+        );
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Randomly generate computations and execute, ensuring that the generated trace
+/// passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn rand_blake_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_test_harness(&mut tester);
+
+    // Interleaves both opcodes, including all-zero inputs and in-place compressions. The trace is
+    // padded with dummy rows.
+    for i in 0..8 {
+        let opcode = if i % 2 == 0 {
+            Rv32BlakeOpcode::BLAKE2S_COMPRESS
+        } else {
+            Rv32BlakeOpcode::BLAKE3_COMPRESS
+        };
+        let zero = (i < 2).then_some(());
+        set_and_execute(
+            &mut tester,
+            &mut harness.executor,
+            &mut harness.arena,
+            &mut rng,
+            opcode,
+            zero.map(|_| [0; BLAKE_STATE_BYTES]),
+            zero.map(|_| [0; BLAKE_BLOCK_BYTES]),
+            i % 3 == 0,
+        );
+    }
+
+    let tester = tester
+        .build()
+        .load(harness)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+// Single-block hashes of "abc", from RFC 7693 and the BLAKE3 reference implementation
+#[test]
+fn test_blake_compress_abc() {
+    let mut block = [0u32; 16];
+    block[0] = u32::from_le_bytes(*b"abc\0");
+
+    // BLAKE2s-256 without a key: the counter is the message length and the last block flag is set
+    let mut state = [0u32; 12];
+    state[..8].copy_from_slice(&BLAKE_IV);
+    state[0] ^= 0x01010020;
+    state[8..].copy_from_slice(&[3, 0, u32::MAX, 0]);
+    let output = blake_compress(false, &state, &block);
+    assert_eq!(
+        output[..8]
+            .iter()
+            .flat_map(|w| w.to_le_bytes())
+            .collect::<Vec<_>>(),
+        hex!("508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982")
+    );
+
+    // BLAKE3: the block length is the message length, with the CHUNK_START, CHUNK_END and ROOT
+    // flags
+    state[..8].copy_from_slice(&BLAKE_IV);
+    state[8..].copy_from_slice(&[0, 0, 3, 1 | 2 | 8]);
+    let output = blake_compress(true, &state, &block);
+    assert_eq!(
+        output[..8]
+            .iter()
+            .flat_map(|w| w.to_le_bytes())
+            .collect::<Vec<_>>(),
+        hex!("6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85")
+    );
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// part of the trace and check that the chip throws the expected error.
+//////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn test_blake_round_negative() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_test_harness(&mut tester);
+
+    set_and_execute(
+        &mut tester,
+        &mut harness.executor,
+        &mut harness.arena,
+        &mut rng,
+        Rv32BlakeOpcode::BLAKE2S_COMPRESS,
+        None,
+        None,
+        false,
+    );
+
+    // Flip the lowest bit of the first sum computed in the first round, which is only checked by
+    // the round constraints and not by any interaction
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let width = trace.width();
+        let cols: &mut BlakeVmCols<F> = trace.values[..width].borrow_mut();
+        cols.g[0].a1[0] = F::ONE - cols.g[0].a1[0];
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(harness, modify_trace)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
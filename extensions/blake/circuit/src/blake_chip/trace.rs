@@ -0,0 +1,416 @@
+use std::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+};
+
+use openvm_blake_transpiler::Rv32BlakeOpcode;
+use openvm_circuit::{
+    arch::*,
+    system::memory::{
+        offline_checker::{MemoryReadAuxRecord, MemoryWriteBytesAuxRecord},
+        online::TracingMemory,
+        MemoryAuxColsFactory,
+    },
+};
+use openvm_circuit_primitives::AlignedBytesBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+    LocalOpcode,
+};
+use openvm_rv32im_circuit::adapters::{tracing_read, tracing_write};
+use openvm_stark_backend::{
+    p3_field::PrimeField32,
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    p3_maybe_rayon::prelude::*,
+};
+
+use super::{
+    blake_round_flag_idx, BlakeInstructionCols, BlakeVmCols, BlakeVmExecutor, BlakeVmFiller,
+    BlakeWordBits, BLAKE2S_OUTPUT_WRITES, BLAKE_ACCESS_SIZE, BLAKE_BLOCK_BYTES, BLAKE_BLOCK_READS,
+    BLAKE_OUTPUT_BYTES, BLAKE_OUTPUT_WRITES, BLAKE_REGISTER_READS, BLAKE_STATE_BYTES,
+    BLAKE_STATE_READS, BLAKE_VM_WIDTH,
+};
+use crate::utils::{
+    blake_compress_bytes, blake_finalize, blake_g, blake_init, blake_msg_schedule,
+    blake_num_rounds, words_from_bytes, BLAKE_G_INDICES,
+};
+
+/// Every instruction is a single compression of one row per round.
+#[derive(Clone, Copy, Debug)]
+pub struct BlakeVmMetadata {
+    pub is_blake3: bool,
+}
+
+impl MultiRowMetadata for BlakeVmMetadata {
+    #[inline(always)]
+    fn get_num_rows(&self) -> usize {
+        blake_num_rounds(self.is_blake3)
+    }
+}
+
+pub(crate) type BlakeVmRecordLayout = MultiRowLayout<BlakeVmMetadata>;
+
+#[repr(C)]
+#[derive(AlignedBytesBorrow, Debug, Clone)]
+pub struct BlakeVmRecord {
+    pub from_pc: u32,
+    pub timestamp: u32,
+    pub rd_ptr: u32,
+    pub rs1_ptr: u32,
+    pub rs2_ptr: u32,
+    pub dst_ptr: u32,
+    pub state_ptr: u32,
+    pub block_ptr: u32,
+    pub is_blake3: bool,
+    /// The chaining value and parameter words, as read from memory
+    pub state: [u8; BLAKE_STATE_BYTES],
+    pub block: [u8; BLAKE_BLOCK_BYTES],
+
+    pub register_reads_aux: [MemoryReadAuxRecord; BLAKE_REGISTER_READS],
+    pub state_reads_aux: [MemoryReadAuxRecord; BLAKE_STATE_READS],
+    pub block_reads_aux: [MemoryReadAuxRecord; BLAKE_BLOCK_READS],
+    /// Only the first [BLAKE2S_OUTPUT_WRITES] are used by BLAKE2s
+    pub writes_aux: [MemoryWriteBytesAuxRecord<BLAKE_ACCESS_SIZE>; BLAKE_OUTPUT_WRITES],
+}
+
+/// The record has a fixed size, but its number of rows depends on the opcode, so the layout
+/// cannot be a default value.
+pub struct BlakeVmRecordMut<'a> {
+    pub inner: &'a mut BlakeVmRecord,
+}
+
+impl<'a> CustomBorrow<'a, BlakeVmRecordMut<'a>, BlakeVmRecordLayout> for [u8] {
+    fn custom_borrow(&'a mut self, _layout: BlakeVmRecordLayout) -> BlakeVmRecordMut<'a> {
+        BlakeVmRecordMut {
+            inner: self.borrow_mut(),
+        }
+    }
+
+    unsafe fn extract_layout(&self) -> BlakeVmRecordLayout {
+        let record: &BlakeVmRecord = self.borrow();
+        BlakeVmRecordLayout::new(BlakeVmMetadata {
+            is_blake3: record.is_blake3,
+        })
+    }
+}
+
+impl SizedRecord<BlakeVmRecordLayout> for BlakeVmRecordMut<'_> {
+    fn size(_layout: &BlakeVmRecordLayout) -> usize {
+        size_of::<BlakeVmRecord>()
+    }
+
+    fn alignment(_layout: &BlakeVmRecordLayout) -> usize {
+        align_of::<BlakeVmRecord>()
+    }
+}
+
+impl<F, RA> PreflightExecutor<F, RA> for BlakeVmExecutor
+where
+    F: PrimeField32,
+    for<'buf> RA: RecordArena<'buf, BlakeVmRecordLayout, BlakeVmRecordMut<'buf>>,
+{
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", Rv32BlakeOpcode::from_usize(opcode - self.offset))
+    }
+
+    fn execute(
+        &self,
+        state: VmStateMut<F, TracingMemory, RA>,
+        instruction: &Instruction<F>,
+    ) -> Result<(), ExecutionError> {
+        let &Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = instruction;
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+        debug_assert_eq!(e.as_canonical_u32(), RV32_MEMORY_AS);
+        let is_blake3 = Rv32BlakeOpcode::from_usize(opcode.local_opcode_idx(self.offset))
+            == Rv32BlakeOpcode::BLAKE3_COMPRESS;
+
+        let record = state
+            .ctx
+            .alloc(BlakeVmRecordLayout::new(BlakeVmMetadata { is_blake3 }))
+            .inner;
+
+        record.from_pc = *state.pc;
+        record.timestamp = state.memory.timestamp();
+        record.is_blake3 = is_blake3;
+        record.rd_ptr = a.as_canonical_u32();
+        record.rs1_ptr = b.as_canonical_u32();
+        record.rs2_ptr = c.as_canonical_u32();
+        let reg_ptrs = [record.rd_ptr, record.rs1_ptr, record.rs2_ptr];
+        let [dst_ptr, state_ptr, block_ptr] = from_fn(|i| {
+            u32::from_le_bytes(tracing_read(
+                state.memory,
+                RV32_REGISTER_AS,
+                reg_ptrs[i],
+                &mut record.register_reads_aux[i].prev_timestamp,
+            ))
+        });
+        record.dst_ptr = dst_ptr;
+        record.state_ptr = state_ptr;
+        record.block_ptr = block_ptr;
+        debug_assert!(record.dst_ptr as usize + BLAKE_OUTPUT_BYTES <= (1 << self.pointer_max_bits));
+        debug_assert!(
+            record.state_ptr as usize + BLAKE_STATE_BYTES <= (1 << self.pointer_max_bits)
+        );
+        debug_assert!(
+            record.block_ptr as usize + BLAKE_BLOCK_BYTES <= (1 << self.pointer_max_bits)
+        );
+
+        for (i, (chunk, aux)) in record
+            .state
+            .chunks_exact_mut(BLAKE_ACCESS_SIZE)
+            .zip(record.state_reads_aux.iter_mut())
+            .enumerate()
+        {
+            chunk.copy_from_slice(&tracing_read::<BLAKE_ACCESS_SIZE>(
+                state.memory,
+                RV32_MEMORY_AS,
+                record.state_ptr + (i * BLAKE_ACCESS_SIZE) as u32,
+                &mut aux.prev_timestamp,
+            ));
+        }
+        for (i, (chunk, aux)) in record
+            .block
+            .chunks_exact_mut(BLAKE_ACCESS_SIZE)
+            .zip(record.block_reads_aux.iter_mut())
+            .enumerate()
+        {
+            chunk.copy_from_slice(&tracing_read::<BLAKE_ACCESS_SIZE>(
+                state.memory,
+                RV32_MEMORY_AS,
+                record.block_ptr + (i * BLAKE_ACCESS_SIZE) as u32,
+                &mut aux.prev_timestamp,
+            ));
+        }
+
+        let output = blake_compress_bytes(is_blake3, &record.state, &record.block);
+        let num_writes = if is_blake3 {
+            BLAKE_OUTPUT_WRITES
+        } else {
+            BLAKE2S_OUTPUT_WRITES
+        };
+        for (i, (chunk, aux)) in output
+            .chunks_exact(BLAKE_ACCESS_SIZE)
+            .zip(record.writes_aux.iter_mut())
+            .take(num_writes)
+            .enumerate()
+        {
+            tracing_write::<BLAKE_ACCESS_SIZE>(
+                state.memory,
+                RV32_MEMORY_AS,
+                record.dst_ptr + (i * BLAKE_ACCESS_SIZE) as u32,
+                chunk.try_into().unwrap(),
+                &mut aux.prev_timestamp,
+                &mut aux.prev_data,
+            );
+        }
+
+        *state.pc = state.pc.wrapping_add(DEFAULT_PC_STEP);
+        Ok(())
+    }
+}
+
+impl<F: PrimeField32> TraceFiller<F> for BlakeVmFiller {
+    fn fill_trace(
+        &self,
+        mem_helper: &MemoryAuxColsFactory<F>,
+        trace_matrix: &mut RowMajorMatrix<F>,
+        rows_used: usize,
+    ) {
+        if rows_used == 0 {
+            return;
+        }
+        debug_assert_eq!(trace_matrix.width(), BLAKE_VM_WIDTH);
+        let (mut trace, dummy_trace) = trace_matrix.values.split_at_mut(rows_used * BLAKE_VM_WIDTH);
+
+        // First pass over the trace to split it into the rows of every instruction, since their
+        // number depends on the opcode
+        let mut chunks = Vec::new();
+        while !trace.is_empty() {
+            // SAFETY:
+            // - caller ensures `trace` contains a valid record representation that was previously
+            //   written by the executor
+            // - every record is allocated at the start of the rows of its instruction
+            let record: &BlakeVmRecord = unsafe { get_record_from_slice(&mut trace, ()) };
+            let num_rows = blake_num_rounds(record.is_blake3);
+            let (chunk, rest) = trace.split_at_mut(num_rows * BLAKE_VM_WIDTH);
+            chunks.push(chunk);
+            trace = rest;
+        }
+
+        chunks.into_par_iter().for_each(|mut slice| {
+            // SAFETY: see above
+            let record: &BlakeVmRecord = unsafe { get_record_from_slice(&mut slice, ()) };
+            // Copy the record to safely fill in the trace matrix without overwriting it
+            let record = record.clone();
+            self.fill_compression(mem_helper, slice, &record);
+        });
+
+        // Dummy rows are all zero, which satisfies the round constraints
+        dummy_trace.par_iter_mut().for_each(|x| *x = F::ZERO);
+    }
+}
+
+impl BlakeVmFiller {
+    /// Fills the rows of every round of the compression of `record` into `slice`.
+    fn fill_compression<F: PrimeField32>(
+        &self,
+        mem_helper: &MemoryAuxColsFactory<F>,
+        slice: &mut [F],
+        record: &BlakeVmRecord,
+    ) {
+        let is_blake3 = record.is_blake3;
+        let num_rounds = blake_num_rounds(is_blake3);
+        let state: [u32; 12] = words_from_bytes(&record.state);
+        let block: [u32; 16] = words_from_bytes(&record.block);
+        let cv: [u32; 8] = from_fn(|i| state[i]);
+
+        // Range check the most significant limbs of the pointers
+        let msl_rshift = RV32_CELL_BITS * (RV32_REGISTER_NUM_LIMBS - 1);
+        let msl_lshift = RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.pointer_max_bits;
+        let [dst_msl, state_msl, block_msl] = [record.dst_ptr, record.state_ptr, record.block_ptr]
+            .map(|ptr| (ptr >> msl_rshift) << msl_lshift);
+        self.bitwise_lookup_chip.request_range(dst_msl, state_msl);
+        self.bitwise_lookup_chip.request_range(block_msl, block_msl);
+
+        let instruction = BlakeInstructionCols {
+            from_state: ExecutionState::new(
+                F::from_canonical_u32(record.from_pc),
+                F::from_canonical_u32(record.timestamp),
+            ),
+            rd_ptr: F::from_canonical_u32(record.rd_ptr),
+            rs1_ptr: F::from_canonical_u32(record.rs1_ptr),
+            rs2_ptr: F::from_canonical_u32(record.rs2_ptr),
+            dst_ptr: record.dst_ptr.to_le_bytes().map(F::from_canonical_u8),
+            state_ptr: record.state_ptr.to_le_bytes().map(F::from_canonical_u8),
+            block_ptr: record.block_ptr.to_le_bytes().map(F::from_canonical_u8),
+            cv: cv.map(word_bits),
+            params: from_fn(|i| F::from_canonical_u8(record.state[32 + i])),
+            block: record.block.map(F::from_canonical_u8),
+        };
+
+        // The state at the start of every round, since rounds are filled in parallel
+        let mut round_states = Vec::with_capacity(num_rounds + 1);
+        round_states.push(blake_init(is_blake3, &state));
+        for round in 0..num_rounds {
+            let mut v = round_states[round];
+            let schedule = blake_msg_schedule(is_blake3, round);
+            for (g, [a, b, c, d]) in BLAKE_G_INDICES.into_iter().enumerate() {
+                let [.., a2, d2, c2, b2] = blake_g(
+                    v[a],
+                    v[b],
+                    v[c],
+                    v[d],
+                    block[schedule[2 * g]],
+                    block[schedule[2 * g + 1]],
+                );
+                (v[a], v[b], v[c], v[d]) = (a2, b2, c2, d2);
+            }
+            round_states.push(v);
+        }
+        let final_state = round_states[num_rounds];
+        let output = blake_finalize(is_blake3, &final_state, &cv);
+
+        let start_read_timestamp = record.timestamp + BLAKE_REGISTER_READS as u32;
+        let start_block_timestamp = start_read_timestamp + BLAKE_STATE_READS as u32;
+        let start_write_timestamp = start_block_timestamp + BLAKE_BLOCK_READS as u32;
+        let num_writes = if is_blake3 {
+            BLAKE_OUTPUT_WRITES
+        } else {
+            BLAKE2S_OUTPUT_WRITES
+        };
+
+        slice
+            .par_chunks_exact_mut(BLAKE_VM_WIDTH)
+            .enumerate()
+            .for_each(|(round, row)| {
+                row.fill(F::ZERO);
+                let cols: &mut BlakeVmCols<F> = row.borrow_mut();
+                cols.round_flags[blake_round_flag_idx(is_blake3, round)] = F::ONE;
+                cols.instruction = instruction;
+
+                let mut v = round_states[round];
+                cols.state = v.map(word_bits);
+                let schedule = blake_msg_schedule(is_blake3, round);
+                for (g, [a, b, c, d]) in BLAKE_G_INDICES.into_iter().enumerate() {
+                    let (mx, my) = (block[schedule[2 * g]], block[schedule[2 * g + 1]]);
+                    let [a1, d1, c1, b1, a2, d2, c2, b2] = blake_g(v[a], v[b], v[c], v[d], mx, my);
+                    let g_cols = &mut cols.g[g];
+                    g_cols.a1 = word_bits(a1);
+                    g_cols.c1 = word_bits(c1);
+                    g_cols.a2 = word_bits(a2);
+                    g_cols.b2 = word_bits(b2);
+                    g_cols.c2 = word_bits(c2);
+                    g_cols.d2 = word_bits(d2);
+                    g_cols.carries = [
+                        add_carries(&[v[a], v[b], mx]),
+                        add_carries(&[v[c], d1]),
+                        add_carries(&[a1, b1, my]),
+                        add_carries(&[c1, d2]),
+                    ];
+                    (v[a], v[b], v[c], v[d]) = (a2, b2, c2, d2);
+                }
+
+                if round != num_rounds - 1 {
+                    return;
+                }
+                cols.final_xor = from_fn(|i| word_bits(final_state[i] ^ final_state[i + 8]));
+                cols.output = from_fn(|i| F::from_canonical_u8(output[i / 4].to_le_bytes()[i % 4]));
+
+                let mut timestamp = record.timestamp;
+                for (aux_cols, aux) in cols
+                    .mem_oc
+                    .register_aux
+                    .iter_mut()
+                    .chain(cols.mem_oc.state_reads.iter_mut())
+                    .chain(cols.mem_oc.block_reads.iter_mut())
+                    .zip(
+                        record
+                            .register_reads_aux
+                            .iter()
+                            .chain(&record.state_reads_aux)
+                            .chain(&record.block_reads_aux),
+                    )
+                {
+                    mem_helper.fill(aux.prev_timestamp, timestamp, aux_cols.as_mut());
+                    timestamp += 1;
+                }
+                debug_assert_eq!(timestamp, start_write_timestamp);
+                for (i, (aux_cols, aux)) in cols
+                    .mem_oc
+                    .output_writes
+                    .iter_mut()
+                    .zip(record.writes_aux.iter())
+                    .take(num_writes)
+                    .enumerate()
+                {
+                    aux_cols.set_prev_data(aux.prev_data.map(F::from_canonical_u8));
+                    mem_helper.fill(
+                        aux.prev_timestamp,
+                        start_write_timestamp + i as u32,
+                        aux_cols.as_mut(),
+                    );
+                }
+            });
+    }
+}
+
+fn word_bits<F: PrimeField32>(word: u32) -> BlakeWordBits<F> {
+    from_fn(|i| F::from_bool((word >> i) & 1 == 1))
+}
+
+/// Carries of the additions of the `u16` limbs of `operands`, as constrained by the AIR
+fn add_carries<F: PrimeField32>(operands: &[u32]) -> [F; 2] {
+    let lo: u32 = operands.iter().map(|x| x & 0xffff).sum();
+    let hi: u32 = operands.iter().map(|x| x >> 16).sum::<u32>() + (lo >> 16);
+    [lo >> 16, hi >> 16].map(F::from_canonical_u32)
+}
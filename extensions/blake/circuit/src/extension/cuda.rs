@@ -0,0 +1,79 @@
+use std::borrow::Borrow;
+
+use openvm_circuit::{
+    arch::{Arena, DenseRecordArena, MatrixRecordArena},
+    system::cuda::extensions::{get_inventory_range_checker, get_or_create_bitwise_op_lookup},
+};
+use openvm_cuda_backend::{
+    chip::{cpu_proving_ctx_to_gpu, get_empty_air_proving_ctx},
+    engine::GpuBabyBearPoseidon2Engine,
+    prover_backend::GpuBackend,
+    types::F,
+};
+use openvm_stark_backend::{prover::types::AirProvingContext, Chip};
+use openvm_stark_sdk::config::baby_bear_poseidon2::BabyBearPoseidon2Config;
+
+use super::*;
+use crate::utils::blake_num_rounds;
+
+/// BLAKE chip for the GPU backend which still does trace generation on CPU.
+#[derive(derive_new::new)]
+pub struct HybridBlakeChip {
+    cpu: BlakeVmChip<F>,
+}
+
+// Conversion of Dense->Matrix Record Arena, cpu tracegen, and then H2D transfer of the trace
+// matrix.
+impl Chip<DenseRecordArena, GpuBackend> for HybridBlakeChip {
+    fn generate_proving_ctx(&self, mut arena: DenseRecordArena) -> AirProvingContext<GpuBackend> {
+        let records = arena.allocated();
+        if records.is_empty() {
+            return get_empty_air_proving_ctx::<GpuBackend>();
+        }
+        debug_assert_eq!(records.len() % size_of::<BlakeVmRecord>(), 0);
+
+        // Every record has the same size, but the number of rows depends on the opcode
+        let num_rows: usize = records
+            .chunks_exact(size_of::<BlakeVmRecord>())
+            .map(|record| {
+                let record: &BlakeVmRecord = record.borrow();
+                blake_num_rounds(record.is_blake3)
+            })
+            .sum();
+        let height = num_rows.next_power_of_two();
+        let mut matrix_arena = MatrixRecordArena::<F>::with_capacity(height, BLAKE_VM_WIDTH);
+        arena
+            .get_record_seeker::<BlakeVmRecordMut, BlakeVmRecordLayout>()
+            .transfer_to_matrix_arena(&mut matrix_arena);
+        let ctx = self.cpu.generate_proving_ctx(matrix_arena);
+        cpu_proving_ctx_to_gpu(ctx)
+    }
+}
+
+pub struct BlakeGpuProverExt;
+
+impl VmProverExtension<GpuBabyBearPoseidon2Engine, DenseRecordArena, Blake> for BlakeGpuProverExt {
+    fn extend_prover(
+        &self,
+        _: &Blake,
+        inventory: &mut ChipInventory<BabyBearPoseidon2Config, DenseRecordArena, GpuBackend>,
+    ) -> Result<(), ChipInventoryError> {
+        let pointer_max_bits = inventory.airs().pointer_max_bits();
+        let timestamp_max_bits = inventory.timestamp_max_bits();
+
+        let range_checker = get_inventory_range_checker(inventory);
+        let bitwise_lu = get_or_create_bitwise_op_lookup(inventory)?;
+
+        // BLAKE trace generation is done on CPU, with the CPU counterparts of the lookup chips
+        inventory.next_air::<BlakeVmAir>()?;
+        let mem_helper =
+            SharedMemoryHelper::new(range_checker.cpu_chip.clone().unwrap(), timestamp_max_bits);
+        let blake = BlakeVmChip::new(
+            BlakeVmFiller::new(bitwise_lu.cpu_chip.clone().unwrap(), pointer_max_bits),
+            mem_helper,
+        );
+        inventory.add_executor_chip(HybridBlakeChip::new(blake));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use std::{result::Result, sync::Arc};
+
+use derive_more::derive::From;
+use openvm_blake_transpiler::Rv32BlakeOpcode;
+use openvm_circuit::{
+    arch::{
+        AirInventory, AirInventoryError, ChipInventory, ChipInventoryError, ExecutionBridge,
+        ExecutorInventoryBuilder, ExecutorInventoryError, RowMajorMatrixArena, VmCircuitExtension,
+        VmExecutionExtension, VmProverExtension,
+    },
+    system::{memory::SharedMemoryHelper, SystemPort},
+};
+use openvm_circuit_derive::{AnyEnum, Executor, MeteredExecutor, PreflightExecutor};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupAir, BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+    SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::*;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_field::PrimeField32,
+    prover::cpu::{CpuBackend, CpuDevice},
+};
+use openvm_stark_sdk::engine::StarkEngine;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::*;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "cuda")] {
+        mod cuda;
+        pub use self::cuda::*;
+        pub use self::cuda::BlakeGpuProverExt as BlakeProverExt;
+    } else if #[cfg(feature = "rocm")] {
+        mod hip;
+        pub use self::hip::*;
+        pub use self::hip::BlakeHipProverExt as BlakeProverExt;
+    } else {
+        pub use self::BlakeCpuProverExt as BlakeProverExt;
+    }
+}
+
+// =================================== VM Extension Implementation =================================
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Blake;
+
+#[derive(Clone, Copy, From, AnyEnum, Executor, MeteredExecutor, PreflightExecutor)]
+pub enum BlakeExecutor {
+    Blake(BlakeVmExecutor),
+}
+
+impl<F: PrimeField32> VmExecutionExtension<F> for Blake {
+    type Executor = BlakeExecutor;
+
+    fn extend_execution(
+        &self,
+        inventory: &mut ExecutorInventoryBuilder<F, BlakeExecutor>,
+    ) -> Result<(), ExecutorInventoryError> {
+        let pointer_max_bits = inventory.pointer_max_bits();
+        let blake = BlakeVmExecutor::new(Rv32BlakeOpcode::CLASS_OFFSET, pointer_max_bits);
+        inventory.add_executor(blake, Rv32BlakeOpcode::iter().map(|x| x.global_opcode()))?;
+
+        Ok(())
+    }
+}
+
+impl<SC: StarkGenericConfig> VmCircuitExtension<SC> for Blake {
+    fn extend_circuit(&self, inventory: &mut AirInventory<SC>) -> Result<(), AirInventoryError> {
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_bridge,
+        } = inventory.system().port();
+        let exec_bridge = ExecutionBridge::new(execution_bus, program_bus);
+        let pointer_max_bits = inventory.pointer_max_bits();
+
+        let bitwise_lu = {
+            let existing_air = inventory.find_air::<BitwiseOperationLookupAir<8>>().next();
+            if let Some(air) = existing_air {
+                air.bus
+            } else {
+                let bus = BitwiseOperationLookupBus::new(inventory.new_bus_idx());
+                let air = BitwiseOperationLookupAir::<8>::new(bus);
+                inventory.add_air(air);
+                air.bus
+            }
+        };
+
+        let blake = BlakeVmAir::new(
+            exec_bridge,
+            memory_bridge,
+            bitwise_lu,
+            pointer_max_bits,
+            Rv32BlakeOpcode::CLASS_OFFSET,
+        );
+        inventory.add_air(blake);
+
+        Ok(())
+    }
+}
+
+pub struct BlakeCpuProverExt;
+// This implementation is specific to CpuBackend because the lookup chips (VariableRangeChecker,
+// BitwiseOperationLookupChip) are specific to CpuBackend.
+impl<E, SC, RA> VmProverExtension<E, RA, Blake> for BlakeCpuProverExt
+where
+    SC: StarkGenericConfig,
+    E: StarkEngine<SC = SC, PB = CpuBackend<SC>, PD = CpuDevice<SC>>,
+    RA: RowMajorMatrixArena<Val<SC>>,
+    Val<SC>: PrimeField32,
+{
+    fn extend_prover(
+        &self,
+        _: &Blake,
+        inventory: &mut ChipInventory<SC, RA, CpuBackend<SC>>,
+    ) -> Result<(), ChipInventoryError> {
+        let range_checker = inventory.range_checker()?.clone();
+        let timestamp_max_bits = inventory.timestamp_max_bits();
+        let mem_helper = SharedMemoryHelper::new(range_checker.clone(), timestamp_max_bits);
+        let pointer_max_bits = inventory.airs().pointer_max_bits();
+
+        let bitwise_lu = {
+            let existing_chip = inventory
+                .find_chip::<SharedBitwiseOperationLookupChip<8>>()
+                .next();
+            if let Some(chip) = existing_chip {
+                chip.clone()
+            } else {
+                let air: &BitwiseOperationLookupAir<8> = inventory.next_air()?;
+                let chip = Arc::new(BitwiseOperationLookupChip::new(air.bus));
+                inventory.add_periphery_chip(chip.clone());
+                chip
+            }
+        };
+
+        inventory.next_air::<BlakeVmAir>()?;
+        let blake = BlakeVmChip::new(BlakeVmFiller::new(bitwise_lu, pointer_max_bits), mem_helper);
+        inventory.add_executor_chip(blake);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+//! Reference implementation of the BLAKE2s and BLAKE3 compression functions. Both use the same
+//! initialization vector and `G` function on a state of 16 words, and only differ in the number of
+//! rounds, the message schedule, and how the state is initialized and finalized.
+
+use std::array::from_fn;
+
+use crate::{BLAKE_BLOCK_BYTES, BLAKE_OUTPUT_BYTES, BLAKE_STATE_BYTES};
+
+/// Initialization vector shared by BLAKE2s and BLAKE3, which is also the SHA-256 initial hash
+/// value.
+pub const BLAKE_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+pub const BLAKE2S_ROUNDS: usize = 10;
+pub const BLAKE3_ROUNDS: usize = 7;
+/// Number of words in the state of the compression function
+pub const BLAKE_STATE_WIDTH: usize = 16;
+/// Number of applications of `G` in a round
+pub const BLAKE_NUM_G: usize = 8;
+/// Rotations of the `G` function, in the order in which they are applied
+pub const BLAKE_G_ROTATIONS: [usize; 4] = [16, 12, 8, 7];
+/// State words `(a, b, c, d)` of each application of `G` in a round: first the four columns of
+/// the state, then the four diagonals.
+pub const BLAKE_G_INDICES: [[usize; 4]; BLAKE_NUM_G] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// Message schedule of BLAKE2s: in round `r`, the `g`-th application of `G` uses the message
+/// words `BLAKE2S_SIGMA[r][2 * g]` and `BLAKE2S_SIGMA[r][2 * g + 1]`.
+pub const BLAKE2S_SIGMA: [[usize; 16]; BLAKE2S_ROUNDS] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// Message schedule of BLAKE3, in the same format as [BLAKE2S_SIGMA]. Round `r` uses the message
+/// permuted `r` times by the BLAKE3 message permutation.
+pub const BLAKE3_MSG_SCHEDULE: [[usize; 16]; BLAKE3_ROUNDS] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8],
+    [3, 4, 10, 12, 13, 2, 7, 14, 6, 5, 9, 0, 11, 15, 8, 1],
+    [10, 7, 12, 9, 14, 3, 13, 15, 4, 0, 11, 2, 5, 8, 1, 6],
+    [12, 13, 9, 11, 15, 10, 14, 8, 7, 2, 5, 3, 0, 1, 6, 4],
+    [9, 14, 11, 5, 8, 12, 15, 1, 13, 3, 0, 10, 2, 6, 4, 7],
+    [11, 15, 5, 0, 1, 9, 8, 6, 14, 10, 2, 12, 3, 4, 7, 13],
+];
+
+/// Number of rounds of the compression function
+#[inline(always)]
+pub fn blake_num_rounds(is_blake3: bool) -> usize {
+    if is_blake3 {
+        BLAKE3_ROUNDS
+    } else {
+        BLAKE2S_ROUNDS
+    }
+}
+
+/// Message schedule of the given round of the compression function
+#[inline(always)]
+pub fn blake_msg_schedule(is_blake3: bool, round: usize) -> &'static [usize; 16] {
+    if is_blake3 {
+        &BLAKE3_MSG_SCHEDULE[round]
+    } else {
+        &BLAKE2S_SIGMA[round]
+    }
+}
+
+/// The intermediate values of an application of `G` to `(a, b, c, d)` with message words
+/// `(mx, my)`, in the order `[a1, d1, c1, b1, a2, d2, c2, b2]`, where the last four are the output.
+#[inline(always)]
+pub fn blake_g(a: u32, b: u32, c: u32, d: u32, mx: u32, my: u32) -> [u32; 8] {
+    let [r1, r2, r3, r4] = BLAKE_G_ROTATIONS.map(|r| r as u32);
+    let a1 = a.wrapping_add(b).wrapping_add(mx);
+    let d1 = (d ^ a1).rotate_right(r1);
+    let c1 = c.wrapping_add(d1);
+    let b1 = (b ^ c1).rotate_right(r2);
+    let a2 = a1.wrapping_add(b1).wrapping_add(my);
+    let d2 = (d1 ^ a2).rotate_right(r3);
+    let c2 = c1.wrapping_add(d2);
+    let b2 = (b1 ^ c2).rotate_right(r4);
+    [a1, d1, c1, b1, a2, d2, c2, b2]
+}
+
+/// Applies one round of the compression function to the state `v`, with the message words
+/// permuted by `schedule`.
+pub fn blake_round(v: &mut [u32; BLAKE_STATE_WIDTH], m: &[u32; 16], schedule: &[usize; 16]) {
+    for (g, [a, b, c, d]) in BLAKE_G_INDICES.into_iter().enumerate() {
+        let [.., a2, d2, c2, b2] = blake_g(
+            v[a],
+            v[b],
+            v[c],
+            v[d],
+            m[schedule[2 * g]],
+            m[schedule[2 * g + 1]],
+        );
+        (v[a], v[b], v[c], v[d]) = (a2, b2, c2, d2);
+    }
+}
+
+/// The state of the compression function before the first round. `state` is the chaining value
+/// followed by the four parameter words, which are the counter and finalization flags for
+/// BLAKE2s, and the counter, block length and domain flags for BLAKE3.
+pub fn blake_init(is_blake3: bool, state: &[u32; 12]) -> [u32; BLAKE_STATE_WIDTH] {
+    from_fn(|i| match i {
+        0..8 => state[i],
+        8..12 => BLAKE_IV[i - 8],
+        _ if is_blake3 => state[i - 4],
+        _ => BLAKE_IV[i - 8] ^ state[i - 4],
+    })
+}
+
+/// The output of the compression function given its state `v` after the last round and the
+/// chaining value `cv`. BLAKE2s only outputs the new chaining value, and the second half of the
+/// output is zero.
+pub fn blake_finalize(
+    is_blake3: bool,
+    v: &[u32; BLAKE_STATE_WIDTH],
+    cv: &[u32; 8],
+) -> [u32; BLAKE_STATE_WIDTH] {
+    from_fn(|i| match i {
+        0..8 if is_blake3 => v[i] ^ v[i + 8],
+        0..8 => v[i] ^ v[i + 8] ^ cv[i],
+        _ if is_blake3 => v[i] ^ cv[i - 8],
+        _ => 0,
+    })
+}
+
+/// The BLAKE2s or BLAKE3 compression function on a state and block given by their words.
+pub fn blake_compress(
+    is_blake3: bool,
+    state: &[u32; 12],
+    block: &[u32; 16],
+) -> [u32; BLAKE_STATE_WIDTH] {
+    let mut v = blake_init(is_blake3, state);
+    for round in 0..blake_num_rounds(is_blake3) {
+        blake_round(&mut v, block, blake_msg_schedule(is_blake3, round));
+    }
+    blake_finalize(is_blake3, &v, state[..8].try_into().unwrap())
+}
+
+/// The compression function on a state and block as they are stored in memory, with every word in
+/// little-endian byte order. Returns the output in the same representation.
+pub fn blake_compress_bytes(
+    is_blake3: bool,
+    state: &[u8; BLAKE_STATE_BYTES],
+    block: &[u8; BLAKE_BLOCK_BYTES],
+) -> [u8; BLAKE_OUTPUT_BYTES] {
+    let output = blake_compress(
+        is_blake3,
+        &words_from_bytes(state),
+        &words_from_bytes(block),
+    );
+    from_fn(|i| output[i / 4].to_le_bytes()[i % 4])
+}
+
+/// Decodes little-endian words from bytes
+pub fn words_from_bytes<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    debug_assert_eq!(bytes.len(), 4 * N);
+    from_fn(|i| u32::from_le_bytes(bytes[4 * i..4 * (i + 1)].try_into().unwrap()))
+}
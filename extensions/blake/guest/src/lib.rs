@@ -0,0 +1,62 @@
+#![no_std]
+
+/// This is custom-0 defined in RISC-V spec document
+pub const OPCODE: u8 = 0x0b;
+pub const BLAKE_FUNCT3: u8 = 0b100;
+pub const BLAKE2S_COMPRESS_FUNCT7: u8 = 0x8;
+pub const BLAKE3_COMPRESS_FUNCT7: u8 = 0x9;
+
+/// Number of 32-bit words in the chaining value
+pub const BLAKE_CV_WORDS: usize = 8;
+/// Number of 32-bit words in the state read by the compression intrinsics: the chaining value,
+/// followed by four parameter words
+pub const BLAKE_STATE_WORDS: usize = BLAKE_CV_WORDS + 4;
+/// Number of 32-bit words in a message block
+pub const BLAKE_BLOCK_WORDS: usize = 16;
+
+/// BLAKE2s compression function intrinsic, as defined in
+/// [RFC 7693](https://datatracker.ietf.org/doc/html/rfc7693#section-3.2).
+///
+/// `state` is the chaining value `h[0..8]` followed by the parameters `t0, t1, f0, f1`, where
+/// `t = t0 + 2^32 * t1` is the byte counter and `f0, f1` are the finalization flags. The VM
+/// writes the new chaining value to `output`, which may alias the first 8 words of `state`.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+pub fn native_blake2s_compress(
+    state: &[u32; BLAKE_STATE_WORDS],
+    block: &[u32; BLAKE_BLOCK_WORDS],
+    output: &mut [u32; BLAKE_CV_WORDS],
+) {
+    // Word arrays are 4-byte aligned, as required by the VM memory accesses.
+    openvm_platform::custom_insn_r!(
+        opcode = OPCODE,
+        funct3 = BLAKE_FUNCT3,
+        funct7 = BLAKE2S_COMPRESS_FUNCT7,
+        rd = In output.as_mut_ptr(),
+        rs1 = In state.as_ptr(),
+        rs2 = In block.as_ptr()
+    );
+}
+
+/// BLAKE3 compression function intrinsic, as defined in the
+/// [BLAKE3 specification](https://github.com/BLAKE3-team/BLAKE3-specs/blob/master/blake3.pdf).
+///
+/// `state` is the chaining value followed by the parameters `counter_lo, counter_hi, block_len,
+/// flags`. The VM writes the full 16-word output of the compression function to `output`: its
+/// first 8 words are the new chaining value, and all 16 words are used for extendable output.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+pub fn native_blake3_compress(
+    state: &[u32; BLAKE_STATE_WORDS],
+    block: &[u32; BLAKE_BLOCK_WORDS],
+    output: &mut [u32; 2 * BLAKE_CV_WORDS],
+) {
+    openvm_platform::custom_insn_r!(
+        opcode = OPCODE,
+        funct3 = BLAKE_FUNCT3,
+        funct7 = BLAKE3_COMPRESS_FUNCT7,
+        rd = In output.as_mut_ptr(),
+        rs1 = In state.as_ptr(),
+        rs2 = In block.as_ptr()
+    );
+}
@@ -4,9 +4,12 @@ use openvm_instructions::{
 };
 use openvm_instructions_derive::LocalOpcode;
 use openvm_stark_backend::p3_field::PrimeField32;
-use openvm_transpiler::{util::from_r_type, TranspilerExtension, TranspilerOutput};
+use openvm_transpiler::{
+    util::{from_r_type, local_opcode_name},
+    TranspilerExtension, TranspilerOutput,
+};
 use rrs_lib::instruction_formats::RType;
-use strum::{EnumCount, EnumIter, FromRepr};
+use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator};
 
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
@@ -84,4 +87,10 @@ impl<F: PrimeField32> TranspilerExtension<F> for EccTranspilerExtension {
         };
         instruction.map(TranspilerOutput::one_to_one)
     }
+
+    fn opcode_name(&self, opcode: VmOpcode) -> Option<String> {
+        // One copy of the opcodes per curve, indexed by funct7
+        let num_curves = 128 / SwBaseFunct7::SHORT_WEIERSTRASS_MAX_KINDS as usize;
+        local_opcode_name(opcode, Rv32WeierstrassOpcode::iter(), num_curves)
+    }
 }
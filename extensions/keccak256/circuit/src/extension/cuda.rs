@@ -1,5 +1,5 @@
 use openvm_circuit::{
-    arch::DenseRecordArena,
+    arch::{Arena, DenseRecordArena},
     system::cuda::{
         extensions::{
             get_inventory_range_checker, get_or_create_bitwise_op_lookup, SystemGpuBuilder,
@@ -7,12 +7,50 @@ use openvm_circuit::{
         SystemChipInventoryGPU,
     },
 };
-use openvm_cuda_backend::{engine::GpuBabyBearPoseidon2Engine, prover_backend::GpuBackend};
+use openvm_cuda_backend::{
+    chip::{cpu_proving_ctx_to_gpu, get_empty_air_proving_ctx},
+    engine::GpuBabyBearPoseidon2Engine,
+    prover_backend::GpuBackend,
+    types::F,
+};
 use openvm_rv32im_circuit::Rv32ImGpuProverExt;
+use openvm_stark_backend::{prover::types::AirProvingContext, Chip};
 use openvm_stark_sdk::config::baby_bear_poseidon2::BabyBearPoseidon2Config;
+use p3_keccak_air::NUM_ROUNDS;
 
 use super::*;
-use crate::{air::KeccakVmAir, cuda::Keccak256ChipGpu};
+use crate::{
+    air::KeccakVmAir,
+    cuda::Keccak256ChipGpu,
+    keccakf::{KeccakfVmRecord, KeccakfVmRecordLayout, NUM_KECCAKF_VM_COLS},
+};
+
+/// Keccak-f chip for the GPU backend which still does trace generation on CPU.
+#[derive(derive_new::new)]
+pub struct HybridKeccakfChip {
+    cpu: KeccakfVmChip<F>,
+}
+
+// Conversion of Dense->Matrix Record Arena, cpu tracegen, and then H2D transfer of the trace
+// matrix.
+impl Chip<DenseRecordArena, GpuBackend> for HybridKeccakfChip {
+    fn generate_proving_ctx(&self, mut arena: DenseRecordArena) -> AirProvingContext<GpuBackend> {
+        let records = arena.allocated();
+        if records.is_empty() {
+            return get_empty_air_proving_ctx::<GpuBackend>();
+        }
+        debug_assert_eq!(records.len() % size_of::<KeccakfVmRecord>(), 0);
+
+        let num_records = records.len() / size_of::<KeccakfVmRecord>();
+        let height = (num_records * NUM_ROUNDS).next_power_of_two();
+        let mut matrix_arena = MatrixRecordArena::<F>::with_capacity(height, NUM_KECCAKF_VM_COLS);
+        arena
+            .get_record_seeker::<&mut KeccakfVmRecord, KeccakfVmRecordLayout>()
+            .transfer_to_matrix_arena(&mut matrix_arena);
+        let ctx = self.cpu.generate_proving_ctx(matrix_arena);
+        cpu_proving_ctx_to_gpu(ctx)
+    }
+}
 
 pub struct Keccak256GpuProverExt;
 
@@ -43,6 +81,16 @@ impl VmProverExtension<GpuBabyBearPoseidon2Engine, DenseRecordArena, Keccak256>
         );
         inventory.add_executor_chip(keccak);
 
+        // Keccak-f trace generation is done on CPU, with the CPU counterparts of the lookup chips
+        inventory.next_air::<KeccakfVmAir>()?;
+        let mem_helper =
+            SharedMemoryHelper::new(range_checker.cpu_chip.clone().unwrap(), timestamp_max_bits);
+        let keccakf = KeccakfVmChip::new(
+            KeccakfVmFiller::new(bitwise_lu.cpu_chip.clone().unwrap(), pointer_max_bits),
+            mem_helper,
+        );
+        inventory.add_executor_chip(HybridKeccakfChip::new(keccakf));
+
         Ok(())
     }
 }
@@ -19,7 +19,7 @@ use openvm_circuit_primitives::bitwise_op_lookup::{
     SharedBitwiseOperationLookupChip,
 };
 use openvm_instructions::*;
-use openvm_keccak256_transpiler::Rv32KeccakOpcode;
+use openvm_keccak256_transpiler::{Rv32KeccakOpcode, Rv32KeccakfOpcode};
 use openvm_rv32im_circuit::{
     Rv32I, Rv32IExecutor, Rv32ImCpuProverExt, Rv32Io, Rv32IoExecutor, Rv32M, Rv32MExecutor,
 };
@@ -32,7 +32,10 @@ use openvm_stark_sdk::engine::StarkEngine;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 
-use crate::{KeccakVmAir, KeccakVmChip, KeccakVmExecutor, KeccakVmFiller};
+use crate::{
+    keccakf::{KeccakfVmAir, KeccakfVmChip, KeccakfVmExecutor, KeccakfVmFiller},
+    KeccakVmAir, KeccakVmChip, KeccakVmExecutor, KeccakVmFiller,
+};
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "cuda")] {
@@ -129,6 +132,7 @@ pub struct Keccak256;
 #[derive(Clone, Copy, From, AnyEnum, Executor, MeteredExecutor, PreflightExecutor)]
 pub enum Keccak256Executor {
     Keccak256(KeccakVmExecutor),
+    Keccakf(KeccakfVmExecutor),
 }
 
 impl<F> VmExecutionExtension<F> for Keccak256 {
@@ -144,6 +148,12 @@ impl<F> VmExecutionExtension<F> for Keccak256 {
             keccak_step,
             Rv32KeccakOpcode::iter().map(|x| x.global_opcode()),
         )?;
+        let keccakf_step =
+            KeccakfVmExecutor::new(Rv32KeccakfOpcode::CLASS_OFFSET, pointer_max_bits);
+        inventory.add_executor(
+            keccakf_step,
+            Rv32KeccakfOpcode::iter().map(|x| x.global_opcode()),
+        )?;
 
         Ok(())
     }
@@ -181,6 +191,15 @@ impl<SC: StarkGenericConfig> VmCircuitExtension<SC> for Keccak256 {
         );
         inventory.add_air(keccak);
 
+        let keccakf = KeccakfVmAir::new(
+            exec_bridge,
+            memory_bridge,
+            bitwise_lu,
+            pointer_max_bits,
+            Rv32KeccakfOpcode::CLASS_OFFSET,
+        );
+        inventory.add_air(keccakf);
+
         Ok(())
     }
 }
@@ -221,11 +240,18 @@ where
 
         inventory.next_air::<KeccakVmAir>()?;
         let keccak = KeccakVmChip::new(
-            KeccakVmFiller::new(bitwise_lu, pointer_max_bits),
-            mem_helper,
+            KeccakVmFiller::new(bitwise_lu.clone(), pointer_max_bits),
+            mem_helper.clone(),
         );
         inventory.add_executor_chip(keccak);
 
+        inventory.next_air::<KeccakfVmAir>()?;
+        let keccakf = KeccakfVmChip::new(
+            KeccakfVmFiller::new(bitwise_lu, pointer_max_bits),
+            mem_helper,
+        );
+        inventory.add_executor_chip(keccakf);
+
         Ok(())
     }
 }
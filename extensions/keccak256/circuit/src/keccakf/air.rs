@@ -0,0 +1,243 @@
+use std::{array::from_fn, borrow::Borrow};
+
+use itertools::izip;
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionState},
+    system::memory::{offline_checker::MemoryBridge, MemoryAddress},
+};
+use openvm_circuit_primitives::{bitwise_op_lookup::BitwiseOperationLookupBus, utils::not};
+use openvm_instructions::riscv::{
+    RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS,
+};
+use openvm_keccak256_transpiler::Rv32KeccakfOpcode;
+use openvm_rv32im_circuit::adapters::abstract_compose;
+use openvm_stark_backend::{
+    air_builders::sub::SubAirBuilder,
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::FieldAlgebra,
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_keccak_air::{KeccakAir, NUM_KECCAK_COLS as NUM_KECCAK_PERM_COLS, U64_LIMBS};
+
+use super::{
+    columns::{KeccakfVmCols, NUM_KECCAKF_VM_COLS},
+    KECCAKF_TIMESTAMP_DELTA,
+};
+use crate::{KECCAK_WIDTH_U16S, KECCAK_WORD_SIZE};
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct KeccakfVmAir {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    /// Bus to send 8-bit range check requests to.
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    /// Maximum number of bits allowed for an address pointer
+    pub ptr_max_bits: usize,
+    pub(crate) offset: usize,
+}
+
+impl<F> BaseAirWithPublicValues<F> for KeccakfVmAir {}
+impl<F> PartitionedBaseAir<F> for KeccakfVmAir {}
+impl<F> BaseAir<F> for KeccakfVmAir {
+    fn width(&self) -> usize {
+        NUM_KECCAKF_VM_COLS
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for KeccakfVmAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &KeccakfVmCols<AB::Var> = (*local).borrow();
+        let next: &KeccakfVmCols<AB::Var> = (*next).borrow();
+
+        builder.assert_bool(local.instruction.is_enabled);
+        // since keccak-f AIR has this column, we might as well use it
+        builder.assert_eq(
+            local.inner.export,
+            local.instruction.is_enabled * local.is_last_round(),
+        );
+
+        self.eval_keccak_f(builder);
+
+        // The instruction columns are the same on all rounds of a permutation
+        let mut transition_builder = builder.when_transition();
+        let mut round_builder = transition_builder.when(not(local.is_last_round()));
+        local
+            .instruction
+            .assert_eq(&mut round_builder, next.instruction);
+
+        let start_read_timestamp = self.eval_instruction(builder, local);
+        let start_write_timestamp = self.constrain_state_read(builder, local, start_read_timestamp);
+        self.constrain_state_write(builder, local, start_write_timestamp);
+    }
+}
+
+impl KeccakfVmAir {
+    /// Evaluate the keccak-f permutation constraints.
+    ///
+    /// WARNING: The keccak-f AIR columns **must** be the first columns in the main AIR.
+    #[inline]
+    pub fn eval_keccak_f<AB: AirBuilder>(&self, builder: &mut AB) {
+        let keccak_f_air = KeccakAir {};
+        let mut sub_builder =
+            SubAirBuilder::<AB, KeccakAir, AB::Var>::new(builder, 0..NUM_KECCAK_PERM_COLS);
+        keccak_f_air.eval(&mut sub_builder);
+    }
+
+    /// Receive the instruction itself on program bus. Send+receive on execution bus.
+    /// Then does memory read in addr space 1 to get the state pointer from the `rs1` register, and
+    /// range checks its most significant limb using BitwiseOperationLookupBus.
+    ///
+    /// Returns `start_read_timestamp` for the state reads.
+    pub fn eval_instruction<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &KeccakfVmCols<AB::Var>,
+    ) -> AB::Expr {
+        let instruction = local.instruction;
+        // Only receive opcode on the first round of an enabled row
+        let should_receive = instruction.is_enabled * local.is_first_round();
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::Expr::from_canonical_usize(Rv32KeccakfOpcode::KECCAKF as usize + self.offset),
+                [
+                    AB::Expr::ZERO,
+                    instruction.rs1_ptr.into(),
+                    AB::Expr::ZERO,
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                ExecutionState::new(instruction.pc, instruction.start_timestamp),
+                AB::Expr::from_canonical_usize(KECCAKF_TIMESTAMP_DELTA),
+            )
+            .eval(builder, should_receive.clone());
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    instruction.rs1_ptr,
+                ),
+                instruction.state_ptr,
+                instruction.start_timestamp,
+                &local.mem_oc.register_aux,
+            )
+            .eval(builder, should_receive.clone());
+
+        // See Rv32VecHeapAdapterAir
+        let limb_shift = AB::F::from_canonical_usize(
+            1 << (RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.ptr_max_bits),
+        );
+        let state_ptr_msl = instruction.state_ptr[RV32_REGISTER_NUM_LIMBS - 1];
+        self.bitwise_lookup_bus
+            .send_range(state_ptr_msl * limb_shift, state_ptr_msl * limb_shift)
+            .eval(builder, should_receive);
+
+        instruction.start_timestamp + AB::Expr::ONE
+    }
+
+    /// Constrain that the preimage of the permutation is read from memory on the first round.
+    /// The preimage bytes are recovered from the `u16` limbs of `preimage` and `state_hi` as in
+    /// [KeccakVmAir::constrain_absorb](crate::KeccakVmAir::constrain_absorb). Since they are read
+    /// from memory, they are bytes.
+    ///
+    /// Returns the `start_write_timestamp` for the state writes.
+    pub fn constrain_state_read<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &KeccakfVmCols<AB::Var>,
+        start_read_timestamp: AB::Expr,
+    ) -> AB::Expr {
+        let is_input = local.instruction.is_enabled * local.is_first_round();
+        let preimage_bytes = state_bytes::<AB>(
+            |y, x, limb| local.inner.preimage[y][x][limb],
+            &local.state_hi,
+        );
+        let state_ptr = abstract_compose::<AB::Expr, _>(local.instruction.state_ptr);
+
+        let mut timestamp = start_read_timestamp;
+        for (i, (word, mem_aux)) in izip!(
+            preimage_bytes.chunks_exact(KECCAK_WORD_SIZE),
+            &local.mem_oc.state_reads
+        )
+        .enumerate()
+        {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(
+                        AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                        state_ptr.clone() + AB::F::from_canonical_usize(i * KECCAK_WORD_SIZE),
+                    ),
+                    from_fn(|j| word[j].clone()),
+                    timestamp.clone(),
+                    mem_aux,
+                )
+                .eval(builder, is_input.clone());
+
+            timestamp += AB::Expr::ONE;
+        }
+        timestamp
+    }
+
+    /// Constrain that the postimage of the permutation is written to memory on the last round, in
+    /// place of the preimage. The postimage bytes are range checked, since the `state_hi` columns
+    /// of the last round are not otherwise constrained.
+    pub fn constrain_state_write<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &KeccakfVmCols<AB::Var>,
+        start_write_timestamp: AB::Expr,
+    ) {
+        let postimage_bytes =
+            state_bytes::<AB>(|y, x, limb| local.postimage(y, x, limb), &local.state_hi);
+        for pair in postimage_bytes.chunks_exact(2) {
+            self.bitwise_lookup_bus
+                .send_range(pair[0].clone(), pair[1].clone())
+                .eval(builder, local.inner.export);
+        }
+
+        let state_ptr = abstract_compose::<AB::Expr, _>(local.instruction.state_ptr);
+        for (i, (word, mem_aux)) in izip!(
+            postimage_bytes.chunks_exact(KECCAK_WORD_SIZE),
+            &local.mem_oc.state_writes
+        )
+        .enumerate()
+        {
+            self.memory_bridge
+                .write(
+                    MemoryAddress::new(
+                        AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                        state_ptr.clone() + AB::F::from_canonical_usize(i * KECCAK_WORD_SIZE),
+                    ),
+                    from_fn(|j| word[j].clone()),
+                    start_write_timestamp.clone() + AB::Expr::from_canonical_usize(i),
+                    mem_aux,
+                )
+                .eval(builder, local.inner.export);
+        }
+    }
+}
+
+/// Decomposes the state given by its `u16` limbs `limbs(y, x, limb)` and the most significant
+/// bytes `state_hi` of the limbs into bytes, in the order of the state in memory.
+fn state_bytes<AB: AirBuilder>(
+    limbs: impl Fn(usize, usize, usize) -> AB::Var,
+    state_hi: &[AB::Var; KECCAK_WIDTH_U16S],
+) -> Vec<AB::Expr> {
+    let mut bytes = Vec::with_capacity(2 * KECCAK_WIDTH_U16S);
+    for i in 0..KECCAK_WIDTH_U16S / U64_LIMBS {
+        let y = i / 5;
+        let x = i % 5;
+        for limb in 0..U64_LIMBS {
+            let hi = state_hi[i * U64_LIMBS + limb];
+            let lo = limbs(y, x, limb) - hi * AB::F::from_canonical_u64(1 << 8);
+            // Conversion from bytes to u64 is little-endian
+            bytes.extend([lo, hi.into()]);
+        }
+    }
+    bytes
+}
@@ -0,0 +1,198 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    mem::size_of,
+};
+
+use openvm_circuit::{arch::*, system::memory::online::GuestMemory};
+use openvm_circuit_primitives_derive::AlignedBytesBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+    LocalOpcode,
+};
+use openvm_keccak256_transpiler::Rv32KeccakfOpcode;
+use openvm_stark_backend::p3_field::PrimeField32;
+use p3_keccak_air::NUM_ROUNDS;
+
+use super::KeccakfVmExecutor;
+use crate::{utils::keccak_f_bytes, KECCAK_WIDTH_BYTES, KECCAK_WORD_SIZE};
+
+#[derive(AlignedBytesBorrow, Clone)]
+#[repr(C)]
+struct KeccakfPreCompute {
+    b: u8,
+}
+
+impl KeccakfVmExecutor {
+    fn pre_compute_impl<F: PrimeField32>(
+        &self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut KeccakfPreCompute,
+    ) -> Result<(), StaticProgramError> {
+        let Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = inst;
+        if d.as_canonical_u32() != RV32_REGISTER_AS
+            || e.as_canonical_u32() != RV32_MEMORY_AS
+            || !a.is_zero()
+            || !c.is_zero()
+        {
+            return Err(StaticProgramError::InvalidInstruction(pc));
+        }
+        *data = KeccakfPreCompute {
+            b: b.as_canonical_u32() as u8,
+        };
+        assert_eq!(&Rv32KeccakfOpcode::KECCAKF.global_opcode(), opcode);
+        Ok(())
+    }
+}
+
+impl<F: PrimeField32> Executor<F> for KeccakfVmExecutor {
+    fn pre_compute_size(&self) -> usize {
+        size_of::<KeccakfPreCompute>()
+    }
+
+    #[cfg(not(feature = "tco"))]
+    fn pre_compute<Ctx>(
+        &self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<ExecuteFunc<F, Ctx>, StaticProgramError>
+    where
+        Ctx: ExecutionCtxTrait,
+    {
+        let data: &mut KeccakfPreCompute = data.borrow_mut();
+        self.pre_compute_impl(pc, inst, data)?;
+        Ok(execute_e1_impl::<_, _>)
+    }
+
+    #[cfg(feature = "tco")]
+    fn handler<Ctx>(
+        &self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<Handler<F, Ctx>, StaticProgramError>
+    where
+        Ctx: ExecutionCtxTrait,
+    {
+        let data: &mut KeccakfPreCompute = data.borrow_mut();
+        self.pre_compute_impl(pc, inst, data)?;
+        Ok(execute_e1_handler)
+    }
+}
+
+impl<F: PrimeField32> MeteredExecutor<F> for KeccakfVmExecutor {
+    fn metered_pre_compute_size(&self) -> usize {
+        size_of::<E2PreCompute<KeccakfPreCompute>>()
+    }
+
+    #[cfg(not(feature = "tco"))]
+    fn metered_pre_compute<Ctx>(
+        &self,
+        chip_idx: usize,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<ExecuteFunc<F, Ctx>, StaticProgramError>
+    where
+        Ctx: MeteredExecutionCtxTrait,
+    {
+        let data: &mut E2PreCompute<KeccakfPreCompute> = data.borrow_mut();
+        data.chip_idx = chip_idx as u32;
+        self.pre_compute_impl(pc, inst, &mut data.data)?;
+        Ok(execute_e2_impl::<_, _>)
+    }
+
+    #[cfg(feature = "tco")]
+    fn metered_handler<Ctx>(
+        &self,
+        chip_idx: usize,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<Handler<F, Ctx>, StaticProgramError>
+    where
+        Ctx: MeteredExecutionCtxTrait,
+    {
+        let data: &mut E2PreCompute<KeccakfPreCompute> = data.borrow_mut();
+        data.chip_idx = chip_idx as u32;
+        self.pre_compute_impl(pc, inst, &mut data.data)?;
+        Ok(execute_e2_handler::<_, _>)
+    }
+}
+
+#[inline(always)]
+unsafe fn execute_e12_impl<F: PrimeField32, CTX: ExecutionCtxTrait, const IS_E1: bool>(
+    pre_compute: &KeccakfPreCompute,
+    instret: &mut u64,
+    pc: &mut u32,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let state_ptr = exec_state.vm_read(RV32_REGISTER_AS, pre_compute.b as u32);
+    let state_ptr_u32 = u32::from_le_bytes(state_ptr);
+
+    if IS_E1 {
+        let state = exec_state.host_read::<u8, KECCAK_WIDTH_BYTES>(RV32_MEMORY_AS, state_ptr_u32);
+        exec_state.host_write(RV32_MEMORY_AS, state_ptr_u32, &keccak_f_bytes(state));
+    } else {
+        // Memory is accessed in words as in the AIR, since metered execution only supports
+        // power-of-two access sizes
+        let mut state = [0u8; KECCAK_WIDTH_BYTES];
+        for (i, word) in state.chunks_exact_mut(KECCAK_WORD_SIZE).enumerate() {
+            word.copy_from_slice(&exec_state.vm_read::<u8, KECCAK_WORD_SIZE>(
+                RV32_MEMORY_AS,
+                state_ptr_u32 + (i * KECCAK_WORD_SIZE) as u32,
+            ));
+        }
+        let state = keccak_f_bytes(state);
+        for (i, word) in state.chunks_exact(KECCAK_WORD_SIZE).enumerate() {
+            exec_state.vm_write::<u8, KECCAK_WORD_SIZE>(
+                RV32_MEMORY_AS,
+                state_ptr_u32 + (i * KECCAK_WORD_SIZE) as u32,
+                word.try_into().unwrap(),
+            );
+        }
+    }
+
+    *pc = pc.wrapping_add(DEFAULT_PC_STEP);
+    *instret += 1;
+}
+
+#[create_handler]
+#[inline(always)]
+unsafe fn execute_e1_impl<F: PrimeField32, CTX: ExecutionCtxTrait>(
+    pre_compute: &[u8],
+    instret: &mut u64,
+    pc: &mut u32,
+    _instret_end: u64,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let pre_compute: &KeccakfPreCompute = pre_compute.borrow();
+    execute_e12_impl::<F, CTX, true>(pre_compute, instret, pc, exec_state);
+}
+
+#[create_handler]
+#[inline(always)]
+unsafe fn execute_e2_impl<F: PrimeField32, CTX: MeteredExecutionCtxTrait>(
+    pre_compute: &[u8],
+    instret: &mut u64,
+    pc: &mut u32,
+    _arg: u64,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let pre_compute: &E2PreCompute<KeccakfPreCompute> = pre_compute.borrow();
+    execute_e12_impl::<F, CTX, false>(&pre_compute.data, instret, pc, exec_state);
+    exec_state
+        .ctx
+        .on_height_change(pre_compute.chip_idx as usize, NUM_ROUNDS as u32);
+}
@@ -0,0 +1,39 @@
+//! Keccak-f\[1600\] permutation applied in place to a 200-byte state in VM memory. Unlike
+//! [KeccakVmExecutor](crate::KeccakVmExecutor), it does no padding or absorbing, which is left to
+//! the guest so that any Keccak-based sponge (SHA3, SHAKE, Keccak-512, ...) can be built on it.
+
+use openvm_circuit::arch::VmChipWrapper;
+use openvm_circuit_primitives::bitwise_op_lookup::SharedBitwiseOperationLookupChip;
+
+use crate::{KECCAK_WIDTH_BYTES, KECCAK_WORD_SIZE};
+
+mod air;
+mod columns;
+mod execution;
+mod trace;
+
+pub use air::*;
+pub use columns::*;
+pub use trace::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Memory reads of the state, which is also the number of memory writes of the permuted state
+const KECCAKF_STATE_READS: usize = KECCAK_WIDTH_BYTES / KECCAK_WORD_SIZE;
+/// Amount the timestamp advances by: one register read, then the state reads and writes
+const KECCAKF_TIMESTAMP_DELTA: usize = 1 + 2 * KECCAKF_STATE_READS;
+
+pub type KeccakfVmChip<F> = VmChipWrapper<F, KeccakfVmFiller>;
+
+#[derive(derive_new::new, Clone, Copy)]
+pub struct KeccakfVmExecutor {
+    pub offset: usize,
+    pub pointer_max_bits: usize,
+}
+
+#[derive(derive_new::new)]
+pub struct KeccakfVmFiller {
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<8>,
+    pub pointer_max_bits: usize,
+}
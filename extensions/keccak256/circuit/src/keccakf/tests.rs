@@ -0,0 +1,219 @@
+use std::{array, borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::{
+    arch::{
+        testing::{
+            memory::gen_pointer, TestBuilder, TestChipHarness, VmChipTestBuilder,
+            BITWISE_OP_LOOKUP_BUS,
+        },
+        Arena, ExecutionBridge, PreflightExecutor,
+    },
+    system::memory::{offline_checker::MemoryBridge, SharedMemoryHelper},
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupAir, BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+    SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS},
+    LocalOpcode,
+};
+use openvm_keccak256_transpiler::Rv32KeccakfOpcode;
+use openvm_stark_backend::{
+    p3_field::FieldAlgebra,
+    p3_matrix::{dense::DenseMatrix, Matrix},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use p3_keccak_air::NUM_ROUNDS;
+use rand::{rngs::StdRng, Rng};
+
+use super::{KeccakfVmAir, KeccakfVmChip, KeccakfVmCols, KeccakfVmExecutor, KeccakfVmFiller};
+use crate::{utils::keccak_f_bytes, KECCAK_WIDTH_BYTES};
+
+type F = BabyBear;
+const MAX_INS_CAPACITY: usize = 128;
+type Harness<RA> = TestChipHarness<F, KeccakfVmExecutor, KeccakfVmAir, KeccakfVmChip<F>, RA>;
+
+fn create_harness_fields(
+    execution_bridge: ExecutionBridge,
+    memory_bridge: MemoryBridge,
+    bitwise_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    memory_helper: SharedMemoryHelper<F>,
+    address_bits: usize,
+) -> (KeccakfVmAir, KeccakfVmExecutor, KeccakfVmChip<F>) {
+    let air = KeccakfVmAir::new(
+        execution_bridge,
+        memory_bridge,
+        bitwise_chip.bus(),
+        address_bits,
+        Rv32KeccakfOpcode::CLASS_OFFSET,
+    );
+    let executor = KeccakfVmExecutor::new(Rv32KeccakfOpcode::CLASS_OFFSET, address_bits);
+    let chip = KeccakfVmChip::new(
+        KeccakfVmFiller::new(bitwise_chip, address_bits),
+        memory_helper,
+    );
+    (air, executor, chip)
+}
+
+fn create_test_harness<RA: Arena>(
+    tester: &mut VmChipTestBuilder<F>,
+) -> (
+    Harness<RA>,
+    (
+        BitwiseOperationLookupAir<RV32_CELL_BITS>,
+        SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    ),
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let (air, executor, chip) = create_harness_fields(
+        tester.execution_bridge(),
+        tester.memory_bridge(),
+        bitwise_chip.clone(),
+        tester.memory_helper(),
+        tester.address_bits(),
+    );
+
+    let harness = Harness::<RA>::with_capacity(executor, air, chip, MAX_INS_CAPACITY);
+
+    (harness, (bitwise_chip.air, bitwise_chip))
+}
+
+fn set_and_execute<RA: Arena, E: PreflightExecutor<F, RA>>(
+    tester: &mut impl TestBuilder<F>,
+    executor: &mut E,
+    arena: &mut RA,
+    rng: &mut StdRng,
+    state: Option<[u8; KECCAK_WIDTH_BYTES]>,
+) {
+    let state = state.unwrap_or_else(|| array::from_fn(|_| rng.gen()));
+
+    let rs1 = gen_pointer(rng, 4);
+    let state_ptr = gen_pointer(rng, 4);
+    tester.write(
+        RV32_REGISTER_AS as usize,
+        rs1,
+        state_ptr.to_le_bytes().map(F::from_canonical_u8),
+    );
+    for (i, word) in state.chunks_exact(4).enumerate() {
+        tester.write::<4>(
+            RV32_MEMORY_AS as usize,
+            state_ptr + i * 4,
+            array::from_fn(|j| F::from_canonical_u8(word[j])),
+        );
+    }
+
+    tester.execute(
+        executor,
+        arena,
+        &Instruction::from_usize(
+            Rv32KeccakfOpcode::KECCAKF.global_opcode(),
+            [
+                0,
+                rs1,
+                0,
+                RV32_REGISTER_AS as usize,
+                RV32_MEMORY_AS as usize,
+            ],
+        ),
+    );
+
+    let expected = keccak_f_bytes(state);
+    for (i, word) in expected.chunks_exact(4).enumerate() {
+        assert_eq!(
+            array::from_fn(|j| F::from_canonical_u8(word[j])),
+            tester.read::<4>(RV32_MEMORY_AS as usize, state_ptr + i * 4)
+        );
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Randomly generate computations and execute, ensuring that the generated trace
+/// passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn rand_keccakf_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_test_harness(&mut tester);
+
+    // Includes the all-zero state. The 5 permutations take 120 rows, so the trace is padded with
+    // dummy rows.
+    set_and_execute(
+        &mut tester,
+        &mut harness.executor,
+        &mut harness.arena,
+        &mut rng,
+        Some([0; KECCAK_WIDTH_BYTES]),
+    );
+    for _ in 0..4 {
+        set_and_execute(
+            &mut tester,
+            &mut harness.executor,
+            &mut harness.arena,
+            &mut rng,
+            None,
+        );
+    }
+
+    let tester = tester
+        .build()
+        .load(harness)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+// Keccak-f[1600] applied to the all-zero state, from https://github.com/XKCP/XKCP/blob/master/tests/TestVectors/KeccakF-1600-IntermediateValues.txt
+#[test]
+fn test_keccakf_zero_state() {
+    let state = keccak_f_bytes([0; KECCAK_WIDTH_BYTES]);
+    let first_lane = u64::from_le_bytes(state[..8].try_into().unwrap());
+    assert_eq!(first_lane, 0xF1258F7940E1DDE7);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// part of the trace and check that the chip throws the expected error.
+//////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn test_keccakf_negative() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_test_harness(&mut tester);
+
+    set_and_execute(
+        &mut tester,
+        &mut harness.executor,
+        &mut harness.arena,
+        &mut rng,
+        None,
+    );
+
+    // Change the high byte of the first limb of the written postimage
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let width = trace.width();
+        let cols: &mut KeccakfVmCols<F> =
+            trace.values[(NUM_ROUNDS - 1) * width..NUM_ROUNDS * width].borrow_mut();
+        cols.state_hi[0] += F::ONE;
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(harness, modify_trace)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::ChallengePhaseError);
+}
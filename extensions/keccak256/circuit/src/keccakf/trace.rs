@@ -0,0 +1,299 @@
+use std::{array::from_fn, borrow::BorrowMut};
+
+use openvm_circuit::{
+    arch::*,
+    system::memory::{
+        offline_checker::{MemoryReadAuxRecord, MemoryWriteBytesAuxRecord},
+        online::TracingMemory,
+        MemoryAuxColsFactory,
+    },
+};
+use openvm_circuit_primitives::AlignedBytesBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+    LocalOpcode,
+};
+use openvm_keccak256_transpiler::Rv32KeccakfOpcode;
+use openvm_rv32im_circuit::adapters::{tracing_read, tracing_write};
+use openvm_stark_backend::{
+    p3_field::PrimeField32,
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    p3_maybe_rayon::prelude::*,
+};
+use p3_keccak_air::{
+    generate_trace_rows, NUM_KECCAK_COLS as NUM_KECCAK_PERM_COLS, NUM_ROUNDS, U64_LIMBS,
+};
+
+use super::{
+    columns::{KeccakfInstructionCols, KeccakfVmCols, NUM_KECCAKF_VM_COLS},
+    KeccakfVmExecutor, KeccakfVmFiller, KECCAKF_STATE_READS,
+};
+use crate::{
+    utils::{keccak_f, keccak_f_bytes},
+    KECCAK_WIDTH_BYTES, KECCAK_WIDTH_U16S, KECCAK_WORD_SIZE,
+};
+
+/// Every instruction is a single keccak-f permutation of [NUM_ROUNDS] rows.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KeccakfVmMetadata;
+
+impl MultiRowMetadata for KeccakfVmMetadata {
+    #[inline(always)]
+    fn get_num_rows(&self) -> usize {
+        NUM_ROUNDS
+    }
+}
+
+pub(crate) type KeccakfVmRecordLayout = MultiRowLayout<KeccakfVmMetadata>;
+
+#[repr(C)]
+#[derive(AlignedBytesBorrow, Debug, Clone)]
+pub struct KeccakfVmRecord {
+    pub from_pc: u32,
+    pub timestamp: u32,
+    pub rs1_ptr: u32,
+    pub state_ptr: u32,
+    /// The state before the permutation, as read from memory
+    pub preimage: [u8; KECCAK_WIDTH_BYTES],
+
+    pub register_read_aux: MemoryReadAuxRecord,
+    pub read_aux: [MemoryReadAuxRecord; KECCAKF_STATE_READS],
+    pub write_aux: [MemoryWriteBytesAuxRecord<KECCAK_WORD_SIZE>; KECCAKF_STATE_READS],
+}
+
+impl<F, RA> PreflightExecutor<F, RA> for KeccakfVmExecutor
+where
+    F: PrimeField32,
+    for<'buf> RA: RecordArena<'buf, KeccakfVmRecordLayout, &'buf mut KeccakfVmRecord>,
+{
+    fn get_opcode_name(&self, _: usize) -> String {
+        format!("{:?}", Rv32KeccakfOpcode::KECCAKF)
+    }
+
+    fn execute(
+        &self,
+        state: VmStateMut<F, TracingMemory, RA>,
+        instruction: &Instruction<F>,
+    ) -> Result<(), ExecutionError> {
+        let &Instruction {
+            opcode, b, d, e, ..
+        } = instruction;
+        debug_assert_eq!(opcode, Rv32KeccakfOpcode::KECCAKF.global_opcode());
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+        debug_assert_eq!(e.as_canonical_u32(), RV32_MEMORY_AS);
+
+        let record = state.ctx.alloc(KeccakfVmRecordLayout::default());
+
+        record.from_pc = *state.pc;
+        record.timestamp = state.memory.timestamp();
+        record.rs1_ptr = b.as_canonical_u32();
+        record.state_ptr = u32::from_le_bytes(tracing_read(
+            state.memory,
+            RV32_REGISTER_AS,
+            record.rs1_ptr,
+            &mut record.register_read_aux.prev_timestamp,
+        ));
+        debug_assert!(
+            record.state_ptr as usize + KECCAK_WIDTH_BYTES <= (1 << self.pointer_max_bits)
+        );
+
+        for (i, word) in record
+            .preimage
+            .chunks_exact_mut(KECCAK_WORD_SIZE)
+            .enumerate()
+        {
+            word.copy_from_slice(&tracing_read::<KECCAK_WORD_SIZE>(
+                state.memory,
+                RV32_MEMORY_AS,
+                record.state_ptr + (i * KECCAK_WORD_SIZE) as u32,
+                &mut record.read_aux[i].prev_timestamp,
+            ));
+        }
+
+        let postimage = keccak_f_bytes(record.preimage);
+        for (i, word) in postimage.chunks_exact(KECCAK_WORD_SIZE).enumerate() {
+            tracing_write::<KECCAK_WORD_SIZE>(
+                state.memory,
+                RV32_MEMORY_AS,
+                record.state_ptr + (i * KECCAK_WORD_SIZE) as u32,
+                word.try_into().unwrap(),
+                &mut record.write_aux[i].prev_timestamp,
+                &mut record.write_aux[i].prev_data,
+            );
+        }
+
+        *state.pc = state.pc.wrapping_add(DEFAULT_PC_STEP);
+        Ok(())
+    }
+}
+
+impl<F: PrimeField32> TraceFiller<F> for KeccakfVmFiller {
+    fn fill_trace(
+        &self,
+        mem_helper: &MemoryAuxColsFactory<F>,
+        trace_matrix: &mut RowMajorMatrix<F>,
+        rows_used: usize,
+    ) {
+        if rows_used == 0 {
+            return;
+        }
+        debug_assert_eq!(trace_matrix.width(), NUM_KECCAKF_VM_COLS);
+        let (trace, dummy_trace) = trace_matrix
+            .values
+            .split_at_mut(rows_used * NUM_KECCAKF_VM_COLS);
+
+        trace
+            .par_chunks_exact_mut(NUM_ROUNDS * NUM_KECCAKF_VM_COLS)
+            .for_each(|mut slice| {
+                // SAFETY:
+                // - caller ensures `trace` contains a valid record representation that was
+                //   previously written by the executor
+                // - every record is allocated at the start of its NUM_ROUNDS rows
+                let record: &KeccakfVmRecord = unsafe { get_record_from_slice(&mut slice, ()) };
+                // Copy the record to safely fill in the trace matrix without overwriting it
+                let record = record.clone();
+                self.fill_permutation(mem_helper, slice, &record);
+            });
+
+        // Fill in the dummy rows with permutations of the zero state, which are repeated every
+        // `NUM_ROUNDS` rows. Since `rows_used` is a multiple of `NUM_ROUNDS`, dummy rows start
+        // at the first round.
+        let p3_trace: RowMajorMatrix<F> = generate_trace_rows(vec![[0u64; 25]; 1], 0);
+        dummy_trace
+            .par_chunks_exact_mut(NUM_KECCAKF_VM_COLS)
+            .enumerate()
+            .for_each(|(row_idx, row)| {
+                let idx = row_idx % NUM_ROUNDS;
+                row[..NUM_KECCAK_PERM_COLS].copy_from_slice(
+                    &p3_trace.values[idx * NUM_KECCAK_PERM_COLS..(idx + 1) * NUM_KECCAK_PERM_COLS],
+                );
+                row[NUM_KECCAK_PERM_COLS..].fill(F::ZERO);
+            });
+    }
+}
+
+impl KeccakfVmFiller {
+    /// Fills the [NUM_ROUNDS] rows of the permutation of `record` into `slice`.
+    fn fill_permutation<F: PrimeField32>(
+        &self,
+        mem_helper: &MemoryAuxColsFactory<F>,
+        slice: &mut [F],
+        record: &KeccakfVmRecord,
+    ) {
+        let preimage: [u64; 25] = from_fn(|i| {
+            u64::from_le_bytes(record.preimage[i * 8..(i + 1) * 8].try_into().unwrap())
+        });
+        let postimage = keccak_f(preimage);
+
+        // We need to transpose state matrices due to a plonky3 issue: https://github.com/Plonky3/Plonky3/issues/672
+        // See `KeccakVmFiller::fill_trace`.
+        let p3_state = from_fn(|i| {
+            let x = i / 5;
+            let y = i % 5;
+            preimage[x + 5 * y]
+        });
+        let p3_trace: RowMajorMatrix<F> = generate_trace_rows(vec![p3_state], 0);
+
+        // Range check the most significant limb of the state pointer and the postimage bytes
+        let msl_rshift = RV32_CELL_BITS * (RV32_REGISTER_NUM_LIMBS - 1);
+        let msl_lshift = RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.pointer_max_bits;
+        self.bitwise_lookup_chip.request_range(
+            (record.state_ptr >> msl_rshift) << msl_lshift,
+            (record.state_ptr >> msl_rshift) << msl_lshift,
+        );
+        for s in postimage {
+            for limb in s.to_le_bytes().chunks_exact(2) {
+                self.bitwise_lookup_chip
+                    .request_range(limb[0] as u32, limb[1] as u32);
+            }
+        }
+
+        let instruction = KeccakfInstructionCols {
+            pc: F::from_canonical_u32(record.from_pc),
+            is_enabled: F::ONE,
+            start_timestamp: F::from_canonical_u32(record.timestamp),
+            rs1_ptr: F::from_canonical_u32(record.rs1_ptr),
+            state_ptr: record.state_ptr.to_le_bytes().map(F::from_canonical_u8),
+        };
+        let state_hi = |state: &[u64; 25]| -> [F; KECCAK_WIDTH_U16S] {
+            from_fn(|i| {
+                F::from_canonical_u8((state[i / U64_LIMBS] >> ((i % U64_LIMBS) * 16 + 8)) as u8)
+            })
+        };
+        let start_read_timestamp = record.timestamp + 1;
+        let start_write_timestamp = start_read_timestamp + KECCAKF_STATE_READS as u32;
+
+        slice
+            .par_chunks_exact_mut(NUM_KECCAKF_VM_COLS)
+            .zip(p3_trace.values.par_chunks(NUM_KECCAK_PERM_COLS))
+            .enumerate()
+            .for_each(|(row_idx, (row, p3_row))| {
+                // Safety: `KeccakPermCols` **must** be the first field in `KeccakfVmCols`
+                row[..NUM_KECCAK_PERM_COLS].copy_from_slice(p3_row);
+
+                let cols: &mut KeccakfVmCols<F> = row.borrow_mut();
+                cols.instruction = instruction;
+
+                let is_first_round = row_idx == 0;
+                let is_last_round = row_idx == NUM_ROUNDS - 1;
+                cols.inner.export = F::from_bool(is_last_round);
+                cols.state_hi = if is_first_round {
+                    state_hi(&preimage)
+                } else if is_last_round {
+                    state_hi(&postimage)
+                } else {
+                    [F::ZERO; KECCAK_WIDTH_U16S]
+                };
+
+                if is_first_round {
+                    mem_helper.fill(
+                        record.register_read_aux.prev_timestamp,
+                        record.timestamp,
+                        cols.mem_oc.register_aux.as_mut(),
+                    );
+                    for (i, (aux_cols, aux)) in cols
+                        .mem_oc
+                        .state_reads
+                        .iter_mut()
+                        .zip(record.read_aux.iter())
+                        .enumerate()
+                    {
+                        mem_helper.fill(
+                            aux.prev_timestamp,
+                            start_read_timestamp + i as u32,
+                            aux_cols.as_mut(),
+                        );
+                    }
+                } else {
+                    mem_helper.fill_zero(cols.mem_oc.register_aux.as_mut());
+                    for aux_cols in cols.mem_oc.state_reads.iter_mut() {
+                        mem_helper.fill_zero(aux_cols.as_mut());
+                    }
+                }
+
+                if is_last_round {
+                    for (i, (aux_cols, aux)) in cols
+                        .mem_oc
+                        .state_writes
+                        .iter_mut()
+                        .zip(record.write_aux.iter())
+                        .enumerate()
+                    {
+                        aux_cols.set_prev_data(aux.prev_data.map(F::from_canonical_u8));
+                        mem_helper.fill(
+                            aux.prev_timestamp,
+                            start_write_timestamp + i as u32,
+                            aux_cols.as_mut(),
+                        );
+                    }
+                } else {
+                    for aux_cols in cols.mem_oc.state_writes.iter_mut() {
+                        aux_cols.set_prev_data([F::ZERO; KECCAK_WORD_SIZE]);
+                        mem_helper.fill_zero(aux_cols.as_mut());
+                    }
+                }
+            });
+    }
+}
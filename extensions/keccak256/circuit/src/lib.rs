@@ -2,13 +2,15 @@
 #![cfg_attr(feature = "tco", feature(explicit_tail_calls))]
 #![cfg_attr(feature = "tco", feature(core_intrinsics))]
 //! Stateful keccak256 hasher. Handles full keccak sponge (padding, absorb, keccak-f) on
-//! variable length inputs read from VM memory.
+//! variable length inputs read from VM memory. Also provides the bare keccak-f permutation on a
+//! state in VM memory, see [keccakf].
 
 use openvm_circuit_primitives::bitwise_op_lookup::SharedBitwiseOperationLookupChip;
 
 pub mod air;
 pub mod columns;
 pub mod execution;
+pub mod keccakf;
 pub mod trace;
 pub mod utils;
 
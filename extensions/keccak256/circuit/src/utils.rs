@@ -1,6 +1,8 @@
+use std::array::from_fn;
+
 use tiny_keccak::{keccakf, Hasher, Keccak};
 
-use super::KECCAK_RATE_BYTES;
+use super::{KECCAK_RATE_BYTES, KECCAK_WIDTH_BYTES};
 
 /// Wrapper function for tiny-keccak's keccak-f permutation.
 /// Returns the new state after permutation.
@@ -9,6 +11,15 @@ pub fn keccak_f(mut state: [u64; 25]) -> [u64; 25] {
     state
 }
 
+/// Keccak-f permutation of a state given as 25 little-endian `u64` lanes in bytes, as it is stored
+/// in memory.
+pub fn keccak_f_bytes(state: [u8; KECCAK_WIDTH_BYTES]) -> [u8; KECCAK_WIDTH_BYTES] {
+    let mut lanes: [u64; 25] =
+        from_fn(|i| u64::from_le_bytes(state[i * 8..(i + 1) * 8].try_into().unwrap()));
+    keccakf(&mut lanes);
+    from_fn(|i| lanes[i / 8].to_le_bytes()[i % 8])
+}
+
 pub fn keccak256(input: &[u8]) -> [u8; 32] {
     let mut hasher = Keccak::v256();
     hasher.update(input);
@@ -9,6 +9,7 @@ use openvm_platform::alloc::AlignedBuf;
 pub const OPCODE: u8 = 0x0b;
 pub const KECCAK256_FUNCT3: u8 = 0b100;
 pub const KECCAK256_FUNCT7: u8 = 0;
+pub const KECCAKF_FUNCT7: u8 = 0x2;
 
 /// Native hook for keccak256 for use with `alloy-primitives` "native-keccak" feature.
 ///
@@ -72,3 +73,24 @@ fn __native_keccak256(bytes: *const u8, len: usize, output: *mut u8) {
         rs2 = In len
     );
 }
+
+/// keccak-f\[1600\] permutation intrinsic, for use by hashers which do their own padding and
+/// absorbing.
+///
+/// The state is 25 lanes of 64 bits, where lane `x + 5 * y` is `A[x, y]` in the notation of
+/// [FIPS 202](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf), stored as 200
+/// little-endian bytes. The permuted state is written back in place.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+pub fn native_keccakf(state: &mut [u64; 25]) {
+    // `[u64; 25]` is 8-byte aligned, so the state satisfies the 4-byte alignment required by the
+    // VM.
+    openvm_platform::custom_insn_r!(
+        opcode = OPCODE,
+        funct3 = KECCAK256_FUNCT3,
+        funct7 = KECCAKF_FUNCT7,
+        rd = Const "x0",
+        rs1 = In state.as_mut_ptr(),
+        rs2 = Const "x0"
+    );
+}
@@ -1,10 +1,13 @@
-use openvm_instructions::LocalOpcode;
+use openvm_instructions::{LocalOpcode, VmOpcode};
 use openvm_instructions_derive::LocalOpcode;
-use openvm_keccak256_guest::{KECCAK256_FUNCT3, KECCAK256_FUNCT7, OPCODE};
+use openvm_keccak256_guest::{KECCAK256_FUNCT3, KECCAK256_FUNCT7, KECCAKF_FUNCT7, OPCODE};
 use openvm_stark_backend::p3_field::PrimeField32;
-use openvm_transpiler::{util::from_r_type, TranspilerExtension, TranspilerOutput};
+use openvm_transpiler::{
+    util::{from_r_type, local_opcode_name},
+    TranspilerExtension, TranspilerOutput,
+};
 use rrs_lib::instruction_formats::RType;
-use strum::{EnumCount, EnumIter, FromRepr};
+use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator};
 
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
@@ -15,6 +18,15 @@ pub enum Rv32KeccakOpcode {
     KECCAK256,
 }
 
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
+)]
+#[opcode_offset = 0x311]
+#[repr(usize)]
+pub enum Rv32KeccakfOpcode {
+    KECCAKF,
+}
+
 #[derive(Default)]
 pub struct Keccak256TranspilerExtension;
 
@@ -31,15 +43,20 @@ impl<F: PrimeField32> TranspilerExtension<F> for Keccak256TranspilerExtension {
             return None;
         }
         let dec_insn = RType::new(instruction_u32);
-        if dec_insn.funct7 != KECCAK256_FUNCT7 as u32 {
-            return None;
-        }
-        let instruction = from_r_type(
-            Rv32KeccakOpcode::KECCAK256.global_opcode().as_usize(),
-            2,
-            &dec_insn,
-            true,
-        );
+        let global_opcode = match dec_insn.funct7 as u8 {
+            KECCAK256_FUNCT7 => Rv32KeccakOpcode::KECCAK256.global_opcode(),
+            // The permutation only reads the state pointer from `rs1`
+            KECCAKF_FUNCT7 if dec_insn.rd == 0 && dec_insn.rs2 == 0 => {
+                Rv32KeccakfOpcode::KECCAKF.global_opcode()
+            }
+            _ => return None,
+        };
+        let instruction = from_r_type(global_opcode.as_usize(), 2, &dec_insn, true);
         Some(TranspilerOutput::one_to_one(instruction))
     }
+
+    fn opcode_name(&self, opcode: VmOpcode) -> Option<String> {
+        local_opcode_name(opcode, Rv32KeccakOpcode::iter(), 1)
+            .or_else(|| local_opcode_name(opcode, Rv32KeccakfOpcode::iter(), 1))
+    }
 }
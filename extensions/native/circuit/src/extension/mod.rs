@@ -355,12 +355,11 @@ where
 pub(crate) mod phantom {
     use eyre::bail;
     use openvm_circuit::{
-        arch::{PhantomSubExecutor, Streams},
+        arch::{PhantomSubExecutor, Streams, VmRng},
         system::memory::online::GuestMemory,
     };
     use openvm_instructions::PhantomDiscriminant;
     use openvm_stark_backend::p3_field::{Field, PrimeField32};
-    use rand::rngs::StdRng;
 
     pub struct NativeHintInputSubEx;
     pub struct NativeHintSliceSubEx<const N: usize>;
@@ -373,7 +372,7 @@ pub(crate) mod phantom {
             &self,
             _: &GuestMemory,
             streams: &mut Streams<F>,
-            _: &mut StdRng,
+            _: &mut VmRng,
             _: PhantomDiscriminant,
             _: u32,
             _: u32,
@@ -399,7 +398,7 @@ pub(crate) mod phantom {
             &self,
             _: &GuestMemory,
             streams: &mut Streams<F>,
-            _: &mut StdRng,
+            _: &mut VmRng,
             _: PhantomDiscriminant,
             _: u32,
             _: u32,
@@ -423,7 +422,7 @@ pub(crate) mod phantom {
             &self,
             memory: &GuestMemory,
             _: &mut Streams<F>,
-            _: &mut StdRng,
+            _: &mut VmRng,
             _: PhantomDiscriminant,
             a: u32,
             _: u32,
@@ -448,7 +447,7 @@ pub(crate) mod phantom {
             &self,
             memory: &GuestMemory,
             streams: &mut Streams<F>,
-            _: &mut StdRng,
+            _: &mut VmRng,
             _: PhantomDiscriminant,
             a: u32,
             len: u32,
@@ -481,7 +480,7 @@ pub(crate) mod phantom {
             &self,
             _: &GuestMemory,
             streams: &mut Streams<F>,
-            _: &mut StdRng,
+            _: &mut VmRng,
             _: PhantomDiscriminant,
             _: u32,
             _: u32,
@@ -11,7 +11,10 @@ use openvm_circuit::system::cuda::extensions::SystemGpuBuilder as SystemBuilder;
 use openvm_circuit::{arch::RowMajorMatrixArena, system::SystemCpuBuilder as SystemBuilder};
 use openvm_circuit::{
     arch::{
-        execution_mode::metered::segment_ctx::{SegmentationLimits, DEFAULT_SEGMENT_CHECK_INSNS},
+        execution_mode::{
+            metered::segment_ctx::{SegmentationLimits, DEFAULT_SEGMENT_CHECK_INSNS},
+            ProfileCtx,
+        },
         hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
         verify_segments, verify_single, AirInventory, ContinuationVmProver,
         PreflightExecutionOutput, SingleSegmentVmProver, VirtualMachine, VmCircuitConfig,
@@ -24,8 +27,8 @@ use openvm_circuit::{
     },
 };
 use openvm_instructions::{
-    exe::VmExe,
-    instruction::Instruction,
+    exe::{FnBounds, VmExe},
+    instruction::{DebugInfo, Instruction},
     program::{Program, DEFAULT_PC_STEP},
     LocalOpcode, PhantomDiscriminant,
     PublishOpcode::PUBLISH,
@@ -495,6 +498,91 @@ fn test_vm_fibonacci_old_cycle_tracker() {
     air_test(NativeBuilder::default(), test_native_config(), program);
 }
 
+#[test]
+fn test_vm_profile_cycle_tracker_spans() {
+    let span = |name: &str| Some(DebugInfo::new(format!("CT-{name}"), None));
+    let instructions_and_debug_infos = vec![
+        (
+            Instruction::debug(PhantomDiscriminant(SysPhantom::CtStart as u16)),
+            span("outer"),
+        ),
+        (
+            Instruction::large_from_isize(ADD.global_opcode(), 0, 0, 1, 4, 0, 0, 0),
+            None,
+        ),
+        (
+            Instruction::debug(PhantomDiscriminant(SysPhantom::CtStart as u16)),
+            span("inner"),
+        ),
+        (
+            Instruction::large_from_isize(ADD.global_opcode(), 1, 0, 2, 4, 0, 0, 0),
+            None,
+        ),
+        (
+            Instruction::large_from_isize(ADD.global_opcode(), 2, 0, 3, 4, 0, 0, 0),
+            None,
+        ),
+        (
+            Instruction::debug(PhantomDiscriminant(SysPhantom::CtEnd as u16)),
+            span("inner"),
+        ),
+        (
+            Instruction::debug(PhantomDiscriminant(SysPhantom::CtEnd as u16)),
+            span("outer"),
+        ),
+        (
+            Instruction::from_isize(TERMINATE.global_opcode(), 0, 0, 0, 0, 0),
+            None,
+        ),
+    ];
+    let (instructions, debug_infos): (Vec<_>, Vec<_>) =
+        instructions_and_debug_infos.into_iter().unzip();
+    let exe = VmExe::new(Program::from_instructions_and_debug_infos(
+        &instructions,
+        &debug_infos,
+    ));
+
+    let executor = VmExecutor::new(test_native_config()).unwrap();
+    let ctx = ProfileCtx::new(FnBounds::default()).with_spans(&exe.program);
+    let (profile, state) = executor
+        .profile_instance(&exe)
+        .unwrap()
+        .execute_profile(vec![], ctx)
+        .unwrap();
+    assert_eq!(profile.span_names, vec!["outer", "inner"]);
+    let instructions_at = |stack: &[&str]| {
+        (0..profile.nodes.len())
+            .find(|&node| profile.stack_names(node) == stack)
+            .map(|node| profile.nodes[node].instructions)
+    };
+    // The CtStart instruction is counted in its span and the CtEnd instruction outside of it
+    assert_eq!(instructions_at(&["outer"]), Some(3));
+    assert_eq!(instructions_at(&["outer", "inner"]), Some(3));
+    assert_eq!(instructions_at(&[]), Some(state.instret() - 6));
+}
+
+#[test]
+fn test_vm_metered_profile_without_cells() {
+    let config = test_native_config();
+    let engine = TestEngine::new(FriParameters::new_for_testing(3));
+    let (vm, _) =
+        VirtualMachine::new_with_keygen(engine, NativeBuilder::default(), config).unwrap();
+    let instructions = vec![
+        Instruction::large_from_isize(ADD.global_opcode(), 0, 0, 1, 4, 0, 0, 0),
+        Instruction::from_isize(TERMINATE.global_opcode(), 0, 0, 0, 0, 0),
+    ];
+    let exe = VmExe::new(Program::from_instructions(&instructions));
+
+    // Height changes are ignored when the context was not created with `with_cells`
+    let (profile, _) = vm
+        .metered_profile_interpreter(&exe)
+        .unwrap()
+        .execute_profile(vec![], ProfileCtx::new(FnBounds::default()))
+        .unwrap();
+    assert!(!profile.profiles_cells());
+    assert!(profile.nodes.iter().all(|node| node.rows.is_empty()));
+}
+
 #[test]
 fn test_vm_field_extension_arithmetic() {
     let instructions = vec![
@@ -110,7 +110,7 @@ pub(crate) mod phantom {
     use eyre::bail;
     use halo2curves_axiom::ff;
     use openvm_circuit::{
-        arch::{PhantomSubExecutor, Streams},
+        arch::{PhantomSubExecutor, Streams, VmRng},
         system::memory::online::GuestMemory,
     };
     use openvm_ecc_guest::{algebra::field::FieldExtension, AffinePoint};
@@ -125,7 +125,6 @@ pub(crate) mod phantom {
     };
     use openvm_rv32im_circuit::adapters::{memory_read, read_rv32_register};
     use openvm_stark_backend::p3_field::Field;
-    use rand::rngs::StdRng;
 
     use super::PairingCurve;
 
@@ -136,7 +135,7 @@ pub(crate) mod phantom {
             &self,
             memory: &GuestMemory,
             streams: &mut Streams<F>,
-            _: &mut StdRng,
+            _: &mut VmRng,
             _: PhantomDiscriminant,
             a: u32,
             b: u32,
@@ -51,4 +51,9 @@ impl<F: PrimeField32> TranspilerExtension<F> for PairingTranspilerExtension {
         }
         None
     }
+
+    fn phantom_name(&self, discriminant: PhantomDiscriminant) -> Option<String> {
+        PairingPhantom::from_repr(discriminant.0)
+            .map(|phantom| format!("PairingPhantom::{phantom:?}"))
+    }
 }
@@ -0,0 +1,75 @@
+use openvm_circuit::{
+    arch::{Arena, DenseRecordArena, EmptyMultiRowLayout, MatrixRecordArena},
+    system::cuda::extensions::{get_inventory_range_checker, get_or_create_bitwise_op_lookup},
+};
+use openvm_cuda_backend::{
+    chip::{cpu_proving_ctx_to_gpu, get_empty_air_proving_ctx},
+    engine::GpuBabyBearPoseidon2Engine,
+    prover_backend::GpuBackend,
+    types::F,
+};
+use openvm_stark_backend::{prover::types::AirProvingContext, Chip};
+use openvm_stark_sdk::config::baby_bear_poseidon2::BabyBearPoseidon2Config;
+
+use super::*;
+
+/// Poseidon2 chip for the GPU backend which still does trace generation on CPU.
+#[derive(derive_new::new)]
+pub struct HybridPoseidon2Chip {
+    cpu: Poseidon2VmChip<F>,
+}
+
+// Conversion of Dense->Matrix Record Arena, cpu tracegen, and then H2D transfer of the trace
+// matrix.
+impl Chip<DenseRecordArena, GpuBackend> for HybridPoseidon2Chip {
+    fn generate_proving_ctx(&self, mut arena: DenseRecordArena) -> AirProvingContext<GpuBackend> {
+        let records = arena.allocated();
+        if records.is_empty() {
+            return get_empty_air_proving_ctx::<GpuBackend>();
+        }
+        debug_assert_eq!(records.len() % size_of::<Poseidon2VmRecord>(), 0);
+
+        let num_records = records.len() / size_of::<Poseidon2VmRecord>();
+        let height = num_records.next_power_of_two();
+        let mut matrix_arena = MatrixRecordArena::<F>::with_capacity(height, POSEIDON2_VM_WIDTH);
+        arena
+            .get_record_seeker::<&mut Poseidon2VmRecord, EmptyMultiRowLayout>()
+            .transfer_to_matrix_arena(&mut matrix_arena);
+        let ctx = self.cpu.generate_proving_ctx(matrix_arena);
+        cpu_proving_ctx_to_gpu(ctx)
+    }
+}
+
+pub struct Poseidon2GpuProverExt;
+
+impl VmProverExtension<GpuBabyBearPoseidon2Engine, DenseRecordArena, Poseidon2>
+    for Poseidon2GpuProverExt
+{
+    fn extend_prover(
+        &self,
+        _: &Poseidon2,
+        inventory: &mut ChipInventory<BabyBearPoseidon2Config, DenseRecordArena, GpuBackend>,
+    ) -> Result<(), ChipInventoryError> {
+        let pointer_max_bits = inventory.airs().pointer_max_bits();
+        let timestamp_max_bits = inventory.timestamp_max_bits();
+
+        let range_checker = get_inventory_range_checker(inventory);
+        let bitwise_lu = get_or_create_bitwise_op_lookup(inventory)?;
+
+        // Poseidon2 trace generation is done on CPU, with the CPU counterparts of the lookup chips
+        inventory.next_air::<Poseidon2VmAir<F>>()?;
+        let mem_helper =
+            SharedMemoryHelper::new(range_checker.cpu_chip.clone().unwrap(), timestamp_max_bits);
+        let poseidon2 = Poseidon2VmChip::new(
+            Poseidon2VmFiller::new(
+                bitwise_lu.cpu_chip.clone().unwrap(),
+                pointer_max_bits,
+                vm_poseidon2_config(),
+            ),
+            mem_helper,
+        );
+        inventory.add_executor_chip(HybridPoseidon2Chip::new(poseidon2));
+
+        Ok(())
+    }
+}
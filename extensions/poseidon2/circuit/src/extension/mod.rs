@@ -0,0 +1,154 @@
+use std::{result::Result, sync::Arc};
+
+use derive_more::derive::From;
+use openvm_circuit::{
+    arch::{
+        vm_poseidon2_config, AirInventory, AirInventoryError, ChipInventory, ChipInventoryError,
+        ExecutionBridge, ExecutorInventoryBuilder, ExecutorInventoryError, RowMajorMatrixArena,
+        VmCircuitExtension, VmExecutionExtension, VmProverExtension,
+    },
+    system::{memory::SharedMemoryHelper, SystemPort},
+};
+use openvm_circuit_derive::{AnyEnum, Executor, MeteredExecutor, PreflightExecutor};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupAir, BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+    SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::*;
+use openvm_poseidon2_transpiler::Rv32Poseidon2Opcode;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_field::PrimeField32,
+    prover::cpu::{CpuBackend, CpuDevice},
+};
+use openvm_stark_sdk::engine::StarkEngine;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::*;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "cuda")] {
+        mod cuda;
+        pub use self::cuda::*;
+        pub use self::cuda::Poseidon2GpuProverExt as Poseidon2ProverExt;
+    } else if #[cfg(feature = "rocm")] {
+        mod hip;
+        pub use self::hip::*;
+        pub use self::hip::Poseidon2HipProverExt as Poseidon2ProverExt;
+    } else {
+        pub use self::Poseidon2CpuProverExt as Poseidon2ProverExt;
+    }
+}
+
+// =================================== VM Extension Implementation =================================
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Poseidon2;
+
+#[derive(Clone, From, AnyEnum, Executor, MeteredExecutor, PreflightExecutor)]
+pub enum Poseidon2Executor<F: PrimeField32> {
+    Poseidon2(Poseidon2VmExecutor<F>),
+}
+
+impl<F: PrimeField32> VmExecutionExtension<F> for Poseidon2 {
+    type Executor = Poseidon2Executor<F>;
+
+    fn extend_execution(
+        &self,
+        inventory: &mut ExecutorInventoryBuilder<F, Poseidon2Executor<F>>,
+    ) -> Result<(), ExecutorInventoryError> {
+        let pointer_max_bits = inventory.pointer_max_bits();
+        let poseidon2 = Poseidon2VmExecutor::new(
+            Rv32Poseidon2Opcode::CLASS_OFFSET,
+            pointer_max_bits,
+            vm_poseidon2_config(),
+        );
+        inventory.add_executor(
+            poseidon2,
+            Rv32Poseidon2Opcode::iter().map(|x| x.global_opcode()),
+        )?;
+
+        Ok(())
+    }
+}
+
+impl<SC: StarkGenericConfig> VmCircuitExtension<SC> for Poseidon2 {
+    fn extend_circuit(&self, inventory: &mut AirInventory<SC>) -> Result<(), AirInventoryError> {
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_bridge,
+        } = inventory.system().port();
+        let exec_bridge = ExecutionBridge::new(execution_bus, program_bus);
+        let pointer_max_bits = inventory.pointer_max_bits();
+
+        let bitwise_lu = {
+            let existing_air = inventory.find_air::<BitwiseOperationLookupAir<8>>().next();
+            if let Some(air) = existing_air {
+                air.bus
+            } else {
+                let bus = BitwiseOperationLookupBus::new(inventory.new_bus_idx());
+                let air = BitwiseOperationLookupAir::<8>::new(bus);
+                inventory.add_air(air);
+                air.bus
+            }
+        };
+
+        let poseidon2 = Poseidon2VmAir::<Val<SC>>::new(
+            exec_bridge,
+            memory_bridge,
+            bitwise_lu,
+            pointer_max_bits,
+            Rv32Poseidon2Opcode::CLASS_OFFSET,
+            vm_poseidon2_config(),
+        );
+        inventory.add_air(poseidon2);
+
+        Ok(())
+    }
+}
+
+pub struct Poseidon2CpuProverExt;
+// This implementation is specific to CpuBackend because the lookup chips (VariableRangeChecker,
+// BitwiseOperationLookupChip) are specific to CpuBackend.
+impl<E, SC, RA> VmProverExtension<E, RA, Poseidon2> for Poseidon2CpuProverExt
+where
+    SC: StarkGenericConfig,
+    E: StarkEngine<SC = SC, PB = CpuBackend<SC>, PD = CpuDevice<SC>>,
+    RA: RowMajorMatrixArena<Val<SC>>,
+    Val<SC>: PrimeField32,
+{
+    fn extend_prover(
+        &self,
+        _: &Poseidon2,
+        inventory: &mut ChipInventory<SC, RA, CpuBackend<SC>>,
+    ) -> Result<(), ChipInventoryError> {
+        let range_checker = inventory.range_checker()?.clone();
+        let timestamp_max_bits = inventory.timestamp_max_bits();
+        let mem_helper = SharedMemoryHelper::new(range_checker.clone(), timestamp_max_bits);
+        let pointer_max_bits = inventory.airs().pointer_max_bits();
+
+        let bitwise_lu = {
+            let existing_chip = inventory
+                .find_chip::<SharedBitwiseOperationLookupChip<8>>()
+                .next();
+            if let Some(chip) = existing_chip {
+                chip.clone()
+            } else {
+                let air: &BitwiseOperationLookupAir<8> = inventory.next_air()?;
+                let chip = Arc::new(BitwiseOperationLookupChip::new(air.bus));
+                inventory.add_periphery_chip(chip.clone());
+                chip
+            }
+        };
+
+        inventory.next_air::<Poseidon2VmAir<Val<SC>>>()?;
+        let poseidon2 = Poseidon2VmChip::new(
+            Poseidon2VmFiller::new(bitwise_lu, pointer_max_bits, vm_poseidon2_config()),
+            mem_helper,
+        );
+        inventory.add_executor_chip(poseidon2);
+
+        Ok(())
+    }
+}
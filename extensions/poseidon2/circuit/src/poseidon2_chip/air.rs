@@ -0,0 +1,291 @@
+use std::{array::from_fn, borrow::Borrow, sync::Arc};
+
+use itertools::izip;
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionState},
+    system::memory::{offline_checker::MemoryBridge, MemoryAddress},
+};
+use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupBus;
+use openvm_instructions::riscv::{
+    RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS,
+};
+use openvm_poseidon2_air::{
+    Poseidon2Config, Poseidon2SubAir, BABY_BEAR_POSEIDON2_HALF_FULL_ROUNDS,
+};
+use openvm_poseidon2_transpiler::Rv32Poseidon2Opcode;
+use openvm_rv32im_circuit::adapters::abstract_compose;
+use openvm_stark_backend::{
+    air_builders::sub::SubAirBuilder,
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra},
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+
+use super::{
+    columns::{Poseidon2VmCols, POSEIDON2_VM_WIDTH},
+    BABY_BEAR_MAX_HI_BYTE, POSEIDON2_CHUNK, POSEIDON2_CHUNK_BYTES, POSEIDON2_TIMESTAMP_DELTA,
+    SBOX_REGISTERS,
+};
+
+#[derive(Clone, Debug)]
+pub struct Poseidon2VmAir<F: Field> {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    /// Bus to send 8-bit range check requests to.
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    /// Maximum number of bits allowed for an address pointer
+    pub ptr_max_bits: usize,
+    pub(crate) offset: usize,
+    pub(crate) subair: Arc<Poseidon2SubAir<F, SBOX_REGISTERS>>,
+}
+
+impl<F: Field> Poseidon2VmAir<F> {
+    pub fn new(
+        execution_bridge: ExecutionBridge,
+        memory_bridge: MemoryBridge,
+        bitwise_lookup_bus: BitwiseOperationLookupBus,
+        ptr_max_bits: usize,
+        offset: usize,
+        poseidon2_config: Poseidon2Config<F>,
+    ) -> Self {
+        Self {
+            execution_bridge,
+            memory_bridge,
+            bitwise_lookup_bus,
+            ptr_max_bits,
+            offset,
+            subair: Arc::new(Poseidon2SubAir::new(poseidon2_config.constants.into())),
+        }
+    }
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for Poseidon2VmAir<F> {}
+impl<F: Field> PartitionedBaseAir<F> for Poseidon2VmAir<F> {}
+impl<F: Field> BaseAir<F> for Poseidon2VmAir<F> {
+    fn width(&self) -> usize {
+        POSEIDON2_VM_WIDTH
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for Poseidon2VmAir<AB::F> {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Poseidon2VmCols<AB::Var> = (*local).borrow();
+
+        builder.assert_bool(local.is_permute);
+        builder.assert_bool(local.is_compress);
+        let is_enabled = local.is_permute + local.is_compress;
+        builder.assert_bool(is_enabled.clone());
+
+        // poseidon2 constraints are always checked
+        let mut sub_builder =
+            SubAirBuilder::<AB, Poseidon2SubAir<AB::F, SBOX_REGISTERS>, AB::F>::new(
+                builder,
+                0..self.subair.width(),
+            );
+        self.subair.eval(&mut sub_builder);
+
+        let start_read_timestamp = self.eval_instruction(builder, local, is_enabled.clone());
+        let start_write_timestamp =
+            self.constrain_state_read(builder, local, is_enabled.clone(), start_read_timestamp);
+        self.constrain_state_write(builder, local, is_enabled, start_write_timestamp);
+    }
+}
+
+impl<F: Field> Poseidon2VmAir<F> {
+    /// Receive the instruction itself on program bus. Send+receive on execution bus.
+    /// Then does memory reads in addr space 1 to get the pointers from the `rd`, `rs1` and, for
+    /// compression, `rs2` registers, and range checks their most significant limbs using
+    /// BitwiseOperationLookupBus.
+    ///
+    /// Returns `start_read_timestamp` for the state reads.
+    pub fn eval_instruction<AB: InteractionBuilder<F = F>>(
+        &self,
+        builder: &mut AB,
+        local: &Poseidon2VmCols<AB::Var>,
+        is_enabled: AB::Expr,
+    ) -> AB::Expr {
+        let opcode = local.is_permute
+            * AB::F::from_canonical_usize(Rv32Poseidon2Opcode::PERM_POS2 as usize + self.offset)
+            + local.is_compress
+                * AB::F::from_canonical_usize(
+                    Rv32Poseidon2Opcode::COMP_POS2 as usize + self.offset,
+                );
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                opcode,
+                [
+                    local.rd_ptr.into(),
+                    local.rs1_ptr.into(),
+                    local.rs2_ptr.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                local.from_state,
+                AB::Expr::from_canonical_usize(POSEIDON2_TIMESTAMP_DELTA),
+            )
+            .eval(builder, is_enabled.clone());
+
+        let mut timestamp: AB::Expr = local.from_state.timestamp.into();
+        for (ptr, data, aux, count) in izip!(
+            [local.rd_ptr, local.rs1_ptr, local.rs2_ptr],
+            [local.dst_ptr, local.lhs_ptr, local.rhs_ptr],
+            &local.register_aux,
+            [
+                is_enabled.clone(),
+                is_enabled.clone(),
+                local.is_compress.into()
+            ]
+        ) {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(AB::Expr::from_canonical_u32(RV32_REGISTER_AS), ptr),
+                    data,
+                    timestamp.clone(),
+                    aux,
+                )
+                .eval(builder, count);
+            timestamp += AB::Expr::ONE;
+        }
+
+        // For permutation, the second half of the state directly follows the first half
+        builder.when(local.is_permute).assert_eq(
+            abstract_compose::<AB::Expr, _>(local.rhs_ptr),
+            abstract_compose::<AB::Expr, _>(local.lhs_ptr)
+                + AB::F::from_canonical_usize(POSEIDON2_CHUNK_BYTES),
+        );
+
+        // See Rv32VecHeapAdapterAir
+        let limb_shift = AB::F::from_canonical_usize(
+            1 << (RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.ptr_max_bits),
+        );
+        let dst_ptr_msl = local.dst_ptr[RV32_REGISTER_NUM_LIMBS - 1];
+        let lhs_ptr_msl = local.lhs_ptr[RV32_REGISTER_NUM_LIMBS - 1];
+        let rhs_ptr_msl = local.rhs_ptr[RV32_REGISTER_NUM_LIMBS - 1];
+        self.bitwise_lookup_bus
+            .send_range(dst_ptr_msl * limb_shift, lhs_ptr_msl * limb_shift)
+            .eval(builder, is_enabled);
+        self.bitwise_lookup_bus
+            .send_range(rhs_ptr_msl * limb_shift, rhs_ptr_msl * limb_shift)
+            .eval(builder, local.is_compress);
+
+        timestamp
+    }
+
+    /// Constrain that the input state of the permutation is read from memory, as two chunks at
+    /// `lhs_ptr` and `rhs_ptr`. Each element of the input state is the composition of its bytes,
+    /// which is reduced modulo the field characteristic.
+    ///
+    /// Returns the `start_write_timestamp` for the state writes.
+    pub fn constrain_state_read<AB: InteractionBuilder<F = F>>(
+        &self,
+        builder: &mut AB,
+        local: &Poseidon2VmCols<AB::Var>,
+        is_enabled: AB::Expr,
+        start_read_timestamp: AB::Expr,
+    ) -> AB::Expr {
+        for (input, bytes) in local.inner.inputs.iter().zip(&local.input_bytes) {
+            builder
+                .when(is_enabled.clone())
+                .assert_eq(*input, abstract_compose::<AB::Expr, _>(*bytes));
+        }
+
+        let mut timestamp = start_read_timestamp;
+        for (ptr, chunk, aux) in izip!(
+            [local.lhs_ptr, local.rhs_ptr],
+            local.input_bytes.chunks_exact(POSEIDON2_CHUNK),
+            &local.input_aux
+        ) {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(
+                        AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                        abstract_compose::<AB::Expr, _>(ptr),
+                    ),
+                    from_fn::<_, POSEIDON2_CHUNK_BYTES, _>(|i| chunk[i / 4][i % 4]),
+                    timestamp.clone(),
+                    aux,
+                )
+                .eval(builder, is_enabled.clone());
+            timestamp += AB::Expr::ONE;
+        }
+        timestamp
+    }
+
+    /// Constrain that the output state is written to memory at `dst_ptr`. Compression only writes
+    /// the first half of the state.
+    ///
+    /// The output bytes are range checked, and each element is constrained to be the canonical
+    /// representative of the corresponding element of the permuted state: its most significant
+    /// byte is at most `0x78`, and if it is equal to `0x78` then the other bytes are zero.
+    pub fn constrain_state_write<AB: InteractionBuilder<F = F>>(
+        &self,
+        builder: &mut AB,
+        local: &Poseidon2VmCols<AB::Var>,
+        is_enabled: AB::Expr,
+        start_write_timestamp: AB::Expr,
+    ) {
+        let outputs = local.inner.ending_full_rounds[BABY_BEAR_POSEIDON2_HALF_FULL_ROUNDS - 1].post;
+        let max_hi_byte = AB::Expr::from_canonical_u32(BABY_BEAR_MAX_HI_BYTE);
+        let dst_ptr = abstract_compose::<AB::Expr, _>(local.dst_ptr);
+
+        for (chunk_idx, count) in [is_enabled, local.is_permute.into()]
+            .into_iter()
+            .enumerate()
+        {
+            let range = chunk_idx * POSEIDON2_CHUNK..(chunk_idx + 1) * POSEIDON2_CHUNK;
+            for i in range.clone() {
+                builder.when(count.clone()).assert_eq(
+                    outputs[i],
+                    abstract_compose::<AB::Expr, _>(local.output_bytes[i]),
+                );
+            }
+
+            let chunk_bytes = &local.output_bytes[range.clone()];
+            for pair in chunk_bytes.as_flattened().chunks_exact(2) {
+                self.bitwise_lookup_bus
+                    .send_range(pair[0], pair[1])
+                    .eval(builder, count.clone());
+            }
+            for pair in chunk_bytes.chunks_exact(2) {
+                self.bitwise_lookup_bus
+                    .send_range(
+                        max_hi_byte.clone() - pair[0][3],
+                        max_hi_byte.clone() - pair[1][3],
+                    )
+                    .eval(builder, count.clone());
+            }
+
+            self.memory_bridge
+                .write(
+                    MemoryAddress::new(
+                        AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                        dst_ptr.clone()
+                            + AB::F::from_canonical_usize(chunk_idx * POSEIDON2_CHUNK_BYTES),
+                    ),
+                    from_fn::<_, POSEIDON2_CHUNK_BYTES, _>(|i| chunk_bytes[i / 4][i % 4]),
+                    start_write_timestamp.clone() + AB::F::from_canonical_usize(chunk_idx),
+                    &local.output_aux[chunk_idx],
+                )
+                .eval(builder, count);
+        }
+
+        // If the most significant byte is `0x78`, then the element is canonical only if all other
+        // bytes are zero. Rows where the output is not written have zero output bytes, so this is
+        // not gated.
+        for (bytes, hi_diff_inv) in local
+            .output_bytes
+            .iter()
+            .zip(local.output_hi_diff_inv.iter())
+        {
+            let hi_diff = max_hi_byte.clone() - bytes[3];
+            builder.assert_zero(
+                (bytes[0] + bytes[1] + bytes[2]) * (AB::Expr::ONE - hi_diff * *hi_diff_inv),
+            );
+        }
+    }
+}
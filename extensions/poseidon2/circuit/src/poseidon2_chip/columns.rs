@@ -0,0 +1,44 @@
+use openvm_circuit::{
+    arch::ExecutionState,
+    system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols},
+};
+use openvm_circuit_primitives::AlignedBorrow;
+use openvm_instructions::riscv::RV32_REGISTER_NUM_LIMBS;
+use openvm_poseidon2_air::{Poseidon2SubCols, POSEIDON2_WIDTH};
+
+use super::{POSEIDON2_CHUNK_BYTES, SBOX_REGISTERS};
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Poseidon2VmCols<T> {
+    /// Columns for the Poseidon2 permutation. These **must** be the first columns.
+    pub inner: Poseidon2SubCols<T, SBOX_REGISTERS>,
+    pub from_state: ExecutionState<T>,
+    /// The opcode flags. At most one of them is set, and neither is set on dummy rows.
+    pub is_permute: T,
+    pub is_compress: T,
+
+    pub rd_ptr: T,
+    pub rs1_ptr: T,
+    pub rs2_ptr: T,
+    /// Memory pointers read from the `rd`, `rs1` and `rs2` registers. For permutation, `rs2` is
+    /// not read and `rhs_ptr` is `lhs_ptr + POSEIDON2_CHUNK_BYTES`.
+    pub dst_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub lhs_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub rhs_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+
+    /// The little-endian bytes of the input state, as read from memory
+    pub input_bytes: [[T; 4]; POSEIDON2_WIDTH],
+    /// The little-endian bytes of the canonical representatives of the output state. Only the
+    /// first half of the state is written for compression, and the rest is zero.
+    pub output_bytes: [[T; 4]; POSEIDON2_WIDTH],
+    /// For each output element, zero if its most significant byte is `0x78`, or else the inverse
+    /// of `0x78` minus that byte. Used to constrain that the output is canonical.
+    pub output_hi_diff_inv: [T; POSEIDON2_WIDTH],
+
+    pub register_aux: [MemoryReadAuxCols<T>; 3],
+    pub input_aux: [MemoryReadAuxCols<T>; 2],
+    pub output_aux: [MemoryWriteAuxCols<T, POSEIDON2_CHUNK_BYTES>; 2],
+}
+
+pub const POSEIDON2_VM_WIDTH: usize = Poseidon2VmCols::<u8>::width();
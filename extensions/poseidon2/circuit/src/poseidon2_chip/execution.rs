@@ -0,0 +1,211 @@
+use std::borrow::{Borrow, BorrowMut};
+
+use openvm_circuit::{arch::*, system::memory::online::GuestMemory};
+use openvm_circuit_primitives::AlignedBytesBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+    LocalOpcode,
+};
+use openvm_poseidon2_air::Poseidon2SubChip;
+use openvm_poseidon2_transpiler::Rv32Poseidon2Opcode;
+use openvm_stark_backend::p3_field::{Field, PrimeField32};
+
+use super::{poseidon2_permute_bytes, Poseidon2VmExecutor, POSEIDON2_CHUNK_BYTES, SBOX_REGISTERS};
+
+#[derive(AlignedBytesBorrow, Clone)]
+#[repr(C)]
+struct Poseidon2PreCompute<'a, F: Field> {
+    subchip: &'a Poseidon2SubChip<F, SBOX_REGISTERS>,
+    a: u8,
+    b: u8,
+    c: u8,
+}
+
+macro_rules! dispatch {
+    ($execute_impl:ident, $opcode:expr) => {
+        if $opcode == Rv32Poseidon2Opcode::PERM_POS2.global_opcode() {
+            Ok($execute_impl::<_, _, true>)
+        } else {
+            Ok($execute_impl::<_, _, false>)
+        }
+    };
+}
+
+impl<F: PrimeField32> Executor<F> for Poseidon2VmExecutor<F> {
+    fn pre_compute_size(&self) -> usize {
+        size_of::<Poseidon2PreCompute<F>>()
+    }
+
+    #[cfg(not(feature = "tco"))]
+    fn pre_compute<Ctx>(
+        &self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<ExecuteFunc<F, Ctx>, StaticProgramError>
+    where
+        Ctx: ExecutionCtxTrait,
+    {
+        let data: &mut Poseidon2PreCompute<F> = data.borrow_mut();
+        self.pre_compute_impl(pc, inst, data)?;
+        dispatch!(execute_e1_impl, inst.opcode)
+    }
+
+    #[cfg(feature = "tco")]
+    fn handler<Ctx>(
+        &self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<Handler<F, Ctx>, StaticProgramError>
+    where
+        Ctx: ExecutionCtxTrait,
+    {
+        let data: &mut Poseidon2PreCompute<F> = data.borrow_mut();
+        self.pre_compute_impl(pc, inst, data)?;
+        dispatch!(execute_e1_handler, inst.opcode)
+    }
+}
+
+impl<F: PrimeField32> MeteredExecutor<F> for Poseidon2VmExecutor<F> {
+    fn metered_pre_compute_size(&self) -> usize {
+        size_of::<E2PreCompute<Poseidon2PreCompute<F>>>()
+    }
+
+    #[cfg(not(feature = "tco"))]
+    fn metered_pre_compute<Ctx>(
+        &self,
+        chip_idx: usize,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<ExecuteFunc<F, Ctx>, StaticProgramError>
+    where
+        Ctx: MeteredExecutionCtxTrait,
+    {
+        let data: &mut E2PreCompute<Poseidon2PreCompute<F>> = data.borrow_mut();
+        data.chip_idx = chip_idx as u32;
+        self.pre_compute_impl(pc, inst, &mut data.data)?;
+        dispatch!(execute_e2_impl, inst.opcode)
+    }
+
+    #[cfg(feature = "tco")]
+    fn metered_handler<Ctx>(
+        &self,
+        chip_idx: usize,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<Handler<F, Ctx>, StaticProgramError>
+    where
+        Ctx: MeteredExecutionCtxTrait,
+    {
+        let data: &mut E2PreCompute<Poseidon2PreCompute<F>> = data.borrow_mut();
+        data.chip_idx = chip_idx as u32;
+        self.pre_compute_impl(pc, inst, &mut data.data)?;
+        dispatch!(execute_e2_handler, inst.opcode)
+    }
+}
+
+#[inline(always)]
+unsafe fn execute_e12_impl<F: PrimeField32, CTX: ExecutionCtxTrait, const IS_PERM: bool>(
+    pre_compute: &Poseidon2PreCompute<F>,
+    instret: &mut u64,
+    pc: &mut u32,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let dst = u32::from_le_bytes(exec_state.vm_read(RV32_REGISTER_AS, pre_compute.a as u32));
+    let lhs = u32::from_le_bytes(exec_state.vm_read(RV32_REGISTER_AS, pre_compute.b as u32));
+    let rhs = if IS_PERM {
+        lhs + POSEIDON2_CHUNK_BYTES as u32
+    } else {
+        u32::from_le_bytes(exec_state.vm_read(RV32_REGISTER_AS, pre_compute.c as u32))
+    };
+
+    let mut state = [0u8; 2 * POSEIDON2_CHUNK_BYTES];
+    let (lhs_bytes, rhs_bytes) = state.split_at_mut(POSEIDON2_CHUNK_BYTES);
+    lhs_bytes
+        .copy_from_slice(&exec_state.vm_read::<u8, POSEIDON2_CHUNK_BYTES>(RV32_MEMORY_AS, lhs));
+    rhs_bytes
+        .copy_from_slice(&exec_state.vm_read::<u8, POSEIDON2_CHUNK_BYTES>(RV32_MEMORY_AS, rhs));
+
+    let output = poseidon2_permute_bytes(pre_compute.subchip, &state);
+    let (output_lhs, output_rhs) = output.split_at(POSEIDON2_CHUNK_BYTES);
+    exec_state.vm_write::<u8, POSEIDON2_CHUNK_BYTES>(
+        RV32_MEMORY_AS,
+        dst,
+        output_lhs.try_into().unwrap(),
+    );
+    if IS_PERM {
+        exec_state.vm_write::<u8, POSEIDON2_CHUNK_BYTES>(
+            RV32_MEMORY_AS,
+            dst + POSEIDON2_CHUNK_BYTES as u32,
+            output_rhs.try_into().unwrap(),
+        );
+    }
+
+    *pc = pc.wrapping_add(DEFAULT_PC_STEP);
+    *instret += 1;
+}
+
+#[create_handler]
+#[inline(always)]
+unsafe fn execute_e1_impl<F: PrimeField32, CTX: ExecutionCtxTrait, const IS_PERM: bool>(
+    pre_compute: &[u8],
+    instret: &mut u64,
+    pc: &mut u32,
+    _instret_end: u64,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let pre_compute: &Poseidon2PreCompute<F> = pre_compute.borrow();
+    execute_e12_impl::<F, CTX, IS_PERM>(pre_compute, instret, pc, exec_state);
+}
+
+#[create_handler]
+#[inline(always)]
+unsafe fn execute_e2_impl<F: PrimeField32, CTX: MeteredExecutionCtxTrait, const IS_PERM: bool>(
+    pre_compute: &[u8],
+    instret: &mut u64,
+    pc: &mut u32,
+    _arg: u64,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let pre_compute: &E2PreCompute<Poseidon2PreCompute<F>> = pre_compute.borrow();
+    execute_e12_impl::<F, CTX, IS_PERM>(&pre_compute.data, instret, pc, exec_state);
+    exec_state
+        .ctx
+        .on_height_change(pre_compute.chip_idx as usize, 1);
+}
+
+impl<'a, F: PrimeField32> Poseidon2VmExecutor<F> {
+    fn pre_compute_impl(
+        &'a self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut Poseidon2PreCompute<'a, F>,
+    ) -> Result<(), StaticProgramError> {
+        let Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = inst;
+        if d.as_canonical_u32() != RV32_REGISTER_AS || e.as_canonical_u32() != RV32_MEMORY_AS {
+            return Err(StaticProgramError::InvalidInstruction(pc));
+        }
+        *data = Poseidon2PreCompute {
+            subchip: &self.subchip,
+            a: a.as_canonical_u32() as u8,
+            b: b.as_canonical_u32() as u8,
+            c: c.as_canonical_u32() as u8,
+        };
+        let local_opcode = opcode.local_opcode_idx(self.offset);
+        assert!(Rv32Poseidon2Opcode::from_repr(local_opcode).is_some());
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+//! Poseidon2 permutation and compression over BabyBear, for use by RV32 guests. Each instruction
+//! is a single row containing the columns of the
+//! [Poseidon2SubAir](openvm_poseidon2_air::Poseidon2SubAir).
+//!
+//! In memory, every BabyBear element is a little-endian `u32`. Inputs are reduced modulo the
+//! BabyBear prime, and outputs are constrained to be canonical so that they can be compared as
+//! integers by the guest.
+
+use openvm_circuit::arch::VmChipWrapper;
+use openvm_circuit_primitives::bitwise_op_lookup::SharedBitwiseOperationLookupChip;
+use openvm_poseidon2_air::{Poseidon2Config, Poseidon2SubChip, POSEIDON2_WIDTH};
+use openvm_stark_backend::p3_field::{Field, PrimeField32};
+
+mod air;
+mod columns;
+mod execution;
+mod trace;
+
+pub use air::*;
+pub use columns::*;
+pub use trace::*;
+
+#[cfg(test)]
+mod tests;
+
+/// The Poseidon2 sub-AIR uses one S-box register, so that the AIR has constraint degree 3
+pub(crate) const SBOX_REGISTERS: usize = 1;
+/// Number of elements in each input of the compression function, and in its output
+pub const POSEIDON2_CHUNK: usize = POSEIDON2_WIDTH / 2;
+/// Number of bytes in memory of [POSEIDON2_CHUNK] elements. Every memory access of the state is
+/// of this size.
+pub const POSEIDON2_CHUNK_BYTES: usize = POSEIDON2_CHUNK * 4;
+/// Most significant byte of `p - 1`, where `p` is the BabyBear prime. The lower bytes of `p - 1`
+/// are all zero.
+pub(crate) const BABY_BEAR_MAX_HI_BYTE: u32 = 0x78;
+/// Amount the timestamp advances by: three register reads, two state reads and two state writes.
+/// The same amount is used for both opcodes, even though compression skips one register read and
+/// one write.
+pub(crate) const POSEIDON2_TIMESTAMP_DELTA: usize = 7;
+
+pub type Poseidon2VmChip<F> = VmChipWrapper<F, Poseidon2VmFiller<F>>;
+
+#[derive(Clone)]
+pub struct Poseidon2VmExecutor<F: Field> {
+    pub offset: usize,
+    pub pointer_max_bits: usize,
+    pub(crate) subchip: Poseidon2SubChip<F, SBOX_REGISTERS>,
+}
+
+pub struct Poseidon2VmFiller<F: Field> {
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<8>,
+    pub pointer_max_bits: usize,
+    pub(crate) subchip: Poseidon2SubChip<F, SBOX_REGISTERS>,
+    // Pre-computed Poseidon2 sub cols for dummy rows
+    empty_poseidon2_sub_cols: Vec<F>,
+}
+
+impl<F: PrimeField32> Poseidon2VmExecutor<F> {
+    pub fn new(
+        offset: usize,
+        pointer_max_bits: usize,
+        poseidon2_config: Poseidon2Config<F>,
+    ) -> Self {
+        Self {
+            offset,
+            pointer_max_bits,
+            subchip: Poseidon2SubChip::new(poseidon2_config.constants),
+        }
+    }
+}
+
+impl<F: PrimeField32> Poseidon2VmFiller<F> {
+    pub fn new(
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<8>,
+        pointer_max_bits: usize,
+        poseidon2_config: Poseidon2Config<F>,
+    ) -> Self {
+        let subchip = Poseidon2SubChip::new(poseidon2_config.constants);
+        let empty_poseidon2_sub_cols = subchip
+            .generate_trace(vec![[F::ZERO; POSEIDON2_WIDTH]])
+            .values;
+        Self {
+            bitwise_lookup_chip,
+            pointer_max_bits,
+            subchip,
+            empty_poseidon2_sub_cols,
+        }
+    }
+}
+
+/// Decodes a state element from its little-endian bytes in memory, reducing it modulo `p`.
+#[inline(always)]
+pub(crate) fn element_from_bytes<F: PrimeField32>(bytes: &[u8]) -> F {
+    F::from_wrapped_u32(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Applies the Poseidon2 permutation to a state of `POSEIDON2_WIDTH` elements in their memory
+/// representation, and returns the permuted state in the same representation.
+pub(crate) fn poseidon2_permute_bytes<F: PrimeField32>(
+    subchip: &Poseidon2SubChip<F, SBOX_REGISTERS>,
+    input: &[u8; 2 * POSEIDON2_CHUNK_BYTES],
+) -> [u8; 2 * POSEIDON2_CHUNK_BYTES] {
+    let state: [F; POSEIDON2_WIDTH] =
+        std::array::from_fn(|i| element_from_bytes(&input[i * 4..(i + 1) * 4]));
+    let output = subchip.permute(state);
+    let mut output_bytes = [0u8; 2 * POSEIDON2_CHUNK_BYTES];
+    for (bytes, x) in output_bytes.chunks_exact_mut(4).zip(output) {
+        bytes.copy_from_slice(&x.as_canonical_u32().to_le_bytes());
+    }
+    output_bytes
+}
@@ -0,0 +1,323 @@
+use std::{array, borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::{
+    arch::{
+        hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+        testing::{
+            memory::gen_pointer, TestBuilder, TestChipHarness, VmChipTestBuilder,
+            BITWISE_OP_LOOKUP_BUS,
+        },
+        vm_poseidon2_config, Arena, ExecutionBridge, PreflightExecutor,
+    },
+    system::memory::{offline_checker::MemoryBridge, SharedMemoryHelper},
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupAir, BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+    SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS},
+    LocalOpcode,
+};
+use openvm_poseidon2_air::{Poseidon2SubChip, POSEIDON2_WIDTH};
+use openvm_poseidon2_transpiler::Rv32Poseidon2Opcode;
+use openvm_stark_backend::{
+    p3_field::{FieldAlgebra, PrimeField32},
+    p3_matrix::{dense::DenseMatrix, Matrix},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{
+    poseidon2_permute_bytes, Poseidon2VmAir, Poseidon2VmChip, Poseidon2VmCols, Poseidon2VmExecutor,
+    Poseidon2VmFiller, POSEIDON2_CHUNK, POSEIDON2_CHUNK_BYTES,
+};
+
+type F = BabyBear;
+const MAX_INS_CAPACITY: usize = 128;
+type Harness<RA> =
+    TestChipHarness<F, Poseidon2VmExecutor<F>, Poseidon2VmAir<F>, Poseidon2VmChip<F>, RA>;
+
+fn create_harness_fields(
+    execution_bridge: ExecutionBridge,
+    memory_bridge: MemoryBridge,
+    bitwise_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    memory_helper: SharedMemoryHelper<F>,
+    address_bits: usize,
+) -> (
+    Poseidon2VmAir<F>,
+    Poseidon2VmExecutor<F>,
+    Poseidon2VmChip<F>,
+) {
+    let air = Poseidon2VmAir::new(
+        execution_bridge,
+        memory_bridge,
+        bitwise_chip.bus(),
+        address_bits,
+        Rv32Poseidon2Opcode::CLASS_OFFSET,
+        vm_poseidon2_config(),
+    );
+    let executor = Poseidon2VmExecutor::new(
+        Rv32Poseidon2Opcode::CLASS_OFFSET,
+        address_bits,
+        vm_poseidon2_config(),
+    );
+    let chip = Poseidon2VmChip::new(
+        Poseidon2VmFiller::new(bitwise_chip, address_bits, vm_poseidon2_config()),
+        memory_helper,
+    );
+    (air, executor, chip)
+}
+
+fn create_test_harness<RA: Arena>(
+    tester: &mut VmChipTestBuilder<F>,
+) -> (
+    Harness<RA>,
+    (
+        BitwiseOperationLookupAir<RV32_CELL_BITS>,
+        SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    ),
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let (air, executor, chip) = create_harness_fields(
+        tester.execution_bridge(),
+        tester.memory_bridge(),
+        bitwise_chip.clone(),
+        tester.memory_helper(),
+        tester.address_bits(),
+    );
+
+    let harness = Harness::<RA>::with_capacity(executor, air, chip, MAX_INS_CAPACITY);
+
+    (harness, (bitwise_chip.air, bitwise_chip))
+}
+
+fn write_chunk(tester: &mut impl TestBuilder<F>, ptr: usize, chunk: &[u8]) {
+    for (i, word) in chunk.chunks_exact(4).enumerate() {
+        tester.write::<4>(
+            RV32_MEMORY_AS as usize,
+            ptr + i * 4,
+            array::from_fn(|j| F::from_canonical_u8(word[j])),
+        );
+    }
+}
+
+/// Executes `opcode` on the input state `input`, given as `u32` words. If `in_place` is set, the
+/// output is written over the first input chunk.
+#[allow(clippy::too_many_arguments)]
+fn set_and_execute<RA: Arena, E: PreflightExecutor<F, RA>>(
+    tester: &mut impl TestBuilder<F>,
+    executor: &mut E,
+    arena: &mut RA,
+    rng: &mut StdRng,
+    opcode: Rv32Poseidon2Opcode,
+    input: Option<[u32; POSEIDON2_WIDTH]>,
+    in_place: bool,
+) {
+    let input = input.unwrap_or_else(|| array::from_fn(|_| rng.gen()));
+    let mut input_bytes = [0u8; 2 * POSEIDON2_CHUNK_BYTES];
+    for (bytes, x) in input_bytes.chunks_exact_mut(4).zip(input) {
+        bytes.copy_from_slice(&x.to_le_bytes());
+    }
+    let is_permute = opcode == Rv32Poseidon2Opcode::PERM_POS2;
+
+    let rd = gen_pointer(rng, 4);
+    let rs1 = gen_pointer(rng, 4);
+    let rs2 = if is_permute { 0 } else { gen_pointer(rng, 4) };
+    let lhs_ptr = gen_pointer(rng, 4 * POSEIDON2_WIDTH);
+    let rhs_ptr = if is_permute {
+        lhs_ptr + POSEIDON2_CHUNK_BYTES
+    } else {
+        gen_pointer(rng, POSEIDON2_CHUNK_BYTES)
+    };
+    let dst_ptr = if in_place {
+        lhs_ptr
+    } else {
+        gen_pointer(rng, 4 * POSEIDON2_WIDTH)
+    };
+
+    tester.write(
+        RV32_REGISTER_AS as usize,
+        rd,
+        (dst_ptr as u32).to_le_bytes().map(F::from_canonical_u8),
+    );
+    tester.write(
+        RV32_REGISTER_AS as usize,
+        rs1,
+        (lhs_ptr as u32).to_le_bytes().map(F::from_canonical_u8),
+    );
+    if !is_permute {
+        tester.write(
+            RV32_REGISTER_AS as usize,
+            rs2,
+            (rhs_ptr as u32).to_le_bytes().map(F::from_canonical_u8),
+        );
+    }
+    write_chunk(tester, lhs_ptr, &input_bytes[..POSEIDON2_CHUNK_BYTES]);
+    write_chunk(tester, rhs_ptr, &input_bytes[POSEIDON2_CHUNK_BYTES..]);
+
+    tester.execute(
+        executor,
+        arena,
+        &Instruction::from_usize(
+            opcode.global_opcode(),
+            [
+                rd,
+                rs1,
+                rs2,
+                RV32_REGISTER_AS as usize,
+                RV32_MEMORY_AS as usize,
+            ],
+        ),
+    );
+
+    let subchip = Poseidon2SubChip::<F, 1>::new(vm_poseidon2_config().constants);
+    let expected = poseidon2_permute_bytes(&subchip, &input_bytes);
+    let output_len = if is_permute {
+        2 * POSEIDON2_CHUNK_BYTES
+    } else {
+        POSEIDON2_CHUNK_BYTES
+    };
+    for (i, word) in expected[..output_len].chunks_exact(4).enumerate() {
+        assert_eq!(
+            array::from_fn(|j| F::from_canonical_u8(word[j])),
+            tester.read::<4>(RV32_MEMORY_AS as usize, dst_ptr + i * 4)
+        );
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Randomly generate computations and execute, ensuring that the generated trace
+/// passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn rand_poseidon2_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_test_harness(&mut tester);
+
+    // Inputs which are not reduced modulo the BabyBear prime
+    let edge_inputs = [
+        [0; POSEIDON2_WIDTH],
+        [u32::MAX; POSEIDON2_WIDTH],
+        [F::ORDER_U32; POSEIDON2_WIDTH],
+        [F::ORDER_U32 - 1; POSEIDON2_WIDTH],
+    ];
+    for opcode in [
+        Rv32Poseidon2Opcode::PERM_POS2,
+        Rv32Poseidon2Opcode::COMP_POS2,
+    ] {
+        for input in edge_inputs {
+            set_and_execute(
+                &mut tester,
+                &mut harness.executor,
+                &mut harness.arena,
+                &mut rng,
+                opcode,
+                Some(input),
+                false,
+            );
+        }
+        for in_place in [false, true, false] {
+            set_and_execute(
+                &mut tester,
+                &mut harness.executor,
+                &mut harness.arena,
+                &mut rng,
+                opcode,
+                None,
+                in_place,
+            );
+        }
+    }
+
+    let tester = tester
+        .build()
+        .load(harness)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that the output agrees with the Poseidon2 hasher of the VM.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn poseidon2_compress_sanity_test() {
+    let mut rng = create_seeded_rng();
+    let hasher = vm_poseidon2_hasher::<F>();
+    let subchip = Poseidon2SubChip::<F, 1>::new(vm_poseidon2_config().constants);
+    for _ in 0..10 {
+        let lhs: [F; POSEIDON2_CHUNK] =
+            array::from_fn(|_| F::from_canonical_u32(rng.gen_range(0..F::ORDER_U32)));
+        let rhs: [F; POSEIDON2_CHUNK] =
+            array::from_fn(|_| F::from_canonical_u32(rng.gen_range(0..F::ORDER_U32)));
+        let mut input = [0u8; 2 * POSEIDON2_CHUNK_BYTES];
+        for (bytes, x) in input.chunks_exact_mut(4).zip(lhs.iter().chain(rhs.iter())) {
+            bytes.copy_from_slice(&x.as_canonical_u32().to_le_bytes());
+        }
+        let output = poseidon2_permute_bytes(&subchip, &input);
+        let expected = hasher.compress(&lhs, &rhs);
+        for (bytes, x) in output.chunks_exact(4).zip(expected) {
+            assert_eq!(
+                u32::from_le_bytes(bytes.try_into().unwrap()),
+                x.as_canonical_u32()
+            );
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// part of the trace and check that the chip throws the expected error.
+//////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn test_poseidon2_non_canonical_output_negative() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_test_harness(&mut tester);
+
+    set_and_execute(
+        &mut tester,
+        &mut harness.executor,
+        &mut harness.arena,
+        &mut rng,
+        Rv32Poseidon2Opcode::PERM_POS2,
+        None,
+        false,
+    );
+
+    // Replace the first output element `x` by `x + p`, which is the same field element but is
+    // not canonical
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let width = trace.width();
+        let cols: &mut Poseidon2VmCols<F> = trace.values[..width].borrow_mut();
+        let x = u32::from_le_bytes(cols.output_bytes[0].map(|b| b.as_canonical_u32() as u8));
+        let bytes = (x + F::ORDER_U32).to_le_bytes();
+        cols.output_bytes[0] = bytes.map(F::from_canonical_u8);
+        cols.output_hi_diff_inv[0] = (F::from_canonical_u8(0x78) - F::from_canonical_u8(bytes[3]))
+            .try_inverse()
+            .unwrap();
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(harness, modify_trace)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::ChallengePhaseError);
+}
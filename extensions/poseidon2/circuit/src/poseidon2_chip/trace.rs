@@ -0,0 +1,278 @@
+use std::{array::from_fn, borrow::BorrowMut};
+
+use openvm_circuit::{
+    arch::*,
+    system::memory::{
+        offline_checker::{MemoryReadAuxRecord, MemoryWriteBytesAuxRecord},
+        online::TracingMemory,
+        MemoryAuxColsFactory,
+    },
+};
+use openvm_circuit_primitives::AlignedBytesBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+    LocalOpcode,
+};
+use openvm_poseidon2_air::POSEIDON2_WIDTH;
+use openvm_poseidon2_transpiler::Rv32Poseidon2Opcode;
+use openvm_rv32im_circuit::adapters::{tracing_read, tracing_write};
+use openvm_stark_backend::{p3_air::BaseAir, p3_field::PrimeField32};
+
+use super::{
+    columns::Poseidon2VmCols, element_from_bytes, poseidon2_permute_bytes, Poseidon2VmExecutor,
+    Poseidon2VmFiller, BABY_BEAR_MAX_HI_BYTE, POSEIDON2_CHUNK, POSEIDON2_CHUNK_BYTES,
+};
+
+#[repr(C)]
+#[derive(AlignedBytesBorrow, Debug, Clone)]
+pub struct Poseidon2VmRecord {
+    pub from_pc: u32,
+    pub timestamp: u32,
+    pub rd_ptr: u32,
+    pub rs1_ptr: u32,
+    pub rs2_ptr: u32,
+    pub dst_ptr: u32,
+    pub lhs_ptr: u32,
+    pub rhs_ptr: u32,
+    pub is_compress: bool,
+    /// The input state, as read from memory
+    pub input: [u8; 2 * POSEIDON2_CHUNK_BYTES],
+
+    pub register_aux: [MemoryReadAuxRecord; 3],
+    pub input_aux: [MemoryReadAuxRecord; 2],
+    pub output_aux: [MemoryWriteBytesAuxRecord<POSEIDON2_CHUNK_BYTES>; 2],
+}
+
+impl<F, RA> PreflightExecutor<F, RA> for Poseidon2VmExecutor<F>
+where
+    F: PrimeField32,
+    for<'buf> RA: RecordArena<'buf, EmptyMultiRowLayout, &'buf mut Poseidon2VmRecord>,
+{
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32Poseidon2Opcode::from_usize(opcode - self.offset)
+        )
+    }
+
+    fn execute(
+        &self,
+        state: VmStateMut<F, TracingMemory, RA>,
+        instruction: &Instruction<F>,
+    ) -> Result<(), ExecutionError> {
+        let &Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = instruction;
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+        debug_assert_eq!(e.as_canonical_u32(), RV32_MEMORY_AS);
+        let is_compress = opcode == Rv32Poseidon2Opcode::COMP_POS2.global_opcode();
+        debug_assert!(is_compress || opcode == Rv32Poseidon2Opcode::PERM_POS2.global_opcode());
+
+        let record = state.ctx.alloc(EmptyMultiRowLayout::default());
+
+        record.from_pc = *state.pc;
+        record.timestamp = state.memory.timestamp();
+        record.rd_ptr = a.as_canonical_u32();
+        record.rs1_ptr = b.as_canonical_u32();
+        record.rs2_ptr = c.as_canonical_u32();
+        record.is_compress = is_compress;
+
+        record.dst_ptr = u32::from_le_bytes(tracing_read(
+            state.memory,
+            RV32_REGISTER_AS,
+            record.rd_ptr,
+            &mut record.register_aux[0].prev_timestamp,
+        ));
+        record.lhs_ptr = u32::from_le_bytes(tracing_read(
+            state.memory,
+            RV32_REGISTER_AS,
+            record.rs1_ptr,
+            &mut record.register_aux[1].prev_timestamp,
+        ));
+        if is_compress {
+            record.rhs_ptr = u32::from_le_bytes(tracing_read(
+                state.memory,
+                RV32_REGISTER_AS,
+                record.rs2_ptr,
+                &mut record.register_aux[2].prev_timestamp,
+            ));
+        } else {
+            record.rhs_ptr = record.lhs_ptr + POSEIDON2_CHUNK_BYTES as u32;
+            state.memory.increment_timestamp();
+        }
+        debug_assert!(
+            record.dst_ptr as usize + 2 * POSEIDON2_CHUNK_BYTES <= (1 << self.pointer_max_bits)
+        );
+        debug_assert!(
+            record.lhs_ptr as usize + POSEIDON2_CHUNK_BYTES <= (1 << self.pointer_max_bits)
+        );
+        debug_assert!(
+            record.rhs_ptr as usize + POSEIDON2_CHUNK_BYTES <= (1 << self.pointer_max_bits)
+        );
+
+        for (i, ptr) in [record.lhs_ptr, record.rhs_ptr].into_iter().enumerate() {
+            record.input[i * POSEIDON2_CHUNK_BYTES..(i + 1) * POSEIDON2_CHUNK_BYTES]
+                .copy_from_slice(&tracing_read::<POSEIDON2_CHUNK_BYTES>(
+                    state.memory,
+                    RV32_MEMORY_AS,
+                    ptr,
+                    &mut record.input_aux[i].prev_timestamp,
+                ));
+        }
+
+        let output = poseidon2_permute_bytes(&self.subchip, &record.input);
+        let num_writes = if is_compress { 1 } else { 2 };
+        for (i, chunk) in output
+            .chunks_exact(POSEIDON2_CHUNK_BYTES)
+            .take(num_writes)
+            .enumerate()
+        {
+            tracing_write::<POSEIDON2_CHUNK_BYTES>(
+                state.memory,
+                RV32_MEMORY_AS,
+                record.dst_ptr + (i * POSEIDON2_CHUNK_BYTES) as u32,
+                chunk.try_into().unwrap(),
+                &mut record.output_aux[i].prev_timestamp,
+                &mut record.output_aux[i].prev_data,
+            );
+        }
+        if is_compress {
+            state.memory.increment_timestamp();
+        }
+
+        *state.pc = state.pc.wrapping_add(DEFAULT_PC_STEP);
+        Ok(())
+    }
+}
+
+impl<F: PrimeField32> TraceFiller<F> for Poseidon2VmFiller<F> {
+    fn fill_trace_row(&self, mem_helper: &MemoryAuxColsFactory<F>, mut row_slice: &mut [F]) {
+        // SAFETY: row_slice is guaranteed by the caller to contain a valid Poseidon2VmRecord
+        //         written by the executor during trace generation
+        let record: &Poseidon2VmRecord = unsafe { get_record_from_slice(&mut row_slice, ()) };
+        // Copy the record, since the Poseidon2 sub-AIR columns overwrite it
+        let record = record.clone();
+
+        let input: [F; POSEIDON2_WIDTH] =
+            from_fn(|i| element_from_bytes(&record.input[i * 4..(i + 1) * 4]));
+        let inner_width = self.subchip.air.width();
+        let inner_trace = self.subchip.generate_trace(vec![input]);
+        // Safety: `Poseidon2SubCols` **must** be the first field in `Poseidon2VmCols`
+        row_slice[..inner_width].copy_from_slice(&inner_trace.values);
+
+        let is_compress = record.is_compress;
+        let num_chunks = if is_compress { 1 } else { 2 };
+        let output = poseidon2_permute_bytes(&self.subchip, &record.input);
+        let output_elements = num_chunks * POSEIDON2_CHUNK;
+
+        // Range check the most significant limbs of the pointers, and the output bytes. These
+        // must match the sends in `Poseidon2VmAir`.
+        let msl_rshift = RV32_CELL_BITS * (RV32_REGISTER_NUM_LIMBS - 1);
+        let msl_lshift = RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.pointer_max_bits;
+        self.bitwise_lookup_chip.request_range(
+            (record.dst_ptr >> msl_rshift) << msl_lshift,
+            (record.lhs_ptr >> msl_rshift) << msl_lshift,
+        );
+        if is_compress {
+            self.bitwise_lookup_chip.request_range(
+                (record.rhs_ptr >> msl_rshift) << msl_lshift,
+                (record.rhs_ptr >> msl_rshift) << msl_lshift,
+            );
+        }
+        for chunk in output.chunks_exact(POSEIDON2_CHUNK_BYTES).take(num_chunks) {
+            for pair in chunk.chunks_exact(2) {
+                self.bitwise_lookup_chip
+                    .request_range(pair[0] as u32, pair[1] as u32);
+            }
+            for pair in chunk.chunks_exact(8) {
+                self.bitwise_lookup_chip.request_range(
+                    BABY_BEAR_MAX_HI_BYTE - pair[3] as u32,
+                    BABY_BEAR_MAX_HI_BYTE - pair[7] as u32,
+                );
+            }
+        }
+
+        let cols: &mut Poseidon2VmCols<F> = row_slice.borrow_mut();
+        cols.from_state = ExecutionState::new(
+            F::from_canonical_u32(record.from_pc),
+            F::from_canonical_u32(record.timestamp),
+        );
+        cols.is_permute = F::from_bool(!is_compress);
+        cols.is_compress = F::from_bool(is_compress);
+        cols.rd_ptr = F::from_canonical_u32(record.rd_ptr);
+        cols.rs1_ptr = F::from_canonical_u32(record.rs1_ptr);
+        cols.rs2_ptr = F::from_canonical_u32(record.rs2_ptr);
+        cols.dst_ptr = record.dst_ptr.to_le_bytes().map(F::from_canonical_u8);
+        cols.lhs_ptr = record.lhs_ptr.to_le_bytes().map(F::from_canonical_u8);
+        cols.rhs_ptr = record.rhs_ptr.to_le_bytes().map(F::from_canonical_u8);
+
+        for (i, bytes) in cols.input_bytes.iter_mut().enumerate() {
+            *bytes = from_fn(|j| F::from_canonical_u8(record.input[i * 4 + j]));
+        }
+        for (i, (bytes, hi_diff_inv)) in cols
+            .output_bytes
+            .iter_mut()
+            .zip(cols.output_hi_diff_inv.iter_mut())
+            .enumerate()
+        {
+            if i < output_elements {
+                *bytes = from_fn(|j| F::from_canonical_u8(output[i * 4 + j]));
+                *hi_diff_inv =
+                    F::from_canonical_u32(BABY_BEAR_MAX_HI_BYTE - output[i * 4 + 3] as u32)
+                        .try_inverse()
+                        .unwrap_or(F::ZERO);
+            } else {
+                *bytes = [F::ZERO; 4];
+                *hi_diff_inv = F::ZERO;
+            }
+        }
+
+        let mut timestamp = record.timestamp;
+        for (i, (aux_cols, aux)) in cols
+            .register_aux
+            .iter_mut()
+            .zip(record.register_aux.iter())
+            .enumerate()
+        {
+            if i < 2 || is_compress {
+                mem_helper.fill(aux.prev_timestamp, timestamp, aux_cols.as_mut());
+            } else {
+                mem_helper.fill_zero(aux_cols.as_mut());
+            }
+            timestamp += 1;
+        }
+        for (aux_cols, aux) in cols.input_aux.iter_mut().zip(record.input_aux.iter()) {
+            mem_helper.fill(aux.prev_timestamp, timestamp, aux_cols.as_mut());
+            timestamp += 1;
+        }
+        for (i, (aux_cols, aux)) in cols
+            .output_aux
+            .iter_mut()
+            .zip(record.output_aux.iter())
+            .enumerate()
+        {
+            if i < num_chunks {
+                aux_cols.set_prev_data(aux.prev_data.map(F::from_canonical_u8));
+                mem_helper.fill(aux.prev_timestamp, timestamp, aux_cols.as_mut());
+            } else {
+                aux_cols.set_prev_data([F::ZERO; POSEIDON2_CHUNK_BYTES]);
+                mem_helper.fill_zero(aux_cols.as_mut());
+            }
+            timestamp += 1;
+        }
+    }
+
+    fn fill_dummy_trace_row(&self, row_slice: &mut [F]) {
+        let inner_width = self.subchip.air.width();
+        row_slice[..inner_width].copy_from_slice(&self.empty_poseidon2_sub_cols);
+        row_slice[inner_width..].fill(F::ZERO);
+    }
+}
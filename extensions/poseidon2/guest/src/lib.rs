@@ -0,0 +1,51 @@
+#![no_std]
+
+/// This is custom-0 defined in RISC-V spec document
+pub const OPCODE: u8 = 0x0b;
+pub const POSEIDON2_FUNCT3: u8 = 0b100;
+pub const POSEIDON2_PERMUTE_FUNCT7: u8 = 0x6;
+pub const POSEIDON2_COMPRESS_FUNCT7: u8 = 0x7;
+
+/// Number of BabyBear elements in the Poseidon2 state
+pub const POSEIDON2_WIDTH: usize = 16;
+/// Number of BabyBear elements in each input and in the output of the Poseidon2 compression
+/// function
+pub const POSEIDON2_CHUNK: usize = 8;
+
+/// Poseidon2 permutation intrinsic over BabyBear, applied in place.
+///
+/// Each element of the state is stored as a little-endian `u32`. Input elements are reduced
+/// modulo the BabyBear prime, and output elements are always canonical.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+pub fn native_poseidon2_permute(state: &mut [u32; POSEIDON2_WIDTH]) {
+    // `[u32; 16]` is 4-byte aligned, so the state satisfies the alignment required by the VM.
+    openvm_platform::custom_insn_r!(
+        opcode = OPCODE,
+        funct3 = POSEIDON2_FUNCT3,
+        funct7 = POSEIDON2_PERMUTE_FUNCT7,
+        rd = In state.as_mut_ptr(),
+        rs1 = In state.as_ptr(),
+        rs2 = Const "x0"
+    );
+}
+
+/// Poseidon2 compression intrinsic over BabyBear. Writes the first [POSEIDON2_CHUNK] elements of
+/// the permutation of `lhs || rhs` to `output`, in the same encoding as
+/// [native_poseidon2_permute].
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+pub fn native_poseidon2_compress(
+    lhs: &[u32; POSEIDON2_CHUNK],
+    rhs: &[u32; POSEIDON2_CHUNK],
+    output: &mut [u32; POSEIDON2_CHUNK],
+) {
+    openvm_platform::custom_insn_r!(
+        opcode = OPCODE,
+        funct3 = POSEIDON2_FUNCT3,
+        funct7 = POSEIDON2_COMPRESS_FUNCT7,
+        rd = In output.as_mut_ptr(),
+        rs1 = In lhs.as_ptr(),
+        rs2 = In rhs.as_ptr()
+    );
+}
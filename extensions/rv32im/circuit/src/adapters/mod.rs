@@ -1,7 +1,10 @@
 use std::ops::Mul;
 
 use openvm_circuit::{
-    arch::{execution_mode::ExecutionCtxTrait, VmStateMut},
+    arch::{
+        execution_mode::{ExecutionCtxTrait, MemoryAccessKind},
+        VmStateMut,
+    },
     system::memory::{
         merkle::public_values::PUBLIC_VALUES_AS,
         online::{GuestMemory, TracingMemory},
@@ -209,9 +212,11 @@ pub fn memory_read_from_state<F, Ctx, const N: usize>(
 where
     Ctx: ExecutionCtxTrait,
 {
-    state.ctx.on_memory_operation(address_space, ptr, N as u32);
-
-    memory_read(state.memory, address_space, ptr)
+    let data = memory_read(state.memory, address_space, ptr);
+    state
+        .ctx
+        .on_memory_access(MemoryAccessKind::Read, address_space, ptr, &data);
+    data
 }
 
 #[inline(always)]
@@ -223,7 +228,9 @@ pub fn memory_write_from_state<F, Ctx, const N: usize>(
 ) where
     Ctx: ExecutionCtxTrait,
 {
-    state.ctx.on_memory_operation(address_space, ptr, N as u32);
+    state
+        .ctx
+        .on_memory_access(MemoryAccessKind::Write, address_space, ptr, &data);
 
     memory_write(state.memory, address_space, ptr, data)
 }
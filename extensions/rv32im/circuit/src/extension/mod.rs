@@ -196,6 +196,10 @@ impl<F: PrimeField32> VmExecutionExtension<F> for Rv32I {
             phantom::Rv32HintLoadByKeySubEx,
             PhantomDiscriminant(Rv32Phantom::HintLoadByKey as u16),
         )?;
+        inventory.add_phantom_sub_executor(
+            phantom::Rv32HintReadSubEx,
+            PhantomDiscriminant(Rv32Phantom::HintRead as u16),
+        )?;
 
         Ok(())
     }
@@ -718,12 +722,12 @@ where
 mod phantom {
     use eyre::bail;
     use openvm_circuit::{
-        arch::{PhantomSubExecutor, Streams},
+        arch::{PhantomSubExecutor, Streams, VmRng},
         system::memory::online::GuestMemory,
     };
     use openvm_instructions::PhantomDiscriminant;
     use openvm_stark_backend::p3_field::{Field, PrimeField32};
-    use rand::{rngs::StdRng, Rng};
+    use rand::Rng;
 
     use crate::adapters::{memory_read, read_rv32_register};
 
@@ -731,13 +735,14 @@ mod phantom {
     pub struct Rv32HintRandomSubEx;
     pub struct Rv32PrintStrSubEx;
     pub struct Rv32HintLoadByKeySubEx;
+    pub struct Rv32HintReadSubEx;
 
     impl<F: Field> PhantomSubExecutor<F> for Rv32HintInputSubEx {
         fn phantom_execute(
             &self,
             _: &GuestMemory,
             streams: &mut Streams<F>,
-            _: &mut StdRng,
+            _: &mut VmRng,
             _: PhantomDiscriminant,
             _: u32,
             _: u32,
@@ -769,7 +774,7 @@ mod phantom {
             &self,
             memory: &GuestMemory,
             streams: &mut Streams<F>,
-            rng: &mut StdRng,
+            rng: &mut VmRng,
             _: PhantomDiscriminant,
             a: u32,
             _: u32,
@@ -794,7 +799,7 @@ mod phantom {
             &self,
             memory: &GuestMemory,
             _: &mut Streams<F>,
-            _: &mut StdRng,
+            _: &mut VmRng,
             _: PhantomDiscriminant,
             a: u32,
             b: u32,
@@ -816,7 +821,7 @@ mod phantom {
             &self,
             memory: &GuestMemory,
             streams: &mut Streams<F>,
-            _: &mut StdRng,
+            _: &mut VmRng,
             _: PhantomDiscriminant,
             a: u32,
             b: u32,
@@ -827,8 +832,8 @@ mod phantom {
             let key: Vec<u8> = (0..len)
                 .map(|i| memory_read::<1>(memory, 2, ptr + i)[0])
                 .collect();
-            if let Some(val) = streams.kv_store.get(&key) {
-                let to_push = hint_load_by_key_decode::<F>(val);
+            if let Some(val) = streams.kv_store.load(&key) {
+                let to_push = hint_load_by_key_decode::<F>(&val);
                 for input in to_push.into_iter().rev() {
                     streams.input_stream.push_front(input);
                 }
@@ -839,6 +844,42 @@ mod phantom {
         }
     }
 
+    impl<F: Field> PhantomSubExecutor<F> for Rv32HintReadSubEx {
+        fn phantom_execute(
+            &self,
+            memory: &GuestMemory,
+            streams: &mut Streams<F>,
+            _: &mut VmRng,
+            _: PhantomDiscriminant,
+            a: u32,
+            b: u32,
+            _: u16,
+        ) -> eyre::Result<()> {
+            let fd = read_rv32_register(memory, a);
+            let max_len = read_rv32_register(memory, b) as usize;
+            // A file descriptor without a buffer is at end of file
+            let mut hint: Vec<F> = match streams.fd_buffers.get_mut(&fd) {
+                Some(buffer) => {
+                    let len = max_len.min(buffer.len());
+                    buffer.drain(..len).map(F::from_canonical_u8).collect()
+                }
+                None => Vec::new(),
+            };
+            streams.hint_stream.clear();
+            streams.hint_stream.extend(
+                (hint.len() as u32)
+                    .to_le_bytes()
+                    .iter()
+                    .map(|b| F::from_canonical_u8(*b)),
+            );
+            // Extend by 0 for 4 byte alignment
+            let capacity = hint.len().div_ceil(4) * 4;
+            hint.resize(capacity, F::ZERO);
+            streams.hint_stream.extend(hint);
+            Ok(())
+        }
+    }
+
     pub fn hint_load_by_key_decode<F: PrimeField32>(value: &[u8]) -> Vec<Vec<F>> {
         let mut offset = 0;
         let len = extract_u32(value, offset) as usize;
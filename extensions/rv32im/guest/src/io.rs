@@ -67,6 +67,32 @@ pub fn hint_load_by_key(ptr: *const u8, len: u32) {
     );
 }
 
+/// Reset the hint stream with up to `len` bytes read from the host buffer of file descriptor `fd`,
+/// prepended with a 4-byte decomposition of the number of bytes read.
+#[inline(always)]
+pub fn hint_read(fd: u32, len: usize) {
+    openvm_custom_insn::custom_insn_i!(
+        opcode = SYSTEM_OPCODE,
+        funct3 = PHANTOM_FUNCT3,
+        rd = In fd,
+        rs1 = In len,
+        imm = Const PhantomImm::HintRead as u16,
+    );
+}
+
+/// Reset the hint stream with the 8-byte decomposition of the number of instructions executed
+/// before this one. The value is unconstrained.
+#[inline(always)]
+pub fn hint_instret() {
+    openvm_custom_insn::custom_insn_i!(
+        opcode = SYSTEM_OPCODE,
+        funct3 = PHANTOM_FUNCT3,
+        rd = Const "x0",
+        rs1 = Const "x0",
+        imm = Const PhantomImm::HintInstret as u16,
+    );
+}
+
 /// Store rs1 to [[rd] + imm]_3.
 #[macro_export]
 macro_rules! reveal {
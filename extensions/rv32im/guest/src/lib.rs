@@ -33,6 +33,8 @@ pub enum PhantomImm {
     PrintStr,
     HintRandom,
     HintLoadByKey,
+    HintRead,
+    HintInstret,
 }
 
 /// Encode a 2d-array of field elements into bytes for `hint_load_by_key`
@@ -0,0 +1,20 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use openvm::io::cycle_count;
+
+openvm::entry!(main);
+
+pub fn main() {
+    let start = cycle_count();
+    let mut x: u32 = core::hint::black_box(0);
+    for i in 0..100 {
+        x = x.wrapping_add(i);
+    }
+    core::hint::black_box(x);
+    let end = cycle_count();
+    // Each loop iteration executes at least one instruction
+    if start == 0 || end < start + 100 {
+        openvm::process::panic();
+    }
+}
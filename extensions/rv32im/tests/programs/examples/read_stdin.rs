@@ -0,0 +1,16 @@
+use std::io::Read;
+
+openvm::entry!(main);
+
+fn main() {
+    let mut stdin = std::io::stdin();
+    // Read a few bytes first so the rest is read into an unaligned position
+    let mut head = [0u8; 3];
+    stdin.read_exact(&mut head).unwrap();
+    let mut data = head.to_vec();
+    stdin.read_to_end(&mut data).unwrap();
+    assert_eq!(data.len(), 1001);
+    for (i, byte) in data.iter().enumerate() {
+        assert_eq!(*byte, (i % 251) as u8);
+    }
+}
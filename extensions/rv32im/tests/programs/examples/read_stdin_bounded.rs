@@ -0,0 +1,27 @@
+use openvm::{
+    pal_abi::{sys_read, sys_read_words},
+    platform::fileno::STDIN,
+};
+
+openvm::entry!(main);
+
+const CANARY: u8 = 0xaa;
+
+fn main() {
+    // The host buffer holds more bytes than each read asks for, and nothing may be written past
+    // the requested length
+    let mut buf = [CANARY; 16];
+    let nread = unsafe { sys_read(STDIN, buf.as_mut_ptr(), 5) };
+    assert_eq!(nread, 5);
+    for (i, byte) in buf.iter().enumerate() {
+        assert_eq!(*byte, if i < 5 { i as u8 } else { CANARY });
+    }
+
+    let mut words = [u32::from_le_bytes([CANARY; 4]); 4];
+    let nread = unsafe { sys_read_words(STDIN, words.as_mut_ptr(), 1) };
+    assert_eq!(nread, 4);
+    assert_eq!(words[0].to_le_bytes(), [5, 6, 7, 8]);
+    for word in &words[1..] {
+        assert_eq!(word.to_le_bytes(), [CANARY; 4]);
+    }
+}
@@ -1,14 +1,19 @@
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashMap, sync::Arc};
+    use std::{collections::HashMap, convert::Infallible, sync::Arc};
 
     use eyre::Result;
     use openvm_circuit::{
-        arch::{hasher::poseidon2::vm_poseidon2_hasher, ExecutionError, Streams, VmExecutor},
+        arch::{
+            execution_mode::MemoryAccessKind, hasher::poseidon2::vm_poseidon2_hasher,
+            ExecutionError, Streams, VmExecutor,
+        },
         system::memory::merkle::public_values::UserPublicValuesProof,
         utils::{air_test, air_test_with_min_segments, test_system_config},
     };
-    use openvm_instructions::{exe::VmExe, instruction::Instruction, LocalOpcode, SystemOpcode};
+    use openvm_instructions::{
+        exe::VmExe, instruction::Instruction, riscv::RV32_REGISTER_AS, LocalOpcode, SystemOpcode,
+    };
     use openvm_rv32im_circuit::{Rv32IBuilder, Rv32IConfig, Rv32ImBuilder, Rv32ImConfig};
     use openvm_rv32im_guest::hint_load_by_key_encode;
     use openvm_rv32im_transpiler::{
@@ -55,6 +60,7 @@ mod tests {
 
     #[test_case("fibonacci", 1)]
     #[test_case("collatz", 1)]
+    #[test_case("cycle_count", 1)]
     fn test_rv32im(example_name: &str, min_segments: usize) -> Result<()> {
         let config = test_rv32im_config();
         let elf = build_example_program_at_path(get_programs_dir!(), example_name, &config)?;
@@ -71,6 +77,7 @@ mod tests {
 
     #[test_case("fibonacci", 1)]
     #[test_case("collatz", 1)]
+    #[test_case("cycle_count", 1)]
     fn test_rv32im_std(example_name: &str, min_segments: usize) -> Result<()> {
         let config = test_rv32im_config();
         let elf = build_example_program_at_path_with_features(
@@ -106,6 +113,31 @@ mod tests {
         Ok(())
     }
 
+    #[test_case("read_stdin")]
+    #[test_case("read_stdin_bounded")]
+    fn test_read_stdin(example_name: &str) -> Result<()> {
+        let config = test_rv32im_config();
+        let elf = build_example_program_at_path_with_features(
+            get_programs_dir!(),
+            example_name,
+            ["std"],
+            &config,
+        )?;
+        let exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension),
+        )?;
+        let mut streams = Streams::<F>::default();
+        streams
+            .fd_buffers
+            .insert(0, (0..1001).map(|i| (i % 251) as u8).collect());
+        air_test_with_min_segments(Rv32ImBuilder, config, exe, streams, 1);
+        Ok(())
+    }
+
     #[test]
     fn test_hint_load_by_key() -> Result<()> {
         let config = test_rv32im_config();
@@ -217,6 +249,108 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_execute_trace() -> Result<()> {
+        let config = test_rv32im_config();
+        let elf = build_example_program_at_path(get_programs_dir!(), "fibonacci", &config)?;
+        let exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension),
+        )?;
+
+        let executor = VmExecutor::new(config)?;
+        let final_state = executor.instance(&exe)?.execute(vec![], None)?;
+        let mut steps = Vec::new();
+        let state = executor
+            .trace_instance(&exe)?
+            .execute_trace(vec![], None, |step| {
+                steps.push(step);
+                Ok::<_, Infallible>(())
+            })??;
+        assert_eq!(state.instret(), final_state.instret());
+        assert_eq!(steps.len() as u64, state.instret());
+        assert_eq!(steps[0].pc, exe.pc_start);
+        for (i, step) in steps.iter().enumerate() {
+            assert_eq!(step.instret, i as u64);
+            assert!(step.register_writes.iter().all(|&(reg, _)| reg != 0));
+        }
+        assert!(steps.iter().any(|step| !step.register_writes.is_empty()));
+        assert!(steps.iter().any(|step| !step.memory_accesses.is_empty()));
+        for kind in [MemoryAccessKind::Read, MemoryAccessKind::Write] {
+            assert!(steps
+                .iter()
+                .flat_map(|step| &step.memory_accesses)
+                .any(|access| access.kind == kind));
+        }
+        // A changed register has the value of the last write to it in the same step
+        for step in &steps {
+            for &(reg, value) in &step.register_writes {
+                let last_write = step.memory_accesses.iter().rev().find(|access| {
+                    access.kind == MemoryAccessKind::Write
+                        && access.address_space == RV32_REGISTER_AS
+                        && access.ptr == 4 * reg as u32
+                });
+                if let Some(access) = last_write {
+                    assert_eq!(access.values, value.to_le_bytes().map(u32::from));
+                }
+            }
+        }
+
+        let mut num_steps = 0;
+        let state = executor
+            .trace_instance(&exe)?
+            .execute_trace(vec![], Some(10), |_| {
+                num_steps += 1;
+                Ok::<_, Infallible>(())
+            })??;
+        assert_eq!(state.instret(), 10);
+        assert_eq!(num_steps, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_profile() -> Result<()> {
+        use openvm_circuit::arch::execution_mode::ProfileCtx;
+        use openvm_instructions::exe::{FnBound, FnBounds};
+
+        let config = test_rv32im_config();
+        let elf = build_example_program_at_path(get_programs_dir!(), "fibonacci", &config)?;
+        let exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension),
+        )?;
+        let program = &exe.program;
+        let start = program.pc_base;
+        let end = start + 4 * (program.len() as u32 - 1);
+        let fn_bounds = FnBounds::from([(
+            start,
+            FnBound {
+                start,
+                end,
+                name: "program".to_string(),
+            },
+        )]);
+
+        let executor = VmExecutor::new(config)?;
+        let final_state = executor.instance(&exe)?.execute(vec![], None)?;
+        let (profile, state) = executor
+            .profile_instance(&exe)?
+            .execute_profile(vec![], ProfileCtx::new(fn_bounds))?;
+        assert_eq!(state.instret(), final_state.instret());
+        let total: u64 = profile.nodes.iter().map(|node| node.instructions).sum();
+        assert_eq!(total, state.instret());
+        // Execution starts inside the function, which is treated as a call from the root
+        assert_eq!(profile.nodes[0].instructions, 0);
+        assert_eq!(profile.stack_names(1), vec!["program"]);
+        Ok(())
+    }
+
     #[test]
     fn test_heap_overflow() -> Result<()> {
         let config = test_rv32im_config();
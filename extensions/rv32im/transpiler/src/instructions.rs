@@ -281,4 +281,7 @@ pub enum Rv32Phantom {
     HintRandom,
     /// Hint the VM to load values from the stream KV store into input streams.
     HintLoadByKey,
+    /// Prepare bytes read from the host buffer of a file descriptor for hinting, prepended with a
+    /// 4-byte decomposition of the number of bytes read.
+    HintRead,
 }
@@ -2,7 +2,7 @@ use std::marker::PhantomData;
 
 use openvm_instructions::{
     instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, LocalOpcode, PhantomDiscriminant,
-    SystemOpcode,
+    SysPhantom, SystemOpcode, VmOpcode,
 };
 use openvm_rv32im_guest::{
     PhantomImm, CSRRW_FUNCT3, CSR_OPCODE, HINT_BUFFER_IMM, HINT_FUNCT3, HINT_STOREW_IMM,
@@ -11,7 +11,7 @@ use openvm_rv32im_guest::{
 };
 use openvm_stark_backend::p3_field::PrimeField32;
 use openvm_transpiler::{
-    util::{nop, unimp},
+    util::{local_opcode_name, nop, unimp},
     TranspilerExtension, TranspilerOutput,
 };
 use rrs::InstructionTranspiler;
@@ -19,6 +19,7 @@ use rrs_lib::{
     instruction_formats::{IType, RType},
     process_instruction,
 };
+use strum::IntoEnumIterator;
 
 mod instructions;
 pub mod rrs;
@@ -98,6 +99,18 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32ITranspilerExtension {
                         F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
                         0,
                     ),
+                    PhantomImm::HintRead => Instruction::phantom(
+                        PhantomDiscriminant(Rv32Phantom::HintRead as u16),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rd),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
+                        0,
+                    ),
+                    PhantomImm::HintInstret => Instruction::phantom(
+                        PhantomDiscriminant(SysPhantom::HintInstret as u16),
+                        F::ZERO,
+                        F::ZERO,
+                        0,
+                    ),
                 })
             }
             (RV32_ALU_OPCODE, _) => {
@@ -114,6 +127,22 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32ITranspilerExtension {
 
         instruction.map(TranspilerOutput::one_to_one)
     }
+
+    fn opcode_name(&self, opcode: VmOpcode) -> Option<String> {
+        local_opcode_name(opcode, BaseAluOpcode::iter(), 1)
+            .or_else(|| local_opcode_name(opcode, ShiftOpcode::iter(), 1))
+            .or_else(|| local_opcode_name(opcode, LessThanOpcode::iter(), 1))
+            .or_else(|| local_opcode_name(opcode, Rv32LoadStoreOpcode::iter(), 1))
+            .or_else(|| local_opcode_name(opcode, BranchEqualOpcode::iter(), 1))
+            .or_else(|| local_opcode_name(opcode, BranchLessThanOpcode::iter(), 1))
+            .or_else(|| local_opcode_name(opcode, Rv32JalLuiOpcode::iter(), 1))
+            .or_else(|| local_opcode_name(opcode, Rv32JalrOpcode::iter(), 1))
+            .or_else(|| local_opcode_name(opcode, Rv32AuipcOpcode::iter(), 1))
+    }
+
+    fn phantom_name(&self, discriminant: PhantomDiscriminant) -> Option<String> {
+        Rv32Phantom::from_repr(discriminant.0).map(|phantom| format!("Rv32Phantom::{phantom:?}"))
+    }
 }
 
 impl<F: PrimeField32> TranspilerExtension<F> for Rv32MTranspilerExtension {
@@ -141,6 +170,12 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32MTranspilerExtension {
 
         instruction.map(TranspilerOutput::one_to_one)
     }
+
+    fn opcode_name(&self, opcode: VmOpcode) -> Option<String> {
+        local_opcode_name(opcode, MulOpcode::iter(), 1)
+            .or_else(|| local_opcode_name(opcode, MulHOpcode::iter(), 1))
+            .or_else(|| local_opcode_name(opcode, DivRemOpcode::iter(), 1))
+    }
 }
 
 impl<F: PrimeField32> TranspilerExtension<F> for Rv32IoTranspilerExtension {
@@ -218,4 +253,8 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32IoTranspilerExtension {
 
         instruction.map(TranspilerOutput::one_to_one)
     }
+
+    fn opcode_name(&self, opcode: VmOpcode) -> Option<String> {
+        local_opcode_name(opcode, Rv32HintStoreOpcode::iter(), 1)
+    }
 }
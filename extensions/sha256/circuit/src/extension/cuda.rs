@@ -1,11 +1,47 @@
 use openvm_circuit::{
-    arch::DenseRecordArena,
+    arch::{Arena, DenseRecordArena, MatrixRecordArena},
     system::cuda::extensions::{get_inventory_range_checker, get_or_create_bitwise_op_lookup},
 };
-use openvm_cuda_backend::{engine::GpuBabyBearPoseidon2Engine, prover_backend::GpuBackend};
+use openvm_cuda_backend::{
+    chip::{cpu_proving_ctx_to_gpu, get_empty_air_proving_ctx},
+    engine::GpuBabyBearPoseidon2Engine,
+    prover_backend::GpuBackend,
+    types::F,
+};
+use openvm_sha256_air::SHA256_ROWS_PER_BLOCK;
+use openvm_stark_backend::{prover::types::AirProvingContext, Chip};
 use openvm_stark_sdk::config::baby_bear_poseidon2::BabyBearPoseidon2Config;
 
 use super::*;
+use crate::sha256_compress_chip::{Sha256CompressVmRecord, Sha256CompressVmRecordLayout};
+
+/// Sha256 compression chip for the GPU backend which still does trace generation on CPU.
+#[derive(derive_new::new)]
+pub struct HybridSha256CompressChip {
+    cpu: Sha256CompressVmChip<F>,
+}
+
+// Conversion of Dense->Matrix Record Arena, cpu tracegen, and then H2D transfer of the trace
+// matrix.
+impl Chip<DenseRecordArena, GpuBackend> for HybridSha256CompressChip {
+    fn generate_proving_ctx(&self, mut arena: DenseRecordArena) -> AirProvingContext<GpuBackend> {
+        let records = arena.allocated();
+        if records.is_empty() {
+            return get_empty_air_proving_ctx::<GpuBackend>();
+        }
+        debug_assert_eq!(records.len() % size_of::<Sha256CompressVmRecord>(), 0);
+
+        let num_records = records.len() / size_of::<Sha256CompressVmRecord>();
+        let height = (num_records * SHA256_ROWS_PER_BLOCK).next_power_of_two();
+        let mut matrix_arena =
+            MatrixRecordArena::<F>::with_capacity(height, SHA256_COMPRESS_VM_WIDTH);
+        arena
+            .get_record_seeker::<&mut Sha256CompressVmRecord, Sha256CompressVmRecordLayout>()
+            .transfer_to_matrix_arena(&mut matrix_arena);
+        let ctx = self.cpu.generate_proving_ctx(matrix_arena);
+        cpu_proving_ctx_to_gpu(ctx)
+    }
+}
 
 pub struct Sha256GpuProverExt;
 
@@ -28,12 +64,23 @@ impl VmProverExtension<GpuBabyBearPoseidon2Engine, DenseRecordArena, Sha256>
         inventory.next_air::<Sha256VmAir>()?;
         let sha256 = Sha256VmChipGpu::new(
             range_checker.clone(),
-            bitwise_lu,
+            bitwise_lu.clone(),
             pointer_max_bits as u32,
             timestamp_max_bits as u32,
         );
         inventory.add_executor_chip(sha256);
 
+        // Sha256 compression trace generation is done on CPU, with the CPU counterparts of the
+        // lookup chips
+        inventory.next_air::<Sha256CompressVmAir>()?;
+        let mem_helper =
+            SharedMemoryHelper::new(range_checker.cpu_chip.clone().unwrap(), timestamp_max_bits);
+        let sha256_compress = Sha256CompressVmChip::new(
+            Sha256CompressVmFiller::new(bitwise_lu.cpu_chip.clone().unwrap(), pointer_max_bits),
+            mem_helper,
+        );
+        inventory.add_executor_chip(HybridSha256CompressChip::new(sha256_compress));
+
         Ok(())
     }
 }
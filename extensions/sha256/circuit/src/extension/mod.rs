@@ -15,7 +15,7 @@ use openvm_circuit_primitives::bitwise_op_lookup::{
     SharedBitwiseOperationLookupChip,
 };
 use openvm_instructions::*;
-use openvm_sha256_transpiler::Rv32Sha256Opcode;
+use openvm_sha256_transpiler::{Rv32Sha256CompressOpcode, Rv32Sha256Opcode};
 use openvm_stark_backend::{
     config::{StarkGenericConfig, Val},
     p3_field::PrimeField32,
@@ -48,6 +48,7 @@ pub struct Sha256;
 #[derive(Clone, From, AnyEnum, Executor, MeteredExecutor, PreflightExecutor)]
 pub enum Sha256Executor {
     Sha256(Sha256VmExecutor),
+    Sha256Compress(Sha256CompressVmExecutor),
 }
 
 impl<F> VmExecutionExtension<F> for Sha256 {
@@ -64,6 +65,13 @@ impl<F> VmExecutionExtension<F> for Sha256 {
             Rv32Sha256Opcode::iter().map(|x| x.global_opcode()),
         )?;
 
+        let sha256_compress_step =
+            Sha256CompressVmExecutor::new(Rv32Sha256CompressOpcode::CLASS_OFFSET, pointer_max_bits);
+        inventory.add_executor(
+            sha256_compress_step,
+            Rv32Sha256CompressOpcode::iter().map(|x| x.global_opcode()),
+        )?;
+
         Ok(())
     }
 }
@@ -92,6 +100,14 @@ impl<SC: StarkGenericConfig> VmCircuitExtension<SC> for Sha256 {
         );
         inventory.add_air(sha256);
 
+        let sha256_compress = Sha256CompressVmAir::new(
+            inventory.system().port(),
+            bitwise_lu,
+            pointer_max_bits,
+            inventory.new_bus_idx(),
+        );
+        inventory.add_air(sha256_compress);
+
         Ok(())
     }
 }
@@ -132,11 +148,18 @@ where
 
         inventory.next_air::<Sha256VmAir>()?;
         let sha256 = Sha256VmChip::new(
-            Sha256VmFiller::new(bitwise_lu, pointer_max_bits),
-            mem_helper,
+            Sha256VmFiller::new(bitwise_lu.clone(), pointer_max_bits),
+            mem_helper.clone(),
         );
         inventory.add_executor_chip(sha256);
 
+        inventory.next_air::<Sha256CompressVmAir>()?;
+        let sha256_compress = Sha256CompressVmChip::new(
+            Sha256CompressVmFiller::new(bitwise_lu, pointer_max_bits),
+            mem_helper,
+        );
+        inventory.add_executor_chip(sha256_compress);
+
         Ok(())
     }
 }
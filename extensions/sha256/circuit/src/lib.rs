@@ -26,6 +26,9 @@ use serde::{Deserialize, Serialize};
 mod sha256_chip;
 pub use sha256_chip::*;
 
+mod sha256_compress_chip;
+pub use sha256_compress_chip::*;
+
 mod extension;
 pub use extension::*;
 
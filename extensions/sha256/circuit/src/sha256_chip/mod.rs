@@ -33,9 +33,9 @@ mod tests;
 
 // ==== Constants for register/memory adapter ====
 /// Register reads to get dst, src, len
-const SHA256_REGISTER_READS: usize = 3;
+pub(crate) const SHA256_REGISTER_READS: usize = 3;
 /// Number of cells to read in a single memory access
-const SHA256_READ_SIZE: usize = 16;
+pub(crate) const SHA256_READ_SIZE: usize = 16;
 /// Number of cells to write in a single memory access
 const SHA256_WRITE_SIZE: usize = 32;
 /// Number of rv32 cells read in a SHA256 block
@@ -349,9 +349,7 @@ impl<F: PrimeField32> TraceFiller<F> for Sha256VmFiller {
                 for i in 0..*num_blocks - 1 {
                     prev_hashes.push(Sha256FillerHelper::get_block_hash(
                         &prev_hashes[i],
-                        padded_input[i * SHA256_BLOCK_CELLS..(i + 1) * SHA256_BLOCK_CELLS]
-                            .try_into()
-                            .unwrap(),
+                        &padded_input[i * SHA256_BLOCK_CELLS..(i + 1) * SHA256_BLOCK_CELLS],
                     ));
                 }
                 // Copy the read aux records and input to another place to safely fill in the trace
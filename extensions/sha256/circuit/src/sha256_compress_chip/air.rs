@@ -0,0 +1,298 @@
+use std::{array, borrow::Borrow};
+
+use openvm_circuit::{
+    arch::ExecutionBridge,
+    system::{
+        memory::{offline_checker::MemoryBridge, MemoryAddress},
+        SystemPort,
+    },
+};
+use openvm_circuit_primitives::{bitwise_op_lookup::BitwiseOperationLookupBus, SubAir};
+use openvm_instructions::{
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+    LocalOpcode,
+};
+use openvm_sha256_air::{compose, Sha256Air, SHA256_HASH_WORDS, SHA256_WORD_U16S, SHA256_WORD_U8S};
+use openvm_sha256_transpiler::Rv32Sha256CompressOpcode;
+use openvm_stark_backend::{
+    interaction::{BusIndex, InteractionBuilder},
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra},
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+
+use super::{
+    Sha256CompressVmDigestCols, Sha256CompressVmRoundCols, SHA256_COMPRESS_TIMESTAMP_DELTA,
+    SHA256_COMPRESS_VM_CONTROL_WIDTH, SHA256_COMPRESS_VM_DIGEST_WIDTH,
+    SHA256_COMPRESS_VM_ROUND_WIDTH, SHA256_COMPRESS_VM_WIDTH, SHA256_STATE_CELLS,
+};
+use crate::{sha256_chip::SHA256_READ_SIZE, SHA256_BLOCK_CELLS, SHA256_NUM_READ_ROWS};
+
+/// Sha256CompressVmAir constrains the memory accesses of the state and the block, and the
+/// Sha256Air subair constrains the actual compression. Every compression is a message of a single
+/// block whose `prev_hash` is the state read from memory.
+#[derive(Clone, Debug)]
+pub struct Sha256CompressVmAir {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    /// Bus to send byte checks to
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    /// Maximum number of bits allowed for an address pointer
+    /// Must be at least 24
+    pub ptr_max_bits: usize,
+    pub(super) sha256_subair: Sha256Air,
+}
+
+impl Sha256CompressVmAir {
+    pub fn new(
+        SystemPort {
+            execution_bus,
+            program_bus,
+            memory_bridge,
+        }: SystemPort,
+        bitwise_lookup_bus: BitwiseOperationLookupBus,
+        ptr_max_bits: usize,
+        self_bus_idx: BusIndex,
+    ) -> Self {
+        Self {
+            execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
+            memory_bridge,
+            bitwise_lookup_bus,
+            ptr_max_bits,
+            sha256_subair: Sha256Air::new_with_free_prev_hash(bitwise_lookup_bus, self_bus_idx),
+        }
+    }
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for Sha256CompressVmAir {}
+impl<F: Field> PartitionedBaseAir<F> for Sha256CompressVmAir {}
+impl<F: Field> BaseAir<F> for Sha256CompressVmAir {
+    fn width(&self) -> usize {
+        SHA256_COMPRESS_VM_WIDTH
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for Sha256CompressVmAir {
+    fn eval(&self, builder: &mut AB) {
+        self.eval_transitions(builder);
+        self.eval_reads(builder);
+        self.eval_digest_row(builder);
+
+        self.sha256_subair
+            .eval(builder, SHA256_COMPRESS_VM_CONTROL_WIDTH);
+    }
+}
+
+impl Sha256CompressVmAir {
+    /// Implement constraints on `read_ptr` and `cur_timestamp`
+    fn eval_transitions<AB: InteractionBuilder>(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local_cols: &Sha256CompressVmRoundCols<AB::Var> =
+            local[..SHA256_COMPRESS_VM_ROUND_WIDTH].borrow();
+        let next_cols: &Sha256CompressVmRoundCols<AB::Var> =
+            next[..SHA256_COMPRESS_VM_ROUND_WIDTH].borrow();
+
+        // Every block is a message of its own, so the control columns only need to be consistent
+        // from the round rows of a block to the next row
+        let is_round_row = local_cols.inner.flags.is_round_row;
+
+        // Read ptr should increment by [SHA256_READ_SIZE] for the first 4 rows and stay the same
+        // otherwise
+        let read_ptr_delta = local_cols.inner.flags.is_first_4_rows
+            * AB::Expr::from_canonical_usize(SHA256_READ_SIZE);
+        builder.when_transition().when(is_round_row).assert_eq(
+            next_cols.control.read_ptr,
+            local_cols.control.read_ptr + read_ptr_delta,
+        );
+
+        // Timestamp should increment by 1 for the first 4 rows and stay the same otherwise
+        let timestamp_delta = local_cols.inner.flags.is_first_4_rows * AB::Expr::ONE;
+        builder.when_transition().when(is_round_row).assert_eq(
+            next_cols.control.cur_timestamp,
+            local_cols.control.cur_timestamp + timestamp_delta,
+        );
+    }
+
+    /// Implement the reads of the block on the first 4 rows and constrain the message schedule to
+    /// be the block
+    fn eval_reads<AB: InteractionBuilder>(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local_cols: &Sha256CompressVmRoundCols<AB::Var> =
+            local[..SHA256_COMPRESS_VM_ROUND_WIDTH].borrow();
+
+        let message: [AB::Var; SHA256_READ_SIZE] = array::from_fn(|i| {
+            local_cols.inner.message_schedule.carry_or_buffer[i / (SHA256_WORD_U16S * 2)]
+                [i % (SHA256_WORD_U16S * 2)]
+        });
+
+        for (i, message_byte) in message.iter().enumerate() {
+            let word = local_cols.inner.message_schedule.w[i / SHA256_WORD_U8S].map(|x| x.into());
+            // Need to reverse the byte order to match the endianness of the memory
+            let byte_idx = SHA256_WORD_U8S - i % SHA256_WORD_U8S - 1;
+            builder
+                .when(local_cols.inner.flags.is_first_4_rows)
+                .assert_eq(
+                    compose::<AB::Expr>(&word[byte_idx * 8..(byte_idx + 1) * 8], 1),
+                    *message_byte,
+                );
+        }
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                    local_cols.control.read_ptr,
+                ),
+                message,
+                local_cols.control.cur_timestamp,
+                &local_cols.read_aux,
+            )
+            .eval(builder, local_cols.inner.flags.is_first_4_rows);
+    }
+
+    /// Implement the constraints for the digest row, which reads the registers and the state and
+    /// writes the new state
+    fn eval_digest_row<AB: InteractionBuilder>(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local_cols: &Sha256CompressVmDigestCols<AB::Var> =
+            local[..SHA256_COMPRESS_VM_DIGEST_WIDTH].borrow();
+
+        let timestamp: AB::Var = local_cols.from_state.timestamp;
+        let mut timestamp_delta: usize = 0;
+        let mut timestamp_pp = || {
+            timestamp_delta += 1;
+            timestamp + AB::Expr::from_canonical_usize(timestamp_delta - 1)
+        };
+
+        let is_digest_row = local_cols.inner.flags.is_digest_row;
+
+        // Every block is the last block of its message, so that its `prev_hash` is free
+        builder
+            .when(is_digest_row)
+            .assert_one(local_cols.inner.flags.is_last_block);
+
+        for (ptr, data, aux) in [
+            (
+                local_cols.rd_ptr,
+                local_cols.dst_ptr,
+                &local_cols.register_reads_aux[0],
+            ),
+            (
+                local_cols.rs1_ptr,
+                local_cols.state_ptr,
+                &local_cols.register_reads_aux[1],
+            ),
+            (
+                local_cols.rs2_ptr,
+                local_cols.block_ptr,
+                &local_cols.register_reads_aux[2],
+            ),
+        ] {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(AB::Expr::from_canonical_u32(RV32_REGISTER_AS), ptr),
+                    data,
+                    timestamp_pp(),
+                    aux,
+                )
+                .eval(builder, is_digest_row);
+        }
+
+        // range check that the memory pointers don't overflow
+        let shift = AB::Expr::from_canonical_usize(
+            1 << (RV32_REGISTER_NUM_LIMBS * RV32_CELL_BITS - self.ptr_max_bits),
+        );
+        // This only works if self.ptr_max_bits >= 24 which is typically the case
+        self.bitwise_lookup_bus
+            .send_range(
+                local_cols.dst_ptr[RV32_REGISTER_NUM_LIMBS - 1] * shift.clone(),
+                local_cols.state_ptr[RV32_REGISTER_NUM_LIMBS - 1] * shift.clone(),
+            )
+            .eval(builder, is_digest_row);
+        self.bitwise_lookup_bus
+            .send_range(
+                local_cols.block_ptr[RV32_REGISTER_NUM_LIMBS - 1] * shift.clone(),
+                local_cols.block_ptr[RV32_REGISTER_NUM_LIMBS - 1] * shift,
+            )
+            .eval(builder, is_digest_row);
+
+        let state_ptr_val =
+            compose::<AB::Expr>(&local_cols.state_ptr.map(|x| x.into()), RV32_CELL_BITS);
+        let prev_state: [AB::Var; SHA256_STATE_CELLS] =
+            array::from_fn(|i| local_cols.prev_state[i / SHA256_WORD_U8S][i % SHA256_WORD_U8S]);
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(AB::Expr::from_canonical_u32(RV32_MEMORY_AS), state_ptr_val),
+                prev_state,
+                timestamp_pp(),
+                &local_cols.state_read_aux,
+            )
+            .eval(builder, is_digest_row);
+
+        // The compression starts from the state read from memory. Since the state words are
+        // stored in little-endian byte order, so are the `u16` limbs of `prev_hash`.
+        for i in 0..SHA256_HASH_WORDS {
+            for j in 0..SHA256_WORD_U16S {
+                builder.when(is_digest_row).assert_eq(
+                    local_cols.inner.prev_hash[i][j],
+                    compose::<AB::Expr>(
+                        &local_cols.prev_state[i][j * 2..(j + 1) * 2],
+                        RV32_CELL_BITS,
+                    ),
+                );
+            }
+        }
+
+        // The block is read on the first 4 rows, right after the state read. Every read
+        // increments the read pointer by [SHA256_READ_SIZE] and the timestamp by 1.
+        let block_ptr_val =
+            compose::<AB::Expr>(&local_cols.block_ptr.map(|x| x.into()), RV32_CELL_BITS);
+        builder.when(is_digest_row).assert_eq(
+            local_cols.control.read_ptr,
+            block_ptr_val + AB::Expr::from_canonical_usize(SHA256_BLOCK_CELLS),
+        );
+        let write_timestamp = timestamp_pp() + AB::Expr::from_canonical_usize(SHA256_NUM_READ_ROWS);
+        builder
+            .when(is_digest_row)
+            .assert_eq(local_cols.control.cur_timestamp, write_timestamp.clone());
+
+        // `final_hash` limbs are bytes in little-endian order, which is the order of the state in
+        // memory
+        let result: [AB::Var; SHA256_STATE_CELLS] = array::from_fn(|i| {
+            local_cols.inner.final_hash[i / SHA256_WORD_U8S][i % SHA256_WORD_U8S]
+        });
+        let dst_ptr_val =
+            compose::<AB::Expr>(&local_cols.dst_ptr.map(|x| x.into()), RV32_CELL_BITS);
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(AB::Expr::from_canonical_u32(RV32_MEMORY_AS), dst_ptr_val),
+                result,
+                write_timestamp,
+                &local_cols.write_aux,
+            )
+            .eval(builder, is_digest_row);
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::Expr::from_canonical_usize(
+                    Rv32Sha256CompressOpcode::SHA256_COMPRESS
+                        .global_opcode()
+                        .as_usize(),
+                ),
+                [
+                    local_cols.rd_ptr.into(),
+                    local_cols.rs1_ptr.into(),
+                    local_cols.rs2_ptr.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                local_cols.from_state,
+                AB::Expr::from_canonical_usize(SHA256_COMPRESS_TIMESTAMP_DELTA),
+            )
+            .eval(builder, is_digest_row);
+    }
+}
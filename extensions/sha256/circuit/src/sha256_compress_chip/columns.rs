@@ -0,0 +1,69 @@
+//! WARNING: the order of fields in the structs is important, do not change it
+
+use openvm_circuit::{
+    arch::ExecutionState,
+    system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols},
+};
+use openvm_circuit_primitives::AlignedBorrow;
+use openvm_instructions::riscv::RV32_REGISTER_NUM_LIMBS;
+use openvm_sha256_air::{Sha256DigestCols, Sha256RoundCols, SHA256_HASH_WORDS, SHA256_WORD_U8S};
+
+use super::SHA256_STATE_CELLS;
+use crate::sha256_chip::SHA256_REGISTER_READS;
+
+/// Every compression is a single block, whose first 16 rows are of type
+/// Sha256CompressVmRoundCols and whose last row is of type Sha256CompressVmDigestCols
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct Sha256CompressVmRoundCols<T> {
+    pub control: Sha256CompressVmControlCols<T>,
+    pub inner: Sha256RoundCols<T>,
+    pub read_aux: MemoryReadAuxCols<T>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct Sha256CompressVmDigestCols<T> {
+    pub control: Sha256CompressVmControlCols<T>,
+    pub inner: Sha256DigestCols<T>,
+
+    pub from_state: ExecutionState<T>,
+    /// As in [Sha256VmDigestCols](crate::Sha256VmDigestCols), the register reads are constrained
+    /// on the digest row
+    pub rd_ptr: T,
+    pub rs1_ptr: T,
+    pub rs2_ptr: T,
+    pub dst_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub state_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub block_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub register_reads_aux: [MemoryReadAuxCols<T>; SHA256_REGISTER_READS],
+    /// The state before the compression as read from memory, with the words in little-endian
+    /// byte order
+    pub prev_state: [[T; SHA256_WORD_U8S]; SHA256_HASH_WORDS],
+    pub state_read_aux: MemoryReadAuxCols<T>,
+    pub write_aux: MemoryWriteAuxCols<T, SHA256_STATE_CELLS>,
+}
+
+/// These are the columns that are used on both round and digest rows
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct Sha256CompressVmControlCols<T> {
+    /// Note: We will use the buffer in `inner.message_schedule` as the block data
+    /// Need to keep timestamp and read_ptr since block reads don't have the necessary information
+    pub cur_timestamp: T,
+    pub read_ptr: T,
+}
+
+/// Width of the Sha256CompressVmControlCols
+pub const SHA256_COMPRESS_VM_CONTROL_WIDTH: usize = Sha256CompressVmControlCols::<u8>::width();
+/// Width of the Sha256CompressVmRoundCols
+pub const SHA256_COMPRESS_VM_ROUND_WIDTH: usize = Sha256CompressVmRoundCols::<u8>::width();
+/// Width of the Sha256CompressVmDigestCols
+pub const SHA256_COMPRESS_VM_DIGEST_WIDTH: usize = Sha256CompressVmDigestCols::<u8>::width();
+/// Width of the Sha256CompressVm trace
+pub const SHA256_COMPRESS_VM_WIDTH: usize =
+    if SHA256_COMPRESS_VM_ROUND_WIDTH > SHA256_COMPRESS_VM_DIGEST_WIDTH {
+        SHA256_COMPRESS_VM_ROUND_WIDTH
+    } else {
+        SHA256_COMPRESS_VM_DIGEST_WIDTH
+    };
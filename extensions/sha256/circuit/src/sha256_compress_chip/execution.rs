@@ -0,0 +1,199 @@
+use std::borrow::{Borrow, BorrowMut};
+
+use openvm_circuit::{arch::*, system::memory::online::GuestMemory};
+use openvm_circuit_primitives::AlignedBytesBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+    LocalOpcode,
+};
+use openvm_sha256_air::{SHA256_BLOCK_U8S, SHA256_ROWS_PER_BLOCK};
+use openvm_sha256_transpiler::Rv32Sha256CompressOpcode;
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use super::{sha256_compress_solve, Sha256CompressVmExecutor, SHA256_STATE_CELLS};
+use crate::sha256_chip::SHA256_READ_SIZE;
+
+#[derive(AlignedBytesBorrow, Clone)]
+#[repr(C)]
+struct Sha256CompressPreCompute {
+    a: u8,
+    b: u8,
+    c: u8,
+}
+
+impl<F: PrimeField32> Executor<F> for Sha256CompressVmExecutor {
+    #[cfg(feature = "tco")]
+    fn handler<Ctx>(
+        &self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<Handler<F, Ctx>, StaticProgramError>
+    where
+        Ctx: ExecutionCtxTrait,
+    {
+        let data: &mut Sha256CompressPreCompute = data.borrow_mut();
+        self.pre_compute_impl(pc, inst, data)?;
+        Ok(execute_e1_handler::<_, _>)
+    }
+
+    fn pre_compute_size(&self) -> usize {
+        size_of::<Sha256CompressPreCompute>()
+    }
+
+    #[cfg(not(feature = "tco"))]
+    fn pre_compute<Ctx>(
+        &self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<ExecuteFunc<F, Ctx>, StaticProgramError>
+    where
+        Ctx: ExecutionCtxTrait,
+    {
+        let data: &mut Sha256CompressPreCompute = data.borrow_mut();
+        self.pre_compute_impl(pc, inst, data)?;
+        Ok(execute_e1_impl::<_, _>)
+    }
+}
+
+impl<F: PrimeField32> MeteredExecutor<F> for Sha256CompressVmExecutor {
+    fn metered_pre_compute_size(&self) -> usize {
+        size_of::<E2PreCompute<Sha256CompressPreCompute>>()
+    }
+
+    #[cfg(not(feature = "tco"))]
+    fn metered_pre_compute<Ctx>(
+        &self,
+        chip_idx: usize,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<ExecuteFunc<F, Ctx>, StaticProgramError>
+    where
+        Ctx: MeteredExecutionCtxTrait,
+    {
+        let data: &mut E2PreCompute<Sha256CompressPreCompute> = data.borrow_mut();
+        data.chip_idx = chip_idx as u32;
+        self.pre_compute_impl(pc, inst, &mut data.data)?;
+        Ok(execute_e2_impl::<_, _>)
+    }
+
+    #[cfg(feature = "tco")]
+    fn metered_handler<Ctx>(
+        &self,
+        chip_idx: usize,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<Handler<F, Ctx>, StaticProgramError>
+    where
+        Ctx: MeteredExecutionCtxTrait,
+    {
+        let data: &mut E2PreCompute<Sha256CompressPreCompute> = data.borrow_mut();
+        data.chip_idx = chip_idx as u32;
+        self.pre_compute_impl(pc, inst, &mut data.data)?;
+        Ok(execute_e2_handler::<_, _>)
+    }
+}
+
+#[inline(always)]
+unsafe fn execute_e12_impl<F: PrimeField32, CTX: ExecutionCtxTrait, const IS_E1: bool>(
+    pre_compute: &Sha256CompressPreCompute,
+    instret: &mut u64,
+    pc: &mut u32,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let dst = exec_state.vm_read(RV32_REGISTER_AS, pre_compute.a as u32);
+    let state_ptr = exec_state.vm_read(RV32_REGISTER_AS, pre_compute.b as u32);
+    let block_ptr = exec_state.vm_read(RV32_REGISTER_AS, pre_compute.c as u32);
+    let dst_u32 = u32::from_le_bytes(dst);
+    let state_ptr_u32 = u32::from_le_bytes(state_ptr);
+    let block_ptr_u32 = u32::from_le_bytes(block_ptr);
+
+    let state: [u8; SHA256_STATE_CELLS] = exec_state.vm_read(RV32_MEMORY_AS, state_ptr_u32);
+    let block: [u8; SHA256_BLOCK_U8S] = if IS_E1 {
+        exec_state.host_read(RV32_MEMORY_AS, block_ptr_u32)
+    } else {
+        let mut block = [0u8; SHA256_BLOCK_U8S];
+        // Reads happen on the first 4 rows of the block
+        for (row, chunk) in block.chunks_exact_mut(SHA256_READ_SIZE).enumerate() {
+            let row_input: [u8; SHA256_READ_SIZE] = exec_state.vm_read(
+                RV32_MEMORY_AS,
+                block_ptr_u32 + (row * SHA256_READ_SIZE) as u32,
+            );
+            chunk.copy_from_slice(&row_input);
+        }
+        block
+    };
+
+    let output = sha256_compress_solve(state, block);
+    exec_state.vm_write(RV32_MEMORY_AS, dst_u32, &output);
+
+    *pc = pc.wrapping_add(DEFAULT_PC_STEP);
+    *instret += 1;
+}
+
+#[create_handler]
+#[inline(always)]
+unsafe fn execute_e1_impl<F: PrimeField32, CTX: ExecutionCtxTrait>(
+    pre_compute: &[u8],
+    instret: &mut u64,
+    pc: &mut u32,
+    _instret_end: u64,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let pre_compute: &Sha256CompressPreCompute = pre_compute.borrow();
+    execute_e12_impl::<F, CTX, true>(pre_compute, instret, pc, exec_state);
+}
+
+#[create_handler]
+#[inline(always)]
+unsafe fn execute_e2_impl<F: PrimeField32, CTX: MeteredExecutionCtxTrait>(
+    pre_compute: &[u8],
+    instret: &mut u64,
+    pc: &mut u32,
+    _arg: u64,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let pre_compute: &E2PreCompute<Sha256CompressPreCompute> = pre_compute.borrow();
+    execute_e12_impl::<F, CTX, false>(&pre_compute.data, instret, pc, exec_state);
+    exec_state
+        .ctx
+        .on_height_change(pre_compute.chip_idx as usize, SHA256_ROWS_PER_BLOCK as u32);
+}
+
+impl Sha256CompressVmExecutor {
+    fn pre_compute_impl<F: PrimeField32>(
+        &self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut Sha256CompressPreCompute,
+    ) -> Result<(), StaticProgramError> {
+        let Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = inst;
+        let e_u32 = e.as_canonical_u32();
+        if d.as_canonical_u32() != RV32_REGISTER_AS || e_u32 != RV32_MEMORY_AS {
+            return Err(StaticProgramError::InvalidInstruction(pc));
+        }
+        *data = Sha256CompressPreCompute {
+            a: a.as_canonical_u32() as u8,
+            b: b.as_canonical_u32() as u8,
+            c: c.as_canonical_u32() as u8,
+        };
+        assert_eq!(
+            &Rv32Sha256CompressOpcode::SHA256_COMPRESS.global_opcode(),
+            opcode
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+//! Sha256 compression function applied to a 32-byte state and a 64-byte block in VM memory.
+//! Unlike [Sha256VmExecutor](crate::Sha256VmExecutor), it does no padding and starts from an
+//! arbitrary state, which is left to the guest so that streaming hashers and HMAC can be built on
+//! it.
+
+use openvm_circuit::arch::VmChipWrapper;
+use openvm_circuit_primitives::bitwise_op_lookup::SharedBitwiseOperationLookupChip;
+use openvm_instructions::riscv::RV32_CELL_BITS;
+use openvm_sha256_air::{Sha256FillerHelper, SHA256_BLOCK_U8S, SHA256_HASH_WORDS, SHA256_WORD_U8S};
+
+use crate::{sha256_chip::SHA256_REGISTER_READS, SHA256_NUM_READ_ROWS};
+
+mod air;
+mod columns;
+mod execution;
+mod trace;
+
+pub use air::*;
+pub use columns::*;
+pub use trace::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of cells of the state, which is read and written in a single memory access each
+pub const SHA256_STATE_CELLS: usize = SHA256_HASH_WORDS * SHA256_WORD_U8S;
+/// Amount the timestamp advances by: the register reads, the state read, the block reads and the
+/// state write
+const SHA256_COMPRESS_TIMESTAMP_DELTA: usize = SHA256_REGISTER_READS + 1 + SHA256_NUM_READ_ROWS + 1;
+
+pub type Sha256CompressVmChip<F> = VmChipWrapper<F, Sha256CompressVmFiller>;
+
+#[derive(derive_new::new, Clone, Copy)]
+pub struct Sha256CompressVmExecutor {
+    pub offset: usize,
+    pub pointer_max_bits: usize,
+}
+
+pub struct Sha256CompressVmFiller {
+    pub inner: Sha256FillerHelper,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    pub pointer_max_bits: usize,
+}
+
+impl Sha256CompressVmFiller {
+    pub fn new(
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+        pointer_max_bits: usize,
+    ) -> Self {
+        Self {
+            inner: Sha256FillerHelper::new(),
+            bitwise_lookup_chip,
+            pointer_max_bits,
+        }
+    }
+}
+
+/// Compresses `block` into `state`, where the state words are stored in memory in little-endian
+/// byte order.
+pub fn sha256_compress_solve(
+    state: [u8; SHA256_STATE_CELLS],
+    block: [u8; SHA256_BLOCK_U8S],
+) -> [u8; SHA256_STATE_CELLS] {
+    let prev_hash = state_to_words(state);
+    let new_hash = Sha256FillerHelper::get_block_hash(&prev_hash, &block);
+    let mut output = [0u8; SHA256_STATE_CELLS];
+    for (word, bytes) in new_hash
+        .iter()
+        .zip(output.chunks_exact_mut(SHA256_WORD_U8S))
+    {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    output
+}
+
+/// Converts a state as stored in memory into its words
+pub(crate) fn state_to_words(state: [u8; SHA256_STATE_CELLS]) -> [u32; SHA256_HASH_WORDS] {
+    std::array::from_fn(|i| {
+        u32::from_le_bytes(
+            state[i * SHA256_WORD_U8S..(i + 1) * SHA256_WORD_U8S]
+                .try_into()
+                .unwrap(),
+        )
+    })
+}
@@ -0,0 +1,287 @@
+use std::{array, borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::{
+    arch::{
+        testing::{
+            memory::gen_pointer, TestBuilder, TestChipHarness, VmChipTestBuilder,
+            BITWISE_OP_LOOKUP_BUS,
+        },
+        Arena, PreflightExecutor,
+    },
+    system::{memory::SharedMemoryHelper, SystemPort},
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupAir, BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+    SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS},
+    LocalOpcode,
+};
+use openvm_sha256_air::{SHA256_BLOCK_U8S, SHA256_H, SHA256_ROWS_PER_BLOCK};
+use openvm_sha256_transpiler::Rv32Sha256CompressOpcode;
+use openvm_stark_backend::{
+    interaction::BusIndex,
+    p3_field::FieldAlgebra,
+    p3_matrix::{dense::DenseMatrix, Matrix},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{
+    sha256_compress_solve, Sha256CompressVmAir, Sha256CompressVmChip, Sha256CompressVmDigestCols,
+    Sha256CompressVmExecutor, Sha256CompressVmFiller, SHA256_COMPRESS_VM_DIGEST_WIDTH,
+    SHA256_STATE_CELLS,
+};
+use crate::sha256_solve;
+
+type F = BabyBear;
+const SELF_BUS_IDX: BusIndex = 28;
+const MAX_INS_CAPACITY: usize = 128;
+type Harness<RA> =
+    TestChipHarness<F, Sha256CompressVmExecutor, Sha256CompressVmAir, Sha256CompressVmChip<F>, RA>;
+
+fn create_harness_fields(
+    system_port: SystemPort,
+    bitwise_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    memory_helper: SharedMemoryHelper<F>,
+    address_bits: usize,
+) -> (
+    Sha256CompressVmAir,
+    Sha256CompressVmExecutor,
+    Sha256CompressVmChip<F>,
+) {
+    let air = Sha256CompressVmAir::new(system_port, bitwise_chip.bus(), address_bits, SELF_BUS_IDX);
+    let executor =
+        Sha256CompressVmExecutor::new(Rv32Sha256CompressOpcode::CLASS_OFFSET, address_bits);
+    let chip = Sha256CompressVmChip::new(
+        Sha256CompressVmFiller::new(bitwise_chip, address_bits),
+        memory_helper,
+    );
+    (air, executor, chip)
+}
+
+fn create_harness<RA: Arena>(
+    tester: &mut VmChipTestBuilder<F>,
+) -> (
+    Harness<RA>,
+    (
+        BitwiseOperationLookupAir<RV32_CELL_BITS>,
+        SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    ),
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let (air, executor, chip) = create_harness_fields(
+        tester.system_port(),
+        bitwise_chip.clone(),
+        tester.memory_helper(),
+        tester.address_bits(),
+    );
+    let harness = Harness::<RA>::with_capacity(executor, air, chip, MAX_INS_CAPACITY);
+    (harness, (bitwise_chip.air, bitwise_chip))
+}
+
+fn write_bytes(tester: &mut impl TestBuilder<F>, ptr: usize, bytes: &[u8]) {
+    for (i, word) in bytes.chunks_exact(4).enumerate() {
+        tester.write::<4>(
+            RV32_MEMORY_AS as usize,
+            ptr + i * 4,
+            array::from_fn(|j| F::from_canonical_u8(word[j])),
+        );
+    }
+}
+
+/// Compresses `block` into `state`, which are random if not given. If `in_place` is set, the new
+/// state overwrites the previous one. Returns the new state.
+fn set_and_execute<RA: Arena, E: PreflightExecutor<F, RA>>(
+    tester: &mut impl TestBuilder<F>,
+    executor: &mut E,
+    arena: &mut RA,
+    rng: &mut StdRng,
+    state: Option<[u8; SHA256_STATE_CELLS]>,
+    block: Option<[u8; SHA256_BLOCK_U8S]>,
+    in_place: bool,
+) -> [u8; SHA256_STATE_CELLS] {
+    let state = state.unwrap_or_else(|| array::from_fn(|_| rng.gen()));
+    let block = block.unwrap_or_else(|| array::from_fn(|_| rng.gen()));
+
+    let rd = gen_pointer(rng, 4);
+    let rs1 = gen_pointer(rng, 4);
+    let rs2 = gen_pointer(rng, 4);
+
+    let state_ptr = gen_pointer(rng, 4);
+    let dst_ptr = if in_place {
+        state_ptr
+    } else {
+        gen_pointer(rng, 4)
+    };
+    let block_ptr = gen_pointer(rng, 4);
+    tester.write(1, rd, dst_ptr.to_le_bytes().map(F::from_canonical_u8));
+    tester.write(1, rs1, state_ptr.to_le_bytes().map(F::from_canonical_u8));
+    tester.write(1, rs2, block_ptr.to_le_bytes().map(F::from_canonical_u8));
+    write_bytes(tester, state_ptr, &state);
+    write_bytes(tester, block_ptr, &block);
+
+    tester.execute(
+        executor,
+        arena,
+        &Instruction::from_usize(
+            Rv32Sha256CompressOpcode::SHA256_COMPRESS.global_opcode(),
+            [
+                rd,
+                rs1,
+                rs2,
+                RV32_REGISTER_AS as usize,
+                RV32_MEMORY_AS as usize,
+            ],
+        ),
+    );
+
+    let output = sha256_compress_solve(state, block);
+    assert_eq!(
+        output.map(F::from_canonical_u8),
+        tester.read::<32>(RV32_MEMORY_AS as usize, dst_ptr)
+    );
+    output
+}
+
+/// The state [SHA256_H] in memory
+fn initial_state() -> [u8; SHA256_STATE_CELLS] {
+    array::from_fn(|i| SHA256_H[i / 4].to_le_bytes()[i % 4])
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Randomly generate computations and execute, ensuring that the generated trace
+/// passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn rand_sha256_compress_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_harness(&mut tester);
+
+    // Includes a compression from the initial state. The 5 compressions take 85 rows, so the
+    // trace is padded with dummy rows.
+    set_and_execute(
+        &mut tester,
+        &mut harness.executor,
+        &mut harness.arena,
+        &mut rng,
+        Some(initial_state()),
+        None,
+        false,
+    );
+    for _ in 0..4 {
+        set_and_execute(
+            &mut tester,
+            &mut harness.executor,
+            &mut harness.arena,
+            &mut rng,
+            None,
+            None,
+            false,
+        );
+    }
+
+    let tester = tester
+        .build()
+        .load(harness)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn sha256_compress_in_place_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_harness(&mut tester);
+
+    // Hash a message of several blocks the way a streaming hasher would
+    let mut state = initial_state();
+    for _ in 0..7 {
+        state = set_and_execute(
+            &mut tester,
+            &mut harness.executor,
+            &mut harness.arena,
+            &mut rng,
+            Some(state),
+            None,
+            true,
+        );
+    }
+
+    let tester = tester
+        .build()
+        .load(harness)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// part of the trace and check that the chip throws the expected error.
+//////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn sha256_compress_negative_prev_state_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_harness(&mut tester);
+
+    set_and_execute(
+        &mut tester,
+        &mut harness.executor,
+        &mut harness.arena,
+        &mut rng,
+        None,
+        None,
+        false,
+    );
+
+    // Start the compression from a state other than the one read from memory
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let width = trace.width();
+        let cols: &mut Sha256CompressVmDigestCols<F> = trace.values
+            [(SHA256_ROWS_PER_BLOCK - 1) * width..][..SHA256_COMPRESS_VM_DIGEST_WIDTH]
+            .borrow_mut();
+        cols.inner.prev_hash[0][0] += F::ONE;
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(harness, modify_trace)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn sha256_compress_solve_sanity_check() {
+    // The padded message "abc" is a single block
+    let mut block = [0u8; SHA256_BLOCK_U8S];
+    block[..3].copy_from_slice(b"abc");
+    block[3] = 0x80;
+    block[SHA256_BLOCK_U8S - 1] = 24;
+
+    let state = sha256_compress_solve(initial_state(), block);
+    // The digest is the state with the words in big-endian byte order
+    let digest: [u8; SHA256_STATE_CELLS] = array::from_fn(|i| state[i - i % 4 + 3 - i % 4]);
+    assert_eq!(digest, sha256_solve(b"abc"));
+}
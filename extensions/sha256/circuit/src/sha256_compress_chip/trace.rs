@@ -0,0 +1,418 @@
+use std::{array, borrow::BorrowMut, cmp::min};
+
+use openvm_circuit::{
+    arch::*,
+    system::memory::{
+        offline_checker::{MemoryReadAuxRecord, MemoryWriteBytesAuxRecord},
+        online::TracingMemory,
+        MemoryAuxColsFactory,
+    },
+};
+use openvm_circuit_primitives::AlignedBytesBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+    LocalOpcode,
+};
+use openvm_rv32im_circuit::adapters::{tracing_read, tracing_write};
+use openvm_sha256_air::{
+    SHA256_BLOCK_U8S, SHA256_BLOCK_WORDS, SHA256_HASH_WORDS, SHA256_ROWS_PER_BLOCK, SHA256_WORD_U8S,
+};
+use openvm_sha256_transpiler::Rv32Sha256CompressOpcode;
+use openvm_stark_backend::{
+    p3_field::PrimeField32,
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    p3_maybe_rayon::prelude::*,
+};
+
+use super::{
+    sha256_compress_solve, state_to_words, Sha256CompressVmControlCols, Sha256CompressVmDigestCols,
+    Sha256CompressVmExecutor, Sha256CompressVmFiller, Sha256CompressVmRoundCols,
+    SHA256_COMPRESS_VM_CONTROL_WIDTH, SHA256_COMPRESS_VM_DIGEST_WIDTH,
+    SHA256_COMPRESS_VM_ROUND_WIDTH, SHA256_COMPRESS_VM_WIDTH, SHA256_STATE_CELLS,
+};
+use crate::{
+    sha256_chip::{SHA256_READ_SIZE, SHA256_REGISTER_READS},
+    SHA256_NUM_READ_ROWS,
+};
+
+/// Every instruction is a single block of [SHA256_ROWS_PER_BLOCK] rows.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sha256CompressVmMetadata;
+
+impl MultiRowMetadata for Sha256CompressVmMetadata {
+    #[inline(always)]
+    fn get_num_rows(&self) -> usize {
+        SHA256_ROWS_PER_BLOCK
+    }
+}
+
+pub(crate) type Sha256CompressVmRecordLayout = MultiRowLayout<Sha256CompressVmMetadata>;
+
+#[repr(C)]
+#[derive(AlignedBytesBorrow, Debug, Clone)]
+pub struct Sha256CompressVmRecord {
+    pub from_pc: u32,
+    pub timestamp: u32,
+    pub rd_ptr: u32,
+    pub rs1_ptr: u32,
+    pub rs2_ptr: u32,
+    pub dst_ptr: u32,
+    pub state_ptr: u32,
+    pub block_ptr: u32,
+    /// The state before the compression, as read from memory
+    pub prev_state: [u8; SHA256_STATE_CELLS],
+    pub block: [u8; SHA256_BLOCK_U8S],
+
+    pub register_reads_aux: [MemoryReadAuxRecord; SHA256_REGISTER_READS],
+    pub state_read_aux: MemoryReadAuxRecord,
+    pub block_reads_aux: [MemoryReadAuxRecord; SHA256_NUM_READ_ROWS],
+    pub write_aux: MemoryWriteBytesAuxRecord<SHA256_STATE_CELLS>,
+}
+
+impl<F, RA> PreflightExecutor<F, RA> for Sha256CompressVmExecutor
+where
+    F: PrimeField32,
+    for<'buf> RA: RecordArena<'buf, Sha256CompressVmRecordLayout, &'buf mut Sha256CompressVmRecord>,
+{
+    fn get_opcode_name(&self, _: usize) -> String {
+        format!("{:?}", Rv32Sha256CompressOpcode::SHA256_COMPRESS)
+    }
+
+    fn execute(
+        &self,
+        state: VmStateMut<F, TracingMemory, RA>,
+        instruction: &Instruction<F>,
+    ) -> Result<(), ExecutionError> {
+        let &Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = instruction;
+        debug_assert_eq!(
+            opcode,
+            Rv32Sha256CompressOpcode::SHA256_COMPRESS.global_opcode()
+        );
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+        debug_assert_eq!(e.as_canonical_u32(), RV32_MEMORY_AS);
+
+        let record = state.ctx.alloc(Sha256CompressVmRecordLayout::default());
+
+        record.from_pc = *state.pc;
+        record.timestamp = state.memory.timestamp();
+        record.rd_ptr = a.as_canonical_u32();
+        record.rs1_ptr = b.as_canonical_u32();
+        record.rs2_ptr = c.as_canonical_u32();
+
+        record.dst_ptr = u32::from_le_bytes(tracing_read(
+            state.memory,
+            RV32_REGISTER_AS,
+            record.rd_ptr,
+            &mut record.register_reads_aux[0].prev_timestamp,
+        ));
+        record.state_ptr = u32::from_le_bytes(tracing_read(
+            state.memory,
+            RV32_REGISTER_AS,
+            record.rs1_ptr,
+            &mut record.register_reads_aux[1].prev_timestamp,
+        ));
+        record.block_ptr = u32::from_le_bytes(tracing_read(
+            state.memory,
+            RV32_REGISTER_AS,
+            record.rs2_ptr,
+            &mut record.register_reads_aux[2].prev_timestamp,
+        ));
+        debug_assert!(record.dst_ptr as usize + SHA256_STATE_CELLS <= (1 << self.pointer_max_bits));
+        debug_assert!(
+            record.state_ptr as usize + SHA256_STATE_CELLS <= (1 << self.pointer_max_bits)
+        );
+        debug_assert!(record.block_ptr as usize + SHA256_BLOCK_U8S <= (1 << self.pointer_max_bits));
+
+        record.prev_state = tracing_read(
+            state.memory,
+            RV32_MEMORY_AS,
+            record.state_ptr,
+            &mut record.state_read_aux.prev_timestamp,
+        );
+        // Reads happen on the first 4 rows of the block
+        for row in 0..SHA256_NUM_READ_ROWS {
+            let row_input: [u8; SHA256_READ_SIZE] = tracing_read(
+                state.memory,
+                RV32_MEMORY_AS,
+                record.block_ptr + (row * SHA256_READ_SIZE) as u32,
+                &mut record.block_reads_aux[row].prev_timestamp,
+            );
+            record.block[row * SHA256_READ_SIZE..(row + 1) * SHA256_READ_SIZE]
+                .copy_from_slice(&row_input);
+        }
+
+        let output = sha256_compress_solve(record.prev_state, record.block);
+        tracing_write(
+            state.memory,
+            RV32_MEMORY_AS,
+            record.dst_ptr,
+            output,
+            &mut record.write_aux.prev_timestamp,
+            &mut record.write_aux.prev_data,
+        );
+
+        *state.pc = state.pc.wrapping_add(DEFAULT_PC_STEP);
+
+        Ok(())
+    }
+}
+
+impl<F: PrimeField32> TraceFiller<F> for Sha256CompressVmFiller {
+    fn fill_trace(
+        &self,
+        mem_helper: &MemoryAuxColsFactory<F>,
+        trace_matrix: &mut RowMajorMatrix<F>,
+        rows_used: usize,
+    ) {
+        if rows_used == 0 {
+            return;
+        }
+        debug_assert_eq!(trace_matrix.width(), SHA256_COMPRESS_VM_WIDTH);
+        let (trace, dummy_trace) = trace_matrix
+            .values
+            .split_at_mut(rows_used * SHA256_COMPRESS_VM_WIDTH);
+
+        // First pass over the trace to copy out the records, since the digest row of every block
+        // holds the state that the next block starts from
+        let records: Vec<Sha256CompressVmRecord> = trace
+            .par_chunks_exact_mut(SHA256_ROWS_PER_BLOCK * SHA256_COMPRESS_VM_WIDTH)
+            .map(|mut slice| {
+                // SAFETY:
+                // - caller ensures `trace` contains a valid record representation that was
+                //   previously written by the executor
+                // - every record is allocated at the start of its SHA256_ROWS_PER_BLOCK rows
+                let record: &Sha256CompressVmRecord =
+                    unsafe { get_record_from_slice(&mut slice, ()) };
+                record.clone()
+            })
+            .collect();
+        let prev_hashes: Vec<[u32; SHA256_HASH_WORDS]> = records
+            .iter()
+            .map(|record| state_to_words(record.prev_state))
+            .collect();
+
+        // During the first pass we will fill out most of the matrix
+        // But there are some cells that can't be generated by the first pass so we will do a second
+        // pass over the matrix later
+        trace
+            .par_chunks_exact_mut(SHA256_ROWS_PER_BLOCK * SHA256_COMPRESS_VM_WIDTH)
+            .zip(records.par_iter())
+            .enumerate()
+            .for_each(|(block_idx, (block_slice, record))| {
+                // Need to get rid of the accidental garbage data that might overflow the
+                // F's prime field. Unfortunately, there is no good way around this
+                // SAFETY:
+                // - block_slice comes from par_chunks_exact_mut with exact size guarantee
+                // - Length is SHA256_ROWS_PER_BLOCK * SHA256_COMPRESS_VM_WIDTH * size_of::<F>()
+                //   bytes
+                // - The record was copied out above and the subsequent trace filling will overwrite
+                //   with valid values
+                unsafe {
+                    std::ptr::write_bytes(
+                        block_slice.as_mut_ptr() as *mut u8,
+                        0,
+                        SHA256_ROWS_PER_BLOCK * SHA256_COMPRESS_VM_WIDTH * size_of::<F>(),
+                    );
+                }
+                // The last block passes the state of the first block on to the padding rows
+                let next_prev_hash = &prev_hashes[(block_idx + 1) % prev_hashes.len()];
+                self.fill_block_trace::<F>(
+                    block_slice,
+                    record,
+                    &prev_hashes[block_idx],
+                    next_prev_hash,
+                    block_idx,
+                    mem_helper,
+                );
+            });
+
+        // Fill in the padding rows, which repeat the hash of the last digest row
+        dummy_trace
+            .par_chunks_exact_mut(SHA256_COMPRESS_VM_WIDTH)
+            .for_each(|row| {
+                // SAFETY:
+                // - row has exactly SHA256_COMPRESS_VM_WIDTH elements
+                // - We're zeroing all SHA256_COMPRESS_VM_WIDTH elements to clear any garbage data
+                //   that might overflow the field
+                unsafe {
+                    std::ptr::write_bytes(
+                        row.as_mut_ptr() as *mut u8,
+                        0,
+                        SHA256_COMPRESS_VM_WIDTH * size_of::<F>(),
+                    );
+                }
+                let cols: &mut Sha256CompressVmRoundCols<F> =
+                    row[..SHA256_COMPRESS_VM_ROUND_WIDTH].borrow_mut();
+                self.inner
+                    .generate_free_default_row(&mut cols.inner, &prev_hashes[0]);
+            });
+
+        // Do a second pass over the trace to fill in the missing values
+        // Note, we need to skip the very first row
+        trace_matrix.values[SHA256_COMPRESS_VM_WIDTH..]
+            .par_chunks_mut(SHA256_COMPRESS_VM_WIDTH * SHA256_ROWS_PER_BLOCK)
+            .take(rows_used / SHA256_ROWS_PER_BLOCK)
+            .for_each(|chunk| {
+                self.inner.generate_missing_cells(
+                    chunk,
+                    SHA256_COMPRESS_VM_WIDTH,
+                    SHA256_COMPRESS_VM_CONTROL_WIDTH,
+                );
+            });
+    }
+}
+
+impl Sha256CompressVmFiller {
+    fn fill_block_trace<F: PrimeField32>(
+        &self,
+        block_slice: &mut [F],
+        record: &Sha256CompressVmRecord,
+        prev_hash: &[u32; SHA256_HASH_WORDS],
+        next_prev_hash: &[u32; SHA256_HASH_WORDS],
+        global_block_idx: usize,
+        mem_helper: &MemoryAuxColsFactory<F>,
+    ) {
+        let input: [u32; SHA256_BLOCK_WORDS] = array::from_fn(|i| {
+            u32::from_be_bytes(
+                record.block[i * SHA256_WORD_U8S..(i + 1) * SHA256_WORD_U8S]
+                    .try_into()
+                    .unwrap(),
+            )
+        });
+
+        // The block is read after the register reads and the state read
+        let block_start_timestamp = record.timestamp + (SHA256_REGISTER_READS + 1) as u32;
+
+        // Fill in the VM columns first because the inner `carry_or_buffer` needs to be filled in
+        block_slice
+            .par_chunks_exact_mut(SHA256_COMPRESS_VM_WIDTH)
+            .enumerate()
+            .for_each(|(row_idx, row_slice)| {
+                if row_idx == SHA256_ROWS_PER_BLOCK - 1 {
+                    // This is a digest row
+                    let digest_cols: &mut Sha256CompressVmDigestCols<F> =
+                        row_slice[..SHA256_COMPRESS_VM_DIGEST_WIDTH].borrow_mut();
+                    digest_cols.from_state.timestamp = F::from_canonical_u32(record.timestamp);
+                    digest_cols.from_state.pc = F::from_canonical_u32(record.from_pc);
+                    digest_cols.rd_ptr = F::from_canonical_u32(record.rd_ptr);
+                    digest_cols.rs1_ptr = F::from_canonical_u32(record.rs1_ptr);
+                    digest_cols.rs2_ptr = F::from_canonical_u32(record.rs2_ptr);
+                    digest_cols.dst_ptr = record.dst_ptr.to_le_bytes().map(F::from_canonical_u8);
+                    digest_cols.state_ptr =
+                        record.state_ptr.to_le_bytes().map(F::from_canonical_u8);
+                    digest_cols.block_ptr =
+                        record.block_ptr.to_le_bytes().map(F::from_canonical_u8);
+                    digest_cols
+                        .register_reads_aux
+                        .iter_mut()
+                        .zip(record.register_reads_aux.iter())
+                        .enumerate()
+                        .for_each(|(idx, (cols_read, record_read))| {
+                            mem_helper.fill(
+                                record_read.prev_timestamp,
+                                record.timestamp + idx as u32,
+                                cols_read.as_mut(),
+                            );
+                        });
+
+                    digest_cols.prev_state = array::from_fn(|i| {
+                        array::from_fn(|j| {
+                            F::from_canonical_u8(record.prev_state[i * SHA256_WORD_U8S + j])
+                        })
+                    });
+                    mem_helper.fill(
+                        record.state_read_aux.prev_timestamp,
+                        record.timestamp + SHA256_REGISTER_READS as u32,
+                        digest_cols.state_read_aux.as_mut(),
+                    );
+
+                    digest_cols
+                        .write_aux
+                        .set_prev_data(record.write_aux.prev_data.map(F::from_canonical_u8));
+                    // We do `SHA256_NUM_READ_ROWS` reads of the block and then write the result
+                    mem_helper.fill(
+                        record.write_aux.prev_timestamp,
+                        block_start_timestamp + SHA256_NUM_READ_ROWS as u32,
+                        digest_cols.write_aux.as_mut(),
+                    );
+
+                    // Need to range check the destination, state and block pointers
+                    let msl_rshift: u32 = ((RV32_REGISTER_NUM_LIMBS - 1) * RV32_CELL_BITS) as u32;
+                    let msl_lshift: u32 =
+                        (RV32_REGISTER_NUM_LIMBS * RV32_CELL_BITS - self.pointer_max_bits) as u32;
+                    self.bitwise_lookup_chip.request_range(
+                        (record.dst_ptr >> msl_rshift) << msl_lshift,
+                        (record.state_ptr >> msl_rshift) << msl_lshift,
+                    );
+                    self.bitwise_lookup_chip.request_range(
+                        (record.block_ptr >> msl_rshift) << msl_lshift,
+                        (record.block_ptr >> msl_rshift) << msl_lshift,
+                    );
+
+                    digest_cols.inner.flags.is_last_block = F::ONE;
+                    digest_cols.inner.flags.is_digest_row = F::ONE;
+                } else {
+                    // This is a round row
+                    let round_cols: &mut Sha256CompressVmRoundCols<F> =
+                        row_slice[..SHA256_COMPRESS_VM_ROUND_WIDTH].borrow_mut();
+                    // Take care of the first 4 round rows (aka read rows)
+                    if row_idx < SHA256_NUM_READ_ROWS {
+                        round_cols
+                            .inner
+                            .message_schedule
+                            .carry_or_buffer
+                            .as_flattened_mut()
+                            .iter_mut()
+                            .zip(
+                                record.block
+                                    [row_idx * SHA256_READ_SIZE..(row_idx + 1) * SHA256_READ_SIZE]
+                                    .iter(),
+                            )
+                            .for_each(|(cell, data)| {
+                                *cell = F::from_canonical_u8(*data);
+                            });
+                        mem_helper.fill(
+                            record.block_reads_aux[row_idx].prev_timestamp,
+                            block_start_timestamp + row_idx as u32,
+                            round_cols.read_aux.as_mut(),
+                        );
+                    } else {
+                        mem_helper.fill_zero(round_cols.read_aux.as_mut());
+                    }
+                }
+                // Fill in the control cols, doesn't matter if it is a round or digest row
+                let control_cols: &mut Sha256CompressVmControlCols<F> =
+                    row_slice[..SHA256_COMPRESS_VM_CONTROL_WIDTH].borrow_mut();
+                // Only the first `SHA256_NUM_READ_ROWS` rows increment the timestamp and read ptr
+                control_cols.cur_timestamp = F::from_canonical_u32(
+                    block_start_timestamp + min(row_idx, SHA256_NUM_READ_ROWS) as u32,
+                );
+                control_cols.read_ptr = F::from_canonical_u32(
+                    record.block_ptr
+                        + (SHA256_READ_SIZE * min(row_idx, SHA256_NUM_READ_ROWS)) as u32,
+                );
+            });
+
+        // Fill in the inner trace when the `buffer_or_carry` is filled in
+        self.inner.generate_free_block_trace::<F>(
+            block_slice,
+            SHA256_COMPRESS_VM_WIDTH,
+            SHA256_COMPRESS_VM_CONTROL_WIDTH,
+            &input,
+            self.bitwise_lookup_chip.as_ref(),
+            prev_hash,
+            next_prev_hash,
+            global_block_idx as u32 + 1, // global block index is 1-indexed
+        );
+    }
+}
@@ -7,6 +7,7 @@ use openvm_platform::alloc::AlignedBuf;
 pub const OPCODE: u8 = 0x0b;
 pub const SHA256_FUNCT3: u8 = 0b100;
 pub const SHA256_FUNCT7: u8 = 0x1;
+pub const SHA256_COMPRESS_FUNCT7: u8 = 0x5;
 
 /// Native hook for sha256
 ///
@@ -67,3 +68,32 @@ pub extern "C" fn zkvm_sha256_impl(bytes: *const u8, len: usize, output: *mut u8
 fn __native_sha256(bytes: *const u8, len: usize, output: *mut u8) {
     openvm_platform::custom_insn_r!(opcode = OPCODE, funct3 = SHA256_FUNCT3, funct7 = SHA256_FUNCT7, rd = In output, rs1 = In bytes, rs2 = In len);
 }
+
+/// sha256 compression function intrinsic binding, for use by hashers which do their own padding.
+///
+/// The VM reads the hash state from `prev_state` and the 64-byte message block from `block`,
+/// and writes the state after compressing the block to `new_state`. The state is the eight
+/// 32-bit words of the intermediate hash value, in native (little-endian) byte order, as in
+/// [FIPS 180-4](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf).
+///
+/// # Safety
+///
+/// - `prev_state` and `new_state` must point to buffers of 8 words, and may be equal.
+/// - `block` must point to a buffer at least 64-bytes long.
+/// - `prev_state`, `block` and `new_state` must be 4-byte aligned.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+pub unsafe fn native_sha256_compress(
+    prev_state: *const u32,
+    block: *const u8,
+    new_state: *mut u32,
+) {
+    openvm_platform::custom_insn_r!(
+        opcode = OPCODE,
+        funct3 = SHA256_FUNCT3,
+        funct7 = SHA256_COMPRESS_FUNCT7,
+        rd = In new_state,
+        rs1 = In prev_state,
+        rs2 = In block
+    );
+}
@@ -1,10 +1,13 @@
-use openvm_instructions::{riscv::RV32_MEMORY_AS, LocalOpcode};
+use openvm_instructions::{riscv::RV32_MEMORY_AS, LocalOpcode, VmOpcode};
 use openvm_instructions_derive::LocalOpcode;
-use openvm_sha256_guest::{OPCODE, SHA256_FUNCT3, SHA256_FUNCT7};
+use openvm_sha256_guest::{OPCODE, SHA256_COMPRESS_FUNCT7, SHA256_FUNCT3, SHA256_FUNCT7};
 use openvm_stark_backend::p3_field::PrimeField32;
-use openvm_transpiler::{util::from_r_type, TranspilerExtension, TranspilerOutput};
+use openvm_transpiler::{
+    util::{from_r_type, local_opcode_name},
+    TranspilerExtension, TranspilerOutput,
+};
 use rrs_lib::instruction_formats::RType;
-use strum::{EnumCount, EnumIter, FromRepr};
+use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator};
 
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
@@ -15,6 +18,16 @@ pub enum Rv32Sha256Opcode {
     SHA256,
 }
 
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
+)]
+#[opcode_offset = 0x321]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32Sha256CompressOpcode {
+    SHA256_COMPRESS,
+}
+
 #[derive(Default)]
 pub struct Sha256TranspilerExtension;
 
@@ -31,16 +44,22 @@ impl<F: PrimeField32> TranspilerExtension<F> for Sha256TranspilerExtension {
             return None;
         }
         let dec_insn = RType::new(instruction_u32);
-
-        if dec_insn.funct7 != SHA256_FUNCT7 as u32 {
-            return None;
-        }
+        let global_opcode = match dec_insn.funct7 as u8 {
+            SHA256_FUNCT7 => Rv32Sha256Opcode::SHA256.global_opcode(),
+            SHA256_COMPRESS_FUNCT7 => Rv32Sha256CompressOpcode::SHA256_COMPRESS.global_opcode(),
+            _ => return None,
+        };
         let instruction = from_r_type(
-            Rv32Sha256Opcode::SHA256.global_opcode().as_usize(),
+            global_opcode.as_usize(),
             RV32_MEMORY_AS as usize,
             &dec_insn,
             true,
         );
         Some(TranspilerOutput::one_to_one(instruction))
     }
+
+    fn opcode_name(&self, opcode: VmOpcode) -> Option<String> {
+        local_opcode_name(opcode, Rv32Sha256Opcode::iter(), 1)
+            .or_else(|| local_opcode_name(opcode, Rv32Sha256CompressOpcode::iter(), 1))
+    }
 }
@@ -0,0 +1,134 @@
+use openvm_circuit::{
+    arch::{
+        Arena, DenseRecordArena, MatrixRecordArena, MultiRowMetadata, RecordSeeker, SizedRecord,
+    },
+    system::hip::extensions::{get_inventory_range_checker, get_or_create_bitwise_op_lookup},
+};
+use openvm_hip_backend::{
+    chip::{cpu_proving_ctx_to_hip, get_empty_air_proving_ctx},
+    engine::HipBabyBearPoseidon2Engine,
+    prover_backend::HipBackend,
+};
+use openvm_sha512_air::SHA512_ROWS_PER_BLOCK;
+use openvm_stark_backend::{prover::types::AirProvingContext, Chip};
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2::BabyBearPoseidon2Config, p3_baby_bear::BabyBear,
+};
+
+use super::*;
+use crate::sha512_compress_chip::{Sha512CompressVmRecord, Sha512CompressVmRecordLayout};
+
+type F = BabyBear;
+
+/// SHA-512/SHA-384 chip for the HIP backend which still does trace generation on CPU.
+#[derive(derive_new::new)]
+pub struct HybridSha512Chip {
+    cpu: Sha512VmChip<F>,
+}
+
+// Conversion of Dense->Matrix Record Arena, cpu tracegen, and then H2D transfer of the trace
+// matrix.
+impl Chip<DenseRecordArena, HipBackend> for HybridSha512Chip {
+    fn generate_proving_ctx(&self, mut arena: DenseRecordArena) -> AirProvingContext<HipBackend> {
+        if arena.is_empty() {
+            return get_empty_air_proving_ctx::<HipBackend>();
+        }
+
+        // Records have a variable number of blocks, so the layout of every record is needed to
+        // size the trace matrix
+        let records = arena.allocated();
+        let mut offset = 0;
+        let mut rows_used = 0;
+        while offset < records.len() {
+            let layout = RecordSeeker::<
+                DenseRecordArena,
+                Sha512VmRecordMut,
+                Sha512VmRecordLayout,
+            >::get_layout_at(&mut offset, records);
+            rows_used += layout.metadata.get_num_rows();
+            offset += Sha512VmRecordMut::size(&layout)
+                .next_multiple_of(Sha512VmRecordMut::alignment(&layout));
+        }
+
+        let height = rows_used.next_power_of_two();
+        let mut matrix_arena = MatrixRecordArena::<F>::with_capacity(height, SHA512VM_WIDTH);
+        arena
+            .get_record_seeker::<Sha512VmRecordMut, _>()
+            .transfer_to_matrix_arena(&mut matrix_arena);
+        let ctx = self.cpu.generate_proving_ctx(matrix_arena);
+        cpu_proving_ctx_to_hip(ctx)
+    }
+}
+
+/// Sha512 compression chip for the HIP backend which still does trace generation on CPU.
+#[derive(derive_new::new)]
+pub struct HybridSha512CompressChip {
+    cpu: Sha512CompressVmChip<F>,
+}
+
+// Conversion of Dense->Matrix Record Arena, cpu tracegen, and then H2D transfer of the trace
+// matrix.
+impl Chip<DenseRecordArena, HipBackend> for HybridSha512CompressChip {
+    fn generate_proving_ctx(&self, mut arena: DenseRecordArena) -> AirProvingContext<HipBackend> {
+        let records = arena.allocated();
+        if records.is_empty() {
+            return get_empty_air_proving_ctx::<HipBackend>();
+        }
+        debug_assert_eq!(records.len() % size_of::<Sha512CompressVmRecord>(), 0);
+
+        let num_records = records.len() / size_of::<Sha512CompressVmRecord>();
+        let height = (num_records * SHA512_ROWS_PER_BLOCK).next_power_of_two();
+        let mut matrix_arena =
+            MatrixRecordArena::<F>::with_capacity(height, SHA512_COMPRESS_VM_WIDTH);
+        arena
+            .get_record_seeker::<&mut Sha512CompressVmRecord, Sha512CompressVmRecordLayout>()
+            .transfer_to_matrix_arena(&mut matrix_arena);
+        let ctx = self.cpu.generate_proving_ctx(matrix_arena);
+        cpu_proving_ctx_to_hip(ctx)
+    }
+}
+
+pub struct Sha512HipProverExt;
+
+impl VmProverExtension<HipBabyBearPoseidon2Engine, DenseRecordArena, Sha512>
+    for Sha512HipProverExt
+{
+    fn extend_prover(
+        &self,
+        _: &Sha512,
+        inventory: &mut ChipInventory<BabyBearPoseidon2Config, DenseRecordArena, HipBackend>,
+    ) -> Result<(), ChipInventoryError> {
+        let pointer_max_bits = inventory.airs().pointer_max_bits();
+        let timestamp_max_bits = inventory.timestamp_max_bits();
+
+        let range_checker = get_inventory_range_checker(inventory);
+        let bitwise_lu = get_or_create_bitwise_op_lookup(inventory)?;
+
+        // Trace generation is done on CPU, with the CPU counterparts of the lookup chips
+        let mem_helper =
+            SharedMemoryHelper::new(range_checker.cpu_chip.clone().unwrap(), timestamp_max_bits);
+        for variant in SHA512_VARIANTS {
+            // These calls to next_air are not strictly necessary to construct the chips, but
+            // provide a safeguard to ensure that chip construction matches the circuit definition
+            inventory.next_air::<Sha512VmAir>()?;
+            let sha512 = Sha512VmChip::new(
+                Sha512VmFiller::new(
+                    bitwise_lu.cpu_chip.clone().unwrap(),
+                    pointer_max_bits,
+                    variant,
+                ),
+                mem_helper.clone(),
+            );
+            inventory.add_executor_chip(HybridSha512Chip::new(sha512));
+        }
+
+        inventory.next_air::<Sha512CompressVmAir>()?;
+        let sha512_compress = Sha512CompressVmChip::new(
+            Sha512CompressVmFiller::new(bitwise_lu.cpu_chip.clone().unwrap(), pointer_max_bits),
+            mem_helper,
+        );
+        inventory.add_executor_chip(HybridSha512CompressChip::new(sha512_compress));
+
+        Ok(())
+    }
+}
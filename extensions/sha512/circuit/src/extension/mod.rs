@@ -0,0 +1,174 @@
+use std::{result::Result, sync::Arc};
+
+use derive_more::derive::From;
+use openvm_circuit::{
+    arch::{
+        AirInventory, AirInventoryError, ChipInventory, ChipInventoryError,
+        ExecutorInventoryBuilder, ExecutorInventoryError, RowMajorMatrixArena, VmCircuitExtension,
+        VmExecutionExtension, VmProverExtension,
+    },
+    system::memory::SharedMemoryHelper,
+};
+use openvm_circuit_derive::{AnyEnum, Executor, MeteredExecutor, PreflightExecutor};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupAir, BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+    SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::*;
+use openvm_sha512_air::Sha512Variant;
+use openvm_sha512_transpiler::{Rv32Sha512CompressOpcode, Rv32Sha512Opcode};
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_field::PrimeField32,
+    prover::cpu::{CpuBackend, CpuDevice},
+};
+use openvm_stark_sdk::engine::StarkEngine;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::*;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "cuda")] {
+        mod cuda;
+        pub use self::cuda::*;
+        pub use self::cuda::Sha512GpuProverExt as Sha512ProverExt;
+    } else if #[cfg(feature = "rocm")] {
+        mod hip;
+        pub use self::hip::*;
+        pub use self::hip::Sha512HipProverExt as Sha512ProverExt;
+    } else {
+        pub use self::Sha512CpuProverExt as Sha512ProverExt;
+    }
+}
+
+/// The variants in the order in which their executors, AIRs and chips are added to the inventories
+const SHA512_VARIANTS: [Sha512Variant; 2] = [Sha512Variant::Sha512, Sha512Variant::Sha384];
+
+// =================================== VM Extension Implementation =================================
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Sha512;
+
+#[derive(Clone, From, AnyEnum, Executor, MeteredExecutor, PreflightExecutor)]
+pub enum Sha512Executor {
+    Sha512(Sha512VmExecutor),
+    Sha512Compress(Sha512CompressVmExecutor),
+}
+
+impl<F> VmExecutionExtension<F> for Sha512 {
+    type Executor = Sha512Executor;
+
+    fn extend_execution(
+        &self,
+        inventory: &mut ExecutorInventoryBuilder<F, Sha512Executor>,
+    ) -> Result<(), ExecutorInventoryError> {
+        let pointer_max_bits = inventory.pointer_max_bits();
+        for variant in SHA512_VARIANTS {
+            let step =
+                Sha512VmExecutor::new(Rv32Sha512Opcode::CLASS_OFFSET, pointer_max_bits, variant);
+            inventory.add_executor(step, [sha512_opcode(variant).global_opcode()])?;
+        }
+
+        let sha512_compress_step =
+            Sha512CompressVmExecutor::new(Rv32Sha512CompressOpcode::CLASS_OFFSET, pointer_max_bits);
+        inventory.add_executor(
+            sha512_compress_step,
+            Rv32Sha512CompressOpcode::iter().map(|x| x.global_opcode()),
+        )?;
+
+        Ok(())
+    }
+}
+
+impl<SC: StarkGenericConfig> VmCircuitExtension<SC> for Sha512 {
+    fn extend_circuit(&self, inventory: &mut AirInventory<SC>) -> Result<(), AirInventoryError> {
+        let pointer_max_bits = inventory.pointer_max_bits();
+
+        let bitwise_lu = {
+            let existing_air = inventory.find_air::<BitwiseOperationLookupAir<8>>().next();
+            if let Some(air) = existing_air {
+                air.bus
+            } else {
+                let bus = BitwiseOperationLookupBus::new(inventory.new_bus_idx());
+                let air = BitwiseOperationLookupAir::<8>::new(bus);
+                inventory.add_air(air);
+                air.bus
+            }
+        };
+
+        for variant in SHA512_VARIANTS {
+            let sha512 = Sha512VmAir::new(
+                inventory.system().port(),
+                bitwise_lu,
+                pointer_max_bits,
+                inventory.new_bus_idx(),
+                variant,
+            );
+            inventory.add_air(sha512);
+        }
+
+        let sha512_compress = Sha512CompressVmAir::new(
+            inventory.system().port(),
+            bitwise_lu,
+            pointer_max_bits,
+            inventory.new_bus_idx(),
+        );
+        inventory.add_air(sha512_compress);
+
+        Ok(())
+    }
+}
+
+pub struct Sha512CpuProverExt;
+// This implementation is specific to CpuBackend because the lookup chips (VariableRangeChecker,
+// BitwiseOperationLookupChip) are specific to CpuBackend.
+impl<E, SC, RA> VmProverExtension<E, RA, Sha512> for Sha512CpuProverExt
+where
+    SC: StarkGenericConfig,
+    E: StarkEngine<SC = SC, PB = CpuBackend<SC>, PD = CpuDevice<SC>>,
+    RA: RowMajorMatrixArena<Val<SC>>,
+    Val<SC>: PrimeField32,
+{
+    fn extend_prover(
+        &self,
+        _: &Sha512,
+        inventory: &mut ChipInventory<SC, RA, CpuBackend<SC>>,
+    ) -> Result<(), ChipInventoryError> {
+        let range_checker = inventory.range_checker()?.clone();
+        let timestamp_max_bits = inventory.timestamp_max_bits();
+        let mem_helper = SharedMemoryHelper::new(range_checker.clone(), timestamp_max_bits);
+        let pointer_max_bits = inventory.airs().pointer_max_bits();
+
+        let bitwise_lu = {
+            let existing_chip = inventory
+                .find_chip::<SharedBitwiseOperationLookupChip<8>>()
+                .next();
+            if let Some(chip) = existing_chip {
+                chip.clone()
+            } else {
+                let air: &BitwiseOperationLookupAir<8> = inventory.next_air()?;
+                let chip = Arc::new(BitwiseOperationLookupChip::new(air.bus));
+                inventory.add_periphery_chip(chip.clone());
+                chip
+            }
+        };
+
+        for variant in SHA512_VARIANTS {
+            inventory.next_air::<Sha512VmAir>()?;
+            let sha512 = Sha512VmChip::new(
+                Sha512VmFiller::new(bitwise_lu.clone(), pointer_max_bits, variant),
+                mem_helper.clone(),
+            );
+            inventory.add_executor_chip(sha512);
+        }
+
+        inventory.next_air::<Sha512CompressVmAir>()?;
+        let sha512_compress = Sha512CompressVmChip::new(
+            Sha512CompressVmFiller::new(bitwise_lu, pointer_max_bits),
+            mem_helper,
+        );
+        inventory.add_executor_chip(sha512_compress);
+
+        Ok(())
+    }
+}
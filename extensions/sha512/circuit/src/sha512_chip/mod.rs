@@ -0,0 +1,96 @@
+//! Sha512 and Sha384 hashers. Handles full hashing with padding.
+//! variable length inputs read from VM memory.
+
+use openvm_circuit::arch::*;
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::SharedBitwiseOperationLookupChip, encoder::Encoder,
+};
+use openvm_instructions::riscv::RV32_CELL_BITS;
+use openvm_sha512_air::{Sha512FillerHelper, Sha512Variant, SHA512_BLOCK_BITS};
+use openvm_sha512_transpiler::Rv32Sha512Opcode;
+use sha2::{Digest, Sha384, Sha512};
+
+mod air;
+mod columns;
+mod execution;
+mod trace;
+
+pub use air::*;
+pub use columns::*;
+pub use trace::*;
+
+#[cfg(test)]
+mod tests;
+
+// ==== Constants for register/memory adapter ====
+/// Register reads to get dst, src, len
+pub(crate) const SHA512_REGISTER_READS: usize = 3;
+/// Number of cells to read in a single memory access
+pub(crate) const SHA512_READ_SIZE: usize = 32;
+/// Number of cells to write in a single memory access
+const SHA512_WRITE_SIZE: usize = 16;
+/// Maximum number of memory writes of the digest, for the 64-byte Sha512 digest
+const SHA512_MAX_WRITES: usize = 4;
+/// Number of rv32 cells read in a SHA512 block
+pub const SHA512_BLOCK_CELLS: usize = SHA512_BLOCK_BITS / RV32_CELL_BITS;
+/// Number of rows we will do a read on for each SHA512 block
+pub const SHA512_NUM_READ_ROWS: usize = SHA512_BLOCK_CELLS / SHA512_READ_SIZE;
+/// Maximum message length that this chip supports in bytes
+pub const SHA512_MAX_MESSAGE_LEN: usize = 1 << 29;
+
+pub type Sha512VmChip<F> = VmChipWrapper<F, Sha512VmFiller>;
+
+#[derive(derive_new::new, Clone)]
+pub struct Sha512VmExecutor {
+    pub offset: usize,
+    pub pointer_max_bits: usize,
+    pub variant: Sha512Variant,
+}
+
+pub struct Sha512VmFiller {
+    pub inner: Sha512FillerHelper,
+    pub padding_encoder: Encoder,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    pub pointer_max_bits: usize,
+}
+
+impl Sha512VmFiller {
+    pub fn new(
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+        pointer_max_bits: usize,
+        variant: Sha512Variant,
+    ) -> Self {
+        Self {
+            inner: variant.filler_helper(),
+            padding_encoder: Encoder::new(PaddingFlags::COUNT, 2, false),
+            bitwise_lookup_chip,
+            pointer_max_bits,
+        }
+    }
+}
+
+/// The opcode which is handled by the chip of `variant`
+pub fn sha512_opcode(variant: Sha512Variant) -> Rv32Sha512Opcode {
+    match variant {
+        Sha512Variant::Sha512 => Rv32Sha512Opcode::SHA512,
+        Sha512Variant::Sha384 => Rv32Sha512Opcode::SHA384,
+    }
+}
+
+/// Returns the digest of `input_message`. Only the first `variant.digest_len()` bytes are set.
+pub fn sha512_solve(
+    variant: Sha512Variant,
+    input_message: &[u8],
+) -> [u8; SHA512_MAX_WRITES * SHA512_WRITE_SIZE] {
+    let mut output = [0u8; SHA512_MAX_WRITES * SHA512_WRITE_SIZE];
+    let digest_len = variant.digest_len();
+    match variant {
+        Sha512Variant::Sha512 => {
+            output[..digest_len].copy_from_slice(Sha512::digest(input_message).as_ref())
+        }
+        Sha512Variant::Sha384 => {
+            output[..digest_len].copy_from_slice(Sha384::digest(input_message).as_ref())
+        }
+    }
+    output
+}
@@ -0,0 +1,276 @@
+use std::{array, sync::Arc};
+
+use hex::FromHex;
+use openvm_circuit::{
+    arch::{
+        testing::{
+            memory::gen_pointer, TestBuilder, TestChipHarness, VmChipTestBuilder,
+            BITWISE_OP_LOOKUP_BUS,
+        },
+        Arena, MatrixRecordArena, PreflightExecutor,
+    },
+    system::{memory::SharedMemoryHelper, SystemPort},
+    utils::get_random_message,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupAir, BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+    SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS},
+    LocalOpcode,
+};
+use openvm_sha512_air::{get_sha512_num_blocks, Sha512Variant, SHA512_BLOCK_U8S};
+use openvm_sha512_transpiler::Rv32Sha512Opcode;
+use openvm_stark_backend::{interaction::BusIndex, p3_field::FieldAlgebra};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{Sha512VmAir, Sha512VmChip, Sha512VmExecutor};
+use crate::{sha512_opcode, sha512_solve, Sha512VmFiller};
+
+type F = BabyBear;
+const SELF_BUS_IDX: BusIndex = 28;
+const MAX_INS_CAPACITY: usize = 4096;
+type Harness<RA> = TestChipHarness<F, Sha512VmExecutor, Sha512VmAir, Sha512VmChip<F>, RA>;
+
+fn create_harness_fields(
+    system_port: SystemPort,
+    bitwise_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    memory_helper: SharedMemoryHelper<F>,
+    address_bits: usize,
+    variant: Sha512Variant,
+) -> (Sha512VmAir, Sha512VmExecutor, Sha512VmChip<F>) {
+    let air = Sha512VmAir::new(
+        system_port,
+        bitwise_chip.bus(),
+        address_bits,
+        SELF_BUS_IDX,
+        variant,
+    );
+    let executor = Sha512VmExecutor::new(Rv32Sha512Opcode::CLASS_OFFSET, address_bits, variant);
+    let chip = Sha512VmChip::new(
+        Sha512VmFiller::new(bitwise_chip, address_bits, variant),
+        memory_helper,
+    );
+    (air, executor, chip)
+}
+
+fn create_harness<RA: Arena>(
+    tester: &mut VmChipTestBuilder<F>,
+    variant: Sha512Variant,
+) -> (
+    Harness<RA>,
+    (
+        BitwiseOperationLookupAir<RV32_CELL_BITS>,
+        SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    ),
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let (air, executor, chip) = create_harness_fields(
+        tester.system_port(),
+        bitwise_chip.clone(),
+        tester.memory_helper(),
+        tester.address_bits(),
+        variant,
+    );
+    let harness = Harness::<RA>::with_capacity(executor, air, chip, MAX_INS_CAPACITY);
+    (harness, (bitwise_chip.air, bitwise_chip))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn set_and_execute<RA: Arena, E: PreflightExecutor<F, RA>>(
+    tester: &mut impl TestBuilder<F>,
+    executor: &mut E,
+    arena: &mut RA,
+    rng: &mut StdRng,
+    variant: Sha512Variant,
+    message: Option<&[u8]>,
+    len: Option<usize>,
+) {
+    let len = len.unwrap_or(rng.gen_range(1..3000));
+    let tmp = get_random_message(rng, len);
+    let message: &[u8] = message.unwrap_or(&tmp);
+    let len = message.len();
+
+    let rd = gen_pointer(rng, 4);
+    let rs1 = gen_pointer(rng, 4);
+    let rs2 = gen_pointer(rng, 4);
+
+    let dst_ptr = gen_pointer(rng, 4);
+    let src_ptr = gen_pointer(rng, 4);
+    tester.write(1, rd, dst_ptr.to_le_bytes().map(F::from_canonical_u8));
+    tester.write(1, rs1, src_ptr.to_le_bytes().map(F::from_canonical_u8));
+    tester.write(1, rs2, len.to_le_bytes().map(F::from_canonical_u8));
+
+    // Adding random memory after the message
+    let num_blocks = get_sha512_num_blocks(len as u32) as usize;
+    for offset in (0..num_blocks * SHA512_BLOCK_U8S).step_by(4) {
+        let chunk: [F; 4] = array::from_fn(|i| {
+            if offset + i < message.len() {
+                F::from_canonical_u8(message[offset + i])
+            } else {
+                F::from_canonical_u8(rng.gen())
+            }
+        });
+
+        tester.write(RV32_MEMORY_AS as usize, src_ptr + offset, chunk);
+    }
+
+    tester.execute(
+        executor,
+        arena,
+        &Instruction::from_usize(sha512_opcode(variant).global_opcode(), [rd, rs1, rs2, 1, 2]),
+    );
+
+    let output = sha512_solve(variant, message);
+    let digest_len = variant.digest_len();
+    for offset in (0..digest_len).step_by(16) {
+        assert_eq!(
+            array::from_fn::<_, 16, _>(|i| F::from_canonical_u8(output[offset + i])),
+            tester.read::<16>(RV32_MEMORY_AS as usize, dst_ptr + offset)
+        );
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Randomly generate computations and execute, ensuring that the generated trace
+/// passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+fn rand_test(variant: Sha512Variant) {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_harness(&mut tester, variant);
+
+    let num_ops: usize = 10;
+    for _ in 0..num_ops {
+        set_and_execute(
+            &mut tester,
+            &mut harness.executor,
+            &mut harness.arena,
+            &mut rng,
+            variant,
+            None,
+            None,
+        );
+    }
+
+    let tester = tester
+        .build()
+        .load(harness)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rand_sha512_test() {
+    rand_test(Sha512Variant::Sha512);
+}
+
+#[test]
+fn rand_sha384_test() {
+    rand_test(Sha512Variant::Sha384);
+}
+
+fn edge_test_lengths(variant: Sha512Variant) {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_harness(&mut tester, variant);
+
+    let test_vectors = ["", "616263", "98c1c0bdb7d5fea9a88859f06c6c439f"];
+
+    for input in test_vectors.iter() {
+        let input = Vec::from_hex(input).unwrap();
+
+        set_and_execute(
+            &mut tester,
+            &mut harness.executor,
+            &mut harness.arena,
+            &mut rng,
+            variant,
+            Some(&input),
+            None,
+        );
+    }
+
+    // check every possible input length modulo 128
+    for i in 129..=256 {
+        set_and_execute(
+            &mut tester,
+            &mut harness.executor,
+            &mut harness.arena,
+            &mut rng,
+            variant,
+            None,
+            Some(i),
+        );
+    }
+
+    let tester = tester
+        .build()
+        .load(harness)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn sha512_edge_test_lengths() {
+    edge_test_lengths(Sha512Variant::Sha512);
+}
+
+#[test]
+fn sha384_edge_test_lengths() {
+    edge_test_lengths(Sha512Variant::Sha384);
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn execute_roundtrip_sanity_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, _) =
+        create_harness::<MatrixRecordArena<F>>(&mut tester, Sha512Variant::Sha512);
+
+    set_and_execute(
+        &mut tester,
+        &mut harness.executor,
+        &mut harness.arena,
+        &mut rng,
+        Sha512Variant::Sha512,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn sha512_solve_sanity_check() {
+    let expected = Vec::from_hex(
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+         2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+    )
+    .unwrap();
+    let output = sha512_solve(Sha512Variant::Sha512, b"abc");
+    assert_eq!(output.as_slice(), expected.as_slice());
+
+    let expected = Vec::from_hex(
+        "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed\
+         8086072ba1e7cc2358baeca134c825a7",
+    )
+    .unwrap();
+    let output = sha512_solve(Sha512Variant::Sha384, b"abc");
+    assert_eq!(&output[..48], expected.as_slice());
+    // The unused tail of the output buffer is never written back to memory
+    assert_eq!(output[48..], [0u8; 16]);
+}
@@ -0,0 +1,70 @@
+//! WARNING: the order of fields in the structs is important, do not change it
+
+use openvm_circuit::{
+    arch::ExecutionState,
+    system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols},
+};
+use openvm_circuit_primitives::AlignedBorrow;
+use openvm_instructions::riscv::RV32_REGISTER_NUM_LIMBS;
+use openvm_sha512_air::{Sha512DigestCols, Sha512RoundCols, SHA512_HASH_WORDS, SHA512_WORD_U8S};
+
+use super::SHA512_STATE_ACCESSES;
+use crate::sha512_chip::{SHA512_READ_SIZE, SHA512_REGISTER_READS};
+
+/// Every compression is a single block, whose first 20 rows are of type
+/// Sha512CompressVmRoundCols and whose last row is of type Sha512CompressVmDigestCols
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct Sha512CompressVmRoundCols<T> {
+    pub control: Sha512CompressVmControlCols<T>,
+    pub inner: Sha512RoundCols<T>,
+    pub read_aux: MemoryReadAuxCols<T>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct Sha512CompressVmDigestCols<T> {
+    pub control: Sha512CompressVmControlCols<T>,
+    pub inner: Sha512DigestCols<T>,
+
+    pub from_state: ExecutionState<T>,
+    /// As in [Sha512VmDigestCols](crate::Sha512VmDigestCols), the register reads are constrained
+    /// on the digest row
+    pub rd_ptr: T,
+    pub rs1_ptr: T,
+    pub rs2_ptr: T,
+    pub dst_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub state_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub block_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub register_reads_aux: [MemoryReadAuxCols<T>; SHA512_REGISTER_READS],
+    /// The state before the compression as read from memory, with the words in little-endian
+    /// byte order
+    pub prev_state: [[T; SHA512_WORD_U8S]; SHA512_HASH_WORDS],
+    /// The state is read and written in chunks of `SHA512_READ_SIZE` cells
+    pub state_reads_aux: [MemoryReadAuxCols<T>; SHA512_STATE_ACCESSES],
+    pub writes_aux: [MemoryWriteAuxCols<T, SHA512_READ_SIZE>; SHA512_STATE_ACCESSES],
+}
+
+/// These are the columns that are used on both round and digest rows
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct Sha512CompressVmControlCols<T> {
+    /// Note: We will use the buffer in `inner.message_schedule` as the block data
+    /// Need to keep timestamp and read_ptr since block reads don't have the necessary information
+    pub cur_timestamp: T,
+    pub read_ptr: T,
+}
+
+/// Width of the Sha512CompressVmControlCols
+pub const SHA512_COMPRESS_VM_CONTROL_WIDTH: usize = Sha512CompressVmControlCols::<u8>::width();
+/// Width of the Sha512CompressVmRoundCols
+pub const SHA512_COMPRESS_VM_ROUND_WIDTH: usize = Sha512CompressVmRoundCols::<u8>::width();
+/// Width of the Sha512CompressVmDigestCols
+pub const SHA512_COMPRESS_VM_DIGEST_WIDTH: usize = Sha512CompressVmDigestCols::<u8>::width();
+/// Width of the Sha512CompressVm trace
+pub const SHA512_COMPRESS_VM_WIDTH: usize =
+    if SHA512_COMPRESS_VM_ROUND_WIDTH > SHA512_COMPRESS_VM_DIGEST_WIDTH {
+        SHA512_COMPRESS_VM_ROUND_WIDTH
+    } else {
+        SHA512_COMPRESS_VM_DIGEST_WIDTH
+    };
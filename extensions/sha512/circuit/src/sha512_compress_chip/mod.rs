@@ -0,0 +1,94 @@
+//! Sha512 compression function applied to a 64-byte state and a 128-byte block in VM memory.
+//! Unlike [Sha512VmExecutor](crate::Sha512VmExecutor), it does no padding and starts from an
+//! arbitrary state, which is left to the guest so that streaming Sha512 and Sha384 hashers can be
+//! built on it.
+
+use openvm_circuit::arch::VmChipWrapper;
+use openvm_circuit_primitives::bitwise_op_lookup::SharedBitwiseOperationLookupChip;
+use openvm_instructions::riscv::RV32_CELL_BITS;
+use openvm_sha512_air::{
+    Sha512FillerHelper, Sha512Variant, SHA512_BLOCK_U8S, SHA512_HASH_WORDS, SHA512_WORD_U8S,
+};
+
+use crate::{
+    sha512_chip::{SHA512_READ_SIZE, SHA512_REGISTER_READS},
+    SHA512_NUM_READ_ROWS,
+};
+
+mod air;
+mod columns;
+mod execution;
+mod trace;
+
+pub use air::*;
+pub use columns::*;
+pub use trace::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of cells of the state
+pub const SHA512_STATE_CELLS: usize = SHA512_HASH_WORDS * SHA512_WORD_U8S;
+/// Number of memory accesses to read or write the state, each of [SHA512_READ_SIZE] cells
+const SHA512_STATE_ACCESSES: usize = SHA512_STATE_CELLS / SHA512_READ_SIZE;
+/// Amount the timestamp advances by: the register reads, the state reads, the block reads and the
+/// state writes
+const SHA512_COMPRESS_TIMESTAMP_DELTA: usize =
+    SHA512_REGISTER_READS + SHA512_STATE_ACCESSES + SHA512_NUM_READ_ROWS + SHA512_STATE_ACCESSES;
+
+pub type Sha512CompressVmChip<F> = VmChipWrapper<F, Sha512CompressVmFiller>;
+
+#[derive(derive_new::new, Clone, Copy)]
+pub struct Sha512CompressVmExecutor {
+    pub offset: usize,
+    pub pointer_max_bits: usize,
+}
+
+pub struct Sha512CompressVmFiller {
+    pub inner: Sha512FillerHelper,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    pub pointer_max_bits: usize,
+}
+
+impl Sha512CompressVmFiller {
+    pub fn new(
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+        pointer_max_bits: usize,
+    ) -> Self {
+        Self {
+            // The initial hash is not used, since every block starts from the state in memory
+            inner: Sha512Variant::Sha512.filler_helper(),
+            bitwise_lookup_chip,
+            pointer_max_bits,
+        }
+    }
+}
+
+/// Compresses `block` into `state`, where the state words are stored in memory in little-endian
+/// byte order.
+pub fn sha512_compress_solve(
+    state: [u8; SHA512_STATE_CELLS],
+    block: [u8; SHA512_BLOCK_U8S],
+) -> [u8; SHA512_STATE_CELLS] {
+    let prev_hash = state_to_words(state);
+    let new_hash = Sha512FillerHelper::get_block_hash(&prev_hash, &block);
+    let mut output = [0u8; SHA512_STATE_CELLS];
+    for (word, bytes) in new_hash
+        .iter()
+        .zip(output.chunks_exact_mut(SHA512_WORD_U8S))
+    {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    output
+}
+
+/// Converts a state as stored in memory into its words
+pub(crate) fn state_to_words(state: [u8; SHA512_STATE_CELLS]) -> [u64; SHA512_HASH_WORDS] {
+    std::array::from_fn(|i| {
+        u64::from_le_bytes(
+            state[i * SHA512_WORD_U8S..(i + 1) * SHA512_WORD_U8S]
+                .try_into()
+                .unwrap(),
+        )
+    })
+}
@@ -0,0 +1,301 @@
+use std::{array, borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::{
+    arch::{
+        testing::{
+            memory::gen_pointer, TestBuilder, TestChipHarness, VmChipTestBuilder,
+            BITWISE_OP_LOOKUP_BUS,
+        },
+        Arena, PreflightExecutor,
+    },
+    system::{memory::SharedMemoryHelper, SystemPort},
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupAir, BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+    SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS},
+    LocalOpcode,
+};
+use openvm_sha512_air::{Sha512Variant, SHA512_BLOCK_U8S, SHA512_ROWS_PER_BLOCK};
+use openvm_sha512_transpiler::Rv32Sha512CompressOpcode;
+use openvm_stark_backend::{
+    interaction::BusIndex,
+    p3_field::FieldAlgebra,
+    p3_matrix::{dense::DenseMatrix, Matrix},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{
+    sha512_compress_solve, Sha512CompressVmAir, Sha512CompressVmChip, Sha512CompressVmDigestCols,
+    Sha512CompressVmExecutor, Sha512CompressVmFiller, SHA512_COMPRESS_VM_DIGEST_WIDTH,
+    SHA512_STATE_CELLS,
+};
+use crate::{sha512_chip::SHA512_READ_SIZE, sha512_solve};
+
+type F = BabyBear;
+const SELF_BUS_IDX: BusIndex = 28;
+const MAX_INS_CAPACITY: usize = 128;
+type Harness<RA> =
+    TestChipHarness<F, Sha512CompressVmExecutor, Sha512CompressVmAir, Sha512CompressVmChip<F>, RA>;
+
+fn create_harness_fields(
+    system_port: SystemPort,
+    bitwise_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    memory_helper: SharedMemoryHelper<F>,
+    address_bits: usize,
+) -> (
+    Sha512CompressVmAir,
+    Sha512CompressVmExecutor,
+    Sha512CompressVmChip<F>,
+) {
+    let air = Sha512CompressVmAir::new(system_port, bitwise_chip.bus(), address_bits, SELF_BUS_IDX);
+    let executor =
+        Sha512CompressVmExecutor::new(Rv32Sha512CompressOpcode::CLASS_OFFSET, address_bits);
+    let chip = Sha512CompressVmChip::new(
+        Sha512CompressVmFiller::new(bitwise_chip, address_bits),
+        memory_helper,
+    );
+    (air, executor, chip)
+}
+
+fn create_harness<RA: Arena>(
+    tester: &mut VmChipTestBuilder<F>,
+) -> (
+    Harness<RA>,
+    (
+        BitwiseOperationLookupAir<RV32_CELL_BITS>,
+        SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    ),
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let (air, executor, chip) = create_harness_fields(
+        tester.system_port(),
+        bitwise_chip.clone(),
+        tester.memory_helper(),
+        tester.address_bits(),
+    );
+    let harness = Harness::<RA>::with_capacity(executor, air, chip, MAX_INS_CAPACITY);
+    (harness, (bitwise_chip.air, bitwise_chip))
+}
+
+fn write_bytes(tester: &mut impl TestBuilder<F>, ptr: usize, bytes: &[u8]) {
+    for (i, word) in bytes.chunks_exact(4).enumerate() {
+        tester.write::<4>(
+            RV32_MEMORY_AS as usize,
+            ptr + i * 4,
+            array::from_fn(|j| F::from_canonical_u8(word[j])),
+        );
+    }
+}
+
+/// Compresses `block` into `state`, which are random if not given. If `in_place` is set, the new
+/// state overwrites the previous one. Returns the new state.
+fn set_and_execute<RA: Arena, E: PreflightExecutor<F, RA>>(
+    tester: &mut impl TestBuilder<F>,
+    executor: &mut E,
+    arena: &mut RA,
+    rng: &mut StdRng,
+    state: Option<[u8; SHA512_STATE_CELLS]>,
+    block: Option<[u8; SHA512_BLOCK_U8S]>,
+    in_place: bool,
+) -> [u8; SHA512_STATE_CELLS] {
+    let state = state.unwrap_or_else(|| array::from_fn(|_| rng.gen()));
+    let block = block.unwrap_or_else(|| array::from_fn(|_| rng.gen()));
+
+    let rd = gen_pointer(rng, 4);
+    let rs1 = gen_pointer(rng, 4);
+    let rs2 = gen_pointer(rng, 4);
+
+    let state_ptr = gen_pointer(rng, 4);
+    let dst_ptr = if in_place {
+        state_ptr
+    } else {
+        gen_pointer(rng, 4)
+    };
+    let block_ptr = gen_pointer(rng, 4);
+    tester.write(1, rd, dst_ptr.to_le_bytes().map(F::from_canonical_u8));
+    tester.write(1, rs1, state_ptr.to_le_bytes().map(F::from_canonical_u8));
+    tester.write(1, rs2, block_ptr.to_le_bytes().map(F::from_canonical_u8));
+    write_bytes(tester, state_ptr, &state);
+    write_bytes(tester, block_ptr, &block);
+
+    tester.execute(
+        executor,
+        arena,
+        &Instruction::from_usize(
+            Rv32Sha512CompressOpcode::SHA512_COMPRESS.global_opcode(),
+            [
+                rd,
+                rs1,
+                rs2,
+                RV32_REGISTER_AS as usize,
+                RV32_MEMORY_AS as usize,
+            ],
+        ),
+    );
+
+    let output = sha512_compress_solve(state, block);
+    for (k, chunk) in output.chunks_exact(SHA512_READ_SIZE).enumerate() {
+        assert_eq!(
+            chunk
+                .iter()
+                .map(|&x| F::from_canonical_u8(x))
+                .collect::<Vec<_>>(),
+            tester
+                .read::<SHA512_READ_SIZE>(RV32_MEMORY_AS as usize, dst_ptr + k * SHA512_READ_SIZE)
+        );
+    }
+    output
+}
+
+/// The initial hash of `variant` in memory
+fn initial_state(variant: Sha512Variant) -> [u8; SHA512_STATE_CELLS] {
+    let initial_hash = variant.initial_hash();
+    array::from_fn(|i| initial_hash[i / 8].to_le_bytes()[i % 8])
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Randomly generate computations and execute, ensuring that the generated trace
+/// passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn rand_sha512_compress_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_harness(&mut tester);
+
+    // Includes a compression from the initial state. The 5 compressions take 105 rows, so the
+    // trace is padded with dummy rows.
+    set_and_execute(
+        &mut tester,
+        &mut harness.executor,
+        &mut harness.arena,
+        &mut rng,
+        Some(initial_state(Sha512Variant::Sha512)),
+        None,
+        false,
+    );
+    for _ in 0..4 {
+        set_and_execute(
+            &mut tester,
+            &mut harness.executor,
+            &mut harness.arena,
+            &mut rng,
+            None,
+            None,
+            false,
+        );
+    }
+
+    let tester = tester
+        .build()
+        .load(harness)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn sha512_compress_in_place_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_harness(&mut tester);
+
+    // Hash a message of several blocks the way a streaming hasher would
+    let mut state = initial_state(Sha512Variant::Sha384);
+    for _ in 0..7 {
+        state = set_and_execute(
+            &mut tester,
+            &mut harness.executor,
+            &mut harness.arena,
+            &mut rng,
+            Some(state),
+            None,
+            true,
+        );
+    }
+
+    let tester = tester
+        .build()
+        .load(harness)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// part of the trace and check that the chip throws the expected error.
+//////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn sha512_compress_negative_prev_state_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_harness(&mut tester);
+
+    set_and_execute(
+        &mut tester,
+        &mut harness.executor,
+        &mut harness.arena,
+        &mut rng,
+        None,
+        None,
+        false,
+    );
+
+    // Start the compression from a state other than the one read from memory
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let width = trace.width();
+        let cols: &mut Sha512CompressVmDigestCols<F> = trace.values
+            [(SHA512_ROWS_PER_BLOCK - 1) * width..][..SHA512_COMPRESS_VM_DIGEST_WIDTH]
+            .borrow_mut();
+        cols.inner.prev_hash[0][0] += F::ONE;
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(harness, modify_trace)
+        .load_periphery(bitwise)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn sha512_compress_solve_sanity_check() {
+    // The padded message "abc" is a single block
+    let mut block = [0u8; SHA512_BLOCK_U8S];
+    block[..3].copy_from_slice(b"abc");
+    block[3] = 0x80;
+    block[SHA512_BLOCK_U8S - 1] = 24;
+
+    // Sha384 is a truncation of the state compressed from its own initial hash
+    for variant in [Sha512Variant::Sha512, Sha512Variant::Sha384] {
+        let state = sha512_compress_solve(initial_state(variant), block);
+        // The digest is the state with the words in big-endian byte order
+        let digest: [u8; SHA512_STATE_CELLS] = array::from_fn(|i| state[i - i % 8 + 7 - i % 8]);
+        let digest_len = variant.digest_len();
+        assert_eq!(
+            digest[..digest_len],
+            sha512_solve(variant, b"abc")[..digest_len]
+        );
+    }
+}
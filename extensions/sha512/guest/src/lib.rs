@@ -0,0 +1,157 @@
+#![no_std]
+
+#[cfg(target_os = "zkvm")]
+use openvm_platform::alloc::AlignedBuf;
+
+/// This is custom-0 defined in RISC-V spec document
+pub const OPCODE: u8 = 0x0b;
+pub const SHA512_FUNCT3: u8 = 0b100;
+pub const SHA512_FUNCT7: u8 = 0x3;
+pub const SHA384_FUNCT7: u8 = 0x4;
+pub const SHA512_COMPRESS_FUNCT7: u8 = 0xa;
+
+/// Number of bytes in a sha512 digest
+pub const SHA512_OUTPUT_LEN: usize = 64;
+/// Number of bytes in a sha384 digest
+pub const SHA384_OUTPUT_LEN: usize = 48;
+
+/// Native hook for sha512
+///
+/// # Safety
+///
+/// The VM accepts the preimage by pointer and length, and writes the
+/// 64-byte hash.
+/// - `bytes` must point to an input buffer at least `len` long.
+/// - `output` must point to a buffer that is at least 64-bytes long.
+///
+/// [`sha2`]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+pub extern "C" fn zkvm_sha512_impl(bytes: *const u8, len: usize, output: *mut u8) {
+    // SAFETY: assuming safety assumptions of the inputs, the alignment of the buffers is handled
+    unsafe {
+        with_aligned_buffers(bytes, len, output, SHA512_OUTPUT_LEN, |bytes, output| {
+            __native_sha512(bytes, len, output)
+        });
+    }
+}
+
+/// Native hook for sha384
+///
+/// # Safety
+///
+/// The VM accepts the preimage by pointer and length, and writes the
+/// 48-byte hash.
+/// - `bytes` must point to an input buffer at least `len` long.
+/// - `output` must point to a buffer that is at least 48-bytes long.
+///
+/// [`sha2`]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+pub extern "C" fn zkvm_sha384_impl(bytes: *const u8, len: usize, output: *mut u8) {
+    // SAFETY: assuming safety assumptions of the inputs, the alignment of the buffers is handled
+    unsafe {
+        with_aligned_buffers(bytes, len, output, SHA384_OUTPUT_LEN, |bytes, output| {
+            __native_sha384(bytes, len, output)
+        });
+    }
+}
+
+/// Calls `f` on copies of `bytes` and `output` that are aligned to 4 bytes, when they are not.
+///
+/// # Safety
+///
+/// - `bytes` must point to an input buffer at least `len` long.
+/// - `output` must point to a buffer that is at least `output_len` bytes long.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+unsafe fn with_aligned_buffers(
+    bytes: *const u8,
+    len: usize,
+    output: *mut u8,
+    output_len: usize,
+    f: impl FnOnce(*const u8, *mut u8),
+) {
+    // The minimum alignment required for the input and output buffers
+    const MIN_ALIGN: usize = 4;
+    // The preferred alignment for the input buffer, since the input is read in chunks of 32 bytes
+    const INPUT_ALIGN: usize = 32;
+    // The preferred alignment for the output buffer, since the output is written in chunks of 16
+    // bytes
+    const OUTPUT_ALIGN: usize = 16;
+    let aligned_in =
+        (bytes as usize % MIN_ALIGN != 0).then(|| AlignedBuf::new(bytes, len, INPUT_ALIGN));
+    let bytes = aligned_in
+        .as_ref()
+        .map_or(bytes, |buf| buf.ptr as *const u8);
+    if output as usize % MIN_ALIGN != 0 {
+        let aligned_out = AlignedBuf::uninit(output_len, OUTPUT_ALIGN);
+        f(bytes, aligned_out.ptr);
+        core::ptr::copy_nonoverlapping(aligned_out.ptr as *const u8, output, output_len);
+    } else {
+        f(bytes, output);
+    }
+}
+
+/// sha512 intrinsic binding
+///
+/// # Safety
+///
+/// The VM accepts the preimage by pointer and length, and writes the
+/// 64-byte hash.
+/// - `bytes` must point to an input buffer at least `len` long.
+/// - `output` must point to a buffer that is at least 64-bytes long.
+/// - `bytes` and `output` must be 4-byte aligned.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+fn __native_sha512(bytes: *const u8, len: usize, output: *mut u8) {
+    openvm_platform::custom_insn_r!(opcode = OPCODE, funct3 = SHA512_FUNCT3, funct7 = SHA512_FUNCT7, rd = In output, rs1 = In bytes, rs2 = In len);
+}
+
+/// sha384 intrinsic binding
+///
+/// # Safety
+///
+/// The VM accepts the preimage by pointer and length, and writes the
+/// 48-byte hash.
+/// - `bytes` must point to an input buffer at least `len` long.
+/// - `output` must point to a buffer that is at least 48-bytes long.
+/// - `bytes` and `output` must be 4-byte aligned.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+fn __native_sha384(bytes: *const u8, len: usize, output: *mut u8) {
+    openvm_platform::custom_insn_r!(opcode = OPCODE, funct3 = SHA512_FUNCT3, funct7 = SHA384_FUNCT7, rd = In output, rs1 = In bytes, rs2 = In len);
+}
+
+/// sha512 compression function intrinsic binding, for use by hashers which do their own padding.
+/// It is shared by sha512 and sha384, which only differ in the initial state and the length of
+/// the digest.
+///
+/// The VM reads the hash state from `prev_state` and the 128-byte message block from `block`,
+/// and writes the state after compressing the block to `new_state`. The state is the eight
+/// 64-bit words of the intermediate hash value, in native (little-endian) byte order, as in
+/// [FIPS 180-4](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf).
+///
+/// # Safety
+///
+/// - `prev_state` and `new_state` must point to buffers of 8 words, and may be equal.
+/// - `block` must point to a buffer at least 128-bytes long.
+/// - `prev_state`, `block` and `new_state` must be 4-byte aligned.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+pub unsafe fn native_sha512_compress(
+    prev_state: *const u64,
+    block: *const u8,
+    new_state: *mut u64,
+) {
+    openvm_platform::custom_insn_r!(
+        opcode = OPCODE,
+        funct3 = SHA512_FUNCT3,
+        funct7 = SHA512_COMPRESS_FUNCT7,
+        rd = In new_state,
+        rs1 = In prev_state,
+        rs2 = In block
+    );
+}
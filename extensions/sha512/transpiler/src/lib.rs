@@ -0,0 +1,69 @@
+use openvm_instructions::{riscv::RV32_MEMORY_AS, LocalOpcode, VmOpcode};
+use openvm_instructions_derive::LocalOpcode;
+use openvm_sha512_guest::{
+    OPCODE, SHA384_FUNCT7, SHA512_COMPRESS_FUNCT7, SHA512_FUNCT3, SHA512_FUNCT7,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_transpiler::{
+    util::{from_r_type, local_opcode_name},
+    TranspilerExtension, TranspilerOutput,
+};
+use rrs_lib::instruction_formats::RType;
+use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator};
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
+)]
+#[opcode_offset = 0x330]
+#[repr(usize)]
+pub enum Rv32Sha512Opcode {
+    SHA512,
+    SHA384,
+}
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
+)]
+#[opcode_offset = 0x332]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32Sha512CompressOpcode {
+    SHA512_COMPRESS,
+}
+
+#[derive(Default)]
+pub struct Sha512TranspilerExtension;
+
+impl<F: PrimeField32> TranspilerExtension<F> for Sha512TranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        if (opcode, funct3) != (OPCODE, SHA512_FUNCT3) {
+            return None;
+        }
+        let dec_insn = RType::new(instruction_u32);
+        let global_opcode = match dec_insn.funct7 as u8 {
+            SHA512_FUNCT7 => Rv32Sha512Opcode::SHA512.global_opcode(),
+            SHA384_FUNCT7 => Rv32Sha512Opcode::SHA384.global_opcode(),
+            SHA512_COMPRESS_FUNCT7 => Rv32Sha512CompressOpcode::SHA512_COMPRESS.global_opcode(),
+            _ => return None,
+        };
+        let instruction = from_r_type(
+            global_opcode.as_usize(),
+            RV32_MEMORY_AS as usize,
+            &dec_insn,
+            true,
+        );
+        Some(TranspilerOutput::one_to_one(instruction))
+    }
+
+    fn opcode_name(&self, opcode: VmOpcode) -> Option<String> {
+        local_opcode_name(opcode, Rv32Sha512Opcode::iter(), 1)
+            .or_else(|| local_opcode_name(opcode, Rv32Sha512CompressOpcode::iter(), 1))
+    }
+}
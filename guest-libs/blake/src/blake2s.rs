@@ -0,0 +1,88 @@
+use crate::{blake2s_compress, BLAKE_BLOCK_LEN, BLAKE_IV};
+
+/// Maximum length in bytes of a BLAKE2s key
+pub const BLAKE2S_MAX_KEY_LEN: usize = 32;
+
+/// The BLAKE2s-256 hash function.
+pub fn blake2s(input: &[u8]) -> [u8; 32] {
+    let mut hasher = Blake2s::new();
+    hasher.update(input);
+    hasher.finalize()
+}
+
+/// A streaming BLAKE2s-256 hasher, optionally keyed.
+#[derive(Clone, Debug)]
+pub struct Blake2s {
+    h: [u32; 8],
+    /// Number of bytes compressed so far
+    t: u64,
+    /// The last block of the input. It is only compressed once more input arrives, since the
+    /// last block is compressed with the finalization flag.
+    buffer: [u8; BLAKE_BLOCK_LEN],
+    buffer_len: usize,
+}
+
+impl Default for Blake2s {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Blake2s {
+    /// Creates a new hasher with an empty input.
+    pub fn new() -> Self {
+        Self::new_keyed(&[])
+    }
+
+    /// Creates a new hasher for the keyed hash (MAC) with `key`, which is empty for the unkeyed
+    /// hash.
+    ///
+    /// # Panics
+    /// If the key is longer than [BLAKE2S_MAX_KEY_LEN] bytes.
+    pub fn new_keyed(key: &[u8]) -> Self {
+        assert!(key.len() <= BLAKE2S_MAX_KEY_LEN, "BLAKE2s key is too long");
+        let mut h = BLAKE_IV;
+        // Parameter block with a digest length of 32 bytes, fanout and depth 1
+        h[0] ^= 0x01010000 ^ ((key.len() as u32) << 8) ^ 32;
+        let mut hasher = Self {
+            h,
+            t: 0,
+            buffer: [0; BLAKE_BLOCK_LEN],
+            buffer_len: 0,
+        };
+        // The key is padded to a full block, which is the first block of the input
+        if !key.is_empty() {
+            hasher.buffer[..key.len()].copy_from_slice(key);
+            hasher.buffer_len = BLAKE_BLOCK_LEN;
+        }
+        hasher
+    }
+
+    /// Appends `input` to the message. Can be called multiple times.
+    pub fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            if self.buffer_len == BLAKE_BLOCK_LEN {
+                self.t += BLAKE_BLOCK_LEN as u64;
+                blake2s_compress(&mut self.h, &self.buffer, self.t, [0, 0]);
+                self.buffer_len = 0;
+            }
+            let len = input.len().min(BLAKE_BLOCK_LEN - self.buffer_len);
+            self.buffer[self.buffer_len..self.buffer_len + len].copy_from_slice(&input[..len]);
+            self.buffer_len += len;
+            input = &input[len..];
+        }
+    }
+
+    /// Returns the BLAKE2s-256 hash of all the input passed to [`Self::update`].
+    pub fn finalize(mut self) -> [u8; 32] {
+        self.t += self.buffer_len as u64;
+        self.buffer[self.buffer_len..].fill(0);
+        blake2s_compress(&mut self.h, &self.buffer, self.t, [u32::MAX, 0]);
+
+        let mut output = [0u8; 32];
+        for (bytes, word) in output.chunks_exact_mut(4).zip(self.h) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        output
+    }
+}
@@ -0,0 +1,251 @@
+//! BLAKE3 in its hash, keyed hash and extendable output modes, following the reference
+//! implementation: the input is split into chunks of [BLAKE3_CHUNK_LEN] bytes, whose chaining
+//! values are merged into a binary tree with a stack.
+
+use crate::{blake3_compress, BLAKE_BLOCK_LEN, BLAKE_IV};
+
+/// Number of bytes of a BLAKE3 chunk
+pub const BLAKE3_CHUNK_LEN: usize = 1024;
+/// Number of bytes of a BLAKE3 key
+pub const BLAKE3_KEY_LEN: usize = 32;
+
+/// Domain separation flags of the BLAKE3 compression function
+pub const BLAKE3_CHUNK_START: u32 = 1 << 0;
+pub const BLAKE3_CHUNK_END: u32 = 1 << 1;
+pub const BLAKE3_PARENT: u32 = 1 << 2;
+pub const BLAKE3_ROOT: u32 = 1 << 3;
+pub const BLAKE3_KEYED_HASH: u32 = 1 << 4;
+
+/// The stack holds at most one chaining value for each level of the tree, and there are at most
+/// `2^64` chunks.
+const CV_STACK_LEN: usize = 54;
+
+/// The BLAKE3 hash function with a 32-byte output.
+pub fn blake3(input: &[u8]) -> [u8; 32] {
+    let mut hasher = Blake3::new();
+    hasher.update(input);
+    hasher.finalize()
+}
+
+fn first_8_words(words: [u32; 16]) -> [u32; 8] {
+    words[..8].try_into().unwrap()
+}
+
+/// The inputs of a compression whose flags are not yet final: it gives the chaining value of a
+/// node, or the output of the root node.
+#[derive(Clone, Copy)]
+struct Output {
+    input_cv: [u32; 8],
+    block: [u8; BLAKE_BLOCK_LEN],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8_words(blake3_compress(
+            &self.input_cv,
+            &self.block,
+            self.counter,
+            self.block_len,
+            self.flags,
+        ))
+    }
+
+    /// Fills `output` with the extendable output of the root node, which compresses the same
+    /// block with an increasing counter.
+    fn root_output_bytes(&self, output: &mut [u8]) {
+        for (counter, out_block) in output.chunks_mut(BLAKE_BLOCK_LEN).enumerate() {
+            let words = blake3_compress(
+                &self.input_cv,
+                &self.block,
+                counter as u64,
+                self.block_len,
+                self.flags | BLAKE3_ROOT,
+            );
+            for (bytes, word) in out_block.chunks_mut(4).zip(words) {
+                bytes.copy_from_slice(&word.to_le_bytes()[..bytes.len()]);
+            }
+        }
+    }
+
+    fn parent(left_cv: &[u32; 8], right_cv: &[u32; 8], key: &[u32; 8], flags: u32) -> Self {
+        let mut block = [0u8; BLAKE_BLOCK_LEN];
+        for (bytes, word) in block
+            .chunks_exact_mut(4)
+            .zip(left_cv.iter().chain(right_cv))
+        {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        Self {
+            input_cv: *key,
+            block,
+            counter: 0,
+            block_len: BLAKE_BLOCK_LEN as u32,
+            flags: BLAKE3_PARENT | flags,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ChunkState {
+    cv: [u32; 8],
+    chunk_counter: u64,
+    block: [u8; BLAKE_BLOCK_LEN],
+    block_len: usize,
+    blocks_compressed: usize,
+    flags: u32,
+}
+
+impl ChunkState {
+    fn new(key: &[u32; 8], chunk_counter: u64, flags: u32) -> Self {
+        Self {
+            cv: *key,
+            chunk_counter,
+            block: [0; BLAKE_BLOCK_LEN],
+            block_len: 0,
+            blocks_compressed: 0,
+            flags,
+        }
+    }
+
+    fn len(&self) -> usize {
+        BLAKE_BLOCK_LEN * self.blocks_compressed + self.block_len
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            BLAKE3_CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            // The last block of the chunk is only compressed in `output`, with the CHUNK_END flag
+            if self.block_len == BLAKE_BLOCK_LEN {
+                self.cv = first_8_words(blake3_compress(
+                    &self.cv,
+                    &self.block,
+                    self.chunk_counter,
+                    BLAKE_BLOCK_LEN as u32,
+                    self.flags | self.start_flag(),
+                ));
+                self.blocks_compressed += 1;
+                self.block = [0; BLAKE_BLOCK_LEN];
+                self.block_len = 0;
+            }
+            let len = input.len().min(BLAKE_BLOCK_LEN - self.block_len);
+            self.block[self.block_len..self.block_len + len].copy_from_slice(&input[..len]);
+            self.block_len += len;
+            input = &input[len..];
+        }
+    }
+
+    fn output(&self) -> Output {
+        Output {
+            input_cv: self.cv,
+            block: self.block,
+            counter: self.chunk_counter,
+            block_len: self.block_len as u32,
+            flags: self.flags | self.start_flag() | BLAKE3_CHUNK_END,
+        }
+    }
+}
+
+/// A streaming BLAKE3 hasher, in hash or keyed hash mode, with extendable output.
+#[derive(Clone, Debug)]
+pub struct Blake3 {
+    chunk_state: ChunkState,
+    key: [u32; 8],
+    /// Chaining values of the complete subtrees on the left of the current chunk
+    cv_stack: [[u32; 8]; CV_STACK_LEN],
+    cv_stack_len: usize,
+    flags: u32,
+}
+
+impl Default for Blake3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Blake3 {
+    fn new_internal(key: [u32; 8], flags: u32) -> Self {
+        Self {
+            chunk_state: ChunkState::new(&key, 0, flags),
+            key,
+            cv_stack: [[0; 8]; CV_STACK_LEN],
+            cv_stack_len: 0,
+            flags,
+        }
+    }
+
+    /// Creates a new hasher with an empty input.
+    pub fn new() -> Self {
+        Self::new_internal(BLAKE_IV, 0)
+    }
+
+    /// Creates a new hasher for the keyed hash (MAC) with `key`.
+    pub fn new_keyed(key: &[u8; BLAKE3_KEY_LEN]) -> Self {
+        let key = core::array::from_fn(|i| {
+            u32::from_le_bytes(key[4 * i..4 * (i + 1)].try_into().unwrap())
+        });
+        Self::new_internal(key, BLAKE3_KEYED_HASH)
+    }
+
+    /// Merges the chaining value of a new chunk with the complete subtrees on its left. After
+    /// `total_chunks` chunks, the number of trailing zeros of `total_chunks` is the number of
+    /// subtrees to merge.
+    fn add_chunk_chaining_value(&mut self, mut new_cv: [u32; 8], mut total_chunks: u64) {
+        while total_chunks & 1 == 0 {
+            self.cv_stack_len -= 1;
+            new_cv = Output::parent(
+                &self.cv_stack[self.cv_stack_len],
+                &new_cv,
+                &self.key,
+                self.flags,
+            )
+            .chaining_value();
+            total_chunks >>= 1;
+        }
+        self.cv_stack[self.cv_stack_len] = new_cv;
+        self.cv_stack_len += 1;
+    }
+
+    /// Appends `input` to the message. Can be called multiple times.
+    pub fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            // A complete chunk is only merged into the tree once more input arrives, since the
+            // last chunk may be the root
+            if self.chunk_state.len() == BLAKE3_CHUNK_LEN {
+                let chunk_cv = self.chunk_state.output().chaining_value();
+                let total_chunks = self.chunk_state.chunk_counter + 1;
+                self.add_chunk_chaining_value(chunk_cv, total_chunks);
+                self.chunk_state = ChunkState::new(&self.key, total_chunks, self.flags);
+            }
+            let len = input.len().min(BLAKE3_CHUNK_LEN - self.chunk_state.len());
+            self.chunk_state.update(&input[..len]);
+            input = &input[len..];
+        }
+    }
+
+    /// Fills `output` with the extendable output of all the input passed to [`Self::update`].
+    /// Its first 32 bytes are the hash.
+    pub fn finalize_xof(&self, output: &mut [u8]) {
+        let mut root = self.chunk_state.output();
+        for cv in self.cv_stack[..self.cv_stack_len].iter().rev() {
+            root = Output::parent(cv, &root.chaining_value(), &self.key, self.flags);
+        }
+        root.root_output_bytes(output);
+    }
+
+    /// Returns the BLAKE3 hash of all the input passed to [`Self::update`].
+    pub fn finalize(self) -> [u8; 32] {
+        let mut output = [0u8; 32];
+        self.finalize_xof(&mut output);
+        output
+    }
+}
@@ -0,0 +1,82 @@
+//! Portable implementation of the compression functions, used when not running as an OpenVM
+//! guest. It is not intended to be performant.
+
+use crate::BLAKE_IV;
+
+const BLAKE2S_SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+const BLAKE3_MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+const BLAKE3_ROUNDS: usize = 7;
+
+fn g(v: &mut [u32; 16], [a, b, c, d]: [usize; 4], mx: u32, my: u32) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(mx);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(12);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(my);
+    v[d] = (v[d] ^ v[a]).rotate_right(8);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(7);
+}
+
+/// One round on the columns and then the diagonals of the state, where `m` is the message
+/// already permuted for the round.
+fn round(v: &mut [u32; 16], m: &[u32; 16]) {
+    g(v, [0, 4, 8, 12], m[0], m[1]);
+    g(v, [1, 5, 9, 13], m[2], m[3]);
+    g(v, [2, 6, 10, 14], m[4], m[5]);
+    g(v, [3, 7, 11, 15], m[6], m[7]);
+    g(v, [0, 5, 10, 15], m[8], m[9]);
+    g(v, [1, 6, 11, 12], m[10], m[11]);
+    g(v, [2, 7, 8, 13], m[12], m[13]);
+    g(v, [3, 4, 9, 14], m[14], m[15]);
+}
+
+pub fn blake2s_compress(h: &mut [u32; 8], m: &[u32; 16], t: u64, f: [u32; 2]) {
+    let mut v = [0u32; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&BLAKE_IV);
+    v[12] ^= t as u32;
+    v[13] ^= (t >> 32) as u32;
+    v[14] ^= f[0];
+    v[15] ^= f[1];
+    for sigma in &BLAKE2S_SIGMA {
+        round(&mut v, &sigma.map(|i| m[i]));
+    }
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
+
+pub fn blake3_compress(
+    cv: &[u32; 8],
+    m: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let mut v = [0u32; 16];
+    v[..8].copy_from_slice(cv);
+    v[8..12].copy_from_slice(&BLAKE_IV[..4]);
+    v[12..].copy_from_slice(&[counter as u32, (counter >> 32) as u32, block_len, flags]);
+    let mut m = *m;
+    for _ in 0..BLAKE3_ROUNDS {
+        round(&mut v, &m);
+        m = BLAKE3_MSG_PERMUTATION.map(|i| m[i]);
+    }
+    for i in 0..8 {
+        v[i] ^= v[i + 8];
+        v[i + 8] ^= cv[i];
+    }
+    v
+}
@@ -0,0 +1,72 @@
+#![no_std]
+//! BLAKE2s and BLAKE3 hash functions, which use the BLAKE compression intrinsics when run as an
+//! OpenVM guest.
+//!
+//! Both one-shot functions and streaming hashers are built on [blake2s_compress] and
+//! [blake3_compress], which are also exposed for protocols that manage counters and flags
+//! themselves.
+
+mod blake2s;
+mod blake3;
+#[cfg(not(target_os = "zkvm"))]
+mod host;
+
+pub use blake2s::*;
+pub use blake3::*;
+
+/// The initialization vector of BLAKE2s and BLAKE3, which is also the SHA-256 initial hash value.
+pub const BLAKE_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+/// Number of bytes of a message block of the compression functions
+pub const BLAKE_BLOCK_LEN: usize = 64;
+
+/// The BLAKE2s compression function `F`, as defined in
+/// [RFC 7693](https://datatracker.ietf.org/doc/html/rfc7693#section-3.2). Compresses `block` into
+/// the chaining value `h`, given the byte counter `t` and the finalization flags `f`.
+pub fn blake2s_compress(h: &mut [u32; 8], block: &[u8; BLAKE_BLOCK_LEN], t: u64, f: [u32; 2]) {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        host::blake2s_compress(h, &block_words(block), t, f);
+    }
+    #[cfg(target_os = "zkvm")]
+    {
+        let mut state = [0u32; openvm_blake_guest::BLAKE_STATE_WORDS];
+        state[..8].copy_from_slice(h);
+        state[8..].copy_from_slice(&[t as u32, (t >> 32) as u32, f[0], f[1]]);
+        openvm_blake_guest::native_blake2s_compress(&state, &block_words(block), h);
+    }
+}
+
+/// The BLAKE3 compression function, as defined in the
+/// [BLAKE3 specification](https://github.com/BLAKE3-team/BLAKE3-specs/blob/master/blake3.pdf).
+/// Returns all 16 output words: the first 8 are the new chaining value, and all of them are used
+/// for extendable output.
+pub fn blake3_compress(
+    cv: &[u32; 8],
+    block: &[u8; BLAKE_BLOCK_LEN],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        host::blake3_compress(cv, &block_words(block), counter, block_len, flags)
+    }
+    #[cfg(target_os = "zkvm")]
+    {
+        let mut state = [0u32; openvm_blake_guest::BLAKE_STATE_WORDS];
+        state[..8].copy_from_slice(cv);
+        state[8..].copy_from_slice(&[counter as u32, (counter >> 32) as u32, block_len, flags]);
+        let mut output = [0u32; 16];
+        openvm_blake_guest::native_blake3_compress(&state, &block_words(block), &mut output);
+        output
+    }
+}
+
+/// The little-endian words of a block. The intrinsics read the block with word-aligned memory
+/// accesses, so this also copies the block to an aligned buffer.
+#[inline(always)]
+fn block_words(block: &[u8; BLAKE_BLOCK_LEN]) -> [u32; 16] {
+    core::array::from_fn(|i| u32::from_le_bytes(block[4 * i..4 * (i + 1)].try_into().unwrap()))
+}
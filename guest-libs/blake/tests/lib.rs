@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod tests {
+    use blake2::{
+        digest::{KeyInit, Mac},
+        Blake2s256, Blake2sMac256, Digest,
+    };
+    use eyre::Result;
+    use openvm_blake::{blake2s, blake3, Blake2s, Blake3};
+    use openvm_blake_circuit::{BlakeRv32Builder, BlakeRv32Config};
+    use openvm_blake_transpiler::BlakeTranspilerExtension;
+    use openvm_circuit::utils::air_test_with_min_segments;
+    use openvm_instructions::exe::VmExe;
+    use openvm_rv32im_transpiler::{
+        Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+    };
+    use openvm_stark_sdk::{openvm_stark_backend::p3_field::FieldAlgebra, p3_baby_bear::BabyBear};
+    use openvm_toolchain_tests::{build_example_program_at_path, get_programs_dir};
+    use openvm_transpiler::{transpiler::Transpiler, FromElf};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    type F = BabyBear;
+
+    /// Lengths around the block and chunk boundaries, up to a tree with several levels
+    const MESSAGE_LENS: [usize; 9] = [0, 1, 63, 64, 65, 1024, 1025, 2049, 5000];
+    /// Length of the extendable output, which spans several output blocks
+    const XOF_LEN: usize = 131;
+
+    fn to_io(bytes: &[u8]) -> Vec<F> {
+        bytes.iter().copied().map(F::from_canonical_u8).collect()
+    }
+
+    /// The BLAKE2s and BLAKE3 hashes of `message`, unkeyed then keyed, followed by the extendable
+    /// output of BLAKE3, computed with the reference implementations.
+    fn expected_digests(key: &[u8; 32], message: &[u8]) -> Vec<u8> {
+        let mut blake2s_mac = <Blake2sMac256 as KeyInit>::new_from_slice(key).unwrap();
+        Mac::update(&mut blake2s_mac, message);
+        let mut xof = [0u8; XOF_LEN];
+        ::blake3::Hasher::new()
+            .update(message)
+            .finalize_xof()
+            .fill(&mut xof);
+
+        let mut digests = Blake2s256::digest(message).to_vec();
+        digests.extend_from_slice(::blake3::hash(message).as_bytes());
+        digests.extend_from_slice(&blake2s_mac.finalize().into_bytes());
+        digests.extend_from_slice(::blake3::keyed_hash(key, message).as_bytes());
+        digests.extend_from_slice(&xof);
+        digests
+    }
+
+    #[test]
+    fn test_blake() -> Result<()> {
+        let config = BlakeRv32Config::default();
+        let elf =
+            build_example_program_at_path(get_programs_dir!("tests/programs"), "blake", &config)?;
+        let openvm_exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension)
+                .with_extension(BlakeTranspilerExtension),
+        )?;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let key: [u8; 32] = rng.gen();
+        let mut io = vec![to_io(&key)];
+        // The guest reads the same number of messages, `NUM_MESSAGES`
+        for len in [0, 1, 64, 65, 1025] {
+            let message: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            io.push(to_io(&message));
+            io.push(to_io(&expected_digests(&key, &message)));
+        }
+
+        air_test_with_min_segments(BlakeRv32Builder, config, openvm_exe, io, 1);
+        Ok(())
+    }
+
+    /// The host implementation must agree with the reference implementations.
+    #[test]
+    fn test_blake_host() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let key: [u8; 32] = rng.gen();
+        for len in MESSAGE_LENS {
+            let message: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let expected = expected_digests(&key, &message);
+
+            // Streaming updates, split at an arbitrary point
+            let split = rng.gen_range(0..=len);
+            let mut blake2s_hasher = Blake2s::new_keyed(&key);
+            blake2s_hasher.update(&message[..split]);
+            blake2s_hasher.update(&message[split..]);
+            let mut blake3_hasher = Blake3::new_keyed(&key);
+            blake3_hasher.update(&message[..split]);
+            blake3_hasher.update(&message[split..]);
+            let mut xof = [0u8; XOF_LEN];
+            let mut xof_hasher = Blake3::new();
+            xof_hasher.update(&message);
+            xof_hasher.finalize_xof(&mut xof);
+
+            assert_eq!(blake2s(&message), expected[..32], "length {len}");
+            assert_eq!(blake3(&message), expected[32..64], "length {len}");
+            assert_eq!(blake2s_hasher.finalize(), expected[64..96], "length {len}");
+            assert_eq!(blake3_hasher.finalize(), expected[96..128], "length {len}");
+            assert_eq!(xof, expected[128..], "length {len}");
+        }
+    }
+
+    /// Keys shorter than the maximum are padded to a full block.
+    #[test]
+    fn test_blake2s_short_key() {
+        let key = b"key";
+        let message = b"The quick brown fox jumps over the lazy dog";
+        let mut expected = <Blake2sMac256 as KeyInit>::new_from_slice(key).unwrap();
+        Mac::update(&mut expected, message);
+
+        let mut hasher = Blake2s::new_keyed(key);
+        hasher.update(message);
+        assert_eq!(hasher.finalize(), expected.finalize().into_bytes()[..]);
+    }
+}
@@ -0,0 +1,55 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::hint::black_box;
+
+use openvm::io::read_vec;
+use openvm_blake::{blake2s, blake3, Blake2s, Blake3};
+
+openvm::entry!(main);
+
+/// Must match the number of messages written by the host
+const NUM_MESSAGES: usize = 5;
+const XOF_LEN: usize = 131;
+
+pub fn main() {
+    let key: [u8; 32] = read_vec().try_into().unwrap();
+
+    for _ in 0..NUM_MESSAGES {
+        // The message, followed by its BLAKE2s and BLAKE3 hashes, unkeyed then keyed, and the
+        // extendable output of BLAKE3
+        let message = read_vec();
+        let expected = read_vec();
+        let (hashes, expected_xof) = expected.split_at(4 * 32);
+
+        if blake2s(black_box(&message)) != hashes[..32] {
+            panic!();
+        }
+        if blake3(black_box(&message)) != hashes[32..64] {
+            panic!();
+        }
+
+        // Streaming updates, split in the middle
+        let (lhs, rhs) = message.split_at(message.len() / 2);
+        let mut hasher = Blake2s::new_keyed(&key);
+        hasher.update(black_box(lhs));
+        hasher.update(black_box(rhs));
+        if hasher.finalize() != hashes[64..96] {
+            panic!();
+        }
+        let mut hasher = Blake3::new_keyed(&key);
+        hasher.update(black_box(lhs));
+        hasher.update(black_box(rhs));
+        if hasher.finalize() != hashes[96..] {
+            panic!();
+        }
+
+        let mut hasher = Blake3::new();
+        hasher.update(black_box(&message));
+        let mut xof = [0u8; XOF_LEN];
+        hasher.finalize_xof(&mut xof);
+        if xof != expected_xof {
+            panic!();
+        }
+    }
+}
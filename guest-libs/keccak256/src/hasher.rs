@@ -0,0 +1,247 @@
+//! Streaming Keccak, SHA-3 and SHAKE hashers built on the keccak-f\[1600\] permutation.
+//!
+//! The API mirrors [`tiny_keccak`](https://docs.rs/tiny-keccak/latest/tiny_keccak/), so these
+//! types can be used in place of `tiny_keccak::{Keccak, Sha3, Shake}` by swapping the imports.
+//! Inside the zkVM every permutation is a single `keccakf` instruction, and input can be absorbed
+//! in arbitrary chunks without buffering it in guest memory first.
+
+/// Number of 64-bit lanes in the keccak-f\[1600\] state.
+const WORDS: usize = 25;
+
+/// Domain separation byte of the original Keccak submission, as used by Ethereum.
+const KECCAK_DELIM: u8 = 0x01;
+/// Domain separation byte of the SHA-3 hash functions.
+const SHA3_DELIM: u8 = 0x06;
+/// Domain separation byte of the SHAKE extendable output functions.
+const SHAKE_DELIM: u8 = 0x1f;
+
+/// Applies the keccak-f\[1600\] permutation to `state` in place.
+#[inline(always)]
+pub fn keccakf(state: &mut [u64; WORDS]) {
+    #[cfg(not(target_os = "zkvm"))]
+    tiny_keccak::keccakf(state);
+    #[cfg(target_os = "zkvm")]
+    openvm_keccak256_guest::native_keccakf(state);
+}
+
+/// A streaming hash function, with the same interface as `tiny_keccak::Hasher`.
+pub trait Hasher {
+    /// Absorbs additional input. Can be called multiple times.
+    fn update(&mut self, input: &[u8]);
+
+    /// Pads the input and squeezes the hash into `output`.
+    fn finalize(self, output: &mut [u8]);
+}
+
+/// An extendable output function, with the same interface as `tiny_keccak::Xof`.
+pub trait Xof {
+    /// Squeezes `output.len()` more bytes of output. Can be called multiple times.
+    fn squeeze(&mut self, output: &mut [u8]);
+}
+
+/// The sponge construction over keccak-f\[1600\] with a rate of `rate` bytes.
+#[derive(Clone)]
+struct KeccakSponge {
+    state: [u64; WORDS],
+    /// Number of bytes of the state which are absorbed or squeezed before each permutation.
+    rate: usize,
+    /// Position of the next byte to absorb or squeeze in the current block.
+    offset: usize,
+    delim: u8,
+    squeezing: bool,
+}
+
+impl KeccakSponge {
+    /// Creates a sponge for a hash function with a capacity of twice the security level `bits`.
+    fn new(bits: usize, delim: u8) -> Self {
+        Self {
+            state: [0; WORDS],
+            rate: 200 - bits / 4,
+            offset: 0,
+            delim,
+            squeezing: false,
+        }
+    }
+
+    #[inline(always)]
+    fn xor_byte(&mut self, idx: usize, byte: u8) {
+        self.state[idx / 8] ^= (byte as u64) << (8 * (idx % 8));
+    }
+
+    #[inline(always)]
+    fn get_byte(&self, idx: usize) -> u8 {
+        (self.state[idx / 8] >> (8 * (idx % 8))) as u8
+    }
+
+    fn absorb(&mut self, mut input: &[u8]) {
+        debug_assert!(!self.squeezing, "cannot absorb after squeezing");
+        while !input.is_empty() {
+            // XOR whole lanes at once when the current position is lane aligned
+            if self.offset % 8 == 0 && input.len() >= 8 {
+                let lane = u64::from_le_bytes(input[..8].try_into().unwrap());
+                self.state[self.offset / 8] ^= lane;
+                self.offset += 8;
+                input = &input[8..];
+            } else {
+                self.xor_byte(self.offset, input[0]);
+                self.offset += 1;
+                input = &input[1..];
+            }
+            if self.offset == self.rate {
+                keccakf(&mut self.state);
+                self.offset = 0;
+            }
+        }
+    }
+
+    /// Applies the multi-rate padding with the domain separation byte and permutes.
+    fn pad(&mut self) {
+        self.xor_byte(self.offset, self.delim);
+        self.xor_byte(self.rate - 1, 0x80);
+        keccakf(&mut self.state);
+        self.offset = 0;
+        self.squeezing = true;
+    }
+
+    fn squeeze(&mut self, output: &mut [u8]) {
+        if !self.squeezing {
+            self.pad();
+        }
+        for byte in output {
+            if self.offset == self.rate {
+                keccakf(&mut self.state);
+                self.offset = 0;
+            }
+            *byte = self.get_byte(self.offset);
+            self.offset += 1;
+        }
+    }
+}
+
+/// The Keccak hash functions with the original padding, as used by Ethereum.
+#[derive(Clone)]
+pub struct Keccak {
+    sponge: KeccakSponge,
+}
+
+impl Keccak {
+    /// Creates a Keccak-224 hasher.
+    pub fn v224() -> Self {
+        Self::new(224)
+    }
+
+    /// Creates a Keccak-256 hasher.
+    pub fn v256() -> Self {
+        Self::new(256)
+    }
+
+    /// Creates a Keccak-384 hasher.
+    pub fn v384() -> Self {
+        Self::new(384)
+    }
+
+    /// Creates a Keccak-512 hasher.
+    pub fn v512() -> Self {
+        Self::new(512)
+    }
+
+    fn new(bits: usize) -> Self {
+        Self {
+            sponge: KeccakSponge::new(bits, KECCAK_DELIM),
+        }
+    }
+}
+
+impl Hasher for Keccak {
+    fn update(&mut self, input: &[u8]) {
+        self.sponge.absorb(input);
+    }
+
+    fn finalize(mut self, output: &mut [u8]) {
+        self.sponge.squeeze(output);
+    }
+}
+
+/// The SHA-3 hash functions of FIPS 202.
+#[derive(Clone)]
+pub struct Sha3 {
+    sponge: KeccakSponge,
+}
+
+impl Sha3 {
+    /// Creates a SHA3-224 hasher.
+    pub fn v224() -> Self {
+        Self::new(224)
+    }
+
+    /// Creates a SHA3-256 hasher.
+    pub fn v256() -> Self {
+        Self::new(256)
+    }
+
+    /// Creates a SHA3-384 hasher.
+    pub fn v384() -> Self {
+        Self::new(384)
+    }
+
+    /// Creates a SHA3-512 hasher.
+    pub fn v512() -> Self {
+        Self::new(512)
+    }
+
+    fn new(bits: usize) -> Self {
+        Self {
+            sponge: KeccakSponge::new(bits, SHA3_DELIM),
+        }
+    }
+}
+
+impl Hasher for Sha3 {
+    fn update(&mut self, input: &[u8]) {
+        self.sponge.absorb(input);
+    }
+
+    fn finalize(mut self, output: &mut [u8]) {
+        self.sponge.squeeze(output);
+    }
+}
+
+/// The SHAKE extendable output functions of FIPS 202.
+#[derive(Clone)]
+pub struct Shake {
+    sponge: KeccakSponge,
+}
+
+impl Shake {
+    /// Creates a SHAKE128 hasher.
+    pub fn v128() -> Self {
+        Self::new(128)
+    }
+
+    /// Creates a SHAKE256 hasher.
+    pub fn v256() -> Self {
+        Self::new(256)
+    }
+
+    fn new(bits: usize) -> Self {
+        Self {
+            sponge: KeccakSponge::new(bits, SHAKE_DELIM),
+        }
+    }
+}
+
+impl Hasher for Shake {
+    fn update(&mut self, input: &[u8]) {
+        self.sponge.absorb(input);
+    }
+
+    fn finalize(mut self, output: &mut [u8]) {
+        self.sponge.squeeze(output);
+    }
+}
+
+impl Xof for Shake {
+    fn squeeze(&mut self, output: &mut [u8]) {
+        self.sponge.squeeze(output);
+    }
+}
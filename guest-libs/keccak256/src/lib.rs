@@ -3,6 +3,9 @@
 #[cfg(target_os = "zkvm")]
 use core::mem::MaybeUninit;
 
+mod hasher;
+pub use hasher::*;
+
 /// The keccak256 cryptographic hash function.
 #[inline(always)]
 pub fn keccak256(input: &[u8]) -> [u8; 32] {
@@ -30,4 +30,24 @@ mod tests {
         air_test(Keccak256Rv32Builder, config, openvm_exe);
         Ok(())
     }
+
+    #[test]
+    fn test_keccak_stream() -> Result<()> {
+        let config = Keccak256Rv32Config::default();
+        let elf = build_example_program_at_path(
+            get_programs_dir!("tests/programs"),
+            "keccak_stream",
+            &config,
+        )?;
+        let openvm_exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Keccak256TranspilerExtension)
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension),
+        )?;
+        air_test(Keccak256Rv32Builder, config, openvm_exe);
+        Ok(())
+    }
 }
@@ -0,0 +1,93 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::hint::black_box;
+
+use hex::FromHex;
+use openvm_keccak256::{keccak256, Hasher, Keccak, Sha3, Shake, Xof};
+
+openvm::entry!(main);
+
+/// Hashes `input` in one call and in chunks of `chunk_size` bytes, and checks that both agree.
+fn hash<H: Hasher + Clone>(hasher: H, input: &[u8], chunk_size: usize, output: &mut [u8]) {
+    let mut oneshot = hasher.clone();
+    oneshot.update(input);
+    let mut expected = alloc::vec![0u8; output.len()];
+    oneshot.finalize(&mut expected);
+
+    let mut streaming = hasher;
+    for chunk in input.chunks(chunk_size) {
+        streaming.update(chunk);
+    }
+    streaming.finalize(output);
+    if output != expected {
+        panic!();
+    }
+}
+
+pub fn main() {
+    let test_vectors = [
+        ("", "C5D2460186F7233C927E7DB2DCC703C0E500B653CA82273B7BFAD8045D85A470", "0EAB42DE4C3CEB9235FC91ACFFE746B29C29A8C366B7C60E4E67C466F36A4304C00FA9CAF9D87976BA469BCBE06713B435F091EF2769FB160CDAB33D3670680E", "A7FFC6F8BF1ED76651C14756A061D662F580FF4DE43B49FA82D80A4B80F8434A", "7F9C2BA4E88F827D616045507605853ED73B8093F6EFBC88EB1A6EACFA66EF26", "46B9DD2B0BA88D13233B3FEB743EEB243FCD52EA62B81B82B50C27646ED5762F"),
+    ];
+    for (input, keccak_256, keccak_512, sha3_256, shake_128, shake_256) in test_vectors.iter() {
+        let input = Vec::from_hex(input).unwrap();
+
+        let mut output = [0u8; 32];
+        hash(Keccak::v256(), &input, 7, &mut output);
+        if output.as_slice() != Vec::from_hex(keccak_256).unwrap() {
+            panic!();
+        }
+
+        let mut output = [0u8; 64];
+        hash(Keccak::v512(), &input, 7, &mut output);
+        if output.as_slice() != Vec::from_hex(keccak_512).unwrap() {
+            panic!();
+        }
+
+        let mut output = [0u8; 32];
+        hash(Sha3::v256(), &input, 7, &mut output);
+        if output.as_slice() != Vec::from_hex(sha3_256).unwrap() {
+            panic!();
+        }
+
+        let mut output = [0u8; 32];
+        hash(Shake::v128(), &input, 7, &mut output);
+        if output.as_slice() != Vec::from_hex(shake_128).unwrap() {
+            panic!();
+        }
+
+        let mut output = [0u8; 32];
+        hash(Shake::v256(), &input, 7, &mut output);
+        if output.as_slice() != Vec::from_hex(shake_256).unwrap() {
+            panic!();
+        }
+    }
+
+    // Streaming Keccak-256 must agree with the keccak256 intrinsic on inputs spanning several
+    // blocks, for chunk sizes that are and are not aligned to lanes and to the rate
+    let input: Vec<u8> = (0..1000u32).map(|i| (i * 37 + 11) as u8).collect();
+    let expected = keccak256(&black_box(input.clone()));
+    for chunk_size in [1, 8, 13, 136, 300] {
+        let mut output = [0u8; 32];
+        hash(Keccak::v256(), &input, chunk_size, &mut output);
+        if output != expected {
+            panic!();
+        }
+    }
+
+    // Squeezing SHAKE output in pieces must agree with squeezing it at once
+    let mut shake = Shake::v128();
+    shake.update(&input);
+    let mut expected = [0u8; 400];
+    shake.clone().finalize(&mut expected);
+    let mut output = [0u8; 400];
+    for chunk in output.chunks_mut(33) {
+        shake.squeeze(chunk);
+    }
+    if output != expected {
+        panic!();
+    }
+}
@@ -0,0 +1,90 @@
+#![no_std]
+//! The Poseidon2 permutation over BabyBear, with the same configuration as the Poseidon2 hasher
+//! used by the OpenVM system, e.g. for the memory Merkle tree and public values commitments.
+//!
+//! BabyBear elements are represented as `u32`s. Inputs are reduced modulo the BabyBear prime, and
+//! outputs are always canonical.
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+pub use openvm_poseidon2_guest::{POSEIDON2_CHUNK, POSEIDON2_WIDTH};
+
+/// A Poseidon2 digest, made of [POSEIDON2_CHUNK] BabyBear elements.
+pub type Digest = [u32; POSEIDON2_CHUNK];
+
+/// Applies the Poseidon2 permutation to `state` in place.
+pub fn permute(state: &mut [u32; POSEIDON2_WIDTH]) {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        host::permute(state);
+    }
+    #[cfg(target_os = "zkvm")]
+    {
+        openvm_poseidon2_guest::native_poseidon2_permute(state);
+    }
+}
+
+/// The Poseidon2 compression function: the first [POSEIDON2_CHUNK] elements of the permutation of
+/// `lhs || rhs`.
+pub fn compress(lhs: &Digest, rhs: &Digest) -> Digest {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        let mut state = [0u32; POSEIDON2_WIDTH];
+        state[..POSEIDON2_CHUNK].copy_from_slice(lhs);
+        state[POSEIDON2_CHUNK..].copy_from_slice(rhs);
+        host::permute(&mut state);
+        state[..POSEIDON2_CHUNK].try_into().unwrap()
+    }
+    #[cfg(target_os = "zkvm")]
+    {
+        let mut output = [0u32; POSEIDON2_CHUNK];
+        openvm_poseidon2_guest::native_poseidon2_compress(lhs, rhs, &mut output);
+        output
+    }
+}
+
+/// Hashes a single chunk, by compressing it with the zero chunk.
+pub fn hash(values: &Digest) -> Digest {
+    compress(values, &[0; POSEIDON2_CHUNK])
+}
+
+/// Computes the root of the Merkle tree whose leaves are the hashes of the consecutive chunks of
+/// `values`, as the OpenVM system does for public values.
+///
+/// # Panics
+/// If the length of `values` is not a power of two multiple of [POSEIDON2_CHUNK].
+pub fn merkle_root(values: &[u32]) -> Digest {
+    let num_leaves = values.len() / POSEIDON2_CHUNK;
+    assert!(
+        values.len() % POSEIDON2_CHUNK == 0 && num_leaves.is_power_of_two(),
+        "number of values must be a power of two multiple of {POSEIDON2_CHUNK}"
+    );
+    let mut layer: Vec<Digest> = values
+        .chunks_exact(POSEIDON2_CHUNK)
+        .map(|chunk| hash(chunk.try_into().unwrap()))
+        .collect();
+    while layer.len() > 1 {
+        layer = layer
+            .chunks_exact(2)
+            .map(|pair| compress(&pair[0], &pair[1]))
+            .collect();
+    }
+    layer[0]
+}
+
+#[cfg(not(target_os = "zkvm"))]
+mod host {
+    use openvm_poseidon2_air::{
+        p3_baby_bear::BabyBear, Poseidon2Config, Poseidon2SubChip, POSEIDON2_WIDTH,
+    };
+    use openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField32};
+
+    /// Host implementation of the permutation. This is not intended to be performant.
+    pub fn permute(state: &mut [u32; POSEIDON2_WIDTH]) {
+        let subchip = Poseidon2SubChip::<BabyBear, 0>::new(Poseidon2Config::default().constants);
+        let output = subchip.permute(state.map(BabyBear::from_wrapped_u32));
+        *state = output.map(|x| x.as_canonical_u32());
+    }
+}
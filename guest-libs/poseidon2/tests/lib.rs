@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod tests {
+    use eyre::Result;
+    use openvm_circuit::{
+        arch::hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+        utils::air_test_with_min_segments,
+    };
+    use openvm_instructions::exe::VmExe;
+    use openvm_poseidon2::{compress, merkle_root, permute, POSEIDON2_CHUNK, POSEIDON2_WIDTH};
+    use openvm_poseidon2_circuit::{Poseidon2Rv32Builder, Poseidon2Rv32Config};
+    use openvm_poseidon2_transpiler::Poseidon2TranspilerExtension;
+    use openvm_rv32im_transpiler::{
+        Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+    };
+    use openvm_stark_sdk::{
+        openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField32},
+        p3_baby_bear::BabyBear,
+    };
+    use openvm_toolchain_tests::{build_example_program_at_path, get_programs_dir};
+    use openvm_transpiler::{transpiler::Transpiler, FromElf};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    type F = BabyBear;
+
+    fn to_field(words: &[u32]) -> Vec<F> {
+        words.iter().map(|&x| F::from_wrapped_u32(x)).collect()
+    }
+
+    fn to_words(elements: &[F]) -> Vec<u32> {
+        elements.iter().map(|x| x.as_canonical_u32()).collect()
+    }
+
+    fn to_io(words: impl IntoIterator<Item = u32>) -> Vec<F> {
+        words
+            .into_iter()
+            .flat_map(|x| x.to_le_bytes())
+            .map(F::from_canonical_u8)
+            .collect()
+    }
+
+    #[test]
+    fn test_poseidon2() -> Result<()> {
+        let config = Poseidon2Rv32Config::default();
+        let elf = build_example_program_at_path(
+            get_programs_dir!("tests/programs"),
+            "poseidon2",
+            &config,
+        )?;
+        let openvm_exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension)
+                .with_extension(Poseidon2TranspilerExtension),
+        )?;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let hasher = vm_poseidon2_hasher::<F>();
+
+        // Not all inputs are reduced modulo the BabyBear prime
+        let input: [u32; POSEIDON2_WIDTH] = std::array::from_fn(|_| rng.gen());
+        let mut state = input;
+        permute(&mut state);
+        let permute_io = to_io(input.into_iter().chain(state));
+
+        let lhs: [u32; POSEIDON2_CHUNK] = std::array::from_fn(|_| rng.gen());
+        let rhs: [u32; POSEIDON2_CHUNK] = std::array::from_fn(|_| rng.gen());
+        let output = hasher.compress(
+            &to_field(&lhs).try_into().unwrap(),
+            &to_field(&rhs).try_into().unwrap(),
+        );
+        let compress_io = to_io(lhs.into_iter().chain(rhs).chain(to_words(&output)));
+
+        let values: Vec<u32> = (0..4 * POSEIDON2_CHUNK)
+            .map(|_| rng.gen_range(0..F::ORDER_U32))
+            .collect();
+        let root = hasher.merkle_root(&to_field(&values));
+        let merkle_io = to_io(values.into_iter().chain(to_words(&root)));
+
+        air_test_with_min_segments(
+            Poseidon2Rv32Builder,
+            config,
+            openvm_exe,
+            vec![permute_io, compress_io, merkle_io],
+            1,
+        );
+        Ok(())
+    }
+
+    /// The host implementation must agree with the Poseidon2 hasher of the VM.
+    #[test]
+    fn test_poseidon2_host() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let hasher = vm_poseidon2_hasher::<F>();
+        for _ in 0..10 {
+            let lhs: [u32; POSEIDON2_CHUNK] = std::array::from_fn(|_| rng.gen());
+            let rhs: [u32; POSEIDON2_CHUNK] = std::array::from_fn(|_| rng.gen());
+            let expected = hasher.compress(
+                &to_field(&lhs).try_into().unwrap(),
+                &to_field(&rhs).try_into().unwrap(),
+            );
+            assert_eq!(compress(&lhs, &rhs).to_vec(), to_words(&expected));
+        }
+
+        for num_leaves in [1, 2, 8] {
+            let values: Vec<u32> = (0..num_leaves * POSEIDON2_CHUNK)
+                .map(|_| rng.gen_range(0..F::ORDER_U32))
+                .collect();
+            let expected = hasher.merkle_root(&to_field(&values));
+            assert_eq!(merkle_root(&values).to_vec(), to_words(&expected));
+        }
+    }
+}
@@ -0,0 +1,45 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::hint::black_box;
+
+use openvm::io::read_vec;
+use openvm_poseidon2::{compress, merkle_root, permute, POSEIDON2_CHUNK, POSEIDON2_WIDTH};
+
+openvm::entry!(main);
+
+fn read_words() -> Vec<u32> {
+    read_vec()
+        .chunks_exact(4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect()
+}
+
+pub fn main() {
+    // The input state, followed by its permutation
+    let io = read_words();
+    let (input, expected) = io.split_at(POSEIDON2_WIDTH);
+    let mut state: [u32; POSEIDON2_WIDTH] = input.try_into().unwrap();
+    permute(black_box(&mut state));
+    if state != expected {
+        panic!();
+    }
+
+    // The two inputs of the compression function, followed by its output
+    let io = read_words();
+    let lhs: [u32; POSEIDON2_CHUNK] = io[..POSEIDON2_CHUNK].try_into().unwrap();
+    let rhs: [u32; POSEIDON2_CHUNK] = io[POSEIDON2_CHUNK..2 * POSEIDON2_CHUNK].try_into().unwrap();
+    if compress(&black_box(lhs), &black_box(rhs)) != io[2 * POSEIDON2_CHUNK..] {
+        panic!();
+    }
+
+    // The leaves of a Merkle tree, followed by its root
+    let io = read_words();
+    let (values, expected) = io.split_at(io.len() - POSEIDON2_CHUNK);
+    if merkle_root(black_box(values)) != expected {
+        panic!();
+    }
+}
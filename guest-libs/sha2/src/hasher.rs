@@ -0,0 +1,262 @@
+//! Streaming SHA-256, SHA-512 and SHA-384 hashers.
+//!
+//! [Sha256] compresses every block as soon as it is complete with the `sha256_compress`
+//! intrinsic, and implements the [digest] traits so that it can be used wherever a
+//! [`sha2::Sha256`](https://docs.rs/sha2) is expected, e.g. for HMAC. [Sha512] and [Sha384] do
+//! the same with the `sha512_compress` intrinsic.
+
+use core::fmt;
+
+use digest::{
+    block_buffer::Eager,
+    core_api::{
+        AlgorithmName, Block, BlockSizeUser, Buffer, BufferKindUser, CoreWrapper, FixedOutputCore,
+        OutputSizeUser, UpdateCore,
+    },
+    typenum::{U128, U32, U48, U64},
+    HashMarker, Output, Reset,
+};
+
+use crate::{sha256_compress, sha512_compress, SHA256_H, SHA384_H, SHA512_H};
+
+/// A streaming sha256 hasher. Use it through the [Digest](crate::Digest) trait.
+pub type Sha256 = CoreWrapper<Sha256Core>;
+
+/// The core of [Sha256], which holds the hash state of the complete blocks of the input.
+#[derive(Clone)]
+pub struct Sha256Core {
+    state: [u32; 8],
+    block_len: u64,
+}
+
+impl HashMarker for Sha256Core {}
+
+impl BlockSizeUser for Sha256Core {
+    type BlockSize = U64;
+}
+
+impl BufferKindUser for Sha256Core {
+    type BufferKind = Eager;
+}
+
+impl OutputSizeUser for Sha256Core {
+    type OutputSize = U32;
+}
+
+impl UpdateCore for Sha256Core {
+    #[inline]
+    fn update_blocks(&mut self, blocks: &[Block<Self>]) {
+        self.block_len += blocks.len() as u64;
+        for block in blocks {
+            sha256_compress(&mut self.state, block.as_slice().try_into().unwrap());
+        }
+    }
+}
+
+impl FixedOutputCore for Sha256Core {
+    #[inline]
+    fn finalize_fixed_core(&mut self, buffer: &mut Buffer<Self>, out: &mut Output<Self>) {
+        let bit_len = 8 * (buffer.get_pos() as u64 + self.block_len * 64);
+        let mut state = self.state;
+        buffer.len64_padding_be(bit_len, |block| {
+            sha256_compress(&mut state, block.as_slice().try_into().unwrap())
+        });
+        for (chunk, word) in out.chunks_exact_mut(4).zip(state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+    }
+}
+
+impl Default for Sha256Core {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            state: SHA256_H,
+            block_len: 0,
+        }
+    }
+}
+
+impl Reset for Sha256Core {
+    #[inline]
+    fn reset(&mut self) {
+        *self = Default::default();
+    }
+}
+
+impl AlgorithmName for Sha256Core {
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Sha256")
+    }
+}
+
+impl fmt::Debug for Sha256Core {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Sha256Core { ... }")
+    }
+}
+
+/// A streaming sha512 hasher. Use it through the [Digest](crate::Digest) trait.
+pub type Sha512 = CoreWrapper<Sha512Core>;
+
+/// A streaming sha384 hasher. Use it through the [Digest](crate::Digest) trait.
+pub type Sha384 = CoreWrapper<Sha384Core>;
+
+/// The core of [Sha512], which holds the hash state of the complete blocks of the input.
+#[derive(Clone)]
+pub struct Sha512Core {
+    state: [u64; 8],
+    block_len: u128,
+}
+
+/// The core of [Sha384], which holds the hash state of the complete blocks of the input.
+#[derive(Clone)]
+pub struct Sha384Core {
+    state: [u64; 8],
+    block_len: u128,
+}
+
+/// Compresses `blocks` into `state`, counting them in `block_len`
+#[inline]
+fn sha512_update_blocks(state: &mut [u64; 8], block_len: &mut u128, blocks: &[Block<Sha512Core>]) {
+    *block_len += blocks.len() as u128;
+    for block in blocks {
+        sha512_compress(state, block.as_slice().try_into().unwrap());
+    }
+}
+
+/// Pads the remaining input in `buffer` and returns the final state, whose big-endian words are
+/// the sha512 hash, or the sha384 hash once truncated
+#[inline]
+fn sha512_finalize_state(
+    mut state: [u64; 8],
+    block_len: u128,
+    buffer: &mut Buffer<Sha512Core>,
+) -> [u64; 8] {
+    let bit_len = 8 * (buffer.get_pos() as u128 + block_len * 128);
+    buffer.len128_padding_be(bit_len, |block| {
+        sha512_compress(&mut state, block.as_slice().try_into().unwrap())
+    });
+    state
+}
+
+impl HashMarker for Sha512Core {}
+
+impl BlockSizeUser for Sha512Core {
+    type BlockSize = U128;
+}
+
+impl BufferKindUser for Sha512Core {
+    type BufferKind = Eager;
+}
+
+impl OutputSizeUser for Sha512Core {
+    type OutputSize = U64;
+}
+
+impl UpdateCore for Sha512Core {
+    #[inline]
+    fn update_blocks(&mut self, blocks: &[Block<Self>]) {
+        sha512_update_blocks(&mut self.state, &mut self.block_len, blocks);
+    }
+}
+
+impl FixedOutputCore for Sha512Core {
+    #[inline]
+    fn finalize_fixed_core(&mut self, buffer: &mut Buffer<Self>, out: &mut Output<Self>) {
+        let state = sha512_finalize_state(self.state, self.block_len, buffer);
+        for (chunk, word) in out.chunks_exact_mut(8).zip(state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+    }
+}
+
+impl Default for Sha512Core {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            state: SHA512_H,
+            block_len: 0,
+        }
+    }
+}
+
+impl Reset for Sha512Core {
+    #[inline]
+    fn reset(&mut self) {
+        *self = Default::default();
+    }
+}
+
+impl AlgorithmName for Sha512Core {
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Sha512")
+    }
+}
+
+impl fmt::Debug for Sha512Core {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Sha512Core { ... }")
+    }
+}
+
+impl HashMarker for Sha384Core {}
+
+impl BlockSizeUser for Sha384Core {
+    type BlockSize = U128;
+}
+
+impl BufferKindUser for Sha384Core {
+    type BufferKind = Eager;
+}
+
+impl OutputSizeUser for Sha384Core {
+    type OutputSize = U48;
+}
+
+impl UpdateCore for Sha384Core {
+    #[inline]
+    fn update_blocks(&mut self, blocks: &[Block<Self>]) {
+        sha512_update_blocks(&mut self.state, &mut self.block_len, blocks);
+    }
+}
+
+impl FixedOutputCore for Sha384Core {
+    #[inline]
+    fn finalize_fixed_core(&mut self, buffer: &mut Buffer<Self>, out: &mut Output<Self>) {
+        let state = sha512_finalize_state(self.state, self.block_len, buffer);
+        // The digest is the first 6 words of the state
+        for (chunk, word) in out.chunks_exact_mut(8).zip(state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+    }
+}
+
+impl Default for Sha384Core {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            state: SHA384_H,
+            block_len: 0,
+        }
+    }
+}
+
+impl Reset for Sha384Core {
+    #[inline]
+    fn reset(&mut self) {
+        *self = Default::default();
+    }
+}
+
+impl AlgorithmName for Sha384Core {
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Sha384")
+    }
+}
+
+impl fmt::Debug for Sha384Core {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Sha384Core { ... }")
+    }
+}
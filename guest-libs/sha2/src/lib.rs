@@ -1,5 +1,15 @@
 #![no_std]
 
+mod hasher;
+pub use digest::{self, Digest};
+pub use hasher::*;
+
+/// The initial sha256 hash state, as defined in
+/// [FIPS 180-4](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf).
+pub const SHA256_H: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
 /// The sha256 cryptographic hash function.
 #[inline(always)]
 pub fn sha256(input: &[u8]) -> [u8; 32] {
@@ -26,3 +36,168 @@ pub fn set_sha256(input: &[u8], output: &mut [u8; 32]) {
         );
     }
 }
+
+/// The sha256 compression function. Compresses the 64-byte message `block` into the hash
+/// `state`, without any padding.
+///
+/// Starting from [SHA256_H] and compressing every block of a padded message gives the words of
+/// its sha256 hash, in big-endian byte order.
+pub fn sha256_compress(state: &mut [u32; 8], block: &[u8; 64]) {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        use sha2::digest::generic_array::GenericArray;
+        sha2::compress256(state, &[GenericArray::clone_from_slice(block)]);
+    }
+    #[cfg(target_os = "zkvm")]
+    {
+        // The intrinsic reads the block with word-aligned memory accesses
+        if block.as_ptr() as usize % 4 == 0 {
+            // SAFETY: `state` and `block` are aligned and of the correct length
+            unsafe {
+                openvm_sha256_guest::native_sha256_compress(
+                    state.as_ptr(),
+                    block.as_ptr(),
+                    state.as_mut_ptr(),
+                );
+            }
+        } else {
+            let mut aligned_block = [0u32; 16];
+            // SAFETY: `aligned_block` is 64 bytes long and does not overlap `block`
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    block.as_ptr(),
+                    aligned_block.as_mut_ptr() as *mut u8,
+                    64,
+                );
+                openvm_sha256_guest::native_sha256_compress(
+                    state.as_ptr(),
+                    aligned_block.as_ptr() as *const u8,
+                    state.as_mut_ptr(),
+                );
+            }
+        }
+    }
+}
+
+/// The initial sha512 hash state, as defined in
+/// [FIPS 180-4](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf).
+pub const SHA512_H: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// The initial sha384 hash state, as defined in
+/// [FIPS 180-4](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf).
+pub const SHA384_H: [u64; 8] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4,
+];
+
+/// The sha512 cryptographic hash function.
+#[inline(always)]
+pub fn sha512(input: &[u8]) -> [u8; 64] {
+    let mut output = [0u8; 64];
+    set_sha512(input, &mut output);
+    output
+}
+
+/// Sets `output` to the sha512 hash of `input`.
+pub fn set_sha512(input: &[u8], output: &mut [u8; 64]) {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        use sha2::{Digest, Sha512};
+        let mut hasher = Sha512::new();
+        hasher.update(input);
+        output.copy_from_slice(hasher.finalize().as_ref());
+    }
+    #[cfg(target_os = "zkvm")]
+    {
+        openvm_sha512_guest::zkvm_sha512_impl(
+            input.as_ptr(),
+            input.len(),
+            output.as_mut_ptr() as *mut u8,
+        );
+    }
+}
+
+/// The sha384 cryptographic hash function.
+#[inline(always)]
+pub fn sha384(input: &[u8]) -> [u8; 48] {
+    let mut output = [0u8; 48];
+    set_sha384(input, &mut output);
+    output
+}
+
+/// Sets `output` to the sha384 hash of `input`.
+pub fn set_sha384(input: &[u8], output: &mut [u8; 48]) {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        use sha2::{Digest, Sha384};
+        let mut hasher = Sha384::new();
+        hasher.update(input);
+        output.copy_from_slice(hasher.finalize().as_ref());
+    }
+    #[cfg(target_os = "zkvm")]
+    {
+        openvm_sha512_guest::zkvm_sha384_impl(
+            input.as_ptr(),
+            input.len(),
+            output.as_mut_ptr() as *mut u8,
+        );
+    }
+}
+
+/// The sha512 compression function, which is shared by sha512 and sha384. Compresses the
+/// 128-byte message `block` into the hash `state`, without any padding.
+///
+/// Starting from [SHA512_H] (resp. [SHA384_H]) and compressing every block of a padded message
+/// gives the words of its sha512 hash (resp. of its sha384 hash, truncated to the first 6 words),
+/// in big-endian byte order.
+pub fn sha512_compress(state: &mut [u64; 8], block: &[u8; 128]) {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        use sha2::digest::generic_array::GenericArray;
+        sha2::compress512(state, &[GenericArray::clone_from_slice(block)]);
+    }
+    #[cfg(target_os = "zkvm")]
+    {
+        // The intrinsic reads the block with word-aligned memory accesses
+        if block.as_ptr() as usize % 4 == 0 {
+            // SAFETY: `state` and `block` are aligned and of the correct length
+            unsafe {
+                openvm_sha512_guest::native_sha512_compress(
+                    state.as_ptr(),
+                    block.as_ptr(),
+                    state.as_mut_ptr(),
+                );
+            }
+        } else {
+            let mut aligned_block = [0u32; 32];
+            // SAFETY: `aligned_block` is 128 bytes long and does not overlap `block`
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    block.as_ptr(),
+                    aligned_block.as_mut_ptr() as *mut u8,
+                    128,
+                );
+                openvm_sha512_guest::native_sha512_compress(
+                    state.as_ptr(),
+                    aligned_block.as_ptr() as *const u8,
+                    state.as_mut_ptr(),
+                );
+            }
+        }
+    }
+}
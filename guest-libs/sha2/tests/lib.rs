@@ -8,6 +8,8 @@ mod tests {
     };
     use openvm_sha256_circuit::{Sha256Rv32Builder, Sha256Rv32Config};
     use openvm_sha256_transpiler::Sha256TranspilerExtension;
+    use openvm_sha512_circuit::{Sha512Rv32Builder, Sha512Rv32Config};
+    use openvm_sha512_transpiler::Sha512TranspilerExtension;
     use openvm_stark_sdk::p3_baby_bear::BabyBear;
     use openvm_toolchain_tests::{build_example_program_at_path, get_programs_dir};
     use openvm_transpiler::{transpiler::Transpiler, FromElf};
@@ -30,4 +32,41 @@ mod tests {
         air_test(Sha256Rv32Builder, config, openvm_exe);
         Ok(())
     }
+
+    #[test]
+    fn test_sha256_stream() -> Result<()> {
+        let config = Sha256Rv32Config::default();
+        let elf = build_example_program_at_path(
+            get_programs_dir!("tests/programs"),
+            "sha256_stream",
+            &config,
+        )?;
+        let openvm_exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension)
+                .with_extension(Sha256TranspilerExtension),
+        )?;
+        air_test(Sha256Rv32Builder, config, openvm_exe);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sha512() -> Result<()> {
+        let config = Sha512Rv32Config::default();
+        let elf =
+            build_example_program_at_path(get_programs_dir!("tests/programs"), "sha512", &config)?;
+        let openvm_exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension)
+                .with_extension(Sha512TranspilerExtension),
+        )?;
+        air_test(Sha512Rv32Builder, config, openvm_exe);
+        Ok(())
+    }
 }
@@ -0,0 +1,63 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::hint::black_box;
+
+use hex::FromHex;
+use hmac::{Hmac, Mac};
+use openvm_sha2::{sha256, Digest, Sha256};
+
+openvm::entry!(main);
+
+pub fn main() {
+    // Streaming hashes match the one-shot hash for every way of splitting the input, including
+    // splits across block boundaries
+    let input: Vec<u8> = (0..200u32).map(|i| (i * 7 + 3) as u8).collect();
+    let input = black_box(input);
+    for len in [0, 1, 55, 56, 63, 64, 65, 119, 128, 200] {
+        let message = &input[..len];
+        let expected = sha256(message);
+        for split in [0, 1, 32, 63, 64, 100] {
+            let split = split.min(len);
+            let mut hasher = Sha256::new();
+            hasher.update(&message[..split]);
+            hasher.update(&message[split..]);
+            if hasher.finalize().as_slice() != expected {
+                panic!();
+            }
+        }
+    }
+
+    // Midstate reuse: hashing a common prefix once and cloning the hasher
+    let mut prefix = Sha256::new();
+    prefix.update(&input[..128]);
+    for suffix in [&input[128..150], &input[150..]] {
+        let mut hasher = prefix.clone();
+        hasher.update(suffix);
+        let mut message = input[..128].to_vec();
+        message.extend_from_slice(suffix);
+        if hasher.finalize().as_slice() != sha256(&message) {
+            panic!();
+        }
+    }
+
+    // Bitcoin-style double sha256
+    let expected =
+        Vec::from_hex("9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50").unwrap();
+    let output = Sha256::digest(Sha256::digest(black_box(b"hello")));
+    if output.as_slice() != expected {
+        panic!();
+    }
+
+    // HMAC-SHA256, test case 2 of RFC 4231
+    let expected =
+        Vec::from_hex("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843").unwrap();
+    let mut mac = Hmac::<Sha256>::new_from_slice(black_box(b"Jefe")).unwrap();
+    mac.update(b"what do ya want for nothing?");
+    if mac.finalize().into_bytes().as_slice() != expected {
+        panic!();
+    }
+}
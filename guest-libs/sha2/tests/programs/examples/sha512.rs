@@ -0,0 +1,76 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::hint::black_box;
+
+use hex::FromHex;
+use hmac::{Hmac, Mac};
+use openvm_sha2::{sha384, sha512, Digest, Sha384, Sha512};
+
+openvm::entry!(main);
+
+pub fn main() {
+    let test_vectors = [
+        ("", "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e", "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b"),
+        ("616263", "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f", "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7"),
+    ];
+    for (input, expected_sha512, expected_sha384) in test_vectors.iter() {
+        let input = Vec::from_hex(input).unwrap();
+        let expected_sha512 = Vec::from_hex(expected_sha512).unwrap();
+        let expected_sha384 = Vec::from_hex(expected_sha384).unwrap();
+        if sha512(&black_box(input.clone())) != *expected_sha512 {
+            panic!();
+        }
+        if sha384(&black_box(input)) != *expected_sha384 {
+            panic!();
+        }
+    }
+
+    // The streaming hashers must agree with the one-shot functions on inputs spanning several
+    // blocks, whatever the chunk size
+    let input: Vec<u8> = (0..1000u32).map(|i| (i * 37 + 11) as u8).collect();
+    let expected_sha512 = sha512(&black_box(input.clone()));
+    let expected_sha384 = sha384(&black_box(input.clone()));
+    for chunk_size in [1, 13, 111, 112, 128, 300] {
+        let mut sha512_hasher = Sha512::new();
+        let mut sha384_hasher = Sha384::new();
+        for chunk in input.chunks(chunk_size) {
+            sha512_hasher.update(chunk);
+            sha384_hasher.update(chunk);
+        }
+        if sha512_hasher.finalize().as_slice() != expected_sha512 {
+            panic!();
+        }
+        if sha384_hasher.finalize().as_slice() != expected_sha384 {
+            panic!();
+        }
+    }
+
+    // Padding which spills into an extra block: 112 bytes leave no room for the 16-byte length
+    for len in [111, 112, 127, 128, 129, 239, 240] {
+        let message = &input[..len];
+        if Sha512::digest(message).as_slice() != sha512(message) {
+            panic!();
+        }
+        if Sha384::digest(message).as_slice() != sha384(message) {
+            panic!();
+        }
+    }
+
+    // HMAC-SHA512 and HMAC-SHA384, test case 2 of RFC 4231
+    let expected = Vec::from_hex("164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737").unwrap();
+    let mut mac = Hmac::<Sha512>::new_from_slice(black_box(b"Jefe")).unwrap();
+    mac.update(b"what do ya want for nothing?");
+    if mac.finalize().into_bytes().as_slice() != expected {
+        panic!();
+    }
+    let expected = Vec::from_hex("af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649").unwrap();
+    let mut mac = Hmac::<Sha384>::new_from_slice(black_box(b"Jefe")).unwrap();
+    mac.update(b"what do ya want for nothing?");
+    if mac.finalize().into_bytes().as_slice() != expected {
+        panic!();
+    }
+}
@@ -0,0 +1,23 @@
+extern crate alloc;
+use alloc::vec::Vec;
+
+use openvm::io::{read, reveal_u32};
+use openvm_verify_stark::define_verify_openvm_stark;
+
+define_verify_openvm_stark!(
+    verify_openvm_stark,
+    env!("CARGO_MANIFEST_DIR"),
+    "root_verifier.asm"
+);
+
+// Verifies a child proof supplied by `StdIn::add_child_proof` and reveals its commits at the offset
+// of the first child, so that the proof of this program is bound to the verified child.
+pub fn main() {
+    let app_exe_commit: [u32; 8] = read();
+    let app_vm_commit: [u32; 8] = read();
+    let pvs: Vec<u8> = read();
+    verify_openvm_stark(&app_exe_commit, &app_vm_commit, &pvs);
+    for (i, &x) in app_exe_commit.iter().chain(&app_vm_commit).enumerate() {
+        reveal_u32(x, i);
+    }
+}
@@ -1,9 +1,13 @@
-use std::path::PathBuf;
+use std::{
+    fs::{copy, create_dir, read_to_string, write},
+    path::PathBuf,
+};
 
 use eyre::Result;
 use openvm_circuit::arch::{SystemConfig, DEFAULT_MAX_NUM_PUBLIC_VALUES};
 use openvm_native_compiler::conversion::CompilerOptions;
 use openvm_sdk::{
+    compose::{check_child_commits, ChildProof},
     config::{AggregationConfig, AppConfig, SdkSystemConfig, SdkVmConfig},
     keygen::AggProvingKey,
     Sdk, StdIn,
@@ -90,3 +94,86 @@ fn test_verify_openvm_stark_e2e() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_prove_composed_e2e() -> Result<()> {
+    const ASM_FILENAME: &str = "root_verifier.asm";
+    // The parent reveals the app exe and app VM commits of the child, which are 32 bytes each
+    const NUM_PUBLIC_VALUES: usize = 64;
+    let mut pkg_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    pkg_dir.pop();
+    pkg_dir.pop();
+    pkg_dir.push("crates/sdk/guest/fib");
+
+    let vm_config = SdkVmConfig::builder()
+        .system(SdkSystemConfig {
+            config: SystemConfig::default().with_public_values(NUM_PUBLIC_VALUES),
+        })
+        .rv32i(Default::default())
+        .rv32m(Default::default())
+        .io(Default::default())
+        .native(Default::default())
+        .build();
+    let fri_params = FriParameters::new_for_testing(LEAF_LOG_BLOWUP);
+    let app_config = AppConfig::new_with_leaf_fri_params(fri_params, vm_config, fri_params);
+    let sdk = Sdk::new(app_config)?;
+    let agg_pk = AggProvingKey::keygen(AggregationConfig {
+        max_num_user_public_values: NUM_PUBLIC_VALUES,
+        leaf_fri_params: FriParameters::new_for_testing(LEAF_LOG_BLOWUP),
+        internal_fri_params: FriParameters::new_for_testing(INTERNAL_LOG_BLOWUP),
+        root_fri_params: FriParameters::new_for_testing(ROOT_LOG_BLOWUP),
+        profiling: false,
+        compiler_options: CompilerOptions {
+            enable_cycle_tracker: true,
+            ..Default::default()
+        },
+        root_max_constraint_degree: (1 << ROOT_LOG_BLOWUP) + 1,
+    })?;
+    let _ = sdk.set_agg_pk(agg_pk);
+
+    let elf = sdk.build(Default::default(), pkg_dir, &None, None)?;
+    let (child_proof, child_commit) = sdk.prove(elf, StdIn::default())?;
+    let child = ChildProof::new(child_commit, child_proof);
+
+    let parent_dir = copy_example_to_tempdir("compose_openvm_stark")?;
+    std::fs::write(
+        parent_dir.path().join(ASM_FILENAME),
+        sdk.generate_root_verifier_asm(),
+    )?;
+    let parent_elf = sdk.build(Default::default(), parent_dir.path(), &None, None)?;
+
+    let (parent_proof, parent_commit) = sdk.prove_composed(
+        parent_elf,
+        StdIn::default(),
+        ASM_FILENAME,
+        std::slice::from_ref(&child),
+    )?;
+    Sdk::verify_proof(&sdk.agg_pk().get_agg_vk(), parent_commit, &parent_proof)?;
+    // The parent only reveals the commits of the child it verified
+    assert!(check_child_commits(&parent_proof.user_public_values, &[parent_commit]).is_err());
+
+    Ok(())
+}
+
+/// Copies the guest package `examples/<name>` to a temporary directory, so that the root verifier
+/// ASM and the build artifacts of the guest are not written into the source tree. The relative
+/// dependency paths of the package are resolved against its original location.
+fn copy_example_to_tempdir(name: &str) -> Result<tempfile::TempDir> {
+    let src = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("examples")
+        .join(name);
+    let dir = tempfile::tempdir()?;
+    let manifest: String = read_to_string(src.join("Cargo.toml"))?
+        .split("path = \"")
+        .enumerate()
+        .map(|(i, part)| match i {
+            0 => part.to_string(),
+            _ => format!("path = \"{}/{part}", src.display()),
+        })
+        .collect();
+    write(dir.path().join("Cargo.toml"), manifest)?;
+    copy(src.join("openvm.toml"), dir.path().join("openvm.toml"))?;
+    create_dir(dir.path().join("src"))?;
+    copy(src.join("src/main.rs"), dir.path().join("src/main.rs"))?;
+    Ok(dir)
+}